@@ -0,0 +1,80 @@
+//! Bulk address encoding and decoding
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::Throughput;
+use ergotree_ir::chain::address::Address;
+use ergotree_ir::chain::address::AddressEncoder;
+use ergotree_ir::chain::address::NetworkPrefix;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::serialization::SigmaSerializable;
+use sigma_benches::p2pk_tree;
+use sigma_benches::sigmausd_bank_tree;
+
+/// Serialized trees as seen by an explorer: a few distinct scripts repeated many times
+fn trees(count: usize) -> Vec<Vec<u8>> {
+    let distinct: Vec<Vec<u8>> = (1..=10u8)
+        .map(p2pk_tree)
+        .chain(std::iter::once(sigmausd_bank_tree()))
+        .map(|tree| tree.sigma_serialize_bytes().unwrap())
+        .collect();
+    distinct.iter().cycle().take(count).cloned().collect()
+}
+
+fn bench_address_conversion(c: &mut Criterion) {
+    let encoder = AddressEncoder::new(NetworkPrefix::Mainnet);
+    let mut group = c.benchmark_group("address_conversion");
+    for count in [100usize, 1000] {
+        let trees = trees(count);
+        let addresses: Vec<String> = encoder
+            .encode_many(trees.iter().map(Vec::as_slice))
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::new("encode", count), &trees, |b, trees| {
+            b.iter(|| {
+                trees
+                    .iter()
+                    .map(|tree_bytes| {
+                        let tree = ErgoTree::sigma_parse_bytes(tree_bytes).unwrap();
+                        Address::recreate_from_ergo_tree(&tree)
+                            .map(|address| encoder.address_to_str(&address))
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("encode_many", count),
+            &trees,
+            |b, trees| b.iter(|| encoder.encode_many(trees.iter().map(Vec::as_slice))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("decode", count),
+            &addresses,
+            |b, addresses| {
+                b.iter(|| {
+                    addresses
+                        .iter()
+                        .map(|address| {
+                            encoder
+                                .parse_address_from_str(address)
+                                .map(|address| address.script())
+                        })
+                        .collect::<Vec<_>>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("decode_many", count),
+            &addresses,
+            |b, addresses| b.iter(|| encoder.decode_many(addresses.iter().map(String::as_str))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_address_conversion);
+criterion_main!(benches);
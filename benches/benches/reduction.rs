@@ -0,0 +1,57 @@
+//! Script parsing and reduction of the common contract templates
+
+use std::rc::Rc;
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use ergotree_interpreter::eval::context::Context;
+use ergotree_interpreter::eval::env::Env;
+use ergotree_interpreter::eval::reduce_to_crypto;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::ergo_tree::SharedErgoTreeCache;
+use ergotree_ir::serialization::SigmaSerializable;
+use sigma_benches::*;
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ergo_tree_parse");
+    for (name, tree) in [
+        ("p2pk", p2pk_tree(1)),
+        ("oracle_pool", oracle_pool_tree()),
+        ("sigmausd_bank", sigmausd_bank_tree()),
+    ] {
+        let bytes = tree.sigma_serialize_bytes().unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| ErgoTree::sigma_parse_bytes(&bytes).unwrap())
+        });
+        group.bench_function(format!("{}_proposition", name), |b| {
+            b.iter(|| tree.proposition().unwrap())
+        });
+        let cache = SharedErgoTreeCache::new(4);
+        group.bench_function(format!("{}_proposition_cached", name), |b| {
+            b.iter(|| cache.proposition(&bytes).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_reduce(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reduce_to_crypto");
+    let any_ctx = Rc::new(seeded_val::<Context>());
+    for (name, tree, ctx) in [
+        ("p2pk", p2pk_tree(1), any_ctx.clone()),
+        ("oracle_pool", oracle_pool_tree(), oracle_pool_context()),
+        ("sigmausd_bank", sigmausd_bank_simplified_tree(), any_ctx),
+    ] {
+        let expr = tree.proposition().unwrap();
+        // make sure the fixture reduces, otherwise the error path is measured
+        reduce_to_crypto(&expr, &Env::empty(), ctx.clone()).unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| reduce_to_crypto(&expr, &Env::empty(), ctx.clone()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_reduce);
+criterion_main!(benches);
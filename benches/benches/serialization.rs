@@ -0,0 +1,39 @@
+//! Transaction serialization and parsing
+
+use bytes::Bytes;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::Throughput;
+use ergo_lib::chain::transaction::view::TransactionView;
+use ergo_lib::chain::transaction::Transaction;
+use ergotree_ir::serialization::SigmaSerializable;
+use sigma_benches::signed_p2pk_tx;
+
+fn bench_tx_serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tx_serialization");
+    for inputs_count in [1u8, 10, 50] {
+        let tx = signed_p2pk_tx(inputs_count).0.spending_tx;
+        let bytes = tx.sigma_serialize_bytes().unwrap();
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::new("serialize", inputs_count), &tx, |b, tx| {
+            b.iter(|| tx.sigma_serialize_bytes().unwrap())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("parse", inputs_count),
+            &bytes,
+            |b, bytes| b.iter(|| Transaction::sigma_parse_bytes(bytes).unwrap()),
+        );
+        let shared = Bytes::from(bytes);
+        group.bench_with_input(
+            BenchmarkId::new("parse_view", inputs_count),
+            &shared,
+            |b, shared| b.iter(|| TransactionView::sigma_parse_bytes(shared.clone()).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_tx_serialization);
+criterion_main!(benches);
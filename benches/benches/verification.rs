@@ -0,0 +1,73 @@
+//! Transaction (spending proofs) verification
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use ergo_lib::chain::transaction::verify_transaction;
+use ergo_lib::wallet::Wallet;
+use ergotree_interpreter::sigma_protocol::verifier::verify_signature;
+use ergotree_interpreter::sigma_protocol::verifier::PrecomputedProveDlog;
+use ergotree_ir::chain::address::Address;
+use sigma_benches::secret_key;
+use sigma_benches::signed_p2pk_tx;
+
+fn bench_verify_transaction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_transaction");
+    for inputs_count in [1u8, 10] {
+        let (tx_context, state_context) = signed_p2pk_tx(inputs_count);
+        assert!(verify_transaction(&tx_context, &state_context)
+            .unwrap()
+            .is_valid());
+        group.bench_with_input(
+            BenchmarkId::new("p2pk", inputs_count),
+            &tx_context,
+            |b, tx_context| b.iter(|| verify_transaction(tx_context, &state_context).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn bench_verify_p2pk_signature(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_p2pk_signature");
+    let secret = secret_key(1);
+    let pk = match secret.get_address_from_public_image() {
+        Address::P2Pk(pk) => pk,
+        _ => unreachable!(),
+    };
+    let message = vec![1u8; 100];
+    let signature = Wallet::from_secrets(vec![secret])
+        .sign_message(pk.clone().into(), &message)
+        .unwrap();
+    assert!(verify_signature(pk.clone().into(), &message, &signature).unwrap());
+    group.bench_function("prepare", |b| {
+        b.iter(|| PrecomputedProveDlog::new(pk.clone()))
+    });
+    group.bench_function("prepare_cached", |b| {
+        b.iter(|| PrecomputedProveDlog::cached(&pk))
+    });
+    // the proposition is prepared on every call
+    group.bench_function("precomputed_per_call", |b| {
+        b.iter(|| {
+            PrecomputedProveDlog::new(pk.clone())
+                .verify(&message, &signature)
+                .unwrap()
+        })
+    });
+    // the proposition is taken from the cache (`verify_signature` path)
+    group.bench_function("cached", |b| {
+        b.iter(|| {
+            PrecomputedProveDlog::cached(&pk)
+                .verify(&message, &signature)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_verify_transaction,
+    bench_verify_p2pk_signature
+);
+criterion_main!(benches);
@@ -0,0 +1,173 @@
+//! Shared fixtures for the benchmarks (see README.md).
+//! The fixtures are deterministic (fixed keys and ids), so the results are comparable between
+//! the runs.
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+use ergo_chain_types::Digest32;
+
+use ergo_lib::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+use ergo_lib::chain::ergo_state_context::ErgoStateContext;
+use ergo_lib::chain::transaction::unsigned::UnsignedTransaction;
+use ergo_lib::chain::transaction::Transaction;
+use ergo_lib::chain::transaction::UnsignedInput;
+use ergo_lib::wallet::secret_key::SecretKey;
+use ergo_lib::wallet::signing::TransactionContext;
+use ergo_lib::wallet::Wallet;
+use ergotree_interpreter::eval::context::Context;
+use ergotree_interpreter::eval::context::TxIoVec;
+use ergotree_ir::chain::address::AddressEncoder;
+use ergotree_ir::chain::address::NetworkPrefix;
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::ergo_box::BoxTokens;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::chain::tx_id::TxId;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::serialization::SigmaSerializable;
+use proptest::arbitrary::any;
+use proptest::arbitrary::Arbitrary;
+use proptest::strategy::Strategy;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::Config;
+use proptest::test_runner::RngAlgorithm;
+use proptest::test_runner::TestRng;
+use proptest::test_runner::TestRunner;
+
+/// Serialized SigmaUSD bank contract deployed on mainnet (EIP-15)
+pub const SIGMAUSD_BANK_TREE_HEX: &str = "102a0400040004000e20011d3364de07e5a26f0c4eef0852cddb387039a921b7154ef3cab22c6eda887f0400040204020400040004020500050005c8010500050005feffffffffffffffff0105000580897a05000580897a040405c80104c0933805c00c0580a8d6b907050005c8010580dac40905000500040404040500050005a0060101050005a0060100040004000e20239c170b7e82f94e6b05416f14b8a2a57e0bfff0e3c93f4abbcd160b6a5b271ad801d601db6501fed1ec9591b172017300d821d602b27201730100d603938cb2db63087202730200017303d604b2a5730400d605c17204d606db6308a7d607b27206730500d6088c720702d609db63087204d60ab27209730600d60b8c720a02d60c947208720bd60db27206730700d60e8c720d02d60fb27209730800d6108c720f02d61194720e7210d612e4c6a70505d613e4c672040505d614e4c6a70405d615e4c672040405d616b2a5730900d617e4c672160405d61895720c730a7217d61995720c7217730bd61ac1a7d61be4c672160505d61c9de4c672020405730cd61da2a1721a9c7214721c730dd61e9572119ca1721c95937214730e730f9d721d72147218d801d61e99721a721d9c9593721e7310731195937212731273139d721e72127219d61f9d9c721e7e7314057315d6209c7215721cd6219591a3731673177318d62295937220731972219d9c7205731a7220edededed7203ededededed927205731b93c27204c2a7edec720c7211efed720c7211ed939a720872129a720b7213939a720e72149a72107215edededed939a721472187215939a721272197213939a721a721b7205927215731c927213731deded938c720f018c720d01938c720a018c720701938cb27209731e00018cb27206731f000193721b9a721e958f721f7320f0721f721f957211959172187321927222732273239591721973249072227221927222732572037326938cb2db6308b2a4732700732800017329";
+
+/// SigmaUSD bank contract with the context accessors replaced by constants, so it can be
+/// fully reduced without a real transaction (see `ergotree-interpreter/src/contracts.rs`)
+pub const SIGMAUSD_BANK_SIMPLIFIED_P2S: &str = "7Nq5tKsVYCgneNgEfA2BJKwGsWozezNLhCNsRBihcHVFkDTuTThd4Qt1bi7NfCK1HuuVfjksMrEftV6MEFajjuyp1TMD2PX7SYWvkg9zH4CtgpdoBjekCNXs5XawxXnW6FT7GCqXTpJUP2TkkuqBh1df99PTigehys36uZz9wQnkrJXrv3mw3Yy4CM622qe5wdqLtpEonjazEmsw8weqEYegDyfJnswDvDkLPXtcCB86i19jik4fnSTtCcYj3jpWCQ7WL5dZn1ivs5JGRsR2ioNCRiZd3Gu1zJBgbHkMg41Z6VeCRWXjGY99BUtgtQiepSHGHajFCVcFAHhVxccdVUPCxGeEL6c2dNx6qzEkVfTfHs5qBgJewR8KCZTCVTurNBHeqCSVdxnfFvhW3f72cNrae5E1UhTAXU2iX4LZMHQsKyefY24Aq1b1srTyRWLpixjbcezFqA2TKjGSn1p1ruxbR7AQpW24ByPKT9sFE9ii4qNeXDnLcGtAAGS9FC5SD1s516a4NCu6v9zZfTvRKGkCwt78J8DEVnhTbttjcsvqFsUXQrvAv7TGVsaT4mL6B7F5BhRoZwFkgRXqFUVCWvgqJrwwjFRtbc5aZz";
+
+/// Oracle pool v2 pool box contract (EIP-23, as deployed by oracle-core):
+/// `sigmaProp(INPUTS(1).tokens(0)._1 == refreshNft || INPUTS(1).tokens(0)._1 == updateNft)`
+pub const ORACLE_POOL_V2_POOL_TREE_HEX: &str = "1004040204000e20546a576e5a7234753778214125442a472d4b614e645267556b587032733576380e206251655468576d5a7134743777217a25432a462d4a404e635266556a586e3272d801d6018cb2db6308b2a473000073010001d1ec93720173029372017303";
+
+/// Pool NFT of the oracle pool fixture
+pub const ORACLE_POOL_NFT: [u8; 32] = [1; 32];
+/// Refresh NFT hardcoded in the oracle pool contract
+pub const ORACLE_REFRESH_NFT_HEX: &str =
+    "546a576e5a7234753778214125442a472d4b614e645267556b58703273357638";
+
+/// Seed of the generated fixtures (contexts, state contexts)
+pub const FIXTURE_SEED: [u8; 32] = [42; 32];
+
+/// Value generated from the fixed seed, the same on every run (unlike `force_any_val`)
+pub fn seeded_val<T: Arbitrary>() -> T {
+    let mut runner = TestRunner::new_with_rng(
+        Config::default(),
+        TestRng::from_seed(RngAlgorithm::ChaCha, &FIXTURE_SEED),
+    );
+    any::<T>().new_tree(&mut runner).unwrap().current()
+}
+
+/// Deterministic secret key, `seed` must be non-zero
+pub fn secret_key(seed: u8) -> SecretKey {
+    let mut bytes = [0u8; 32];
+    bytes[31] = seed;
+    SecretKey::dlog_from_bytes(&bytes).unwrap()
+}
+
+/// P2PK tree of [`secret_key`]
+pub fn p2pk_tree(seed: u8) -> ErgoTree {
+    secret_key(seed)
+        .get_address_from_public_image()
+        .script()
+        .unwrap()
+}
+
+/// Mainnet SigmaUSD bank tree
+pub fn sigmausd_bank_tree() -> ErgoTree {
+    ErgoTree::sigma_parse_bytes(&base16::decode(SIGMAUSD_BANK_TREE_HEX).unwrap()).unwrap()
+}
+
+/// Simplified SigmaUSD bank tree (reduces without a transaction)
+pub fn sigmausd_bank_simplified_tree() -> ErgoTree {
+    AddressEncoder::new(NetworkPrefix::Mainnet)
+        .parse_address_from_str(SIGMAUSD_BANK_SIMPLIFIED_P2S)
+        .unwrap()
+        .script()
+        .unwrap()
+}
+
+/// Oracle pool v2 pool box tree
+pub fn oracle_pool_tree() -> ErgoTree {
+    ErgoTree::sigma_parse_bytes(&base16::decode(ORACLE_POOL_V2_POOL_TREE_HEX).unwrap()).unwrap()
+}
+
+/// Context spending the oracle pool box (SELF) with the refresh box as the second input
+pub fn oracle_pool_context() -> Rc<Context> {
+    let box_with_token = |tree: ErgoTree, nft: TokenId, index: u16| {
+        let token = Token {
+            token_id: nft,
+            amount: 1u64.try_into().unwrap(),
+        };
+        ErgoBox::new(
+            BoxValue::SAFE_USER_MIN,
+            tree,
+            Some(BoxTokens::from_vec(vec![token]).unwrap()),
+            NonMandatoryRegisters::empty(),
+            0,
+            TxId::zero(),
+            index,
+        )
+        .unwrap()
+    };
+    let pool_nft = TokenId::from(Digest32::from(ORACLE_POOL_NFT));
+    let refresh_nft = ORACLE_REFRESH_NFT_HEX.parse().unwrap();
+    let pool_box = Arc::new(box_with_token(oracle_pool_tree(), pool_nft, 0));
+    let refresh_box = Arc::new(box_with_token(p2pk_tree(1), refresh_nft, 1));
+    let mut ctx = seeded_val::<Context>();
+    ctx.self_box = pool_box.clone();
+    ctx.inputs = TxIoVec::from_vec(vec![pool_box, refresh_box]).unwrap();
+    Rc::new(ctx)
+}
+
+/// Transaction spending `inputs_count` P2PK boxes (of different keys) to the same number of
+/// outputs, signed and ready for verification
+pub fn signed_p2pk_tx(inputs_count: u8) -> (TransactionContext<Transaction>, ErgoStateContext) {
+    let secrets: Vec<SecretKey> = (1..=inputs_count).map(secret_key).collect();
+    let boxes_to_spend: Vec<ErgoBox> = (1..=inputs_count)
+        .map(|seed| {
+            ErgoBox::new(
+                BoxValue::SAFE_USER_MIN,
+                p2pk_tree(seed),
+                None,
+                NonMandatoryRegisters::empty(),
+                0,
+                TxId::zero(),
+                seed as u16,
+            )
+            .unwrap()
+        })
+        .collect();
+    let inputs: Vec<UnsignedInput> = boxes_to_spend
+        .iter()
+        .cloned()
+        .map(UnsignedInput::from)
+        .collect();
+    let outputs = boxes_to_spend
+        .iter()
+        .map(|b| {
+            ErgoBoxCandidateBuilder::new(b.value, b.ergo_tree.clone(), 0)
+                .build()
+                .unwrap()
+        })
+        .collect();
+    let unsigned_tx = UnsignedTransaction::new_from_vec(inputs, vec![], outputs).unwrap();
+    let state_context = seeded_val::<ErgoStateContext>();
+    let unsigned_tx_context =
+        TransactionContext::new(unsigned_tx, boxes_to_spend.clone(), vec![]).unwrap();
+    let tx = Wallet::from_secrets(secrets)
+        .sign_transaction(unsigned_tx_context, &state_context, None)
+        .unwrap();
+    (
+        TransactionContext::new(tx, boxes_to_spend, vec![]).unwrap(),
+        state_context,
+    )
+}
@@ -5,6 +5,7 @@ use crate::{
 use ergo_lib::ergotree_ir::chain::address as addr;
 
 /// Address wrapper
+#[derive(Clone)]
 pub struct Address(pub(crate) ergo_lib::ergotree_ir::chain::address::Address);
 pub type AddressPtr = *mut Address;
 pub type ConstAddressPtr = *const Address;
@@ -97,6 +98,34 @@ pub unsafe fn address_from_ergo_tree(
     }
 }
 
+/// Create P2PK address from serialized PK bytes(EcPoint/GroupElement)
+pub unsafe fn address_p2pk_from_pk_bytes(
+    bytes_ptr: *const u8,
+    len: usize,
+    address_out: *mut AddressPtr,
+) -> Result<(), Error> {
+    if bytes_ptr.is_null() {
+        return Err(Error::Misc("bytes_ptr is null".into()));
+    }
+    let address_out = mut_ptr_as_mut(address_out, "address_out")?;
+    let bytes = std::slice::from_raw_parts(bytes_ptr, len);
+    let address = addr::Address::p2pk_from_pk_bytes(bytes).map_err(Error::misc)?;
+    *address_out = Box::into_raw(Box::new(Address(address)));
+    Ok(())
+}
+
+/// Create ErgoTree script from the address
+pub unsafe fn address_to_ergo_tree(
+    address: ConstAddressPtr,
+    ergo_tree_out: *mut crate::ergo_tree::ErgoTreePtr,
+) -> Result<(), Error> {
+    let address = const_ptr_as_ref(address, "address")?;
+    let ergo_tree_out = mut_ptr_as_mut(ergo_tree_out, "ergo_tree_out")?;
+    let ergo_tree = address.0.script().map_err(Error::misc)?;
+    *ergo_tree_out = Box::into_raw(Box::new(crate::ergo_tree::ErgoTree(ergo_tree)));
+    Ok(())
+}
+
 /// Drop the `Address`
 pub unsafe fn address_delete(address: AddressPtr) {
     if !address.is_null() {
@@ -3,6 +3,7 @@
 use std::convert::{TryFrom, TryInto};
 
 use bounded_vec::{BoundedVec, BoundedVecOutOfBounds, OptBoundedVecToVec};
+use ergo_lib::BoundedVecIterExt;
 
 use crate::error::*;
 use crate::util::{const_ptr_as_ref, mut_ptr_as_mut};
@@ -107,11 +108,10 @@ impl<T, S: Into<T> + Clone, const L: usize, const U: usize> TryFrom<&Collection<
             Ok(Some(
                 tokens
                     .0
-                    .clone()
-                    .into_iter()
+                    .iter()
+                    .cloned()
                     .map(Into::into)
-                    .collect::<Vec<T>>()
-                    .try_into()?,
+                    .try_collect_bounded()?,
             ))
         }
     }
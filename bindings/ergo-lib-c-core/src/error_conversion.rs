@@ -28,6 +28,7 @@ use ergo_lib::{
     wallet::{box_selector::BoxSelectorError, tx_builder::TxBuilderError, WalletError},
 };
 use serde_json::error::Error as SerdeError;
+use sigma_util::encoding::DecodingError;
 
 macro_rules! convert_error {
     ($t:ident) => {
@@ -54,6 +55,7 @@ convert_error!(TxBuilderError);
 convert_error!(TxSigningError);
 convert_error!(WalletError);
 convert_error!(DecodeError);
+convert_error!(DecodingError);
 convert_error!(TryFromSliceError);
 convert_error!(TransactionContextError);
 
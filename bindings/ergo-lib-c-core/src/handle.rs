@@ -0,0 +1,433 @@
+//! Thread-safety guarantees and the handle-based API mode
+//!
+//! # Pointers and threads
+//!
+//! Value types (`Address`, `ErgoBox`, `ErgoTree`, `Transaction`, `UnsignedTransaction`,
+//! `SecretKey`, collections, etc.) are `Send + Sync` (checked at compile time below). A pointer to
+//! such a value may be passed to another thread, and the value may be read concurrently through
+//! `Const*Ptr` pointers from any number of threads. Calls that take a mutable pointer (e.g.
+//! `*_add` on collections) and `*_delete` must not run concurrently with any other call using the
+//! same pointer, and the pointer must not be used after `*_delete`. These rules are not checked at
+//! runtime.
+//!
+//! `Wallet` is NOT `Send` (it holds a `dyn Prover`) and is bound to the thread it was created on:
+//! the wallet functions return an [`Error`] when called on another thread, and a wallet deleted
+//! on another thread is leaked instead of being dropped there. For the same reason a wallet can
+//! not be moved into the handle registry.
+//!
+//! # Handles
+//!
+//! With the `handle_registry` feature enabled, values can be moved into a global registry and
+//! referenced by an integer `Handle` instead of a raw pointer. The functions taking a handle
+//! (`address_handle_*`, `ergo_box_handle_*`, `tx_handle_*`, `wallet_sign_transaction_handles`)
+//! run on the registered value and return an [`Error`] for a released handle, or a handle of the
+//! wrong type, instead of dereferencing a dangling pointer. The value stays alive until such a
+//! call returns even if the handle is released concurrently. All registry access is serialized
+//! with a lock, so handles may be used from any thread.
+//!
+//! The rest of the API takes pointers only: `*_from_handle` copies the value into a new pointer,
+//! which has the ownership rules of any other pointer (it has to be deleted and must not be used
+//! afterwards).
+
+use crate::address::Address;
+use crate::collections::Collection;
+use crate::ergo_box::{BoxId, BoxValue, ErgoBox, ErgoBoxCandidate};
+use crate::ergo_tree::ErgoTree;
+use crate::secret_key::SecretKey;
+use crate::transaction::{Transaction, TxId, UnsignedTransaction};
+
+#[cfg(feature = "handle_registry")]
+pub use registry::*;
+
+#[allow(dead_code)]
+fn assert_send_sync<T: Send + Sync>() {}
+
+// Compile-time check of the documented guarantees
+#[allow(dead_code)]
+fn assert_value_types_send_sync() {
+    assert_send_sync::<Address>();
+    assert_send_sync::<BoxId>();
+    assert_send_sync::<BoxValue>();
+    assert_send_sync::<ErgoBox>();
+    assert_send_sync::<ErgoBoxCandidate>();
+    assert_send_sync::<ErgoTree>();
+    assert_send_sync::<SecretKey>();
+    assert_send_sync::<Transaction>();
+    assert_send_sync::<TxId>();
+    assert_send_sync::<UnsignedTransaction>();
+    assert_send_sync::<Collection<ErgoBox>>();
+}
+
+#[cfg(feature = "handle_registry")]
+mod registry {
+    use std::any::Any;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex, MutexGuard};
+
+    use crate::address::{address_from_base58, address_to_base58, Address, NetworkPrefix};
+    use crate::collections::Collection;
+    use crate::ergo_box::{
+        ergo_box_box_id, ergo_box_from_json, ergo_box_to_json, ergo_box_value, BoxIdPtr,
+        BoxValuePtr, ErgoBox,
+    };
+    use crate::ergo_state_ctx::ConstErgoStateContextPtr;
+    use crate::error::Error;
+    use crate::transaction::{
+        tx_from_json, tx_id, tx_to_json, Transaction, TxIdPtr, UnsignedTransaction,
+    };
+    use crate::wallet::{wallet_sign_transaction, ConstWalletPtr};
+
+    /// Integer id of a value stored in the registry. `0` is never a valid handle.
+    pub type Handle = u64;
+
+    type Entry = Arc<dyn Any + Send + Sync>;
+
+    static NEXT_HANDLE: AtomicU64 = AtomicU64::new(1);
+    static REGISTRY: Mutex<BTreeMap<Handle, Entry>> = Mutex::new(BTreeMap::new());
+
+    fn registry() -> MutexGuard<'static, BTreeMap<Handle, Entry>> {
+        // the map is always left in a consistent state, so a poisoned lock is safe to reuse
+        REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get_entry(handle: Handle) -> Result<Entry, Error> {
+        registry()
+            .get(&handle)
+            .cloned()
+            .ok_or(Error::InvalidArgument(
+                "handle is not registered or was released",
+            ))
+    }
+
+    /// Move the value into the registry and return its handle
+    pub fn handle_register<T: Any + Send + Sync>(value: T) -> Handle {
+        let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+        registry().insert(handle, Arc::new(value));
+        handle
+    }
+
+    /// Call `f` with a reference to the value behind the handle. The registry lock is not held
+    /// while `f` runs, and the value stays alive until `f` returns even if the handle is
+    /// released concurrently.
+    pub fn handle_with<T: Any + Send + Sync, R>(
+        handle: Handle,
+        f: impl FnOnce(&T) -> R,
+    ) -> Result<R, Error> {
+        let entry = get_entry(handle)?;
+        let value = entry.downcast_ref::<T>().ok_or(Error::InvalidArgument(
+            "handle refers to a value of another type",
+        ))?;
+        Ok(f(value))
+    }
+
+    /// Remove the value from the registry. Returns `false` if the handle was not registered.
+    pub fn handle_release(handle: Handle) -> bool {
+        registry().remove(&handle).is_some()
+    }
+
+    /// Check whether the handle is registered
+    pub fn handle_is_valid(handle: Handle) -> bool {
+        registry().contains_key(&handle)
+    }
+
+    /// Take ownership of the value behind the pointer and move it into the registry. The pointer
+    /// must not be used (or deleted) afterwards.
+    pub unsafe fn handle_from_ptr<T: Any + Send + Sync>(
+        ptr: *mut T,
+        handle_out: *mut Handle,
+    ) -> Result<(), Error> {
+        if ptr.is_null() {
+            return Err(Error::InvalidArgument("ptr"));
+        }
+        if handle_out.is_null() {
+            return Err(Error::InvalidArgument("handle_out"));
+        }
+        let value = *Box::from_raw(ptr);
+        *handle_out = handle_register(value);
+        Ok(())
+    }
+
+    /// Copy the value behind the handle into a newly allocated pointer (to be deleted with the
+    /// corresponding `*_delete` function). The handle stays registered.
+    pub unsafe fn handle_to_ptr<T: Any + Send + Sync + Clone>(
+        handle: Handle,
+        ptr_out: *mut *mut T,
+    ) -> Result<(), Error> {
+        if ptr_out.is_null() {
+            return Err(Error::InvalidArgument("ptr_out"));
+        }
+        let value = handle_with(handle, T::clone)?;
+        *ptr_out = Box::into_raw(Box::new(value));
+        Ok(())
+    }
+
+    /// Register the value written by `f` into the pointer (e.g. by a `*_from_json` function)
+    unsafe fn handle_from_out<T: Any + Send + Sync>(
+        handle_out: *mut Handle,
+        f: impl FnOnce(*mut *mut T) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        if handle_out.is_null() {
+            return Err(Error::InvalidArgument("handle_out"));
+        }
+        let mut ptr: *mut T = std::ptr::null_mut();
+        f(&mut ptr)?;
+        handle_from_ptr(ptr, handle_out)
+    }
+
+    unsafe fn boxes_from_handles(
+        handles_ptr: *const Handle,
+        len: usize,
+        ptr_name: &'static str,
+    ) -> Result<Vec<ErgoBox>, Error> {
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        if handles_ptr.is_null() {
+            return Err(Error::InvalidArgument(ptr_name));
+        }
+        std::slice::from_raw_parts(handles_ptr, len)
+            .iter()
+            .map(|handle| handle_with(*handle, ErgoBox::clone))
+            .collect()
+    }
+
+    /// Decode (base58) address from string without checking the network prefix
+    pub unsafe fn address_handle_from_base58(
+        address_str: &str,
+        handle_out: *mut Handle,
+    ) -> Result<(), Error> {
+        handle_from_out(handle_out, |address_out| {
+            address_from_base58(address_str, address_out)
+        })
+    }
+
+    /// Encode address as base58 string
+    pub fn address_handle_to_base58(
+        handle: Handle,
+        network_prefix: NetworkPrefix,
+    ) -> Result<String, Error> {
+        handle_with(handle, |address: &Address| unsafe {
+            address_to_base58(address, network_prefix)
+        })?
+    }
+
+    /// Parse box from JSON (compatible with Ergo Node/Explorer API)
+    pub unsafe fn ergo_box_handle_from_json(
+        json: &str,
+        handle_out: *mut Handle,
+    ) -> Result<(), Error> {
+        handle_from_out(handle_out, |ergo_box_out| {
+            ergo_box_from_json(json, ergo_box_out)
+        })
+    }
+
+    /// Get box id
+    pub unsafe fn ergo_box_handle_id(
+        handle: Handle,
+        box_id_out: *mut BoxIdPtr,
+    ) -> Result<(), Error> {
+        handle_with(handle, |ergo_box: &ErgoBox| {
+            ergo_box_box_id(ergo_box, box_id_out)
+        })?
+    }
+
+    /// Get box value in nanoERGs
+    pub unsafe fn ergo_box_handle_value(
+        handle: Handle,
+        box_value_out: *mut BoxValuePtr,
+    ) -> Result<(), Error> {
+        handle_with(handle, |ergo_box: &ErgoBox| {
+            ergo_box_value(ergo_box, box_value_out)
+        })?
+    }
+
+    /// JSON representation as text (compatible with Ergo Node/Explorer API)
+    pub fn ergo_box_handle_to_json(handle: Handle) -> Result<String, Error> {
+        handle_with(handle, |ergo_box: &ErgoBox| unsafe {
+            ergo_box_to_json(ergo_box)
+        })?
+    }
+
+    /// Parse transaction from JSON (compatible with Ergo Node/Explorer API)
+    pub unsafe fn tx_handle_from_json(json: &str, handle_out: *mut Handle) -> Result<(), Error> {
+        handle_from_out(handle_out, |tx_out| tx_from_json(json, tx_out))
+    }
+
+    /// Get transaction id
+    pub unsafe fn tx_handle_id(handle: Handle, tx_id_out: *mut TxIdPtr) -> Result<(), Error> {
+        handle_with(handle, |tx: &Transaction| tx_id(tx, tx_id_out))?
+    }
+
+    /// JSON representation as text (compatible with Ergo Node/Explorer API)
+    pub fn tx_handle_to_json(handle: Handle) -> Result<String, Error> {
+        handle_with(handle, |tx: &Transaction| unsafe { tx_to_json(tx) })?
+    }
+
+    /// Sign the unsigned transaction behind the handle, spending the boxes behind the handles.
+    /// The signed transaction is registered and its handle is written to `transaction_out`.
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn wallet_sign_transaction_handles(
+        wallet_ptr: ConstWalletPtr,
+        state_context_ptr: ConstErgoStateContextPtr,
+        unsigned_tx: Handle,
+        boxes_to_spend_ptr: *const Handle,
+        boxes_to_spend_len: usize,
+        data_boxes_ptr: *const Handle,
+        data_boxes_len: usize,
+        transaction_out: *mut Handle,
+    ) -> Result<(), Error> {
+        let boxes_to_spend = Collection(boxes_from_handles(
+            boxes_to_spend_ptr,
+            boxes_to_spend_len,
+            "boxes_to_spend_ptr",
+        )?);
+        let data_boxes = Collection(boxes_from_handles(
+            data_boxes_ptr,
+            data_boxes_len,
+            "data_boxes_ptr",
+        )?);
+        handle_with(unsigned_tx, |unsigned_tx: &UnsignedTransaction| {
+            handle_from_out(transaction_out, |tx_out| {
+                wallet_sign_transaction(
+                    wallet_ptr,
+                    state_context_ptr,
+                    unsigned_tx,
+                    &boxes_to_spend,
+                    &data_boxes,
+                    tx_out,
+                )
+            })
+        })?
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::unwrap_used)]
+    mod tests {
+        use std::thread;
+
+        use super::*;
+
+        #[test]
+        fn register_lookup_release() {
+            let handle = handle_register(String::from("box"));
+            assert!(handle_is_valid(handle));
+            assert_eq!(handle_with(handle, |s: &String| s.clone()).unwrap(), "box");
+            // the value is kept registered after the lookup
+            assert_eq!(handle_with(handle, String::len).unwrap(), 3);
+            assert!(matches!(
+                handle_with(handle, |_: &u64| ()),
+                Err(Error::InvalidArgument(_))
+            ));
+            assert!(handle_release(handle));
+            assert!(!handle_is_valid(handle));
+            assert!(matches!(
+                handle_with(handle, |_: &String| ()),
+                Err(Error::InvalidArgument(_))
+            ));
+            assert!(!handle_release(handle));
+            assert!(!handle_is_valid(0));
+        }
+
+        #[test]
+        fn handles_are_not_reused() {
+            let first = handle_register(1u32);
+            let second = handle_register(2u32);
+            assert!(first > 0 && second > first);
+            assert!(handle_release(first));
+            let third = handle_register(3u32);
+            assert!(third > second);
+            assert_eq!(handle_with(second, |v: &u32| *v).unwrap(), 2);
+            assert_eq!(handle_with(third, |v: &u32| *v).unwrap(), 3);
+            assert!(handle_release(second));
+            assert!(handle_release(third));
+        }
+
+        #[test]
+        fn value_outlives_release_during_lookup() {
+            let handle = handle_register(vec![1u8, 2, 3]);
+            let sum = handle_with(handle, |v: &Vec<u8>| {
+                assert!(handle_release(handle));
+                v.iter().sum::<u8>()
+            })
+            .unwrap();
+            assert_eq!(sum, 6);
+            assert!(!handle_is_valid(handle));
+        }
+
+        #[test]
+        fn from_and_to_ptr() {
+            unsafe {
+                let mut handle: Handle = 0;
+                let ptr = Box::into_raw(Box::new(String::from("tx")));
+                handle_from_ptr(ptr, &mut handle).unwrap();
+                assert!(handle_is_valid(handle));
+                let mut ptr_out: *mut String = std::ptr::null_mut();
+                handle_to_ptr(handle, &mut ptr_out).unwrap();
+                assert_eq!(*Box::from_raw(ptr_out), "tx");
+                // the copy is independent of the registered value
+                assert_eq!(handle_with(handle, |s: &String| s.clone()).unwrap(), "tx");
+                assert!(handle_release(handle));
+                assert!(handle_to_ptr::<String>(handle, &mut ptr_out).is_err());
+                assert!(handle_from_ptr::<String>(std::ptr::null_mut(), &mut handle).is_err());
+                assert!(handle_to_ptr::<String>(handle, std::ptr::null_mut()).is_err());
+            }
+        }
+
+        #[test]
+        fn handle_ops() {
+            let address_str = "9gmNsqrqdSppLUBqg2UzREmmivgqh1r3jmNcLAc53hk3YCvAGWE";
+            unsafe {
+                let mut handle: Handle = 0;
+                address_handle_from_base58(address_str, &mut handle).unwrap();
+                assert_eq!(
+                    address_handle_to_base58(handle, NetworkPrefix::Mainnet).unwrap(),
+                    address_str
+                );
+                assert!(handle_release(handle));
+                assert!(matches!(
+                    address_handle_to_base58(handle, NetworkPrefix::Mainnet),
+                    Err(Error::InvalidArgument(_))
+                ));
+                let mut invalid: Handle = 0;
+                assert!(address_handle_from_base58("not an address", &mut invalid).is_err());
+                assert_eq!(invalid, 0);
+                let other = handle_register(1u32);
+                let mut tx_id_out: TxIdPtr = std::ptr::null_mut();
+                assert!(matches!(
+                    tx_handle_id(other, &mut tx_id_out),
+                    Err(Error::InvalidArgument(_))
+                ));
+                assert!(tx_id_out.is_null());
+                assert!(handle_release(other));
+            }
+        }
+
+        #[test]
+        fn concurrent_access() {
+            let threads: Vec<_> = (0..8u64)
+                .map(|i| {
+                    thread::spawn(move || {
+                        let handles: Vec<Handle> =
+                            (0..100).map(|j| handle_register(i * 1000 + j)).collect();
+                        for (j, handle) in handles.iter().enumerate() {
+                            assert_eq!(
+                                handle_with(*handle, |v: &u64| *v).unwrap(),
+                                i * 1000 + j as u64
+                            );
+                            assert!(handle_release(*handle));
+                        }
+                        handles
+                    })
+                })
+                .collect();
+            let mut all: Vec<Handle> = threads
+                .into_iter()
+                .flat_map(|t| t.join().unwrap())
+                .collect();
+            all.sort_unstable();
+            all.dedup();
+            assert_eq!(all.len(), 800);
+        }
+    }
+}
@@ -19,6 +19,7 @@ pub unsafe fn node_conf_from_addr(addr: &str, ptr_out: *mut NodeConfPtr) -> Resu
         addr: peer_addr,
         api_key: None,
         timeout: None,
+        retry: Default::default(),
     };
     *ptr_out = Box::into_raw(Box::new(node_conf.into()));
     Ok(())
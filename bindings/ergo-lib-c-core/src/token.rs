@@ -2,10 +2,7 @@
 
 use std::convert::TryFrom;
 
-use ergo_lib::{
-    ergo_chain_types::{Base16DecodedBytes, Digest32},
-    ergotree_ir::chain,
-};
+use ergo_lib::ergotree_ir::chain;
 
 use crate::{
     ergo_box::ConstBoxIdPtr,
@@ -34,11 +31,7 @@ pub unsafe fn token_id_from_box_id(
 /// Parse token id (32 byte digest) from base16-encoded string
 pub unsafe fn token_id_from_str(str: &str, token_id_out: *mut TokenIdPtr) -> Result<(), Error> {
     let token_id_out = mut_ptr_as_mut(token_id_out, "token_id_out")?;
-    let base_16_decoded_bytes = Base16DecodedBytes::try_from(str.to_string())?;
-
-    let token_id = Digest32::try_from(base_16_decoded_bytes)
-        .map(|dig| dig.into())
-        .map(TokenId)?;
+    let token_id = str.parse::<chain::token::TokenId>().map(TokenId)?;
     *token_id_out = Box::into_raw(Box::new(token_id));
     Ok(())
 }
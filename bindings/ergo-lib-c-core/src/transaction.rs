@@ -1,11 +1,6 @@
 //! Ergo transaction
 
-use std::convert::{TryFrom, TryInto};
-
-use ergo_lib::{
-    chain,
-    ergo_chain_types::{Base16DecodedBytes, Base16EncodedBytes},
-};
+use ergo_lib::chain;
 
 use crate::{
     collections::{Collection, CollectionPtr, ConstCollectionPtr},
@@ -281,10 +276,7 @@ pub type ConstTxIdPtr = *const TxId;
 /// Convert a hex string into a TxId
 pub unsafe fn tx_id_from_str(str: &str, tx_id_out: *mut TxIdPtr) -> Result<(), Error> {
     let tx_id_out = mut_ptr_as_mut(tx_id_out, "tx_id_out")?;
-    let bytes = Base16DecodedBytes::try_from(str.to_string())?;
-    let tx_id = bytes
-        .try_into()
-        .map(|digest| TxId(chain::transaction::TxId(digest)))?;
+    let tx_id = str.parse::<chain::transaction::TxId>().map(TxId)?;
     *tx_id_out = Box::into_raw(Box::new(tx_id));
     Ok(())
 }
@@ -292,8 +284,7 @@ pub unsafe fn tx_id_from_str(str: &str, tx_id_out: *mut TxIdPtr) -> Result<(), E
 /// Get the tx id as bytes
 pub unsafe fn tx_id_to_str(tx_id_ptr: ConstTxIdPtr) -> Result<String, Error> {
     let tx_id = const_ptr_as_ref(tx_id_ptr, "tx_id_ptr")?;
-    let base16_bytes = Base16EncodedBytes::new(tx_id.0 .0 .0.as_ref());
-    Ok(base16_bytes.into())
+    Ok(tx_id.0.to_string())
 }
 
 /**
@@ -306,6 +297,7 @@ pub unsafe fn tx_id_to_str(tx_id_ptr: ConstTxIdPtr) -> Result<String, Error> {
  * Transactions are not encrypted, so it is possible to browse and view every transaction ever
  * collected into a block.
  */
+#[derive(Clone)]
 pub struct Transaction(pub(crate) chain::transaction::Transaction);
 pub type TransactionPtr = *mut Transaction;
 pub type ConstTransactionPtr = *const Transaction;
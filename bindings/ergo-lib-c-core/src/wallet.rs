@@ -3,12 +3,14 @@
 use ergo_lib::ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
 
 use std::str::FromStr;
+use std::thread::{self, ThreadId};
 
 use crate::{
     address::{Address, ConstAddressPtr},
     collections::ConstCollectionPtr,
     ergo_box::ErgoBox,
     ergo_state_ctx::ConstErgoStateContextPtr,
+    input::{Input, InputPtr},
     reduced::ConstReducedTransactionPtr,
     secret_key::SecretKey,
     transaction::{
@@ -20,10 +22,34 @@ use crate::{
 };
 
 /// A collection of secret keys. This simplified signing by matching the secret keys to the correct inputs automatically.
-pub struct Wallet(ergo_lib::wallet::Wallet);
+/// The wallet is bound to the thread it was created on (see `crate::handle`).
+pub struct Wallet(ergo_lib::wallet::Wallet, ThreadId);
 pub type WalletPtr = *mut Wallet;
 pub type ConstWalletPtr = *const Wallet;
 
+impl Wallet {
+    fn new(wallet: ergo_lib::wallet::Wallet) -> Self {
+        Wallet(wallet, thread::current().id())
+    }
+
+    /// `ergo_lib::wallet::Wallet` is not `Send`, so it may only be used on its own thread
+    fn check_thread(&self) -> Result<(), Error> {
+        if self.1 == thread::current().id() {
+            Ok(())
+        } else {
+            Err(Error::InvalidArgument(
+                "wallet_ptr is used on a thread other than the one it was created on",
+            ))
+        }
+    }
+}
+
+unsafe fn wallet_ref<'a>(wallet_ptr: ConstWalletPtr) -> Result<&'a Wallet, Error> {
+    let wallet = const_ptr_as_ref(wallet_ptr, "wallet_ptr")?;
+    wallet.check_thread()?;
+    Ok(wallet)
+}
+
 pub struct MnemonicGenerator(ergo_lib::wallet::mnemonic_generator::MnemonicGenerator);
 pub type MnemonicGeneratorPtr = *mut MnemonicGenerator;
 
@@ -81,7 +107,7 @@ pub unsafe fn wallet_from_mnemonic(
     if let Ok(wallet_inner) =
         ergo_lib::wallet::Wallet::from_mnemonic(mnemonic_phrase, mnemonic_pass)
     {
-        *wallet_out = Box::into_raw(Box::new(Wallet(wallet_inner)));
+        *wallet_out = Box::into_raw(Box::new(Wallet::new(wallet_inner)));
         Ok(())
     } else {
         Err(Error::Misc(
@@ -97,9 +123,11 @@ pub unsafe fn wallet_from_secrets(
 ) -> Result<(), Error> {
     let secret_keys = const_ptr_as_ref(secret_keys_ptr, "secret_keys_ptr")?;
     let wallet_out = mut_ptr_as_mut(wallet_out, "wallet_out")?;
-    *wallet_out = Box::into_raw(Box::new(Wallet(ergo_lib::wallet::Wallet::from_secrets(
-        secret_keys.0.clone().into_iter().map(|s| s.0).collect(),
-    ))));
+    *wallet_out = Box::into_raw(Box::new(Wallet::new(
+        ergo_lib::wallet::Wallet::from_secrets(
+            secret_keys.0.clone().into_iter().map(|s| s.0).collect(),
+        ),
+    )));
     Ok(())
 }
 
@@ -109,6 +137,7 @@ pub unsafe fn wallet_add_secret(
     secret_key_ptr: *mut SecretKey,
 ) -> Result<(), Error> {
     let wallet = mut_ptr_as_mut(wallet_ptr, "wallet_ptr")?;
+    wallet.check_thread()?;
     let sk = mut_ptr_as_mut(secret_key_ptr, "secret_key_ptr")?;
     wallet.0.add_secret(sk.0.clone());
     Ok(())
@@ -123,7 +152,7 @@ pub unsafe fn wallet_sign_transaction(
     data_boxes_ptr: ConstCollectionPtr<ErgoBox>,
     transaction_out: *mut TransactionPtr,
 ) -> Result<(), Error> {
-    let wallet = const_ptr_as_ref(wallet_ptr, "wallet_ptr")?;
+    let wallet = wallet_ref(wallet_ptr)?;
     let state_context = const_ptr_as_ref(state_context_ptr, "state_context_ptr")?;
     let unsigned_tx = const_ptr_as_ref(unsigned_tx_ptr, "unsigned_tx_ptr")?;
     let boxes_to_spend = const_ptr_as_ref(boxes_to_spend_ptr, "boxes_to_spend_ptr")?;
@@ -153,7 +182,7 @@ pub unsafe fn wallet_sign_transaction_multi(
     tx_hints_ptr: ConstTransactionHintsBagPtr,
     transaction_out: *mut TransactionPtr,
 ) -> Result<(), Error> {
-    let wallet = const_ptr_as_ref(wallet_ptr, "wallet_ptr")?;
+    let wallet = wallet_ref(wallet_ptr)?;
     let state_context = const_ptr_as_ref(state_context_ptr, "state_context_ptr")?;
     let unsigned_tx = const_ptr_as_ref(unsigned_tx_ptr, "unsigned_tx_ptr")?;
     let boxes_to_spend = const_ptr_as_ref(boxes_to_spend_ptr, "boxes_to_spend_ptr")?;
@@ -169,18 +198,48 @@ pub unsafe fn wallet_sign_transaction_multi(
     )?;
     let tx = wallet
         .0
-        .sign_transaction(tx_context, &state_context.0, Some(&tx_hints.0))?;
+        .sign_transaction_multi(tx_context, &state_context.0, &tx_hints.0)?;
     *transaction_out = Box::into_raw(Box::new(Transaction(tx)));
     Ok(())
 }
 
+/// Signs a given transaction input
+pub unsafe fn wallet_sign_tx_input(
+    wallet_ptr: ConstWalletPtr,
+    input_idx: usize,
+    state_context_ptr: ConstErgoStateContextPtr,
+    unsigned_tx_ptr: ConstUnsignedTransactionPtr,
+    boxes_to_spend_ptr: ConstCollectionPtr<ErgoBox>,
+    data_boxes_ptr: ConstCollectionPtr<ErgoBox>,
+    input_out: *mut InputPtr,
+) -> Result<(), Error> {
+    let wallet = wallet_ref(wallet_ptr)?;
+    let state_context = const_ptr_as_ref(state_context_ptr, "state_context_ptr")?;
+    let unsigned_tx = const_ptr_as_ref(unsigned_tx_ptr, "unsigned_tx_ptr")?;
+    let boxes_to_spend = const_ptr_as_ref(boxes_to_spend_ptr, "boxes_to_spend_ptr")?;
+    let data_boxes = const_ptr_as_ref(data_boxes_ptr, "data_boxes_ptr")?;
+    let input_out = mut_ptr_as_mut(input_out, "input_out")?;
+    let boxes_to_spend = boxes_to_spend.0.clone().into_iter().map(|b| b.0).collect();
+    let data_boxes = data_boxes.0.clone().into_iter().map(|b| b.0).collect();
+    let tx_context = ergo_lib::wallet::signing::TransactionContext::new(
+        unsigned_tx.0.clone(),
+        boxes_to_spend,
+        data_boxes,
+    )?;
+    let input = wallet
+        .0
+        .sign_tx_input(input_idx, tx_context, &state_context.0, None)?;
+    *input_out = Box::into_raw(Box::new(Input(input)));
+    Ok(())
+}
+
 /// Signs a reduced transaction (generating proofs for inputs)
 pub unsafe fn wallet_sign_reduced_transaction(
     wallet_ptr: ConstWalletPtr,
     reduced_tx_ptr: ConstReducedTransactionPtr,
     transaction_out: *mut TransactionPtr,
 ) -> Result<(), Error> {
-    let wallet = const_ptr_as_ref(wallet_ptr, "wallet_ptr")?;
+    let wallet = wallet_ref(wallet_ptr)?;
     let reduced_tx = const_ptr_as_ref(reduced_tx_ptr, "reduced_tx_ptr")?;
     let transaction_out = mut_ptr_as_mut(transaction_out, "transaction_out")?;
     let tx = wallet
@@ -198,7 +257,7 @@ pub unsafe fn wallet_sign_reduced_transaction_multi(
     tx_hints_ptr: ConstTransactionHintsBagPtr,
     transaction_out: *mut TransactionPtr,
 ) -> Result<(), Error> {
-    let wallet = const_ptr_as_ref(wallet_ptr, "wallet_ptr")?;
+    let wallet = wallet_ref(wallet_ptr)?;
     let reduced_tx = const_ptr_as_ref(reduced_tx_ptr, "reduced_tx_ptr")?;
     let transaction_out = mut_ptr_as_mut(transaction_out, "transaction_out")?;
     let tx_hints = const_ptr_as_ref(tx_hints_ptr, "tx_hints_ptr")?;
@@ -219,7 +278,7 @@ pub unsafe fn wallet_generate_commitments(
     data_boxes_ptr: ConstCollectionPtr<ErgoBox>,
     transaction_hints_bag_out: *mut TransactionHintsBagPtr,
 ) -> Result<(), Error> {
-    let wallet = const_ptr_as_ref(wallet_ptr, "wallet_ptr")?;
+    let wallet = wallet_ref(wallet_ptr)?;
     let state_context = const_ptr_as_ref(state_context_ptr, "state_context_ptr")?;
     let unsigned_tx = const_ptr_as_ref(unsigned_tx_ptr, "unsigned_tx_ptr")?;
     let boxes_to_spend = const_ptr_as_ref(boxes_to_spend_ptr, "boxes_to_spend_ptr")?;
@@ -246,7 +305,7 @@ pub unsafe fn wallet_generate_commitments_for_reduced_transaction(
     reduced_tx_ptr: ConstReducedTransactionPtr,
     transaction_hints_bag_out: *mut TransactionHintsBagPtr,
 ) -> Result<(), Error> {
-    let wallet = const_ptr_as_ref(wallet_ptr, "wallet_ptr")?;
+    let wallet = wallet_ref(wallet_ptr)?;
     let reduced_tx = const_ptr_as_ref(reduced_tx_ptr, "reduced_tx_ptr")?;
     let transaction_hints_bag_out = mut_ptr_as_mut(transaction_hints_bag_out, "transaction_out")?;
     *transaction_hints_bag_out = Box::into_raw(Box::new(TransactionHintsBag(
@@ -270,7 +329,7 @@ pub unsafe fn wallet_sign_message_using_p2pk(
     message_length: usize,
     signed_message_out: *mut SignedMessagePtr,
 ) -> Result<(), Error> {
-    let wallet = const_ptr_as_ref(wallet_ptr, "wallet_ptr")?;
+    let wallet = wallet_ref(wallet_ptr)?;
     let address = const_ptr_as_ref(address_ptr, "address_ptr")?;
     let msg = std::slice::from_raw_parts(message_ptr, message_length);
     let signed_message_out = mut_ptr_as_mut(signed_message_out, "signed_message_out")?;
@@ -311,3 +370,68 @@ pub unsafe fn verify_signature(
         ))
     }
 }
+
+/// Drop the wallet. A wallet deleted on a thread other than the one it was created on is leaked
+/// instead of being dropped there.
+pub unsafe fn wallet_delete(wallet_ptr: WalletPtr) {
+    if !wallet_ptr.is_null() {
+        let wallet = Box::from_raw(wallet_ptr);
+        if wallet.check_thread().is_err() {
+            std::mem::forget(wallet);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::collections::Collection;
+
+    #[test]
+    fn wallet_is_bound_to_its_thread() {
+        unsafe {
+            let mut wallet_ptr: WalletPtr = std::ptr::null_mut();
+            wallet_from_secrets(&Collection(Vec::new()), &mut wallet_ptr).unwrap();
+            // the wallet check passes on the owner thread, the secret key is missing
+            assert!(matches!(
+                wallet_add_secret(wallet_ptr, std::ptr::null_mut()),
+                Err(Error::InvalidArgument("secret_key_ptr"))
+            ));
+            assert!(matches!(
+                wallet_sign_transaction(
+                    wallet_ptr,
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    std::ptr::null_mut(),
+                ),
+                Err(Error::InvalidArgument("state_context_ptr"))
+            ));
+            let wallet_addr = wallet_ptr as usize;
+            let other_thread = std::thread::spawn(move || {
+                let wallet_ptr = wallet_addr as WalletPtr;
+                let is_rejected = |res: Result<(), Error>| {
+                    matches!(
+                        res,
+                        Err(Error::InvalidArgument(
+                            "wallet_ptr is used on a thread other than the one it was created on"
+                        ))
+                    )
+                };
+                is_rejected(wallet_add_secret(wallet_ptr, std::ptr::null_mut()))
+                    && is_rejected(wallet_sign_transaction(
+                        wallet_ptr,
+                        std::ptr::null(),
+                        std::ptr::null(),
+                        std::ptr::null(),
+                        std::ptr::null(),
+                        std::ptr::null_mut(),
+                    ))
+            });
+            assert!(other_thread.join().unwrap());
+            wallet_delete(wallet_ptr);
+        }
+    }
+}
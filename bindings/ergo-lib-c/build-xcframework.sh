@@ -0,0 +1,98 @@
+#!/usr/bin/env bash
+# Builds `ErgoLibC.xcframework` (static library + headers + module map) for iOS device and
+# simulator (Apple silicon and Intel) to be consumed as a SwiftPM binary target.
+#
+# Usage: ./build-xcframework.sh [--features "rest mnemonic_gen"] [--skip-header] [--zip]
+#
+# The framework is also copied to `bindings/ergo-lib-ios` where it's picked up by `Package.swift`
+# when `ERGO_LIB_XCFRAMEWORK` environment variable is set.
+#
+# Requires macOS with Xcode command line tools, `cbindgen` and a nightly toolchain for the
+# header generation (see README.md). Output is placed in `<repo root>/target/xcframework`.
+
+set -euo pipefail
+
+FEATURES="rest mnemonic_gen"
+SKIP_HEADER=0
+ZIP=0
+
+while [[ $# -gt 0 ]]; do
+  case "$1" in
+    --features) FEATURES="$2"; shift 2 ;;
+    --skip-header) SKIP_HEADER=1; shift ;;
+    --zip) ZIP=1; shift ;;
+    *) echo "unknown argument: $1" >&2; exit 1 ;;
+  esac
+done
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+ROOT_DIR="$(cd "$SCRIPT_DIR/../.." && pwd)"
+TARGET_DIR="$ROOT_DIR/target"
+OUT_DIR="$TARGET_DIR/xcframework"
+LIB_NAME="libergo.a"
+MODULE_NAME="ErgoLibC"
+
+DEVICE_TARGET="aarch64-apple-ios"
+SIM_TARGETS=("aarch64-apple-ios-sim" "x86_64-apple-ios")
+
+# 1. C header (cbindgen needs a nightly rustc to expand the macros)
+if [[ $SKIP_HEADER -eq 0 ]]; then
+  (cd "$SCRIPT_DIR" && rustup run nightly cbindgen --config cbindgen.toml --crate ergo-lib-c --output h/ergo_lib.h)
+fi
+
+# 2. Static library slices
+for target in "$DEVICE_TARGET" "${SIM_TARGETS[@]}"; do
+  rustup target add "$target"
+  cargo build --release -p ergo-lib-c --target "$target" --features "$FEATURES" --manifest-path "$ROOT_DIR/Cargo.toml"
+done
+
+rm -rf "$OUT_DIR"
+mkdir -p "$OUT_DIR/sim" "$OUT_DIR/headers"
+
+# simulator slices are merged into one fat library (one library per platform in xcframework)
+lipo -create \
+  "$TARGET_DIR/${SIM_TARGETS[0]}/release/$LIB_NAME" \
+  "$TARGET_DIR/${SIM_TARGETS[1]}/release/$LIB_NAME" \
+  -output "$OUT_DIR/sim/$LIB_NAME"
+
+# 3. Umbrella header and module map. Feature defines used in the generated header (see
+# `[defines]` in cbindgen.toml) are set according to the enabled features.
+cp "$SCRIPT_DIR/h/ergo_lib.h" "$OUT_DIR/headers/"
+{
+  echo "#ifndef ERGO_LIB_C_UMBRELLA_H_"
+  echo "#define ERGO_LIB_C_UMBRELLA_H_"
+  echo
+  for feature in $FEATURES; do
+    case "$feature" in
+      rest) echo "#define ERGO_REST" ;;
+      handle_registry) echo "#define ERGO_HANDLE_REGISTRY" ;;
+    esac
+  done
+  echo
+  echo "#include \"ergo_lib.h\""
+  echo
+  echo "#endif /* ERGO_LIB_C_UMBRELLA_H_ */"
+} > "$OUT_DIR/headers/$MODULE_NAME.h"
+
+cat > "$OUT_DIR/headers/module.modulemap" <<EOF
+module $MODULE_NAME {
+    umbrella header "$MODULE_NAME.h"
+    export *
+}
+EOF
+
+# 4. XCFramework
+xcodebuild -create-xcframework \
+  -library "$TARGET_DIR/$DEVICE_TARGET/release/$LIB_NAME" -headers "$OUT_DIR/headers" \
+  -library "$OUT_DIR/sim/$LIB_NAME" -headers "$OUT_DIR/headers" \
+  -output "$OUT_DIR/$MODULE_NAME.xcframework"
+
+rm -rf "$ROOT_DIR/bindings/ergo-lib-ios/$MODULE_NAME.xcframework"
+cp -R "$OUT_DIR/$MODULE_NAME.xcframework" "$ROOT_DIR/bindings/ergo-lib-ios/"
+
+if [[ $ZIP -eq 1 ]]; then
+  (cd "$OUT_DIR" && zip -qry "$MODULE_NAME.xcframework.zip" "$MODULE_NAME.xcframework")
+  echo "checksum: $(swift package compute-checksum "$OUT_DIR/$MODULE_NAME.xcframework.zip")"
+fi
+
+echo "built $OUT_DIR/$MODULE_NAME.xcframework"
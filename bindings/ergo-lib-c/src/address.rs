@@ -1,10 +1,10 @@
 use ergo_lib_c_core::{
     address::{
         address_delete, address_from_base58, address_from_ergo_tree, address_from_mainnet,
-        address_from_testnet, address_to_base58, address_type_prefix, AddressPtr, ConstAddressPtr,
-        NetworkPrefix,
+        address_from_testnet, address_p2pk_from_pk_bytes, address_to_base58, address_to_ergo_tree,
+        address_type_prefix, AddressPtr, ConstAddressPtr, NetworkPrefix,
     },
-    ergo_tree::ConstErgoTreePtr,
+    ergo_tree::{ConstErgoTreePtr, ErgoTreePtr},
     Error,
 };
 use std::{
@@ -66,6 +66,25 @@ pub unsafe extern "C" fn ergo_lib_address_from_ergo_tree(
     Error::c_api_from(res)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_address_p2pk_from_pk_bytes(
+    bytes_ptr: *const u8,
+    len: usize,
+    address_out: *mut AddressPtr,
+) -> ErrorPtr {
+    let res = address_p2pk_from_pk_bytes(bytes_ptr, len, address_out);
+    Error::c_api_from(res)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_address_to_ergo_tree(
+    address: ConstAddressPtr,
+    ergo_tree_out: *mut ErgoTreePtr,
+) -> ErrorPtr {
+    let res = address_to_ergo_tree(address, ergo_tree_out);
+    Error::c_api_from(res)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn ergo_lib_address_type_prefix(address: ConstAddressPtr) -> u8 {
     #[allow(clippy::unwrap_used)]
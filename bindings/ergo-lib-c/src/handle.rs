@@ -0,0 +1,171 @@
+//! Handle-based (integer id) API mode. See `ergo_lib_c_core::handle` for the thread-safety rules
+//! of the pointer-based API.
+
+use ergo_lib_c_core::{
+    address::{AddressPtr, NetworkPrefix},
+    ergo_box::{BoxIdPtr, BoxValuePtr, ErgoBoxCandidatePtr, ErgoBoxPtr},
+    ergo_state_ctx::ConstErgoStateContextPtr,
+    ergo_tree::ErgoTreePtr,
+    handle::*,
+    secret_key::SecretKeyPtr,
+    transaction::{TransactionPtr, TxIdPtr, UnsignedTransactionPtr},
+    wallet::ConstWalletPtr,
+    Error,
+};
+use paste::paste;
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+};
+
+use crate::ErrorPtr;
+
+/// Remove the value from the registry. Returns `false` if the handle was not registered (or
+/// already released).
+#[no_mangle]
+pub extern "C" fn ergo_lib_handle_release(handle: Handle) -> bool {
+    handle_release(handle)
+}
+
+/// Check whether the handle is registered
+#[no_mangle]
+pub extern "C" fn ergo_lib_handle_is_valid(handle: Handle) -> bool {
+    handle_is_valid(handle)
+}
+
+make_handle!(Address);
+make_handle!(ErgoBox);
+make_handle!(ErgoBoxCandidate);
+make_handle!(ErgoTree);
+make_handle!(SecretKey);
+make_handle!(Transaction);
+make_handle!(UnsignedTransaction);
+
+unsafe fn string_out(res: Result<String, Error>, str_out: *mut *const c_char) -> ErrorPtr {
+    #[allow(clippy::unwrap_used)]
+    let res = res.map(|s| {
+        *str_out = CString::new(s).unwrap().into_raw();
+    });
+    Error::c_api_from(res)
+}
+
+/// Decode (base58) address from string without checking the network prefix and register it
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_address_handle_from_base58(
+    address_str: *const c_char,
+    handle_out: *mut Handle,
+) -> ErrorPtr {
+    let address = CStr::from_ptr(address_str).to_string_lossy();
+    let res = address_handle_from_base58(&address, handle_out);
+    Error::c_api_from(res)
+}
+
+/// Encode the address behind the handle as base58 string
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_address_handle_to_base58(
+    handle: Handle,
+    network_prefix: NetworkPrefix,
+    _address_str: *mut *const c_char,
+) -> ErrorPtr {
+    string_out(
+        address_handle_to_base58(handle, network_prefix),
+        _address_str,
+    )
+}
+
+/// Parse box from JSON (compatible with Ergo Node/Explorer API) and register it
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_ergo_box_handle_from_json(
+    json_str: *const c_char,
+    handle_out: *mut Handle,
+) -> ErrorPtr {
+    let json = CStr::from_ptr(json_str).to_string_lossy();
+    let res = ergo_box_handle_from_json(&json, handle_out);
+    Error::c_api_from(res)
+}
+
+/// Get the id of the box behind the handle
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_ergo_box_handle_id(
+    handle: Handle,
+    box_id_out: *mut BoxIdPtr,
+) -> ErrorPtr {
+    let res = ergo_box_handle_id(handle, box_id_out);
+    Error::c_api_from(res)
+}
+
+/// Get the value of the box behind the handle
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_ergo_box_handle_value(
+    handle: Handle,
+    box_value_out: *mut BoxValuePtr,
+) -> ErrorPtr {
+    let res = ergo_box_handle_value(handle, box_value_out);
+    Error::c_api_from(res)
+}
+
+/// JSON representation of the box behind the handle (compatible with Ergo Node/Explorer API)
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_ergo_box_handle_to_json(
+    handle: Handle,
+    _json_str: *mut *const c_char,
+) -> ErrorPtr {
+    string_out(ergo_box_handle_to_json(handle), _json_str)
+}
+
+/// Parse transaction from JSON (compatible with Ergo Node/Explorer API) and register it
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_tx_handle_from_json(
+    json_str: *const c_char,
+    handle_out: *mut Handle,
+) -> ErrorPtr {
+    let json = CStr::from_ptr(json_str).to_string_lossy();
+    let res = tx_handle_from_json(&json, handle_out);
+    Error::c_api_from(res)
+}
+
+/// Get the id of the transaction behind the handle
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_tx_handle_id(
+    handle: Handle,
+    tx_id_out: *mut TxIdPtr,
+) -> ErrorPtr {
+    let res = tx_handle_id(handle, tx_id_out);
+    Error::c_api_from(res)
+}
+
+/// JSON representation of the transaction behind the handle (compatible with Ergo Node/Explorer
+/// API)
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_tx_handle_to_json(
+    handle: Handle,
+    _json_str: *mut *const c_char,
+) -> ErrorPtr {
+    string_out(tx_handle_to_json(handle), _json_str)
+}
+
+/// Sign the unsigned transaction behind the handle, spending the boxes behind the handles. The
+/// signed transaction is registered and its handle is written to `transaction_out`.
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_wallet_sign_transaction_handles(
+    wallet_ptr: ConstWalletPtr,
+    state_context_ptr: ConstErgoStateContextPtr,
+    unsigned_tx: Handle,
+    boxes_to_spend_ptr: *const Handle,
+    boxes_to_spend_len: usize,
+    data_boxes_ptr: *const Handle,
+    data_boxes_len: usize,
+    transaction_out: *mut Handle,
+) -> ErrorPtr {
+    let res = wallet_sign_transaction_handles(
+        wallet_ptr,
+        state_context_ptr,
+        unsigned_tx,
+        boxes_to_spend_ptr,
+        boxes_to_spend_len,
+        data_boxes_ptr,
+        data_boxes_len,
+        transaction_out,
+    );
+    Error::c_api_from(res)
+}
@@ -28,6 +28,8 @@ mod ergo_box;
 mod ergo_state_ctx;
 mod ergo_tree;
 mod ext_secret_key;
+#[cfg(feature = "handle_registry")]
+mod handle;
 mod header;
 mod input;
 mod merkleproof;
@@ -56,6 +58,8 @@ pub use crate::data_input::*;
 pub use crate::ergo_box::*;
 pub use crate::ergo_state_ctx::*;
 pub use crate::ergo_tree::*;
+#[cfg(feature = "handle_registry")]
+pub use crate::handle::*;
 pub use crate::header::*;
 pub use crate::input::*;
 pub use crate::merkleproof::*;
@@ -137,3 +137,34 @@ macro_rules! make_ffi_eq {
         }
     };
 }
+
+/// Generates functions to move a value of the given type into the handle registry and to copy it
+/// back out into a pointer. See `ergo_lib_c_core::handle`.
+#[cfg(feature = "handle_registry")]
+#[macro_export]
+macro_rules! make_handle {
+    ($type_name:ident) => {
+        paste! {
+            /// Move the value into the handle registry. The pointer is consumed and must not be
+            /// used or deleted afterwards.
+            #[no_mangle]
+            pub unsafe extern "C" fn [<ergo_lib_ $type_name:snake _into_handle>](
+                [< $type_name:snake _ptr>]: [< $type_name Ptr>],
+                handle_out: *mut Handle,
+            ) -> ErrorPtr {
+                let res = handle_from_ptr([< $type_name:snake _ptr>], handle_out);
+                Error::c_api_from(res)
+            }
+
+            /// Copy the value behind the handle into a new pointer. The handle stays registered.
+            #[no_mangle]
+            pub unsafe extern "C" fn [<ergo_lib_ $type_name:snake _from_handle>](
+                handle: Handle,
+                [< $type_name:snake _out>]: *mut [< $type_name Ptr>],
+            ) -> ErrorPtr {
+                let res = handle_to_ptr(handle, [< $type_name:snake _out>]);
+                Error::c_api_from(res)
+            }
+        }
+    };
+}
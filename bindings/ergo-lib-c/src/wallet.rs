@@ -7,6 +7,7 @@ use ergo_lib_c_core::{
     collections::ConstCollectionPtr,
     ergo_box::ErgoBox,
     ergo_state_ctx::ConstErgoStateContextPtr,
+    input::InputPtr,
     reduced::ConstReducedTransactionPtr,
     secret_key::SecretKey,
     transaction::{
@@ -165,6 +166,29 @@ pub unsafe extern "C" fn ergo_lib_wallet_sign_transaction_multi(
     Error::c_api_from(res)
 }
 
+/// Signs a given transaction input
+#[no_mangle]
+pub unsafe extern "C" fn ergo_lib_wallet_sign_tx_input(
+    wallet_ptr: ConstWalletPtr,
+    input_idx: usize,
+    state_context_ptr: ConstErgoStateContextPtr,
+    unsigned_tx_ptr: ConstUnsignedTransactionPtr,
+    boxes_to_spend_ptr: ConstCollectionPtr<ErgoBox>,
+    data_boxes_ptr: ConstCollectionPtr<ErgoBox>,
+    input_out: *mut InputPtr,
+) -> ErrorPtr {
+    let res = wallet_sign_tx_input(
+        wallet_ptr,
+        input_idx,
+        state_context_ptr,
+        unsigned_tx_ptr,
+        boxes_to_spend_ptr,
+        data_boxes_ptr,
+        input_out,
+    );
+    Error::c_api_from(res)
+}
+
 /// Signs a reduced transaction (generating proofs for inputs)
 #[no_mangle]
 pub unsafe extern "C" fn ergo_lib_wallet_sign_reduced_transaction(
@@ -277,5 +301,5 @@ pub unsafe extern "C" fn ergo_lib_signed_message_delete(ptr: SignedMessagePtr) {
 /// Drop `Wallet`
 #[no_mangle]
 pub unsafe extern "C" fn ergo_lib_wallet_delete(ptr: WalletPtr) {
-    delete_ptr(ptr)
+    wallet_delete(ptr)
 }
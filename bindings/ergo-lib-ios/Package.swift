@@ -1,7 +1,17 @@
-// swift-tools-version:5.2
+// swift-tools-version:5.3
 
+import Foundation
 import PackageDescription
 
+// Set `ERGO_LIB_XCFRAMEWORK` environment variable to use the prebuilt `ErgoLibC.xcframework`
+// (see `../ergo-lib-c/build-xcframework.sh`) instead of linking `libergo` from the library search
+// path.
+let useXCFramework = ProcessInfo.processInfo.environment["ERGO_LIB_XCFRAMEWORK"] != nil
+
+let ergoLibC: Target = useXCFramework
+    ? .binaryTarget(name: "ErgoLibC", path: "ErgoLibC.xcframework")
+    : .systemLibrary(name: "ErgoLibC")
+
 let package = Package(
     name: "ErgoLib",
     products: [
@@ -11,7 +21,7 @@ let package = Package(
         )
     ],
     targets: [
-        .systemLibrary(name: "ErgoLibC"),
+        ergoLibC,
         .target(
             name: "ErgoLib",
             dependencies: ["ErgoLibC"]
@@ -77,6 +77,14 @@ class Address {
         self.pointer = ptr!
     }
     
+    /// Create a P2PK address from serialized PK bytes (EcPoint/GroupElement)
+    init(withP2pkPkBytes bytes: [UInt8]) throws {
+        var ptr: AddressPtr?
+        let error = ergo_lib_address_p2pk_from_pk_bytes(bytes, UInt(bytes.count), &ptr)
+        try checkError(error)
+        self.pointer = ptr!
+    }
+    
     /// Takes ownership of an existing ``AddressPtr``. Note: we must ensure that no other instance
     /// of ``Address`` can hold this pointer.
     internal init(withRawPointer ptr: AddressPtr) {
@@ -92,6 +100,14 @@ class Address {
         return str
     }
     
+    /// Get the ``ErgoTree`` (script) of the address
+    func toErgoTree() throws -> ErgoTree {
+        var ptr: ErgoTreePtr?
+        let error = ergo_lib_address_to_ergo_tree(self.pointer, &ptr)
+        try checkError(error)
+        return ErgoTree(withRawPointer: ptr!)
+    }
+    
     /// Get the type of the address
     func typePrefix() -> AddressTypePrefix {
         let value = ergo_lib_address_type_prefix(self.pointer)
@@ -83,6 +83,28 @@ class Wallet {
         return Transaction(withRawPointer: ptr!)
     }
     
+    /// Sign the given input of the transaction (e.g. to sign the inputs one by one)
+    func signTxInput(
+        inputIdx: UInt,
+        stateContext: ErgoStateContext,
+        unsignedTx: UnsignedTransaction,
+        boxesToSpend: ErgoBoxes,
+        dataBoxes: ErgoBoxes
+    ) throws -> Input {
+        var ptr: InputPtr?
+        let error = ergo_lib_wallet_sign_tx_input(
+            self.pointer,
+            inputIdx,
+            stateContext.pointer,
+            unsignedTx.pointer,
+            boxesToSpend.pointer,
+            dataBoxes.pointer,
+            &ptr
+        )
+        try checkError(error)
+        return Input(withRawPointer: ptr!)
+    }
+    
     /// Sign a multi signature transaction
     func signTransactionMulti(
         stateContext: ErgoStateContext,
@@ -6,7 +6,9 @@ import android.text.TextUtils.writeToParcel
 import androidx.test.filters.SmallTest
 import org.junit.Test
 import org.junit.runner.RunWith
+import org.junit.Assert.assertEquals
 import org.junit.Assert.assertNotEquals
+import org.junit.Assert.assertThrows
 
 
 @SmallTest
@@ -18,4 +20,42 @@ class WalletLibTest {
         assertNotEquals(0, addressPtr)
         WalletLib.addressDelete(addressPtr)
     }
+
+    @Test
+    fun addressRoundtrip() {
+        val addressStr = "9fRAWhdxEsTcdb8PhGNrZfwqa65zfkuYHAMmkQLcic1gdLSV5vA"
+        Address.fromMainnet(addressStr).use { address ->
+            assertEquals(addressStr, address.toBase58(NetworkPrefix.Mainnet))
+            assertEquals(AddressTypePrefix.P2Pk, address.typePrefix())
+        }
+    }
+
+    @Test
+    fun invalidAddressThrows() {
+        assertThrows(ErgoLibException::class.java) { Address.fromTestnet("sss") }
+    }
+
+    @Test
+    fun buildAndSignTx() {
+        val boxJson = """{
+            "boxId": "e56847ed19b3dc6b72828fcfb992fdf7310828cf291221269b7ffc72fd66706e",
+            "value": 67500000000,
+            "ergoTree": "100204a00b08cd021dde34603426402615658f1d970cfa7c7bd92ac81a8b16eeebff264d59ce4604ea02d192a39a8cc7a70173007301",
+            "assets": [],
+            "creationHeight": 284761,
+            "additionalRegisters": {},
+            "transactionId": "9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9",
+            "index": 1
+        }"""
+        val recipient = Address.fromTestnet("3WvsT2Gm4EpsM9Pg18PdY6XyhNNMqXDsvJTbbf6ihLvAmSb7u5RN")
+        val inputs = ErgoBoxes.fromJson(listOf(boxJson))
+        assertEquals(1, inputs.size())
+        val fee = 1100000L
+        val outValue = 1000000000L
+        val output = ErgoBoxCandidate(outValue, recipient, 0)
+        val selection = BoxSelection.select(inputs, outValue + fee)
+        val unsignedTx = TxBuilder(selection, listOf(output), 0, fee, recipient).use { it.build() }
+        assertNotEquals("", unsignedTx.toJson())
+        listOf(unsignedTx, selection, output, inputs, recipient).forEach { it.close() }
+    }
 }
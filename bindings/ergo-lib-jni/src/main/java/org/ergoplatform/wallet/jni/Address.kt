@@ -0,0 +1,39 @@
+package org.ergoplatform.wallet.jni
+
+/** Network type */
+enum class NetworkPrefix(val value: Byte) {
+    Mainnet(0),
+    Testnet(16),
+}
+
+/** Address type */
+enum class AddressTypePrefix(val value: Byte) {
+    P2Pk(1),
+    Pay2Sh(2),
+    Pay2S(3),
+}
+
+/** Address (P2PK, P2SH, P2S) */
+class Address internal constructor(handle: Long) : NativeHandle(handle, WalletLib::addressDelete) {
+
+    /** Encode (base58) address */
+    fun toBase58(networkPrefix: NetworkPrefix): String =
+        WalletLib.addressToBase58(handle, networkPrefix.value)
+
+    /** Type of the address */
+    fun typePrefix(): AddressTypePrefix {
+        val value = WalletLib.addressTypePrefix(handle)
+        return AddressTypePrefix.values().first { it.value == value }
+    }
+
+    companion object {
+        /** Decode (base58) testnet address */
+        fun fromTestnet(addressStr: String) = Address(WalletLib.addressFromTestNet(addressStr))
+
+        /** Decode (base58) mainnet address */
+        fun fromMainnet(addressStr: String) = Address(WalletLib.addressFromMainNet(addressStr))
+
+        /** Decode (base58) address without checking the network prefix */
+        fun fromBase58(addressStr: String) = Address(WalletLib.addressFromBase58(addressStr))
+    }
+}
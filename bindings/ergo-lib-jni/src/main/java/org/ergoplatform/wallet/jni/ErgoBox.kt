@@ -0,0 +1,45 @@
+package org.ergoplatform.wallet.jni
+
+/** Box (UTXO) */
+class ErgoBox internal constructor(handle: Long) : NativeHandle(handle, WalletLib::ergoBoxDelete) {
+
+    /** Box id (base16) */
+    fun id(): String = WalletLib.ergoBoxId(handle)
+
+    /** Value in nanoERGs */
+    fun value(): Long = WalletLib.ergoBoxValue(handle)
+
+    /** JSON (Node API) */
+    fun toJson(): String = WalletLib.ergoBoxToJson(handle)
+
+    companion object {
+        /** Parse from JSON (Node API, Explorer API and EIP-12 are supported) */
+        fun fromJson(json: String) = ErgoBox(WalletLib.ergoBoxFromJson(json))
+    }
+}
+
+/** Collection of boxes (e.g. inputs to spend), holds copies of the boxes */
+class ErgoBoxes internal constructor(handle: Long) : NativeHandle(handle, WalletLib::ergoBoxesDelete) {
+
+    constructor(boxes: List<ErgoBox>) : this(WalletLib.ergoBoxesNew(boxes.map { it.handle }.toLongArray()))
+
+    fun size(): Int = WalletLib.ergoBoxesLen(handle)
+
+    /** Copy of the box at the given index */
+    fun get(index: Int) = ErgoBox(WalletLib.ergoBoxesGet(handle, index))
+
+    companion object {
+        fun fromJson(json: List<String>) = ErgoBoxes(WalletLib.ergoBoxesFromJson(json.toTypedArray()))
+
+        fun empty() = ErgoBoxes(emptyList())
+    }
+}
+
+/** Output box candidate */
+class ErgoBoxCandidate internal constructor(handle: Long) :
+    NativeHandle(handle, WalletLib::ergoBoxCandidateDelete) {
+
+    /** Box with the given value (in nanoERGs) guarded by the address */
+    constructor(value: Long, address: Address, creationHeight: Int) :
+        this(WalletLib.ergoBoxCandidateNew(value, address.handle, creationHeight))
+}
@@ -0,0 +1,4 @@
+package org.ergoplatform.wallet.jni
+
+/** Error returned by ergo-lib (e.g. invalid address, failed signing) */
+class ErgoLibException(message: String) : RuntimeException(message)
@@ -0,0 +1,17 @@
+package org.ergoplatform.wallet.jni
+
+/** Owner of a native object handle, releases the native object on [close] */
+abstract class NativeHandle internal constructor(handle: Long, private val delete: (Long) -> Unit) :
+    AutoCloseable {
+
+    /** Native object handle, 0 after [close] */
+    var handle: Long = handle
+        private set
+
+    override fun close() {
+        if (handle != 0L) {
+            delete(handle)
+            handle = 0
+        }
+    }
+}
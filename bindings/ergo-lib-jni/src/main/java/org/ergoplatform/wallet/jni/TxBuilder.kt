@@ -0,0 +1,53 @@
+package org.ergoplatform.wallet.jni
+
+/** Inputs selected for the transaction and the change */
+class BoxSelection internal constructor(handle: Long) :
+    NativeHandle(handle, WalletLib::boxSelectionDelete) {
+
+    companion object {
+        /** Select the inputs to cover the target balance (in nanoERGs) with the simple box selector */
+        fun select(inputs: ErgoBoxes, targetBalance: Long) =
+            BoxSelection(WalletLib.simpleBoxSelectorSelect(inputs.handle, targetBalance))
+    }
+}
+
+/** Unsigned transaction builder */
+class TxBuilder(
+    boxSelection: BoxSelection,
+    outputCandidates: List<ErgoBoxCandidate>,
+    currentHeight: Int,
+    feeAmount: Long,
+    changeAddress: Address
+) : NativeHandle(
+    WalletLib.txBuilderNew(
+        boxSelection.handle,
+        outputCandidates.map { it.handle }.toLongArray(),
+        currentHeight,
+        feeAmount,
+        changeAddress.handle
+    ),
+    WalletLib::txBuilderDelete
+) {
+
+    /** Build the unsigned transaction */
+    fun build() = UnsignedTransaction(WalletLib.txBuilderBuild(handle))
+}
+
+/** Unsigned transaction */
+class UnsignedTransaction internal constructor(handle: Long) :
+    NativeHandle(handle, WalletLib::unsignedTxDelete) {
+
+    fun toJson(): String = WalletLib.unsignedTxToJson(handle)
+
+    companion object {
+        fun fromJson(json: String) = UnsignedTransaction(WalletLib.unsignedTxFromJson(json))
+    }
+}
+
+/** Signed transaction */
+class Transaction internal constructor(handle: Long) : NativeHandle(handle, WalletLib::txDelete) {
+
+    fun id(): String = WalletLib.txId(handle)
+
+    fun toJson(): String = WalletLib.txToJson(handle)
+}
@@ -0,0 +1,65 @@
+package org.ergoplatform.wallet.jni
+
+/** Blockchain state (last headers) used in the transaction signing */
+class ErgoStateContext internal constructor(handle: Long) :
+    NativeHandle(handle, WalletLib::ergoStateContextDelete) {
+
+    companion object {
+        /** State context from the last 10 block headers (Node API JSON, the latest first) */
+        fun fromHeadersJson(headersJson: List<String>) =
+            ErgoStateContext(WalletLib.ergoStateContextFromHeadersJson(headersJson.toTypedArray()))
+    }
+}
+
+/** Transaction reduced to the sigma propositions (e.g. for signing on the cold wallet) */
+class ReducedTransaction internal constructor(handle: Long) :
+    NativeHandle(handle, WalletLib::reducedTxDelete) {
+
+    fun unsignedTx() = UnsignedTransaction(WalletLib.reducedTxUnsignedTx(handle))
+
+    companion object {
+        fun fromUnsignedTx(
+            unsignedTx: UnsignedTransaction,
+            boxesToSpend: ErgoBoxes,
+            dataBoxes: ErgoBoxes,
+            stateContext: ErgoStateContext
+        ) = ReducedTransaction(
+            WalletLib.reducedTxFromUnsignedTx(
+                unsignedTx.handle,
+                boxesToSpend.handle,
+                dataBoxes.handle,
+                stateContext.handle
+            )
+        )
+    }
+}
+
+/** Wallet holding the secrets */
+class Wallet internal constructor(handle: Long) : NativeHandle(handle, WalletLib::walletDelete) {
+
+    /** Sign the transaction */
+    fun signTransaction(
+        stateContext: ErgoStateContext,
+        unsignedTx: UnsignedTransaction,
+        boxesToSpend: ErgoBoxes,
+        dataBoxes: ErgoBoxes
+    ) = Transaction(
+        WalletLib.walletSignTransaction(
+            handle,
+            stateContext.handle,
+            unsignedTx.handle,
+            boxesToSpend.handle,
+            dataBoxes.handle
+        )
+    )
+
+    /** Sign the reduced transaction */
+    fun signReducedTransaction(reducedTx: ReducedTransaction) =
+        Transaction(WalletLib.walletSignReducedTransaction(handle, reducedTx.handle))
+
+    companion object {
+        /** Wallet from the mnemonic phrase and the (optional) password */
+        fun fromMnemonic(mnemonicPhrase: String, mnemonicPass: String = "") =
+            Wallet(WalletLib.walletFromMnemonic(mnemonicPhrase, mnemonicPass))
+    }
+}
@@ -1,10 +1,79 @@
 package org.ergoplatform.wallet.jni
 
+/**
+ * Native methods operating on the handles (pointers to the native objects).
+ * Every handle returned by the methods should be released with the corresponding `*Delete` method,
+ * prefer the wrapper classes (e.g. [Address], [Wallet]) that do it on [AutoCloseable.close].
+ * Errors are thrown as [ErgoLibException].
+ */
 object WalletLib {
     init {
         System.loadLibrary("ergowalletlibjni")
     }
 
     @JvmStatic external fun addressFromTestNet(addressStr: String): Long
+    @JvmStatic external fun addressFromMainNet(addressStr: String): Long
+    @JvmStatic external fun addressFromBase58(addressStr: String): Long
+    @JvmStatic external fun addressToBase58(address: Long, networkPrefix: Byte): String
+    @JvmStatic external fun addressTypePrefix(address: Long): Byte
     @JvmStatic external fun addressDelete(address: Long)
+
+    @JvmStatic external fun ergoBoxFromJson(json: String): Long
+    @JvmStatic external fun ergoBoxToJson(ergoBox: Long): String
+    @JvmStatic external fun ergoBoxId(ergoBox: Long): String
+    @JvmStatic external fun ergoBoxValue(ergoBox: Long): Long
+    @JvmStatic external fun ergoBoxDelete(ergoBox: Long)
+
+    @JvmStatic external fun ergoBoxesFromJson(json: Array<String>): Long
+    @JvmStatic external fun ergoBoxesNew(ergoBoxes: LongArray): Long
+    @JvmStatic external fun ergoBoxesLen(ergoBoxes: Long): Int
+    @JvmStatic external fun ergoBoxesGet(ergoBoxes: Long, index: Int): Long
+    @JvmStatic external fun ergoBoxesDelete(ergoBoxes: Long)
+
+    @JvmStatic external fun ergoBoxCandidateNew(value: Long, address: Long, creationHeight: Int): Long
+    @JvmStatic external fun ergoBoxCandidateDelete(candidate: Long)
+
+    @JvmStatic external fun simpleBoxSelectorSelect(inputs: Long, targetBalance: Long): Long
+    @JvmStatic external fun boxSelectionDelete(boxSelection: Long)
+
+    @JvmStatic external fun txBuilderNew(
+        boxSelection: Long,
+        outputCandidates: LongArray,
+        currentHeight: Int,
+        feeAmount: Long,
+        changeAddress: Long
+    ): Long
+    @JvmStatic external fun txBuilderBuild(txBuilder: Long): Long
+    @JvmStatic external fun txBuilderDelete(txBuilder: Long)
+
+    @JvmStatic external fun unsignedTxFromJson(json: String): Long
+    @JvmStatic external fun unsignedTxToJson(unsignedTx: Long): String
+    @JvmStatic external fun unsignedTxDelete(unsignedTx: Long)
+
+    @JvmStatic external fun ergoStateContextFromHeadersJson(headersJson: Array<String>): Long
+    @JvmStatic external fun ergoStateContextDelete(stateContext: Long)
+
+    @JvmStatic external fun reducedTxFromUnsignedTx(
+        unsignedTx: Long,
+        boxesToSpend: Long,
+        dataBoxes: Long,
+        stateContext: Long
+    ): Long
+    @JvmStatic external fun reducedTxUnsignedTx(reducedTx: Long): Long
+    @JvmStatic external fun reducedTxDelete(reducedTx: Long)
+
+    @JvmStatic external fun walletFromMnemonic(mnemonicPhrase: String, mnemonicPass: String): Long
+    @JvmStatic external fun walletSignTransaction(
+        wallet: Long,
+        stateContext: Long,
+        unsignedTx: Long,
+        boxesToSpend: Long,
+        dataBoxes: Long
+    ): Long
+    @JvmStatic external fun walletSignReducedTransaction(wallet: Long, reducedTx: Long): Long
+    @JvmStatic external fun walletDelete(wallet: Long)
+
+    @JvmStatic external fun txId(tx: Long): String
+    @JvmStatic external fun txToJson(tx: Long): String
+    @JvmStatic external fun txDelete(tx: Long)
 }
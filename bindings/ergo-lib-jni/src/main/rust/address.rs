@@ -0,0 +1,94 @@
+use ergo_lib_c_core::address::{
+    address_delete, address_from_base58, address_from_mainnet, address_from_testnet,
+    address_to_base58, address_type_prefix, AddressPtr, NetworkPrefix,
+};
+use ergo_lib_c_core::Error;
+use jni::{
+    objects::{JClass, JString},
+    sys::{jbyte, jlong, jstring},
+    JNIEnv,
+};
+use std::ptr::null_mut;
+
+use crate::exception::catch_exc_or;
+use crate::util::{get_string, new_string};
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_addressFromTestNet(
+    env: JNIEnv,
+    _: JClass,
+    address_str: JString,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut address: AddressPtr = null_mut();
+        address_from_testnet(&get_string(&env, address_str)?, &mut address)?;
+        Ok(address as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_addressFromMainNet(
+    env: JNIEnv,
+    _: JClass,
+    address_str: JString,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut address: AddressPtr = null_mut();
+        address_from_mainnet(&get_string(&env, address_str)?, &mut address)?;
+        Ok(address as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_addressFromBase58(
+    env: JNIEnv,
+    _: JClass,
+    address_str: JString,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut address: AddressPtr = null_mut();
+        address_from_base58(&get_string(&env, address_str)?, &mut address)?;
+        Ok(address as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_addressToBase58(
+    env: JNIEnv,
+    _: JClass,
+    address: jlong,
+    network_prefix: jbyte,
+) -> jstring {
+    catch_exc_or(&env, null_mut(), || {
+        let network_prefix = match network_prefix as u8 {
+            0 => NetworkPrefix::Mainnet,
+            16 => NetworkPrefix::Testnet,
+            _ => return Err(Error::InvalidArgument("network_prefix").into()),
+        };
+        let s = address_to_base58(address as AddressPtr, network_prefix)?;
+        new_string(&env, &s)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_addressTypePrefix(
+    env: JNIEnv,
+    _: JClass,
+    address: jlong,
+) -> jbyte {
+    catch_exc_or(&env, 0, || {
+        Ok(address_type_prefix(address as AddressPtr)? as u8 as jbyte)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_addressDelete(
+    _: JNIEnv,
+    _: JClass,
+    address: jlong,
+) {
+    let address_ptr: AddressPtr = address as AddressPtr;
+    if !address_ptr.is_null() {
+        address_delete(address_ptr);
+    }
+}
@@ -0,0 +1,200 @@
+use ergo_lib_c_core::address::AddressPtr;
+use ergo_lib_c_core::box_builder::{
+    ergo_box_candidate_builder_build, ergo_box_candidate_builder_new, ErgoBoxCandidateBuilderPtr,
+};
+use ergo_lib_c_core::collections::{collection_get, collection_len, Collection, CollectionPtr};
+use ergo_lib_c_core::contract::{contract_pay_to_address, ContractPtr};
+use ergo_lib_c_core::ergo_box::{
+    box_id_to_str, box_value_as_i64, box_value_from_i64, ergo_box_box_id, ergo_box_from_json,
+    ergo_box_to_json, ergo_box_value, BoxIdPtr, BoxValuePtr, ErgoBox, ErgoBoxCandidate,
+    ErgoBoxCandidatePtr, ErgoBoxPtr,
+};
+use ergo_lib_c_core::Error;
+use jni::{
+    objects::{JClass, JString},
+    sys::{jint, jlong, jlongArray, jobjectArray, jstring},
+    JNIEnv,
+};
+use std::ptr::null_mut;
+
+use crate::exception::{catch_exc_or, JniError};
+use crate::util::{
+    collection_from_handles, delete_ptr, get_long_array, get_string, get_string_array, new_string,
+};
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxFromJson(
+    env: JNIEnv,
+    _: JClass,
+    json: JString,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut ergo_box: ErgoBoxPtr = null_mut();
+        ergo_box_from_json(&get_string(&env, json)?, &mut ergo_box)?;
+        Ok(ergo_box as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxToJson(
+    env: JNIEnv,
+    _: JClass,
+    ergo_box: jlong,
+) -> jstring {
+    catch_exc_or(&env, null_mut(), || {
+        new_string(&env, &ergo_box_to_json(ergo_box as ErgoBoxPtr)?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxId(
+    env: JNIEnv,
+    _: JClass,
+    ergo_box: jlong,
+) -> jstring {
+    catch_exc_or(&env, null_mut(), || {
+        let mut box_id: BoxIdPtr = null_mut();
+        ergo_box_box_id(ergo_box as ErgoBoxPtr, &mut box_id)?;
+        let res = box_id_to_str(box_id);
+        delete_ptr(box_id);
+        new_string(&env, &res?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxValue(
+    env: JNIEnv,
+    _: JClass,
+    ergo_box: jlong,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut value: BoxValuePtr = null_mut();
+        ergo_box_value(ergo_box as ErgoBoxPtr, &mut value)?;
+        let res = box_value_as_i64(value);
+        delete_ptr(value);
+        Ok(res?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxDelete(
+    _: JNIEnv,
+    _: JClass,
+    ergo_box: jlong,
+) {
+    delete_ptr(ergo_box as ErgoBoxPtr)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxesFromJson(
+    env: JNIEnv,
+    _: JClass,
+    json_array: jobjectArray,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut boxes = Vec::new();
+        for json in get_string_array(&env, json_array)? {
+            let mut ergo_box: ErgoBoxPtr = null_mut();
+            ergo_box_from_json(&json, &mut ergo_box)?;
+            boxes.push(*Box::from_raw(ergo_box));
+        }
+        let collection: CollectionPtr<ErgoBox> = Box::into_raw(Box::new(Collection(boxes)));
+        Ok(collection as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxesNew(
+    env: JNIEnv,
+    _: JClass,
+    ergo_boxes: jlongArray,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let handles = get_long_array(&env, ergo_boxes)?;
+        Ok(collection_from_handles::<ErgoBox>(&handles)? as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxesLen(
+    env: JNIEnv,
+    _: JClass,
+    ergo_boxes: jlong,
+) -> jint {
+    catch_exc_or(&env, 0, || {
+        Ok(collection_len(ergo_boxes as CollectionPtr<ErgoBox>)? as jint)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxesGet(
+    env: JNIEnv,
+    _: JClass,
+    ergo_boxes: jlong,
+    index: jint,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut ergo_box: ErgoBoxPtr = null_mut();
+        if !collection_get(
+            ergo_boxes as CollectionPtr<ErgoBox>,
+            index as usize,
+            &mut ergo_box,
+        )? {
+            return Err(JniError::Lib(Error::InvalidArgument("index")));
+        }
+        Ok(ergo_box as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxesDelete(
+    _: JNIEnv,
+    _: JClass,
+    ergo_boxes: jlong,
+) {
+    delete_ptr(ergo_boxes as CollectionPtr<ErgoBox>)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxCandidateNew(
+    env: JNIEnv,
+    _: JClass,
+    value: jlong,
+    address: jlong,
+    creation_height: jint,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut box_value: BoxValuePtr = null_mut();
+        box_value_from_i64(value, &mut box_value)?;
+        let mut contract: ContractPtr = null_mut();
+        let res = contract_pay_to_address(address as AddressPtr, &mut contract);
+        if let Err(e) = res {
+            delete_ptr(box_value);
+            return Err(e.into());
+        }
+        let mut builder: ErgoBoxCandidateBuilderPtr = null_mut();
+        let res = ergo_box_candidate_builder_new(
+            box_value,
+            contract,
+            creation_height as u32,
+            &mut builder,
+        );
+        delete_ptr(box_value);
+        delete_ptr(contract);
+        res?;
+        let mut candidate: ErgoBoxCandidatePtr = null_mut();
+        let res = ergo_box_candidate_builder_build(builder, &mut candidate);
+        delete_ptr(builder);
+        res?;
+        Ok(candidate as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoBoxCandidateDelete(
+    _: JNIEnv,
+    _: JClass,
+    candidate: jlong,
+) {
+    delete_ptr(candidate as *mut ErgoBoxCandidate)
+}
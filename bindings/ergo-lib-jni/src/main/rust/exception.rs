@@ -12,11 +12,48 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use ergo_lib_c_core::Error;
 use jni::JNIEnv;
 use std::any::Any;
+use std::panic;
 use std::thread;
 
-type ExceptionResult<T> = thread::Result<Result<T, jni::errors::Error>>;
+// Java class of the exceptions thrown for the ergo-lib errors
+const ERGO_LIB_EXCEPTION_CLASS: &str = "org/ergoplatform/wallet/jni/ErgoLibException";
+
+// Error of a native method call.
+pub enum JniError {
+    // ergo-lib error, thrown as `ErgoLibException`
+    Lib(Error),
+    // JNI call error
+    Jni(jni::errors::Error),
+}
+
+impl From<Error> for JniError {
+    fn from(e: Error) -> Self {
+        JniError::Lib(e)
+    }
+}
+
+impl From<jni::errors::Error> for JniError {
+    fn from(e: jni::errors::Error) -> Self {
+        JniError::Jni(e)
+    }
+}
+
+type ExceptionResult<T> = thread::Result<Result<T, JniError>>;
+
+// Runs `f` catching the panics, returns its value or "throws" exception (see `unwrap_exc_or`).
+pub fn catch_exc_or<T, F>(env: &JNIEnv, error_val: T, f: F) -> T
+where
+    F: FnOnce() -> Result<T, JniError>,
+{
+    unwrap_exc_or(
+        env,
+        panic::catch_unwind(panic::AssertUnwindSafe(f)),
+        error_val,
+    )
+}
 
 // Returns value or "throws" exception. `error_val` is returned, because exception will be thrown
 // at the Java side. So this function should be used only for the `panic::catch_unwind` result.
@@ -25,7 +62,11 @@ pub fn unwrap_exc_or<T>(env: &JNIEnv, res: ExceptionResult<T>, error_val: T) ->
         Ok(val) => {
             match val {
                 Ok(val) => val,
-                Err(jni_error) => {
+                Err(JniError::Lib(error)) => {
+                    throw_class(env, ERGO_LIB_EXCEPTION_CLASS, &error.to_string());
+                    error_val
+                }
+                Err(JniError::Jni(jni_error)) => {
                     // Do nothing if there is a pending Java-exception that will be thrown
                     // automatically by the JVM when the native method returns.
                     if !env.exception_check().unwrap() {
@@ -52,16 +93,20 @@ pub fn unwrap_exc_or_default<T: Default>(env: &JNIEnv, res: ExceptionResult<T>)
 // Calls a corresponding `JNIEnv` method, so exception will be thrown when execution returns to
 // the Java side.
 fn throw(env: &JNIEnv, description: &str) {
+    throw_class(env, "java/lang/RuntimeException", description)
+}
+
+fn throw_class(env: &JNIEnv, class: &str, description: &str) {
     // We cannot throw exception from this function, so errors should be written in log instead.
-    let exception = match env.find_class("java/lang/RuntimeException") {
+    let exception = match env.find_class(class) {
         Ok(val) => val,
         Err(e) => {
-            error!("Unable to find 'RuntimeException' class: {:?}", e);
+            error!("Unable to find '{}' class: {:?}", class, e);
             return;
         }
     };
     if let Err(e) = env.throw_new(exception, description) {
-        error!("Unable to find 'RuntimeException' class: {:?}", e);
+        error!("Unable to throw '{}': {:?}", class, e);
     }
 }
 
@@ -13,49 +13,10 @@
 #[macro_use]
 extern crate log;
 
+mod address;
+mod ergo_box;
 mod exception;
-
-use ergo_lib_c_core::address::{address_delete, address_from_testnet, AddressPtr};
-use exception::unwrap_exc_or;
-use jni::{
-    objects::{JClass, JString},
-    sys::jlong,
-    JNIEnv,
-};
-use std::{panic, ptr::null_mut};
-
-#[no_mangle]
-pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_addressFromTestNet(
-    env: JNIEnv,
-    _: JClass,
-    address_str: JString,
-) -> jlong {
-    let res = panic::catch_unwind(|| {
-        let address_str_j = env
-            .get_string(address_str)
-            .expect("Couldn't get address String");
-
-        let mut address: AddressPtr = null_mut();
-        let result = address_from_testnet(&address_str_j.to_string_lossy(), &mut address);
-
-        if let Some(error) = result.err() {
-            let _ = env.throw(error.to_string());
-            Ok(0)
-        } else {
-            Ok(address as jlong)
-        }
-    });
-    unwrap_exc_or(&env, res, 0)
-}
-
-#[no_mangle]
-pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_addressDelete(
-    _: JNIEnv,
-    _: JClass,
-    address: jlong,
-) {
-    let address_ptr: AddressPtr = address as AddressPtr;
-    if !address_ptr.is_null() {
-        address_delete(address_ptr);
-    }
-}
+mod reduced;
+mod tx_builder;
+mod util;
+mod wallet;
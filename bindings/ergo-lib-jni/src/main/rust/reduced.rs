@@ -0,0 +1,56 @@
+use ergo_lib_c_core::collections::CollectionPtr;
+use ergo_lib_c_core::ergo_box::ErgoBox;
+use ergo_lib_c_core::ergo_state_ctx::ErgoStateContextPtr;
+use ergo_lib_c_core::reduced::{
+    reduced_tx_from_unsigned_tx, reduced_tx_unsigned_tx, ReducedTransactionPtr,
+};
+use ergo_lib_c_core::transaction::UnsignedTransactionPtr;
+use jni::{objects::JClass, sys::jlong, JNIEnv};
+use std::ptr::null_mut;
+
+use crate::exception::catch_exc_or;
+use crate::util::delete_ptr;
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_reducedTxFromUnsignedTx(
+    env: JNIEnv,
+    _: JClass,
+    unsigned_tx: jlong,
+    boxes_to_spend: jlong,
+    data_boxes: jlong,
+    state_context: jlong,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut reduced_tx: ReducedTransactionPtr = null_mut();
+        reduced_tx_from_unsigned_tx(
+            unsigned_tx as UnsignedTransactionPtr,
+            boxes_to_spend as CollectionPtr<ErgoBox>,
+            data_boxes as CollectionPtr<ErgoBox>,
+            state_context as ErgoStateContextPtr,
+            &mut reduced_tx,
+        )?;
+        Ok(reduced_tx as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_reducedTxUnsignedTx(
+    env: JNIEnv,
+    _: JClass,
+    reduced_tx: jlong,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut unsigned_tx: UnsignedTransactionPtr = null_mut();
+        reduced_tx_unsigned_tx(reduced_tx as ReducedTransactionPtr, &mut unsigned_tx)?;
+        Ok(unsigned_tx as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_reducedTxDelete(
+    _: JNIEnv,
+    _: JClass,
+    reduced_tx: jlong,
+) {
+    delete_ptr(reduced_tx as ReducedTransactionPtr)
+}
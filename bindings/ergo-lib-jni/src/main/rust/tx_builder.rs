@@ -0,0 +1,148 @@
+use ergo_lib_c_core::address::AddressPtr;
+use ergo_lib_c_core::box_selector::{
+    simple_box_selector_new, simple_box_selector_select, BoxSelectionPtr, SimpleBoxSelectorPtr,
+};
+use ergo_lib_c_core::collections::{Collection, CollectionPtr};
+use ergo_lib_c_core::ergo_box::{box_value_from_i64, BoxValuePtr, ErgoBox, ErgoBoxCandidate};
+use ergo_lib_c_core::token::Token;
+use ergo_lib_c_core::transaction::{
+    unsigned_tx_from_json, unsigned_tx_to_json, UnsignedTransactionPtr,
+};
+use ergo_lib_c_core::tx_builder::{tx_builder_build, tx_builder_new, TxBuilderPtr};
+use jni::{
+    objects::{JClass, JString},
+    sys::{jint, jlong, jlongArray, jstring},
+    JNIEnv,
+};
+use std::ptr::null_mut;
+
+use crate::exception::catch_exc_or;
+use crate::util::{collection_from_handles, delete_ptr, get_long_array, get_string, new_string};
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_simpleBoxSelectorSelect(
+    env: JNIEnv,
+    _: JClass,
+    inputs: jlong,
+    target_balance: jlong,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut target: BoxValuePtr = null_mut();
+        box_value_from_i64(target_balance, &mut target)?;
+        let mut selector: SimpleBoxSelectorPtr = null_mut();
+        let target_tokens: Collection<Token> = Collection(Vec::new());
+        let mut selection: BoxSelectionPtr = null_mut();
+        let res = simple_box_selector_new(&mut selector).and_then(|()| {
+            simple_box_selector_select(
+                selector,
+                inputs as CollectionPtr<ErgoBox>,
+                target,
+                &target_tokens,
+                &mut selection,
+            )
+        });
+        delete_ptr(target);
+        delete_ptr(selector);
+        res?;
+        Ok(selection as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_boxSelectionDelete(
+    _: JNIEnv,
+    _: JClass,
+    box_selection: jlong,
+) {
+    delete_ptr(box_selection as BoxSelectionPtr)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_txBuilderNew(
+    env: JNIEnv,
+    _: JClass,
+    box_selection: jlong,
+    output_candidates: jlongArray,
+    current_height: jint,
+    fee_amount: jlong,
+    change_address: jlong,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let handles = get_long_array(&env, output_candidates)?;
+        let candidates = collection_from_handles::<ErgoBoxCandidate>(&handles)?;
+        let mut fee: BoxValuePtr = null_mut();
+        let mut tx_builder: TxBuilderPtr = null_mut();
+        let res = box_value_from_i64(fee_amount, &mut fee).and_then(|()| {
+            tx_builder_new(
+                box_selection as BoxSelectionPtr,
+                candidates,
+                current_height as u32,
+                fee,
+                change_address as AddressPtr,
+                &mut tx_builder,
+            )
+        });
+        delete_ptr(candidates);
+        delete_ptr(fee);
+        res?;
+        Ok(tx_builder as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_txBuilderBuild(
+    env: JNIEnv,
+    _: JClass,
+    tx_builder: jlong,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut unsigned_tx: UnsignedTransactionPtr = null_mut();
+        tx_builder_build(tx_builder as TxBuilderPtr, &mut unsigned_tx)?;
+        Ok(unsigned_tx as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_txBuilderDelete(
+    _: JNIEnv,
+    _: JClass,
+    tx_builder: jlong,
+) {
+    delete_ptr(tx_builder as TxBuilderPtr)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_unsignedTxFromJson(
+    env: JNIEnv,
+    _: JClass,
+    json: JString,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut unsigned_tx: UnsignedTransactionPtr = null_mut();
+        unsigned_tx_from_json(&get_string(&env, json)?, &mut unsigned_tx)?;
+        Ok(unsigned_tx as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_unsignedTxToJson(
+    env: JNIEnv,
+    _: JClass,
+    unsigned_tx: jlong,
+) -> jstring {
+    catch_exc_or(&env, null_mut(), || {
+        new_string(
+            &env,
+            &unsigned_tx_to_json(unsigned_tx as UnsignedTransactionPtr)?,
+        )
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_unsignedTxDelete(
+    _: JNIEnv,
+    _: JClass,
+    unsigned_tx: jlong,
+) {
+    delete_ptr(unsigned_tx as UnsignedTransactionPtr)
+}
@@ -0,0 +1,65 @@
+//! Conversions between the JVM and native values
+
+use ergo_lib_c_core::collections::{collection_add, collection_new, CollectionPtr};
+use ergo_lib_c_core::Error;
+use jni::objects::{JObject, JString};
+use jni::sys::{jlong, jlongArray, jobjectArray, jstring};
+use jni::JNIEnv;
+use std::ptr::null_mut;
+
+use crate::exception::JniError;
+
+/// Drop the value behind the handle (raw pointer) returned to the JVM
+pub unsafe fn delete_ptr<T>(ptr: *mut T) {
+    if !ptr.is_null() {
+        let boxed = Box::from_raw(ptr);
+        std::mem::drop(boxed);
+    }
+}
+
+/// Read JVM string
+pub fn get_string(env: &JNIEnv, s: JString) -> Result<String, JniError> {
+    Ok(env.get_string(s)?.into())
+}
+
+/// Create JVM string
+pub fn new_string(env: &JNIEnv, s: &str) -> Result<jstring, JniError> {
+    Ok(env.new_string(s)?.into_inner())
+}
+
+/// Read the elements of JVM `String[]`. Each element is a new local reference, so it's deleted
+/// right after reading to not exhaust the local reference table on the large arrays.
+pub fn get_string_array(env: &JNIEnv, array: jobjectArray) -> Result<Vec<String>, JniError> {
+    let len = env.get_array_length(array)?;
+    let mut strings = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        let elem: JObject = env.get_object_array_element(array, i)?;
+        let s = get_string(env, JString::from(elem));
+        env.delete_local_ref(elem)?;
+        strings.push(s?);
+    }
+    Ok(strings)
+}
+
+/// Read the elements of JVM `long[]` (handles)
+pub fn get_long_array(env: &JNIEnv, array: jlongArray) -> Result<Vec<jlong>, JniError> {
+    let len = env.get_array_length(array)?;
+    let mut handles = vec![0; len as usize];
+    env.get_long_array_region(array, 0, &mut handles)?;
+    Ok(handles)
+}
+
+/// New collection with the clones of the values behind the handles
+pub unsafe fn collection_from_handles<T: Clone>(
+    handles: &[jlong],
+) -> Result<CollectionPtr<T>, Error> {
+    let mut collection: CollectionPtr<T> = null_mut();
+    collection_new(&mut collection)?;
+    for handle in handles {
+        if let Err(e) = collection_add(collection, *handle as *const T) {
+            delete_ptr(collection);
+            return Err(e);
+        }
+    }
+    Ok(collection)
+}
@@ -0,0 +1,164 @@
+use ergo_lib_c_core::block_header::{block_header_from_json, BlockHeader, BlockHeaderPtr};
+use ergo_lib_c_core::collections::{Collection, CollectionPtr};
+use ergo_lib_c_core::ergo_box::ErgoBox;
+use ergo_lib_c_core::ergo_state_ctx::{ergo_state_context_new, ErgoStateContextPtr};
+use ergo_lib_c_core::header::{preheader_from_block_header, PreHeaderPtr};
+use ergo_lib_c_core::reduced::ReducedTransactionPtr;
+use ergo_lib_c_core::transaction::{
+    tx_id, tx_id_to_str, tx_to_json, TransactionPtr, TxIdPtr, UnsignedTransactionPtr,
+};
+use ergo_lib_c_core::wallet::{
+    wallet_from_mnemonic, wallet_sign_reduced_transaction, wallet_sign_transaction, WalletPtr,
+};
+use ergo_lib_c_core::Error;
+use jni::{
+    objects::{JClass, JString},
+    sys::{jlong, jobjectArray, jstring},
+    JNIEnv,
+};
+use std::ptr::null_mut;
+
+use crate::exception::catch_exc_or;
+use crate::util::{delete_ptr, get_string, get_string_array, new_string};
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_walletFromMnemonic(
+    env: JNIEnv,
+    _: JClass,
+    mnemonic_phrase: JString,
+    mnemonic_pass: JString,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut wallet: WalletPtr = null_mut();
+        wallet_from_mnemonic(
+            &get_string(&env, mnemonic_phrase)?,
+            &get_string(&env, mnemonic_pass)?,
+            &mut wallet,
+        )?;
+        Ok(wallet as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_walletSignTransaction(
+    env: JNIEnv,
+    _: JClass,
+    wallet: jlong,
+    state_context: jlong,
+    unsigned_tx: jlong,
+    boxes_to_spend: jlong,
+    data_boxes: jlong,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut tx: TransactionPtr = null_mut();
+        wallet_sign_transaction(
+            wallet as WalletPtr,
+            state_context as ErgoStateContextPtr,
+            unsigned_tx as UnsignedTransactionPtr,
+            boxes_to_spend as CollectionPtr<ErgoBox>,
+            data_boxes as CollectionPtr<ErgoBox>,
+            &mut tx,
+        )?;
+        Ok(tx as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_walletSignReducedTransaction(
+    env: JNIEnv,
+    _: JClass,
+    wallet: jlong,
+    reduced_tx: jlong,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut tx: TransactionPtr = null_mut();
+        wallet_sign_reduced_transaction(
+            wallet as WalletPtr,
+            reduced_tx as ReducedTransactionPtr,
+            &mut tx,
+        )?;
+        Ok(tx as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_walletDelete(
+    _: JNIEnv,
+    _: JClass,
+    wallet: jlong,
+) {
+    delete_ptr(wallet as WalletPtr)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_txId(
+    env: JNIEnv,
+    _: JClass,
+    tx: jlong,
+) -> jstring {
+    catch_exc_or(&env, null_mut(), || {
+        let mut id: TxIdPtr = null_mut();
+        tx_id(tx as TransactionPtr, &mut id)?;
+        let res = tx_id_to_str(id);
+        delete_ptr(id);
+        new_string(&env, &res?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_txToJson(
+    env: JNIEnv,
+    _: JClass,
+    tx: jlong,
+) -> jstring {
+    catch_exc_or(&env, null_mut(), || {
+        new_string(&env, &tx_to_json(tx as TransactionPtr)?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_txDelete(
+    _: JNIEnv,
+    _: JClass,
+    tx: jlong,
+) {
+    delete_ptr(tx as TransactionPtr)
+}
+
+/// State context from the last block headers (JSON, the latest first), the pre-header is
+/// built from the latest header
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoStateContextFromHeadersJson(
+    env: JNIEnv,
+    _: JClass,
+    headers_json: jobjectArray,
+) -> jlong {
+    catch_exc_or(&env, 0, || {
+        let mut headers = Vec::new();
+        for json in get_string_array(&env, headers_json)? {
+            let mut header: BlockHeaderPtr = null_mut();
+            block_header_from_json(&json, &mut header)?;
+            headers.push(*Box::from_raw(header));
+        }
+        let latest: *const BlockHeader = headers
+            .first()
+            .ok_or(Error::InvalidArgument("headers_json"))?;
+        let mut pre_header: PreHeaderPtr = null_mut();
+        preheader_from_block_header(latest, &mut pre_header)?;
+        let headers = Collection(headers);
+        let mut state_context: ErgoStateContextPtr = null_mut();
+        let res = ergo_state_context_new(pre_header, &headers, &mut state_context);
+        delete_ptr(pre_header);
+        res?;
+        Ok(state_context as jlong)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_org_ergoplatform_wallet_jni_WalletLib_ergoStateContextDelete(
+    _: JNIEnv,
+    _: JClass,
+    state_context: jlong,
+) {
+    delete_ptr(state_context as ErgoStateContextPtr)
+}
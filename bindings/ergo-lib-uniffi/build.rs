@@ -0,0 +1,4 @@
+fn main() {
+    #[allow(clippy::unwrap_used)]
+    uniffi::generate_scaffolding("src/ergo_lib.udl").unwrap();
+}
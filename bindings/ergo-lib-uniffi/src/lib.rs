@@ -0,0 +1,610 @@
+//! Kotlin, Swift and Python bindings for ergo-lib generated with UniFFI from the interface
+//! definition in `ergo_lib.udl`.
+//! Objects are immutable and shared (`Arc`) with the foreign side, so the wrappers clone the
+//! inner values where ergo-lib takes them by value.
+
+// Coding conventions
+// (the generated scaffolding is included in this module, so the lints are the ones it passes)
+#![deny(non_upper_case_globals)]
+#![deny(non_camel_case_types)]
+#![deny(non_snake_case)]
+#![deny(unused_mut)]
+#![deny(unused_imports)]
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use ergo_lib::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+use ergo_lib::chain::ergo_state_context;
+use ergo_lib::chain::transaction;
+use ergo_lib::ergo_chain_types::Header;
+use ergo_lib::ergotree_ir::chain::address;
+use ergo_lib::ergotree_ir::chain::address::AddressEncoder;
+use ergo_lib::ergotree_ir::chain::ergo_box;
+use ergo_lib::ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergo_lib::ergotree_ir::serialization::SigmaSerializable;
+use ergo_lib::wallet;
+use ergo_lib::wallet::box_selector;
+use ergo_lib::wallet::box_selector::BoxSelector;
+use ergo_lib::wallet::box_selector::SimpleBoxSelector;
+use ergo_lib::wallet::ext_secret_key::ExtSecretKey;
+use ergo_lib::wallet::mnemonic::Mnemonic;
+use ergo_lib::wallet::multi_sig;
+use ergo_lib::wallet::secret_key::SecretKey;
+use ergo_lib::wallet::signing::TransactionContext;
+use ergo_lib::wallet::tx_builder;
+
+uniffi::include_scaffolding!("ergo_lib");
+
+/// Errors surfaced to the foreign side (as exceptions/errors with the message)
+#[derive(thiserror::Error, Debug)]
+pub enum ErgoLibError {
+    /// Address decoding error
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    /// JSON (de)serialization error
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(String),
+    /// Invalid argument
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    /// Box selection error
+    #[error("Box selection error: {0}")]
+    BoxSelection(String),
+    /// Transaction building error
+    #[error("TxBuilder error: {0}")]
+    TxBuilder(String),
+    /// Transaction signing error
+    #[error("Signing error: {0}")]
+    Signing(String),
+    /// Wallet error
+    #[error("Wallet error: {0}")]
+    Wallet(String),
+}
+
+fn json_err(e: serde_json::Error) -> ErgoLibError {
+    ErgoLibError::InvalidJson(e.to_string())
+}
+
+fn box_value(value: u64) -> Result<BoxValue, ErgoLibError> {
+    BoxValue::try_from(value).map_err(|e| ErgoLibError::InvalidArgument(e.to_string()))
+}
+
+/// Network type
+#[derive(Clone, Copy, Debug)]
+pub enum NetworkPrefix {
+    /// Mainnet
+    Mainnet,
+    /// Testnet
+    Testnet,
+}
+
+impl From<NetworkPrefix> for address::NetworkPrefix {
+    fn from(v: NetworkPrefix) -> Self {
+        match v {
+            NetworkPrefix::Mainnet => address::NetworkPrefix::Mainnet,
+            NetworkPrefix::Testnet => address::NetworkPrefix::Testnet,
+        }
+    }
+}
+
+/// Address
+pub struct Address(address::Address);
+
+impl Address {
+    /// Decode (base58) address string without checking the network prefix
+    pub fn from_base58(address: String) -> Result<Self, ErgoLibError> {
+        AddressEncoder::unchecked_parse_address_from_str(&address)
+            .map(Address)
+            .map_err(|e| ErgoLibError::InvalidAddress(e.to_string()))
+    }
+
+    /// Decode (base58) address string, checking the network prefix
+    pub fn from_base58_checked(
+        address: String,
+        network_prefix: NetworkPrefix,
+    ) -> Result<Self, ErgoLibError> {
+        AddressEncoder::new(network_prefix.into())
+            .parse_address_from_str(&address)
+            .map(Address)
+            .map_err(|e| ErgoLibError::InvalidAddress(e.to_string()))
+    }
+
+    /// Encode (base58) address
+    pub fn to_base58(&self, network_prefix: NetworkPrefix) -> String {
+        AddressEncoder::encode_address_as_string(network_prefix.into(), &self.0)
+    }
+
+    /// Serialized ErgoTree guarding the address
+    pub fn ergo_tree_bytes(&self) -> Result<Vec<u8>, ErgoLibError> {
+        self.0
+            .script()
+            .map_err(|e| ErgoLibError::InvalidAddress(e.to_string()))?
+            .sigma_serialize_bytes()
+            .map_err(|e| ErgoLibError::InvalidAddress(e.to_string()))
+    }
+}
+
+/// Box (UTXO)
+pub struct ErgoBox(ergo_box::ErgoBox);
+
+impl ErgoBox {
+    /// Parse box from JSON (node/explorer API format)
+    pub fn from_json(json: String) -> Result<Self, ErgoLibError> {
+        serde_json::from_str(&json).map(ErgoBox).map_err(json_err)
+    }
+
+    /// JSON representation
+    pub fn to_json(&self) -> Result<String, ErgoLibError> {
+        serde_json::to_string(&self.0).map_err(json_err)
+    }
+
+    /// Box id (base16)
+    pub fn box_id(&self) -> String {
+        self.0.box_id().to_string()
+    }
+
+    /// Value in nanoERGs
+    pub fn value(&self) -> u64 {
+        *self.0.value.as_u64()
+    }
+
+    /// Height at which the box was created
+    pub fn creation_height(&self) -> u32 {
+        self.0.creation_height
+    }
+}
+
+fn unwrap_boxes(boxes: Vec<Arc<ErgoBox>>) -> Vec<ergo_box::ErgoBox> {
+    boxes.into_iter().map(|b| b.0.clone()).collect()
+}
+
+/// Box candidate (transaction output before the transaction is signed)
+pub struct ErgoBoxCandidate(ergo_box::ErgoBoxCandidate);
+
+impl ErgoBoxCandidate {
+    /// Box guarded by the given address with value in nanoERGs
+    pub fn new(
+        value: u64,
+        address: Arc<Address>,
+        creation_height: u32,
+    ) -> Result<Self, ErgoLibError> {
+        let tree = address
+            .0
+            .script()
+            .map_err(|e| ErgoLibError::InvalidAddress(e.to_string()))?;
+        ErgoBoxCandidateBuilder::new(box_value(value)?, tree, creation_height)
+            .build()
+            .map(ErgoBoxCandidate)
+            .map_err(|e| ErgoLibError::TxBuilder(e.to_string()))
+    }
+
+    /// Value in nanoERGs
+    pub fn value(&self) -> u64 {
+        *self.0.value.as_u64()
+    }
+
+    /// Height at which the box is created
+    pub fn creation_height(&self) -> u32 {
+        self.0.creation_height
+    }
+}
+
+/// Selected boxes with the change
+pub struct BoxSelection(box_selector::BoxSelection<ergo_box::ErgoBox>);
+
+impl BoxSelection {
+    /// Selected boxes to spend
+    pub fn boxes(&self) -> Vec<Arc<ErgoBox>> {
+        self.0
+            .boxes
+            .iter()
+            .map(|b| Arc::new(ErgoBox(b.clone())))
+            .collect()
+    }
+
+    /// Total change value in nanoERGs
+    pub fn change_value(&self) -> u64 {
+        self.0.change_boxes.iter().map(|c| c.value.as_u64()).sum()
+    }
+}
+
+/// Select boxes to cover the target balance (nanoERGs) with `SimpleBoxSelector`
+pub fn select_boxes_simple(
+    inputs: Vec<Arc<ErgoBox>>,
+    target_balance: u64,
+) -> Result<Arc<BoxSelection>, ErgoLibError> {
+    SimpleBoxSelector::new()
+        .select(unwrap_boxes(inputs), box_value(target_balance)?, &[])
+        .map(|s| Arc::new(BoxSelection(s)))
+        .map_err(|e| ErgoLibError::BoxSelection(e.to_string()))
+}
+
+/// Unsigned transaction
+pub struct UnsignedTransaction(transaction::unsigned::UnsignedTransaction);
+
+impl UnsignedTransaction {
+    /// Parse from JSON
+    pub fn from_json(json: String) -> Result<Self, ErgoLibError> {
+        serde_json::from_str(&json)
+            .map(UnsignedTransaction)
+            .map_err(json_err)
+    }
+
+    /// JSON representation
+    pub fn to_json(&self) -> Result<String, ErgoLibError> {
+        serde_json::to_string(&self.0).map_err(json_err)
+    }
+
+    /// Transaction id (base16)
+    pub fn id(&self) -> String {
+        self.0.id().to_string()
+    }
+}
+
+/// Signed transaction
+pub struct Transaction(transaction::Transaction);
+
+impl Transaction {
+    /// Parse from JSON
+    pub fn from_json(json: String) -> Result<Self, ErgoLibError> {
+        serde_json::from_str(&json)
+            .map(Transaction)
+            .map_err(json_err)
+    }
+
+    /// JSON representation
+    pub fn to_json(&self) -> Result<String, ErgoLibError> {
+        serde_json::to_string(&self.0).map_err(json_err)
+    }
+
+    /// Transaction id (base16)
+    pub fn id(&self) -> String {
+        self.0.id().to_string()
+    }
+}
+
+/// Unsigned transaction builder
+pub struct TxBuilder(tx_builder::TxBuilder<ergo_box::ErgoBox>);
+
+impl TxBuilder {
+    /// New builder, spending the selected boxes with the change sent to `change_address`
+    pub fn new(
+        box_selection: Arc<BoxSelection>,
+        output_candidates: Vec<Arc<ErgoBoxCandidate>>,
+        current_height: u32,
+        fee_amount: u64,
+        change_address: Arc<Address>,
+    ) -> Result<Self, ErgoLibError> {
+        Ok(TxBuilder(tx_builder::TxBuilder::new(
+            box_selection.0.clone(),
+            output_candidates.into_iter().map(|c| c.0.clone()).collect(),
+            current_height,
+            box_value(fee_amount)?,
+            change_address.0.clone(),
+        )))
+    }
+
+    /// Build unsigned transaction
+    pub fn build(&self) -> Result<Arc<UnsignedTransaction>, ErgoLibError> {
+        self.0
+            .clone()
+            .build()
+            .map(|tx| Arc::new(UnsignedTransaction(tx)))
+            .map_err(|e| ErgoLibError::TxBuilder(e.to_string()))
+    }
+}
+
+/// Blockchain state (last headers, etc.)
+pub struct ErgoStateContext(ergo_state_context::ErgoStateContext);
+
+impl ErgoStateContext {
+    /// Context for the next block from the last 10 block headers (JSON) in any order
+    pub fn from_headers_json(headers_json: Vec<String>) -> Result<Self, ErgoLibError> {
+        let headers = headers_json
+            .iter()
+            .map(|json| serde_json::from_str::<Header>(json))
+            .collect::<Result<Vec<Header>, _>>()
+            .map_err(json_err)?;
+        ergo_state_context::ErgoStateContext::from_last_headers(headers)
+            .map(ErgoStateContext)
+            .map_err(|e| ErgoLibError::InvalidArgument(e.to_string()))
+    }
+}
+
+/// Commitments and partial proofs of the signers for each input of a multi signature transaction
+pub struct TransactionHintsBag(multi_sig::TransactionHintsBag);
+
+impl TransactionHintsBag {
+    /// Parse from JSON
+    pub fn from_json(json: String) -> Result<Self, ErgoLibError> {
+        serde_json::from_str(&json)
+            .map(TransactionHintsBag)
+            .map_err(json_err)
+    }
+
+    /// JSON representation
+    pub fn to_json(&self) -> Result<String, ErgoLibError> {
+        serde_json::to_string(&self.0).map_err(json_err)
+    }
+}
+
+/// Wallet holding the secret keys to sign transactions
+pub struct Wallet {
+    // `wallet::Wallet` is not `Sync`, so the wallet is re-created from the secrets on signing
+    secrets: Vec<SecretKey>,
+}
+
+impl Wallet {
+    /// Wallet with the master key derived from the mnemonic phrase and password
+    pub fn from_mnemonic(
+        mnemonic_phrase: String,
+        mnemonic_pass: String,
+    ) -> Result<Self, ErgoLibError> {
+        let seed = Mnemonic::to_seed(&mnemonic_phrase, &mnemonic_pass);
+        let ext_sk =
+            ExtSecretKey::derive_master(seed).map_err(|e| ErgoLibError::Wallet(e.to_string()))?;
+        Ok(Wallet {
+            secrets: vec![ext_sk.secret_key()],
+        })
+    }
+
+    /// Sign the transaction spending `boxes_to_spend` (in the order of inputs)
+    pub fn sign_transaction(
+        &self,
+        state_context: Arc<ErgoStateContext>,
+        unsigned_tx: Arc<UnsignedTransaction>,
+        boxes_to_spend: Vec<Arc<ErgoBox>>,
+        data_boxes: Vec<Arc<ErgoBox>>,
+    ) -> Result<Arc<Transaction>, ErgoLibError> {
+        let tx_context = tx_context(unsigned_tx, boxes_to_spend, data_boxes)?;
+        wallet::Wallet::from_secrets(self.secrets.clone())
+            .sign_transaction(tx_context, &state_context.0, None)
+            .map(|tx| Arc::new(Transaction(tx)))
+            .map_err(|e| ErgoLibError::Signing(e.to_string()))
+    }
+
+    /// Sign the multi signature transaction with the hints gathered from all the signers
+    pub fn sign_transaction_multi(
+        &self,
+        state_context: Arc<ErgoStateContext>,
+        unsigned_tx: Arc<UnsignedTransaction>,
+        boxes_to_spend: Vec<Arc<ErgoBox>>,
+        data_boxes: Vec<Arc<ErgoBox>>,
+        tx_hints: Arc<TransactionHintsBag>,
+    ) -> Result<Arc<Transaction>, ErgoLibError> {
+        let tx_context = tx_context(unsigned_tx, boxes_to_spend, data_boxes)?;
+        wallet::Wallet::from_secrets(self.secrets.clone())
+            .sign_transaction_multi(tx_context, &state_context.0, &tx_hints.0)
+            .map(|tx| Arc::new(Transaction(tx)))
+            .map_err(|e| ErgoLibError::Signing(e.to_string()))
+    }
+
+    /// Commitments of the wallet secrets for the inputs of the transaction (first signing round)
+    pub fn generate_commitments(
+        &self,
+        state_context: Arc<ErgoStateContext>,
+        unsigned_tx: Arc<UnsignedTransaction>,
+        boxes_to_spend: Vec<Arc<ErgoBox>>,
+        data_boxes: Vec<Arc<ErgoBox>>,
+    ) -> Result<Arc<TransactionHintsBag>, ErgoLibError> {
+        let tx_context = tx_context(unsigned_tx, boxes_to_spend, data_boxes)?;
+        wallet::Wallet::from_secrets(self.secrets.clone())
+            .generate_commitments(tx_context, &state_context.0)
+            .map(|hints| Arc::new(TransactionHintsBag(hints)))
+            .map_err(|e| ErgoLibError::Signing(e.to_string()))
+    }
+}
+
+fn tx_context(
+    unsigned_tx: Arc<UnsignedTransaction>,
+    boxes_to_spend: Vec<Arc<ErgoBox>>,
+    data_boxes: Vec<Arc<ErgoBox>>,
+) -> Result<TransactionContext<transaction::unsigned::UnsignedTransaction>, ErgoLibError> {
+    TransactionContext::new(
+        unsigned_tx.0.clone(),
+        unwrap_boxes(boxes_to_spend),
+        unwrap_boxes(data_boxes),
+    )
+    .map_err(|e| ErgoLibError::Signing(e.to_string()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergo_lib::chain::transaction::verify_transaction;
+    use ergo_lib::chain::transaction::UnsignedInput;
+    use ergo_lib::ergotree_interpreter::sigma_protocol::private_input::PrivateInput;
+    use ergo_lib::ergotree_interpreter::sigma_protocol::prover::hint::CommitmentHint;
+    use ergo_lib::ergotree_interpreter::sigma_protocol::prover::hint::Hint;
+    use ergo_lib::ergotree_interpreter::sigma_protocol::prover::hint::HintsBag;
+    use ergo_lib::ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergo_lib::ergotree_ir::chain::tx_id::TxId;
+    use ergo_lib::ergotree_ir::ergo_tree::ErgoTree;
+    use ergo_lib::ergotree_ir::mir::expr::Expr;
+    use ergo_lib::ergotree_ir::mir::sigma_and::SigmaAnd;
+    use ergo_lib::ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+    use ergo_lib::ergotree_ir::sigma_protocol::sigma_boolean::SigmaProp;
+
+    use super::*;
+
+    const MAINNET_ADDRESS: &str = "9gmNsqrqdSppLUBqg2UzREmmivgqh1r3jmNcLAc53hk3YCvAGWE";
+    const NODE_LAST_HEADERS: &str =
+        include_str!("../../../ergo-lib/tests/fixtures/node_last_headers.json");
+    const MNEMONIC: &str = "change me do not use me change me do not use me";
+
+    fn state_context() -> Arc<ErgoStateContext> {
+        let headers: Vec<serde_json::Value> = serde_json::from_str(NODE_LAST_HEADERS).unwrap();
+        let headers_json = headers.iter().map(|h| h.to_string()).collect();
+        Arc::new(ErgoStateContext::from_headers_json(headers_json).unwrap())
+    }
+
+    fn public_key(wallet: &Wallet) -> SigmaBoolean {
+        PrivateInput::from(wallet.secrets[0].clone()).public_image()
+    }
+
+    /// Transaction spending a box guarded by `pk_a && pk_b`
+    fn multi_sig_tx(
+        pk_a: SigmaBoolean,
+        pk_b: SigmaBoolean,
+    ) -> (Arc<UnsignedTransaction>, Vec<Arc<ErgoBox>>) {
+        let expr: Expr = SigmaAnd::new(vec![
+            Expr::Const(SigmaProp::new(pk_a).into()),
+            Expr::Const(SigmaProp::new(pk_b).into()),
+        ])
+        .unwrap()
+        .into();
+        let input = ergo_box::ErgoBox::new(
+            box_value(1000000000).unwrap(),
+            ErgoTree::try_from(expr).unwrap(),
+            None,
+            NonMandatoryRegisters::empty(),
+            0,
+            TxId::zero(),
+            0,
+        )
+        .unwrap();
+        let addr = Arc::new(Address::from_base58(MAINNET_ADDRESS.to_string()).unwrap());
+        let output = ErgoBoxCandidate::new(1000000000, addr, 0).unwrap();
+        let tx = transaction::unsigned::UnsignedTransaction::new_from_vec(
+            vec![UnsignedInput::from(input.clone())],
+            vec![],
+            vec![output.0],
+        )
+        .unwrap();
+        (
+            Arc::new(UnsignedTransaction(tx)),
+            vec![Arc::new(ErgoBox(input))],
+        )
+    }
+
+    fn is_valid(tx: &Transaction, boxes_to_spend: &[Arc<ErgoBox>]) -> bool {
+        let tx_context =
+            TransactionContext::new(tx.0.clone(), unwrap_boxes(boxes_to_spend.to_vec()), vec![])
+                .unwrap();
+        verify_transaction(&tx_context, &state_context().0)
+            .unwrap()
+            .is_valid()
+    }
+
+    #[test]
+    fn generate_commitments() {
+        let alice = Wallet::from_mnemonic(MNEMONIC.to_string(), "alice".to_string()).unwrap();
+        let bob = Wallet::from_mnemonic(MNEMONIC.to_string(), "bob".to_string()).unwrap();
+        let (tx, boxes) = multi_sig_tx(public_key(&alice), public_key(&bob));
+        let bag = alice
+            .generate_commitments(state_context(), tx, boxes, vec![])
+            .unwrap();
+        // the own (secret randomness) and the real (shared) commitment for the alice key
+        let hints = bag.0.all_hints_for_input(0);
+        assert_eq!(hints.own_commitments().len(), 1);
+        assert_eq!(hints.real_commitments().len(), 1);
+        assert_eq!(hints.real_commitments()[0].image, public_key(&alice));
+        assert!(bag.0.all_hints_for_input(1).hints.is_empty());
+        let json = bag.to_json().unwrap();
+        assert_eq!(TransactionHintsBag::from_json(json).unwrap().0, bag.0);
+        assert!(matches!(
+            TransactionHintsBag::from_json("{}".to_string()),
+            Err(ErgoLibError::InvalidJson(_))
+        ));
+    }
+
+    #[test]
+    fn sign_transaction_multi() {
+        let alice = Wallet::from_mnemonic(MNEMONIC.to_string(), "alice".to_string()).unwrap();
+        let bob = Wallet::from_mnemonic(MNEMONIC.to_string(), "bob".to_string()).unwrap();
+        let (pk_alice, pk_bob) = (public_key(&alice), public_key(&bob));
+        let (tx, boxes) = multi_sig_tx(pk_alice.clone(), pk_bob);
+        let state_context = state_context();
+
+        // first round: bob shares the real commitment with alice
+        let bob_bag = bob
+            .generate_commitments(state_context.clone(), tx.clone(), boxes.clone(), vec![])
+            .unwrap();
+        let mut alice_bag = multi_sig::TransactionHintsBag::empty();
+        alice_bag.add_hints_for_input(
+            0,
+            HintsBag {
+                hints: bob_bag
+                    .0
+                    .all_hints_for_input(0)
+                    .real_commitments()
+                    .into_iter()
+                    .map(|c| Hint::CommitmentHint(CommitmentHint::RealCommitment(c)))
+                    .collect(),
+            },
+        );
+        // alice signs partially
+        let partial_tx = alice
+            .sign_transaction_multi(
+                state_context.clone(),
+                tx.clone(),
+                boxes.clone(),
+                vec![],
+                Arc::new(TransactionHintsBag(alice_bag)),
+            )
+            .unwrap();
+        assert_eq!(partial_tx.id(), tx.id());
+        assert!(!is_valid(&partial_tx, &boxes));
+
+        // second round: bob completes the proof with the alice partial proof and the own commitment
+        let mut bob_hints = multi_sig::extract_hints(
+            &TransactionContext::new(partial_tx.0.clone(), unwrap_boxes(boxes.clone()), vec![])
+                .unwrap(),
+            &state_context.0,
+            vec![pk_alice],
+            vec![],
+        )
+        .unwrap();
+        bob_hints.add_hints_for_input(0, bob_bag.0.all_hints_for_input(0));
+        let signed_tx = bob
+            .sign_transaction_multi(
+                state_context,
+                tx.clone(),
+                boxes.clone(),
+                vec![],
+                Arc::new(TransactionHintsBag(bob_hints)),
+            )
+            .unwrap();
+        assert_eq!(signed_tx.id(), tx.id());
+        assert!(is_valid(&signed_tx, &boxes));
+    }
+
+    #[test]
+    fn address_roundtrip() {
+        let addr = Address::from_base58(MAINNET_ADDRESS.to_string()).unwrap();
+        assert_eq!(addr.to_base58(NetworkPrefix::Mainnet), MAINNET_ADDRESS);
+        let checked =
+            Address::from_base58_checked(MAINNET_ADDRESS.to_string(), NetworkPrefix::Mainnet)
+                .unwrap();
+        assert_eq!(checked.to_base58(NetworkPrefix::Mainnet), MAINNET_ADDRESS);
+        assert_eq!(
+            checked.ergo_tree_bytes().unwrap(),
+            addr.ergo_tree_bytes().unwrap()
+        );
+    }
+
+    #[test]
+    fn address_parse_errors() {
+        assert!(matches!(
+            Address::from_base58_checked(MAINNET_ADDRESS.to_string(), NetworkPrefix::Testnet),
+            Err(ErgoLibError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            Address::from_base58("not an address".to_string()),
+            Err(ErgoLibError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn box_candidate_and_selection() {
+        let addr = Arc::new(Address::from_base58(MAINNET_ADDRESS.to_string()).unwrap());
+        let candidate = ErgoBoxCandidate::new(1000000, addr, 100).unwrap();
+        assert_eq!(candidate.value(), 1000000);
+        assert_eq!(candidate.creation_height(), 100);
+        assert!(matches!(
+            select_boxes_simple(vec![], 1000000),
+            Err(ErgoLibError::BoxSelection(_))
+        ));
+    }
+}
@@ -28,6 +28,11 @@ impl BlockHeader {
         self.0.id.into()
     }
 
+    /// Get Header's height
+    pub fn height(&self) -> u32 {
+        self.0.height
+    }
+
     /// Get transactions root
     pub fn transactions_root(&self) -> Vec<u8> {
         self.0.transaction_root.into()
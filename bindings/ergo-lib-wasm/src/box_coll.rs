@@ -38,6 +38,19 @@ impl ErgoBoxes {
             .map(ErgoBoxes::from)
     }
 
+    /// Parse ErgoBox array from a single JSON array string (e.g. node or explorer API response).
+    /// Much faster than [`Self::from_boxes_json`] for a large number of boxes.
+    pub fn from_boxes_json_array(json: &str) -> Result<ErgoBoxes, JsValue> {
+        serde_json::from_str::<Vec<chain::ergo_box::ErgoBox>>(json)
+            .map(ErgoBoxes::from)
+            .map_err(|e| {
+                JsValue::from_str(&format!(
+                    "Failed to parse ErgoBox array from JSON string with error: {:?}",
+                    e
+                ))
+            })
+    }
+
     /// Create new collection with one element
     #[wasm_bindgen(constructor)]
     pub fn new(b: &ErgoBox) -> ErgoBoxes {
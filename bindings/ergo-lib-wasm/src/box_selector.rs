@@ -34,6 +34,17 @@ impl BoxSelection {
         }))
     }
 
+    /// Create a selection for the boxes chosen by a custom selection algorithm, with the change
+    /// (value and all the tokens not in `target_tokens`) computed by [`calc_change`]
+    pub fn with_change(
+        boxes: &ErgoBoxes,
+        target_balance: &BoxValue,
+        target_tokens: &Tokens,
+    ) -> Result<BoxSelection, JsValue> {
+        let change = calc_change(boxes, target_balance, target_tokens)?;
+        BoxSelection::new(boxes, &change)
+    }
+
     /// Selected boxes to spend as transaction inputs
     pub fn boxes(&self) -> ErgoBoxes {
         self.0.boxes.as_vec().clone().into()
@@ -72,15 +83,38 @@ impl SimpleBoxSelector {
             .select(
                 inputs.clone().into(),
                 target_balance.clone().into(),
-                target_tokens
-                    .clone()
-                    .0
-                    .into_iter()
-                    .map(|t| t.into())
-                    .collect::<Vec<chain::token::Token>>()
-                    .as_slice(),
+                target_tokens_vec(target_tokens).as_slice(),
             )
             .map_err(to_js)
             .map(BoxSelection)
     }
 }
+
+/// Change (value and tokens left after `target_balance` and `target_tokens` are taken) of the
+/// selected inputs. Tokens over the max tokens per box limit are split into multiple change boxes.
+/// Returns an empty list if the inputs exactly match the targets, and an error if the inputs
+/// do not have enough coins or tokens.
+#[wasm_bindgen]
+pub fn calc_change(
+    selected_inputs: &ErgoBoxes,
+    target_balance: &BoxValue,
+    target_tokens: &Tokens,
+) -> Result<ErgoBoxAssetsDataList, JsValue> {
+    let inputs: Vec<chain::ergo_box::ErgoBox> = selected_inputs.clone().into();
+    wallet::box_selector::calc_change(
+        inputs.as_slice(),
+        target_balance.clone().into(),
+        target_tokens_vec(target_tokens).as_slice(),
+    )
+    .map_err(to_js)
+    .map(ErgoBoxAssetsDataList::from)
+}
+
+fn target_tokens_vec(target_tokens: &Tokens) -> Vec<chain::token::Token> {
+    target_tokens
+        .clone()
+        .0
+        .into_iter()
+        .map(|t| t.into())
+        .collect()
+}
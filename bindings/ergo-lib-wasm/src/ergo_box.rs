@@ -226,6 +226,15 @@ impl ErgoBox {
             .map_err(|e| JsValue::from_str(&format!("{}", e)))
     }
 
+    /// Parse from JS object according to EIP-12 <https://github.com/ergoplatform/eips/pull/23>
+    /// (box value and token amounts encoded as strings or numbers)
+    pub fn from_js_eip12(js: &JsValue) -> Result<ErgoBox, JsValue> {
+        let box_dapp: ErgoBoxJsonEip12 = JsValueSerdeExt::into_serde(js).map_err(to_js)?;
+        chain::ergo_box::ErgoBox::try_from(box_dapp)
+            .map(Self)
+            .map_err(to_js)
+    }
+
     /// parse from JSON
     /// supports Ergo Node/Explorer API and box values and token amount encoded as strings
     pub fn from_json(json: &str) -> Result<ErgoBox, JsValue> {
@@ -6,6 +6,7 @@ use std::num::ParseIntError;
 
 use base16::DecodeError;
 use bounded_vec::BoundedVecOutOfBounds;
+use ergo_lib::chain::json::eip12::Eip12JsonError;
 use ergo_lib::chain::transaction::TransactionSignatureVerificationError;
 use ergo_lib::chain::transaction::TxVerifyError;
 use ergo_lib::ergo_chain_types::DigestNError;
@@ -34,6 +35,7 @@ use ergo_lib::{
     wallet::{box_selector::BoxSelectorError, tx_builder::TxBuilderError, WalletError},
 };
 use serde_json::error::Error;
+use sigma_util::encoding::DecodingError;
 #[cfg(feature = "rest")]
 use url::ParseError;
 use wasm_bindgen::JsValue;
@@ -77,6 +79,7 @@ from_error_to_wrap!(TxBuilderError);
 from_error_to_wrap!(TxSigningError);
 from_error_to_wrap!(WalletError);
 from_error_to_wrap!(DecodeError);
+from_error_to_wrap!(DecodingError);
 from_error_to_wrap!(TryFromSliceError);
 from_error_to_wrap!(AddrParseError);
 from_error_to_wrap!(TransactionSignatureVerificationError);
@@ -98,6 +101,7 @@ from_error_to_wrap!(ConvError);
 from_error_to_wrap!(TransactionContextError);
 from_error_to_wrap!(TxVerifyError);
 from_error_to_wrap!(RegisterValueError);
+from_error_to_wrap!(Eip12JsonError);
 from_error_to_wrap!(String);
 
 macro_rules! from_error_to_wrap_via_debug {
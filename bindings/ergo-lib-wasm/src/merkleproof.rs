@@ -1,6 +1,7 @@
 //! Merkle Proof verification
 use ergo_lib::ergo_merkle_tree;
 use serde::{Deserialize, Serialize};
+use sigma_util::encoding::{decode_base16, DecodingMode};
 use std::convert::TryInto;
 use wasm_bindgen::prelude::*;
 
@@ -63,5 +64,5 @@ impl MerkleProof {
 #[wasm_bindgen]
 /// Decodes a base16 string into an array of bytes
 pub fn base16_decode(data: &str) -> Result<Vec<u8>, JsValue> {
-    base16::decode(&data).map_err(|_| "Failed to decode base16 input".into())
+    decode_base16(data, DecodingMode::Strict).map_err(|_| "Failed to decode base16 input".into())
 }
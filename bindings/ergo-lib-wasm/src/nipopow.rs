@@ -64,9 +64,14 @@ impl NipopowVerifier {
         BlockHeaders(self.0.best_chain().into_iter().map(|h| h.into()).collect())
     }
 
+    /// Returns suffix `BlockHeader`s (the most recent `k` headers) of the best proof.
+    pub fn best_suffix(&self) -> BlockHeaders {
+        BlockHeaders(self.0.best_suffix().into_iter().map(|h| h.into()).collect())
+    }
+
     /// Process given proof
     pub fn process(&mut self, new_proof: NipopowProof) -> Result<(), JsValue> {
-        self.0.process(new_proof.0).map_err(to_js)
+        self.0.process(new_proof.0).map(|_| ()).map_err(to_js)
     }
 }
 /// PoPowHeader structure. Represents the block header and unpacked interlinks
@@ -1,5 +1,7 @@
 //! Wasm API for ergo-rest crate
 
+#[cfg(target_arch = "wasm32")]
+mod abort;
 #[cfg(target_arch = "wasm32")]
 pub mod api;
 #[cfg(target_arch = "wasm32")]
@@ -0,0 +1,49 @@
+//! Cancellation of the REST API requests with `AbortSignal` (from JS `AbortController`)
+
+use std::future::Future;
+
+use futures::channel::oneshot;
+use futures::future::select;
+use futures::future::Either;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// Run the future until it's complete or the signal is aborted. On abort the future is dropped
+/// (cancelling the in-flight HTTP requests) and the result is `AbortError`.
+pub(crate) async fn abortable<T, F>(
+    signal: Option<web_sys::AbortSignal>,
+    fut: F,
+) -> Result<T, JsValue>
+where
+    F: Future<Output = Result<T, JsValue>>,
+{
+    let signal = match signal {
+        Some(signal) => signal,
+        None => return fut.await,
+    };
+    if signal.aborted() {
+        return Err(abort_error());
+    }
+    let (sender, receiver) = oneshot::channel::<()>();
+    let mut sender = Some(sender);
+    let on_abort = Closure::<dyn FnMut()>::new(move || {
+        if let Some(sender) = sender.take() {
+            let _ = sender.send(());
+        }
+    });
+    signal.add_event_listener_with_callback("abort", on_abort.as_ref().unchecked_ref())?;
+    futures::pin_mut!(fut);
+    let res = match select(fut, receiver).await {
+        Either::Left((res, _)) => res,
+        Either::Right(_) => Err(abort_error()),
+    };
+    // the closure is dropped on return, so the listener has to be removed
+    signal.remove_event_listener_with_callback("abort", on_abort.as_ref().unchecked_ref())?;
+    res
+}
+
+fn abort_error() -> JsValue {
+    let e = js_sys::Error::new("The operation was aborted");
+    e.set_name("AbortError");
+    e.into()
+}
@@ -5,22 +5,69 @@
 //! problem since futures need to have a 'static lifetime. The workaround is to clone the args and
 //! pass it into an `async move` block, and convert that into a JS promise directly (described in
 //! https://github.com/rustwasm/wasm-bindgen/issues/1858).
+//!
+//! The requests are cancelled with the optional `AbortSignal` (from JS `AbortController`), the
+//! promise is rejected with `AbortError` in this case. The returned promises have the resolved
+//! type in the TypeScript definitions.
 
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 
+use super::abort::abortable;
 use super::node_conf::NodeConf;
 use crate::{
-    block_header::{BlockHeader, BlockId},
+    block_header::{BlockHeader, BlockHeaders, BlockId},
+    ergo_box::{BoxId, ErgoBox},
     error_conversion::to_js,
     nipopow::NipopowProof,
-    transaction::TxId,
+    transaction::{Transaction, Transactions, TxId},
 };
 use bounded_vec::NonEmptyVec;
 use std::time::Duration;
 
+#[wasm_bindgen]
+extern "C" {
+    /// Promise resolving to `NodeInfo`
+    #[wasm_bindgen(typescript_type = "Promise<NodeInfo>")]
+    pub type NodeInfoPromise;
+    /// Promise resolving to `BlockHeader`
+    #[wasm_bindgen(typescript_type = "Promise<BlockHeader>")]
+    pub type BlockHeaderPromise;
+    /// Promise resolving to `BlockHeaders`
+    #[wasm_bindgen(typescript_type = "Promise<BlockHeaders>")]
+    pub type BlockHeadersPromise;
+    /// Promise resolving to an array of `BlockId`
+    #[wasm_bindgen(typescript_type = "Promise<BlockId[]>")]
+    pub type BlockIdsPromise;
+    /// Promise resolving to `NipopowProof`
+    #[wasm_bindgen(typescript_type = "Promise<NipopowProof>")]
+    pub type NipopowProofPromise;
+    /// Promise resolving to `MerkleProof` or `undefined`
+    #[wasm_bindgen(typescript_type = "Promise<MerkleProof | undefined>")]
+    pub type MerkleProofPromise;
+    /// Promise resolving to `ErgoBox` or `undefined`
+    #[wasm_bindgen(typescript_type = "Promise<ErgoBox | undefined>")]
+    pub type ErgoBoxPromise;
+    /// Promise resolving to `TxId`
+    #[wasm_bindgen(typescript_type = "Promise<TxId>")]
+    pub type TxIdPromise;
+    /// Promise resolving to `Transaction` or `undefined`
+    #[wasm_bindgen(typescript_type = "Promise<Transaction | undefined>")]
+    pub type TransactionPromise;
+    /// Promise resolving to `Transactions`
+    #[wasm_bindgen(typescript_type = "Promise<Transactions>")]
+    pub type TransactionsPromise;
+    /// Promise resolving to `PeerUrls`
+    #[wasm_bindgen(typescript_type = "Promise<PeerUrls>")]
+    pub type PeerUrlsPromise;
+    /// Promise resolving to `ChromePeerDiscoveryScan`
+    #[wasm_bindgen(typescript_type = "Promise<ChromePeerDiscoveryScan>")]
+    pub type ChromePeerDiscoveryScanPromise;
+}
+
 #[wasm_bindgen]
 /// GET on /info endpoint
-pub fn get_info(node: &NodeConf) -> js_sys::Promise {
+pub fn get_info(node: &NodeConf, signal: Option<web_sys::AbortSignal>) -> NodeInfoPromise {
     // Note that we can't pass in `node` by value as it will fail on the JS side if used again,
     // despite the Copy implementation.  The problem is a bit mysterious; after calling this
     // function, `node` isn't null on the JS side but when used again it will crash on the rust side
@@ -29,28 +76,34 @@ pub fn get_info(node: &NodeConf) -> js_sys::Promise {
     // A related issue is here: https://github.com/rustwasm/wasm-bindgen/issues/2204
     #[allow(clippy::clone_on_copy)]
     let node_cloned = node.0.clone();
-    wasm_bindgen_futures::future_to_promise(async move {
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
         let info = ergo_lib::ergo_rest::api::node::get_info(node_cloned)
             .await
             .map_err(to_js)
             .map(super::node_info::NodeInfo::from)?;
         Ok(wasm_bindgen::JsValue::from(info))
-    })
+    }))
+    .unchecked_into()
 }
 
 #[wasm_bindgen]
 /// GET on /blocks/{header_id}/header endpoint
-pub fn get_header(node: &NodeConf, header_id: &BlockId) -> js_sys::Promise {
+pub fn get_header(
+    node: &NodeConf,
+    header_id: &BlockId,
+    signal: Option<web_sys::AbortSignal>,
+) -> BlockHeaderPromise {
     let header_id_cloned = header_id.0.clone();
     #[allow(clippy::clone_on_copy)]
     let node_cloned = node.0.clone();
-    wasm_bindgen_futures::future_to_promise(async move {
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
         let header = ergo_lib::ergo_rest::api::node::get_header(node_cloned, header_id_cloned)
             .await
             .map_err(to_js)
             .map(BlockHeader::from)?;
         Ok(wasm_bindgen::JsValue::from(header))
-    })
+    }))
+    .unchecked_into()
 }
 
 #[wasm_bindgen]
@@ -60,11 +113,12 @@ pub fn get_nipopow_proof_by_header_id(
     min_chain_length: u32,
     suffix_len: u32,
     header_id: &BlockId,
-) -> js_sys::Promise {
+    signal: Option<web_sys::AbortSignal>,
+) -> NipopowProofPromise {
     let header_id_cloned = header_id.0.clone();
     #[allow(clippy::clone_on_copy)]
     let node_cloned = node.0.clone();
-    wasm_bindgen_futures::future_to_promise(async move {
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
         let proof = ergo_lib::ergo_rest::api::node::get_nipopow_proof_by_header_id(
             node_cloned,
             min_chain_length,
@@ -75,7 +129,8 @@ pub fn get_nipopow_proof_by_header_id(
         .map_err(to_js)
         .map(NipopowProof::from)?;
         Ok(wasm_bindgen::JsValue::from(proof))
-    })
+    }))
+    .unchecked_into()
 }
 
 #[wasm_bindgen]
@@ -85,12 +140,13 @@ pub fn get_blocks_header_id_proof_for_tx_id(
     node: &NodeConf,
     header_id: &BlockId,
     tx_id: &TxId,
-) -> js_sys::Promise {
+    signal: Option<web_sys::AbortSignal>,
+) -> MerkleProofPromise {
     let header_id_cloned = header_id.0.clone();
     let tx_id_cloned = tx_id.0.clone();
     #[allow(clippy::clone_on_copy)]
     let node_cloned = node.0.clone();
-    wasm_bindgen_futures::future_to_promise(async move {
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
         let merkle_proof = ergo_lib::ergo_rest::api::node::get_blocks_header_id_proof_for_tx_id(
             node_cloned,
             header_id_cloned,
@@ -100,7 +156,175 @@ pub fn get_blocks_header_id_proof_for_tx_id(
         .map_err(to_js)
         .map(|m| m.map(crate::merkleproof::MerkleProof))?;
         Ok(wasm_bindgen::JsValue::from(merkle_proof))
-    })
+    }))
+    .unchecked_into()
+}
+
+#[wasm_bindgen]
+/// GET on /blocks/at/{height} endpoint. Returns ids of the blocks at the given height (the first
+/// one is on the best chain)
+pub fn get_blocks_at_height(
+    node: &NodeConf,
+    height: u32,
+    signal: Option<web_sys::AbortSignal>,
+) -> BlockIdsPromise {
+    #[allow(clippy::clone_on_copy)]
+    let node_cloned = node.0.clone();
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
+        let block_ids = ergo_lib::ergo_rest::api::node::get_blocks_at_height(node_cloned, height)
+            .await
+            .map_err(to_js)?;
+        Ok(block_ids
+            .into_iter()
+            .map(|id| wasm_bindgen::JsValue::from(BlockId(id)))
+            .collect::<js_sys::Array>()
+            .into())
+    }))
+    .unchecked_into()
+}
+
+#[wasm_bindgen]
+/// GET on /blocks/lastHeaders/{count} endpoint. Returns the last `count` headers of the best chain
+pub fn get_last_headers(
+    node: &NodeConf,
+    count: u32,
+    signal: Option<web_sys::AbortSignal>,
+) -> BlockHeadersPromise {
+    #[allow(clippy::clone_on_copy)]
+    let node_cloned = node.0.clone();
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
+        let headers = ergo_lib::ergo_rest::api::node::get_last_headers(node_cloned, count)
+            .await
+            .map_err(to_js)
+            .map(BlockHeaders::from)?;
+        Ok(wasm_bindgen::JsValue::from(headers))
+    }))
+    .unchecked_into()
+}
+
+#[wasm_bindgen]
+/// GET on /utxo/byId/{box_id} endpoint. Resolves to `undefined` if the box is not in the UTXO set
+pub fn get_utxo_by_id(
+    node: &NodeConf,
+    box_id: &BoxId,
+    signal: Option<web_sys::AbortSignal>,
+) -> ErgoBoxPromise {
+    let box_id_cloned: ergo_lib::ergotree_ir::chain::ergo_box::BoxId = box_id.clone().into();
+    #[allow(clippy::clone_on_copy)]
+    let node_cloned = node.0.clone();
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
+        let ergo_box = ergo_lib::ergo_rest::api::node::get_utxo_by_id(node_cloned, box_id_cloned)
+            .await
+            .map_err(to_js)?;
+        Ok(wasm_bindgen::JsValue::from(ergo_box.map(ErgoBox::from)))
+    }))
+    .unchecked_into()
+}
+
+#[wasm_bindgen]
+/// GET on /utxo/withPool/byId/{box_id} endpoint. Same as [`get_utxo_by_id`], but also looks up
+/// the boxes created by the transactions in the mempool
+pub fn get_utxo_with_pool_by_id(
+    node: &NodeConf,
+    box_id: &BoxId,
+    signal: Option<web_sys::AbortSignal>,
+) -> ErgoBoxPromise {
+    let box_id_cloned: ergo_lib::ergotree_ir::chain::ergo_box::BoxId = box_id.clone().into();
+    #[allow(clippy::clone_on_copy)]
+    let node_cloned = node.0.clone();
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
+        let ergo_box =
+            ergo_lib::ergo_rest::api::node::get_utxo_with_pool_by_id(node_cloned, box_id_cloned)
+                .await
+                .map_err(to_js)?;
+        Ok(wasm_bindgen::JsValue::from(ergo_box.map(ErgoBox::from)))
+    }))
+    .unchecked_into()
+}
+
+#[wasm_bindgen]
+/// POST on /transactions endpoint to submit the signed transaction. Resolves to the id of the
+/// accepted transaction
+pub fn submit_transaction(
+    node: &NodeConf,
+    tx: &Transaction,
+    signal: Option<web_sys::AbortSignal>,
+) -> TxIdPromise {
+    let tx_cloned: ergo_lib::chain::transaction::Transaction = tx.clone().into();
+    #[allow(clippy::clone_on_copy)]
+    let node_cloned = node.0.clone();
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
+        let tx_id = ergo_lib::ergo_rest::api::node::submit_transaction(node_cloned, &tx_cloned)
+            .await
+            .map_err(to_js)?;
+        Ok(wasm_bindgen::JsValue::from(TxId(tx_id)))
+    }))
+    .unchecked_into()
+}
+
+#[wasm_bindgen]
+/// POST on /transactions/check endpoint to validate the signed transaction against the node's
+/// UTXO set and mempool without submitting it. Resolves to the transaction id if it's valid
+pub fn check_transaction(
+    node: &NodeConf,
+    tx: &Transaction,
+    signal: Option<web_sys::AbortSignal>,
+) -> TxIdPromise {
+    let tx_cloned: ergo_lib::chain::transaction::Transaction = tx.clone().into();
+    #[allow(clippy::clone_on_copy)]
+    let node_cloned = node.0.clone();
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
+        let tx_id = ergo_lib::ergo_rest::api::node::check_transaction(node_cloned, &tx_cloned)
+            .await
+            .map_err(to_js)?;
+        Ok(wasm_bindgen::JsValue::from(TxId(tx_id)))
+    }))
+    .unchecked_into()
+}
+
+#[wasm_bindgen]
+/// GET on /transactions/unconfirmed endpoint. Resolves to up to `limit` mempool transactions
+/// starting from `offset`
+pub fn get_unconfirmed_transactions(
+    node: &NodeConf,
+    offset: u32,
+    limit: u32,
+    signal: Option<web_sys::AbortSignal>,
+) -> TransactionsPromise {
+    #[allow(clippy::clone_on_copy)]
+    let node_cloned = node.0.clone();
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
+        let txs = ergo_lib::ergo_rest::api::node::get_unconfirmed_transactions::<
+            ergo_lib::chain::transaction::Transaction,
+        >(node_cloned, offset, limit)
+        .await
+        .map_err(to_js)
+        .map(Transactions::from)?;
+        Ok(wasm_bindgen::JsValue::from(txs))
+    }))
+    .unchecked_into()
+}
+
+#[wasm_bindgen]
+/// GET on /transactions/unconfirmed/byTransactionId/{tx_id} endpoint. Resolves to `undefined` if
+/// the transaction is not in the mempool
+pub fn get_unconfirmed_transaction_by_id(
+    node: &NodeConf,
+    tx_id: &TxId,
+    signal: Option<web_sys::AbortSignal>,
+) -> TransactionPromise {
+    let tx_id_cloned = tx_id.0.clone();
+    #[allow(clippy::clone_on_copy)]
+    let node_cloned = node.0.clone();
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
+        let tx = ergo_lib::ergo_rest::api::node::get_unconfirmed_transaction_by_id::<
+            ergo_lib::chain::transaction::Transaction,
+        >(node_cloned, tx_id_cloned)
+        .await
+        .map_err(to_js)?;
+        Ok(wasm_bindgen::JsValue::from(tx.map(Transaction::from)))
+    }))
+    .unchecked_into()
 }
 
 /// List of peer urls returned from `peer_discovery`. We need this wrapper struct because the
@@ -134,18 +358,24 @@ impl PeerUrls {
 ///  - `is_chrome` **MUST** be set to true if running this function on a Chromium-based browser.
 ///    There are some limitations on this platform regarding network requests. Please see the
 ///    documentation for [`peer_discovery_chrome`].
+///  - `signal` stops the discovery (the promise is rejected with `AbortError`).
 #[wasm_bindgen]
-pub async fn peer_discovery(
+pub fn peer_discovery(
     seeds: Box<[web_sys::Url]>,
     max_parallel_requests: u16,
     timeout_sec: u32,
     is_chrome: bool,
-) -> Result<PeerUrls, JsValue> {
-    if is_chrome {
-        peer_discovery_chrome(seeds, max_parallel_requests, timeout_sec).await
-    } else {
-        peer_discovery_non_chrome(seeds, max_parallel_requests, timeout_sec).await
-    }
+    signal: Option<web_sys::AbortSignal>,
+) -> PeerUrlsPromise {
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
+        let peer_urls = if is_chrome {
+            peer_discovery_chrome(seeds, max_parallel_requests, timeout_sec).await
+        } else {
+            peer_discovery_non_chrome(seeds, max_parallel_requests, timeout_sec).await
+        }?;
+        Ok(wasm_bindgen::JsValue::from(peer_urls))
+    }))
+    .unchecked_into()
 }
 
 /// IMPORTANT: do not call this function on Chromium, as it will likely mess with the browser's
@@ -247,9 +477,10 @@ pub fn incremental_peer_discovery_chrome(
     scan: &super::chrome_peer_discovery_scan::ChromePeerDiscoveryScan,
     max_parallel_requests: u16,
     timeout_sec: u32,
-) -> js_sys::Promise {
+    signal: Option<web_sys::AbortSignal>,
+) -> ChromePeerDiscoveryScanPromise {
     let scan_cloned = scan.clone();
-    wasm_bindgen_futures::future_to_promise(async move {
+    wasm_bindgen_futures::future_to_promise(abortable(signal, async move {
         let n = u16::max(max_parallel_requests, 1);
         #[allow(clippy::unwrap_used)]
         let max_parallel_requests = bounded_integer::BoundedU16::new(n).unwrap();
@@ -263,5 +494,6 @@ pub fn incremental_peer_discovery_chrome(
         .map_err(to_js)
         .map(super::chrome_peer_discovery_scan::ChromePeerDiscoveryScan::from)?;
         Ok(wasm_bindgen::JsValue::from(updated_scan))
-    })
+    }))
+    .unchecked_into()
 }
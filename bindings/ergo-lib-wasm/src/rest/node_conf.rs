@@ -25,6 +25,7 @@ impl NodeConf {
             addr: peer_addr,
             api_key: None,
             timeout: None,
+            retry: Default::default(),
         }
         .into())
     }
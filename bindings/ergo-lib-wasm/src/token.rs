@@ -3,8 +3,6 @@
 use std::convert::TryFrom;
 
 use bounded_vec::OptBoundedVecToVec;
-use ergo_lib::ergo_chain_types::Base16DecodedBytes;
-use ergo_lib::ergo_chain_types::Digest32;
 use ergo_lib::ergotree_ir::chain;
 use gloo_utils::format::JsValueSerdeExt;
 use js_sys::Uint8Array;
@@ -31,11 +29,9 @@ impl TokenId {
     /// Parse token id (32 byte digest) from base16-encoded string
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(str: &str) -> Result<TokenId, JsValue> {
-        Base16DecodedBytes::try_from(str.to_string())
-            .map_err(to_js)
-            .and_then(|bytes| Digest32::try_from(bytes).map_err(to_js))
-            .map(|dig| dig.into())
+        str.parse::<chain::token::TokenId>()
             .map(TokenId)
+            .map_err(to_js)
     }
 
     /// Base16 encoded string
@@ -15,7 +15,7 @@ use ergo_lib::chain::transaction::{distinct_token_ids, TxIoVec};
 use ergo_lib::ergotree_ir::serialization::SigmaSerializable;
 use gloo_utils::format::JsValueSerdeExt;
 use js_sys::Uint8Array;
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryFrom;
 use wasm_bindgen::prelude::*;
 
 extern crate derive_more;
@@ -23,7 +23,6 @@ extern crate derive_more;
 use crate::ergo_state_ctx::ErgoStateContext;
 use crate::transaction::reduced::Propositions;
 use derive_more::{From, Into};
-use ergo_lib::ergo_chain_types::{Base16DecodedBytes, Base16EncodedBytes, Digest32};
 
 pub mod reduced;
 
@@ -155,25 +154,15 @@ impl TxId {
 
     /// get the tx id as bytes
     pub fn to_str(&self) -> String {
-        let base16_bytes = Base16EncodedBytes::new(self.0 .0 .0.as_ref());
-        base16_bytes.into()
+        self.0.to_string()
     }
 
     /// convert a hex string into a TxId
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Result<TxId, JsValue> {
-        let bytes = Base16DecodedBytes::try_from(s.to_string()).map_err(to_js)?;
-
-        bytes
-            .try_into()
-            .map(|digest| chain::transaction::TxId(digest).into())
-            .map_err(|_e| {
-                JsValue::from_str(&format!(
-                    "Expected a Vec of length {} but it was {}",
-                    Digest32::SIZE,
-                    s.len()
-                ))
-            })
+        s.parse::<chain::transaction::TxId>()
+            .map(TxId)
+            .map_err(to_js)
     }
 }
 
@@ -188,6 +177,7 @@ impl TxId {
  * collected into a block.
  */
 #[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Transaction(chain::transaction::Transaction);
 
 #[wasm_bindgen]
@@ -249,6 +239,15 @@ impl Transaction {
             .map_err(|e| JsValue::from_str(&format!("{}", e)))
     }
 
+    /// Parse from JS object according to EIP-12 <https://github.com/ergoplatform/eips/pull/23>
+    /// (box values and token amounts encoded as strings or numbers)
+    pub fn from_js_eip12(js: &JsValue) -> Result<Transaction, JsValue> {
+        let tx_dapp: TransactionJsonEip12 = JsValueSerdeExt::into_serde(js).map_err(to_js)?;
+        chain::transaction::Transaction::try_from(tx_dapp)
+            .map(Self)
+            .map_err(to_js)
+    }
+
     /// parse from JSON
     /// supports Ergo Node/Explorer API and box values and token amount encoded as strings
     pub fn from_json(json: &str) -> Result<Transaction, JsValue> {
@@ -299,6 +298,12 @@ impl Transaction {
     }
 }
 
+impl From<Transaction> for chain::transaction::Transaction {
+    fn from(t: Transaction) -> Self {
+        t.0
+    }
+}
+
 impl From<chain::transaction::Transaction> for Transaction {
     fn from(t: chain::transaction::Transaction) -> Self {
         Transaction(t)
@@ -392,6 +397,16 @@ impl UnsignedTransaction {
             .map_err(|e| JsValue::from_str(&format!("{}", e)))
     }
 
+    /// Parse from JS object according to EIP-12 <https://github.com/ergoplatform/eips/pull/23>
+    /// (box values and token amounts encoded as strings or numbers)
+    pub fn from_js_eip12(js: &JsValue) -> Result<UnsignedTransaction, JsValue> {
+        let tx_dapp: UnsignedTransactionJsonEip12 =
+            JsValueSerdeExt::into_serde(js).map_err(to_js)?;
+        chain::transaction::unsigned::UnsignedTransaction::try_from(tx_dapp)
+            .map(Self)
+            .map_err(to_js)
+    }
+
     /// parse from JSON
     /// supports Ergo Node/Explorer API and box values and token amount encoded as strings
     pub fn from_json(json: &str) -> Result<UnsignedTransaction, JsValue> {
@@ -407,6 +422,84 @@ impl UnsignedTransaction {
     }
 }
 
+/// Collection of transactions
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Transactions(pub(crate) Vec<Transaction>);
+
+#[wasm_bindgen]
+impl Transactions {
+    /// Create empty Transactions
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Transactions(vec![])
+    }
+
+    /// Returns the number of elements in the collection
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the element of the collection with a given index
+    pub fn get(&self, index: usize) -> Transaction {
+        self.0[index].clone()
+    }
+
+    /// Adds an elements to the collection
+    pub fn add(&mut self, elem: &Transaction) {
+        self.0.push(elem.clone());
+    }
+
+    /// JSON array of the transactions (compatible with Ergo Node/Explorer API)
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        let txs: Vec<&chain::transaction::Transaction> = self.0.iter().map(|tx| &tx.0).collect();
+        serde_json::to_string(&txs).map_err(|e| JsValue::from_str(&format!("{}", e)))
+    }
+}
+
+impl From<Vec<chain::transaction::Transaction>> for Transactions {
+    fn from(txs: Vec<chain::transaction::Transaction>) -> Self {
+        Transactions(txs.into_iter().map(Transaction).collect())
+    }
+}
+
+/// Collection of unsigned transactions
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct UnsignedTransactions(pub(crate) Vec<UnsignedTransaction>);
+
+#[wasm_bindgen]
+impl UnsignedTransactions {
+    /// Create empty UnsignedTransactions
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        UnsignedTransactions(vec![])
+    }
+
+    /// Returns the number of elements in the collection
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the element of the collection with a given index
+    pub fn get(&self, index: usize) -> UnsignedTransaction {
+        self.0[index].clone()
+    }
+
+    /// Adds an elements to the collection
+    pub fn add(&mut self, elem: &UnsignedTransaction) {
+        self.0.push(elem.clone());
+    }
+
+    /// Parse from JSON array of the unsigned transactions
+    /// (supports Ergo Node/Explorer API and box values and token amount encoded as strings)
+    pub fn from_json(json: &str) -> Result<UnsignedTransactions, JsValue> {
+        serde_json::from_str::<Vec<chain::transaction::unsigned::UnsignedTransaction>>(json)
+            .map(|txs| UnsignedTransactions(txs.into_iter().map(UnsignedTransaction).collect()))
+            .map_err(to_js)
+    }
+}
+
 /// Verify transaction input's proof
 #[wasm_bindgen]
 pub fn verify_tx_input_proof(
@@ -1,5 +1,9 @@
 //! Wallet-like features
+use std::collections::HashMap;
+
 use derive_more::{From, Into};
+use ergo_lib::ergotree_ir::chain::ergo_box::BoxId;
+use ergo_lib::ergotree_ir::chain::ergo_box::ErgoBox;
 use ergo_lib::ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
 use js_sys::Uint8Array;
 use wasm_bindgen::prelude::*;
@@ -19,7 +23,9 @@ use crate::{
     secret_key::{SecretKey, SecretKeys},
     transaction::reduced::ReducedTransaction,
     transaction::Transaction,
+    transaction::Transactions,
     transaction::UnsignedTransaction,
+    transaction::UnsignedTransactions,
 };
 
 /// A collection of secret keys. This simplified signing by matching the secret keys to the correct inputs automatically.
@@ -100,15 +106,75 @@ impl Wallet {
         )
         .map_err(to_js)?;
         self.0
-            .sign_transaction(
-                tx_context,
-                &_state_context.clone().into(),
-                Some(&tx_hints.0),
-            )
+            .sign_transaction_multi(tx_context, &_state_context.clone().into(), &tx_hints.0)
             .map_err(to_js)
             .map(Transaction::from)
     }
 
+    /// Sign multiple transactions in one call:
+    /// `txs` - transactions to sign
+    /// `boxes_to_spend` - boxes corresponding to the inputs of all the transactions. Outputs of
+    /// the signed transactions are spendable by the next ones, so a chain of transactions can be
+    /// signed at once.
+    /// `data_boxes` - boxes corresponding to the data inputs of all the transactions
+    #[wasm_bindgen]
+    pub fn sign_transactions(
+        &self,
+        state_context: &ErgoStateContext,
+        txs: &UnsignedTransactions,
+        boxes_to_spend: &ErgoBoxes,
+        data_boxes: &ErgoBoxes,
+    ) -> Result<Transactions, JsValue> {
+        let state_context: ergo_lib::chain::ergo_state_context::ErgoStateContext =
+            state_context.clone().into();
+        let mut boxes: HashMap<BoxId, ErgoBox> = Vec::<ErgoBox>::from(boxes_to_spend.clone())
+            .into_iter()
+            .map(|b| (b.box_id(), b))
+            .collect();
+        let data_boxes: HashMap<BoxId, ErgoBox> = Vec::<ErgoBox>::from(data_boxes.clone())
+            .into_iter()
+            .map(|b| (b.box_id(), b))
+            .collect();
+        let mut signed = Vec::with_capacity(txs.0.len());
+        for (i, tx) in txs.0.iter().enumerate() {
+            let tx = &tx.0;
+            let tx_boxes_to_spend = tx
+                .inputs
+                .iter()
+                .filter_map(|input| boxes.get(&input.box_id).cloned())
+                .collect();
+            let tx_data_boxes = tx
+                .data_inputs
+                .iter()
+                .flatten()
+                .filter_map(|d| {
+                    data_boxes
+                        .get(&d.box_id)
+                        .or_else(|| boxes.get(&d.box_id))
+                        .cloned()
+                })
+                .collect();
+            let signed_tx = ergo_lib::wallet::signing::TransactionContext::new(
+                tx.clone(),
+                tx_boxes_to_spend,
+                tx_data_boxes,
+            )
+            .map_err(|e| e.to_string())
+            .and_then(|tx_context| {
+                self.0
+                    .sign_transaction(tx_context, &state_context, None)
+                    .map_err(|e| e.to_string())
+            })
+            .map_err(|e| JsValue::from_str(&format!("Failed to sign transaction #{}: {}", i, e)))?;
+            for input in signed_tx.inputs.iter() {
+                boxes.remove(&input.box_id);
+            }
+            boxes.extend(signed_tx.outputs.iter().map(|b| (b.box_id(), b.clone())));
+            signed.push(signed_tx);
+        }
+        Ok(signed.into())
+    }
+
     /// Sign a transaction:
     /// `reduced_tx` - reduced transaction, i.e. unsigned transaction where for each unsigned input
     /// added a script reduction result.
@@ -49,6 +49,19 @@ impl ExtPubKey {
         Ok(self.0.derive(path.into()).map_err(to_js)?.into())
     }
 
+    /// Derive `count` addresses (P2PK) from the child public keys with the indices starting at
+    /// `start_index` (soft derivation), returning an array of [`Address`]
+    pub fn derive_addresses(&self, start_index: u32, count: u32) -> Result<Vec<JsValue>, JsValue> {
+        (start_index..start_index.saturating_add(count))
+            .map(|index| {
+                let index = ChildIndexNormal::normal(index).map_err(to_js)?;
+                let address: ergo_lib::ergotree_ir::chain::address::Address =
+                    self.0.child(index).into();
+                Ok(Address::from(address).into())
+            })
+            .collect()
+    }
+
     /// Create address (P2PK) from this extended public key
     pub fn to_address(&self) -> Address {
         let address: ergo_lib::ergotree_ir::chain::address::Address = self.0.clone().into();
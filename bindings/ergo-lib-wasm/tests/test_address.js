@@ -23,3 +23,21 @@ it("P2S from base16 ergo tree", async () => {
   let addr = ergo_wasm.Address.recreate_from_ergo_tree(tree);
   assert(addr != null);
 });
+
+it("derive addresses in batch", async () => {
+  const seed = ergo_wasm.Mnemonic.to_seed(
+    "change me do not use me change me do not use me",
+    ""
+  );
+  const root = ergo_wasm.ExtSecretKey.derive_master(seed);
+  const account_path = ergo_wasm.DerivationPath.from_string("m/44'/429'/0'/0");
+  const account_pub = root.derive(account_path).public_key();
+  const addresses = account_pub.derive_addresses(0, 5);
+  assert(addresses.length == 5);
+  for (let i = 0; i < 5; i++) {
+    assert(
+      addresses[i].to_base58(ergo_wasm.NetworkPrefix.Mainnet) ==
+        account_pub.child(i).to_address().to_base58(ergo_wasm.NetworkPrefix.Mainnet)
+    );
+  }
+});
@@ -33,3 +33,43 @@ it("SimpleBoxSelector test", async () => {
       unspent_boxes.get(0).box_id().to_str()
   );
 });
+
+it("calc_change for custom selection test", async () => {
+  const unspent_boxes = ergo_wasm.ErgoBoxes.from_boxes_json([
+    {
+      boxId: "e56847ed19b3dc6b72828fcfb992fdf7310828cf291221269b7ffc72fd66706e",
+      value: 67500000000,
+      ergoTree:
+        "100204a00b08cd021dde34603426402615658f1d970cfa7c7bd92ac81a8b16eeebff264d59ce4604ea02d192a39a8cc7a70173007301",
+      assets: [],
+      creationHeight: 284761,
+      additionalRegisters: {},
+      transactionId:
+        "9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9",
+      index: 1,
+    },
+  ]);
+  const target_balance = ergo_wasm.BoxValue.from_i64(
+    ergo_wasm.I64.from_str("10000000")
+  );
+  const change = ergo_wasm.calc_change(
+    unspent_boxes,
+    target_balance,
+    new ergo_wasm.Tokens()
+  );
+  assert(change.len() == 1);
+  assert(change.get(0).value().as_i64().to_str() == "67490000000");
+  const selection = ergo_wasm.BoxSelection.with_change(
+    unspent_boxes,
+    target_balance,
+    new ergo_wasm.Tokens()
+  );
+  assert(selection.change().len() == 1);
+  expect(() =>
+    ergo_wasm.calc_change(
+      unspent_boxes,
+      ergo_wasm.BoxValue.from_i64(ergo_wasm.I64.from_str("67500000001")),
+      new ergo_wasm.Tokens()
+    )
+  ).to.throw();
+});
@@ -193,6 +193,43 @@ it('use signed tx outputs as inputs in a new tx', async () => {
   assert(new_tx != null);
 });
 
+it('sign transactions in batch', async () => {
+  const sk = ergo_wasm.SecretKey.random_dlog();
+  const input_contract = ergo_wasm.Contract.pay_to_address(sk.get_address());
+  const recipient = ergo_wasm.Address.from_testnet_str('3WvsT2Gm4EpsM9Pg18PdY6XyhNNMqXDsvJTbbf6ihLvAmSb7u5RN');
+  const contract = ergo_wasm.Contract.pay_to_address(recipient);
+  const fee = ergo_wasm.TxBuilder.SUGGESTED_TX_FEE();
+  const outbox_value = ergo_wasm.BoxValue.SAFE_USER_MIN();
+  const target_balance = ergo_wasm.BoxValue.from_i64(outbox_value.as_i64().checked_add(fee.as_i64()));
+  const box_selector = new ergo_wasm.SimpleBoxSelector();
+  const all_boxes = ergo_wasm.ErgoBoxes.empty();
+  const txs = new ergo_wasm.UnsignedTransactions();
+  for (let i = 0; i < 3; i++) {
+    const input_box = new ergo_wasm.ErgoBox(ergo_wasm.BoxValue.from_i64(ergo_wasm.I64.from_str('1000000000')), 0, input_contract, ergo_wasm.TxId.zero(), i, new ergo_wasm.Tokens());
+    all_boxes.add(input_box);
+    const box_selection = box_selector.select(new ergo_wasm.ErgoBoxes(input_box), target_balance, new ergo_wasm.Tokens());
+    const outbox = new ergo_wasm.ErgoBoxCandidateBuilder(outbox_value, contract, 0).build();
+    const tx_builder = ergo_wasm.TxBuilder.new(box_selection, new ergo_wasm.ErgoBoxCandidates(outbox), 0, fee, recipient);
+    txs.add(tx_builder.build());
+  }
+  const block_headers = generate_block_headers();
+  const pre_header = ergo_wasm.PreHeader.from_block_header(block_headers.get(0));
+  const ctx = new ergo_wasm.ErgoStateContext(pre_header, block_headers);
+  const sks = new ergo_wasm.SecretKeys();
+  sks.add(sk);
+  const wallet = ergo_wasm.Wallet.from_secrets(sks);
+  const signed_txs = wallet.sign_transactions(ctx, txs, all_boxes, ergo_wasm.ErgoBoxes.empty());
+  assert(signed_txs.len() == 3);
+  for (let i = 0; i < 3; i++) {
+    assert(signed_txs.get(i).id().to_str() == txs.get(i).id().to_str());
+  }
+  const outputs = ergo_wasm.ErgoBoxes.from_boxes_json_array(JSON.stringify(JSON.parse(signed_txs.to_json())[0].outputs));
+  assert(outputs.len() == 3);
+  // box for the second tx is missing
+  const missing_boxes = new ergo_wasm.ErgoBoxes(all_boxes.get(0));
+  expect(() => wallet.sign_transactions(ctx, txs, missing_boxes, ergo_wasm.ErgoBoxes.empty())).to.throw();
+});
+
 it('Transaction::from_unsigned_tx test', async () => {
   const recipient = ergo_wasm.Address.from_testnet_str('3WvsT2Gm4EpsM9Pg18PdY6XyhNNMqXDsvJTbbf6ihLvAmSb7u5RN');
   const unspent_boxes = ergo_wasm.ErgoBoxes.from_boxes_json([
@@ -76,6 +76,39 @@ it('node REST API: example SPV workflow', async () => {
     assert(merkle_proof.valid(header.transactions_root()), "merkle_proof should be valid");
 });
 
+it('node REST API: get_last_headers and get_blocks_at_height endpoints', async () => {
+    let node_conf = new ergo_wasm.NodeConf(new URL("http://213.239.193.208:9053"));
+    let headers = await ergo_wasm.get_last_headers(node_conf, 3);
+    assert(headers.len() == 3);
+    let last_header = headers.get(2);
+    let block_ids = await ergo_wasm.get_blocks_at_height(node_conf, last_header.height());
+    assert(block_ids.length > 0);
+    assert(block_ids.some(id => id.equals(last_header.id())), "last header should be at its height");
+});
+
+it('node REST API: request cancelled with AbortController', async () => {
+    let node_conf = new ergo_wasm.NodeConf(new URL("http://213.239.193.208:9053"));
+    let controller = new AbortController();
+    controller.abort();
+    try {
+        await ergo_wasm.get_info(node_conf, controller.signal);
+        assert.fail("should be rejected");
+    } catch (e) {
+        expect(e.name).equal("AbortError");
+    }
+
+    controller = new AbortController();
+    const seeds = get_ergo_node_seeds();
+    const discovery = ergo_wasm.peer_discovery(seeds, 150, 140, true, controller.signal);
+    setTimeout(() => controller.abort(), 100);
+    try {
+        await discovery;
+        assert.fail("should be rejected");
+    } catch (e) {
+        expect(e.name).equal("AbortError");
+    }
+});
+
 async function get_nipopow_proof(url, header_id) {
     let node_conf = new ergo_wasm.NodeConf(url);
     assert(node_conf != null);
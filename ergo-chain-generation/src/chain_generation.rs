@@ -373,7 +373,7 @@ fn transactions_root(txs: &[Transaction], block_version: u8) -> Digest32 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ergo_nipopow::{NipopowAlgos, NipopowProof, PoPowHeader};
+    use ergo_nipopow::{NipopowAlgos, NipopowProof, NipopowVerifier, PoPowHeader};
 
     fn generate_popowheader_chain(len: usize, start: Option<PoPowHeader>) -> Vec<PoPowHeader> {
         block_stream(start.map(|p| ErgoFullBlock {
@@ -477,6 +477,52 @@ mod tests {
         assert!(proof.is_better_than(&disconnected_proof).unwrap());
     }
 
+    #[test]
+    fn test_nipopow_verifier_autolykos_v1() {
+        use ergo_nipopow::NipopowProofVerificationResult::*;
+        let m = 30;
+        let k = 30;
+        let popow_algos = NipopowAlgos::default();
+
+        let long_chain = generate_popowheader_chain(200, None);
+        let short_chain = long_chain[0..100].to_vec();
+        let short_proof = popow_algos.prove(&short_chain, k, m).unwrap();
+        let long_proof = popow_algos.prove(&long_chain, k, m).unwrap();
+        assert!(long_proof.is_valid());
+        assert!(long_proof.has_valid_pow().unwrap());
+
+        let mut verifier = NipopowVerifier::new(long_chain[0].header.id);
+        assert_eq!(verifier.process(short_proof.clone()).unwrap(), BetterChain);
+        assert_eq!(verifier.process(long_proof.clone()).unwrap(), BetterChain);
+        assert_eq!(verifier.process(short_proof).unwrap(), NoBetterChain);
+        assert_eq!(
+            verifier.best_chain(),
+            long_proof.headers_chain().cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(verifier.best_suffix().len(), k as usize);
+        assert_eq!(
+            verifier.best_suffix().last(),
+            long_chain.last().map(|p| &p.header)
+        );
+
+        let mut wrong_genesis_verifier = NipopowVerifier::new(long_chain[1].header.id);
+        assert_eq!(
+            wrong_genesis_verifier.process(long_proof.clone()).unwrap(),
+            WrongGenesis
+        );
+
+        let mut invalid_pow_proof = long_proof;
+        invalid_pow_proof.suffix_tail[0]
+            .autolykos_solution
+            .pow_distance = Some(BigInt::from(1_u8));
+        assert_eq!(
+            NipopowVerifier::new(long_chain[0].header.id)
+                .process(invalid_pow_proof)
+                .unwrap(),
+            ValidationError
+        );
+    }
+
     #[test]
     fn test_popow_extension_hash() {
         let size = 10;
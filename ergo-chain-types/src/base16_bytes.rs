@@ -2,6 +2,10 @@
 
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::encode_base16;
+use sigma_util::encoding::DecodingError;
+use sigma_util::encoding::DecodingMode;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 extern crate derive_more;
@@ -19,7 +23,7 @@ pub struct Base16EncodedBytes(String);
 impl Base16EncodedBytes {
     /// Create from byte array ref (&[u8])
     pub fn new<T: ?Sized + AsRef<[u8]>>(input: &T) -> Base16EncodedBytes {
-        Base16EncodedBytes(base16::encode_lower(input))
+        Base16EncodedBytes(encode_base16(input.as_ref()))
     }
 }
 
@@ -30,20 +34,23 @@ impl Base16EncodedBytes {
 pub struct Base16DecodedBytes(pub Vec<u8>);
 
 impl TryFrom<String> for Base16DecodedBytes {
-    type Error = base16::DecodeError;
+    type Error = DecodingError;
     fn try_from(str: String) -> Result<Self, Self::Error> {
-        Ok(Base16DecodedBytes(base16::decode(&str)?))
+        Ok(Base16DecodedBytes(decode_base16(
+            &str,
+            DecodingMode::Strict,
+        )?))
     }
 }
 
 impl From<Base16DecodedBytes> for String {
     fn from(b: Base16DecodedBytes) -> Self {
-        base16::encode_lower(&b.0)
+        encode_base16(&b.0)
     }
 }
 
 impl TryFrom<&str> for Base16DecodedBytes {
-    type Error = base16::DecodeError;
+    type Error = DecodingError;
     fn try_from(v: &str) -> Result<Self, Self::Error> {
         Base16DecodedBytes::try_from(v.to_string())
     }
@@ -0,0 +1,400 @@
+//! Difficulty recalculation (port of the node's `DifficultyAdjustment`)
+
+use num_bigint::BigInt;
+use num_traits::One;
+use thiserror::Error;
+
+use crate::autolykos_pow_scheme::decode_compact_bits;
+use crate::autolykos_pow_scheme::encode_compact_bits;
+use crate::Header;
+
+/// Precision of the linear least squares coefficients
+const PRECISION_CONSTANT: i64 = 1_000_000_000;
+
+/// Difficulty recalculation errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum DifficultyAdjustmentError {
+    /// Headers heights differ from the ones required for the recalculation
+    #[error("Expected headers at heights {expected:?}, got {actual:?}")]
+    UnexpectedHeaders {
+        /// Required heights (see [`DifficultyAdjustment::previous_heights_required`])
+        expected: Vec<u32>,
+        /// Heights of the provided headers
+        actual: Vec<u32>,
+    },
+    /// Epoch end timestamp is not greater than the epoch start timestamp
+    #[error("Non-increasing timestamps in the epoch ending at height {0}")]
+    NonIncreasingTimestamps(u32),
+}
+
+/// Header data used in the difficulty recalculation
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct EpochBoundary {
+    height: u32,
+    timestamp: u64,
+    difficulty: BigInt,
+}
+
+impl From<&Header> for EpochBoundary {
+    fn from(h: &Header) -> Self {
+        EpochBoundary {
+            height: h.height,
+            timestamp: h.timestamp,
+            difficulty: h.required_difficulty(),
+        }
+    }
+}
+
+/// Difficulty recalculation settings of the network.
+///
+/// The difficulty is recalculated in the first block of every epoch from the difficulties and
+/// timestamps of the last `use_last_epochs` epochs: before EIP-37 with the linear least squares
+/// prediction, since EIP-37 with the average of the limited prediction and the classic (last
+/// epoch only) recalculation. Inside the epoch the difficulty of the parent is kept.
+///
+/// See <https://github.com/ergoplatform/ergo/blob/master/src/main/scala/org/ergoplatform/mining/difficulty/DifficultyAdjustment.scala>
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct DifficultyAdjustment {
+    /// Desired interval between the blocks (ms)
+    pub desired_interval_ms: u64,
+    /// Number of the last epochs used in the recalculation
+    pub use_last_epochs: u32,
+    /// Epoch length (blocks) before EIP-37
+    pub epoch_length: u32,
+    /// Epoch length (blocks) since EIP-37
+    pub eip37_epoch_length: u32,
+    /// Height EIP-37 is activated at
+    pub eip37_activation_height: u32,
+    /// Height Autolykos v2 is activated at
+    pub v2_activation_height: u32,
+    /// Difficulty of the first blocks with Autolykos v2 (before the recalculation)
+    pub v2_activation_difficulty: BigInt,
+    /// Difficulty used when the recalculated one is less than 1
+    pub initial_difficulty: BigInt,
+}
+
+impl DifficultyAdjustment {
+    /// Mainnet settings
+    pub fn mainnet() -> Self {
+        DifficultyAdjustment {
+            desired_interval_ms: 120_000,
+            use_last_epochs: 8,
+            epoch_length: 1024,
+            eip37_epoch_length: 128,
+            eip37_activation_height: 844_673,
+            v2_activation_height: 417_792,
+            v2_activation_difficulty: BigInt::from(0x6f98_d500_0000u64),
+            initial_difficulty: BigInt::from(0x0117_6500_0000u64),
+        }
+    }
+
+    /// Epoch length for the block at `height`
+    pub fn epoch_length_at(&self, height: u32) -> u32 {
+        if height >= self.eip37_activation_height {
+            self.eip37_epoch_length
+        } else {
+            self.epoch_length
+        }
+    }
+
+    /// Heights (ascending) of the headers needed to calculate the difficulty of the block at
+    /// `height`
+    pub fn previous_heights_required(&self, height: u32) -> Vec<u32> {
+        let parent_height = height.saturating_sub(1);
+        let epoch_length = self.epoch_length_at(height);
+        if parent_height % epoch_length == 0 && epoch_length > 1 {
+            let mut heights: Vec<u32> = (0..=self.use_last_epochs)
+                .filter_map(|i| parent_height.checked_sub(i * epoch_length))
+                .collect();
+            heights.reverse();
+            heights
+        } else {
+            vec![parent_height]
+        }
+    }
+
+    /// Difficulty of the block at `height` calculated from the headers at
+    /// [`Self::previous_heights_required`] (in ascending order)
+    pub fn required_difficulty(
+        &self,
+        height: u32,
+        previous_headers: &[Header],
+    ) -> Result<BigInt, DifficultyAdjustmentError> {
+        let expected = self.previous_heights_required(height);
+        let actual: Vec<u32> = previous_headers.iter().map(|h| h.height).collect();
+        if expected != actual {
+            return Err(DifficultyAdjustmentError::UnexpectedHeaders { expected, actual });
+        }
+        let headers: Vec<EpochBoundary> = previous_headers.iter().map(Into::into).collect();
+        self.required_difficulty_for(height, &headers)
+    }
+
+    /// `nBits` of the block at `height` (see [`Self::required_difficulty`])
+    pub fn required_n_bits(
+        &self,
+        height: u32,
+        previous_headers: &[Header],
+    ) -> Result<u64, DifficultyAdjustmentError> {
+        Ok(encode_compact_bits(
+            &self.required_difficulty(height, previous_headers)?,
+        ))
+    }
+
+    fn required_difficulty_for(
+        &self,
+        height: u32,
+        headers: &[EpochBoundary],
+    ) -> Result<BigInt, DifficultyAdjustmentError> {
+        let parent_height = height.saturating_sub(1);
+        let epoch_length = self.epoch_length_at(height);
+        if parent_height == self.v2_activation_height
+            || parent_height + 1 == self.v2_activation_height
+        {
+            Ok(self.v2_activation_difficulty.clone())
+        } else if parent_height % epoch_length != 0 || headers.len() < 2 {
+            Ok(headers
+                .last()
+                .map(|h| h.difficulty.clone())
+                .unwrap_or_else(|| self.initial_difficulty.clone()))
+        } else if height >= self.eip37_activation_height {
+            self.eip37_calculate(headers, epoch_length)
+        } else {
+            self.calculate(headers, epoch_length)
+        }
+    }
+
+    /// Difficulty of the epoch ending with the `end` header to get the desired block interval
+    fn epoch_difficulty(
+        &self,
+        start: &EpochBoundary,
+        end: &EpochBoundary,
+        epoch_length: u32,
+    ) -> Result<BigInt, DifficultyAdjustmentError> {
+        if end.timestamp <= start.timestamp {
+            return Err(DifficultyAdjustmentError::NonIncreasingTimestamps(
+                end.height,
+            ));
+        }
+        Ok(&end.difficulty * self.desired_interval_ms * epoch_length
+            / (end.timestamp - start.timestamp))
+    }
+
+    /// Linear least squares prediction of the difficulty, normalized with the serialization cycle
+    /// (as the node does, so the result is the difficulty encoded in `nBits`)
+    fn calculate(
+        &self,
+        headers: &[EpochBoundary],
+        epoch_length: u32,
+    ) -> Result<BigInt, DifficultyAdjustmentError> {
+        Ok(normalize(
+            &self.calculate_uncompressed(headers, epoch_length)?,
+        ))
+    }
+
+    fn calculate_uncompressed(
+        &self,
+        headers: &[EpochBoundary],
+        epoch_length: u32,
+    ) -> Result<BigInt, DifficultyAdjustmentError> {
+        match (headers.first(), headers.last()) {
+            (Some(first), Some(last)) if headers.len() > 1 && first.timestamp < last.timestamp => {
+                let data = headers
+                    .windows(2)
+                    .map(|w| {
+                        Ok((
+                            w[1].height,
+                            self.epoch_difficulty(&w[0], &w[1], epoch_length)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, DifficultyAdjustmentError>>()?;
+                let diff = interpolate(&data, epoch_length);
+                if diff >= BigInt::one() {
+                    Ok(diff)
+                } else {
+                    Ok(self.initial_difficulty.clone())
+                }
+            }
+            (Some(first), _) => Ok(first.difficulty.clone()),
+            (None, _) => Ok(self.initial_difficulty.clone()),
+        }
+    }
+
+    /// EIP-37 recalculation: the average of the predicted difficulty and the classic (last epoch)
+    /// one, both changes limited to the range from 1/2 to 3/2 of the last difficulty
+    fn eip37_calculate(
+        &self,
+        headers: &[EpochBoundary],
+        epoch_length: u32,
+    ) -> Result<BigInt, DifficultyAdjustmentError> {
+        let (start, end) = match headers {
+            [.., start, end] => (start, end),
+            _ => return self.calculate(headers, epoch_length),
+        };
+        let last_diff = &end.difficulty;
+        let predictive_diff = limit_change(self.calculate(headers, epoch_length)?, last_diff);
+        let classic_diff = self.epoch_difficulty(start, end, epoch_length)?;
+        let uncompressed_diff = limit_change((classic_diff + predictive_diff) / 2, last_diff);
+        Ok(normalize(&uncompressed_diff))
+    }
+}
+
+/// Normalize the difficulty with the serialization cycle (drop the precision lost in `nBits`)
+fn normalize(diff: &BigInt) -> BigInt {
+    decode_compact_bits(encode_compact_bits(diff))
+}
+
+/// Limit the difficulty change to the range from 1/2 to 3/2 of the last difficulty
+fn limit_change(diff: BigInt, last_diff: &BigInt) -> BigInt {
+    if diff > *last_diff {
+        diff.min(last_diff * 3 / 2)
+    } else {
+        diff.max(last_diff / 2)
+    }
+}
+
+/// Linear least squares fit of the `(height, difficulty)` points evaluated at the end of the next
+/// epoch
+fn interpolate(data: &[(u32, BigInt)], epoch_length: u32) -> BigInt {
+    match data {
+        [] => BigInt::from(0),
+        [(_, diff)] => diff.clone(),
+        _ => {
+            let size = BigInt::from(data.len());
+            let precision = BigInt::from(PRECISION_CONSTANT);
+            let x_sum: BigInt = data.iter().map(|(x, _)| BigInt::from(*x)).sum();
+            let y_sum: BigInt = data.iter().map(|(_, y)| y.clone()).sum();
+            let xy_sum: BigInt = data.iter().map(|(x, y)| y * *x).sum();
+            let x2_sum: BigInt = data
+                .iter()
+                .map(|(x, _)| BigInt::from(*x) * BigInt::from(*x))
+                .sum();
+            let k = (&xy_sum * &size - &x_sum * &y_sum) * &precision
+                / (&x2_sum * &size - &x_sum * &x_sum);
+            let b = (&y_sum * &precision - &k * &x_sum) / &size / &precision;
+            let point = data.iter().map(|(x, _)| *x).max().unwrap_or(0) + epoch_length;
+            b + k * point / precision
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn boundary(height: u32, timestamp: u64, difficulty: u64) -> EpochBoundary {
+        EpochBoundary {
+            height,
+            timestamp,
+            difficulty: BigInt::from(difficulty),
+        }
+    }
+
+    #[test]
+    fn test_compact_bits_roundtrip() {
+        for n_bits in [0x0701_1765u64, 0x0610_0000, 0x0512_3456, 0x0600_c0de] {
+            assert_eq!(encode_compact_bits(&decode_compact_bits(n_bits)), n_bits);
+        }
+        assert_eq!(
+            encode_compact_bits(&BigInt::from(0x12_3456_0000u64)),
+            0x0512_3456
+        );
+        assert_eq!(
+            encode_compact_bits(&BigInt::from(0xc0_de00_0000u64)),
+            0x0600_c0de
+        );
+        assert_eq!(encode_compact_bits(&BigInt::from(0)), 0);
+    }
+
+    #[test]
+    fn test_previous_heights_required() {
+        let da = DifficultyAdjustment::mainnet();
+        assert_eq!(da.previous_heights_required(1000), vec![999]);
+        assert_eq!(da.previous_heights_required(1025), vec![0, 1024]);
+        assert_eq!(
+            da.previous_heights_required(10 * 1024 + 1),
+            (2..=10).map(|i| i * 1024).collect::<Vec<_>>()
+        );
+        // the last epoch before EIP-37
+        assert_eq!(
+            da.previous_heights_required(824 * 1024 + 1),
+            (816..=824).map(|i| i * 1024).collect::<Vec<_>>()
+        );
+        // EIP-37 epochs since the activation height (844_673)
+        assert_eq!(
+            da.previous_heights_required(da.eip37_activation_height),
+            (6591..=6599).map(|i| i * 128).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            da.previous_heights_required(6600 * 128 + 1),
+            (6592..=6600).map(|i| i * 128).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_calculate() {
+        let da = DifficultyAdjustment::mainnet();
+        let interval = da.desired_interval_ms;
+        // blocks at the desired interval keep the difficulty
+        let steady: Vec<EpochBoundary> = (0..=8u32)
+            .map(|i| boundary(i * 1024, i as u64 * 1024 * interval, 1_000_000))
+            .collect();
+        assert_eq!(
+            da.required_difficulty_for(8 * 1024 + 1, &steady),
+            Ok(BigInt::from(1_000_000))
+        );
+        // inside the epoch the parent difficulty is kept
+        assert_eq!(
+            da.required_difficulty_for(8 * 1024 + 2, &[boundary(8 * 1024 + 1, 0, 42)]),
+            Ok(BigInt::from(42))
+        );
+        // the result is normalized to the precision of `nBits`
+        let unnormalized: Vec<EpochBoundary> = (0..=8u32)
+            .map(|i| boundary(i * 1024, i as u64 * 1024 * interval, 1_000_000_007))
+            .collect();
+        assert_eq!(
+            da.required_difficulty_for(8 * 1024 + 1, &unnormalized),
+            Ok(BigInt::from(1_000_000_000))
+        );
+        // blocks twice faster than desired double the difficulty
+        let fast: Vec<EpochBoundary> = (0..=1u32)
+            .map(|i| boundary(i * 1024, i as u64 * 512 * interval, 1_000_000))
+            .collect();
+        assert_eq!(
+            da.required_difficulty_for(1025, &fast),
+            Ok(BigInt::from(2_000_000))
+        );
+    }
+
+    #[test]
+    fn test_eip37_calculate() {
+        let da = DifficultyAdjustment::mainnet();
+        let interval = da.desired_interval_ms;
+        let epochs = 7000u32;
+        // blocks 4 times faster, the change is limited to 3/2
+        let fast: Vec<EpochBoundary> = (epochs - 8..=epochs)
+            .map(|i| boundary(i * 128, i as u64 * 32 * interval, 1_000_000))
+            .collect();
+        let diff = da.required_difficulty_for(epochs * 128 + 1, &fast).unwrap();
+        assert_eq!(diff, decode_compact_bits(encode_compact_bits(&diff)));
+        assert_eq!(diff, BigInt::from(1_500_000));
+        // the predictive difficulty is normalized before the averaging (1_068_181_760 otherwise)
+        let mut slower: Vec<EpochBoundary> = (epochs - 8..=epochs)
+            .map(|i| boundary(i * 128, i as u64 * 128 * interval, 1_000_000_007))
+            .collect();
+        slower[8].timestamp = slower[7].timestamp + 128 * 110_000;
+        assert_eq!(
+            da.required_difficulty_for(epochs * 128 + 1, &slower),
+            Ok(BigInt::from(1_068_181_504))
+        );
+        let non_increasing = vec![
+            boundary(0, 10, 1),
+            boundary(128, 20, 1),
+            boundary(256, 20, 1),
+        ];
+        assert_eq!(
+            da.eip37_calculate(&non_increasing, 128),
+            Err(DifficultyAdjustmentError::NonIncreasingTimestamps(256))
+        );
+    }
+}
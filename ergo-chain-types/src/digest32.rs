@@ -1,14 +1,20 @@
 //! Digest types for various sizes
 
+use k256::elliptic_curve::subtle::ConstantTimeEq;
 use sigma_ser::vlq_encode::ReadSigmaVlqExt;
 use sigma_ser::vlq_encode::WriteSigmaVlqExt;
 use sigma_ser::ScorexParsingError;
 use sigma_ser::ScorexSerializable;
 use sigma_ser::ScorexSerializeResult;
+use sigma_util::encoding::decode_base16_exact;
+use sigma_util::encoding::decode_base64_exact;
+use sigma_util::encoding::encode_base16;
+use sigma_util::encoding::DecodingError;
 use sigma_util::AsVecI8;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::fmt::Formatter;
+use std::str::FromStr;
 use thiserror::Error;
 
 /// N-bytes array in a box. `Digest32` is most type synonym.
@@ -41,21 +47,27 @@ impl<const N: usize> Digest<N> {
 
     /// Parse Digest<N> from base64 encoded string
     pub fn from_base64(s: &str) -> Result<Digest<N>, DigestNError> {
-        let bytes = base64::decode(s)?;
-        let arr: [u8; N] = bytes.as_slice().try_into()?;
-        Ok(Digest(arr))
+        decode_base64_exact::<N>(s)
+            .map(Digest)
+            .map_err(DigestNError::Base64DecodingError)
+    }
+
+    /// Constant-time equality check (does not short-circuit on the first differing byte).
+    /// Use it instead of `==` when comparing digests derived from secret data.
+    pub fn ct_eq(&self, other: &Digest<N>) -> bool {
+        self.0[..].ct_eq(&other.0[..]).into()
     }
 }
 
 impl<const N: usize> std::fmt::Debug for Digest<N> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        base16::encode_lower(&(self.0)).fmt(f)
+        encode_base16(&self.0).fmt(f)
     }
 }
 
 impl<const N: usize> std::fmt::Display for Digest<N> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        base16::encode_lower(&(self.0)).fmt(f)
+        encode_base16(&self.0).fmt(f)
     }
 }
 
@@ -96,7 +108,18 @@ impl<const N: usize> From<Digest<N>> for [u8; N] {
 
 impl<const N: usize> From<Digest<N>> for String {
     fn from(v: Digest<N>) -> Self {
-        base16::encode_lower(&v.0.as_ref())
+        encode_base16(&v.0)
+    }
+}
+
+impl<const N: usize> FromStr for Digest<N> {
+    type Err = DigestNError;
+
+    /// Parse from base16-encoded string, failing if decoded bytes length is not exactly N
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        decode_base16_exact::<N>(s)
+            .map(Digest)
+            .map_err(DigestNError::Base16DecodingError)
     }
 }
 
@@ -104,9 +127,7 @@ impl<const N: usize> TryFrom<String> for Digest<N> {
     type Error = DigestNError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let bytes = base16::decode(&value)?;
-        let arr: [u8; N] = bytes.as_slice().try_into()?;
-        Ok(Digest(arr))
+        value.parse()
     }
 }
 
@@ -148,15 +169,15 @@ impl AsRef<[u8]> for Digest32 {
 /// Invalid byte array size
 #[derive(Error, Debug)]
 pub enum DigestNError {
-    /// error decoding from Base16
+    /// error decoding from Base16 (including the unexpected decoded size)
     #[error("error decoding from Base16: {0}")]
-    Base16DecodingError(#[from] base16::DecodeError),
+    Base16DecodingError(DecodingError),
     /// Invalid byte array size
     #[error("Invalid byte array size ({0})")]
     InvalidSize(#[from] std::array::TryFromSliceError),
-    /// error decoding from Base64
+    /// error decoding from Base64 (including the unexpected decoded size)
     #[error("error decoding from Base64: {0}")]
-    Base64DecodingError(#[from] base64::DecodeError),
+    Base64DecodingError(DecodingError),
 }
 
 /// Arbitrary
@@ -182,6 +203,7 @@ pub(crate) mod arbitrary {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -190,4 +212,52 @@ mod tests {
         let s = "KkctSmFOZFJnVWtYcDJzNXY4eS9CP0UoSCtNYlBlU2g=";
         assert!(Digest32::from_base64(s).is_ok());
     }
+
+    #[test]
+    fn test_from_str_display_roundtrip() {
+        let s = "9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9";
+        let d = Digest32::from_str(s).unwrap();
+        assert_eq!(d.to_string(), s);
+        assert_eq!(String::from(d), s);
+        assert!(d.ct_eq(&Digest32::try_from(s.to_string()).unwrap()));
+        assert!(!d.ct_eq(&Digest32::zero()));
+    }
+
+    #[test]
+    fn test_from_str_invalid_length() {
+        assert!(matches!(
+            Digest32::from_str("9148408c"),
+            Err(DigestNError::Base16DecodingError(
+                DecodingError::UnexpectedSize {
+                    expected: 32,
+                    actual: 4
+                }
+            ))
+        ));
+        assert!(matches!(
+            Digest32::from_str(&"00".repeat(33)),
+            Err(DigestNError::Base16DecodingError(
+                DecodingError::UnexpectedSize {
+                    expected: 32,
+                    actual: 33
+                }
+            ))
+        ));
+        assert!(matches!(
+            Digest32::from_str("zz"),
+            Err(DigestNError::Base16DecodingError(
+                DecodingError::InvalidCharacter { position: 0, .. }
+            ))
+        ));
+        assert!(matches!(
+            Digest32::from_base64("AAAA"),
+            Err(DigestNError::Base64DecodingError(
+                DecodingError::UnexpectedSize { .. }
+            ))
+        ));
+        assert!(matches!(
+            Digest32::try_from(vec![0u8; 31]),
+            Err(DigestNError::InvalidSize(_))
+        ));
+    }
 }
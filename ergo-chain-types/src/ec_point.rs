@@ -6,6 +6,7 @@ use k256::elliptic_curve::sec1::ToEncodedPoint;
 use k256::{ProjectivePoint, PublicKey, Scalar};
 use sigma_ser::vlq_encode::{ReadSigmaVlqExt, WriteSigmaVlqExt};
 use sigma_ser::{ScorexParsingError, ScorexSerializable, ScorexSerializeResult};
+use sigma_util::encoding::{decode_base16, DecodingMode};
 use std::convert::TryFrom;
 use std::ops::{Add, Mul, Neg};
 
@@ -43,7 +44,7 @@ impl EcPoint {
 
     /// Attempts to parse from Base16-encoded string
     pub fn from_base16_str(str: String) -> Option<Self> {
-        base16::decode(&str)
+        decode_base16(&str, DecodingMode::Strict)
             .ok()
             .and_then(|bytes| Self::scorex_parse_bytes(&bytes).ok())
     }
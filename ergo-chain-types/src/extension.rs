@@ -0,0 +1,353 @@
+//! Block extension section
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+
+use sigma_ser::vlq_encode::ReadSigmaVlqExt;
+use sigma_ser::vlq_encode::WriteSigmaVlqExt;
+use sigma_ser::ScorexParsingError;
+use sigma_ser::ScorexSerializable;
+use sigma_ser::ScorexSerializeResult;
+use thiserror::Error;
+
+use crate::BlockId;
+use crate::Digest32;
+use crate::ExtensionCandidate;
+
+/// Key prefix of the system parameters fields
+pub const SYSTEM_PARAMETERS_PREFIX: u8 = 0x00;
+/// Key prefix of the interlinks vector fields
+pub const INTERLINK_VECTOR_PREFIX: u8 = 0x01;
+/// Key prefix of the validation rules fields
+pub const VALIDATION_RULES_PREFIX: u8 = 0x02;
+/// Maximum size of the field value
+pub const FIELD_VALUE_MAX_SIZE: usize = 64;
+
+/// Extension section errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum ExtensionError {
+    /// Field value is too big
+    #[error("Field {key:?} value size {size} exceeds {FIELD_VALUE_MAX_SIZE} bytes")]
+    FieldValueTooBig {
+        /// Field key
+        key: [u8; 2],
+        /// Field value size
+        size: usize,
+    },
+    /// Packed interlink is expected to be 33 bytes long (`[qty | block_id]`)
+    #[error("Interlink field {key:?} has invalid size {size} (expected 33 bytes)")]
+    InvalidInterlinkSize {
+        /// Field key
+        key: [u8; 2],
+        /// Field value size
+        size: usize,
+    },
+    /// System parameter value is expected to be 4 bytes long (big-endian Int)
+    #[error("System parameter {id} has invalid size {size} (expected 4 bytes)")]
+    InvalidParameterSize {
+        /// Parameter id
+        id: u8,
+        /// Field value size
+        size: usize,
+    },
+}
+
+/// Extension section of Ergo block (key-value storage) for the block with `header_id`
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "json",
+    serde(
+        into = "crate::json::extension::ExtensionJson",
+        try_from = "crate::json::extension::ExtensionJson"
+    )
+)]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Extension {
+    /// Id of the block this extension belongs to
+    pub header_id: BlockId,
+    /// Fields as a sequence of key -> value records. A key is 2-bytes long, value is 64 bytes max.
+    pub(crate) fields: Vec<([u8; 2], Vec<u8>)>,
+}
+
+impl Extension {
+    /// Creates a new [`Extension`]. Fails if a field has a value > 64 bytes
+    pub fn new(
+        header_id: BlockId,
+        fields: Vec<([u8; 2], Vec<u8>)>,
+    ) -> Result<Extension, ExtensionError> {
+        check_fields(&fields)?;
+        Ok(Extension { header_id, fields })
+    }
+
+    /// Extension of the given block without any fields
+    pub fn empty(header_id: BlockId) -> Extension {
+        Extension {
+            header_id,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Fields of this extension
+    pub fn fields(&self) -> &[([u8; 2], Vec<u8>)] {
+        &self.fields
+    }
+
+    /// Value of the field with the given key
+    pub fn field(&self, key: [u8; 2]) -> Option<&[u8]> {
+        find_field(&self.fields, key)
+    }
+
+    /// Interlinks vector unpacked from the [`INTERLINK_VECTOR_PREFIX`] fields
+    pub fn interlinks(&self) -> Result<Vec<BlockId>, ExtensionError> {
+        unpack_interlinks(&self.fields)
+    }
+
+    /// System parameters (id -> value) from the [`SYSTEM_PARAMETERS_PREFIX`] fields
+    pub fn parameters(&self) -> Result<BTreeMap<u8, i32>, ExtensionError> {
+        parse_parameters(&self.fields)
+    }
+}
+
+impl From<Extension> for ExtensionCandidate {
+    fn from(ext: Extension) -> Self {
+        ExtensionCandidate { fields: ext.fields }
+    }
+}
+
+impl ExtensionCandidate {
+    /// Value of the field with the given key
+    pub fn field(&self, key: [u8; 2]) -> Option<&[u8]> {
+        find_field(&self.fields, key)
+    }
+
+    /// Interlinks vector unpacked from the [`INTERLINK_VECTOR_PREFIX`] fields
+    pub fn interlinks(&self) -> Result<Vec<BlockId>, ExtensionError> {
+        unpack_interlinks(&self.fields)
+    }
+
+    /// System parameters (id -> value) from the [`SYSTEM_PARAMETERS_PREFIX`] fields
+    pub fn parameters(&self) -> Result<BTreeMap<u8, i32>, ExtensionError> {
+        parse_parameters(&self.fields)
+    }
+
+    /// Extension of the block with `header_id` made of this candidate's fields
+    pub fn into_extension(self, header_id: BlockId) -> Extension {
+        Extension {
+            header_id,
+            fields: self.fields,
+        }
+    }
+}
+
+impl ScorexSerializable for Extension {
+    fn scorex_serialize<W: WriteSigmaVlqExt>(&self, w: &mut W) -> ScorexSerializeResult {
+        self.header_id.0.scorex_serialize(w)?;
+        w.put_u16(self.fields.len().try_into()?)?;
+        for (key, value) in &self.fields {
+            w.write_all(key)?;
+            w.put_u8(value.len().try_into()?)?;
+            w.write_all(value)?;
+        }
+        Ok(())
+    }
+
+    fn scorex_parse<R: ReadSigmaVlqExt>(r: &mut R) -> Result<Self, ScorexParsingError> {
+        let header_id = BlockId(Digest32::scorex_parse(r)?);
+        let fields_count = r.get_u16()?;
+        let mut fields = Vec::with_capacity(fields_count as usize);
+        for _ in 0..fields_count {
+            let mut key = [0u8; 2];
+            r.read_exact(&mut key)?;
+            let value_size = r.get_u8()?;
+            let mut value = vec![0u8; value_size as usize];
+            r.read_exact(&mut value)?;
+            fields.push((key, value));
+        }
+        Extension::new(header_id, fields)
+            .map_err(|e| ScorexParsingError::ValueOutOfBounds(e.to_string()))
+    }
+}
+
+/// Packs interlinks into key-value format of the block extension. Consecutive duplicate ids are
+/// packed into a single `[qty | block_id]` field.
+pub fn pack_interlinks(interlinks: &[BlockId]) -> Vec<([u8; 2], Vec<u8>)> {
+    let mut runs: Vec<(BlockId, u8)> = Vec::new();
+    for id in interlinks {
+        match runs.last_mut() {
+            Some((last_id, qty)) if last_id == id && *qty < u8::MAX => *qty += 1,
+            _ => runs.push((*id, 1)),
+        }
+    }
+    runs.into_iter()
+        .enumerate()
+        .map(|(idx, (id, qty))| {
+            let value = std::iter::once(qty).chain(id.0 .0).collect();
+            ([INTERLINK_VECTOR_PREFIX, idx as u8], value)
+        })
+        .collect()
+}
+
+fn check_fields(fields: &[([u8; 2], Vec<u8>)]) -> Result<(), ExtensionError> {
+    match fields
+        .iter()
+        .find(|(_, value)| value.len() > FIELD_VALUE_MAX_SIZE)
+    {
+        Some((key, value)) => Err(ExtensionError::FieldValueTooBig {
+            key: *key,
+            size: value.len(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn find_field(fields: &[([u8; 2], Vec<u8>)], key: [u8; 2]) -> Option<&[u8]> {
+    fields
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.as_slice())
+}
+
+fn unpack_interlinks(fields: &[([u8; 2], Vec<u8>)]) -> Result<Vec<BlockId>, ExtensionError> {
+    let mut res = vec![];
+    for (key, value) in fields
+        .iter()
+        .filter(|(key, _)| key[0] == INTERLINK_VECTOR_PREFIX)
+    {
+        let block_id_bytes: [u8; 32] = value
+            .get(1..)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(ExtensionError::InvalidInterlinkSize {
+                key: *key,
+                size: value.len(),
+            })?;
+        let block_id = BlockId(Digest32::from(block_id_bytes));
+        res.extend(std::iter::repeat(block_id).take(value[0] as usize));
+    }
+    Ok(res)
+}
+
+fn parse_parameters(fields: &[([u8; 2], Vec<u8>)]) -> Result<BTreeMap<u8, i32>, ExtensionError> {
+    fields
+        .iter()
+        .filter(|(key, _)| key[0] == SYSTEM_PARAMETERS_PREFIX)
+        .map(|(key, value)| {
+            let bytes: [u8; 4] =
+                value
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| ExtensionError::InvalidParameterSize {
+                        id: key[1],
+                        size: value.len(),
+                    })?;
+            Ok((key[1], i32::from_be_bytes(bytes)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use sigma_ser::scorex_serialize_roundtrip;
+
+    fn block_id(b: u8) -> BlockId {
+        BlockId(Digest32::from([b; 32]))
+    }
+
+    #[test]
+    fn test_interlinks_roundtrip() {
+        let interlinks = vec![block_id(0), block_id(1), block_id(1), block_id(2)];
+        let fields = pack_interlinks(&interlinks);
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields[1].0, [INTERLINK_VECTOR_PREFIX, 1]);
+        assert_eq!(fields[1].1[0], 2);
+        let ext = Extension::new(block_id(9), fields).unwrap();
+        assert_eq!(ext.interlinks().unwrap(), interlinks);
+        assert!(pack_interlinks(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_interlink() {
+        let ext = Extension::new(
+            block_id(0),
+            vec![([INTERLINK_VECTOR_PREFIX, 0], vec![1; 3])],
+        )
+        .unwrap();
+        assert_eq!(
+            ext.interlinks(),
+            Err(ExtensionError::InvalidInterlinkSize {
+                key: [INTERLINK_VECTOR_PREFIX, 0],
+                size: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_parameters() {
+        let fields = vec![
+            (
+                [SYSTEM_PARAMETERS_PREFIX, 1],
+                1_250_000i32.to_be_bytes().to_vec(),
+            ),
+            ([SYSTEM_PARAMETERS_PREFIX, 123], 2i32.to_be_bytes().to_vec()),
+            ([INTERLINK_VECTOR_PREFIX, 0], vec![1; 33]),
+        ];
+        let ext = Extension::new(block_id(0), fields).unwrap();
+        let params = ext.parameters().unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params.get(&1), Some(&1_250_000));
+        assert_eq!(params.get(&123), Some(&2));
+        assert_eq!(
+            ext.field([SYSTEM_PARAMETERS_PREFIX, 123]),
+            Some(&[0u8, 0, 0, 2][..])
+        );
+    }
+
+    #[test]
+    fn test_field_value_too_big() {
+        assert_eq!(
+            Extension::new(block_id(0), vec![([0, 1], vec![0; 65])]),
+            Err(ExtensionError::FieldValueTooBig {
+                key: [0, 1],
+                size: 65
+            })
+        );
+    }
+
+    #[test]
+    fn test_ser_roundtrip() {
+        let ext = Extension::new(
+            block_id(3),
+            pack_interlinks(&[block_id(0), block_id(1), block_id(1)]),
+        )
+        .unwrap();
+        assert_eq!(scorex_serialize_roundtrip(&ext), ext);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_parse_node_json() {
+        // Taken from the node for block 96911575efdceb082b974aa3042263be07632de48031aa2204d77d8d5a8240b8
+        let json = r#"{
+            "headerId": "96911575efdceb082b974aa3042263be07632de48031aa2204d77d8d5a8240b8",
+            "digest": "badffc4d646e1c2babcf1ce8422b4f2430b6262c947c964671e97486d8bdb601",
+            "fields": [
+                ["0100", "01b0244dfc267baca974a4caee06120321562784303a8a688976ae56170e4d175b"],
+                ["0101", "01557fd0590616b4f6e51eaf54436d61e5585eebfc5a9e860861fc0876064bd3d9"],
+                ["0102", "03296e2707cf72b6a2c71e4966028d8786c7f5425850e9609757ce8b3713f548fe"],
+                ["0105", "027ddba9db07cce855cd911c9bee9376be9e16cedf66eeed2175072816c5678cdb"]
+            ]
+        }"#;
+        let ext: Extension = serde_json::from_str(json).unwrap();
+        assert_eq!(ext.fields().len(), 4);
+        let interlinks = ext.interlinks().unwrap();
+        assert_eq!(interlinks.len(), 1 + 1 + 3 + 2);
+        assert_eq!(
+            String::from(interlinks[0].0),
+            "b0244dfc267baca974a4caee06120321562784303a8a688976ae56170e4d175b"
+        );
+        assert!(ext.parameters().unwrap().is_empty());
+        let encoded = serde_json::to_string(&ext).unwrap();
+        assert_eq!(serde_json::from_str::<Extension>(&encoded).unwrap(), ext);
+    }
+}
@@ -8,6 +8,9 @@ use sigma_ser::{
 use sigma_util::hash::blake2b256_hash;
 use std::io::Write;
 
+use crate::autolykos_pow_scheme::{
+    decode_compact_bits, AutolykosPowScheme, AutolykosPowSchemeError,
+};
 use crate::votes::Votes;
 
 /// Represents data of the block header available in Sigma propositions.
@@ -54,8 +57,51 @@ pub struct Header {
 }
 
 impl Header {
+    /// Difficulty decoded from the compact `n_bits` representation
+    pub fn required_difficulty(&self) -> BigInt {
+        decode_compact_bits(self.n_bits)
+    }
+
+    /// Verify the Autolykos PoW solution of this header (v1 or v2 depending on the block version)
+    /// against the difficulty encoded in `n_bits`
+    pub fn check_pow(&self) -> Result<bool, AutolykosPowSchemeError> {
+        AutolykosPowScheme::default().check_pow(self)
+    }
+
     /// Used in nipowpow
     pub fn serialize_without_pow(&self) -> Result<Vec<u8>, ScorexSerializationError> {
+        HeaderWithoutPow::from(self).bytes()
+    }
+}
+
+/// Header fields except the PoW solution (the block candidate header for the miners)
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct HeaderWithoutPow {
+    /// Block version, to be increased on every soft and hardfork.
+    pub version: u8,
+    /// Id of parent block
+    pub parent_id: BlockId,
+    /// Hash of ADProofs for transactions in a block
+    pub ad_proofs_root: Digest32,
+    /// AvlTree of a state after block application
+    pub state_root: ADDigest,
+    /// Root hash (for a Merkle tree) of transactions in a block.
+    pub transaction_root: Digest32,
+    /// Timestamp of a block in ms from UNIX epoch
+    pub timestamp: u64,
+    /// Current difficulty in a compressed view.
+    pub n_bits: u64,
+    /// Block height
+    pub height: u32,
+    /// Root hash of extension section
+    pub extension_root: Digest32,
+    /// Votes
+    pub votes: Votes,
+}
+
+impl HeaderWithoutPow {
+    /// Serialized header fields (as hashed into the message to solve)
+    pub fn bytes(&self) -> Result<Vec<u8>, ScorexSerializationError> {
         use byteorder::{BigEndian, WriteBytesExt};
         let mut data = Vec::new();
         let mut w = &mut data;
@@ -86,6 +132,28 @@ impl Header {
         }
         Ok(data)
     }
+
+    /// Message the miner has to solve the PoW puzzle for (hash of the header bytes)
+    pub fn msg(&self) -> Result<Digest32, ScorexSerializationError> {
+        Ok(Digest32::from(blake2b256_hash(&self.bytes()?)))
+    }
+}
+
+impl From<&Header> for HeaderWithoutPow {
+    fn from(h: &Header) -> Self {
+        HeaderWithoutPow {
+            version: h.version,
+            parent_id: h.parent_id,
+            ad_proofs_root: h.ad_proofs_root,
+            state_root: h.state_root,
+            transaction_root: h.transaction_root,
+            timestamp: h.timestamp,
+            n_bits: h.n_bits,
+            height: h.height,
+            extension_root: h.extension_root,
+            votes: h.votes.clone(),
+        }
+    }
 }
 
 impl ScorexSerializable for Header {
@@ -133,35 +201,7 @@ impl ScorexSerializable for Header {
         }
 
         // Parse `AutolykosSolution`
-        let autolykos_solution = if version == 1 {
-            let miner_pk = EcPoint::scorex_parse(r)?.into();
-            let pow_onetime_pk = Some(EcPoint::scorex_parse(r)?.into());
-            let mut nonce: Vec<u8> = std::iter::repeat(0).take(8).collect();
-            r.read_exact(&mut nonce)?;
-            let d_bytes_len = r.get_u8()?;
-            let mut d_bytes: Vec<u8> = std::iter::repeat(0).take(d_bytes_len as usize).collect();
-            r.read_exact(&mut d_bytes)?;
-            let pow_distance = Some(BigInt::from_signed_bytes_be(&d_bytes));
-            AutolykosSolution {
-                miner_pk,
-                pow_onetime_pk,
-                nonce,
-                pow_distance,
-            }
-        } else {
-            // autolykos v2
-            let pow_onetime_pk = None;
-            let pow_distance = None;
-            let miner_pk = EcPoint::scorex_parse(r)?.into();
-            let mut nonce: Vec<u8> = std::iter::repeat(0).take(8).collect();
-            r.read_exact(&mut nonce)?;
-            AutolykosSolution {
-                miner_pk,
-                pow_onetime_pk,
-                nonce,
-                pow_distance,
-            }
-        };
+        let autolykos_solution = AutolykosSolution::parse_bytes(version, r)?;
 
         // The `Header.id` field isn't serialized/deserialized but rather computed as a hash of
         // every other field in `Header`. First we initialize header with dummy id field then
@@ -265,11 +305,48 @@ impl AutolykosSolution {
         }
         Ok(())
     }
+
+    /// Parse instance serialized for the given block version
+    pub fn parse_bytes<R: ReadSigmaVlqExt>(
+        version: u8,
+        r: &mut R,
+    ) -> Result<Self, ScorexParsingError> {
+        if version == 1 {
+            let miner_pk = EcPoint::scorex_parse(r)?.into();
+            let pow_onetime_pk = Some(EcPoint::scorex_parse(r)?.into());
+            let mut nonce: Vec<u8> = std::iter::repeat(0).take(8).collect();
+            r.read_exact(&mut nonce)?;
+            let d_bytes_len = r.get_u8()?;
+            let mut d_bytes: Vec<u8> = std::iter::repeat(0).take(d_bytes_len as usize).collect();
+            r.read_exact(&mut d_bytes)?;
+            let pow_distance = Some(BigInt::from_signed_bytes_be(&d_bytes));
+            Ok(AutolykosSolution {
+                miner_pk,
+                pow_onetime_pk,
+                nonce,
+                pow_distance,
+            })
+        } else {
+            // autolykos v2
+            let miner_pk = EcPoint::scorex_parse(r)?.into();
+            let mut nonce: Vec<u8> = std::iter::repeat(0).take(8).collect();
+            r.read_exact(&mut nonce)?;
+            Ok(AutolykosSolution {
+                miner_pk,
+                pow_onetime_pk: None,
+                nonce,
+                pow_distance: None,
+            })
+        }
+    }
 }
 
+/// Arbitrary
 #[cfg(feature = "arbitrary")]
 #[allow(clippy::unwrap_used)]
-mod arbitrary {
+pub mod arbitrary {
+
+    use std::ops::Range;
 
     use crate::*;
     use num_bigint::BigInt;
@@ -278,9 +355,74 @@ mod arbitrary {
 
     use super::{AutolykosSolution, BlockId, Header, Votes};
 
+    /// Average time between the blocks (ms)
+    const BLOCK_INTERVAL_MS: u64 = 120_000;
+
+    /// Parameters for the arbitrary [`Header`]
+    #[derive(Debug, Clone)]
+    pub struct ArbHeaderParams {
+        /// Block heights
+        pub height: Range<u32>,
+        /// Block versions to choose from
+        pub versions: Vec<u8>,
+    }
+
+    impl Default for ArbHeaderParams {
+        fn default() -> Self {
+            ArbHeaderParams {
+                height: 1_000_000..10_000_000,
+                versions: vec![1, 2],
+            }
+        }
+    }
+
+    /// Chain of `len` headers of the same version linked by the parent ids, with consecutive
+    /// heights (starting in `params.height`) and timestamps, the newest header first (as the last
+    /// headers in the context)
+    pub fn arb_header_chain(len: usize, params: ArbHeaderParams) -> BoxedStrategy<Vec<Header>> {
+        prop::sample::select(params.versions.clone())
+            .prop_flat_map(move |version| {
+                let params = ArbHeaderParams {
+                    versions: vec![version],
+                    ..params.clone()
+                };
+                prop::collection::vec(any_with::<Header>(params), len)
+            })
+            .prop_map(|mut headers| {
+                for i in 1..headers.len() {
+                    let (parent_id, height, timestamp) = {
+                        let parent = &headers[i - 1];
+                        (parent.id, parent.height, parent.timestamp)
+                    };
+                    let header = &mut headers[i];
+                    header.parent_id = parent_id;
+                    header.height = height + 1;
+                    header.timestamp = timestamp + BLOCK_INTERVAL_MS;
+                    header.id = header_id(header);
+                }
+                headers.reverse();
+                headers
+            })
+            .boxed()
+    }
+
+    /// The `Header.id` field isn't serialized/deserialized but rather computed as a hash of every
+    /// other field in `Header`
+    fn header_id(header: &Header) -> BlockId {
+        let mut id_bytes = header.serialize_without_pow().unwrap();
+        let mut data = Vec::new();
+        let mut w = &mut data;
+        header
+            .autolykos_solution
+            .serialize_bytes(header.version, &mut w)
+            .unwrap();
+        id_bytes.extend(data);
+        BlockId(blake2b256_hash(&id_bytes))
+    }
+
     impl Arbitrary for Header {
-        type Parameters = ();
-        fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        type Parameters = ArbHeaderParams;
+        fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
             (
                 uniform32(1u8..),
                 uniform32(1u8..),
@@ -289,8 +431,8 @@ mod arbitrary {
                 // Timestamps between 2000-2050
                 946_674_000_000..2_500_400_300_000u64,
                 any::<u32>(), // Note: n_bits must fit in u32
-                1_000_000u32..10_000_000u32,
-                prop::sample::select(vec![1_u8, 2]),
+                args.height,
+                prop::sample::select(args.versions),
                 any::<Box<AutolykosSolution>>(),
                 uniform3(1u8..),
             )
@@ -313,9 +455,7 @@ mod arbitrary {
                         let extension_root = Digest(extension_root);
                         let votes = Votes(votes);
 
-                        // The `Header.id` field isn't serialized/deserialized but rather computed
-                        // as a hash of every other field in `Header`. First we initialize header
-                        // with dummy id field then compute the hash.
+                        // First we initialize header with dummy id field then compute the hash.
                         let mut header = Self {
                             version,
                             id: BlockId(Digest32::zero()),
@@ -327,16 +467,10 @@ mod arbitrary {
                             n_bits: n_bits as u64,
                             height,
                             extension_root,
-                            autolykos_solution: *autolykos_solution.clone(),
+                            autolykos_solution: *autolykos_solution,
                             votes,
                         };
-                        let mut id_bytes = header.serialize_without_pow().unwrap();
-                        let mut data = Vec::new();
-                        let mut w = &mut data;
-                        autolykos_solution.serialize_bytes(version, &mut w).unwrap();
-                        id_bytes.extend(data);
-                        let id = BlockId(blake2b256_hash(&id_bytes));
-                        header.id = id;
+                        header.id = header_id(&header);
 
                         // Manually set the following parameters to `None` for autolykos v2. This is
                         // allowable since serialization/deserialization of the `Header` ignores
@@ -351,7 +485,6 @@ mod arbitrary {
                 )
                 .boxed()
         }
-
         type Strategy = BoxedStrategy<Header>;
     }
 
@@ -398,6 +531,15 @@ mod tests {
         fn ser_roundtrip(v in any::<Header>()) {
             assert_eq![scorex_serialize_roundtrip(&v), v]
         }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn json_roundtrip(v in any::<Header>()) {
+            let json = serde_json::to_string(&v).unwrap();
+            let parsed: Header = serde_json::from_str(&json).unwrap();
+            prop_assert_eq![serde_json::to_string(&parsed).unwrap(), json];
+            prop_assert_eq![parsed, v];
+        }
     }
 
     #[test]
@@ -435,6 +577,9 @@ mod tests {
             )
             .unwrap())
         );
+        let reparsed: Header =
+            serde_json::from_str(&serde_json::to_string(&header).unwrap()).unwrap();
+        assert_eq!(reparsed, header);
     }
 
     #[test]
@@ -1,4 +1,5 @@
 //! JSON serialization
 
 pub(crate) mod autolykos_solution;
+pub(crate) mod extension;
 pub(crate) mod votes;
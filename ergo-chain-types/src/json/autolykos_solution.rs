@@ -5,6 +5,7 @@ use std::str::FromStr;
 use num_bigint::BigInt;
 use num_traits::FromPrimitive;
 use serde::{Deserialize, Deserializer};
+use sigma_util::encoding::{decode_base16, DecodingMode};
 
 pub(crate) fn as_base16_string<S>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -18,8 +19,9 @@ where
     D: Deserializer<'de>,
 {
     use serde::de::Error;
-    String::deserialize(deserializer)
-        .and_then(|string| base16::decode(&string).map_err(|err| Error::custom(err.to_string())))
+    String::deserialize(deserializer).and_then(|string| {
+        decode_base16(&string, DecodingMode::Strict).map_err(|err| Error::custom(err.to_string()))
+    })
 }
 
 /// Serialize `BigInt` as a string
@@ -48,6 +50,7 @@ where
 
     match DeserializeBigIntFrom::deserialize(deserializer) {
         Ok(s) => match s {
+            DeserializeBigIntFrom::Null => Ok(None),
             DeserializeBigIntFrom::String(s) => BigInt::from_str(&s)
                 .map(Some)
                 .map_err(|e| Error::custom(e.to_string())),
@@ -74,6 +77,8 @@ where
 #[derive(Deserialize)]
 #[serde(untagged)]
 enum DeserializeBigIntFrom {
+    /// `pow_distance` is not set (serialized as `null`)
+    Null,
     String(String),
     SerdeJsonNumber(serde_json::Number),
 }
@@ -0,0 +1,45 @@
+//! Code to implement `Extension` JSON encoding
+
+use std::convert::TryFrom;
+
+use crate::extension::Extension;
+use crate::{Base16DecodedBytes, BlockId};
+
+/// Extension JSON as returned by the node (`digest` is ignored on parsing)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ExtensionJson {
+    #[serde(rename = "headerId")]
+    header_id: BlockId,
+    fields: Vec<(Base16DecodedBytes, Base16DecodedBytes)>,
+}
+
+impl TryFrom<ExtensionJson> for Extension {
+    type Error = String;
+
+    fn try_from(json: ExtensionJson) -> Result<Self, Self::Error> {
+        let fields =
+            json.fields
+                .into_iter()
+                .map(|(key, value)| {
+                    let key: [u8; 2] = key.0.as_slice().try_into().map_err(|_| {
+                        format!("Invalid extension field key size: {}", key.0.len())
+                    })?;
+                    Ok((key, value.0))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+        Extension::new(json.header_id, fields).map_err(|e| e.to_string())
+    }
+}
+
+impl From<Extension> for ExtensionJson {
+    fn from(ext: Extension) -> Self {
+        ExtensionJson {
+            header_id: ext.header_id,
+            fields: ext
+                .fields
+                .into_iter()
+                .map(|(key, value)| (Base16DecodedBytes(key.to_vec()), Base16DecodedBytes(value)))
+                .collect(),
+        }
+    }
+}
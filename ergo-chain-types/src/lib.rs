@@ -18,13 +18,17 @@
 #![deny(clippy::unreachable)]
 #![deny(clippy::panic)]
 
+pub mod autolykos_pow_scheme;
 mod base16_bytes;
 mod block_id;
+pub mod difficulty_adjustment;
 mod digest32;
 pub mod ec_point;
+pub mod extension;
 mod extensioncandidate;
 mod header;
 mod json;
+pub mod parameters;
 mod peer_addr;
 mod peer_connection_dir;
 mod preheader;
@@ -33,14 +37,19 @@ mod votes;
 pub use base16_bytes::Base16DecodedBytes;
 pub use base16_bytes::Base16EncodedBytes;
 pub use block_id::BlockId;
+pub use difficulty_adjustment::DifficultyAdjustment;
 pub use digest32::blake2b256_hash;
 pub use digest32::ADDigest;
 pub use digest32::Digest;
 pub use digest32::Digest32;
 pub use digest32::DigestNError;
 pub use ec_point::EcPoint;
+pub use extension::Extension;
 pub use extensioncandidate::ExtensionCandidate;
-pub use header::{AutolykosSolution, Header};
+#[cfg(feature = "arbitrary")]
+pub use header::arbitrary::{arb_header_chain, ArbHeaderParams};
+pub use header::{AutolykosSolution, Header, HeaderWithoutPow};
+pub use parameters::Parameters;
 pub use peer_addr::PeerAddr;
 pub use peer_connection_dir::ConnectionDirection;
 pub use preheader::PreHeader;
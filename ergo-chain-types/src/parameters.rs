@@ -0,0 +1,444 @@
+//! System parameters of the blockchain (changed by the miners voting)
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use thiserror::Error;
+
+use crate::extension::ExtensionError;
+use crate::Extension;
+use crate::Votes;
+
+/// Default storage fee factor (nanoERGs per byte per storage period)
+pub const STORAGE_FEE_FACTOR_DEFAULT: u32 = 1_250_000;
+/// Default minimum box value per byte (nanoERGs)
+pub const MIN_VALUE_PER_BYTE_DEFAULT: u32 = 30 * 12;
+/// Default maximum block size (bytes)
+pub const MAX_BLOCK_SIZE_DEFAULT: u32 = 512 * 1024;
+/// Default maximum cost of all transactions in a block
+pub const MAX_BLOCK_COST_DEFAULT: u32 = 1_000_000;
+/// Default cost of the token access
+pub const TOKEN_ACCESS_COST_DEFAULT: u32 = 100;
+/// Default cost of the transaction input
+pub const INPUT_COST_DEFAULT: u32 = 2000;
+/// Default cost of the transaction data input
+pub const DATA_INPUT_COST_DEFAULT: u32 = 100;
+/// Default cost of the transaction output
+pub const OUTPUT_COST_DEFAULT: u32 = 100;
+/// Initial block version
+pub const BLOCK_VERSION_DEFAULT: u32 = 1;
+
+/// System parameter (the id is the key of the parameter field in the block extension and the
+/// vote for increasing its value)
+#[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Parameter {
+    /// Storage fee factor (nanoERGs per byte per storage period)
+    StorageFeeFactor = 1,
+    /// Minimum box value per byte (nanoERGs)
+    MinValuePerByte = 2,
+    /// Maximum block size (bytes)
+    MaxBlockSize = 3,
+    /// Maximum cost of all transactions in a block
+    MaxBlockCost = 4,
+    /// Cost of the token access
+    TokenAccessCost = 5,
+    /// Cost of the transaction input
+    InputCost = 6,
+    /// Cost of the transaction data input
+    DataInputCost = 7,
+    /// Cost of the transaction output
+    OutputCost = 8,
+    /// Number of the votes collected for the soft-fork
+    SoftForkVotesCollected = 121,
+    /// Height the soft-fork voting started at
+    SoftForkStartingHeight = 122,
+    /// Block version
+    BlockVersion = 123,
+}
+
+impl Parameter {
+    /// Id of the vote for the soft-fork
+    pub const SOFT_FORK_VOTE_ID: u8 = 120;
+
+    /// All parameters
+    pub const ALL: [Parameter; 11] = [
+        Parameter::StorageFeeFactor,
+        Parameter::MinValuePerByte,
+        Parameter::MaxBlockSize,
+        Parameter::MaxBlockCost,
+        Parameter::TokenAccessCost,
+        Parameter::InputCost,
+        Parameter::DataInputCost,
+        Parameter::OutputCost,
+        Parameter::SoftForkVotesCollected,
+        Parameter::SoftForkStartingHeight,
+        Parameter::BlockVersion,
+    ];
+
+    /// Parameter id
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for Parameter {
+    type Error = u8;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        Parameter::ALL
+            .iter()
+            .copied()
+            .find(|p| p.id() == id)
+            .ok_or(id)
+    }
+}
+
+/// Miner's vote (one of the three in the block header [`Votes`])
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ParameterVote {
+    /// Increase the parameter value
+    Increase(Parameter),
+    /// Decrease the parameter value
+    Decrease(Parameter),
+    /// Vote for the soft-fork (protocol update)
+    SoftFork,
+    /// Vote id not known to this library
+    Unknown(i8),
+}
+
+impl From<i8> for ParameterVote {
+    fn from(id: i8) -> Self {
+        if id as u8 == Parameter::SOFT_FORK_VOTE_ID {
+            return ParameterVote::SoftFork;
+        }
+        match Parameter::try_from(id.unsigned_abs()) {
+            Ok(p) if id > 0 => ParameterVote::Increase(p),
+            Ok(p) => ParameterVote::Decrease(p),
+            Err(_) => ParameterVote::Unknown(id),
+        }
+    }
+}
+
+impl Votes {
+    /// Parsed non-empty votes of the miner
+    pub fn parsed_votes(&self) -> Vec<ParameterVote> {
+        self.parameter_votes()
+            .into_iter()
+            .map(ParameterVote::from)
+            .collect()
+    }
+
+    /// Returns true if the miner voted for the soft-fork
+    pub fn is_soft_fork_vote(&self) -> bool {
+        self.parsed_votes().contains(&ParameterVote::SoftFork)
+    }
+}
+
+/// Errors on parsing the system parameters
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum ParametersError {
+    /// Extension parsing error
+    #[error("Extension error: {0}")]
+    ExtensionError(#[from] ExtensionError),
+    /// Parameter value is negative
+    #[error("Parameter {id} has negative value {value}")]
+    NegativeValue {
+        /// Parameter id
+        id: u8,
+        /// Parameter value
+        value: i32,
+    },
+}
+
+/// System parameters in effect at the given height (the values the miners voted for, published in
+/// the extension of the first block of every voting epoch and by the node `/info` endpoint)
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Parameters {
+    /// Height the parameters were set at (the start of the voting epoch)
+    #[cfg_attr(feature = "json", serde(rename = "height"))]
+    pub height: u32,
+    /// Storage fee factor (nanoERGs per byte per storage period)
+    #[cfg_attr(feature = "json", serde(rename = "storageFeeFactor"))]
+    pub storage_fee_factor: u32,
+    /// Minimum box value per byte (nanoERGs)
+    #[cfg_attr(feature = "json", serde(rename = "minValuePerByte"))]
+    pub min_value_per_byte: u32,
+    /// Maximum block size (bytes)
+    #[cfg_attr(feature = "json", serde(rename = "maxBlockSize"))]
+    pub max_block_size: u32,
+    /// Maximum cost of all transactions in a block
+    #[cfg_attr(feature = "json", serde(rename = "maxBlockCost"))]
+    pub max_block_cost: u32,
+    /// Cost of the token access
+    #[cfg_attr(feature = "json", serde(rename = "tokenAccessCost"))]
+    pub token_access_cost: u32,
+    /// Cost of the transaction input
+    #[cfg_attr(feature = "json", serde(rename = "inputCost"))]
+    pub input_cost: u32,
+    /// Cost of the transaction data input
+    #[cfg_attr(feature = "json", serde(rename = "dataInputCost"))]
+    pub data_input_cost: u32,
+    /// Cost of the transaction output
+    #[cfg_attr(feature = "json", serde(rename = "outputCost"))]
+    pub output_cost: u32,
+    /// Block version
+    #[cfg_attr(feature = "json", serde(rename = "blockVersion"))]
+    pub block_version: u32,
+    /// Number of the votes collected for the soft-fork (if the soft-fork voting is in progress)
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            rename = "softForkVotesCollected",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub soft_fork_votes_collected: Option<u32>,
+    /// Height the soft-fork voting started at (if the soft-fork voting is in progress)
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            rename = "softForkStartingHeight",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub soft_fork_starting_height: Option<u32>,
+}
+
+impl Default for Parameters {
+    /// Parameters at the launch of the mainnet
+    fn default() -> Self {
+        Parameters {
+            height: 0,
+            storage_fee_factor: STORAGE_FEE_FACTOR_DEFAULT,
+            min_value_per_byte: MIN_VALUE_PER_BYTE_DEFAULT,
+            max_block_size: MAX_BLOCK_SIZE_DEFAULT,
+            max_block_cost: MAX_BLOCK_COST_DEFAULT,
+            token_access_cost: TOKEN_ACCESS_COST_DEFAULT,
+            input_cost: INPUT_COST_DEFAULT,
+            data_input_cost: DATA_INPUT_COST_DEFAULT,
+            output_cost: OUTPUT_COST_DEFAULT,
+            block_version: BLOCK_VERSION_DEFAULT,
+            soft_fork_votes_collected: None,
+            soft_fork_starting_height: None,
+        }
+    }
+}
+
+impl Parameters {
+    /// Parameters from the extension of the block at `height` (the first block of the voting
+    /// epoch). Parameters missing in the extension keep the default values, unknown ids are
+    /// ignored.
+    pub fn from_extension(height: u32, ext: &Extension) -> Result<Parameters, ParametersError> {
+        Parameters::from_table(height, &ext.parameters()?)
+    }
+
+    /// Parameters from the table of parameter id -> value (see [`Extension::parameters`])
+    pub fn from_table(
+        height: u32,
+        table: &BTreeMap<u8, i32>,
+    ) -> Result<Parameters, ParametersError> {
+        let mut params = Parameters {
+            height,
+            ..Parameters::default()
+        };
+        for (id, value) in table {
+            // unknown (e.g. newly voted) parameters are not range-checked
+            if let Ok(p) = Parameter::try_from(*id) {
+                let value = u32::try_from(*value).map_err(|_| ParametersError::NegativeValue {
+                    id: *id,
+                    value: *value,
+                })?;
+                params.set(p, value);
+            }
+        }
+        Ok(params)
+    }
+
+    /// Value of the parameter (`None` for the soft-fork voting parameters if there is no voting)
+    pub fn get(&self, p: Parameter) -> Option<u32> {
+        match p {
+            Parameter::StorageFeeFactor => Some(self.storage_fee_factor),
+            Parameter::MinValuePerByte => Some(self.min_value_per_byte),
+            Parameter::MaxBlockSize => Some(self.max_block_size),
+            Parameter::MaxBlockCost => Some(self.max_block_cost),
+            Parameter::TokenAccessCost => Some(self.token_access_cost),
+            Parameter::InputCost => Some(self.input_cost),
+            Parameter::DataInputCost => Some(self.data_input_cost),
+            Parameter::OutputCost => Some(self.output_cost),
+            Parameter::SoftForkVotesCollected => self.soft_fork_votes_collected,
+            Parameter::SoftForkStartingHeight => self.soft_fork_starting_height,
+            Parameter::BlockVersion => Some(self.block_version),
+        }
+    }
+
+    /// Set the value of the parameter
+    pub fn set(&mut self, p: Parameter, value: u32) {
+        match p {
+            Parameter::StorageFeeFactor => self.storage_fee_factor = value,
+            Parameter::MinValuePerByte => self.min_value_per_byte = value,
+            Parameter::MaxBlockSize => self.max_block_size = value,
+            Parameter::MaxBlockCost => self.max_block_cost = value,
+            Parameter::TokenAccessCost => self.token_access_cost = value,
+            Parameter::InputCost => self.input_cost = value,
+            Parameter::DataInputCost => self.data_input_cost = value,
+            Parameter::OutputCost => self.output_cost = value,
+            Parameter::SoftForkVotesCollected => self.soft_fork_votes_collected = Some(value),
+            Parameter::SoftForkStartingHeight => self.soft_fork_starting_height = Some(value),
+            Parameter::BlockVersion => self.block_version = value,
+        }
+    }
+
+    /// Table of parameter id -> value as packed into the extension
+    pub fn to_table(&self) -> BTreeMap<u8, i32> {
+        Parameter::ALL
+            .iter()
+            .filter_map(|p| self.get(*p).map(|v| (p.id(), v as i32)))
+            .collect()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary {
+    use super::*;
+    use proptest::prelude::*;
+
+    impl Arbitrary for Parameters {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+            (
+                any::<u32>(),
+                prop::collection::vec(0..i32::MAX as u32, 9),
+                any::<Option<(u32, u32)>>(),
+            )
+                .prop_map(|(height, values, soft_fork)| Parameters {
+                    height,
+                    storage_fee_factor: values[0],
+                    min_value_per_byte: values[1],
+                    max_block_size: values[2],
+                    max_block_cost: values[3],
+                    token_access_cost: values[4],
+                    input_cost: values[5],
+                    data_input_cost: values[6],
+                    output_cost: values[7],
+                    block_version: values[8],
+                    soft_fork_votes_collected: soft_fork.map(|(v, _)| v % i32::MAX as u32),
+                    soft_fork_starting_height: soft_fork.map(|(_, h)| h % i32::MAX as u32),
+                })
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::extension::INTERLINK_VECTOR_PREFIX;
+    use crate::extension::SYSTEM_PARAMETERS_PREFIX;
+    use crate::BlockId;
+    use crate::Digest32;
+
+    #[test]
+    fn test_from_extension() {
+        let fields = vec![
+            ([SYSTEM_PARAMETERS_PREFIX, 2], 720i32.to_be_bytes().to_vec()),
+            ([SYSTEM_PARAMETERS_PREFIX, 123], 2i32.to_be_bytes().to_vec()),
+            ([SYSTEM_PARAMETERS_PREFIX, 99], 7i32.to_be_bytes().to_vec()),
+            ([INTERLINK_VECTOR_PREFIX, 0], vec![1; 33]),
+        ];
+        let ext = Extension::new(BlockId(Digest32::zero()), fields).unwrap();
+        let params = Parameters::from_extension(1024, &ext).unwrap();
+        assert_eq!(
+            params,
+            Parameters {
+                height: 1024,
+                min_value_per_byte: 720,
+                block_version: 2,
+                ..Parameters::default()
+            }
+        );
+        assert_eq!(
+            Parameters::from_table(0, &params.to_table()).unwrap(),
+            Parameters {
+                height: 0,
+                ..params
+            }
+        );
+        assert_eq!(
+            Parameters::from_table(0, &[(4, -1)].into_iter().collect()),
+            Err(ParametersError::NegativeValue { id: 4, value: -1 })
+        );
+        // unknown ids are ignored whatever their value
+        let fields = vec![
+            (
+                [SYSTEM_PARAMETERS_PREFIX, 3],
+                1_000_000i32.to_be_bytes().to_vec(),
+            ),
+            (
+                [SYSTEM_PARAMETERS_PREFIX, 99],
+                (-7i32).to_be_bytes().to_vec(),
+            ),
+        ];
+        let ext = Extension::new(BlockId(Digest32::zero()), fields).unwrap();
+        assert_eq!(
+            Parameters::from_extension(1024, &ext).unwrap(),
+            Parameters {
+                height: 1024,
+                max_block_size: 1_000_000,
+                ..Parameters::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parsed_votes() {
+        let votes = Votes([4, (-2i8) as u8, 0]);
+        assert_eq!(
+            votes.parsed_votes(),
+            vec![
+                ParameterVote::Increase(Parameter::MaxBlockCost),
+                ParameterVote::Decrease(Parameter::MinValuePerByte)
+            ]
+        );
+        assert!(!votes.is_soft_fork_vote());
+        let votes = Votes([120, 99, 0]);
+        assert_eq!(
+            votes.parsed_votes(),
+            vec![ParameterVote::SoftFork, ParameterVote::Unknown(99)]
+        );
+        assert!(votes.is_soft_fork_vote());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_parse_node_info_json() {
+        // `parameters` of the node /info response
+        let json = r#"{
+            "outputCost": 214,
+            "tokenAccessCost": 100,
+            "maxBlockCost": 8001091,
+            "height": 1341440,
+            "maxBlockSize": 1271009,
+            "dataInputCost": 100,
+            "blockVersion": 3,
+            "inputCost": 2407,
+            "storageFeeFactor": 1250000,
+            "minValuePerByte": 360
+        }"#;
+        let params: Parameters = serde_json::from_str(json).unwrap();
+        assert_eq!(params.max_block_cost, 8_001_091);
+        assert_eq!(params.block_version, 3);
+        assert_eq!(params.soft_fork_votes_collected, None);
+        let encoded = serde_json::to_string(&params).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Parameters>(&encoded).unwrap(),
+            params
+        );
+    }
+}
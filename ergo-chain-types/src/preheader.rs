@@ -3,21 +3,29 @@ use crate::{BlockId, EcPoint, Header, Votes};
 
 /// Block header with the current `spendingTransaction`, that can be predicted
 /// by a miner before it's formation
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct PreHeader {
     /// Block version, to be increased on every soft and hardfork
+    #[cfg_attr(feature = "json", serde(rename = "version"))]
     pub version: u8,
     /// Hash of parent block
+    #[cfg_attr(feature = "json", serde(rename = "parentId"))]
     pub parent_id: BlockId,
     /// Timestamp of a block in ms from UNIX epoch
+    #[cfg_attr(feature = "json", serde(rename = "timestamp"))]
     pub timestamp: u64,
     /// Current difficulty in a compressed view.
+    #[cfg_attr(feature = "json", serde(rename = "nBits"))]
     pub n_bits: u64,
     /// Block height
+    #[cfg_attr(feature = "json", serde(rename = "height"))]
     pub height: u32,
     /// Public key of miner
+    #[cfg_attr(feature = "json", serde(rename = "minerPk"))]
     pub miner_pk: Box<EcPoint>,
     /// Votes
+    #[cfg_attr(feature = "json", serde(rename = "votes"))]
     pub votes: Votes,
 }
 
@@ -75,3 +83,85 @@ mod arbitrary {
         type Strategy = BoxedStrategy<PreHeader>;
     }
 }
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[cfg(feature = "json")]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn json_from_node_header() {
+        // node `/blocks/{headerId}/header` response
+        let header_json = r#"{
+            "extensionId": "d16f25b14457186df4c5f6355579cc769261ce1aebc8209949ca6feadbac5a3f",
+            "difficulty": "626412390187008",
+            "votes": "040000",
+            "timestamp": 1618929697400,
+            "size": 221,
+            "stateRoot": "8ad868627ea4f7de6e2a2fe3f98fafe57f914e0f2ef3331c006def36c697f92713",
+            "height": 471746,
+            "nBits": 117586360,
+            "version": 2,
+            "id": "4caa17e62fe66ba7bd69597afdc996ae35b1ff12e0ba90c22ff288a4de10e91b",
+            "adProofsRoot": "d882aaf42e0a95eb95fcce5c3705adf758e591532f733efe790ac3c404730c39",
+            "transactionsRoot": "63eaa9aff76a1de3d71c81e4b2d92e8d97ae572a8e9ab9e66599ed0912dd2f8b",
+            "extensionHash": "3f91f3c680beb26615fdec251aee3f81aaf5a02740806c167c0f3c929471df44",
+            "powSolutions": {
+              "pk": "02b3a06d6eaa8671431ba1db4dd427a77f75a5c2acbd71bfb725d38adc2b55f669",
+              "w": "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+              "n": "5939ecfee6b0d7f4",
+              "d": "1234000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
+            },
+            "adProofsId": "86eaa41f328bee598e33e52c9e515952ad3b7874102f762847f17318a776a7ae",
+            "transactionsId": "ac80245714f25aa2fafe5494ad02a26d46e7955b8f5709f3659f1b9440797b3e",
+            "parentId": "6481752bace5fa5acba5d5ef7124d48826664742d46c974c98a2d60ace229a34"
+        }"#;
+        let header: Header = serde_json::from_str(header_json).unwrap();
+        let pre_header = PreHeader::from(header);
+        let json = serde_json::to_value(&pre_header).unwrap();
+        let mut keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "height",
+                "minerPk",
+                "nBits",
+                "parentId",
+                "timestamp",
+                "version",
+                "votes"
+            ]
+        );
+        assert_eq!(json["version"], 2);
+        assert_eq!(
+            json["parentId"],
+            "6481752bace5fa5acba5d5ef7124d48826664742d46c974c98a2d60ace229a34"
+        );
+        assert_eq!(json["timestamp"], 1618929697400u64);
+        assert_eq!(json["nBits"], 117586360);
+        assert_eq!(json["height"], 471746);
+        assert_eq!(
+            json["minerPk"],
+            "02b3a06d6eaa8671431ba1db4dd427a77f75a5c2acbd71bfb725d38adc2b55f669"
+        );
+        assert_eq!(json["votes"], "040000");
+        assert_eq!(
+            serde_json::from_value::<PreHeader>(json).unwrap(),
+            pre_header
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn json_roundtrip(v in any::<PreHeader>()) {
+            let json = serde_json::to_string(&v).unwrap();
+            prop_assert_eq![serde_json::from_str::<PreHeader>(&json).unwrap(), v];
+        }
+    }
+}
@@ -16,6 +16,18 @@ use std::convert::{TryFrom, TryInto};
 )]
 pub struct Votes(pub [u8; 3]);
 
+impl Votes {
+    /// Ids of the parameters the miner voted for, skipping empty (zero) votes.
+    /// A negative id is a vote for decreasing the parameter value, a positive one for increasing it.
+    pub fn parameter_votes(&self) -> Vec<i8> {
+        self.0
+            .iter()
+            .map(|v| *v as i8)
+            .filter(|id| *id != 0)
+            .collect()
+    }
+}
+
 impl From<Votes> for Vec<u8> {
     fn from(v: Votes) -> Self {
         v.0.to_vec()
@@ -4,7 +4,17 @@
 pub mod json;
 
 pub mod block;
+pub mod block_candidate;
 pub mod contract;
+pub mod emission;
 pub mod ergo_box;
 pub mod ergo_state_context;
+pub mod oracle_pool;
+pub mod reemission;
+pub mod scan_predicate;
+pub mod singleton;
+pub mod storage_rent;
+pub mod template_registry;
 pub mod transaction;
+pub mod utxo_proof;
+pub mod utxo_snapshot;
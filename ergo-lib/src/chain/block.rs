@@ -1,8 +1,27 @@
 //! Block on the Ergo chain
 
 use bounded_vec::BoundedVec;
+use bytes::Bytes;
+use ergo_chain_types::BlockId;
+use ergo_chain_types::Digest32;
+use ergo_chain_types::Extension;
 use ergo_chain_types::Header;
+use ergo_merkle_tree::MerkleNode;
+use ergo_merkle_tree::MerkleProof;
+use ergo_merkle_tree::MerkleTree;
+use std::io::Read;
 
+use ergotree_ir::chain::tx_id::TxId;
+use ergotree_ir::serialization::constant_store::ConstantStore;
+use ergotree_ir::serialization::sigma_byte_reader;
+use ergotree_ir::serialization::sigma_byte_reader::SigmaByteRead;
+use ergotree_ir::serialization::sigma_byte_reader::SigmaByteReader;
+use ergotree_ir::serialization::SigmaParsingError;
+use ergotree_ir::serialization::SigmaSerializable;
+use sigma_ser::ScorexSerializable;
+use sigma_util::hash::blake2b256_hash;
+
+use super::transaction::view::TransactionView;
 use super::transaction::Transaction;
 
 /// Maximum number of transactions that can be contained in a block. See
@@ -17,8 +36,288 @@ pub struct BlockTransactions {
     pub transactions: BoundedVec<Transaction, 1, MAX_NUM_TRANSACTIONS>,
 }
 
+impl BlockTransactions {
+    /// Merkle tree of the block transactions built the same way as the node does: leaves are
+    /// transaction ids, followed by the transaction witness ids for block version > 1.
+    /// See https://github.com/ergoplatform/ergo/blob/fc292f6bc2d3c6ca27ce5f6a316186d8459150cc/src/main/scala/org/ergoplatform/modifiers/history/BlockTransactions.scala
+    pub fn merkle_tree(&self, block_version: u8) -> MerkleTree {
+        let tx_ids = self
+            .transactions
+            .iter()
+            .map(|tx| MerkleNode::from_bytes(tx.id().0.as_ref()));
+        let leaves: Vec<MerkleNode> = if block_version == INITIAL_BLOCK_VERSION {
+            tx_ids.collect()
+        } else {
+            tx_ids
+                .chain(
+                    self.transactions
+                        .iter()
+                        .map(|tx| MerkleNode::from_bytes(witness_id(tx))),
+                )
+                .collect()
+        };
+        MerkleTree::new(leaves)
+    }
+
+    /// Transactions root (`Header::transaction_root`) for the given block version
+    pub fn transactions_root(&self, block_version: u8) -> Digest32 {
+        self.merkle_tree(block_version).root_hash_special()
+    }
+
+    /// Merkle proof of inclusion of the transaction in the block (same as returned by the node's
+    /// `/blocks/{header_id}/proofFor/{tx_id}`). Returns `None` if the transaction is not in the block.
+    pub fn proof_for(&self, block_version: u8, tx_id: &TxId) -> Option<MerkleProof> {
+        self.merkle_tree(block_version)
+            .proof_by_element(tx_id.0.as_ref())
+    }
+}
+
+/// Block version before the first soft-fork (witness ids are not included in the transactions root)
+const INITIAL_BLOCK_VERSION: u8 = 1;
+
+/// Commitment to the transaction spending proofs, 31 bytes long to be distinguishable from the
+/// transaction ids in the Merkle tree
+fn witness_id(tx: &Transaction) -> Vec<u8> {
+    let proofs: Vec<u8> = tx
+        .inputs
+        .iter()
+        .flat_map(|input| Vec::from(input.spending_proof.proof.clone()))
+        .collect();
+    blake2b256_hash(&proofs)[1..].to_vec()
+}
+
+/// Parse the header id, block version and transactions count at the start of the serialized
+/// block transactions section
+fn parse_section_prefix<R: SigmaByteRead>(
+    r: &mut R,
+) -> Result<(BlockId, u8, usize), SigmaParsingError> {
+    let header_id = BlockId(Digest32::scorex_parse(r)?);
+    // block version > 1 is encoded as the transactions count overflow
+    let count_or_version = r.get_u32()? as usize;
+    let (block_version, txs_count) = if count_or_version > MAX_NUM_TRANSACTIONS {
+        let block_version = u8::try_from(count_or_version - MAX_NUM_TRANSACTIONS)
+            .map_err(|_| SigmaParsingError::ValueOutOfBounds("block version".to_string()))?;
+        (block_version, r.get_u32()? as usize)
+    } else {
+        (INITIAL_BLOCK_VERSION, count_or_version)
+    };
+    if txs_count == 0 || txs_count > MAX_NUM_TRANSACTIONS {
+        return Err(SigmaParsingError::ValueOutOfBounds(format!(
+            "invalid transactions count: {}",
+            txs_count
+        )));
+    }
+    Ok((header_id, block_version, txs_count))
+}
+
+/// Zero-copy view of the serialized block transactions section (as stored and sent by the node)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockTransactionsView {
+    header_id: BlockId,
+    block_version: u8,
+    transactions: Vec<TransactionView>,
+}
+
+impl BlockTransactionsView {
+    /// Parse the serialized block transactions, `bytes` are kept as the backing buffer of the
+    /// transaction views.
+    /// See https://github.com/ergoplatform/ergo/blob/fc292f6bc2d3c6ca27ce5f6a316186d8459150cc/src/main/scala/org/ergoplatform/modifiers/history/BlockTransactions.scala
+    pub fn sigma_parse_bytes(bytes: Bytes) -> Result<BlockTransactionsView, SigmaParsingError> {
+        let mut r = sigma_byte_reader::from_bytes(&bytes[..]);
+        let (header_id, block_version, txs_count) = parse_section_prefix(&mut r)?;
+        let mut pos = r.position() as usize;
+        let mut transactions = Vec::new();
+        for _ in 0..txs_count {
+            let tx = TransactionView::parse_at(&bytes, pos)?;
+            pos += tx.bytes().len();
+            transactions.push(tx);
+        }
+        Ok(BlockTransactionsView {
+            header_id,
+            block_version,
+            transactions,
+        })
+    }
+
+    /// Id of the block header
+    pub fn header_id(&self) -> BlockId {
+        self.header_id
+    }
+
+    /// Block version
+    pub fn block_version(&self) -> u8 {
+        self.block_version
+    }
+
+    /// Transactions of the block
+    pub fn transactions(&self) -> &[TransactionView] {
+        &self.transactions
+    }
+}
+
+/// Streaming parser of the serialized block transactions section (as stored and sent by the node),
+/// yielding the transactions one at a time without keeping the whole block in memory (e.g. for
+/// the indexers syncing the chain). Stops after the first parsing error.
+pub struct BlockTransactionsReader<R> {
+    reader: SigmaByteReader<R>,
+    header_id: BlockId,
+    block_version: u8,
+    transactions_count: usize,
+    parsed_count: usize,
+    failed: bool,
+}
+
+impl<R: Read> BlockTransactionsReader<R> {
+    /// Parse the section header (block id, version and transactions count) from `reader`, the
+    /// transactions are parsed on iteration
+    pub fn new(reader: R) -> Result<BlockTransactionsReader<R>, SigmaParsingError> {
+        BlockTransactionsReader::from_sigma_reader(SigmaByteReader::new(
+            reader,
+            ConstantStore::empty(),
+        ))
+    }
+
+    fn from_sigma_reader(
+        mut reader: SigmaByteReader<R>,
+    ) -> Result<BlockTransactionsReader<R>, SigmaParsingError> {
+        let (header_id, block_version, transactions_count) = parse_section_prefix(&mut reader)?;
+        Ok(BlockTransactionsReader {
+            reader,
+            header_id,
+            block_version,
+            transactions_count,
+            parsed_count: 0,
+            failed: false,
+        })
+    }
+
+    /// Id of the block header
+    pub fn header_id(&self) -> BlockId {
+        self.header_id
+    }
+
+    /// Block version
+    pub fn block_version(&self) -> u8 {
+        self.block_version
+    }
+
+    /// Number of transactions in the block
+    pub fn transactions_count(&self) -> usize {
+        self.transactions_count
+    }
+
+    /// Number of bytes consumed from the underlying reader so far
+    pub fn position(&self) -> u64 {
+        self.reader.position()
+    }
+}
+
+impl<R: Read> Iterator for BlockTransactionsReader<R> {
+    type Item = Result<Transaction, SigmaParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.parsed_count >= self.transactions_count {
+            return None;
+        }
+        let res = self.reader.parse_in_context(
+            || format!("tx[{}]", self.parsed_count),
+            Transaction::sigma_parse,
+        );
+        self.parsed_count += 1;
+        self.failed = res.is_err();
+        Some(res)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            (0, Some(0))
+        } else {
+            let left = self.transactions_count - self.parsed_count;
+            (0, Some(left))
+        }
+    }
+}
+
+/// Streaming parser of the serialized full block: the header, the block transactions section and
+/// the extension section one after another (the block sections as sent by the node). The header
+/// is parsed first, the transactions are yielded one at a time on iteration and the extension is
+/// parsed by [`FullBlockReader::finish`]. Stops after the first parsing error.
+pub struct FullBlockReader<R> {
+    header: Header,
+    transactions: BlockTransactionsReader<R>,
+}
+
+impl<R: Read> FullBlockReader<R> {
+    /// Parse the header and the block transactions section header from `reader`, the
+    /// transactions are parsed on iteration
+    pub fn new(reader: R) -> Result<FullBlockReader<R>, SigmaParsingError> {
+        let mut reader = SigmaByteReader::new(reader, ConstantStore::empty());
+        let header = Header::scorex_parse(&mut reader)?;
+        let transactions = BlockTransactionsReader::from_sigma_reader(reader)?;
+        check_section_header_id(&header, transactions.header_id(), "block transactions")?;
+        Ok(FullBlockReader {
+            header,
+            transactions,
+        })
+    }
+
+    /// Block header
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Number of transactions in the block
+    pub fn transactions_count(&self) -> usize {
+        self.transactions.transactions_count()
+    }
+
+    /// Number of bytes consumed from the underlying reader so far
+    pub fn position(&self) -> u64 {
+        self.transactions.position()
+    }
+
+    /// Parse the extension section following the transactions, the transactions not yet
+    /// iterated over are parsed and dropped
+    pub fn finish(mut self) -> Result<Extension, SigmaParsingError> {
+        for tx in self.transactions.by_ref() {
+            tx?;
+        }
+        let extension = Extension::scorex_parse(&mut self.transactions.reader)?;
+        check_section_header_id(&self.header, extension.header_id, "extension")?;
+        Ok(extension)
+    }
+}
+
+impl<R: Read> Iterator for FullBlockReader<R> {
+    type Item = Result<Transaction, SigmaParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.transactions.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.transactions.size_hint()
+    }
+}
+
+fn check_section_header_id(
+    header: &Header,
+    section_header_id: BlockId,
+    section: &str,
+) -> Result<(), SigmaParsingError> {
+    if section_header_id == header.id {
+        Ok(())
+    } else {
+        Err(SigmaParsingError::Misc(format!(
+            "{} section of the block {:?} is for another block {:?}",
+            section, header.id, section_header_id
+        )))
+    }
+}
+
 /// A block on the Ergo chain
 #[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(from = "FullBlockJson"))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FullBlock {
     /// Block header
@@ -26,11 +325,77 @@ pub struct FullBlock {
     /// Transactions in this block
     #[cfg_attr(feature = "json", serde(rename = "blockTransactions"))]
     pub block_transactions: BlockTransactions,
+    /// Extension section of this block
+    pub extension: Extension,
+}
+
+/// Node JSON of a block, the extension section is optional (empty if missing)
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize)]
+struct FullBlockJson {
+    header: Header,
+    #[serde(rename = "blockTransactions")]
+    block_transactions: BlockTransactions,
+    #[serde(default)]
+    extension: Option<Extension>,
+}
+
+#[cfg(feature = "json")]
+impl From<FullBlockJson> for FullBlock {
+    fn from(block: FullBlockJson) -> Self {
+        match block.extension {
+            Some(extension) => FullBlock::new(block.header, block.block_transactions, extension),
+            None => FullBlock::without_extension(block.header, block.block_transactions),
+        }
+    }
+}
+
+impl FullBlock {
+    /// Creates a new block from its sections
+    pub fn new(
+        header: Header,
+        block_transactions: BlockTransactions,
+        extension: Extension,
+    ) -> FullBlock {
+        FullBlock {
+            header,
+            block_transactions,
+            extension,
+        }
+    }
+
+    /// Creates a new block with an empty extension section
+    pub fn without_extension(header: Header, block_transactions: BlockTransactions) -> FullBlock {
+        let extension = Extension::empty(header.id);
+        FullBlock::new(header, block_transactions, extension)
+    }
+
+    /// Returns `true` if the block transactions match the header's transactions root
+    pub fn check_transactions_root(&self) -> bool {
+        self.block_transactions
+            .transactions_root(self.header.version)
+            == self.header.transaction_root
+    }
+
+    /// Merkle proof of inclusion of the transaction in the block, verifiable against the
+    /// header's transactions root with [`MerkleProof::valid`]
+    pub fn proof_for(&self, tx_id: &TxId) -> Option<MerkleProof> {
+        self.block_transactions
+            .proof_for(self.header.version, tx_id)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::BlockTransactionsReader;
+    use super::BlockTransactionsView;
     use super::FullBlock;
+    use super::FullBlockReader;
+    use bytes::Bytes;
+    use ergotree_ir::chain::tx_id::TxId;
+    use ergotree_ir::serialization::SigmaSerializable;
+    use sigma_ser::vlq_encode::WriteSigmaVlqExt;
+    use sigma_ser::ScorexSerializable;
 
     #[test]
     #[allow(clippy::unwrap_used)]
@@ -162,5 +527,86 @@ mod tests {
         let encoded_json = serde_json::to_string(&block_0).unwrap();
         let block_1: FullBlock = serde_json::from_str(&encoded_json).unwrap();
         assert_eq!(block_0, block_1);
+
+        assert!(block_0.check_transactions_root());
+        assert_eq!(block_0.extension.header_id, block_0.header.id);
+        assert_eq!(block_0.extension.interlinks().unwrap().len(), 20);
+        let tx_id = block_0.block_transactions.transactions.first().id();
+        let proof = block_0.proof_for(&tx_id).unwrap();
+        assert_eq!(proof.get_leaf_data(), tx_id.0.as_ref());
+        assert!(proof.valid(block_0.header.transaction_root.as_ref()));
+        assert!(block_0.proof_for(&TxId::zero()).is_none());
+        // blocks without the extension section (e.g. stored by older versions)
+        let mut json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        json_value.as_object_mut().unwrap().remove("extension");
+        let block_no_ext: FullBlock = serde_json::from_value(json_value).unwrap();
+        assert_eq!(
+            block_no_ext,
+            FullBlock::without_extension(
+                block_0.header.clone(),
+                block_0.block_transactions.clone()
+            )
+        );
+        assert!(block_no_ext.extension.fields().is_empty());
+        assert_eq!(block_no_ext.extension.header_id, block_0.header.id);
+        // transactions root differs for the initial block version (no witness ids)
+        assert_ne!(
+            block_0.block_transactions.transactions_root(1),
+            block_0.header.transaction_root
+        );
+
+        // serialized block transactions section, as the node stores it
+        let mut bytes: Vec<u8> = block_0.header.id.0.into();
+        bytes
+            .put_u32(10_000_000 + block_0.header.version as u32)
+            .unwrap();
+        bytes.put_u32(1).unwrap();
+        let tx = block_0.block_transactions.transactions.first();
+        bytes.extend(tx.sigma_serialize_bytes().unwrap());
+        // "size" of the section in the node's JSON
+        assert_eq!(bytes.len(), 381);
+        let mut stream = BlockTransactionsReader::new(&bytes[..]).unwrap();
+        assert_eq!(stream.header_id(), block_0.header.id);
+        assert_eq!(stream.block_version(), 2);
+        assert_eq!(stream.transactions_count(), 1);
+        assert_eq!(stream.next().unwrap().unwrap(), *tx);
+        assert!(stream.next().is_none());
+        assert_eq!(stream.position(), 381);
+        // truncated section
+        let mut stream = BlockTransactionsReader::new(&bytes[..300]).unwrap();
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+
+        // full block: header, block transactions and extension sections
+        let mut block_bytes = block_0.header.scorex_serialize_bytes().unwrap();
+        block_bytes.extend_from_slice(&bytes);
+        block_bytes.extend(block_0.extension.scorex_serialize_bytes().unwrap());
+        let mut stream = FullBlockReader::new(&block_bytes[..]).unwrap();
+        // the PoW one-time pk and distance are not serialized for Autolykos v2
+        assert_eq!(stream.header().id, block_0.header.id);
+        assert_eq!(stream.transactions_count(), 1);
+        assert_eq!(stream.next().unwrap().unwrap(), *tx);
+        assert!(stream.next().is_none());
+        assert_eq!(stream.finish().unwrap(), block_0.extension);
+        // the extension is parsed without iterating over the transactions
+        let stream = FullBlockReader::new(&block_bytes[..]).unwrap();
+        assert_eq!(stream.finish().unwrap(), block_0.extension);
+        // sections of another block
+        let mut other_block_bytes = block_0.header.scorex_serialize_bytes().unwrap();
+        other_block_bytes.extend_from_slice(&[0u8; 32]);
+        other_block_bytes.extend_from_slice(&bytes[32..]);
+        assert!(FullBlockReader::new(&other_block_bytes[..]).is_err());
+        // truncated extension
+        let stream = FullBlockReader::new(&block_bytes[..block_bytes.len() - 10]).unwrap();
+        assert!(stream.finish().is_err());
+
+        let view = BlockTransactionsView::sigma_parse_bytes(Bytes::from(bytes)).unwrap();
+        assert_eq!(view.header_id(), block_0.header.id);
+        assert_eq!(view.block_version(), 2);
+        assert_eq!(view.transactions()[0].id(), tx.id());
+        assert_eq!(
+            view.transactions()[0].outputs()[1].box_id(),
+            tx.outputs.as_vec()[1].box_id()
+        );
     }
 }
@@ -0,0 +1,353 @@
+//! Block candidate assembly (for the mining pool software)
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use bounded_vec::BoundedVec;
+use ergo_chain_types::ADDigest;
+use ergo_chain_types::Digest32;
+use ergo_chain_types::HeaderWithoutPow;
+use ergo_chain_types::Parameters;
+use ergo_chain_types::PreHeader;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::tx_id::TxId;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::serialization::SigmaSerializationError;
+use sigma_ser::ScorexSerializationError;
+use thiserror::Error;
+
+use super::block::BlockTransactions;
+use super::transaction::Transaction;
+use crate::wallet::mempool::MempoolSnapshot;
+use crate::wallet::miner_fee::MINERS_FEE_ERGO_TREE;
+
+/// Block candidate errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum BlockCandidateError {
+    /// Block candidate has no transactions
+    #[error("Block candidate has no transactions")]
+    NoTransactions,
+    /// Transaction serialization failed
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SigmaSerializationError),
+    /// Header serialization failed
+    #[error("Header serialization error: {0}")]
+    HeaderSerializationError(#[from] ScorexSerializationError),
+}
+
+/// Unconfirmed transaction with its verification cost (see
+/// [`super::transaction::TxVerificationResult::total_cost`])
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct MempoolTx {
+    /// Transaction
+    pub tx: Transaction,
+    /// Cost of the transaction verification
+    pub cost: u64,
+}
+
+impl MempoolTx {
+    /// Fee paid to the miner (the value of the outputs protected by the miner fee contract)
+    pub fn fee(&self) -> u64 {
+        self.tx
+            .output_candidates
+            .iter()
+            .filter(|b| b.ergo_tree == *MINERS_FEE_ERGO_TREE)
+            .map(|b| *b.value.as_u64())
+            .sum()
+    }
+}
+
+/// Limits for the transactions in the block candidate
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct BlockLimits {
+    /// Maximum total cost of the transactions
+    pub max_cost: u64,
+    /// Maximum total size of the serialized transactions (bytes)
+    pub max_size: usize,
+}
+
+impl From<&Parameters> for BlockLimits {
+    fn from(p: &Parameters) -> Self {
+        BlockLimits {
+            max_cost: p.max_block_cost as u64,
+            max_size: p.max_block_size as usize,
+        }
+    }
+}
+
+/// Transactions of the block candidate
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct BlockCandidate {
+    /// Transactions in the block order
+    pub transactions: Vec<Transaction>,
+    /// Total cost of the transactions
+    pub total_cost: u64,
+    /// Total size of the serialized transactions (bytes)
+    pub total_size: usize,
+    /// Total fee paid to the miner
+    pub total_fee: u64,
+}
+
+impl BlockCandidate {
+    /// Pick the mempool transactions with the highest fee per byte first while they fit into the
+    /// limits. A transaction is skipped if it double-spends an input of already picked one.
+    /// A transaction spending an output of a mempool transaction which is not picked yet waits
+    /// for it and is re-queued right after the parent is picked (so a high fee child pays for
+    /// its parent), it's skipped if the parent is never picked.
+    /// The transactions of the pool itself (e.g. the emission transaction) can be inserted into
+    /// [`Self::transactions`] afterwards, with the limits reduced accordingly.
+    pub fn assemble(
+        mempool: Vec<MempoolTx>,
+        limits: &BlockLimits,
+    ) -> Result<BlockCandidate, BlockCandidateError> {
+        let mut candidates = mempool
+            .into_iter()
+            .map(|mtx| {
+                let size = mtx.tx.sigma_serialize_bytes()?.len();
+                let fee = mtx.fee();
+                Ok((mtx, size, fee))
+            })
+            .collect::<Result<Vec<_>, BlockCandidateError>>()?;
+        // descending fee per byte
+        candidates.sort_by(|(_, size_a, fee_a), (_, size_b, fee_b)| {
+            (*fee_b as u128 * *size_a as u128).cmp(&(*fee_a as u128 * *size_b as u128))
+        });
+        let created_by: HashMap<BoxId, TxId> = candidates
+            .iter()
+            .flat_map(|(mtx, _, _)| {
+                let tx_id = mtx.tx.id();
+                mtx.tx.output_ids().map(move |box_id| (box_id, tx_id))
+            })
+            .collect();
+        let mut picked_ids: HashSet<TxId> = HashSet::new();
+        // transactions waiting for their parent (by the parent id) to be picked
+        let mut waiting: HashMap<TxId, Vec<(MempoolTx, usize, u64)>> = HashMap::new();
+        let mut spent = MempoolSnapshot::default();
+        let mut candidate = BlockCandidate::default();
+        for next in candidates {
+            // the candidate followed by the children released by picking it (in the fee order)
+            let mut ready = vec![next];
+            while let Some((mtx, size, fee)) = ready.pop() {
+                let total_cost = candidate.total_cost.saturating_add(mtx.cost);
+                let total_size = candidate.total_size.saturating_add(size);
+                if total_cost > limits.max_cost || total_size > limits.max_size {
+                    continue;
+                }
+                if !spent.tx_conflicts(&mtx.tx).is_empty() {
+                    continue;
+                }
+                let unpicked_parent = mtx.tx.inputs.iter().find_map(|i| {
+                    created_by
+                        .get(&i.box_id)
+                        .filter(|parent_id| !picked_ids.contains(*parent_id))
+                        .copied()
+                });
+                if let Some(parent_id) = unpicked_parent {
+                    waiting.entry(parent_id).or_default().push((mtx, size, fee));
+                    continue;
+                }
+                let tx_id = mtx.tx.id();
+                spent.add_transaction(&mtx.tx);
+                picked_ids.insert(tx_id);
+                candidate.total_cost = total_cost;
+                candidate.total_size = total_size;
+                candidate.total_fee = candidate.total_fee.saturating_add(fee);
+                candidate.transactions.push(mtx.tx);
+                if let Some(children) = waiting.remove(&tx_id) {
+                    ready.extend(children.into_iter().rev());
+                }
+            }
+        }
+        Ok(candidate)
+    }
+
+    /// Transactions root (`Header::transaction_root`) for the given block version
+    pub fn transactions_root(&self, block_version: u8) -> Result<Digest32, BlockCandidateError> {
+        let transactions = BoundedVec::from_vec(self.transactions.clone())
+            .map_err(|_| BlockCandidateError::NoTransactions)?;
+        Ok(BlockTransactions { transactions }.transactions_root(block_version))
+    }
+
+    /// Header of the block candidate (without the PoW solution) for the next block described by
+    /// `pre_header`. The state root and the AD proofs root after applying the transactions and
+    /// the extension root are computed by the node (e.g. returned with the mining candidate).
+    pub fn header(
+        &self,
+        pre_header: &PreHeader,
+        state_root: ADDigest,
+        ad_proofs_root: Digest32,
+        extension_root: Digest32,
+    ) -> Result<HeaderWithoutPow, BlockCandidateError> {
+        Ok(HeaderWithoutPow {
+            version: pre_header.version,
+            parent_id: pre_header.parent_id,
+            ad_proofs_root,
+            state_root,
+            transaction_root: self.transactions_root(pre_header.version)?,
+            timestamp: pre_header.timestamp,
+            n_bits: pre_header.n_bits,
+            height: pre_header.height,
+            extension_root,
+            votes: pre_header.votes.clone(),
+        })
+    }
+
+    /// Message to be solved by the miners (see [`HeaderWithoutPow::msg`])
+    pub fn message_to_solve(
+        &self,
+        pre_header: &PreHeader,
+        state_root: ADDigest,
+        ad_proofs_root: Digest32,
+        extension_root: Digest32,
+    ) -> Result<Digest32, BlockCandidateError> {
+        Ok(self
+            .header(pre_header, state_root, ad_proofs_root, extension_root)?
+            .msg()?)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::transaction::input::prover_result::ProverResult;
+    use crate::chain::transaction::Input;
+
+    use super::*;
+
+    fn with_fee(tx: &Transaction, fee: u64, out: &ErgoBoxCandidate) -> Transaction {
+        let fee_box = ErgoBoxCandidate {
+            value: BoxValue::try_from(fee).unwrap(),
+            ergo_tree: MINERS_FEE_ERGO_TREE.clone(),
+            tokens: None,
+            ..force_any_val::<ErgoBoxCandidate>()
+        };
+        Transaction::new_from_vec(
+            tx.inputs.as_vec().clone(),
+            vec![],
+            vec![out.clone(), fee_box],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_assemble() {
+        let out = force_any_val::<ErgoBoxCandidate>();
+        let low = with_fee(&force_any_val::<Transaction>(), 1_000_000, &out);
+        let high = with_fee(&force_any_val::<Transaction>(), 10_000_000_000, &out);
+        let double_spend = with_fee(&high, 1_000_000_000, &out);
+        let mempool = vec![
+            MempoolTx {
+                tx: low.clone(),
+                cost: 10,
+            },
+            MempoolTx {
+                tx: double_spend,
+                cost: 10,
+            },
+            MempoolTx {
+                tx: high.clone(),
+                cost: 10,
+            },
+        ];
+        let candidate = BlockCandidate::assemble(
+            mempool.clone(),
+            &BlockLimits {
+                max_cost: 1000,
+                max_size: 1_000_000,
+            },
+        )
+        .unwrap();
+        assert_eq!(candidate.transactions, vec![high.clone(), low]);
+        assert_eq!(candidate.total_cost, 20);
+        assert_eq!(candidate.total_fee, 10_001_000_000);
+        let candidate = BlockCandidate::assemble(
+            mempool,
+            &BlockLimits {
+                max_cost: 15,
+                max_size: 1_000_000,
+            },
+        )
+        .unwrap();
+        assert_eq!(candidate.transactions, vec![high]);
+        let pre_header = force_any_val::<PreHeader>();
+        let header = candidate
+            .header(
+                &pre_header,
+                ADDigest::zero(),
+                Digest32::zero(),
+                Digest32::zero(),
+            )
+            .unwrap();
+        assert_eq!(
+            header.transaction_root,
+            candidate.transactions_root(pre_header.version).unwrap()
+        );
+        assert_eq!(
+            candidate
+                .message_to_solve(
+                    &pre_header,
+                    ADDigest::zero(),
+                    Digest32::zero(),
+                    Digest32::zero()
+                )
+                .unwrap(),
+            header.msg().unwrap()
+        );
+        assert_eq!(
+            BlockCandidate::default().transactions_root(2),
+            Err(BlockCandidateError::NoTransactions)
+        );
+    }
+
+    #[test]
+    fn test_assemble_child_pays_for_parent() {
+        let out = force_any_val::<ErgoBoxCandidate>();
+        let parent = with_fee(&force_any_val::<Transaction>(), 1_000_000, &out);
+        let spending_parent = Transaction::new_from_vec(
+            vec![Input::new(
+                parent.output_ids().next().unwrap(),
+                force_any_val::<ProverResult>(),
+            )],
+            vec![],
+            vec![out.clone()],
+        )
+        .unwrap();
+        let child = with_fee(&spending_parent, 20_000_000_000, &out);
+        let other = with_fee(&force_any_val::<Transaction>(), 10_000_000_000, &out);
+        let mempool: Vec<MempoolTx> = [&child, &other, &parent]
+            .iter()
+            .map(|tx| MempoolTx {
+                tx: (*tx).clone(),
+                cost: 10,
+            })
+            .collect();
+        let limits = BlockLimits {
+            max_cost: 1000,
+            max_size: 1_000_000,
+        };
+        // the child waits for the parent and is picked right after it
+        let candidate = BlockCandidate::assemble(mempool.clone(), &limits).unwrap();
+        assert_eq!(
+            candidate.transactions,
+            vec![other.clone(), parent.clone(), child]
+        );
+        assert_eq!(candidate.total_fee, 30_001_000_000);
+        // the parent exceeds the cost limit, so the child is never picked
+        let mempool: Vec<MempoolTx> = mempool
+            .into_iter()
+            .map(|m| MempoolTx {
+                cost: if m.tx == parent { 2000 } else { m.cost },
+                ..m
+            })
+            .collect();
+        let candidate = BlockCandidate::assemble(mempool, &limits).unwrap();
+        assert_eq!(candidate.transactions, vec![other]);
+    }
+}
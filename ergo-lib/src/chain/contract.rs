@@ -1,5 +1,10 @@
 //! Ergo contract
 
+mod templates;
+
+pub use templates::ContractTemplateError;
+pub use templates::MULTISIG_MAX_KEYS;
+
 use ergotree_ir::chain::address::Address;
 use ergotree_ir::ergo_tree::ErgoTree;
 use ergotree_ir::serialization::SigmaParsingError;
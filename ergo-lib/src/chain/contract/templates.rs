@@ -0,0 +1,236 @@
+//! Standard contracts for the common cases, built directly from the IR (no ErgoScript compiler
+//! needed at runtime).
+//! Each contract is a fixed template with constant segregation, so the parameters are the only
+//! constants in the tree and every instance shares the same template hash
+//! (see [`crate::chain::template_registry::TemplateHash`]).
+
+use ergo_chain_types::Digest32;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::ergo_tree::ErgoTreeError;
+use ergotree_ir::ergo_tree::ErgoTreeHeader;
+use ergotree_ir::mir::atleast::Atleast;
+use ergotree_ir::mir::bin_op::BinOp;
+use ergotree_ir::mir::bin_op::BinOpKind;
+use ergotree_ir::mir::bin_op::RelationOp;
+use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
+use ergotree_ir::mir::calc_blake2b256::CalcBlake2b256;
+use ergotree_ir::mir::coll_by_index::ByIndex;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::mir::expr::InvalidArgumentError;
+use ergotree_ir::mir::extract_reg_as::ExtractRegisterAs;
+use ergotree_ir::mir::global_vars::GlobalVars;
+use ergotree_ir::mir::option_get::OptionGet;
+use ergotree_ir::mir::sigma_and::SigmaAnd;
+use ergotree_ir::mir::sigma_or::SigmaOr;
+use ergotree_ir::mir::unary_op::OneArgOpTryBuild;
+use ergotree_ir::sigma_protocol::sigma_boolean::ProveDlog;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProp;
+use ergotree_ir::types::stype::SType;
+use thiserror::Error;
+
+use super::Contract;
+
+/// Maximum number of keys in [`Contract::multisig`] (`atLeast` limit)
+pub const MULTISIG_MAX_KEYS: usize = 255;
+
+/// Errors on building a standard contract
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum ContractTemplateError {
+    /// Invalid `k` for `k-of-n` multisig
+    #[error("Invalid multisig threshold {k} for {n} keys")]
+    InvalidThreshold {
+        /// required signatures
+        k: usize,
+        /// total keys
+        n: usize,
+    },
+    /// Too many keys for multisig
+    #[error("Too many multisig keys: {0}, max is 255")]
+    TooManyKeys(usize),
+    /// Deadline is out of `Int` range
+    #[error("Deadline height {0} is out of range")]
+    InvalidDeadline(u32),
+    /// IR node construction error
+    #[error("InvalidArgumentError: {0}")]
+    InvalidArgumentError(#[from] InvalidArgumentError),
+    /// ErgoTree error
+    #[error("ErgoTreeError: {0}")]
+    ErgoTreeError(#[from] ErgoTreeError),
+}
+
+impl Contract {
+    /// Pin-lock contract, spendable by anyone who reveals the pin (preimage of `pin_hash`) in the
+    /// R4 (`Coll[Byte]`) of the first output:
+    /// `sigmaProp(blake2b256(OUTPUTS(0).R4[Coll[Byte]].get) == pinHash)`
+    ///
+    /// The pin is public once the spending transaction is in the mempool, so it's only meant for
+    /// the cases where front-running is not a concern (e.g. demos, faucets).
+    pub fn pin_lock(pin_hash: Digest32) -> Result<Contract, ContractTemplateError> {
+        let first_output: Expr = ByIndex::new(
+            GlobalVars::Outputs.into(),
+            Constant::from(0i32).into(),
+            None,
+        )?
+        .into();
+        let pin: Expr = OptionGet::try_build(
+            ExtractRegisterAs::new(
+                first_output,
+                4,
+                SType::SOption(SType::SColl(SType::SByte.into()).into()),
+            )?
+            .into(),
+        )?
+        .into();
+        let hash: Expr = CalcBlake2b256::try_build(pin)?.into();
+        let eq: Expr = BinOp::new(
+            BinOpKind::Relation(RelationOp::Eq),
+            hash,
+            Constant::from(pin_hash).into(),
+        )?
+        .into();
+        Self::from_template(BoolToSigmaProp::try_build(eq)?.into())
+    }
+
+    /// `k-of-n` multisig contract: `atLeast(k, Coll(pk1, ..., pkN))`
+    pub fn multisig(k: usize, pks: Vec<ProveDlog>) -> Result<Contract, ContractTemplateError> {
+        let n = pks.len();
+        if n > MULTISIG_MAX_KEYS {
+            return Err(ContractTemplateError::TooManyKeys(n));
+        }
+        if k == 0 || k > n {
+            return Err(ContractTemplateError::InvalidThreshold { k, n });
+        }
+        let keys: Vec<SigmaProp> = pks.into_iter().map(SigmaProp::from).collect();
+        let expr: Expr =
+            Atleast::new(Constant::from(k as i32).into(), Constant::from(keys).into())?.into();
+        Self::from_template(expr)
+    }
+
+    /// Timed escrow contract. Funds are released with both buyer and seller signatures, and
+    /// the buyer alone can take them back after `deadline` height:
+    /// `buyer && (seller || HEIGHT > deadline)`
+    pub fn timed_escrow(
+        buyer: ProveDlog,
+        seller: ProveDlog,
+        deadline: u32,
+    ) -> Result<Contract, ContractTemplateError> {
+        let deadline_int = i32::try_from(deadline)
+            .map_err(|_| ContractTemplateError::InvalidDeadline(deadline))?;
+        let buyer: Expr = Constant::from(buyer).into();
+        let seller: Expr = Constant::from(seller).into();
+        let after_deadline: Expr = BoolToSigmaProp::try_build(
+            BinOp::new(
+                BinOpKind::Relation(RelationOp::Gt),
+                GlobalVars::Height.into(),
+                Constant::from(deadline_int).into(),
+            )?
+            .into(),
+        )?
+        .into();
+        let seller_or_deadline: Expr = SigmaOr::new(vec![seller, after_deadline])?.into();
+        Self::from_template(SigmaAnd::new(vec![buyer, seller_or_deadline])?.into())
+    }
+
+    fn from_template(expr: Expr) -> Result<Contract, ContractTemplateError> {
+        Ok(Contract::new(ErgoTree::new(
+            ErgoTreeHeader::v0(true),
+            &expr,
+        )?))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::rc::Rc;
+
+    use ergo_chain_types::blake2b256_hash;
+    use ergotree_interpreter::eval::context::Context;
+    use ergotree_interpreter::eval::env::Env;
+    use ergotree_interpreter::eval::reduce_to_crypto;
+    use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+    use ergotree_ir::sigma_protocol::sigma_boolean::SigmaConjecture;
+    use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProofOfKnowledgeTree;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::template_registry::TemplateHash;
+
+    use super::*;
+
+    fn reduce(contract: &Contract, height: u32) -> SigmaBoolean {
+        let mut ctx = force_any_val::<Context>();
+        ctx.height = height;
+        let expr = contract.ergo_tree().proposition().unwrap();
+        reduce_to_crypto(&expr, &Env::empty(), Rc::new(ctx))
+            .unwrap()
+            .sigma_prop
+    }
+
+    #[test]
+    fn multisig_threshold() {
+        let pks: Vec<ProveDlog> = (0..3).map(|_| force_any_val::<ProveDlog>()).collect();
+        assert_eq!(
+            Contract::multisig(0, pks.clone()),
+            Err(ContractTemplateError::InvalidThreshold { k: 0, n: 3 })
+        );
+        assert_eq!(
+            Contract::multisig(4, pks.clone()),
+            Err(ContractTemplateError::InvalidThreshold { k: 4, n: 3 })
+        );
+        let contract = Contract::multisig(2, pks).unwrap();
+        assert!(matches!(
+            reduce(&contract, 1),
+            SigmaBoolean::SigmaConjecture(SigmaConjecture::Cthreshold(ct)) if ct.k == 2
+        ));
+    }
+
+    #[test]
+    fn same_template_for_any_params() {
+        let a = Contract::timed_escrow(
+            force_any_val::<ProveDlog>(),
+            force_any_val::<ProveDlog>(),
+            100,
+        )
+        .unwrap();
+        let b = Contract::timed_escrow(
+            force_any_val::<ProveDlog>(),
+            force_any_val::<ProveDlog>(),
+            200,
+        )
+        .unwrap();
+        assert_eq!(
+            TemplateHash::from_ergo_tree(&a.ergo_tree()).unwrap(),
+            TemplateHash::from_ergo_tree(&b.ergo_tree()).unwrap()
+        );
+        let p1 = Contract::pin_lock(blake2b256_hash(b"1234")).unwrap();
+        let p2 = Contract::pin_lock(blake2b256_hash(b"4321")).unwrap();
+        assert_ne!(p1, p2);
+        assert_eq!(
+            TemplateHash::from_ergo_tree(&p1.ergo_tree()).unwrap(),
+            TemplateHash::from_ergo_tree(&p2.ergo_tree()).unwrap()
+        );
+    }
+
+    #[test]
+    fn timed_escrow_deadline() {
+        let buyer = force_any_val::<ProveDlog>();
+        let seller = force_any_val::<ProveDlog>();
+        let contract = Contract::timed_escrow(buyer.clone(), seller, 100).unwrap();
+        // before the deadline both signatures are required
+        assert!(matches!(
+            reduce(&contract, 100),
+            SigmaBoolean::SigmaConjecture(SigmaConjecture::Cand(_))
+        ));
+        // after the deadline the buyer's signature is enough
+        assert_eq!(
+            reduce(&contract, 101),
+            SigmaBoolean::ProofOfKnowledge(SigmaProofOfKnowledgeTree::ProveDlog(buyer))
+        );
+        assert_eq!(
+            Contract::timed_escrow(force_any_val(), force_any_val(), u32::MAX),
+            Err(ContractTemplateError::InvalidDeadline(u32::MAX))
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Monetary settings and the emission schedule of the network
+//!
+//! Only the base emission curve is modelled, the EIP-27 re-emission (moving a part of the miners
+//! reward into the re-emission contract) is not taken into account.
+//!
+//! Of the genesis state boxes only the mainnet emission box is provided (it is derived from the
+//! monetary settings). The no-premine proof and founders boxes, as well as the testnet genesis
+//! boxes, are not included.
+
+use std::convert::TryFrom;
+
+use ergotree_ir::chain::address::NetworkPrefix;
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+use ergotree_ir::chain::tx_id::TxId;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::serialization::SigmaSerializable;
+use lazy_static::lazy_static;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+
+use crate::wallet::miner_fee::MINER_REWARD_DELAY;
+
+/// Number of nanoERGs in one ERG
+pub const COINS_IN_ONE_ERG: u64 = 1_000_000_000;
+
+/// Monetary settings of the network (sigmastate/node `MonetarySettings`)
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct MonetarySettings {
+    /// Number of blocks with the fixed emission rate
+    pub fixed_rate_period: u32,
+    /// Number of blocks in the emission epoch after the fixed rate period
+    pub epoch_length: u32,
+    /// Emission per block during the fixed rate period (nanoERGs)
+    pub fixed_rate: u64,
+    /// Emission per block reduction in every epoch after the fixed rate period (nanoERGs)
+    pub one_epoch_reduction: u64,
+    /// Number of blocks the miner's reward is locked for
+    pub miner_reward_delay: u32,
+    /// Founders reward per block during the fixed rate period (nanoERGs)
+    pub founders_initial_reward: u64,
+}
+
+/// Mainnet monetary settings
+pub const MAINNET_MONETARY_SETTINGS: MonetarySettings = MonetarySettings {
+    fixed_rate_period: 525_600,
+    epoch_length: 64_800,
+    fixed_rate: 75 * COINS_IN_ONE_ERG,
+    one_epoch_reduction: 3 * COINS_IN_ONE_ERG,
+    miner_reward_delay: MINER_REWARD_DELAY as u32,
+    founders_initial_reward: 75 * COINS_IN_ONE_ERG / 10,
+};
+
+/// Testnet monetary settings (same as the mainnet ones)
+pub const TESTNET_MONETARY_SETTINGS: MonetarySettings = MAINNET_MONETARY_SETTINGS;
+
+/// Base16 encoded serialized ErgoTree of the mainnet emission contract (the emission box script,
+/// kept by the emission box re-created in every block)
+pub const MAINNET_EMISSION_CONTRACT_BASE16_BYTES: &str = "101004020e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a7017300730110010204020404040004c0fd4f05808c82f5f6030580b8c9e5ae040580f882ad16040204c0944004c0f407040004000580f882ad16d19683030191a38cc7a7019683020193c2b2a57300007473017302830108cdeeac93a38cc7b2a573030001978302019683040193b1a5730493c2a7c2b2a573050093958fa3730673079973089c73097e9a730a9d99a3730b730c0599c1a7c1b2a5730d00938cc7b2a5730e0001a390c1a7730f";
+
+lazy_static! {
+    /// Mainnet genesis emission box holding all the miners' coins (spent by the emission
+    /// transaction of the block at height 1)
+    pub static ref MAINNET_GENESIS_EMISSION_BOX: ErgoBox =
+        #[allow(clippy::unwrap_used)]
+        ErgoBox::new(
+            BoxValue::try_from(MAINNET_MONETARY_SETTINGS.miners_coins_total()).unwrap(),
+            ErgoTree::sigma_parse_bytes(
+                &decode_base16(MAINNET_EMISSION_CONTRACT_BASE16_BYTES, DecodingMode::Strict).unwrap(),
+            )
+            .unwrap(),
+            None,
+            NonMandatoryRegisters::empty(),
+            0,
+            TxId::zero(),
+            0,
+        )
+        .unwrap();
+}
+
+/// Genesis emission box of the network (only the mainnet one is provided)
+pub fn genesis_emission_box(network_prefix: NetworkPrefix) -> Option<&'static ErgoBox> {
+    match network_prefix {
+        NetworkPrefix::Mainnet => Some(&MAINNET_GENESIS_EMISSION_BOX),
+        NetworkPrefix::Testnet => None,
+    }
+}
+
+impl MonetarySettings {
+    /// Monetary settings of the network
+    pub fn for_network(network_prefix: NetworkPrefix) -> MonetarySettings {
+        match network_prefix {
+            NetworkPrefix::Mainnet => MAINNET_MONETARY_SETTINGS,
+            NetworkPrefix::Testnet => TESTNET_MONETARY_SETTINGS,
+        }
+    }
+
+    /// Emission epoch of the block at `height` (0 for the fixed rate period)
+    fn epoch(&self, height: u32) -> u64 {
+        if height < self.fixed_rate_period {
+            0
+        } else {
+            1 + ((height - self.fixed_rate_period) / self.epoch_length) as u64
+        }
+    }
+
+    /// Number of nanoERGs emitted in the block at `height`
+    pub fn emission_at_height(&self, height: u32) -> u64 {
+        self.fixed_rate
+            .saturating_sub(self.one_epoch_reduction * self.epoch(height))
+    }
+
+    /// Founders reward (nanoERGs) in the block at `height`
+    pub fn founders_reward_at_height(&self, height: u32) -> u64 {
+        self.founders_initial_reward
+            .saturating_sub(self.one_epoch_reduction * self.epoch(height))
+    }
+
+    /// Miners reward (nanoERGs) in the block at `height`
+    pub fn miners_reward_at_height(&self, height: u32) -> u64 {
+        self.emission_at_height(height) - self.founders_reward_at_height(height)
+    }
+
+    /// Total number of nanoERGs issued in the blocks up to (and including) `height`
+    pub fn issued_coins_after_height(&self, height: u32) -> u64 {
+        if height < self.fixed_rate_period {
+            return self.fixed_rate * height as u64;
+        }
+        let fixed_rate_issued = self.fixed_rate * (self.fixed_rate_period - 1) as u64;
+        let epoch = self.epoch(height);
+        let full_epochs_issued: u64 = (1..epoch)
+            .map(|e| {
+                self.fixed_rate.saturating_sub(self.one_epoch_reduction * e)
+                    * self.epoch_length as u64
+            })
+            .sum();
+        let height_in_epoch = ((height - self.fixed_rate_period) % self.epoch_length) as u64 + 1;
+        fixed_rate_issued + full_epochs_issued + height_in_epoch * self.emission_at_height(height)
+    }
+
+    /// Height of the last block with the non-zero emission
+    pub fn blocks_total(&self) -> u32 {
+        let epochs = (self.fixed_rate / self.one_epoch_reduction) as u32;
+        self.fixed_rate_period + (epochs - 1) * self.epoch_length - 1
+    }
+
+    /// Total number of nanoERGs ever issued
+    pub fn coins_total(&self) -> u64 {
+        self.issued_coins_after_height(self.blocks_total())
+    }
+
+    /// Total number of nanoERGs ever paid to the founders
+    pub fn founders_coins_total(&self) -> u64 {
+        let fixed_rate_paid = self.founders_initial_reward * (self.fixed_rate_period - 1) as u64;
+        let epochs_paid: u64 = (1..)
+            .map(|e| {
+                self.founders_initial_reward
+                    .saturating_sub(self.one_epoch_reduction * e)
+            })
+            .take_while(|reward| *reward > 0)
+            .map(|reward| reward * self.epoch_length as u64)
+            .sum();
+        fixed_rate_paid + epochs_paid
+    }
+
+    /// Total number of nanoERGs ever paid to the miners (the value of the genesis emission box)
+    pub fn miners_coins_total(&self) -> u64 {
+        self.coins_total() - self.founders_coins_total()
+    }
+}
+
+/// Number of nanoERGs emitted in the block at `height` on the network
+pub fn emission_at_height(network_prefix: NetworkPrefix, height: u32) -> u64 {
+    MonetarySettings::for_network(network_prefix).emission_at_height(height)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emission_schedule() {
+        let s = MAINNET_MONETARY_SETTINGS;
+        assert_eq!(
+            emission_at_height(NetworkPrefix::Mainnet, 1),
+            75 * COINS_IN_ONE_ERG
+        );
+        assert_eq!(s.miners_reward_at_height(1), 67_500_000_000);
+        assert_eq!(s.founders_reward_at_height(1), 7_500_000_000);
+        assert_eq!(s.emission_at_height(525_599), 75 * COINS_IN_ONE_ERG);
+        assert_eq!(s.emission_at_height(525_600), 72 * COINS_IN_ONE_ERG);
+        assert_eq!(s.founders_reward_at_height(525_600), 4_500_000_000);
+        assert_eq!(
+            s.emission_at_height(525_600 + 64_800),
+            69 * COINS_IN_ONE_ERG
+        );
+        assert_eq!(s.founders_reward_at_height(525_600 + 2 * 64_800), 0);
+        assert_eq!(s.emission_at_height(s.blocks_total()), 3 * COINS_IN_ONE_ERG);
+        assert_eq!(s.emission_at_height(s.blocks_total() + 64_800), 0);
+    }
+
+    #[test]
+    fn test_issued_coins() {
+        let s = MAINNET_MONETARY_SETTINGS;
+        assert_eq!(s.issued_coins_after_height(10), 750 * COINS_IN_ONE_ERG);
+        assert_eq!(
+            s.issued_coins_after_height(525_600 + 1) - s.issued_coins_after_height(525_600),
+            s.emission_at_height(525_601)
+        );
+        assert_eq!(s.coins_total(), 97_739_925 * COINS_IN_ONE_ERG);
+        assert_eq!(
+            s.issued_coins_after_height(s.blocks_total() + 1_000_000),
+            s.coins_total()
+        );
+        assert_eq!(s.founders_coins_total(), 4_330_792_500_000_000);
+        assert_eq!(s.miners_coins_total(), 93_409_132_500_000_000);
+    }
+
+    #[test]
+    fn test_genesis_emission_box() {
+        let emission_box = genesis_emission_box(NetworkPrefix::Mainnet).unwrap();
+        // spent by the emission transaction of the block at height 1
+        assert_eq!(
+            String::from(emission_box.box_id()),
+            "b69575e11c5c43400bfead5976ee0d6245a1168396b2e2a4f384691f275d501c"
+        );
+        assert_eq!(*emission_box.value.as_u64(), 93_409_132_500_000_000);
+        assert_eq!(genesis_emission_box(NetworkPrefix::Testnet), None);
+    }
+}
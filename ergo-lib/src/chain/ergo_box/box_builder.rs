@@ -3,6 +3,7 @@
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 
+use ergo_chain_types::Parameters;
 use ergotree_ir::chain::address::AddressEncoderError;
 use ergotree_ir::chain::ergo_box::box_value::BoxValue;
 use ergotree_ir::chain::ergo_box::BoxTokens;
@@ -13,7 +14,7 @@ use ergotree_ir::chain::ergo_box::NonMandatoryRegistersError;
 use ergotree_ir::chain::token::Token;
 use ergotree_ir::ergo_tree::ErgoTree;
 use ergotree_ir::mir::constant::Constant;
-use ergotree_ir::serialization::{SigmaParsingError, SigmaSerializable, SigmaSerializationError};
+use ergotree_ir::serialization::{SigmaParsingError, SigmaSerializationError};
 use thiserror::Error;
 
 /// ErgoBoxCandidate builder errors
@@ -102,6 +103,11 @@ impl ErgoBoxCandidateBuilder {
         self.min_value_per_byte = new_min_value_per_byte;
     }
 
+    /// Set minimal value (per byte of the serialized box size) from the current system parameters
+    pub fn set_parameters(&mut self, parameters: &Parameters) {
+        self.min_value_per_byte = parameters.min_value_per_byte;
+    }
+
     /// Get minimal value (per byte of the serialized box size)
     pub fn min_box_value_per_byte(&self) -> u32 {
         self.min_value_per_byte
@@ -120,7 +126,7 @@ impl ErgoBoxCandidateBuilder {
     /// Calculate serialized box size(in bytes)
     pub fn calc_box_size_bytes(&self) -> Result<usize, ErgoBoxCandidateBuilderError> {
         let b = self.build_box_unchecked()?;
-        Ok(b.sigma_serialize_bytes()?.len())
+        Ok(b.serialized_size()?)
     }
 
     /// Calculate minimal box value for the current box serialized size(in bytes)
@@ -252,7 +258,7 @@ impl ErgoBoxCandidateBuilder {
 
     fn build_box(&self) -> Result<ErgoBoxCandidate, ErgoBoxCandidateBuilderError> {
         let b = self.build_box_unchecked()?;
-        let box_size_bytes = b.sigma_serialize_bytes()?.len();
+        let box_size_bytes = b.serialized_size()?;
 
         // Won't be overflowing an i64, so unwrap is safe.
         #[allow(clippy::unwrap_used)]
@@ -282,6 +288,7 @@ mod tests {
 
     use ergotree_ir::base16_str::Base16Str;
     use ergotree_ir::chain::token::TokenId;
+    use ergotree_ir::serialization::SigmaSerializable;
     use sigma_test_util::force_any_val;
     use NonMandatoryRegisterId::*;
 
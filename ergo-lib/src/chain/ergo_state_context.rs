@@ -1,17 +1,24 @@
 //! Blockchain state
-use ergo_chain_types::{Header, PreHeader};
+use ergo_chain_types::{Header, Parameters, PreHeader};
+use thiserror::Error;
 
 /// Fixed number of last block headers in descending order (first header is the newest one)
 pub type Headers = [Header; 10];
 
 /// Blockchain state (last headers, etc.)
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct ErgoStateContext {
     /// Block header with the current `spendingTransaction`, that can be predicted
     /// by a miner before it's formation
+    #[cfg_attr(feature = "json", serde(rename = "preHeader"))]
     pub pre_header: PreHeader,
     /// Fixed number of last block headers in descending order (first header is the newest one)
+    #[cfg_attr(feature = "json", serde(rename = "headers"))]
     pub headers: Headers,
+    /// System parameters in effect (the defaults unless set with [`Self::with_parameters`])
+    #[cfg_attr(feature = "json", serde(rename = "parameters", default))]
+    parameters: Parameters,
 }
 
 impl ErgoStateContext {
@@ -20,10 +27,112 @@ impl ErgoStateContext {
         ErgoStateContext {
             pre_header,
             headers,
+            parameters: Parameters::default(),
         }
     }
+
+    /// Return a new context with the given system parameters
+    pub fn with_parameters(self, parameters: Parameters) -> ErgoStateContext {
+        ErgoStateContext { parameters, ..self }
+    }
+
+    /// System parameters in effect
+    pub fn parameters(&self) -> &Parameters {
+        &self.parameters
+    }
+
+    /// Context for the next block from the last 10 block headers of the best chain given in any
+    /// order (e.g. as returned by the node `/blocks/lastHeaders/10` endpoint, the oldest first).
+    /// The headers must be linked by the parent ids. The pre-header is derived from the newest
+    /// header (its id as the parent id, the next height, the same version, difficulty, miner and
+    /// votes) with the timestamp of the newest header (set `pre_header.timestamp` to the expected
+    /// block time if needed).
+    pub fn from_last_headers(
+        mut headers: Vec<Header>,
+    ) -> Result<ErgoStateContext, ErgoStateContextError> {
+        headers.sort_by_key(|h| std::cmp::Reverse(h.height));
+        if let Some(w) = headers
+            .windows(2)
+            .find(|w| w[0].parent_id != w[1].id || w[1].height.checked_add(1) != Some(w[0].height))
+        {
+            return Err(ErgoStateContextError::HeadersNotLinked {
+                height: w[0].height,
+            });
+        }
+        let headers: Headers = headers
+            .try_into()
+            .map_err(|h: Vec<Header>| ErgoStateContextError::InvalidHeadersCount(h.len()))?;
+        let last_header = &headers[0];
+        let height = last_header
+            .height
+            .checked_add(1)
+            .ok_or(ErgoStateContextError::HeightOverflow(last_header.height))?;
+        let pre_header = PreHeader {
+            version: last_header.version,
+            parent_id: last_header.id,
+            timestamp: last_header.timestamp,
+            n_bits: last_header.n_bits,
+            height,
+            miner_pk: last_header.autolykos_solution.miner_pk.clone(),
+            votes: last_header.votes.clone(),
+        };
+        Ok(ErgoStateContext::new(pre_header, headers))
+    }
+}
+
+/// Errors on building [`ErgoStateContext`] from the last block headers
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum ErgoStateContextError {
+    /// Number of headers is not 10
+    #[error("Expected 10 headers, got {0}")]
+    InvalidHeadersCount(usize),
+    /// Header is not linked to the previous one (by the parent id and height)
+    #[error("Header at height {height} is not linked to the previous header")]
+    HeadersNotLinked {
+        /// Height of the header
+        height: u32,
+    },
+    /// Height of the newest header is the maximum one, so there is no next block
+    #[error("No next block after the header at height {0}")]
+    HeightOverflow(u32),
 }
 
+#[cfg(feature = "rest")]
+mod rest {
+    use ergo_rest::api::node::get_info;
+    use ergo_rest::api::node::get_last_headers;
+    use ergo_rest::NodeConf;
+    use ergo_rest::NodeError;
+
+    use super::*;
+
+    /// Errors on fetching [`ErgoStateContext`] from the node
+    #[derive(Error, Debug)]
+    pub enum StateContextFetchError {
+        /// Node request failed
+        #[error("Node error: {0}")]
+        NodeError(#[from] NodeError),
+        /// Node returned invalid headers
+        #[error("Invalid headers: {0}")]
+        InvalidHeaders(#[from] ErgoStateContextError),
+    }
+
+    /// Fetch the last 10 block headers and the current system parameters from the node and build
+    /// the context for the next block (see [`ErgoStateContext::from_last_headers`])
+    pub async fn fetch_ergo_state_context(
+        node: NodeConf,
+    ) -> Result<ErgoStateContext, StateContextFetchError> {
+        let headers = get_last_headers(node, 10).await?;
+        let parameters = get_info(node).await?.parameters.unwrap_or_default();
+        Ok(ErgoStateContext::from_last_headers(headers)?.with_parameters(parameters))
+    }
+}
+
+#[cfg(feature = "rest")]
+pub use rest::fetch_ergo_state_context;
+#[cfg(feature = "rest")]
+pub use rest::StateContextFetchError;
+
 #[cfg(feature = "arbitrary")]
 mod arbitrary {
     use super::*;
@@ -40,3 +149,105 @@ mod arbitrary {
         }
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "json")]
+#[allow(clippy::panic, clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use ergo_chain_types::arb_header_chain;
+    use ergo_chain_types::ArbHeaderParams;
+    use proptest::prelude::*;
+
+    // node `/blocks/lastHeaders/10` response (the oldest header first)
+    const NODE_LAST_HEADERS: &str = include_str!("../../tests/fixtures/node_last_headers.json");
+
+    #[test]
+    fn from_node_last_headers() {
+        let headers: Vec<Header> = serde_json::from_str(NODE_LAST_HEADERS).unwrap();
+        let ctx = ErgoStateContext::from_last_headers(headers).unwrap();
+        assert_eq!(ctx.headers[0].height, 141540);
+        assert_eq!(ctx.headers[9].height, 141531);
+        assert_eq!(ctx.pre_header.height, 141541);
+
+        let json = serde_json::to_value(&ctx).unwrap();
+        let newest = &json["headers"][0];
+        assert_eq!(
+            newest["id"],
+            "682541dbc65155ac587f6e89a421455645d5203089eac6cdcecb06291c629380"
+        );
+        assert_eq!(
+            newest["parentId"],
+            "576faa14bf2d7412b219a054a0382f5da5173373dd486030128ea2ae571b90c5"
+        );
+        assert_eq!(
+            newest["stateRoot"],
+            "17c241cc696589d00f1addba7ba424263b38cc582d4639ffd8257668457eeb4615"
+        );
+        assert_eq!(
+            newest["extensionHash"],
+            "4e47fa1146fcbc6afeef876c66611074e7e7bbdf5d664ce8c604eccdc5a8832e"
+        );
+        assert_eq!(newest["timestamp"], 1641546078256u64);
+        assert_eq!(newest["nBits"], 84554043);
+        assert_eq!(newest["version"], 2);
+        assert_eq!(newest["votes"], "000000");
+        assert_eq!(
+            newest["powSolutions"]["pk"],
+            "030f484a917e618df59d9738d94804448b299eaefd552f6a67f66223faacca09dd"
+        );
+        assert_eq!(newest["powSolutions"]["n"], "2267e2103039511c");
+
+        let pre_header = &json["preHeader"];
+        assert_eq!(pre_header["parentId"], newest["id"]);
+        assert_eq!(pre_header["minerPk"], newest["powSolutions"]["pk"]);
+        assert_eq!(pre_header["height"], 141541);
+        assert_eq!(pre_header["nBits"], 84554043);
+        assert_eq!(pre_header["votes"], "000000");
+
+        assert_eq!(
+            serde_json::from_value::<ErgoStateContext>(json).unwrap(),
+            ctx
+        );
+
+        let mut headers: Vec<Header> = serde_json::from_str(NODE_LAST_HEADERS).unwrap();
+        headers
+            .iter_mut()
+            .for_each(|h| h.height = u32::MAX - (141540 - h.height));
+        assert_eq!(
+            ErgoStateContext::from_last_headers(headers),
+            Err(ErgoStateContextError::HeightOverflow(u32::MAX))
+        );
+    }
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn from_last_headers(headers in arb_header_chain(10, ArbHeaderParams::default())) {
+            let mut oldest_first = headers.clone();
+            oldest_first.reverse();
+            let ctx = ErgoStateContext::from_last_headers(oldest_first).unwrap();
+            prop_assert_eq![ctx.headers.to_vec(), headers.clone()];
+            prop_assert_eq![ctx.pre_header.parent_id, headers[0].id];
+            prop_assert_eq![ctx.pre_header.height, headers[0].height + 1];
+            prop_assert_eq![
+                ErgoStateContext::from_last_headers(headers[..9].to_vec()),
+                Err(ErgoStateContextError::InvalidHeadersCount(9))
+            ];
+            let mut gap = headers.clone();
+            gap.remove(5);
+            prop_assert_eq![
+                ErgoStateContext::from_last_headers(gap),
+                Err(ErgoStateContextError::HeadersNotLinked { height: headers[4].height })
+            ];
+        }
+
+        #[test]
+        fn json_roundtrip(v in any::<ErgoStateContext>()) {
+            let json = serde_json::to_string(&v).unwrap();
+            prop_assert_eq![serde_json::from_str::<ErgoStateContext>(&json).unwrap(), v];
+        }
+    }
+}
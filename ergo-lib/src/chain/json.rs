@@ -5,7 +5,10 @@ use serde::{Deserialize, Serialize};
 use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
 
 pub(crate) mod context_extension;
+pub mod dialect;
+pub mod eip12;
 pub(crate) mod hint;
+pub(crate) mod scan_predicate;
 pub(crate) mod transaction;
 
 /// Serde remote type
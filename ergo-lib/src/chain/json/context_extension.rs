@@ -1,7 +1,7 @@
 use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
-use ergotree_ir::{mir::constant::Constant, serialization::SigmaSerializable};
+use ergotree_ir::mir::constant::Constant;
 use indexmap::IndexMap;
-use serde::{ser::SerializeMap, Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 
 #[cfg_attr(
     feature = "json",
@@ -20,15 +20,10 @@ impl Serialize for ContextExtensionSerde {
     where
         S: serde::Serializer,
     {
-        use serde::ser::Error;
-        let mut map = serializer.serialize_map(Some(self.values.len()))?;
-        for (k, v) in &self.values {
-            map.serialize_entry(
-                &format!("{}", k),
-                &base16::encode_lower(&v.sigma_serialize_bytes().map_err(Error::custom)?),
-            )?;
+        ContextExtension {
+            values: self.values.clone(),
         }
-        map.end()
+        .serialize(serializer)
     }
 }
 
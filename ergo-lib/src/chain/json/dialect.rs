@@ -0,0 +1,219 @@
+//! Node API and Explorer API dialects of the transaction JSON.
+//!
+//! The node dialect is the default serde representation. The Explorer dialect differs in:
+//! - input spending proof is a hex string (context extension is in the input `extension` field);
+//! - assets carry their `index` in the box;
+//! - registers are objects with `serializedValue` instead of hex strings;
+//! - empty collections may be `null`.
+
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::chain::transaction::Transaction;
+
+/// JSON dialect of the transaction
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum JsonDialect {
+    /// Node REST API
+    Node,
+    /// Explorer API
+    Explorer,
+}
+
+impl Transaction {
+    /// Transaction JSON in the given dialect
+    pub fn to_json_value(&self, dialect: JsonDialect) -> Result<Value, serde_json::Error> {
+        let mut json = serde_json::to_value(self)?;
+        if dialect == JsonDialect::Explorer {
+            tx_to_explorer(&mut json);
+        }
+        Ok(json)
+    }
+
+    /// Parse the transaction JSON in the given dialect
+    pub fn from_json_value(
+        mut json: Value,
+        dialect: JsonDialect,
+    ) -> Result<Transaction, serde_json::Error> {
+        if dialect == JsonDialect::Explorer {
+            tx_from_explorer(&mut json);
+        }
+        serde_json::from_value(json)
+    }
+}
+
+fn items_mut<'a>(json: &'a mut Value, field: &str) -> impl Iterator<Item = &'a mut Value> {
+    json.get_mut(field)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+}
+
+fn tx_to_explorer(tx: &mut Value) {
+    for input in items_mut(tx, "inputs") {
+        let proof = input
+            .get_mut("spendingProof")
+            .and_then(Value::as_object_mut)
+            .map(std::mem::take);
+        if let (Some(mut proof), Some(input)) = (proof, input.as_object_mut()) {
+            let proof_bytes = proof.remove("proofBytes").unwrap_or_default();
+            input.insert("spendingProof".to_string(), proof_bytes);
+            match proof.remove("extension") {
+                Some(Value::Object(ext)) if ext.is_empty() => (),
+                Some(ext) => {
+                    input.insert("extension".to_string(), ext);
+                }
+                None => (),
+            }
+        }
+    }
+    for output in items_mut(tx, "outputs") {
+        for (index, asset) in items_mut(output, "assets").enumerate() {
+            if let Some(asset) = asset.as_object_mut() {
+                asset.insert("index".to_string(), index.into());
+            }
+        }
+        if let Some(regs) = output
+            .get_mut("additionalRegisters")
+            .and_then(Value::as_object_mut)
+        {
+            for value in regs.values_mut() {
+                let mut reg = Map::new();
+                reg.insert("serializedValue".to_string(), value.take());
+                *value = reg.into();
+            }
+        }
+    }
+}
+
+fn tx_from_explorer(tx: &mut Value) {
+    replace_null(tx, "dataInputs", Value::Array(Vec::new()));
+    for input in items_mut(tx, "inputs") {
+        if let Some(input) = input.as_object_mut() {
+            let extension = input
+                .remove("extension")
+                .filter(|ext| !ext.is_null())
+                .unwrap_or_else(|| Map::new().into());
+            let proof_bytes = match input.remove("spendingProof") {
+                Some(Value::Object(proof)) => {
+                    input.insert("spendingProof".to_string(), proof.into());
+                    continue;
+                }
+                Some(Value::Null) | None => Value::String(String::new()),
+                Some(proof_bytes) => proof_bytes,
+            };
+            let mut proof = Map::new();
+            proof.insert("proofBytes".to_string(), proof_bytes);
+            proof.insert("extension".to_string(), extension);
+            input.insert("spendingProof".to_string(), proof.into());
+        }
+    }
+    for output in items_mut(tx, "outputs") {
+        replace_null(output, "assets", Value::Array(Vec::new()));
+        replace_null(output, "additionalRegisters", Map::new().into());
+    }
+}
+
+/// Replace the missing or `null` field with the given value
+fn replace_null(json: &mut Value, field: &str, value: Value) {
+    if let Some(obj) = json.as_object_mut() {
+        let entry = obj.entry(field).or_insert(Value::Null);
+        if entry.is_null() {
+            *entry = value;
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use ergotree_ir::serialization::SigmaSerializable;
+    use proptest::prelude::*;
+
+    #[test]
+    fn parse_explorer_api_tx() {
+        // Explorer API v1 `/api/v1/transactions/{id}` layout of the mainnet emission tx
+        // (inputs with the spent box fields, null proof, addresses and the spending status of
+        // the outputs)
+        let json = r#"
+        {
+          "id": "9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9",
+          "inputs": [
+            {
+              "boxId": "9126af0675056b80d1fda7af9bf658464dbfa0b128afca7bf7dae18c27fe8456",
+              "value": 74187832500000000,
+              "index": 0,
+              "spendingProof": null,
+              "ergoTree": "101004020e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a7017300730110010204020404040004c0fd4f05808c82f5f6030580b8c9e5ae040580f882ad16040204c0944004c0f407040004000580f882ad16d19683030191a38cc7a7019683020193c2b2a57300007473017302830108cdeeac93a38cc7b2a573030001978302019683040193b1a5730493c2a7c2b2a573050093958fa3730673079973089c73097e9a730a9d99a3730b730c0599c1a7c1b2a5730d00938cc7b2a5730e0001a390c1a7730f",
+              "address": "2Z4YBkDsDvQj8BX7xiySFewjitqp2ge9c99jfes2whbtKitZTxdBYqbrVZUvZvKv6aqn9by4kp3LE1c26LCyosFnVnm6b6U1JYvWpYmL2ZnixJbXLjWAWuBThV1D6dLpqZJYQHYDznJCk49g5TUiS4q8khpag2aNmHwREV7JSsypHdHLgJT7MGaw51aJfNubyzSKxZ4AJXFS27EfXwyCLzW1K6GVqwkJtCoPvrcLqmqwacAWJPkmh78nke9H4oT88XmSbRt2n9aWZjosiZCafZ4osUDxmZcc5QVEeTWn8drSraY3eFKe8Mu9MSCcVU",
+              "assets": [],
+              "additionalRegisters": {}
+            }
+          ],
+          "dataInputs": [],
+          "outputs": [
+            {
+              "boxId": "b979c439dc698ce5e823b21c722a6e23721af010e4df8c72de0bfd0c3d9ccf6b",
+              "transactionId": "9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9",
+              "value": 74187765000000000,
+              "index": 0,
+              "creationHeight": 284761,
+              "ergoTree": "101004020e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a7017300730110010204020404040004c0fd4f05808c82f5f6030580b8c9e5ae040580f882ad16040204c0944004c0f407040004000580f882ad16d19683030191a38cc7a7019683020193c2b2a57300007473017302830108cdeeac93a38cc7b2a573030001978302019683040193b1a5730493c2a7c2b2a573050093958fa3730673079973089c73097e9a730a9d99a3730b730c0599c1a7c1b2a5730d00938cc7b2a5730e0001a390c1a7730f",
+              "address": "2Z4YBkDsDvQj8BX7xiySFewjitqp2ge9c99jfes2whbtKitZTxdBYqbrVZUvZvKv6aqn9by4kp3LE1c26LCyosFnVnm6b6U1JYvWpYmL2ZnixJbXLjWAWuBThV1D6dLpqZJYQHYDznJCk49g5TUiS4q8khpag2aNmHwREV7JSsypHdHLgJT7MGaw51aJfNubyzSKxZ4AJXFS27EfXwyCLzW1K6GVqwkJtCoPvrcLqmqwacAWJPkmh78nke9H4oT88XmSbRt2n9aWZjosiZCafZ4osUDxmZcc5QVEeTWn8drSraY3eFKe8Mu9MSCcVU",
+              "assets": [],
+              "additionalRegisters": {},
+              "spentTransactionId": null,
+              "mainChain": true
+            },
+            {
+              "boxId": "e56847ed19b3dc6b72828fcfb992fdf7310828cf291221269b7ffc72fd66706e",
+              "transactionId": "9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9",
+              "value": 67500000000,
+              "index": 1,
+              "creationHeight": 284761,
+              "ergoTree": "100204a00b08cd021dde34603426402615658f1d970cfa7c7bd92ac81a8b16eeebff264d59ce4604ea02d192a39a8cc7a70173007301",
+              "address": "88dhgzEuTXaQLG2u9aud6SkPCGyXvw8mQWLCWfkv6wwuC9X9gdzELR9mt2hHQaM654aamzscP8r45NsJ",
+              "assets": null,
+              "additionalRegisters": null,
+              "spentTransactionId": null,
+              "mainChain": true
+            }
+          ],
+          "size": 345
+        }"#;
+        let tx = Transaction::from_json_value(
+            serde_json::from_str(json).unwrap(),
+            JsonDialect::Explorer,
+        )
+        .unwrap();
+        let tx_id: String = tx.id().into();
+        assert_eq!(
+            tx_id,
+            "9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9"
+        );
+        assert_eq!(tx.inputs.len(), 1);
+        assert_eq!(tx.outputs.len(), 2);
+        assert_eq!(tx.sigma_serialize_bytes().unwrap().len(), 345);
+        assert_eq!(*tx.outputs.get(1).unwrap().value.as_u64(), 67500000000);
+        // no extension is rendered for the empty proof
+        let json = tx.to_json_value(JsonDialect::Explorer).unwrap();
+        assert_eq!(json["inputs"][0]["spendingProof"], "");
+        assert!(json["inputs"][0].get("extension").is_none());
+    }
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn explorer_dialect_roundtrip(t in any::<Transaction>()) {
+            let json = t.to_json_value(JsonDialect::Explorer).unwrap();
+            prop_assert!(json["inputs"][0]["spendingProof"].is_string());
+            let parsed = Transaction::from_json_value(json, JsonDialect::Explorer).unwrap();
+            prop_assert_eq![parsed, t.clone()];
+            let json = t.to_json_value(JsonDialect::Node).unwrap();
+            prop_assert_eq![Transaction::from_json_value(json, JsonDialect::Node).unwrap(), t];
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! JSON representation according to EIP-12 <https://github.com/ergoplatform/eips/pull/23>
+//! (dApp connector), box values and token amounts are encoded as strings.
+//! On parsing, amounts encoded as numbers are accepted as well.
+
+use std::convert::TryFrom;
+use std::convert::TryInto;
+
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::ergo_box::box_value::BoxValueError;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::BoxTokens;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenAmount;
+use ergotree_ir::chain::token::TokenAmountError;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::chain::tx_id::TxId;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::serialization::SigmaSerializationError;
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::chain::transaction::unsigned::UnsignedTransaction;
+use crate::chain::transaction::DataInput;
+use crate::chain::transaction::Input;
+use crate::chain::transaction::Transaction;
+use crate::chain::transaction::TransactionError;
+use crate::chain::transaction::UnsignedInput;
+
+/// Errors on converting EIP-12 JSON types
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum Eip12JsonError {
+    /// Number is not a valid unsigned 64-bit integer
+    #[error("Invalid number: {0}")]
+    InvalidNumber(String),
+    /// Invalid box value
+    #[error("BoxValue error: {0}")]
+    BoxValue(#[from] BoxValueError),
+    /// Invalid token amount
+    #[error("Token amount error: {0}")]
+    TokenAmount(#[from] TokenAmountError),
+    /// Too many tokens in the box
+    #[error("More than ErgoBox::MAX_TOKENS_COUNT tokens are not allowed in a box")]
+    TooManyTokens,
+    /// Box id in JSON does not match the box contents
+    #[error("Box id parsed from JSON {json} differs from calculated {actual}")]
+    InvalidBoxId {
+        /// Box id parsed from JSON
+        json: BoxId,
+        /// Box id calculated from the box contents
+        actual: BoxId,
+    },
+    /// Transaction id in JSON does not match the transaction contents
+    #[error("Tx id parsed from JSON {json} differs from calculated {actual}")]
+    InvalidTxId {
+        /// Transaction id parsed from JSON
+        json: TxId,
+        /// Transaction id calculated from the transaction contents
+        actual: TxId,
+    },
+    /// Serialization error (calculating the ids)
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SigmaSerializationError),
+    /// Invalid transaction
+    #[error("Tx error: {0}")]
+    TransactionError(#[from] TransactionError),
+}
+
+/// Box value in nanoERGs encoded as string
+#[serde_with::serde_as]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct BoxValueJsonEip12(
+    #[serde_as(as = "serde_with::PickFirst<(serde_with::DisplayFromStr, _)>")] serde_json::Number,
+);
+
+impl From<BoxValue> for BoxValueJsonEip12 {
+    fn from(v: BoxValue) -> Self {
+        BoxValueJsonEip12(serde_json::Number::from(*v.as_u64()))
+    }
+}
+
+impl TryFrom<BoxValueJsonEip12> for BoxValue {
+    type Error = Eip12JsonError;
+    fn try_from(v: BoxValueJsonEip12) -> Result<Self, Self::Error> {
+        Ok(BoxValue::try_from(as_u64(&v.0)?)?)
+    }
+}
+
+/// Token amount encoded as string
+#[serde_with::serde_as]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TokenAmountJsonEip12(
+    #[serde_as(as = "serde_with::PickFirst<(serde_with::DisplayFromStr, _)>")] serde_json::Number,
+);
+
+impl From<TokenAmount> for TokenAmountJsonEip12 {
+    fn from(v: TokenAmount) -> Self {
+        TokenAmountJsonEip12(serde_json::Number::from(*v.as_u64()))
+    }
+}
+
+impl TryFrom<TokenAmountJsonEip12> for TokenAmount {
+    type Error = Eip12JsonError;
+    fn try_from(v: TokenAmountJsonEip12) -> Result<Self, Self::Error> {
+        Ok(TokenAmount::try_from(as_u64(&v.0)?)?)
+    }
+}
+
+fn as_u64(n: &serde_json::Number) -> Result<u64, Eip12JsonError> {
+    n.as_u64()
+        .ok_or_else(|| Eip12JsonError::InvalidNumber(n.to_string()))
+}
+
+/// Token id paired with its amount
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TokenJsonEip12 {
+    /// token id
+    #[serde(rename = "tokenId")]
+    pub token_id: TokenId,
+    /// token amount
+    #[serde(rename = "amount")]
+    pub amount: TokenAmountJsonEip12,
+}
+
+impl From<Token> for TokenJsonEip12 {
+    fn from(t: Token) -> Self {
+        TokenJsonEip12 {
+            token_id: t.token_id,
+            amount: t.amount.into(),
+        }
+    }
+}
+
+impl TryFrom<TokenJsonEip12> for Token {
+    type Error = Eip12JsonError;
+    fn try_from(t: TokenJsonEip12) -> Result<Self, Self::Error> {
+        Ok(Token {
+            token_id: t.token_id,
+            amount: t.amount.try_into()?,
+        })
+    }
+}
+
+fn tokens_to_json(tokens: Option<BoxTokens>) -> Vec<TokenJsonEip12> {
+    tokens.into_iter().flatten().map(Into::into).collect()
+}
+
+fn tokens_from_json(tokens: Vec<TokenJsonEip12>) -> Result<Option<BoxTokens>, Eip12JsonError> {
+    let tokens = tokens
+        .into_iter()
+        .map(Token::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    if tokens.is_empty() {
+        Ok(None)
+    } else {
+        BoxTokens::from_vec(tokens)
+            .map(Some)
+            .map_err(|_| Eip12JsonError::TooManyTokens)
+    }
+}
+
+/// Box with the value and token amounts encoded as strings
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ErgoBoxJsonEip12 {
+    /// box id (calculated if missing)
+    #[serde(rename = "boxId", alias = "id", default)]
+    pub box_id: Option<BoxId>,
+    /// amount of money associated with the box
+    #[serde(rename = "value")]
+    pub value: BoxValueJsonEip12,
+    /// guarding script, which should be evaluated to true in order to open this box
+    #[serde(rename = "ergoTree", with = "ergotree_ir::chain::json::ergo_tree")]
+    pub ergo_tree: ErgoTree,
+    /// secondary tokens the box contains
+    #[serde(rename = "assets", default)]
+    pub tokens: Vec<TokenJsonEip12>,
+    ///  additional registers the box can carry over
+    #[serde(rename = "additionalRegisters")]
+    pub additional_registers: NonMandatoryRegisters,
+    /// height when a transaction containing the box was created.
+    #[serde(rename = "creationHeight")]
+    pub creation_height: u32,
+    /// id of transaction which created the box
+    #[serde(rename = "transactionId", alias = "txId")]
+    pub transaction_id: TxId,
+    /// number of box (from 0 to total number of boxes the transaction with transactionId created - 1)
+    #[serde(rename = "index")]
+    pub index: u16,
+}
+
+impl From<ErgoBox> for ErgoBoxJsonEip12 {
+    fn from(b: ErgoBox) -> Self {
+        ErgoBoxJsonEip12 {
+            box_id: Some(b.box_id()),
+            value: b.value.into(),
+            ergo_tree: b.ergo_tree,
+            tokens: tokens_to_json(b.tokens),
+            additional_registers: b.additional_registers,
+            creation_height: b.creation_height,
+            transaction_id: b.transaction_id,
+            index: b.index,
+        }
+    }
+}
+
+impl TryFrom<ErgoBoxJsonEip12> for ErgoBox {
+    type Error = Eip12JsonError;
+    fn try_from(b: ErgoBoxJsonEip12) -> Result<Self, Self::Error> {
+        let ergo_box = ErgoBox::new(
+            b.value.try_into()?,
+            b.ergo_tree,
+            tokens_from_json(b.tokens)?,
+            b.additional_registers,
+            b.creation_height,
+            b.transaction_id,
+            b.index,
+        )?;
+        match b.box_id {
+            Some(box_id) if box_id != ergo_box.box_id() => Err(Eip12JsonError::InvalidBoxId {
+                json: box_id,
+                actual: ergo_box.box_id(),
+            }),
+            _ => Ok(ergo_box),
+        }
+    }
+}
+
+/// Box candidate with the value and token amounts encoded as strings
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ErgoBoxCandidateJsonEip12 {
+    /// amount of money associated with the box
+    #[serde(rename = "value")]
+    pub value: BoxValueJsonEip12,
+    /// guarding script, which should be evaluated to true in order to open this box
+    #[serde(rename = "ergoTree", with = "ergotree_ir::chain::json::ergo_tree")]
+    pub ergo_tree: ErgoTree,
+    /// secondary tokens the box contains
+    #[serde(rename = "assets", default)]
+    pub tokens: Vec<TokenJsonEip12>,
+    ///  additional registers the box can carry over
+    #[serde(rename = "additionalRegisters")]
+    pub additional_registers: NonMandatoryRegisters,
+    /// height when a transaction containing the box was created.
+    #[serde(rename = "creationHeight")]
+    pub creation_height: u32,
+}
+
+impl From<ErgoBoxCandidate> for ErgoBoxCandidateJsonEip12 {
+    fn from(b: ErgoBoxCandidate) -> Self {
+        ErgoBoxCandidateJsonEip12 {
+            value: b.value.into(),
+            ergo_tree: b.ergo_tree,
+            tokens: tokens_to_json(b.tokens),
+            additional_registers: b.additional_registers,
+            creation_height: b.creation_height,
+        }
+    }
+}
+
+impl TryFrom<ErgoBoxCandidateJsonEip12> for ErgoBoxCandidate {
+    type Error = Eip12JsonError;
+    fn try_from(b: ErgoBoxCandidateJsonEip12) -> Result<Self, Self::Error> {
+        Ok(ErgoBoxCandidate {
+            value: b.value.try_into()?,
+            ergo_tree: b.ergo_tree,
+            tokens: tokens_from_json(b.tokens)?,
+            additional_registers: b.additional_registers,
+            creation_height: b.creation_height,
+        })
+    }
+}
+
+/// Signed transaction with the box values and token amounts encoded as strings
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TransactionJsonEip12 {
+    /// transaction id (checked on parsing if present)
+    #[serde(rename = "id", default)]
+    pub tx_id: Option<TxId>,
+    /// inputs, that will be spent by this transaction.
+    #[serde(rename = "inputs")]
+    pub inputs: Vec<Input>,
+    /// inputs, that are not going to be spent by transaction, but will be reachable from inputs
+    /// scripts.
+    #[serde(rename = "dataInputs", default)]
+    pub data_inputs: Vec<DataInput>,
+    /// boxes created by this transaction
+    #[serde(rename = "outputs")]
+    pub outputs: Vec<ErgoBoxJsonEip12>,
+}
+
+impl From<Transaction> for TransactionJsonEip12 {
+    fn from(t: Transaction) -> Self {
+        TransactionJsonEip12 {
+            tx_id: Some(t.id()),
+            inputs: t.inputs.as_vec().clone(),
+            data_inputs: t
+                .data_inputs
+                .map(|di| di.as_vec().clone())
+                .unwrap_or_default(),
+            outputs: t.outputs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<TransactionJsonEip12> for Transaction {
+    type Error = Eip12JsonError;
+    fn try_from(t: TransactionJsonEip12) -> Result<Self, Self::Error> {
+        let output_candidates = t
+            .outputs
+            .into_iter()
+            .map(|b| ErgoBox::try_from(b).map(ErgoBoxCandidate::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        let tx = Transaction::new_from_vec(t.inputs, t.data_inputs, output_candidates)?;
+        match t.tx_id {
+            Some(tx_id) if tx_id != tx.id() => Err(Eip12JsonError::InvalidTxId {
+                json: tx_id,
+                actual: tx.id(),
+            }),
+            _ => Ok(tx),
+        }
+    }
+}
+
+/// Unsigned transaction with the box values and token amounts encoded as strings.
+/// EIP-12 unsigned inputs carry the full box, only the box id and the context extension are used.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct UnsignedTransactionJsonEip12 {
+    /// unsigned inputs, that will be spent by this transaction.
+    #[serde(rename = "inputs")]
+    pub inputs: Vec<UnsignedInput>,
+    /// inputs, that are not going to be spent by transaction, but will be reachable from inputs
+    /// scripts.
+    #[serde(rename = "dataInputs", default)]
+    pub data_inputs: Vec<DataInput>,
+    /// box candidates to be created by this transaction
+    #[serde(rename = "outputs")]
+    pub outputs: Vec<ErgoBoxCandidateJsonEip12>,
+}
+
+impl From<UnsignedTransaction> for UnsignedTransactionJsonEip12 {
+    fn from(t: UnsignedTransaction) -> Self {
+        UnsignedTransactionJsonEip12 {
+            inputs: t.inputs.as_vec().clone(),
+            data_inputs: t
+                .data_inputs
+                .map(|di| di.as_vec().clone())
+                .unwrap_or_default(),
+            outputs: t
+                .output_candidates
+                .as_vec()
+                .iter()
+                .cloned()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<UnsignedTransactionJsonEip12> for UnsignedTransaction {
+    type Error = Eip12JsonError;
+    fn try_from(t: UnsignedTransactionJsonEip12) -> Result<Self, Self::Error> {
+        let output_candidates = t
+            .outputs
+            .into_iter()
+            .map(ErgoBoxCandidate::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(UnsignedTransaction::new_from_vec(
+            t.inputs,
+            t.data_inputs,
+            output_candidates,
+        )?)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn tx_roundtrip(t in any::<Transaction>()) {
+            let json = serde_json::to_value(TransactionJsonEip12::from(t.clone())).unwrap();
+            prop_assert!(json["outputs"][0]["value"].is_string());
+            let parsed: TransactionJsonEip12 = serde_json::from_value(json).unwrap();
+            prop_assert_eq![Transaction::try_from(parsed).unwrap(), t];
+        }
+
+        #[test]
+        fn unsigned_tx_roundtrip(t in any::<UnsignedTransaction>()) {
+            let json = serde_json::to_string(&UnsignedTransactionJsonEip12::from(t.clone())).unwrap();
+            let parsed: UnsignedTransactionJsonEip12 = serde_json::from_str(&json).unwrap();
+            prop_assert_eq![UnsignedTransaction::try_from(parsed).unwrap(), t];
+        }
+    }
+
+    #[test]
+    fn parse_number_amounts() {
+        let json = r#"{"tokenId": "2d554219a80c011cc51509e34fa4950965bb8e01de4d012536e766c9ca08bc2c", "amount": 10}"#;
+        let t: TokenJsonEip12 = serde_json::from_str(json).unwrap();
+        assert_eq!(*Token::try_from(t).unwrap().amount.as_u64(), 10);
+    }
+}
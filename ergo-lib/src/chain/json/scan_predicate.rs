@@ -0,0 +1,193 @@
+use ergotree_ir::chain::ergo_box::RegisterId;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::serialization::SigmaSerializable;
+use serde::Deserialize;
+use serde::Serialize;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+
+use crate::chain::scan_predicate::ScanningPredicate;
+
+/// Node's JSON representation of [`ScanningPredicate`]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(tag = "predicate")]
+pub(crate) enum ScanningPredicateJson {
+    #[serde(rename = "contains")]
+    Contains {
+        #[serde(default = "default_register")]
+        register: RegisterIdJson,
+        value: ConstantJson,
+    },
+    #[serde(rename = "equals")]
+    Equals {
+        #[serde(default = "default_register")]
+        register: RegisterIdJson,
+        value: ConstantJson,
+    },
+    #[serde(rename = "containsAsset")]
+    ContainsAsset {
+        #[serde(rename = "assetId")]
+        asset_id: TokenId,
+    },
+    #[serde(rename = "and")]
+    And { args: Vec<ScanningPredicate> },
+    #[serde(rename = "or")]
+    Or { args: Vec<ScanningPredicate> },
+    #[serde(rename = "not")]
+    Not { arg: Box<ScanningPredicate> },
+}
+
+fn default_register() -> RegisterIdJson {
+    RegisterIdJson(RegisterId::R1)
+}
+
+/// Register id as "R0".."R9"
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(into = "String", try_from = "String")]
+pub(crate) struct RegisterIdJson(RegisterId);
+
+impl From<RegisterIdJson> for String {
+    fn from(v: RegisterIdJson) -> Self {
+        v.0.to_string()
+    }
+}
+
+impl TryFrom<String> for RegisterIdJson {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.strip_prefix('R')
+            .and_then(|idx| idx.parse::<u8>().ok())
+            .and_then(|idx| RegisterId::try_from(idx).ok())
+            .map(RegisterIdJson)
+            .ok_or_else(|| format!("invalid register id: {}", s))
+    }
+}
+
+/// Base16-encoded serialized constant
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(into = "String", try_from = "String")]
+pub(crate) struct ConstantJson(Constant);
+
+impl From<ConstantJson> for String {
+    fn from(v: ConstantJson) -> Self {
+        // constants parsed from bytes (or created from literals) are always serializable
+        #[allow(clippy::unwrap_used)]
+        base16::encode_lower(&v.0.sigma_serialize_bytes().unwrap())
+    }
+}
+
+impl TryFrom<String> for ConstantJson {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let bytes = decode_base16(&s, DecodingMode::Strict).map_err(|e| e.to_string())?;
+        Constant::sigma_parse_bytes(&bytes)
+            .map(ConstantJson)
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl From<ScanningPredicate> for ScanningPredicateJson {
+    fn from(p: ScanningPredicate) -> Self {
+        match p {
+            ScanningPredicate::Contains { register, value } => ScanningPredicateJson::Contains {
+                register: RegisterIdJson(register),
+                value: ConstantJson(value),
+            },
+            ScanningPredicate::Equals { register, value } => ScanningPredicateJson::Equals {
+                register: RegisterIdJson(register),
+                value: ConstantJson(value),
+            },
+            ScanningPredicate::ContainsAsset(asset_id) => {
+                ScanningPredicateJson::ContainsAsset { asset_id }
+            }
+            ScanningPredicate::And(args) => ScanningPredicateJson::And { args },
+            ScanningPredicate::Or(args) => ScanningPredicateJson::Or { args },
+            ScanningPredicate::Not(arg) => ScanningPredicateJson::Not { arg },
+        }
+    }
+}
+
+impl TryFrom<ScanningPredicateJson> for ScanningPredicate {
+    type Error = String;
+
+    fn try_from(p: ScanningPredicateJson) -> Result<Self, Self::Error> {
+        Ok(match p {
+            ScanningPredicateJson::Contains { register, value } => ScanningPredicate::Contains {
+                register: register.0,
+                value: value.0,
+            },
+            ScanningPredicateJson::Equals { register, value } => ScanningPredicate::Equals {
+                register: register.0,
+                value: value.0,
+            },
+            ScanningPredicateJson::ContainsAsset { asset_id } => {
+                ScanningPredicate::ContainsAsset(asset_id)
+            }
+            ScanningPredicateJson::And { args } => ScanningPredicate::And(args),
+            ScanningPredicateJson::Or { args } => ScanningPredicate::Or(args),
+            ScanningPredicateJson::Not { arg } => ScanningPredicate::Not(arg),
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisterId;
+
+    use super::*;
+
+    #[test]
+    fn parse_node_scan_request() {
+        // predicate in the node's `/scan/register` request format
+        let json = r#"
+        {
+          "predicate": "and",
+          "args": [
+            {
+              "predicate": "contains",
+              "value": "0e2102d6e44bc0a0ddb2b0db0e36e4d2b2a0e9e9c2ddf2a5abd5aeb4fd8e1e1e9fbabb"
+            },
+            {
+              "predicate": "containsAsset",
+              "assetId": "02dada811a888cd0dc7a0a41739a3ad9b0f427741fe6ca19700cbd8b4d2de3b3"
+            },
+            {
+              "predicate": "equals",
+              "register": "R4",
+              "value": "0402"
+            }
+          ]
+        }"#;
+        let p: ScanningPredicate = serde_json::from_str(json).unwrap();
+        let args = if let ScanningPredicate::And(args) = &p {
+            args
+        } else {
+            panic!("expected and")
+        };
+        assert!(matches!(
+            &args[0],
+            ScanningPredicate::Contains { register, .. } if *register == RegisterId::R1
+        ));
+        assert!(matches!(&args[1], ScanningPredicate::ContainsAsset(_)));
+        assert_eq!(
+            args[2],
+            ScanningPredicate::Equals {
+                register: NonMandatoryRegisterId::R4.into(),
+                value: 1i32.into(),
+            }
+        );
+        let encoded = serde_json::to_string(&p).unwrap();
+        let decoded: ScanningPredicate = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(decoded, p);
+    }
+
+    #[test]
+    fn parse_invalid_register() {
+        let json = r#"{"predicate": "equals", "register": "R10", "value": "0402"}"#;
+        assert!(serde_json::from_str::<ScanningPredicate>(json).is_err());
+    }
+}
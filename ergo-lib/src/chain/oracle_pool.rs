@@ -0,0 +1,485 @@
+//! Oracle pool v2 (EIP-23) boxes: typed access to the pool, oracle (datapoint) and refresh boxes
+//! registers and tokens, and the candidates for the next pool state and the new datapoint.
+//!
+//! Pool box: R4 - rate (Long), R5 - epoch counter (Int), tokens(0) - pool NFT, tokens(1) - reward
+//! tokens.
+//! Oracle box: R4 - oracle public key (GroupElement), R5 - epoch counter of the pool box the
+//! datapoint is published for (Int), R6 - datapoint (Long), tokens(0) - oracle token,
+//! tokens(1) - reward tokens.
+//! Refresh box: tokens(0) - refresh NFT.
+
+use ergo_chain_types::EcPoint;
+use ergotree_ir::chain::ergo_box::BoxTokens;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+use ergotree_ir::chain::ergo_box::NonMandatoryRegisterId;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenAmount;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::mir::constant::Literal;
+use ergotree_ir::mir::constant::TryExtractFrom;
+use ergotree_ir::mir::constant::TryExtractInto;
+use thiserror::Error;
+
+use super::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+use super::ergo_box::box_builder::ErgoBoxCandidateBuilderError;
+use crate::wallet::token_holdings::DecimalAmount;
+
+/// Pool box rate register
+pub const POOL_RATE_REGISTER: NonMandatoryRegisterId = NonMandatoryRegisterId::R4;
+/// Pool box epoch counter register
+pub const POOL_EPOCH_COUNTER_REGISTER: NonMandatoryRegisterId = NonMandatoryRegisterId::R5;
+/// Oracle box public key register
+pub const ORACLE_PUBLIC_KEY_REGISTER: NonMandatoryRegisterId = NonMandatoryRegisterId::R4;
+/// Oracle box epoch counter register
+pub const ORACLE_EPOCH_COUNTER_REGISTER: NonMandatoryRegisterId = NonMandatoryRegisterId::R5;
+/// Oracle box datapoint register
+pub const ORACLE_DATAPOINT_REGISTER: NonMandatoryRegisterId = NonMandatoryRegisterId::R6;
+
+/// Oracle pool box errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum OraclePoolError {
+    /// Box builder error
+    #[error("ErgoBoxCandidateBuilder error: {0}")]
+    ErgoBoxCandidateBuilderError(#[from] ErgoBoxCandidateBuilderError),
+    /// Box has no token at the index
+    #[error("No token at index {index} (expected {expected})")]
+    MissingToken {
+        /// Token index in the box
+        index: usize,
+        /// Expected token id
+        expected: TokenId,
+    },
+    /// Token at the index has an unexpected id
+    #[error("Token {actual} at index {index} (expected {expected})")]
+    UnexpectedToken {
+        /// Token index in the box
+        index: usize,
+        /// Expected token id
+        expected: TokenId,
+        /// Token id in the box
+        actual: TokenId,
+    },
+    /// Register is empty
+    #[error("Register {0} is empty")]
+    MissingRegister(NonMandatoryRegisterId),
+    /// Register value can't be parsed or has an unexpected type
+    #[error("Register {register} has invalid value: {error}")]
+    InvalidRegister {
+        /// Register id
+        register: NonMandatoryRegisterId,
+        /// Error description
+        error: String,
+    },
+    /// Epoch counter of the pool box is the maximum one, so there is no next epoch
+    #[error("Epoch counter {0} can't be incremented")]
+    EpochCounterOverflow(i32),
+}
+
+/// Token ids of the oracle pool
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct OraclePoolConfig {
+    /// Pool NFT id (singleton of the pool box)
+    pub pool_nft_id: TokenId,
+    /// Refresh NFT id (singleton of the refresh box)
+    pub refresh_nft_id: TokenId,
+    /// Oracle token id (held by each oracle box)
+    pub oracle_token_id: TokenId,
+    /// Reward token id
+    pub reward_token_id: TokenId,
+}
+
+/// Pool box, holds the pool NFT and the current rate
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PoolBox {
+    ergo_box: ErgoBox,
+    rate: i64,
+    epoch_counter: i32,
+}
+
+impl PoolBox {
+    /// Check the tokens and parse the registers of the pool box
+    pub fn new(config: &OraclePoolConfig, ergo_box: ErgoBox) -> Result<Self, OraclePoolError> {
+        token_at(&ergo_box.tokens, 0, config.pool_nft_id)?;
+        token_at(&ergo_box.tokens, 1, config.reward_token_id)?;
+        let rate = register(&ergo_box, POOL_RATE_REGISTER)?;
+        let epoch_counter = register(&ergo_box, POOL_EPOCH_COUNTER_REGISTER)?;
+        Ok(PoolBox {
+            ergo_box,
+            rate,
+            epoch_counter,
+        })
+    }
+
+    /// Underlying box
+    pub fn ergo_box(&self) -> &ErgoBox {
+        &self.ergo_box
+    }
+
+    /// Current rate (R4)
+    pub fn rate(&self) -> i64 {
+        self.rate
+    }
+
+    /// Current rate with the given number of decimals, `None` if the rate is negative
+    pub fn rate_decimal(&self, decimals: u8) -> Option<DecimalAmount> {
+        u64::try_from(self.rate)
+            .ok()
+            .map(|amount| DecimalAmount::new(amount, decimals))
+    }
+
+    /// Current epoch counter (R5)
+    pub fn epoch_counter(&self) -> i32 {
+        self.epoch_counter
+    }
+
+    /// Reward tokens left in the pool
+    pub fn reward_tokens(&self) -> &Token {
+        reward_token(&self.ergo_box)
+    }
+
+    /// Pool box for the next epoch (refresh): same script, value and pool NFT, the new `rate`,
+    /// the epoch counter incremented and `reward_amount` reward tokens left in the pool
+    pub fn successor(
+        &self,
+        rate: i64,
+        reward_amount: TokenAmount,
+        creation_height: u32,
+    ) -> Result<ErgoBoxCandidate, OraclePoolError> {
+        let epoch_counter = self
+            .epoch_counter
+            .checked_add(1)
+            .ok_or(OraclePoolError::EpochCounterOverflow(self.epoch_counter))?;
+        let mut builder = ErgoBoxCandidateBuilder::new(
+            self.ergo_box.value,
+            self.ergo_box.ergo_tree.clone(),
+            creation_height,
+        );
+        builder.add_token(pool_token(&self.ergo_box).clone());
+        builder.add_token(Token {
+            token_id: self.reward_tokens().token_id,
+            amount: reward_amount,
+        });
+        builder.set_register_value(POOL_RATE_REGISTER, rate.into());
+        builder.set_register_value(POOL_EPOCH_COUNTER_REGISTER, epoch_counter.into());
+        Ok(builder.build()?)
+    }
+}
+
+/// Oracle box, holds the oracle token and the datapoint published by the oracle
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct OracleBox {
+    ergo_box: ErgoBox,
+    public_key: EcPoint,
+    epoch_counter: i32,
+    datapoint: i64,
+}
+
+impl OracleBox {
+    /// Check the tokens and parse the registers of the oracle box
+    pub fn new(config: &OraclePoolConfig, ergo_box: ErgoBox) -> Result<Self, OraclePoolError> {
+        token_at(&ergo_box.tokens, 0, config.oracle_token_id)?;
+        token_at(&ergo_box.tokens, 1, config.reward_token_id)?;
+        let public_key = register(&ergo_box, ORACLE_PUBLIC_KEY_REGISTER)?;
+        let epoch_counter = register(&ergo_box, ORACLE_EPOCH_COUNTER_REGISTER)?;
+        let datapoint = register(&ergo_box, ORACLE_DATAPOINT_REGISTER)?;
+        Ok(OracleBox {
+            ergo_box,
+            public_key,
+            epoch_counter,
+            datapoint,
+        })
+    }
+
+    /// Underlying box
+    pub fn ergo_box(&self) -> &ErgoBox {
+        &self.ergo_box
+    }
+
+    /// Oracle public key (R4)
+    pub fn public_key(&self) -> &EcPoint {
+        &self.public_key
+    }
+
+    /// Epoch counter of the pool box the datapoint is published for (R5)
+    pub fn epoch_counter(&self) -> i32 {
+        self.epoch_counter
+    }
+
+    /// Published datapoint (R6)
+    pub fn datapoint(&self) -> i64 {
+        self.datapoint
+    }
+
+    /// Published datapoint with the given number of decimals, `None` if the datapoint is negative
+    pub fn datapoint_decimal(&self, decimals: u8) -> Option<DecimalAmount> {
+        u64::try_from(self.datapoint)
+            .ok()
+            .map(|amount| DecimalAmount::new(amount, decimals))
+    }
+
+    /// Reward tokens collected by the oracle
+    pub fn reward_tokens(&self) -> &Token {
+        reward_token(&self.ergo_box)
+    }
+
+    /// Whether the datapoint is published for the current epoch of the pool box (and can be
+    /// collected by the refresh)
+    pub fn is_for_epoch(&self, pool_box: &PoolBox) -> bool {
+        self.epoch_counter == pool_box.epoch_counter()
+    }
+
+    /// Oracle box publishing the `datapoint` for the current epoch of `pool_box`: same script,
+    /// value, tokens and public key
+    pub fn publish_datapoint(
+        &self,
+        pool_box: &PoolBox,
+        datapoint: i64,
+        creation_height: u32,
+    ) -> Result<ErgoBoxCandidate, OraclePoolError> {
+        let mut builder = ErgoBoxCandidateBuilder::new(
+            self.ergo_box.value,
+            self.ergo_box.ergo_tree.clone(),
+            creation_height,
+        );
+        if let Some(tokens) = &self.ergo_box.tokens {
+            tokens.iter().for_each(|t| builder.add_token(t.clone()));
+        }
+        builder.set_register_value(ORACLE_PUBLIC_KEY_REGISTER, self.public_key.clone().into());
+        builder.set_register_value(
+            ORACLE_EPOCH_COUNTER_REGISTER,
+            pool_box.epoch_counter().into(),
+        );
+        builder.set_register_value(ORACLE_DATAPOINT_REGISTER, datapoint.into());
+        Ok(builder.build()?)
+    }
+}
+
+/// Refresh box, holds the refresh NFT
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RefreshBox {
+    ergo_box: ErgoBox,
+}
+
+impl RefreshBox {
+    /// Check the tokens of the refresh box
+    pub fn new(config: &OraclePoolConfig, ergo_box: ErgoBox) -> Result<Self, OraclePoolError> {
+        token_at(&ergo_box.tokens, 0, config.refresh_nft_id)?;
+        Ok(RefreshBox { ergo_box })
+    }
+
+    /// Underlying box
+    pub fn ergo_box(&self) -> &ErgoBox {
+        &self.ergo_box
+    }
+}
+
+fn token_at(
+    tokens: &Option<BoxTokens>,
+    index: usize,
+    expected: TokenId,
+) -> Result<&Token, OraclePoolError> {
+    let token = tokens
+        .as_ref()
+        .and_then(|tokens| tokens.get(index))
+        .ok_or(OraclePoolError::MissingToken { index, expected })?;
+    if token.token_id == expected {
+        Ok(token)
+    } else {
+        Err(OraclePoolError::UnexpectedToken {
+            index,
+            expected,
+            actual: token.token_id,
+        })
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+fn pool_token(b: &ErgoBox) -> &Token {
+    // checked in the wrapper constructors
+    b.tokens.as_ref().unwrap().get(0).unwrap()
+}
+
+#[allow(clippy::unwrap_used)]
+fn reward_token(b: &ErgoBox) -> &Token {
+    // checked in the wrapper constructors
+    b.tokens.as_ref().unwrap().get(1).unwrap()
+}
+
+fn register<T: TryExtractFrom<Literal>>(
+    b: &ErgoBox,
+    register: NonMandatoryRegisterId,
+) -> Result<T, OraclePoolError> {
+    b.get_register(register.into())
+        .map_err(|e| OraclePoolError::InvalidRegister {
+            register,
+            error: e.to_string(),
+        })?
+        .ok_or(OraclePoolError::MissingRegister(register))?
+        .try_extract_into::<T>()
+        .map_err(|e| OraclePoolError::InvalidRegister {
+            register,
+            error: e.to_string(),
+        })
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::tx_id::TxId;
+    use ergotree_ir::mir::constant::Constant;
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+
+    fn config() -> OraclePoolConfig {
+        OraclePoolConfig {
+            pool_nft_id: TokenId::from([1; 32]),
+            refresh_nft_id: TokenId::from([2; 32]),
+            oracle_token_id: TokenId::from([3; 32]),
+            reward_token_id: TokenId::from([4; 32]),
+        }
+    }
+
+    fn make_box(tokens: Vec<(TokenId, u64)>, registers: Vec<Constant>) -> ErgoBox {
+        let mut builder = ErgoBoxCandidateBuilder::new(
+            BoxValue::SAFE_USER_MIN,
+            force_any_val::<ErgoBoxCandidate>().ergo_tree,
+            0,
+        );
+        tokens.into_iter().for_each(|(id, amount)| {
+            builder.add_token(Token {
+                token_id: id,
+                amount: amount.try_into().unwrap(),
+            })
+        });
+        registers.into_iter().enumerate().for_each(|(i, c)| {
+            builder.set_register_value(NonMandatoryRegisterId::get_by_zero_index(i), c)
+        });
+        ErgoBox::from_box_candidate(&builder.build().unwrap(), force_any_val::<TxId>(), 0).unwrap()
+    }
+
+    #[test]
+    fn test_pool_box() {
+        let config = config();
+        let b = make_box(
+            vec![(config.pool_nft_id, 1), (config.reward_token_id, 1000)],
+            vec![1_234_567i64.into(), 7i32.into()],
+        );
+        let pool_box = PoolBox::new(&config, b).unwrap();
+        assert_eq!(pool_box.rate(), 1_234_567);
+        assert_eq!(pool_box.epoch_counter(), 7);
+        assert_eq!(pool_box.rate_decimal(3).unwrap().to_string(), "1234.567");
+        assert_eq!(*pool_box.reward_tokens().amount.as_u64(), 1000);
+
+        let next = PoolBox::new(
+            &config,
+            ErgoBox::from_box_candidate(
+                &pool_box.successor(42, 990.try_into().unwrap(), 1).unwrap(),
+                force_any_val::<TxId>(),
+                0,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(next.rate(), 42);
+        assert_eq!(next.epoch_counter(), 8);
+        assert_eq!(*next.reward_tokens().amount.as_u64(), 990);
+
+        let b = make_box(
+            vec![(config.pool_nft_id, 1), (config.reward_token_id, 1000)],
+            vec![1i64.into(), i32::MAX.into()],
+        );
+        assert_eq!(
+            PoolBox::new(&config, b)
+                .unwrap()
+                .successor(42, 990.try_into().unwrap(), 1),
+            Err(OraclePoolError::EpochCounterOverflow(i32::MAX))
+        );
+
+        // wrong NFT
+        let b = make_box(
+            vec![(config.refresh_nft_id, 1), (config.reward_token_id, 1000)],
+            vec![1i64.into(), 7i32.into()],
+        );
+        assert!(matches!(
+            PoolBox::new(&config, b),
+            Err(OraclePoolError::UnexpectedToken { index: 0, .. })
+        ));
+        // rate of the wrong type
+        let b = make_box(
+            vec![(config.pool_nft_id, 1), (config.reward_token_id, 1000)],
+            vec![1i32.into(), 7i32.into()],
+        );
+        assert!(matches!(
+            PoolBox::new(&config, b),
+            Err(OraclePoolError::InvalidRegister { .. })
+        ));
+        // no epoch counter
+        let b = make_box(
+            vec![(config.pool_nft_id, 1), (config.reward_token_id, 1000)],
+            vec![1i64.into()],
+        );
+        assert_eq!(
+            PoolBox::new(&config, b),
+            Err(OraclePoolError::MissingRegister(NonMandatoryRegisterId::R5))
+        );
+    }
+
+    #[test]
+    fn test_oracle_box() {
+        let config = config();
+        let public_key = force_any_val::<EcPoint>();
+        let pool_box = PoolBox::new(
+            &config,
+            make_box(
+                vec![(config.pool_nft_id, 1), (config.reward_token_id, 1000)],
+                vec![100i64.into(), 7i32.into()],
+            ),
+        )
+        .unwrap();
+        let oracle_box = OracleBox::new(
+            &config,
+            make_box(
+                vec![(config.oracle_token_id, 1), (config.reward_token_id, 5)],
+                vec![public_key.clone().into(), 6i32.into(), 99i64.into()],
+            ),
+        )
+        .unwrap();
+        assert_eq!(oracle_box.public_key(), &public_key);
+        assert_eq!(oracle_box.epoch_counter(), 6);
+        assert_eq!(oracle_box.datapoint(), 99);
+        assert!(!oracle_box.is_for_epoch(&pool_box));
+
+        let published = OracleBox::new(
+            &config,
+            ErgoBox::from_box_candidate(
+                &oracle_box.publish_datapoint(&pool_box, -5, 1).unwrap(),
+                force_any_val::<TxId>(),
+                0,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(published.public_key(), &public_key);
+        assert_eq!(published.datapoint(), -5);
+        assert_eq!(published.datapoint_decimal(2), None);
+        assert!(published.is_for_epoch(&pool_box));
+        assert_eq!(published.ergo_box().tokens, oracle_box.ergo_box().tokens);
+
+        assert_eq!(
+            OracleBox::new(&config, pool_box.ergo_box().clone()),
+            Err(OraclePoolError::UnexpectedToken {
+                index: 0,
+                expected: config.oracle_token_id,
+                actual: config.pool_nft_id,
+            })
+        );
+        assert!(RefreshBox::new(&config, pool_box.ergo_box().clone()).is_err());
+        assert!(
+            RefreshBox::new(&config, make_box(vec![(config.refresh_nft_id, 1)], vec![])).is_ok()
+        );
+    }
+}
@@ -0,0 +1,455 @@
+//! EIP-27 re-emission
+//!
+//! Since the activation height a part of the block emission is not paid to the miner right away.
+//! The emission box (holding the emission NFT) gives the miner's reward box re-emission tokens in
+//! the same amount as the nanoERGs the reward is reduced by. When the miner spends the reward,
+//! the re-emission tokens have to be burnt and the same amount of nanoERGs has to be sent to the
+//! pay-to-reemission contract, which can only be merged into the re-emission box (holding the
+//! re-emission NFT). The re-emission box pays the miners after the end of the emission.
+
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::ergo_box::box_value::BoxValueError;
+use ergotree_ir::chain::ergo_box::BoxTokens;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::ergo_tree::ErgoTreeHeader;
+use ergotree_ir::mir::bin_op::BinOp;
+use ergotree_ir::mir::bin_op::RelationOp;
+use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
+use ergotree_ir::mir::coll_by_index::ByIndex;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::mir::extract_reg_as::ExtractRegisterAs;
+use ergotree_ir::mir::global_vars::GlobalVars;
+use ergotree_ir::mir::option_get::OptionGet;
+use ergotree_ir::mir::select_field::SelectField;
+use ergotree_ir::mir::select_field::TupleFieldIndex;
+use ergotree_ir::mir::unary_op::OneArgOpTryBuild;
+use ergotree_ir::types::stuple::STuple;
+use ergotree_ir::types::stype::SType;
+use lazy_static::lazy_static;
+use thiserror::Error;
+
+use super::emission::MonetarySettings;
+use super::emission::COINS_IN_ONE_ERG;
+use super::transaction::Transaction;
+use crate::wallet::tx_context::TransactionContext;
+use crate::wallet::tx_context::TransactionContextError;
+
+/// Amount of nanoERGs moved to the re-emission per block while the emission is high enough
+pub const BASIC_CHARGE_AMOUNT: u64 = 12 * COINS_IN_ONE_ERG;
+
+/// Re-emission settings of the network
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ReemissionSettings {
+    /// Id of the NFT held by the emission box
+    pub emission_nft_id: TokenId,
+    /// Id of the re-emission token
+    pub reemission_token_id: TokenId,
+    /// Id of the NFT held by the re-emission box
+    pub reemission_nft_id: TokenId,
+    /// Height the re-emission rules are activated at
+    pub activation_height: u32,
+    /// Height the re-emission box starts paying the miners at
+    pub reemission_start_height: u32,
+}
+
+lazy_static! {
+    /// Mainnet re-emission settings
+    pub static ref MAINNET_REEMISSION_SETTINGS: ReemissionSettings =
+        #[allow(clippy::unwrap_used)]
+        ReemissionSettings {
+        emission_nft_id: "20fa2bf23962cdf51b07722d6237c0c7b8a44f78856c0f7ec308dc1ef1a92a51"
+            .parse()
+            .unwrap(),
+        reemission_token_id: "d9a2cc8a09abfaed87afacfbb7daee79a6b26f10c6613fc13d3f3953e5521d1a"
+            .parse()
+            .unwrap(),
+        reemission_nft_id: "d3feeffa87f2df63a7a15b4905e618ae3ce4c69a7975f171bd314d0b877927b8"
+            .parse()
+            .unwrap(),
+        activation_height: 777_217,
+        reemission_start_height: 2_080_800,
+    };
+}
+
+/// Violation of the re-emission rules
+#[derive(Error, Debug)]
+pub enum ReemissionError {
+    /// Emission transaction output is missing
+    #[error("Emission transaction has no output {0}")]
+    MissingOutput(usize),
+    /// Emission box lost a wrong amount of the re-emission tokens
+    #[error("Emission box has to give {expected} re-emission tokens, gave {actual}")]
+    EmissionTokensMismatch {
+        /// Expected amount
+        expected: u64,
+        /// Amount taken from the emission box
+        actual: u64,
+    },
+    /// Miner's reward box got a wrong amount of the re-emission tokens
+    #[error("Miner's reward box has to get {expected} re-emission tokens, got {actual}")]
+    RewardTokensMismatch {
+        /// Expected amount
+        expected: u64,
+        /// Amount in the reward box
+        actual: u64,
+    },
+    /// Amount sent to the pay-to-reemission contract differs from the burnt re-emission tokens
+    #[error("{burnt} re-emission tokens are burnt, but {paid} nanoERGs are paid to re-emission")]
+    PayToReemissionMismatch {
+        /// Amount of the burnt re-emission tokens
+        burnt: u64,
+        /// Amount of nanoERGs sent to the pay-to-reemission contract
+        paid: u64,
+    },
+    /// Transaction context error
+    #[error("Transaction context error: {0}")]
+    TransactionContextError(#[from] TransactionContextError),
+    /// Box value error
+    #[error("Box value error: {0}")]
+    BoxValueError(#[from] BoxValueError),
+}
+
+impl ReemissionSettings {
+    /// Amount of nanoERGs moved to the re-emission (and re-emission tokens given to the miner) in
+    /// the block at `height`
+    pub fn reemission_for_height(&self, height: u32, monetary: &MonetarySettings) -> u64 {
+        let emission = monetary.emission_at_height(height);
+        if height < self.activation_height {
+            0
+        } else if emission >= BASIC_CHARGE_AMOUNT + 3 * COINS_IN_ONE_ERG {
+            BASIC_CHARGE_AMOUNT
+        } else {
+            emission.saturating_sub(3 * COINS_IN_ONE_ERG)
+        }
+    }
+
+    /// Pay-to-reemission contract: the box can only be spent by a transaction with the first
+    /// output holding the re-emission NFT (i.e. merged into the re-emission box)
+    #[allow(clippy::unwrap_used)]
+    pub fn pay_to_reemission_contract(&self) -> ErgoTree {
+        // all the nodes are built with the expected argument types, so unwraps never fail
+        let reemission_out: Expr = ByIndex::new(GlobalVars::Outputs.into(), 0i32.into(), None)
+            .unwrap()
+            .into();
+        let tokens_tpe = SType::SColl(
+            SType::STuple(STuple::pair(
+                SType::SColl(SType::SByte.into()),
+                SType::SLong,
+            ))
+            .into(),
+        );
+        let tokens: Expr = OptionGet::try_build(
+            ExtractRegisterAs::new(reemission_out, 2, SType::SOption(tokens_tpe.into()))
+                .unwrap()
+                .into(),
+        )
+        .unwrap()
+        .into();
+        let first_token_id: Expr = SelectField::new(
+            ByIndex::new(tokens, 0i32.into(), None).unwrap().into(),
+            TupleFieldIndex::try_from(1u8).unwrap(),
+        )
+        .unwrap()
+        .into();
+        let nft_id: Vec<u8> = self.reemission_nft_id.into();
+        let correct_nft = BinOp {
+            kind: RelationOp::Eq.into(),
+            left: Box::new(first_token_id),
+            right: Box::new(Constant::from(nft_id).into()),
+        };
+        let expr: Expr = BoolToSigmaProp::try_build(correct_nft.into())
+            .unwrap()
+            .into();
+        ErgoTree::new(ErgoTreeHeader::v0(true), &expr).unwrap()
+    }
+
+    /// Returns true if the box is the emission box (holds the emission NFT)
+    pub fn is_emission_box(&self, b: &ErgoBoxCandidate) -> bool {
+        token_amount(&b.tokens, self.emission_nft_id) > 0
+    }
+
+    /// Returns true if the box is the re-emission box (holds the re-emission NFT)
+    pub fn is_reemission_box(&self, b: &ErgoBoxCandidate) -> bool {
+        token_amount(&b.tokens, self.reemission_nft_id) > 0
+    }
+
+    /// Returns true if the box is protected by the pay-to-reemission contract
+    pub fn is_pay_to_reemission_box(&self, b: &ErgoBoxCandidate) -> bool {
+        b.ergo_tree == self.pay_to_reemission_contract()
+    }
+
+    /// Amount of the re-emission tokens in the box
+    pub fn reemission_tokens(&self, b: &ErgoBoxCandidate) -> u64 {
+        token_amount(&b.tokens, self.reemission_token_id)
+    }
+
+    /// Output paying to the pay-to-reemission contract the amount of the re-emission tokens held
+    /// by `inputs` (e.g. miner's reward boxes), `None` if there are no re-emission tokens.
+    /// The transaction spending `inputs` has to burn the re-emission tokens (see
+    /// [`crate::wallet::tx_builder::TxBuilder::set_token_burn_permit`]).
+    pub fn pay_to_reemission_output(
+        &self,
+        inputs: &[ErgoBox],
+        creation_height: u32,
+    ) -> Result<Option<ErgoBoxCandidate>, ReemissionError> {
+        let amount: u64 = inputs
+            .iter()
+            .map(|b| token_amount(&b.tokens, self.reemission_token_id))
+            .sum();
+        if amount == 0 {
+            return Ok(None);
+        }
+        Ok(Some(ErgoBoxCandidate {
+            value: BoxValue::try_from(amount)?,
+            ergo_tree: self.pay_to_reemission_contract(),
+            tokens: None,
+            additional_registers: NonMandatoryRegisters::empty(),
+            creation_height,
+        }))
+    }
+
+    /// Check the re-emission rules for the transaction included in the block at `height`:
+    /// - the emission transaction (spending the emission box) takes
+    ///   [`Self::reemission_for_height`] re-emission tokens from the emission box (the first
+    ///   output) and gives them to the miner's reward box (the second output);
+    /// - any other transaction burning the re-emission tokens pays the same amount of nanoERGs to
+    ///   the pay-to-reemission contract.
+    pub fn check_reemission_rules(
+        &self,
+        tx_context: &TransactionContext<Transaction>,
+        height: u32,
+        monetary: &MonetarySettings,
+    ) -> Result<(), ReemissionError> {
+        let tx = &tx_context.spending_tx;
+        let inputs = tx
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(idx, input)| {
+                tx_context
+                    .get_input_box(&input.box_id)
+                    .ok_or(TransactionContextError::InputBoxNotFound(idx))
+            })
+            .collect::<Result<Vec<ErgoBox>, _>>()?;
+        let outputs = tx.output_candidates.as_slice();
+        if let Some(emission_box) = inputs
+            .iter()
+            .find(|b| token_amount(&b.tokens, self.emission_nft_id) > 0)
+        {
+            if height < self.activation_height {
+                return Ok(());
+            }
+            let emission_out = outputs.first().ok_or(ReemissionError::MissingOutput(0))?;
+            let reward_out = outputs.get(1).ok_or(ReemissionError::MissingOutput(1))?;
+            let expected = self.reemission_for_height(height, monetary);
+            let taken = token_amount(&emission_box.tokens, self.reemission_token_id)
+                .saturating_sub(self.reemission_tokens(emission_out));
+            if taken != expected {
+                return Err(ReemissionError::EmissionTokensMismatch {
+                    expected,
+                    actual: taken,
+                });
+            }
+            let rewarded = self.reemission_tokens(reward_out);
+            if rewarded != expected {
+                return Err(ReemissionError::RewardTokensMismatch {
+                    expected,
+                    actual: rewarded,
+                });
+            }
+            return Ok(());
+        }
+        let tokens_in: u64 = inputs
+            .iter()
+            .map(|b| token_amount(&b.tokens, self.reemission_token_id))
+            .sum();
+        let tokens_out: u64 = outputs.iter().map(|b| self.reemission_tokens(b)).sum();
+        let burnt = tokens_in.saturating_sub(tokens_out);
+        if burnt > 0 {
+            let pay_to_reemission = self.pay_to_reemission_contract();
+            let paid: u64 = outputs
+                .iter()
+                .filter(|b| b.ergo_tree == pay_to_reemission)
+                .map(|b| *b.value.as_u64())
+                .sum();
+            if paid != burnt {
+                return Err(ReemissionError::PayToReemissionMismatch { burnt, paid });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn token_amount(tokens: &Option<BoxTokens>, token_id: TokenId) -> u64 {
+    tokens
+        .iter()
+        .flat_map(|tokens| tokens.iter())
+        .filter(|t| t.token_id == token_id)
+        .map(|t| *t.amount.as_u64())
+        .sum()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+    use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+    use ergotree_ir::chain::token::Token;
+    use ergotree_ir::chain::token::TokenAmount;
+    use ergotree_ir::chain::tx_id::TxId;
+    use ergotree_ir::serialization::SigmaSerializable;
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+    use crate::chain::emission::MAINNET_MONETARY_SETTINGS;
+    use crate::chain::transaction::input::prover_result::ProverResult;
+    use crate::chain::transaction::Input;
+
+    fn settings() -> ReemissionSettings {
+        MAINNET_REEMISSION_SETTINGS.clone()
+    }
+
+    fn with_tokens(tokens: Vec<(TokenId, u64)>) -> ErgoBox {
+        let b = force_any_val::<ErgoBoxCandidate>();
+        let tokens = tokens
+            .into_iter()
+            .map(|(id, amount)| Token::from((id, TokenAmount::try_from(amount).unwrap())))
+            .collect();
+        ErgoBox::from_box_candidate(
+            &ErgoBoxCandidate {
+                tokens: BoxTokens::from_vec(tokens).ok(),
+                ..b
+            },
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap()
+    }
+
+    fn tx_context(
+        inputs: Vec<ErgoBox>,
+        outputs: Vec<ErgoBoxCandidate>,
+    ) -> TransactionContext<Transaction> {
+        let tx = Transaction::new_from_vec(
+            inputs
+                .iter()
+                .map(|b| {
+                    Input::new(
+                        b.box_id(),
+                        ProverResult {
+                            proof: ProofBytes::Empty,
+                            extension: ContextExtension::empty(),
+                        },
+                    )
+                })
+                .collect(),
+            vec![],
+            outputs,
+        )
+        .unwrap();
+        TransactionContext::new(tx, inputs, vec![]).unwrap()
+    }
+
+    #[test]
+    fn test_reemission_for_height() {
+        let s = settings();
+        let m = MAINNET_MONETARY_SETTINGS;
+        assert_eq!(s.reemission_for_height(777_216, &m), 0);
+        assert_eq!(s.reemission_for_height(777_217, &m), BASIC_CHARGE_AMOUNT);
+        // emission is 15 ERG in the 20th epoch
+        assert_eq!(
+            s.reemission_for_height(525_600 + 19 * 64_800, &m),
+            BASIC_CHARGE_AMOUNT
+        );
+        assert_eq!(
+            s.reemission_for_height(525_600 + 20 * 64_800, &m),
+            9 * COINS_IN_ONE_ERG
+        );
+        assert_eq!(s.reemission_for_height(m.blocks_total(), &m), 0);
+    }
+
+    #[test]
+    fn test_pay_to_reemission_contract_bytes() {
+        // `ReemissionRules.payToReemission` of the node with the mainnet re-emission NFT id:
+        // header 0x10 (v0, constant segregation), constants [Int 0, Int 0, Coll[Byte] NFT id],
+        // `sigmaProp(OUTPUTS(0).R2[Coll[(Coll[Byte], Long)]].get(0)._1 == NFT id)`
+        let expected = "1003040004000e20\
+                        d3feeffa87f2df63a7a15b4905e618ae3ce4c69a7975f171bd314d0b877927b8\
+                        d1938cb2e4c6b2a5730000020c4d0e730100017302";
+        let tree = settings().pay_to_reemission_contract();
+        assert_eq!(
+            base16::encode_lower(&tree.sigma_serialize_bytes().unwrap()),
+            expected
+        );
+        assert_eq!(
+            ErgoTree::sigma_parse_bytes(&base16::decode(expected).unwrap()).unwrap(),
+            tree
+        );
+    }
+
+    #[test]
+    fn test_emission_tx() {
+        let s = settings();
+        let m = MAINNET_MONETARY_SETTINGS;
+        let height = 800_000;
+        let emission_box = with_tokens(vec![
+            (s.emission_nft_id, 1),
+            (s.reemission_token_id, 100 * BASIC_CHARGE_AMOUNT),
+        ]);
+        assert!(s.is_emission_box(&emission_box.clone().into()));
+        let emission_out = with_tokens(vec![
+            (s.emission_nft_id, 1),
+            (s.reemission_token_id, 99 * BASIC_CHARGE_AMOUNT),
+        ]);
+        let reward_out = with_tokens(vec![(s.reemission_token_id, BASIC_CHARGE_AMOUNT)]);
+        let ctx = tx_context(
+            vec![emission_box.clone()],
+            vec![emission_out.clone().into(), reward_out.into()],
+        );
+        assert!(s.check_reemission_rules(&ctx, height, &m).is_ok());
+        let reward_out = with_tokens(vec![(s.reemission_token_id, 1)]);
+        let ctx = tx_context(
+            vec![emission_box],
+            vec![emission_out.into(), reward_out.into()],
+        );
+        assert!(matches!(
+            s.check_reemission_rules(&ctx, height, &m),
+            Err(ReemissionError::RewardTokensMismatch {
+                expected: BASIC_CHARGE_AMOUNT,
+                actual: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_reward_spending() {
+        let s = settings();
+        let m = MAINNET_MONETARY_SETTINGS;
+        let reward_box = with_tokens(vec![(s.reemission_token_id, BASIC_CHARGE_AMOUNT)]);
+        let pay_to_reemission = s
+            .pay_to_reemission_output(&[reward_box.clone()], 800_000)
+            .unwrap()
+            .unwrap();
+        assert!(s.is_pay_to_reemission_box(&pay_to_reemission));
+        assert_eq!(*pay_to_reemission.value.as_u64(), BASIC_CHARGE_AMOUNT);
+        let change = force_any_val::<ErgoBoxCandidate>();
+        let ctx = tx_context(
+            vec![reward_box.clone()],
+            vec![pay_to_reemission, change.clone()],
+        );
+        assert!(s.check_reemission_rules(&ctx, 800_000, &m).is_ok());
+        let ctx = tx_context(vec![reward_box], vec![change.clone()]);
+        assert!(matches!(
+            s.check_reemission_rules(&ctx, 800_000, &m),
+            Err(ReemissionError::PayToReemissionMismatch {
+                burnt: BASIC_CHARGE_AMOUNT,
+                paid: 0
+            })
+        ));
+    }
+}
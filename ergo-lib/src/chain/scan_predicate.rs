@@ -0,0 +1,163 @@
+//! Scan predicates (EIP-1) used by the node to find the boxes for the registered scans (node's
+//! `/scan/register` API), see https://github.com/ergoplatform/eips/blob/master/eip-0001.md
+
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::RegisterId;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::constant::TryExtractInto;
+
+/// Predicate testing a box, serialized to JSON in the node API format
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "crate::chain::json::scan_predicate::ScanningPredicateJson",
+        try_from = "crate::chain::json::scan_predicate::ScanningPredicateJson"
+    )
+)]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ScanningPredicate {
+    /// Register value is a byte array containing the given bytes (e.g. a public key in the
+    /// box script bytes stored in R1)
+    Contains {
+        /// Register to check (R1 by default in JSON)
+        register: RegisterId,
+        /// Byte array constant to look for
+        value: Constant,
+    },
+    /// Register value is equal to the given constant
+    Equals {
+        /// Register to check (R1 by default in JSON)
+        register: RegisterId,
+        /// Expected value
+        value: Constant,
+    },
+    /// Box contains the token
+    ContainsAsset(TokenId),
+    /// All the predicates are satisfied
+    And(Vec<ScanningPredicate>),
+    /// At least one of the predicates is satisfied
+    Or(Vec<ScanningPredicate>),
+    /// The predicate is not satisfied. Local matching only, not supported by the node.
+    Not(Box<ScanningPredicate>),
+}
+
+impl ScanningPredicate {
+    /// `Contains` predicate for the box script bytes (R1)
+    pub fn contains_script_bytes(bytes: Vec<u8>) -> Self {
+        ScanningPredicate::Contains {
+            register: RegisterId::R1,
+            value: bytes.into(),
+        }
+    }
+
+    /// `Equals` predicate for the box script bytes (R1)
+    pub fn equals_script_bytes(bytes: Vec<u8>) -> Self {
+        ScanningPredicate::Equals {
+            register: RegisterId::R1,
+            value: bytes.into(),
+        }
+    }
+
+    /// Test the box against the predicate the same way the node does
+    pub fn matches(&self, b: &ErgoBox) -> bool {
+        match self {
+            ScanningPredicate::Contains { register, value } => {
+                let needle = match value.v.clone().try_extract_into::<Vec<i8>>() {
+                    Ok(bytes) => bytes,
+                    Err(_) => return false,
+                };
+                register_value(b, *register)
+                    .and_then(|c| c.v.try_extract_into::<Vec<i8>>().ok())
+                    .map(|haystack| contains_slice(&haystack, &needle))
+                    .unwrap_or(false)
+            }
+            ScanningPredicate::Equals { register, value } => {
+                register_value(b, *register).as_ref() == Some(value)
+            }
+            ScanningPredicate::ContainsAsset(token_id) => {
+                b.tokens.iter().flatten().any(|t| &t.token_id == token_id)
+            }
+            ScanningPredicate::And(args) => args.iter().all(|p| p.matches(b)),
+            ScanningPredicate::Or(args) => args.iter().any(|p| p.matches(b)),
+            ScanningPredicate::Not(p) => !p.matches(b),
+        }
+    }
+}
+
+fn register_value(b: &ErgoBox, register: RegisterId) -> Option<Constant> {
+    b.get_register(register).ok().flatten()
+}
+
+fn contains_slice(haystack: &[i8], needle: &[i8]) -> bool {
+    needle.is_empty() || haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergotree_interpreter::sigma_protocol::private_input::DlogProverInput;
+    use ergotree_ir::chain::address::Address;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisterId;
+    use ergotree_ir::chain::token::Token;
+    use ergotree_ir::chain::token::TokenAmount;
+    use ergotree_ir::chain::tx_id::TxId;
+    use ergotree_ir::serialization::SigmaSerializable;
+    use sigma_ser::ScorexSerializable;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let pk = force_any_val::<DlogProverInput>().public_image();
+        let tree = Address::P2Pk(pk.clone()).script().unwrap();
+        let token_id = force_any_val::<TokenId>();
+        let mut builder = ErgoBoxCandidateBuilder::new(BoxValue::SAFE_USER_MIN, tree.clone(), 0);
+        builder.add_token(Token {
+            token_id,
+            amount: TokenAmount::try_from(1u64).unwrap(),
+        });
+        builder.set_register_value(NonMandatoryRegisterId::R4, 7i32.into());
+        let candidate = builder.build().unwrap();
+        let b = ErgoBox::from_box_candidate(&candidate, TxId::zero(), 0).unwrap();
+
+        let pk_bytes = pk.h.scorex_serialize_bytes().unwrap();
+        assert!(ScanningPredicate::contains_script_bytes(pk_bytes.clone()).matches(&b));
+        assert!(
+            ScanningPredicate::equals_script_bytes(tree.sigma_serialize_bytes().unwrap())
+                .matches(&b)
+        );
+        assert!(!ScanningPredicate::equals_script_bytes(pk_bytes.clone()).matches(&b));
+        assert!(ScanningPredicate::ContainsAsset(token_id).matches(&b));
+        let r4 = ScanningPredicate::Equals {
+            register: NonMandatoryRegisterId::R4.into(),
+            value: 7i32.into(),
+        };
+        assert!(r4.matches(&b));
+        let r5 = ScanningPredicate::Equals {
+            register: NonMandatoryRegisterId::R5.into(),
+            value: 7i32.into(),
+        };
+        assert!(!r5.matches(&b));
+        assert!(ScanningPredicate::And(vec![
+            r4.clone(),
+            ScanningPredicate::ContainsAsset(token_id)
+        ])
+        .matches(&b));
+        assert!(!ScanningPredicate::And(vec![r4.clone(), r5.clone()]).matches(&b));
+        assert!(ScanningPredicate::Or(vec![r5.clone(), r4]).matches(&b));
+        assert!(ScanningPredicate::Not(Box::new(r5)).matches(&b));
+        // non-byte array value never matches `contains`
+        assert!(!ScanningPredicate::Contains {
+            register: RegisterId::R1,
+            value: 1i32.into()
+        }
+        .matches(&b));
+    }
+}
@@ -0,0 +1,437 @@
+//! Singleton NFT (token with the total supply of one unit) used as a protocol nonce: the box
+//! holding the NFT is the protocol state, each state transition spends it and creates the
+//! successor box holding the same NFT, so the state can't be forked or replayed.
+
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::BoxTokens;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenAmount;
+use ergotree_ir::chain::token::TokenAmountError;
+use ergotree_ir::chain::token::TokenId;
+use thiserror::Error;
+
+use super::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+use super::ergo_box::box_builder::ErgoBoxCandidateBuilderError;
+use super::transaction::unsigned::UnsignedTransaction;
+use super::transaction::Transaction;
+
+/// Singleton NFT errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum SingletonError {
+    /// Box builder error
+    #[error("ErgoBoxCandidateBuilder error: {0}")]
+    ErgoBoxCandidateBuilderError(#[from] ErgoBoxCandidateBuilderError),
+    /// Token amount error (e.g. the sum of the amounts overflows)
+    #[error("TokenAmountError: {0}")]
+    TokenAmountError(#[from] TokenAmountError),
+    /// Minted token id is not the id of the first input box
+    #[error("Token {token_id} is not minted by this transaction (first input is {first_input})")]
+    NotMintedByTx {
+        /// Token id
+        token_id: TokenId,
+        /// Id of the first transaction input
+        first_input: BoxId,
+    },
+    /// Box does not hold exactly one unit of the singleton token
+    #[error("Box {0} does not hold the singleton token")]
+    NotSingletonBox(BoxId),
+    /// Singleton box is not spent by the transaction
+    #[error("Singleton box {0} is not spent by the transaction")]
+    NotSpent(BoxId),
+    /// Singleton token is not in the outputs
+    #[error("Singleton token {0} is burned")]
+    Burned(TokenId),
+    /// Singleton token is in more than one unit in the outputs
+    #[error("Singleton token {token_id} is in {amount} units in the outputs")]
+    Duplicated {
+        /// Token id
+        token_id: TokenId,
+        /// Total amount in the outputs
+        amount: u64,
+    },
+    /// Successor box script differs from the spent singleton box script
+    #[error("Successor box script differs from the singleton box script")]
+    ScriptChanged,
+}
+
+/// Mints the singleton token in a box, the token id is the id of the first input of the minting
+/// transaction
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SingletonMinter {
+    token_id: TokenId,
+}
+
+impl SingletonMinter {
+    /// Minter for the transaction spending `first_input` as the first input
+    pub fn new(first_input: BoxId) -> Self {
+        SingletonMinter {
+            token_id: first_input.into(),
+        }
+    }
+
+    /// Id of the minted token
+    pub fn token_id(&self) -> TokenId {
+        self.token_id
+    }
+
+    /// Build the box holding the singleton token (one unit), e.g. the initial protocol state box
+    pub fn build_box(
+        &self,
+        mut builder: ErgoBoxCandidateBuilder,
+    ) -> Result<ErgoBoxCandidate, SingletonError> {
+        builder.add_token(Token {
+            token_id: self.token_id,
+            amount: TokenAmount::MIN,
+        });
+        let candidate = builder.build()?;
+        let amount = token_amount(&candidate.tokens, self.token_id)?;
+        if amount != 1 {
+            return Err(SingletonError::Duplicated {
+                token_id: self.token_id,
+                amount,
+            });
+        }
+        Ok(candidate)
+    }
+
+    /// Check that the transaction mints this singleton token (one unit in exactly one output)
+    pub fn validate_mint(&self, tx: &UnsignedTransaction) -> Result<(), SingletonError> {
+        let first_input = tx.inputs.first().box_id;
+        if TokenId::from(first_input) != self.token_id {
+            return Err(SingletonError::NotMintedByTx {
+                token_id: self.token_id,
+                first_input,
+            });
+        }
+        find_single_output(tx.output_candidates.as_slice(), self.token_id).map(|_| ())
+    }
+}
+
+/// Returns true if the box holds exactly one unit of the token
+pub fn is_singleton_box(b: &ErgoBox, token_id: TokenId) -> bool {
+    matches!(token_amount(&b.tokens, token_id), Ok(1))
+}
+
+/// Check that the transaction spends the singleton box `current` and creates exactly one
+/// successor box holding the singleton token (and guarded by the same script, if
+/// `require_same_script`). Returns the successor box.
+pub fn validate_successor<'a>(
+    tx: &'a Transaction,
+    current: &ErgoBox,
+    token_id: TokenId,
+    require_same_script: bool,
+) -> Result<&'a ErgoBox, SingletonError> {
+    check_spent(tx.inputs.iter().map(|i| i.box_id), current, token_id)?;
+    let idx = find_single_output(tx.output_candidates.as_slice(), token_id)?;
+    let successor = tx
+        .outputs
+        .get(idx)
+        .ok_or(SingletonError::Burned(token_id))?;
+    if require_same_script && successor.ergo_tree != current.ergo_tree {
+        return Err(SingletonError::ScriptChanged);
+    }
+    Ok(successor)
+}
+
+/// Same as [`validate_successor`] for the unsigned transaction (e.g. before signing)
+pub fn validate_successor_unsigned<'a>(
+    tx: &'a UnsignedTransaction,
+    current: &ErgoBox,
+    token_id: TokenId,
+    require_same_script: bool,
+) -> Result<&'a ErgoBoxCandidate, SingletonError> {
+    check_spent(tx.inputs.iter().map(|i| i.box_id), current, token_id)?;
+    let idx = find_single_output(tx.output_candidates.as_slice(), token_id)?;
+    let successor = tx
+        .output_candidates
+        .get(idx)
+        .ok_or(SingletonError::Burned(token_id))?;
+    if require_same_script && successor.ergo_tree != current.ergo_tree {
+        return Err(SingletonError::ScriptChanged);
+    }
+    Ok(successor)
+}
+
+fn check_spent<I: IntoIterator<Item = BoxId>>(
+    inputs: I,
+    current: &ErgoBox,
+    token_id: TokenId,
+) -> Result<(), SingletonError> {
+    if !is_singleton_box(current, token_id) {
+        return Err(SingletonError::NotSingletonBox(current.box_id()));
+    }
+    if !inputs.into_iter().any(|id| id == current.box_id()) {
+        return Err(SingletonError::NotSpent(current.box_id()));
+    }
+    Ok(())
+}
+
+/// Index of the only output holding the token, which should be exactly one unit
+fn find_single_output(
+    outputs: &[ErgoBoxCandidate],
+    token_id: TokenId,
+) -> Result<usize, SingletonError> {
+    let mut holders: Vec<usize> = Vec::new();
+    let mut amount: u64 = 0;
+    for (idx, b) in outputs.iter().enumerate() {
+        let box_amount = token_amount(&b.tokens, token_id)?;
+        if box_amount > 0 {
+            holders.push(idx);
+            amount = amount
+                .checked_add(box_amount)
+                .ok_or(TokenAmountError::TokenOverflow(token_id))?;
+        }
+    }
+    match holders.as_slice() {
+        [] => Err(SingletonError::Burned(token_id)),
+        [idx] if amount == 1 => Ok(*idx),
+        _ => Err(SingletonError::Duplicated { token_id, amount }),
+    }
+}
+
+fn token_amount(tokens: &Option<BoxTokens>, token_id: TokenId) -> Result<u64, TokenAmountError> {
+    tokens
+        .iter()
+        .flatten()
+        .filter(|t| t.token_id == token_id)
+        .try_fold(0u64, |acc, t| {
+            acc.checked_add(*t.amount.as_u64())
+                .ok_or(TokenAmountError::TokenOverflow(token_id))
+        })
+}
+
+#[cfg(feature = "rest")]
+mod rest {
+    use ergo_rest::api::node::get_unspent_boxes_by_token_id;
+    use ergo_rest::NodeConf;
+    use ergo_rest::NodeError;
+
+    use super::*;
+
+    /// Errors on looking up the singleton box
+    #[derive(Error, Debug)]
+    pub enum SingletonLookupError {
+        /// Node (or explorer) request failed
+        #[error("Node error: {0}")]
+        NodeError(#[from] NodeError),
+        /// No unspent box holds the token (unknown or burned token)
+        #[error("No unspent box holds the token {0}")]
+        NotFound(TokenId),
+        /// Token is held by more than one unspent box or in more than one unit
+        #[error("Token {0} is not a singleton")]
+        NotSingleton(TokenId),
+    }
+
+    /// Find the current unspent box holding the singleton token with the node API (requires the
+    /// node with the extra indexing enabled)
+    pub async fn find_singleton_box(
+        node: NodeConf,
+        token_id: TokenId,
+    ) -> Result<ErgoBox, SingletonLookupError> {
+        // ask for two boxes to detect the non-singleton token
+        let boxes = get_unspent_boxes_by_token_id(node, token_id, 0, 2).await?;
+        single_box(boxes, token_id)
+    }
+
+    /// Find the current unspent box holding the singleton token with the explorer API
+    #[cfg(feature = "explorer")]
+    pub async fn find_singleton_box_explorer(
+        explorer: &ergo_rest::api::explorer::ExplorerConf,
+        token_id: TokenId,
+    ) -> Result<ErgoBox, SingletonLookupError> {
+        let boxes =
+            ergo_rest::api::explorer::get_unspent_boxes_by_token_id(explorer, token_id, 0, 2)
+                .await?;
+        single_box(boxes.items, token_id)
+    }
+
+    fn single_box(boxes: Vec<ErgoBox>, token_id: TokenId) -> Result<ErgoBox, SingletonLookupError> {
+        match boxes.as_slice() {
+            [] => Err(SingletonLookupError::NotFound(token_id)),
+            [b] if is_singleton_box(b, token_id) => Ok(b.clone()),
+            _ => Err(SingletonLookupError::NotSingleton(token_id)),
+        }
+    }
+}
+
+#[cfg(feature = "rest")]
+pub use rest::find_singleton_box;
+#[cfg(feature = "explorer")]
+pub use rest::find_singleton_box_explorer;
+#[cfg(feature = "rest")]
+pub use rest::SingletonLookupError;
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+    use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::tx_id::TxId;
+    use ergotree_ir::ergo_tree::ErgoTree;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::transaction::input::prover_result::ProverResult;
+    use crate::chain::transaction::Input;
+    use crate::chain::transaction::UnsignedInput;
+    use crate::wallet::secret_key::SecretKey;
+
+    use super::*;
+
+    fn spend(inputs: &[&ErgoBox], outputs: Vec<ErgoBoxCandidate>) -> Transaction {
+        Transaction::new_from_vec(
+            inputs
+                .iter()
+                .map(|b| {
+                    Input::new(
+                        b.box_id(),
+                        ProverResult {
+                            proof: ProofBytes::Empty,
+                            extension: ContextExtension::empty(),
+                        },
+                    )
+                })
+                .collect(),
+            vec![],
+            outputs,
+        )
+        .unwrap()
+    }
+
+    fn with_tokens(tree: &ErgoTree, tokens: Vec<Token>) -> ErgoBoxCandidate {
+        let mut builder = ErgoBoxCandidateBuilder::new(BoxValue::SAFE_USER_MIN, tree.clone(), 0);
+        tokens.into_iter().for_each(|t| builder.add_token(t));
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn mint_and_follow() {
+        let tree = force_any_val::<ErgoTree>();
+        let funds = ErgoBox::new(
+            BoxValue::SAFE_USER_MIN,
+            tree.clone(),
+            None,
+            NonMandatoryRegisters::empty(),
+            0,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let minter = SingletonMinter::new(funds.box_id());
+        let token_id = minter.token_id();
+        let state = minter
+            .build_box(ErgoBoxCandidateBuilder::new(
+                BoxValue::SAFE_USER_MIN,
+                tree.clone(),
+                0,
+            ))
+            .unwrap();
+        let mint_tx = UnsignedTransaction::new_from_vec(
+            vec![UnsignedInput::new(
+                funds.box_id(),
+                ContextExtension::empty(),
+            )],
+            vec![],
+            vec![state.clone()],
+        )
+        .unwrap();
+        assert_eq!(minter.validate_mint(&mint_tx), Ok(()));
+        assert!(matches!(
+            SingletonMinter::new(force_any_val::<BoxId>()).validate_mint(&mint_tx),
+            Err(SingletonError::NotMintedByTx { .. })
+        ));
+
+        let current = ErgoBox::from_box_candidate(&state, force_any_val::<TxId>(), 0).unwrap();
+        assert!(is_singleton_box(&current, token_id));
+        let nft = Token {
+            token_id,
+            amount: TokenAmount::MIN,
+        };
+        let tx = spend(&[&current], vec![with_tokens(&tree, vec![nft.clone()])]);
+        let successor = validate_successor(&tx, &current, token_id, true).unwrap();
+        assert!(is_singleton_box(successor, token_id));
+        assert_eq!(
+            validate_successor(&tx, &current, force_any_val::<TokenId>(), true),
+            Err(SingletonError::NotSingletonBox(current.box_id()))
+        );
+
+        // successor with the other script
+        let other_tree = SecretKey::random_dlog()
+            .get_address_from_public_image()
+            .script()
+            .unwrap();
+        let tx = spend(
+            &[&current],
+            vec![with_tokens(&other_tree, vec![nft.clone()])],
+        );
+        assert_eq!(
+            validate_successor(&tx, &current, token_id, true),
+            Err(SingletonError::ScriptChanged)
+        );
+        assert!(validate_successor(&tx, &current, token_id, false).is_ok());
+
+        // burned
+        let tx = spend(&[&current], vec![with_tokens(&tree, vec![])]);
+        assert_eq!(
+            validate_successor(&tx, &current, token_id, true),
+            Err(SingletonError::Burned(token_id))
+        );
+
+        // not spent
+        let tx = spend(&[&funds], vec![with_tokens(&tree, vec![nft.clone()])]);
+        assert_eq!(
+            validate_successor(&tx, &current, token_id, true),
+            Err(SingletonError::NotSpent(current.box_id()))
+        );
+
+        // duplicated (e.g. the spending of another box with the same token id)
+        let tx = spend(
+            &[&current],
+            vec![
+                with_tokens(&tree, vec![nft.clone()]),
+                with_tokens(&tree, vec![nft]),
+            ],
+        );
+        assert_eq!(
+            validate_successor(&tx, &current, token_id, true),
+            Err(SingletonError::Duplicated {
+                token_id,
+                amount: 2
+            })
+        );
+        let two = Token {
+            token_id,
+            amount: TokenAmount::try_from(2u64).unwrap(),
+        };
+        let tx = spend(&[&current], vec![with_tokens(&tree, vec![two])]);
+        assert!(matches!(
+            validate_successor(&tx, &current, token_id, true),
+            Err(SingletonError::Duplicated { .. })
+        ));
+        // the total amount in the outputs overflows u64
+        let max = Token {
+            token_id,
+            amount: TokenAmount::try_from(TokenAmount::MAX_RAW).unwrap(),
+        };
+        let tx = spend(
+            &[&current],
+            vec![
+                with_tokens(&tree, vec![max.clone()]),
+                with_tokens(&tree, vec![max.clone()]),
+                with_tokens(&tree, vec![max]),
+            ],
+        );
+        assert_eq!(
+            validate_successor(&tx, &current, token_id, true),
+            Err(SingletonError::TokenAmountError(
+                TokenAmountError::TokenOverflow(token_id)
+            ))
+        );
+    }
+}
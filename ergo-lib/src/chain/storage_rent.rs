@@ -0,0 +1,230 @@
+//! Storage rent: spending of the boxes which were not moved for the storage period.
+//! The miner is allowed to collect the storage fee from such a box, recreating the box with the
+//! rest of its value and the same script, tokens and registers. Reference implementation -
+//! https://github.com/ergoplatform/ergo/blob/3a1a5b5fee55ad0be8b0ac6ed8e75a8bc1a9bd69/src/main/scala/org/ergoplatform/wallet/interpreter/ErgoInterpreter.scala
+
+use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::ergo_box::box_value::BoxValueError;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::constant::TryExtractInto;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::serialization::SigmaSerializationError;
+use thiserror::Error;
+
+use super::transaction::UnsignedInput;
+
+/// Number of blocks (~4 years) after which the box can be spent with storage rent rules
+pub const STORAGE_PERIOD: u32 = 1_051_200;
+
+/// Context extension variable id holding the index (Short) of the recreated box in the outputs
+pub const STORAGE_INDEX_VAR_ID: u8 = 127;
+
+/// Default storage fee in nanoERGs per byte of the serialized box (node `storageFeeFactor`)
+pub const STORAGE_FEE_FACTOR: u64 = ergo_chain_types::parameters::STORAGE_FEE_FACTOR_DEFAULT as u64;
+
+/// Cost of the storage rent rules check (used instead of the box script cost)
+pub const STORAGE_CONTRACT_COST: u64 = 50;
+
+/// Storage rent errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum StorageRentError {
+    /// Box is not old enough to be spent with storage rent rules
+    #[error("Box created at height {creation_height} cannot be claimed at height {height}")]
+    NotEligible {
+        /// Box creation height
+        creation_height: u32,
+        /// Current height
+        height: u32,
+    },
+    /// Box serialization failed
+    #[error("Box serialization failed: {0}")]
+    SerializationError(#[from] SigmaSerializationError),
+    /// Recreated box value is invalid
+    #[error("Invalid recreated box value: {0}")]
+    BoxValueError(#[from] BoxValueError),
+}
+
+/// Returns `true` if the box was not moved for at least [`STORAGE_PERIOD`] blocks at the given height
+pub fn is_storage_rent_eligible(b: &ErgoBox, height: u32) -> bool {
+    height.saturating_sub(b.creation_height) >= STORAGE_PERIOD
+}
+
+/// Maximum storage fee that can be collected from the box
+pub fn storage_fee(b: &ErgoBox, storage_fee_factor: u64) -> Result<u64, StorageRentError> {
+    let box_size = b.serialized_size()? as u64;
+    Ok(box_size.saturating_mul(storage_fee_factor))
+}
+
+/// Box candidate recreating the given box at `height` with the storage fee deducted from its value.
+/// Returns `None` if the storage fee covers the whole box value, i.e. the box can be collected
+/// entirely without recreation.
+pub fn storage_rent_output(
+    b: &ErgoBox,
+    height: u32,
+    storage_fee_factor: u64,
+) -> Result<Option<ErgoBoxCandidate>, StorageRentError> {
+    if !is_storage_rent_eligible(b, height) {
+        return Err(StorageRentError::NotEligible {
+            creation_height: b.creation_height,
+            height,
+        });
+    }
+    let fee = storage_fee(b, storage_fee_factor)?;
+    if *b.value.as_u64() <= fee {
+        return Ok(None);
+    }
+    let value = BoxValue::new(*b.value.as_u64() - fee)?;
+    Ok(Some(ErgoBoxCandidate {
+        value,
+        ergo_tree: b.ergo_tree.clone(),
+        tokens: b.tokens.clone(),
+        additional_registers: b.additional_registers.clone(),
+        creation_height: height,
+    }))
+}
+
+/// Context extension pointing to the recreated box in the transaction outputs
+pub fn storage_rent_extension(output_index: i16) -> ContextExtension {
+    let mut extension = ContextExtension::empty();
+    extension
+        .values
+        .insert(STORAGE_INDEX_VAR_ID, Constant::from(output_index));
+    extension
+}
+
+/// Input spending the box with storage rent rules, where the box is recreated (if needed) at
+/// `output_index` in the transaction outputs. Storage rent inputs do not require a proof.
+pub fn storage_rent_input(b: &ErgoBox, output_index: i16) -> UnsignedInput {
+    UnsignedInput::new(b.box_id(), storage_rent_extension(output_index))
+}
+
+/// Check the storage rent spending rules for the box spent with the given context extension.
+/// Returns `None` if the rules do not apply (the box is not old enough, or there is no
+/// [`STORAGE_INDEX_VAR_ID`] variable in the extension), meaning the box script should be checked.
+pub fn check_storage_rent(
+    b: &ErgoBox,
+    extension: &ContextExtension,
+    output_candidates: &[ErgoBoxCandidate],
+    height: u32,
+    storage_fee_factor: u64,
+) -> Option<bool> {
+    if !is_storage_rent_eligible(b, height) {
+        return None;
+    }
+    let output_index = extension.values.get(&STORAGE_INDEX_VAR_ID)?;
+    let output = match output_index.clone().try_extract_into::<i16>() {
+        Ok(idx) if idx >= 0 => output_candidates.get(idx as usize),
+        _ => None,
+    };
+    Some(match (output, storage_fee(b, storage_fee_factor)) {
+        (Some(output), Ok(fee)) => {
+            let fee_not_covered = *b.value.as_u64() <= fee;
+            let correct_creation_height = output.creation_height == height;
+            let correct_value = *output.value.as_u64() >= b.value.as_u64().saturating_sub(fee);
+            let correct_registers = output.ergo_tree.sigma_serialize_bytes().ok()
+                == b.ergo_tree.sigma_serialize_bytes().ok()
+                && output.tokens == b.tokens
+                && output.additional_registers == b.additional_registers;
+            fee_not_covered || (correct_creation_height && correct_value && correct_registers)
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use super::*;
+
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::tx_id::TxId;
+    use ergotree_ir::ergo_tree::ErgoTree;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::sigma_protocol::sigma_boolean::ProveDlog;
+    use sigma_test_util::force_any_val;
+    use std::convert::TryFrom;
+
+    fn old_box(value: u64) -> ErgoBox {
+        ErgoBox::new(
+            BoxValue::new(value).unwrap(),
+            ErgoTree::try_from(Expr::Const(force_any_val::<ProveDlog>().into())).unwrap(),
+            None,
+            NonMandatoryRegisters::try_from(vec![Constant::from(1i32)]).unwrap(),
+            1,
+            TxId::zero(),
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_recreated_box_passes_check() {
+        let b = old_box(BoxValue::UNITS_PER_ERGO as u64 * 10);
+        let height = STORAGE_PERIOD + 1;
+        let output = storage_rent_output(&b, height, STORAGE_FEE_FACTOR)
+            .unwrap()
+            .unwrap();
+        let fee = storage_fee(&b, STORAGE_FEE_FACTOR).unwrap();
+        assert_eq!(*output.value.as_u64(), *b.value.as_u64() - fee);
+        let extension = storage_rent_input(&b, 0).extension;
+        assert_eq!(
+            check_storage_rent(
+                &b,
+                &extension,
+                &[output.clone()],
+                height,
+                STORAGE_FEE_FACTOR
+            ),
+            Some(true)
+        );
+        let tampered = ErgoBoxCandidate {
+            additional_registers: NonMandatoryRegisters::empty(),
+            ..output
+        };
+        assert_eq!(
+            check_storage_rent(&b, &extension, &[tampered], height, STORAGE_FEE_FACTOR),
+            Some(false)
+        );
+        assert_eq!(
+            check_storage_rent(&b, &extension, &[], height, STORAGE_FEE_FACTOR),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_box_value_fully_collected() {
+        let b = old_box(BoxValue::MIN_RAW);
+        let height = STORAGE_PERIOD + 1;
+        assert_eq!(
+            storage_rent_output(&b, height, STORAGE_FEE_FACTOR).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_not_eligible() {
+        let b = old_box(BoxValue::UNITS_PER_ERGO as u64);
+        let extension = storage_rent_extension(0);
+        assert_eq!(
+            check_storage_rent(&b, &extension, &[], STORAGE_PERIOD, STORAGE_FEE_FACTOR),
+            None
+        );
+        assert!(matches!(
+            storage_rent_output(&b, STORAGE_PERIOD, STORAGE_FEE_FACTOR),
+            Err(StorageRentError::NotEligible { .. })
+        ));
+        assert_eq!(
+            check_storage_rent(
+                &b,
+                &ContextExtension::empty(),
+                &[],
+                STORAGE_PERIOD + 1,
+                STORAGE_FEE_FACTOR
+            ),
+            None
+        );
+    }
+}
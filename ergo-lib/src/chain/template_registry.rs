@@ -0,0 +1,284 @@
+//! Registry of known contract templates.
+//! Contracts are identified by the hash of their ErgoTree template (the script with constants
+//! replaced by placeholders), so that the same contract with different parameters (token ids,
+//! public keys, etc.) is recognized as one.
+
+#[cfg(feature = "known_templates")]
+mod known_templates;
+pub mod well_known;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use ergo_chain_types::blake2b256_hash;
+use ergo_chain_types::Digest32;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::ergo_tree::ErgoTreeError;
+use thiserror::Error;
+
+/// Blake2b256 hash of the ErgoTree template bytes (see [`ErgoTree::template_bytes`])
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct TemplateHash(pub Digest32);
+
+impl TemplateHash {
+    /// Template hash of the given ErgoTree
+    pub fn from_ergo_tree(tree: &ErgoTree) -> Result<TemplateHash, ErgoTreeError> {
+        Ok(TemplateHash(blake2b256_hash(&tree.template_bytes()?)))
+    }
+}
+
+impl fmt::Display for TemplateHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Semantic version of the contract (`major.minor.patch`)
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct ContractVersion {
+    /// Major version (incompatible changes)
+    pub major: u16,
+    /// Minor version
+    pub minor: u16,
+    /// Patch version
+    pub patch: u16,
+}
+
+impl ContractVersion {
+    /// Create a new version
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        ContractVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl fmt::Display for ContractVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Error on parsing [`ContractVersion`]
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+#[error("Invalid contract version {0:?}, expected major.minor.patch")]
+pub struct ContractVersionParsingError(pub String);
+
+impl FromStr for ContractVersion {
+    type Err = ContractVersionParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s
+            .split('.')
+            .map(u16::from_str)
+            .collect::<Result<Vec<u16>, _>>()
+            .map_err(|_| ContractVersionParsingError(s.to_string()))?;
+        match parts.as_slice() {
+            [major, minor, patch] => Ok(ContractVersion::new(*major, *minor, *patch)),
+            _ => Err(ContractVersionParsingError(s.to_string())),
+        }
+    }
+}
+
+/// Known contract template
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct KnownTemplate {
+    /// Contract name (e.g. "SigmaUSD bank")
+    pub name: String,
+    /// Contract version
+    pub version: ContractVersion,
+}
+
+impl fmt::Display for KnownTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} v{}", self.name, self.version)
+    }
+}
+
+/// Registry mapping template hashes to the known contracts
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct TemplateRegistry {
+    templates: HashMap<TemplateHash, KnownTemplate>,
+}
+
+impl TemplateRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        TemplateRegistry::default()
+    }
+
+    /// Registry with the bundled data set of the known dApp contracts
+    #[cfg(feature = "known_templates")]
+    pub fn with_known_templates() -> Self {
+        let mut registry = TemplateRegistry::new();
+        known_templates::register_known_templates(&mut registry);
+        registry
+    }
+
+    /// Register the template under the given hash.
+    /// Returns the previously registered template for this hash, if any.
+    pub fn register(
+        &mut self,
+        hash: TemplateHash,
+        name: impl Into<String>,
+        version: ContractVersion,
+    ) -> Option<KnownTemplate> {
+        self.templates.insert(
+            hash,
+            KnownTemplate {
+                name: name.into(),
+                version,
+            },
+        )
+    }
+
+    /// Register the template of the given ErgoTree.
+    /// Returns the previously registered template for its hash, if any.
+    pub fn register_ergo_tree(
+        &mut self,
+        tree: &ErgoTree,
+        name: impl Into<String>,
+        version: ContractVersion,
+    ) -> Result<Option<KnownTemplate>, ErgoTreeError> {
+        let hash = TemplateHash::from_ergo_tree(tree)?;
+        Ok(self.register(hash, name, version))
+    }
+
+    /// Remove the template, returning it if it was registered
+    pub fn unregister(&mut self, hash: &TemplateHash) -> Option<KnownTemplate> {
+        self.templates.remove(hash)
+    }
+
+    /// Template registered under the given hash
+    pub fn get(&self, hash: &TemplateHash) -> Option<&KnownTemplate> {
+        self.templates.get(hash)
+    }
+
+    /// Known template of the given ErgoTree. Returns `None` for unknown (or unparseable) trees.
+    pub fn recognize(&self, tree: &ErgoTree) -> Option<&KnownTemplate> {
+        TemplateHash::from_ergo_tree(tree)
+            .ok()
+            .and_then(|hash| self.get(&hash))
+    }
+
+    /// All registered versions of the contract with the given name, ordered by version
+    pub fn versions(&self, name: &str) -> Vec<(TemplateHash, &KnownTemplate)> {
+        let mut versions: Vec<(TemplateHash, &KnownTemplate)> = self
+            .templates
+            .iter()
+            .filter(|(_, t)| t.name == name)
+            .map(|(hash, t)| (*hash, t))
+            .collect();
+        versions.sort_by_key(|(_, t)| t.version);
+        versions
+    }
+
+    /// The latest registered version of the contract with the given name
+    pub fn latest(&self, name: &str) -> Option<(TemplateHash, &KnownTemplate)> {
+        self.versions(name).pop()
+    }
+
+    /// Iterate over the registered templates (in no particular order)
+    pub fn iter(&self) -> impl Iterator<Item = (&TemplateHash, &KnownTemplate)> {
+        self.templates.iter()
+    }
+
+    /// Number of registered templates
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    /// Returns `true` if no templates are registered
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    use ergotree_ir::ergo_tree::ErgoTreeHeader;
+    use ergotree_ir::mir::constant::Constant;
+    use ergotree_ir::mir::expr::Expr;
+
+    fn bool_tree(v: bool) -> ErgoTree {
+        let expr = Expr::Const(Constant::from(v));
+        ErgoTree::new(ErgoTreeHeader::v0(true), &expr).unwrap()
+    }
+
+    #[test]
+    fn test_recognize_regardless_of_constants() {
+        let mut registry = TemplateRegistry::new();
+        assert!(registry.is_empty());
+        registry
+            .register_ergo_tree(&bool_tree(true), "Test", ContractVersion::new(1, 0, 0))
+            .unwrap();
+        let known = registry.recognize(&bool_tree(false)).unwrap();
+        assert_eq!(known.name, "Test");
+        assert_eq!(known.to_string(), "Test v1.0.0");
+        // no constant segregation, different template
+        let tree = ErgoTree::new(
+            ErgoTreeHeader::v0(false),
+            &Expr::Const(Constant::from(true)),
+        )
+        .unwrap();
+        assert_eq!(tree.get_constants().unwrap().len(), 0);
+        assert!(registry.recognize(&tree).is_none());
+    }
+
+    #[test]
+    fn test_versions() {
+        let mut registry = TemplateRegistry::new();
+        let v1 = TemplateHash(blake2b256_hash(&[1]));
+        let v2 = TemplateHash(blake2b256_hash(&[2]));
+        registry.register(v2, "Pool", "2.0.1".parse().unwrap());
+        registry.register(v1, "Pool", "1.3.0".parse().unwrap());
+        registry.register(
+            TemplateHash(blake2b256_hash(&[3])),
+            "Other",
+            ContractVersion::new(9, 0, 0),
+        );
+        assert_eq!(registry.len(), 3);
+        let versions = registry.versions("Pool");
+        assert_eq!(
+            versions.iter().map(|(h, _)| *h).collect::<Vec<_>>(),
+            vec![v1, v2]
+        );
+        assert_eq!(registry.latest("Pool").unwrap().0, v2);
+        assert!(registry.latest("Unknown").is_none());
+        let replaced = registry.register(v2, "Pool", ContractVersion::new(2, 0, 2));
+        assert_eq!(replaced.unwrap().version, ContractVersion::new(2, 0, 1));
+        assert!(registry.unregister(&v1).is_some());
+        assert_eq!(registry.versions("Pool").len(), 1);
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            ContractVersion::from_str("1.2.3").unwrap(),
+            ContractVersion::new(1, 2, 3)
+        );
+        assert!(ContractVersion::from_str("1.2").is_err());
+        assert!(ContractVersion::from_str("1.2.x").is_err());
+        assert!(ContractVersion::new(1, 10, 0) > ContractVersion::new(1, 9, 5));
+    }
+
+    #[cfg(feature = "known_templates")]
+    #[test]
+    fn test_known_templates() {
+        use ergotree_ir::serialization::SigmaSerializable;
+        let registry = TemplateRegistry::with_known_templates();
+        assert_eq!(registry.len(), known_templates::KNOWN_TEMPLATES.len());
+        for (name, version, tree_hex) in known_templates::KNOWN_TEMPLATES {
+            let tree = ErgoTree::sigma_parse_bytes(&base16::decode(tree_hex).unwrap()).unwrap();
+            let known = registry.recognize(&tree).unwrap();
+            assert_eq!(known.name, *name);
+            assert_eq!(known.version, *version);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Bundled data set of the known dApp contracts
+
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::serialization::SigmaSerializable;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+
+use super::well_known::ORACLE_POOL_V2_POOL_TREE_HEX;
+use super::well_known::SIGMAUSD_BANK_TREE_HEX;
+use super::ContractVersion;
+use super::TemplateRegistry;
+
+/// Known contracts as (name, version, base16-encoded ErgoTree of a deployed instance)
+pub(crate) const KNOWN_TEMPLATES: &[(&str, ContractVersion, &str)] = &[
+    // Mainnet bank contract from EIP-15 (https://github.com/ergoplatform/eips/pull/27)
+    (
+        "SigmaUSD bank",
+        ContractVersion::new(1, 0, 0),
+        SIGMAUSD_BANK_TREE_HEX,
+    ),
+    // Pool box contract of the oracle pool v2 (EIP-23)
+    (
+        "Oracle pool v2 pool",
+        ContractVersion::new(2, 0, 0),
+        ORACLE_POOL_V2_POOL_TREE_HEX,
+    ),
+    // Token-to-token AMM pool
+    (
+        "Spectrum T2T pool",
+        ContractVersion::new(1, 0, 0),
+        "19a3030f0400040204020404040404060406058080a0f6f4acdbe01b058080a0f6f4acdbe01b050004d00f0400040005000500d81ad601b2a5730000d602e4c6a70405d603db63087201d604db6308a7d605b27203730100d606b27204730200d607b27203730300d608b27204730400d609b27203730500d60ab27204730600d60b9973078c720602d60c999973088c720502720bd60d8c720802d60e998c720702720dd60f91720e7309d6108c720a02d6117e721006d6127e720e06d613998c7209027210d6147e720d06d615730ad6167e721306d6177e720c06d6187e720b06d6199c72127218d61a9c72167218d1edededededed93c27201c2a793e4c672010405720292c17201c1a793b27203730b00b27204730c00938c7205018c720601ed938c7207018c720801938c7209018c720a019593720c730d95720f929c9c721172127e7202069c7ef07213069a9c72147e7215067e9c720e720206929c9c721472167e7202069c7ef0720e069a9c72117e7215067e9c721372020695ed720f917213730e907217a19d721972149d721a7211ed9272199c7217721492721a9c72177211",
+    ),
+];
+
+pub(crate) fn register_known_templates(registry: &mut TemplateRegistry) {
+    for (name, version, tree_hex) in KNOWN_TEMPLATES {
+        let tree = decode_base16(tree_hex, DecodingMode::Strict)
+            .ok()
+            .and_then(|bytes| ErgoTree::sigma_parse_bytes(&bytes).ok());
+        if let Some(tree) = tree {
+            let _ = registry.register_ergo_tree(&tree, *name, *version);
+        }
+    }
+}
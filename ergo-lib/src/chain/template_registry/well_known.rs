@@ -0,0 +1,428 @@
+//! Recognition of the well-known contracts with their parameters extracted from the ErgoTree
+//! constants
+
+use std::convert::TryFrom;
+
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::ergo_tree::ErgoTreeError;
+use ergotree_ir::mir::constant::TryExtractInto;
+use ergotree_ir::serialization::SigmaSerializable;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+
+use super::ContractVersion;
+use super::TemplateRegistry;
+use crate::chain::reemission::MAINNET_REEMISSION_SETTINGS;
+use crate::wallet::miner_fee::MINERS_FEE_ERGO_TREE;
+
+/// Mainnet SigmaUSD bank contract (EIP-15)
+pub(crate) const SIGMAUSD_BANK_TREE_HEX: &str = "102a0400040004000e20011d3364de07e5a26f0c4eef0852cddb387039a921b7154ef3cab22c6eda887f0400040204020400040004020500050005c8010500050005feffffffffffffffff0105000580897a05000580897a040405c80104c0933805c00c0580a8d6b907050005c8010580dac40905000500040404040500050005a0060101050005a0060100040004000e20239c170b7e82f94e6b05416f14b8a2a57e0bfff0e3c93f4abbcd160b6a5b271ad801d601db6501fed1ec9591b172017300d821d602b27201730100d603938cb2db63087202730200017303d604b2a5730400d605c17204d606db6308a7d607b27206730500d6088c720702d609db63087204d60ab27209730600d60b8c720a02d60c947208720bd60db27206730700d60e8c720d02d60fb27209730800d6108c720f02d61194720e7210d612e4c6a70505d613e4c672040505d614e4c6a70405d615e4c672040405d616b2a5730900d617e4c672160405d61895720c730a7217d61995720c7217730bd61ac1a7d61be4c672160505d61c9de4c672020405730cd61da2a1721a9c7214721c730dd61e9572119ca1721c95937214730e730f9d721d72147218d801d61e99721a721d9c9593721e7310731195937212731273139d721e72127219d61f9d9c721e7e7314057315d6209c7215721cd6219591a3731673177318d62295937220731972219d9c7205731a7220edededed7203ededededed927205731b93c27204c2a7edec720c7211efed720c7211ed939a720872129a720b7213939a720e72149a72107215edededed939a721472187215939a721272197213939a721a721b7205927215731c927213731deded938c720f018c720d01938c720a018c720701938cb27209731e00018cb27206731f000193721b9a721e958f721f7320f0721f721f957211959172187321927222732273239591721973249072227221927222732572037326938cb2db6308b2a4732700732800017329";
+
+/// Oracle pool v2 pool box contract (EIP-23) of the oracle-core deployments, the refresh and
+/// update NFT ids are the constants 2 and 3
+pub(crate) const ORACLE_POOL_V2_POOL_TREE_HEX: &str = "1004040204000e20546a576e5a7234753778214125442a472d4b614e645267556b587032733576380e206251655468576d5a7134743777217a25432a462d4a404e635266556a586e3272d801d6018cb2db6308b2a473000073010001d1ec93720173029372017303";
+
+/// EIP-31 babel fee box contract with the token id (constant 1) zeroed
+pub(crate) const BABEL_FEE_TREE_HEX: &str = "100604000e2000000000000000000000000000000000000000000000000000000000000000000400040005000500d803d601e30004d602e4c6a70408d603e4c6a7050595e67201d804d604b2a5e4720100d605b2db63087204730000d606db6308a7d60799c1a7c17204d1968302019683050193c27204c2a7938c720501730193e4c672040408720293e4c672040505720393e4c67204060ec5a796830201929c998c7205029591b1720673028cb272067303000273047203720792720773057202";
+
+/// Kind of the well-known contract (the template it is recognized by)
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum WellKnownContractKind {
+    /// Miner fee proposition
+    MinerFee,
+    /// EIP-27 pay-to-reemission contract
+    PayToReemission,
+    /// EIP-31 babel fee box contract
+    BabelFee,
+    /// Oracle pool v2 pool box contract
+    OraclePoolV2,
+    /// SigmaUSD bank contract (EIP-15)
+    SigmaUsdBank,
+}
+
+impl WellKnownContractKind {
+    /// All kinds
+    pub const ALL: [WellKnownContractKind; 5] = [
+        WellKnownContractKind::MinerFee,
+        WellKnownContractKind::PayToReemission,
+        WellKnownContractKind::BabelFee,
+        WellKnownContractKind::OraclePoolV2,
+        WellKnownContractKind::SigmaUsdBank,
+    ];
+
+    /// Name of the contract in the [`TemplateRegistry`] (the same as in the bundled data set of
+    /// the known templates)
+    pub fn name(&self) -> &'static str {
+        match self {
+            WellKnownContractKind::MinerFee => "Miner fee",
+            WellKnownContractKind::PayToReemission => "Pay-to-reemission",
+            WellKnownContractKind::BabelFee => "Babel fee",
+            WellKnownContractKind::OraclePoolV2 => "Oracle pool v2 pool",
+            WellKnownContractKind::SigmaUsdBank => "SigmaUSD bank",
+        }
+    }
+
+    /// Version of the contract the template is registered with
+    pub fn version(&self) -> ContractVersion {
+        match self {
+            WellKnownContractKind::OraclePoolV2 => ContractVersion::new(2, 0, 0),
+            WellKnownContractKind::MinerFee
+            | WellKnownContractKind::PayToReemission
+            | WellKnownContractKind::BabelFee
+            | WellKnownContractKind::SigmaUsdBank => ContractVersion::new(1, 0, 0),
+        }
+    }
+
+    /// Kind of the contract registered in the [`TemplateRegistry`] under the given name
+    pub fn from_name(name: &str) -> Option<WellKnownContractKind> {
+        WellKnownContractKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == name)
+    }
+}
+
+/// Well-known contract with the parameters extracted from its ErgoTree
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum WellKnownContract {
+    /// Miner fee proposition
+    MinerFee {
+        /// Number of blocks the miner's reward is locked for
+        reward_delay: i32,
+    },
+    /// EIP-27 pay-to-reemission contract
+    PayToReemission {
+        /// Id of the NFT held by the re-emission box
+        reemission_nft_id: TokenId,
+    },
+    /// EIP-31 babel fee box contract
+    BabelFee {
+        /// Id of the token the fee is paid in
+        token_id: TokenId,
+    },
+    /// Oracle pool v2 pool box contract
+    OraclePoolV2 {
+        /// Id of the refresh NFT
+        refresh_nft_id: TokenId,
+        /// Id of the update NFT
+        update_nft_id: TokenId,
+    },
+    /// SigmaUSD bank contract (EIP-15)
+    SigmaUsdBank {
+        /// Id of the NFT of the oracle pool providing the rate
+        oracle_pool_nft_id: TokenId,
+        /// Id of the update NFT
+        update_nft_id: TokenId,
+    },
+}
+
+impl WellKnownContract {
+    /// Kind of the contract
+    pub fn kind(&self) -> WellKnownContractKind {
+        match self {
+            WellKnownContract::MinerFee { .. } => WellKnownContractKind::MinerFee,
+            WellKnownContract::PayToReemission { .. } => WellKnownContractKind::PayToReemission,
+            WellKnownContract::BabelFee { .. } => WellKnownContractKind::BabelFee,
+            WellKnownContract::OraclePoolV2 { .. } => WellKnownContractKind::OraclePoolV2,
+            WellKnownContract::SigmaUsdBank { .. } => WellKnownContractKind::SigmaUsdBank,
+        }
+    }
+}
+
+/// Recognizer of the well-known contracts by their template hashes, registered in the
+/// [`TemplateRegistry`] under the [`WellKnownContractKind::name`]
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct WellKnownContracts {
+    registry: TemplateRegistry,
+}
+
+impl WellKnownContracts {
+    /// Recognizer without any templates
+    pub fn new() -> Self {
+        WellKnownContracts::default()
+    }
+
+    /// Recognizer of the well-known contracts among the templates of the given registry (e.g.
+    /// [`TemplateRegistry::with_known_templates`]), the other templates are not recognized
+    pub fn from_registry(registry: TemplateRegistry) -> Self {
+        WellKnownContracts { registry }
+    }
+
+    /// Recognizer of the mainnet miner fee, pay-to-reemission, babel fee, oracle pool v2 pool and
+    /// SigmaUSD bank contracts. Other deployments (contract versions) are added with
+    /// [`Self::register`].
+    #[allow(clippy::unwrap_used)]
+    pub fn mainnet() -> Self {
+        let mut contracts = WellKnownContracts::new();
+        // the trees are built by the library or parsed from the test-covered constants, so
+        // unwraps never fail
+        contracts
+            .register(WellKnownContractKind::MinerFee, &MINERS_FEE_ERGO_TREE)
+            .unwrap();
+        contracts
+            .register(
+                WellKnownContractKind::PayToReemission,
+                &MAINNET_REEMISSION_SETTINGS.pay_to_reemission_contract(),
+            )
+            .unwrap();
+        for (kind, tree_hex) in [
+            (WellKnownContractKind::BabelFee, BABEL_FEE_TREE_HEX),
+            (
+                WellKnownContractKind::OraclePoolV2,
+                ORACLE_POOL_V2_POOL_TREE_HEX,
+            ),
+            (WellKnownContractKind::SigmaUsdBank, SIGMAUSD_BANK_TREE_HEX),
+        ] {
+            let tree = ErgoTree::sigma_parse_bytes(
+                &decode_base16(tree_hex, DecodingMode::Strict).unwrap(),
+            )
+            .unwrap();
+            contracts.register(kind, &tree).unwrap();
+        }
+        contracts
+    }
+
+    /// Register the template of the given (deployed) contract tree.
+    /// Returns the kind previously registered for the template, if any.
+    pub fn register(
+        &mut self,
+        kind: WellKnownContractKind,
+        tree: &ErgoTree,
+    ) -> Result<Option<WellKnownContractKind>, ErgoTreeError> {
+        Ok(self
+            .registry
+            .register_ergo_tree(tree, kind.name(), kind.version())?
+            .and_then(|known| WellKnownContractKind::from_name(&known.name)))
+    }
+
+    /// Registry of the templates
+    pub fn registry(&self) -> &TemplateRegistry {
+        &self.registry
+    }
+
+    /// Kind of the contract registered for the template of the given tree
+    pub fn kind(&self, tree: &ErgoTree) -> Option<WellKnownContractKind> {
+        self.registry
+            .recognize(tree)
+            .and_then(|known| WellKnownContractKind::from_name(&known.name))
+    }
+
+    /// Recognize the contract and extract its parameters.
+    /// Returns `None` for the unknown trees and the trees which parameters can't be extracted.
+    pub fn recognize(&self, tree: &ErgoTree) -> Option<WellKnownContract> {
+        let kind = self.kind(tree)?;
+        let token_ids = token_id_constants(tree)?;
+        match kind {
+            WellKnownContractKind::MinerFee => {
+                // the miner's reward script (with the delay as the first constant) is embedded
+                // into the fee proposition as bytes
+                let reward_script = tree
+                    .get_constants()
+                    .ok()?
+                    .into_iter()
+                    .find_map(|c| c.try_extract_into::<Vec<u8>>().ok())?;
+                let reward_delay = ErgoTree::sigma_parse_bytes(&reward_script)
+                    .ok()?
+                    .get_constant(0)
+                    .ok()??
+                    .try_extract_into::<i32>()
+                    .ok()?;
+                Some(WellKnownContract::MinerFee { reward_delay })
+            }
+            WellKnownContractKind::PayToReemission => Some(WellKnownContract::PayToReemission {
+                reemission_nft_id: *token_ids.first()?,
+            }),
+            WellKnownContractKind::BabelFee => Some(WellKnownContract::BabelFee {
+                token_id: *token_ids.first()?,
+            }),
+            WellKnownContractKind::OraclePoolV2 => match token_ids.as_slice() {
+                [refresh_nft_id, update_nft_id, ..] => Some(WellKnownContract::OraclePoolV2 {
+                    refresh_nft_id: *refresh_nft_id,
+                    update_nft_id: *update_nft_id,
+                }),
+                _ => None,
+            },
+            WellKnownContractKind::SigmaUsdBank => match token_ids.as_slice() {
+                [oracle_pool_nft_id, .., update_nft_id] => Some(WellKnownContract::SigmaUsdBank {
+                    oracle_pool_nft_id: *oracle_pool_nft_id,
+                    update_nft_id: *update_nft_id,
+                }),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Token ids (32 bytes long `Coll[Byte]` constants) of the tree in the constants order
+fn token_id_constants(tree: &ErgoTree) -> Option<Vec<TokenId>> {
+    Some(
+        tree.get_constants()
+            .ok()?
+            .into_iter()
+            .filter_map(|c| c.try_extract_into::<Vec<u8>>().ok())
+            .filter_map(|bytes| TokenId::try_from(bytes.as_slice()).ok())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergotree_ir::ergo_tree::ErgoTreeHeader;
+    use ergotree_ir::mir::bin_op::BinOp;
+    use ergotree_ir::mir::bin_op::RelationOp;
+    use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
+    use ergotree_ir::mir::constant::Constant;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::unary_op::OneArgOpTryBuild;
+
+    use super::*;
+    use crate::chain::reemission::ReemissionSettings;
+    use crate::wallet::miner_fee::fee_proposition;
+
+    fn token_id(b: u8) -> TokenId {
+        TokenId::from([b; 32])
+    }
+
+    fn ids_eq_tree(a: TokenId, b: TokenId) -> ErgoTree {
+        let a: Vec<u8> = a.into();
+        let b: Vec<u8> = b.into();
+        let eq = BinOp {
+            kind: RelationOp::Eq.into(),
+            left: Box::new(Constant::from(a).into()),
+            right: Box::new(Constant::from(b).into()),
+        };
+        let expr: Expr = BoolToSigmaProp::try_build(eq.into()).unwrap().into();
+        ErgoTree::new(ErgoTreeHeader::v0(true), &expr).unwrap()
+    }
+
+    #[test]
+    fn test_recognize_mainnet() {
+        let contracts = WellKnownContracts::mainnet();
+        assert_eq!(
+            contracts.recognize(&MINERS_FEE_ERGO_TREE),
+            Some(WellKnownContract::MinerFee { reward_delay: 720 })
+        );
+        assert_eq!(
+            contracts.recognize(&fee_proposition(10)),
+            Some(WellKnownContract::MinerFee { reward_delay: 10 })
+        );
+        let settings = ReemissionSettings {
+            reemission_nft_id: token_id(7),
+            ..MAINNET_REEMISSION_SETTINGS.clone()
+        };
+        assert_eq!(
+            contracts.recognize(&settings.pay_to_reemission_contract()),
+            Some(WellKnownContract::PayToReemission {
+                reemission_nft_id: token_id(7)
+            })
+        );
+        let bank_tree =
+            ErgoTree::sigma_parse_bytes(&base16::decode(SIGMAUSD_BANK_TREE_HEX).unwrap()).unwrap();
+        let bank = contracts.recognize(&bank_tree).unwrap();
+        assert_eq!(bank.kind(), WellKnownContractKind::SigmaUsdBank);
+        assert_eq!(
+            bank,
+            WellKnownContract::SigmaUsdBank {
+                oracle_pool_nft_id:
+                    "011d3364de07e5a26f0c4eef0852cddb387039a921b7154ef3cab22c6eda887f"
+                        .parse()
+                        .unwrap(),
+                update_nft_id: "239c170b7e82f94e6b05416f14b8a2a57e0bfff0e3c93f4abbcd160b6a5b271a"
+                    .parse()
+                    .unwrap(),
+            }
+        );
+        let babel_fee_tree = ErgoTree::sigma_parse_bytes(
+            &base16::decode(&BABEL_FEE_TREE_HEX.replace(
+                &format!("0e20{}", "00".repeat(32)),
+                &format!("0e20{}", "05".repeat(32)),
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            contracts.recognize(&babel_fee_tree),
+            Some(WellKnownContract::BabelFee {
+                token_id: token_id(5)
+            })
+        );
+        let pool_tree =
+            ErgoTree::sigma_parse_bytes(&base16::decode(ORACLE_POOL_V2_POOL_TREE_HEX).unwrap())
+                .unwrap();
+        assert_eq!(
+            contracts.recognize(&pool_tree),
+            Some(WellKnownContract::OraclePoolV2 {
+                refresh_nft_id: "546a576e5a7234753778214125442a472d4b614e645267556b58703273357638"
+                    .parse()
+                    .unwrap(),
+                update_nft_id: "6251655468576d5a7134743777217a25432a462d4a404e635266556a586e3272"
+                    .parse()
+                    .unwrap(),
+            })
+        );
+        assert_eq!(
+            contracts.recognize(&ids_eq_tree(token_id(1), token_id(2))),
+            None
+        );
+        assert_eq!(contracts.registry().len(), WellKnownContractKind::ALL.len());
+    }
+
+    #[test]
+    fn test_kind_names() {
+        for kind in WellKnownContractKind::ALL {
+            assert_eq!(WellKnownContractKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(WellKnownContractKind::from_name("Spectrum T2T pool"), None);
+    }
+
+    #[cfg(feature = "known_templates")]
+    #[test]
+    fn test_from_known_templates() {
+        let contracts = WellKnownContracts::from_registry(TemplateRegistry::with_known_templates());
+        let bank_tree =
+            ErgoTree::sigma_parse_bytes(&base16::decode(SIGMAUSD_BANK_TREE_HEX).unwrap()).unwrap();
+        assert_eq!(
+            contracts.kind(&bank_tree),
+            Some(WellKnownContractKind::SigmaUsdBank)
+        );
+        let pool_tree =
+            ErgoTree::sigma_parse_bytes(&base16::decode(ORACLE_POOL_V2_POOL_TREE_HEX).unwrap())
+                .unwrap();
+        assert_eq!(
+            contracts.kind(&pool_tree),
+            Some(WellKnownContractKind::OraclePoolV2)
+        );
+    }
+
+    #[test]
+    fn test_register() {
+        let mut contracts = WellKnownContracts::new();
+        let tree = ids_eq_tree(token_id(1), token_id(2));
+        assert_eq!(
+            contracts
+                .register(WellKnownContractKind::OraclePoolV2, &tree)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            contracts.recognize(&ids_eq_tree(token_id(3), token_id(4))),
+            Some(WellKnownContract::OraclePoolV2 {
+                refresh_nft_id: token_id(3),
+                update_nft_id: token_id(4),
+            })
+        );
+        assert_eq!(
+            contracts
+                .register(WellKnownContractKind::BabelFee, &tree)
+                .unwrap(),
+            Some(WellKnownContractKind::OraclePoolV2)
+        );
+        assert_eq!(
+            contracts.recognize(&tree),
+            Some(WellKnownContract::BabelFee {
+                token_id: token_id(1)
+            })
+        );
+    }
+}
@@ -1,18 +1,22 @@
 //! Ergo transaction
 
+pub mod canonical;
 mod data_input;
+pub mod dependency_graph;
 pub mod input;
 pub mod reduced;
 pub mod unsigned;
+pub mod view;
 
 use bounded_vec::BoundedVec;
-use ergo_chain_types::blake2b256_hash;
+use ergo_chain_types::Digest32;
 pub use ergotree_interpreter::eval::context::TxIoVec;
 use ergotree_interpreter::eval::env::Env;
 use ergotree_interpreter::eval::extract_sigma_boolean;
 use ergotree_interpreter::eval::EvalError;
 use ergotree_interpreter::sigma_protocol::verifier::verify_signature;
 use ergotree_interpreter::sigma_protocol::verifier::TestVerifier;
+use ergotree_interpreter::sigma_protocol::verifier::VerificationFailureReason;
 use ergotree_interpreter::sigma_protocol::verifier::Verifier;
 use ergotree_interpreter::sigma_protocol::verifier::VerifierError;
 use ergotree_ir::chain::ergo_box::BoxId;
@@ -21,17 +25,22 @@ use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
 use ergotree_ir::chain::token::TokenId;
 pub use ergotree_ir::chain::tx_id::TxId;
 use ergotree_ir::ergo_tree::ErgoTreeError;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
 use thiserror::Error;
 
 pub use data_input::*;
+use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
 use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
 use ergotree_ir::serialization::sigma_byte_reader::SigmaByteRead;
 use ergotree_ir::serialization::sigma_byte_writer::SigmaByteWrite;
+use ergotree_ir::serialization::sigma_byte_writer::SigmaByteWriter;
 use ergotree_ir::serialization::SigmaParsingError;
 use ergotree_ir::serialization::SigmaSerializable;
 use ergotree_ir::serialization::SigmaSerializationError;
 use ergotree_ir::serialization::SigmaSerializeResult;
 pub use input::*;
+use sigma_ser::byte_counter::CountingWriter;
+use sigma_util::hash::Blake2b256Writer;
 
 use crate::wallet::signing::make_context;
 use crate::wallet::signing::TransactionContext;
@@ -41,12 +50,16 @@ use self::unsigned::UnsignedTransaction;
 
 use indexmap::IndexSet;
 
+use std::borrow::Borrow;
 use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::io::Write;
 use std::iter::FromIterator;
 use std::rc::Rc;
 
 use super::ergo_state_context::ErgoStateContext;
+use super::storage_rent::check_storage_rent;
+use super::storage_rent::STORAGE_CONTRACT_COST;
 
 /**
  * ErgoTransaction is an atomic state transition operation. It destroys Boxes from the state
@@ -114,28 +127,35 @@ impl Transaction {
         data_inputs: Option<TxIoVec<DataInput>>,
         output_candidates: TxIoVec<ErgoBoxCandidate>,
     ) -> Result<Transaction, SigmaSerializationError> {
-        let outputs_with_zero_tx_id =
-            output_candidates
-                .clone()
-                .enumerated()
-                .try_mapped_ref(|(idx, bc)| {
-                    ErgoBox::from_box_candidate(bc, TxId::zero(), *idx as u16)
-                })?;
-        let tx_to_sign = Transaction {
-            tx_id: TxId::zero(),
-            inputs,
-            data_inputs,
-            output_candidates: output_candidates.clone(),
-            outputs: outputs_with_zero_tx_id,
-        };
-        let tx_id = tx_to_sign.calc_tx_id()?;
+        let tx_id = calc_tx_id(
+            inputs
+                .iter()
+                .map(|i| (i.box_id, &i.spending_proof.extension)),
+            &data_inputs,
+            &output_candidates,
+        )?;
+        Transaction::with_tx_id(tx_id, inputs, data_inputs, output_candidates)
+    }
+
+    /// Creates new transaction with the id already calculated for the same inputs (the proofs
+    /// are not part of the id), data inputs and outputs, e.g. the id of the unsigned transaction
+    /// being signed. Skips the id re-calculation.
+    pub(crate) fn with_tx_id(
+        tx_id: TxId,
+        inputs: TxIoVec<Input>,
+        data_inputs: Option<TxIoVec<DataInput>>,
+        output_candidates: TxIoVec<ErgoBoxCandidate>,
+    ) -> Result<Transaction, SigmaSerializationError> {
         let outputs = output_candidates
+            .clone()
             .enumerated()
             .try_mapped_ref(|(idx, bc)| ErgoBox::from_box_candidate(bc, tx_id, *idx as u16))?;
         Ok(Transaction {
             tx_id,
+            inputs,
+            data_inputs,
+            output_candidates,
             outputs,
-            ..tx_to_sign
         })
     }
 
@@ -145,6 +165,7 @@ impl Transaction {
         unsigned_tx: UnsignedTransaction,
         proofs: Vec<ProofBytes>,
     ) -> Result<Self, TransactionError> {
+        let tx_id = unsigned_tx.id();
         let inputs = unsigned_tx
             .inputs
             .enumerated()
@@ -159,26 +180,35 @@ impl Transaction {
                         ))
                     })
             })?;
-        Ok(Transaction::new(
+        Ok(Transaction::with_tx_id(
+            tx_id,
             inputs,
             unsigned_tx.data_inputs,
             unsigned_tx.output_candidates,
         )?)
     }
 
-    fn calc_tx_id(&self) -> Result<TxId, SigmaSerializationError> {
-        let bytes = self.bytes_to_sign()?;
-        Ok(TxId(blake2b256_hash(&bytes)))
-    }
-
     /// Serialized tx with empty proofs
     pub fn bytes_to_sign(&self) -> Result<Vec<u8>, SigmaSerializationError> {
-        let empty_proof_inputs = self.inputs.mapped_ref(|i| i.input_to_sign());
-        let tx_to_sign = Transaction {
-            inputs: empty_proof_inputs,
-            ..(*self).clone()
-        };
-        tx_to_sign.sigma_serialize_bytes()
+        let mut bytes = Vec::new();
+        self.write_bytes_to_sign(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Write the serialized tx with empty proofs (see [`Transaction::bytes_to_sign`]) to any
+    /// writer without cloning the transaction. Returns the number of bytes written
+    pub fn write_bytes_to_sign<W: Write + ?Sized>(
+        &self,
+        w: &mut W,
+    ) -> Result<usize, SigmaSerializationError> {
+        write_tx_bytes_to_sign(
+            w,
+            self.inputs
+                .iter()
+                .map(|i| (i.box_id, &i.spending_proof.extension)),
+            &self.data_inputs,
+            &self.output_candidates,
+        )
     }
 
     /// Get transaction id
@@ -186,6 +216,25 @@ impl Transaction {
         self.tx_id
     }
 
+    /// Boxes created by this transaction, with box ids computed from the transaction id and the
+    /// output index (computed once on transaction creation)
+    pub fn outputs(&self) -> &TxIoVec<ErgoBox> {
+        &self.outputs
+    }
+
+    /// Ids of the boxes created by this transaction, in the outputs order
+    pub fn output_ids(&self) -> impl Iterator<Item = BoxId> + '_ {
+        self.outputs.iter().map(ErgoBox::box_id)
+    }
+
+    /// Box created by this transaction with the given id (and its index in the outputs)
+    pub fn output_by_id(&self, box_id: &BoxId) -> Option<(usize, &ErgoBox)> {
+        self.outputs
+            .iter()
+            .enumerate()
+            .find(|(_, b)| b.box_id() == *box_id)
+    }
+
     /// Check the signature of the transaction's input corresponding
     /// to the given input box, guarded by P2PK script
     pub fn verify_p2pk_input(
@@ -227,14 +276,16 @@ pub enum TransactionSignatureVerificationError {
 /// Returns distinct token ids from all given ErgoBoxCandidate's
 pub fn distinct_token_ids<I>(output_candidates: I) -> IndexSet<TokenId>
 where
-    I: IntoIterator<Item = ErgoBoxCandidate>,
+    I: IntoIterator,
+    I::Item: Borrow<ErgoBoxCandidate>,
 {
     let token_ids: Vec<TokenId> = output_candidates
         .into_iter()
         .flat_map(|b| {
-            b.tokens
-                .into_iter()
-                .flatten()
+            b.borrow()
+                .tokens
+                .iter()
+                .flat_map(|tokens| tokens.iter())
                 .map(|t| t.token_id)
                 .collect::<Vec<TokenId>>()
         })
@@ -242,78 +293,145 @@ where
     IndexSet::<_>::from_iter(token_ids)
 }
 
-impl SigmaSerializable for Transaction {
-    #[allow(clippy::unwrap_used)]
-    fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
-        // reference implementation - https://github.com/ScorexFoundation/sigmastate-interpreter/blob/9b20cb110effd1987ff76699d637174a4b2fb441/sigmastate/src/main/scala/org/ergoplatform/ErgoLikeTransaction.scala#L112-L112
-        w.put_usize_as_u16_unwrapped(self.inputs.len())?;
-        self.inputs.iter().try_for_each(|i| i.sigma_serialize(w))?;
-        if let Some(data_inputs) = &self.data_inputs {
-            w.put_usize_as_u16_unwrapped(data_inputs.len())?;
-            data_inputs.iter().try_for_each(|i| i.sigma_serialize(w))?;
-        } else {
-            w.put_u16(0)?;
-        }
+/// Transaction id: Blake2b256 hash of the bytes to sign, hashed as they are serialized
+pub(crate) fn calc_tx_id<'a>(
+    inputs: impl ExactSizeIterator<Item = (BoxId, &'a ContextExtension)>,
+    data_inputs: &Option<TxIoVec<DataInput>>,
+    output_candidates: &TxIoVec<ErgoBoxCandidate>,
+) -> Result<TxId, SigmaSerializationError> {
+    let mut hasher = Blake2b256Writer::new();
+    write_tx_bytes_to_sign(&mut hasher, inputs, data_inputs, output_candidates)?;
+    Ok(TxId(Digest32::from(hasher.finalize())))
+}
 
-        // Serialize distinct ids of tokens in transaction outputs.
-        let distinct_token_ids = distinct_token_ids(self.output_candidates.clone());
+/// Writes the transaction with empty proofs in the inputs (message to sign, tx id preimage).
+/// Returns the number of bytes written
+pub(crate) fn write_tx_bytes_to_sign<'a, W: Write + ?Sized>(
+    w: &mut W,
+    mut inputs: impl ExactSizeIterator<Item = (BoxId, &'a ContextExtension)>,
+    data_inputs: &Option<TxIoVec<DataInput>>,
+    output_candidates: &TxIoVec<ErgoBoxCandidate>,
+) -> Result<usize, SigmaSerializationError> {
+    let mut counting = CountingWriter::new(w);
+    let mut w = SigmaByteWriter::new(&mut counting, None);
+    let inputs_count = inputs.len();
+    serialize_tx_body(
+        &mut w,
+        inputs_count,
+        move |w| {
+            inputs.try_for_each(|(box_id, extension)| {
+                box_id.sigma_serialize(w)?;
+                ProofBytes::Empty.sigma_serialize(w)?;
+                extension.sigma_serialize(w)
+            })
+        },
+        data_inputs,
+        output_candidates,
+    )?;
+    Ok(counting.bytes_written())
+}
 
-        // Note that `self.output_candidates` is of type `TxIoVec` which has a max length of
-        // `u16::MAX`. Therefore the following unwrap is safe.
-        w.put_u32(u32::try_from(distinct_token_ids.len()).unwrap())?;
-        distinct_token_ids
-            .iter()
-            .try_for_each(|t_id| t_id.sigma_serialize(w))?;
-
-        // serialize outputs
-        w.put_usize_as_u16_unwrapped(self.output_candidates.len())?;
-        self.output_candidates.iter().try_for_each(|o| {
-            ErgoBoxCandidate::serialize_body_with_indexed_digests(o, Some(&distinct_token_ids), w)
-        })?;
-        Ok(())
+#[allow(clippy::unwrap_used)]
+fn serialize_tx_body<W: SigmaByteWrite>(
+    w: &mut W,
+    inputs_count: usize,
+    serialize_inputs: impl FnOnce(&mut W) -> SigmaSerializeResult,
+    data_inputs: &Option<TxIoVec<DataInput>>,
+    output_candidates: &TxIoVec<ErgoBoxCandidate>,
+) -> SigmaSerializeResult {
+    // reference implementation - https://github.com/ScorexFoundation/sigmastate-interpreter/blob/9b20cb110effd1987ff76699d637174a4b2fb441/sigmastate/src/main/scala/org/ergoplatform/ErgoLikeTransaction.scala#L112-L112
+    w.put_usize_as_u16_unwrapped(inputs_count)?;
+    serialize_inputs(w)?;
+    if let Some(data_inputs) = data_inputs {
+        w.put_usize_as_u16_unwrapped(data_inputs.len())?;
+        data_inputs.iter().try_for_each(|i| i.sigma_serialize(w))?;
+    } else {
+        w.put_u16(0)?;
+    }
+
+    // Serialize distinct ids of tokens in transaction outputs.
+    let distinct_token_ids = distinct_token_ids(output_candidates.iter());
+
+    // Note that `output_candidates` is of type `TxIoVec` which has a max length of
+    // `u16::MAX`. Therefore the following unwrap is safe.
+    w.put_u32(u32::try_from(distinct_token_ids.len()).unwrap())?;
+    distinct_token_ids
+        .iter()
+        .try_for_each(|t_id| t_id.sigma_serialize(w))?;
+
+    // serialize outputs
+    w.put_usize_as_u16_unwrapped(output_candidates.len())?;
+    output_candidates.iter().try_for_each(|o| {
+        ErgoBoxCandidate::serialize_body_with_indexed_digests(o, Some(&distinct_token_ids), w)
+    })?;
+    Ok(())
+}
+
+impl SigmaSerializable for Transaction {
+    fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
+        serialize_tx_body(
+            w,
+            self.inputs.len(),
+            |w| self.inputs.iter().try_for_each(|i| i.sigma_serialize(w)),
+            &self.data_inputs,
+            &self.output_candidates,
+        )
     }
 
     fn sigma_parse<R: SigmaByteRead>(r: &mut R) -> Result<Self, SigmaParsingError> {
         // reference implementation - https://github.com/ScorexFoundation/sigmastate-interpreter/blob/9b20cb110effd1987ff76699d637174a4b2fb441/sigmastate/src/main/scala/org/ergoplatform/ErgoLikeTransaction.scala#L146-L146
 
-        // parse transaction inputs
-        let inputs_count = r.get_u16()?;
-        let mut inputs = Vec::with_capacity(inputs_count as usize);
-        for _ in 0..inputs_count {
-            inputs.push(Input::sigma_parse(r)?);
-        }
+        r.parse_in_context(
+            || "tx".to_string(),
+            |r| {
+                // parse transaction inputs
+                let inputs_count = r.get_u16()?;
+                let mut inputs = Vec::with_capacity(inputs_count as usize);
+                for i in 0..inputs_count {
+                    inputs
+                        .push(r.parse_in_context(|| format!("input[{}]", i), Input::sigma_parse)?);
+                }
 
-        // parse transaction data inputs
-        let data_inputs_count = r.get_u16()?;
-        let mut data_inputs = Vec::with_capacity(data_inputs_count as usize);
-        for _ in 0..data_inputs_count {
-            data_inputs.push(DataInput::sigma_parse(r)?);
-        }
+                // parse transaction data inputs
+                let data_inputs_count = r.get_u16()?;
+                let mut data_inputs = Vec::with_capacity(data_inputs_count as usize);
+                for i in 0..data_inputs_count {
+                    data_inputs.push(r.parse_in_context(
+                        || format!("data_input[{}]", i),
+                        DataInput::sigma_parse,
+                    )?);
+                }
 
-        // parse distinct ids of tokens in transaction outputs
-        let tokens_count = r.get_u32()?;
-        if tokens_count as usize > Transaction::MAX_OUTPUTS_COUNT * ErgoBox::MAX_TOKENS_COUNT {
-            return Err(SigmaParsingError::ValueOutOfBounds(
-                "too many tokens in transaction".to_string(),
-            ));
-        }
-        let mut token_ids = IndexSet::with_capacity(tokens_count as usize);
-        for _ in 0..tokens_count {
-            token_ids.insert(TokenId::sigma_parse(r)?);
-        }
+                // parse distinct ids of tokens in transaction outputs
+                let tokens_count = r.get_u32()?;
+                if tokens_count as usize
+                    > Transaction::MAX_OUTPUTS_COUNT * ErgoBox::MAX_TOKENS_COUNT
+                {
+                    return Err(SigmaParsingError::ValueOutOfBounds(
+                        "too many tokens in transaction".to_string(),
+                    ));
+                }
+                let mut token_ids = IndexSet::with_capacity(tokens_count as usize);
+                for i in 0..tokens_count {
+                    token_ids.insert(
+                        r.parse_in_context(|| format!("token_id[{}]", i), TokenId::sigma_parse)?,
+                    );
+                }
 
-        // parse outputs
-        let outputs_count = r.get_u16()?;
-        let mut outputs = Vec::with_capacity(outputs_count as usize);
-        for _ in 0..outputs_count {
-            outputs.push(ErgoBoxCandidate::parse_body_with_indexed_digests(
-                Some(&token_ids),
-                r,
-            )?)
-        }
+                // parse outputs
+                let outputs_count = r.get_u16()?;
+                let mut outputs = Vec::with_capacity(outputs_count as usize);
+                for i in 0..outputs_count {
+                    outputs.push(r.parse_in_context(
+                        || format!("output[{}]", i),
+                        |r| ErgoBoxCandidate::parse_body_with_indexed_digests(Some(&token_ids), r),
+                    )?)
+                }
 
-        Transaction::new_from_vec(inputs, data_inputs, outputs)
-            .map_err(|e| SigmaParsingError::Misc(format!("{}", e)))
+                Transaction::new_from_vec(inputs, data_inputs, outputs)
+                    .map_err(|e| SigmaParsingError::Misc(format!("{}", e)))
+            },
+        )
     }
 }
 
@@ -325,11 +443,11 @@ pub enum TransactionError {
     SigmaSerializationError(#[from] SigmaSerializationError),
     #[error("Tx innvalid argument: {0}")]
     InvalidArgument(String),
-    #[error("Invalid Tx inputs: {0:?}")]
+    #[error("Invalid Tx inputs count: {0}")]
     InvalidInputsCount(bounded_vec::BoundedVecOutOfBounds),
-    #[error("Invalid Tx output_candidates: {0:?}")]
+    #[error("Invalid Tx output candidates count: {0}")]
     InvalidOutputCandidatesCount(bounded_vec::BoundedVecOutOfBounds),
-    #[error("Invalid Tx data inputs: {0:?}")]
+    #[error("Invalid Tx data inputs count: {0}")]
     InvalidDataInputsCount(bounded_vec::BoundedVecOutOfBounds),
     #[error("input with index {0} not found")]
     InputNofFound(usize),
@@ -347,6 +465,24 @@ pub enum TxVerifyError {
     /// VerifierError
     #[error("VerifierError: {0}")]
     VerifierError(#[from] VerifierError),
+    /// Accumulated cost of the inputs scripts evaluation exceeded the limit
+    #[error("Cost limit exceeded: cost {cost} is over the limit of {limit}")]
+    CostLimitExceeded {
+        /// Cost limit
+        limit: u64,
+        /// Accumulated cost at the moment the limit was exceeded
+        cost: u64,
+    },
+    /// Output value is below the minimum for its size (`min_value_per_byte` parameter)
+    #[error("Output {output_idx} value {value} is below the minimum of {min_value} nanoERGs")]
+    OutputValueTooLow {
+        /// Index of the output in the transaction
+        output_idx: usize,
+        /// Output value
+        value: u64,
+        /// Minimum value for the output size
+        min_value: u64,
+    },
 }
 
 /// Verify transaction input's proof
@@ -355,6 +491,211 @@ pub fn verify_tx_input_proof(
     state_context: &ErgoStateContext,
     input_idx: usize,
 ) -> Result<bool, TxVerifyError> {
+    let message_to_sign = tx_context.spending_tx.bytes_to_sign()?;
+    Ok(verify_input(
+        tx_context,
+        state_context,
+        input_idx,
+        message_to_sign.as_slice(),
+        None,
+    )?
+    .result)
+}
+
+/// Verification result of a single transaction input
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct InputVerificationResult {
+    /// Index of the input in the transaction
+    pub input_idx: usize,
+    /// Id of the box spent by the input
+    pub box_id: BoxId,
+    /// `true` if the spending proof satisfies the input box script
+    pub result: bool,
+    /// Cost of the input box script evaluation
+    pub cost: u64,
+    /// SigmaBoolean the input box script was reduced to (`None` for the storage rent spending or
+    /// if the script evaluation failed)
+    pub reduced: Option<SigmaBoolean>,
+    /// Reason of the rejection, `None` if verified
+    pub failure_reason: Option<VerificationFailureReason>,
+}
+
+/// Verification result of all transaction inputs
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TxVerificationResult {
+    /// Verification results in the order of the transaction inputs
+    pub inputs: Vec<InputVerificationResult>,
+    /// Accumulated cost of all inputs scripts evaluation
+    pub total_cost: u64,
+}
+
+impl TxVerificationResult {
+    /// Returns `true` if the proofs of all inputs are valid
+    pub fn is_valid(&self) -> bool {
+        self.inputs.iter().all(|i| i.result)
+    }
+}
+
+/// Verify the proofs of all transaction inputs the same way the node does: every input box script
+/// is evaluated in the context built from `tx_context` and `state_context` and checked against the
+/// input's spending proof. The system parameters of [`ErgoStateContext::parameters`] are used: the
+/// cost of every input is accumulated and checked against `max_block_cost` (the evaluation of an
+/// input script is stopped as soon as its own cost exceeds the limit), every output value has to
+/// be at least `min_value_per_byte` times the output size and storage rent is checked with
+/// `storage_fee_factor`.
+/// Data inputs are required to be present in `tx_context`. Data inputs spent by the inputs of the
+/// same transaction are accepted as by the node (see
+/// [`TransactionContext::validate_data_inputs`] for the opt-in check).
+pub fn verify_transaction(
+    tx_context: &TransactionContext<Transaction>,
+    state_context: &ErgoStateContext,
+) -> Result<TxVerificationResult, TxVerifyError> {
+    let cost_limit = state_context.parameters().max_block_cost as u64;
+    let message_to_sign = prepare_tx_verification(tx_context, state_context)?;
+    let mut accumulated_cost: u64 = 0;
+    aggregate_inputs_verification(
+        (0..tx_context.spending_tx.inputs.len()).map(|input_idx| {
+            // every input is evaluated within the budget left after the previous inputs
+            let res = verify_input(
+                tx_context,
+                state_context,
+                input_idx,
+                message_to_sign.as_slice(),
+                Some(cost_limit.saturating_sub(accumulated_cost)),
+            )
+            .map_err(|e| {
+                // the input cost is reported against the whole budget
+                if let TxVerifyError::CostLimitExceeded { cost, .. } = e {
+                    TxVerifyError::CostLimitExceeded {
+                        limit: cost_limit,
+                        cost: accumulated_cost.saturating_add(cost),
+                    }
+                } else {
+                    e
+                }
+            })?;
+            accumulated_cost = accumulated_cost.saturating_add(res.cost);
+            Ok(res)
+        }),
+        cost_limit,
+    )
+}
+
+/// Verify the proofs of all inputs of the given transactions (e.g. a block) concurrently on the
+/// rayon thread pool. Results are aggregated in the transactions and inputs order, so they are the
+/// same as calling [`verify_transaction`] for every transaction (including which error is returned
+/// when several inputs fail), except that all inputs of a failed transaction are evaluated and
+/// every input is evaluated within the whole cost limit (so the cost reported by
+/// [`TxVerifyError::CostLimitExceeded`] can be higher).
+#[cfg(feature = "parallel")]
+pub fn verify_transaction_parallel(
+    tx_contexts: &[TransactionContext<Transaction>],
+    state_context: &ErgoStateContext,
+) -> Vec<Result<TxVerificationResult, TxVerifyError>> {
+    use rayon::prelude::*;
+
+    let cost_limit = state_context.parameters().max_block_cost as u64;
+    let messages: Vec<Result<Vec<u8>, TxVerifyError>> = tx_contexts
+        .par_iter()
+        .map(|tx_context| prepare_tx_verification(tx_context, state_context))
+        .collect();
+    let jobs: Vec<(usize, usize, &[u8])> = messages
+        .iter()
+        .enumerate()
+        .filter_map(|(tx_idx, message)| message.as_ref().ok().map(|m| (tx_idx, m.as_slice())))
+        .flat_map(|(tx_idx, message)| {
+            (0..tx_contexts[tx_idx].spending_tx.inputs.len())
+                .map(move |input_idx| (tx_idx, input_idx, message))
+        })
+        .collect();
+    let mut input_results = jobs
+        .into_par_iter()
+        .map(|(tx_idx, input_idx, message)| {
+            verify_input(
+                &tx_contexts[tx_idx],
+                state_context,
+                input_idx,
+                message,
+                Some(cost_limit),
+            )
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+    tx_contexts
+        .iter()
+        .zip(messages)
+        .map(|(tx_context, message)| {
+            message?;
+            let inputs: Vec<_> = input_results
+                .by_ref()
+                .take(tx_context.spending_tx.inputs.len())
+                .collect();
+            aggregate_inputs_verification(inputs, cost_limit)
+        })
+        .collect()
+}
+
+/// Check the data inputs are in the context and the output values are not below the minimum,
+/// return the message to sign
+fn prepare_tx_verification(
+    tx_context: &TransactionContext<Transaction>,
+    state_context: &ErgoStateContext,
+) -> Result<Vec<u8>, TxVerifyError> {
+    if let Some(data_inputs) = tx_context.spending_tx.data_inputs.as_ref() {
+        for (idx, data_input) in data_inputs.iter().enumerate() {
+            if tx_context.get_data_input_box(&data_input.box_id).is_none() {
+                return Err(TransactionContextError::DataInputBoxNotFound(idx).into());
+            }
+        }
+    }
+    let min_value_per_byte = state_context.parameters().min_value_per_byte as u64;
+    for (output_idx, output) in tx_context.spending_tx.outputs.iter().enumerate() {
+        let min_value = output.sigma_serialize_bytes()?.len() as u64 * min_value_per_byte;
+        let value = *output.value.as_u64();
+        if value < min_value {
+            return Err(TxVerifyError::OutputValueTooLow {
+                output_idx,
+                value,
+                min_value,
+            });
+        }
+    }
+    Ok(tx_context.spending_tx.bytes_to_sign()?)
+}
+
+/// Accumulate the cost of the inputs (in the inputs order), stop on the first error or when the
+/// cost limit is exceeded
+fn aggregate_inputs_verification<I>(
+    input_results: I,
+    cost_limit: u64,
+) -> Result<TxVerificationResult, TxVerifyError>
+where
+    I: IntoIterator<Item = Result<InputVerificationResult, TxVerifyError>>,
+{
+    let input_results = input_results.into_iter();
+    let mut total_cost: u64 = 0;
+    let mut inputs = Vec::with_capacity(input_results.size_hint().0);
+    for res in input_results {
+        let res = res?;
+        total_cost = total_cost.saturating_add(res.cost);
+        if total_cost > cost_limit {
+            return Err(TxVerifyError::CostLimitExceeded {
+                limit: cost_limit,
+                cost: total_cost,
+            });
+        }
+        inputs.push(res);
+    }
+    Ok(TxVerificationResult { inputs, total_cost })
+}
+
+fn verify_input(
+    tx_context: &TransactionContext<Transaction>,
+    state_context: &ErgoStateContext,
+    input_idx: usize,
+    message_to_sign: &[u8],
+    cost_limit: Option<u64>,
+) -> Result<InputVerificationResult, TxVerifyError> {
     let input = tx_context
         .spending_tx
         .inputs
@@ -363,18 +704,63 @@ pub fn verify_tx_input_proof(
     let input_box = tx_context
         .get_input_box(&input.box_id)
         .ok_or(TransactionContextError::InputBoxNotFound(input_idx))?;
+    // as on the node, the storage rent rules apply only to the inputs without a proof
+    let storage_rent_result = match input.spending_proof.proof {
+        ProofBytes::Empty => check_storage_rent(
+            &input_box,
+            &input.spending_proof.extension,
+            tx_context.spending_tx.output_candidates.as_slice(),
+            state_context.pre_header.height,
+            state_context.parameters().storage_fee_factor as u64,
+        ),
+        ProofBytes::Some(_) => None,
+    };
+    if let Some(result) = storage_rent_result {
+        return Ok(InputVerificationResult {
+            input_idx,
+            box_id: input.box_id,
+            result,
+            cost: STORAGE_CONTRACT_COST,
+            reduced: None,
+            failure_reason: (!result).then_some(VerificationFailureReason::ScriptFalse),
+        });
+    }
     let ctx = Rc::new(make_context(state_context, tx_context, input_idx)?);
     let verifier = TestVerifier;
-    let message_to_sign = tx_context.spending_tx.bytes_to_sign()?;
-    Ok(verifier
-        .verify(
+    let proof = input.spending_proof.proof.clone();
+    let report = match cost_limit {
+        Some(limit) => verifier.verify_with_report_limited(
             &input_box.ergo_tree,
             &Env::empty(),
             ctx,
-            input.spending_proof.proof.clone(),
-            message_to_sign.as_slice(),
-        )?
-        .result)
+            proof,
+            message_to_sign,
+            limit,
+        ),
+        None => verifier.verify_with_report(
+            &input_box.ergo_tree,
+            &Env::empty(),
+            ctx,
+            proof,
+            message_to_sign,
+        ),
+    };
+    if let (Some(limit), Some(VerificationFailureReason::CostExceeded)) =
+        (cost_limit, &report.failure_reason)
+    {
+        return Err(TxVerifyError::CostLimitExceeded {
+            limit,
+            cost: report.cost,
+        });
+    }
+    Ok(InputVerificationResult {
+        input_idx,
+        box_id: input.box_id,
+        result: report.verified,
+        cost: report.cost,
+        reduced: report.reduced,
+        failure_reason: report.failure_reason,
+    })
 }
 
 /// Arbitrary impl
@@ -410,8 +796,50 @@ pub mod tests {
 
     use super::*;
 
+    use ergo_chain_types::Parameters;
+    use ergotree_interpreter::sigma_protocol::private_input::DlogProverInput;
+    use ergotree_interpreter::sigma_protocol::private_input::PrivateInput;
+    use ergotree_interpreter::sigma_protocol::prover::TestProver;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::ergo_tree::ErgoTree;
+    use ergotree_ir::mir::expr::Expr;
     use ergotree_ir::serialization::sigma_serialize_roundtrip;
+    use ergotree_ir::sigma_protocol::sigma_boolean::ProveDlog;
     use proptest::prelude::*;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+    use crate::chain::storage_rent::storage_rent_extension;
+    use crate::chain::storage_rent::storage_rent_output;
+    use crate::chain::storage_rent::STORAGE_FEE_FACTOR;
+    use crate::chain::storage_rent::STORAGE_PERIOD;
+    use crate::chain::transaction::input::prover_result::ProverResult;
+    use crate::wallet::signing::sign_transaction;
+
+    #[test]
+    fn test_output_accessors() {
+        let tx = force_any_val::<Transaction>();
+        for (idx, b) in tx.outputs().iter().enumerate() {
+            assert_eq!(b.transaction_id, tx.id());
+            assert_eq!(b.index as usize, idx);
+            assert_eq!(tx.output_by_id(&b.box_id()), Some((idx, b)));
+        }
+        assert_eq!(
+            tx.output_ids().collect::<Vec<_>>(),
+            tx.outputs.iter().map(ErgoBox::box_id).collect::<Vec<_>>()
+        );
+        assert_eq!(tx.output_by_id(&force_any_val::<BoxId>()), None);
+    }
+
+    #[test]
+    fn test_invalid_io_count_error_message() {
+        let err = Transaction::new_from_vec(vec![], vec![], vec![force_any_val()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Tx inputs count: Lower bound violation: got 0 (expected >= 1)"
+        );
+    }
 
     proptest! {
 
@@ -428,6 +856,213 @@ pub mod tests {
             prop_assert_eq![sigma_serialize_roundtrip(&v), v];
         }
 
+        #[test]
+        fn tx_parse_error_context(v in any::<Transaction>()) {
+            let bytes = v.sigma_serialize_bytes().unwrap();
+            let last_output = format!("output[{}]", v.output_candidates.len() - 1);
+            match Transaction::sigma_parse_bytes(&bytes[..bytes.len() - 1]) {
+                Err(SigmaParsingError::WithContext { offset, path, .. }) => {
+                    prop_assert!(offset < bytes.len() as u64);
+                    prop_assert_eq!(&path[..2], &["tx".to_string(), last_output][..]);
+                }
+                res => prop_assert!(false, "unexpected result: {:?}", res),
+            }
+        }
+
+    }
+
+    #[test]
+    fn test_verify_transaction_data_input_spent() {
+        let input_box = force_any_val::<ErgoBox>();
+        let tx = Transaction::new_from_vec(
+            vec![Input::new(
+                input_box.box_id(),
+                ProverResult {
+                    proof: ProofBytes::Empty,
+                    extension: ContextExtension::empty(),
+                },
+            )],
+            vec![input_box.box_id().into()],
+            vec![force_any_val::<ErgoBoxCandidate>()],
+        )
+        .unwrap();
+        let tx_context =
+            TransactionContext::new(tx, vec![input_box.clone()], vec![input_box]).unwrap();
+        // not a consensus rule, only reported by the opt-in check
+        assert!(matches!(
+            tx_context.validate_data_inputs(),
+            Err(TransactionContextError::DataInputSpent {
+                data_input_idx: 0,
+                input_idx: 0
+            })
+        ));
+        let res = verify_transaction(&tx_context, &force_any_val::<ErgoStateContext>());
+        assert!(!matches!(
+            res,
+            Err(TxVerifyError::TransactionContextError(
+                TransactionContextError::DataInputSpent { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_verify_storage_rent_input() {
+        let input_box = ErgoBox::new(
+            BoxValue::new(BoxValue::UNITS_PER_ERGO as u64 * 10).unwrap(),
+            ErgoTree::try_from(Expr::Const(force_any_val::<ProveDlog>().into())).unwrap(),
+            None,
+            NonMandatoryRegisters::empty(),
+            1,
+            TxId::zero(),
+            0,
+        )
+        .unwrap();
+        let height = STORAGE_PERIOD + 1;
+        let output = storage_rent_output(&input_box, height, STORAGE_FEE_FACTOR)
+            .unwrap()
+            .unwrap();
+        let mut state_context =
+            force_any_val::<ErgoStateContext>().with_parameters(Parameters::default());
+        state_context.pre_header.height = height;
+        let verify = |proof: ProofBytes| {
+            let tx = Transaction::new_from_vec(
+                vec![Input::new(
+                    input_box.box_id(),
+                    ProverResult {
+                        proof,
+                        extension: storage_rent_extension(0),
+                    },
+                )],
+                vec![],
+                vec![output.clone()],
+            )
+            .unwrap();
+            let tx_context = TransactionContext::new(tx, vec![input_box.clone()], vec![]).unwrap();
+            verify_transaction(&tx_context, &state_context).unwrap()
+        };
+        let res = verify(ProofBytes::Empty);
+        assert!(res.is_valid());
+        assert_eq!(res.inputs[0].cost, STORAGE_CONTRACT_COST);
+        // with a proof the box script has to be satisfied
+        let res = verify(ProofBytes::Some(vec![1u8; 56]));
+        assert!(!res.is_valid());
+        assert_eq!(
+            res.inputs[0].failure_reason,
+            Some(VerificationFailureReason::InvalidProof)
+        );
+    }
+
+    fn with_max_block_cost(
+        state_context: &ErgoStateContext,
+        max_block_cost: u64,
+    ) -> ErgoStateContext {
+        state_context.clone().with_parameters(Parameters {
+            max_block_cost: max_block_cost as u32,
+            ..state_context.parameters().clone()
+        })
+    }
+
+    #[test]
+    fn test_verify_transaction_output_value_too_low() {
+        let input_box = force_any_val::<ErgoBox>();
+        let output =
+            ErgoBoxCandidateBuilder::new(BoxValue::SAFE_USER_MIN, input_box.ergo_tree.clone(), 0)
+                .build()
+                .unwrap();
+        let tx = Transaction::new_from_vec(
+            vec![Input::new(
+                input_box.box_id(),
+                ProverResult {
+                    proof: ProofBytes::Empty,
+                    extension: ContextExtension::empty(),
+                },
+            )],
+            vec![],
+            vec![output],
+        )
+        .unwrap();
+        let output_size = tx.outputs.first().sigma_serialize_bytes().unwrap().len() as u64;
+        let tx_context = TransactionContext::new(tx, vec![input_box], vec![]).unwrap();
+        let state_context = force_any_val::<ErgoStateContext>();
+        let min_value_per_byte = *BoxValue::SAFE_USER_MIN.as_u64() / output_size + 1;
+        let state_context = state_context.clone().with_parameters(Parameters {
+            min_value_per_byte: min_value_per_byte as u32,
+            ..state_context.parameters().clone()
+        });
+        let err = verify_transaction(&tx_context, &state_context).unwrap_err();
+        assert!(matches!(
+            err,
+            TxVerifyError::OutputValueTooLow {
+                output_idx: 0,
+                value,
+                min_value,
+            } if value == *BoxValue::SAFE_USER_MIN.as_u64() && min_value == output_size * min_value_per_byte
+        ));
+    }
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_verify_transaction(secrets in proptest::collection::vec(any::<DlogProverInput>(), 1..5)) {
+            let boxes_to_spend: Vec<ErgoBox> = secrets.iter().map(|secret| {
+                let tree = ErgoTree::try_from(Expr::Const(secret.public_image().into())).unwrap();
+                ErgoBox::new(BoxValue::SAFE_USER_MIN,
+                             tree,
+                             None,
+                             NonMandatoryRegisters::empty(),
+                             0,
+                             TxId::zero(),
+                             0).unwrap()
+            }).collect();
+            let prover = TestProver {
+                secrets: secrets.into_iter().map(PrivateInput::DlogProverInput).collect(),
+            };
+            let inputs: Vec<UnsignedInput> = boxes_to_spend.clone().into_iter().map(UnsignedInput::from).collect();
+            let candidate = ErgoBoxCandidateBuilder::new(
+                BoxValue::SAFE_USER_MIN,
+                boxes_to_spend[0].ergo_tree.clone(),
+                0,
+            )
+            .build()
+            .unwrap();
+            let unsigned_tx = UnsignedTransaction::new_from_vec(inputs, vec![], vec![candidate]).unwrap();
+            let state_context = force_any_val::<ErgoStateContext>();
+            let unsigned_tx_context = TransactionContext::new(unsigned_tx, boxes_to_spend.clone(), vec![]).unwrap();
+            let signed_tx = sign_transaction(&prover, unsigned_tx_context, &state_context, None).unwrap();
+            let inputs_count = signed_tx.inputs.len();
+            let tx_context = TransactionContext::new(signed_tx, boxes_to_spend, vec![]).unwrap();
+            let res = verify_transaction(&tx_context, &state_context).unwrap();
+            prop_assert!(res.is_valid());
+            prop_assert_eq!(res.inputs.len(), inputs_count);
+            prop_assert_eq!(res.total_cost, res.inputs.iter().map(|i| i.cost).sum::<u64>());
+            prop_assert!(res.inputs.iter().all(|i| i.cost > 0));
+            let limit = res.total_cost - 1;
+            let limited_res = verify_transaction(&tx_context, &with_max_block_cost(&state_context, limit));
+            prop_assert!(
+                matches!(limited_res, Err(TxVerifyError::CostLimitExceeded { limit: l, cost }) if l == limit && cost > limit),
+                "unexpected result: {:?}",
+                limited_res
+            );
+            prop_assert!(res.inputs.iter().all(|i| i.failure_reason.is_none() && i.reduced.is_some()));
+            #[cfg(feature = "parallel")]
+            {
+                let contexts = vec![tx_context.clone(), tx_context];
+                let par_res = verify_transaction_parallel(&contexts, &state_context);
+                prop_assert_eq!(par_res.len(), 2);
+                prop_assert!(par_res.iter().all(|r| r.as_ref().unwrap() == &res));
+                let limited = with_max_block_cost(&state_context, res.inputs[0].cost.saturating_sub(1));
+                let seq_err = verify_transaction(&contexts[0], &limited).unwrap_err();
+                prop_assert!(
+                    matches!(seq_err, TxVerifyError::CostLimitExceeded { .. }),
+                    "unexpected error: {}",
+                    seq_err
+                );
+                let par_err = verify_transaction_parallel(&contexts, &limited);
+                prop_assert!(par_err.iter().all(|r| r.as_ref().unwrap_err().to_string() == seq_err.to_string()));
+            }
+        }
     }
 
     #[test]
@@ -0,0 +1,223 @@
+//! Canonical form of the transaction.
+//!
+//! Transaction id is the hash of the serialized transaction, so it matches the id computed by
+//! the node only if the serialization is deterministic. Distinct token ids are serialized in the
+//! order of the first appearance in the outputs and registers are densely packed starting from
+//! R4 (guaranteed by [`NonMandatoryRegisters`]). On top of that, in the canonical form every token
+//! id appears at most once per box.
+//!
+//! [`NonMandatoryRegisters`]: ergotree_ir::chain::ergo_box::NonMandatoryRegisters
+
+use ergotree_ir::chain::ergo_box::BoxTokens;
+use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenAmount;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::chain::tx_id::TxId;
+use ergotree_ir::serialization::SigmaParsingError;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::serialization::SigmaSerializationError;
+use indexmap::IndexMap;
+use thiserror::Error;
+
+use super::unsigned::UnsignedTransaction;
+use super::Transaction;
+use super::TransactionError;
+
+/// Divergence of the transaction from the canonical form
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum CanonicalFormError {
+    /// Output box lists the same token more than once
+    #[error("Output {output_index} contains token {token_id} more than once")]
+    DuplicateToken {
+        /// Index of the output box
+        output_index: usize,
+        /// Repeated token id
+        token_id: TokenId,
+    },
+    /// Merged token amount is out of bounds
+    #[error("Output {output_index} token {token_id} amount overflow")]
+    TokenAmountOverflow {
+        /// Index of the output box
+        output_index: usize,
+        /// Token id
+        token_id: TokenId,
+    },
+    /// Transaction id differs from the one computed from the serialized bytes
+    #[error("Tx id {tx_id} differs from the id computed from the bytes {computed}")]
+    TxIdMismatch {
+        /// Transaction id
+        tx_id: TxId,
+        /// Id computed from the serialized transaction
+        computed: TxId,
+    },
+    /// Parsed serialized transaction is serialized into different bytes
+    #[error("Serialization is not deterministic (serialized {0} bytes, re-serialized {1} bytes)")]
+    BytesMismatch(usize, usize),
+    /// Serialization failed
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SigmaSerializationError),
+    /// Parsing of the serialized transaction failed
+    #[error("Parsing error: {0}")]
+    ParsingError(#[from] SigmaParsingError),
+    /// Transaction error
+    #[error("Tx error: {0}")]
+    TransactionError(#[from] TransactionError),
+}
+
+/// Check that every token id appears at most once per output box
+pub fn check_output_tokens<'a, I>(output_candidates: I) -> Result<(), CanonicalFormError>
+where
+    I: IntoIterator<Item = &'a ErgoBoxCandidate>,
+{
+    output_candidates
+        .into_iter()
+        .enumerate()
+        .try_for_each(|(output_index, b)| {
+            let mut seen = Vec::new();
+            b.tokens
+                .iter()
+                .flat_map(|tokens| tokens.iter())
+                .try_for_each(|t| {
+                    if seen.contains(&t.token_id) {
+                        Err(CanonicalFormError::DuplicateToken {
+                            output_index,
+                            token_id: t.token_id,
+                        })
+                    } else {
+                        seen.push(t.token_id);
+                        Ok(())
+                    }
+                })
+        })
+}
+
+/// Merge the repeated tokens of the box (the merged token keeps the position of its first
+/// appearance)
+pub fn canonicalize_box_candidate(
+    output_index: usize,
+    b: ErgoBoxCandidate,
+) -> Result<ErgoBoxCandidate, CanonicalFormError> {
+    let tokens = match b.tokens {
+        Some(tokens) => tokens,
+        None => return Ok(b),
+    };
+    let mut merged: IndexMap<TokenId, TokenAmount> = IndexMap::with_capacity(tokens.len());
+    for t in tokens.iter() {
+        match merged.get_mut(&t.token_id) {
+            Some(amount) => {
+                *amount = amount.checked_add(&t.amount).map_err(|_| {
+                    CanonicalFormError::TokenAmountOverflow {
+                        output_index,
+                        token_id: t.token_id,
+                    }
+                })?
+            }
+            None => {
+                merged.insert(t.token_id, t.amount);
+            }
+        }
+    }
+    let merged: Vec<Token> = merged.into_iter().map(Token::from).collect();
+    Ok(ErgoBoxCandidate {
+        // merged tokens are not empty and not more than the original ones
+        #[allow(clippy::unwrap_used)]
+        tokens: Some(BoxTokens::from_vec(merged).unwrap()),
+        ..b
+    })
+}
+
+impl Transaction {
+    /// Check that the transaction is in the canonical form (see the module docs) and its id
+    /// matches the id computed from the serialized bytes (as the node computes it)
+    pub fn check_canonical(&self) -> Result<(), CanonicalFormError> {
+        check_output_tokens(self.output_candidates.iter())?;
+        let bytes = self.sigma_serialize_bytes()?;
+        let parsed = Transaction::sigma_parse_bytes(&bytes)?;
+        if parsed.id() != self.id() {
+            return Err(CanonicalFormError::TxIdMismatch {
+                tx_id: self.id(),
+                computed: parsed.id(),
+            });
+        }
+        let reserialized = parsed.sigma_serialize_bytes()?;
+        if reserialized != bytes {
+            return Err(CanonicalFormError::BytesMismatch(
+                bytes.len(),
+                reserialized.len(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns true if the transaction is in the canonical form (see [`Self::check_canonical`])
+    pub fn is_canonical(&self) -> bool {
+        self.check_canonical().is_ok()
+    }
+}
+
+impl UnsignedTransaction {
+    /// Check that the transaction outputs are in the canonical form
+    pub fn check_canonical(&self) -> Result<(), CanonicalFormError> {
+        check_output_tokens(self.output_candidates.iter())
+    }
+
+    /// Transaction with the repeated tokens of the outputs merged (the id is re-calculated)
+    pub fn canonicalize(self) -> Result<UnsignedTransaction, CanonicalFormError> {
+        let output_candidates = self
+            .output_candidates
+            .enumerated()
+            .try_mapped(|(idx, b)| canonicalize_box_candidate(idx, b))?;
+        Ok(UnsignedTransaction::new(
+            self.inputs,
+            self.data_inputs,
+            output_candidates,
+        )?)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::convert::TryFrom;
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn arbitrary_tx_is_canonical(t in any::<Transaction>()) {
+            prop_assert_eq![t.check_canonical(), Ok(())];
+        }
+
+        #[test]
+        fn merge_duplicate_tokens(t in any::<UnsignedTransaction>(), token in any::<Token>()) {
+            let mut output_candidates = t.output_candidates.as_vec().clone();
+            let half = TokenAmount::try_from(1 + token.amount.as_u64() % 1000).unwrap();
+            output_candidates[0].tokens = Some(
+                BoxTokens::from_vec(vec![(token.token_id, half).into(), (token.token_id, half).into()])
+                    .unwrap(),
+            );
+            let t = UnsignedTransaction::new_from_vec(
+                t.inputs.as_vec().clone(),
+                t.data_inputs.map(|di| di.as_vec().clone()).unwrap_or_default(),
+                output_candidates,
+            )
+            .unwrap();
+            prop_assert_eq![
+                t.check_canonical(),
+                Err(CanonicalFormError::DuplicateToken {
+                    output_index: 0,
+                    token_id: token.token_id
+                })
+            ];
+            let canonical = t.canonicalize().unwrap();
+            prop_assert_eq![canonical.check_canonical(), Ok(())];
+            let tokens = canonical.output_candidates.first().tokens.clone().unwrap();
+            prop_assert_eq![tokens.len(), 1];
+            prop_assert_eq![*tokens.first().amount.as_u64(), *half.as_u64() * 2];
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Transaction dependency resolution for a set of (chained) transactions
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use ergotree_ir::chain::ergo_box::BoxId;
+use thiserror::Error;
+
+use super::Transaction;
+use super::TxId;
+
+/// Errors on transaction dependency resolution
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum TxDependencyError {
+    /// The same transaction is present more than once
+    #[error("Duplicate transaction {0}")]
+    DuplicateTransaction(TxId),
+    /// The box is spent by more than one transaction
+    #[error("Box {box_id} is spent by both {first} and {second} transactions")]
+    DoubleSpend {
+        /// Id of the box spent twice
+        box_id: BoxId,
+        /// First spending transaction
+        first: TxId,
+        /// Second spending transaction
+        second: TxId,
+    },
+    /// Input (or data input) box is neither created by the transactions in the set, nor available
+    #[error("Transaction {tx_id} refers to a missing box {box_id}")]
+    MissingParent {
+        /// Id of the transaction referring to the missing box
+        tx_id: TxId,
+        /// Id of the missing box
+        box_id: BoxId,
+    },
+    /// Transactions depend on each other in a cycle
+    #[error("Dependency cycle between transactions {0:?}")]
+    Cycle(Vec<TxId>),
+}
+
+/// Indices of the transactions (in the given slice) whose outputs are spent or used as data inputs
+/// by the transaction at the corresponding index
+pub fn tx_parents(txs: &[Transaction]) -> Vec<BTreeSet<usize>> {
+    let creators: HashMap<BoxId, usize> = txs
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, tx)| tx.outputs.iter().map(move |b| (b.box_id(), idx)))
+        .collect();
+    txs.iter()
+        .enumerate()
+        .map(|(idx, tx)| {
+            referenced_box_ids(tx)
+                .filter_map(|box_id| creators.get(&box_id).copied())
+                .filter(|parent_idx| *parent_idx != idx)
+                .collect()
+        })
+        .collect()
+}
+
+/// Order transactions so that every transaction comes after the transactions which create the boxes
+/// it spends (or uses as data inputs), i.e. the order in which they can be submitted to the node.
+/// Transactions independent from each other keep their relative order.
+///
+/// `is_box_available` is asked for every box which is not created in `txs` (e.g. checked against
+/// the UTXO set), returning `false` results in [`TxDependencyError::MissingParent`].
+pub fn sort_by_dependencies<F>(
+    txs: Vec<Transaction>,
+    is_box_available: F,
+) -> Result<Vec<Transaction>, TxDependencyError>
+where
+    F: Fn(&BoxId) -> bool,
+{
+    check_duplicates(&txs)?;
+    let created_box_ids: HashSet<BoxId> = txs
+        .iter()
+        .flat_map(|tx| tx.outputs.iter().map(|b| b.box_id()))
+        .collect();
+    for tx in &txs {
+        if let Some(box_id) = referenced_box_ids(tx)
+            .find(|box_id| !created_box_ids.contains(box_id) && !is_box_available(box_id))
+        {
+            return Err(TxDependencyError::MissingParent {
+                tx_id: tx.id(),
+                box_id,
+            });
+        }
+    }
+
+    let parents = tx_parents(&txs);
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); txs.len()];
+    for (idx, tx_parents) in parents.iter().enumerate() {
+        for parent_idx in tx_parents {
+            children[*parent_idx].push(idx);
+        }
+    }
+    let mut pending_parents_count: Vec<usize> = parents.iter().map(BTreeSet::len).collect();
+    let mut ready: BTreeSet<usize> = pending_parents_count
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count == 0)
+        .map(|(idx, _)| idx)
+        .collect();
+    let mut order = Vec::with_capacity(txs.len());
+    while let Some(idx) = ready.iter().next().copied() {
+        ready.remove(&idx);
+        order.push(idx);
+        for child_idx in &children[idx] {
+            pending_parents_count[*child_idx] -= 1;
+            if pending_parents_count[*child_idx] == 0 {
+                ready.insert(*child_idx);
+            }
+        }
+    }
+    if order.len() < txs.len() {
+        let in_cycle = pending_parents_count
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(idx, _)| txs[idx].id())
+            .collect();
+        return Err(TxDependencyError::Cycle(in_cycle));
+    }
+
+    let mut txs: Vec<Option<Transaction>> = txs.into_iter().map(Some).collect();
+    Ok(order
+        .into_iter()
+        .filter_map(|idx| txs[idx].take())
+        .collect())
+}
+
+fn check_duplicates(txs: &[Transaction]) -> Result<(), TxDependencyError> {
+    let mut tx_ids = HashSet::new();
+    let mut spent_by: HashMap<BoxId, TxId> = HashMap::new();
+    for tx in txs {
+        if !tx_ids.insert(tx.id()) {
+            return Err(TxDependencyError::DuplicateTransaction(tx.id()));
+        }
+        for input in tx.inputs.iter() {
+            if let Some(first) = spent_by.insert(input.box_id, tx.id()) {
+                return Err(TxDependencyError::DoubleSpend {
+                    box_id: input.box_id,
+                    first,
+                    second: tx.id(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn referenced_box_ids(tx: &Transaction) -> impl Iterator<Item = BoxId> + '_ {
+    tx.inputs.iter().map(|i| i.box_id).chain(
+        tx.data_inputs
+            .iter()
+            .flat_map(|data_inputs| data_inputs.iter().map(|d| d.box_id)),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use super::*;
+
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use proptest::prelude::*;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::transaction::input::prover_result::ProverResult;
+    use crate::chain::transaction::DataInput;
+    use crate::chain::transaction::Input;
+
+    fn spend(box_ids: &[BoxId], data_inputs: Vec<DataInput>) -> Transaction {
+        let inputs = box_ids
+            .iter()
+            .map(|box_id| Input::new(*box_id, force_any_val::<ProverResult>()))
+            .collect();
+        Transaction::new_from_vec(
+            inputs,
+            data_inputs,
+            vec![force_any_val::<ErgoBoxCandidate>()],
+        )
+        .unwrap()
+    }
+
+    fn first_output(tx: &Transaction) -> BoxId {
+        tx.outputs.first().box_id()
+    }
+
+    #[test]
+    fn test_sort_chained_txs() {
+        let utxo = force_any_val::<BoxId>();
+        let tx1 = spend(&[utxo], vec![]);
+        let tx2 = spend(&[first_output(&tx1)], vec![]);
+        let tx3 = spend(
+            &[first_output(&tx2)],
+            vec![DataInput::from(first_output(&tx1))],
+        );
+        let sorted =
+            sort_by_dependencies(vec![tx3.clone(), tx2.clone(), tx1.clone()], |b| *b == utxo)
+                .unwrap();
+        assert_eq!(sorted, vec![tx1, tx2, tx3]);
+    }
+
+    #[test]
+    fn test_missing_parent() {
+        let tx1 = spend(&[force_any_val::<BoxId>()], vec![]);
+        let tx2 = spend(&[first_output(&tx1)], vec![]);
+        let res = sort_by_dependencies(vec![tx2.clone()], |_| false);
+        assert_eq!(
+            res,
+            Err(TxDependencyError::MissingParent {
+                tx_id: tx2.id(),
+                box_id: first_output(&tx1),
+            })
+        );
+    }
+
+    #[test]
+    fn test_double_spend() {
+        let utxo = force_any_val::<BoxId>();
+        let tx1 = spend(&[utxo], vec![]);
+        let tx2 = spend(&[utxo], vec![]);
+        assert!(matches!(
+            sort_by_dependencies(vec![tx1, tx2], |_| true),
+            Err(TxDependencyError::DoubleSpend { .. })
+        ));
+    }
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_independent_txs_keep_order(txs in proptest::collection::vec(any::<Transaction>(), 1..5)) {
+            let sorted = sort_by_dependencies(txs.clone(), |_| true).unwrap();
+            prop_assert_eq!(sorted, txs);
+        }
+    }
+}
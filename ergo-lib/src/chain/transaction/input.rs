@@ -146,17 +146,97 @@ impl SigmaSerializable for Input {
 }
 
 #[cfg(test)]
-#[allow(clippy::panic)]
+#[allow(clippy::panic, clippy::unwrap_used)]
 mod tests {
     use super::*;
     use ergotree_ir::serialization::sigma_serialize_roundtrip;
     use proptest::prelude::*;
 
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_node_format() {
+        // inputs of the transactions 0e6acf3f18b95bdc5bb1b060baa1eafe53bd89fb08b0e86d6cc00fbdd9e43189
+        // and c8520befd345ff40fcf244b44ffe8cea29c8b116b174cfaf4f2a521604d531a4 in the node JSON
+        let with_proof_json = r#"{
+          "boxId": "56111b039b86f71004b768d2e8b4579f1d79e28e7a617fd5add57a5239498c26",
+          "spendingProof": {
+            "proofBytes": "6542a8b8914b103dcbc36d77da3bd58e42ca35755a5190b507764b0bae330b924ce86acfa1b5f9bfc8216c3c4628738e8274d902bea06b48",
+            "extension": {}
+          }
+        }"#;
+        let with_extension_json = r#"{
+          "boxId": "59f2856068c56264d290520043044ace138a3a80d414748d0e4dcd0806188546",
+          "spendingProof": {
+            "proofBytes": "",
+            "extension": {
+              "0": "04c60f",
+              "5": "0514",
+              "10": "0eee03101808cd0279aed8dea2b2a25316d5d49d13bf51c0b2c1dc696974bb4b0c07b5894e998e56040005e0e0a447040404060402040004000e201d5afc59838920bb5ef2a8f9d63825a55b1d48e269d7cecee335d637c3ff5f3f0e20003bd19d0187117f130b62e1bcab0939929ff5c7709f843c5c4dd158949285d005e201058c85a2010514040404c60f06010104d00f05e0e0a44704c60f0e691005040004000e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300000193c2b2a57301007473027303830108cdeeac93b1a57304050005000580ade2040100d803d6017300d602b2a4730100d6037302eb027201d195ed93b1a4730393b1db630872027304d804d604db63087202d605b2a5730500d606b2db63087205730600d6077e8c72060206edededededed938cb2720473070001730893c27205d07201938c72060173099272077e730a06927ec172050699997ec1a7069d9c72077e730b067e730c067e720306909c9c7e8cb27204730d0002067e7203067e730e069c9a7207730f9a9c7ec17202067e7310067e9c73117e7312050690b0ada5d90108639593c272087313c1720873147315d90108599a8c7208018c72080273167317",
+              "1": "0e20003bd19d0187117f130b62e1bcab0939929ff5c7709f843c5c4dd158949285d0",
+              "6": "0580ade204",
+              "9": "0580b48913",
+              "2": "05e201",
+              "7": "0e201d5afc59838920bb5ef2a8f9d63825a55b1d48e269d7cecee335d637c3ff5f3f",
+              "3": "05e0e0a447",
+              "8": "0580ade204",
+              "4": "058c85a201"
+            }
+          }
+        }"#;
+        for node_json in [with_proof_json, with_extension_json] {
+            let input: Input = serde_json::from_str(node_json).unwrap();
+            assert_eq!(
+                serde_json::to_value(&input).unwrap(),
+                serde_json::from_str::<serde_json::Value>(node_json).unwrap()
+            );
+        }
+
+        let input: Input = serde_json::from_str(with_proof_json).unwrap();
+        assert_eq!(
+            String::from(input.box_id),
+            "56111b039b86f71004b768d2e8b4579f1d79e28e7a617fd5add57a5239498c26"
+        );
+        assert_eq!(input.spending_proof.proof.to_bytes().len(), 56);
+        assert!(input.spending_proof.extension.values.is_empty());
+
+        let input: Input = serde_json::from_str(with_extension_json).unwrap();
+        assert_eq!(input.spending_proof.proof, ProofBytes::Empty);
+        assert_eq!(
+            input
+                .spending_proof
+                .extension
+                .values
+                .keys()
+                .collect::<Vec<_>>(),
+            vec![&0u8, &5, &10, &1, &6, &9, &2, &7, &3, &8, &4]
+        );
+        assert_eq!(
+            input.spending_proof.extension.values[&2],
+            ergotree_ir::mir::constant::Constant::from(113i64)
+        );
+    }
+
     proptest! {
 
         #[test]
         fn ser_roundtrip(v in any::<Input>()) {
             prop_assert_eq![sigma_serialize_roundtrip(&v), v];
         }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn json_roundtrip(v in any::<Input>()) {
+            let json = serde_json::to_string(&v).unwrap();
+            let parsed: Input = serde_json::from_str(&json).unwrap();
+            prop_assert_eq![serde_json::to_string(&parsed).unwrap(), json];
+            prop_assert_eq![parsed, v];
+        }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn unsigned_input_json_roundtrip(v in any::<UnsignedInput>()) {
+            let json = serde_json::to_string(&v).unwrap();
+            prop_assert_eq![serde_json::from_str::<UnsignedInput>(&json).unwrap(), v];
+        }
     }
 }
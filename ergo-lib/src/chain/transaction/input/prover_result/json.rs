@@ -4,6 +4,9 @@ use crate::chain::json::context_extension::ContextExtensionSerde;
 use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
 use serde::ser::SerializeStruct;
 use serde::Serialize;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingError;
+use sigma_util::encoding::DecodingMode;
 
 use super::ProverResult;
 
@@ -23,10 +26,10 @@ impl Serialize for ProverResult {
 }
 
 impl FromStr for ProverResult {
-    type Err = base16::DecodeError;
+    type Err = DecodingError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let proof_bytes: Vec<u8> = base16::decode(s)?;
+        let proof_bytes: Vec<u8> = decode_base16(s, DecodingMode::Strict)?;
         Ok(ProverResult {
             proof: proof_bytes.into(),
             extension: ContextExtension::empty(),
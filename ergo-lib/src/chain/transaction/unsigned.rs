@@ -3,11 +3,9 @@
 use super::input::UnsignedInput;
 
 use super::DataInput;
-use super::Transaction;
 use super::TxIoVec;
-use super::{distinct_token_ids, TransactionError};
+use super::{calc_tx_id, distinct_token_ids, write_tx_bytes_to_sign, TransactionError};
 use bounded_vec::BoundedVec;
-use ergo_chain_types::blake2b256_hash;
 
 use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
 use ergotree_ir::chain::token::TokenId;
@@ -15,6 +13,7 @@ use ergotree_ir::chain::tx_id::TxId;
 use ergotree_ir::serialization::SigmaSerializationError;
 use indexmap::IndexSet;
 use std::convert::TryInto;
+use std::io::Write;
 
 /// Unsigned (inputs without proofs) transaction
 #[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
@@ -63,33 +62,19 @@ impl UnsignedTransaction {
         data_inputs: Option<TxIoVec<DataInput>>,
         output_candidates: TxIoVec<ErgoBoxCandidate>,
     ) -> Result<UnsignedTransaction, SigmaSerializationError> {
-        let tx_to_sign = UnsignedTransaction {
-            tx_id: TxId::zero(),
+        let tx_id = calc_tx_id(
+            inputs.iter().map(|ui| (ui.box_id, &ui.extension)),
+            &data_inputs,
+            &output_candidates,
+        )?;
+        Ok(UnsignedTransaction {
+            tx_id,
             inputs,
             data_inputs,
             output_candidates,
-        };
-        let tx_id = tx_to_sign.calc_tx_id()?;
-        Ok(UnsignedTransaction {
-            tx_id,
-            ..tx_to_sign
         })
     }
 
-    fn calc_tx_id(&self) -> Result<TxId, SigmaSerializationError> {
-        let bytes = self.bytes_to_sign()?;
-        Ok(TxId(blake2b256_hash(&bytes)))
-    }
-
-    fn to_tx_without_proofs(&self) -> Result<Transaction, SigmaSerializationError> {
-        let empty_proofs_input = self.inputs.mapped_ref(|ui| ui.input_to_sign());
-        Transaction::new(
-            empty_proofs_input,
-            self.data_inputs.clone(),
-            self.output_candidates.clone(),
-        )
-    }
-
     /// Get transaction id
     pub fn id(&self) -> TxId {
         self.tx_id
@@ -97,8 +82,23 @@ impl UnsignedTransaction {
 
     /// message to be signed by the [`ergotree_interpreter::sigma_protocol::prover::Prover`] (serialized tx)
     pub fn bytes_to_sign(&self) -> Result<Vec<u8>, SigmaSerializationError> {
-        let tx = self.to_tx_without_proofs()?;
-        tx.bytes_to_sign()
+        let mut bytes = Vec::new();
+        self.write_bytes_to_sign(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Write the message to be signed (see [`UnsignedTransaction::bytes_to_sign`]) to any writer
+    /// without the intermediate transaction. Returns the number of bytes written
+    pub fn write_bytes_to_sign<W: Write + ?Sized>(
+        &self,
+        w: &mut W,
+    ) -> Result<usize, SigmaSerializationError> {
+        write_tx_bytes_to_sign(
+            w,
+            self.inputs.iter().map(|ui| (ui.box_id, &ui.extension)),
+            &self.data_inputs,
+            &self.output_candidates,
+        )
     }
 
     /// Returns distinct token ids from all output_candidates
@@ -138,6 +138,9 @@ pub mod arbitrary {
 #[allow(clippy::unwrap_used, clippy::panic)]
 pub mod tests {
     use super::*;
+    use crate::chain::transaction::Transaction;
+    use ergo_chain_types::blake2b256_hash;
+    use ergotree_ir::serialization::SigmaSerializable;
 
     use proptest::prelude::*;
 
@@ -150,5 +153,20 @@ pub mod tests {
             prop_assert!(!v.bytes_to_sign().unwrap().is_empty());
         }
 
+        #[test]
+        fn test_unsigned_tx_bytes_to_sign_match_tx(v in any::<UnsignedTransaction>()) {
+            let tx = Transaction::new(
+                v.inputs.mapped_ref(|ui| ui.input_to_sign()),
+                v.data_inputs.clone(),
+                v.output_candidates.clone(),
+            )
+            .unwrap();
+            let bytes = v.bytes_to_sign().unwrap();
+            prop_assert_eq!(&bytes, &tx.bytes_to_sign().unwrap());
+            prop_assert_eq!(&bytes, &tx.sigma_serialize_bytes().unwrap());
+            prop_assert_eq!(v.id(), tx.id());
+            prop_assert_eq!(v.id(), TxId(blake2b256_hash(&bytes)));
+        }
+
     }
 }
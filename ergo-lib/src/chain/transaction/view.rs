@@ -0,0 +1,243 @@
+//! Zero-copy view of the serialized transaction, for the indexers parsing a lot of transactions
+//! and interested only in a few fields (ids, guarding scripts, etc.).
+//! Transaction id and output box ids are computed from the serialized bytes, the rest is parsed
+//! on demand (see [`ErgoBoxView`]).
+
+use std::io::Write;
+
+use crate::chain::transaction::input::prover_result::ProverResult;
+use bytes::Bytes;
+use ergo_chain_types::Digest32;
+use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::ErgoBoxCandidateView;
+use ergotree_ir::chain::ergo_box::ErgoBoxView;
+use ergotree_ir::serialization::sigma_byte_reader;
+use ergotree_ir::serialization::SigmaParsingError;
+use ergotree_ir::serialization::SigmaSerializable;
+use sigma_ser::vlq_encode::ReadSigmaVlqExt;
+use sigma_ser::vlq_encode::WriteSigmaVlqExt;
+use sigma_util::hash::Blake2b256Writer;
+
+use super::DataInput;
+use super::Input;
+use super::Transaction;
+use super::TxId;
+
+/// View of the serialized transaction input
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct InputView {
+    box_id: BoxId,
+    proof: Bytes,
+    extension: Bytes,
+}
+
+impl InputView {
+    /// Id of the box to spend
+    pub fn box_id(&self) -> BoxId {
+        self.box_id
+    }
+
+    /// Spending proof bytes (empty if no proof)
+    pub fn proof_bytes(&self) -> &[u8] {
+        &self.proof
+    }
+
+    /// Serialized context extension
+    pub fn extension_bytes(&self) -> &[u8] {
+        &self.extension
+    }
+
+    /// Parse the input
+    pub fn to_input(&self) -> Result<Input, SigmaParsingError> {
+        let proof = if self.proof.is_empty() {
+            ProofBytes::Empty
+        } else {
+            ProofBytes::Some(self.proof.to_vec())
+        };
+        Ok(Input::new(
+            self.box_id,
+            ProverResult {
+                proof,
+                extension: ContextExtension::sigma_parse_bytes(&self.extension)?,
+            },
+        ))
+    }
+}
+
+/// View of the serialized transaction over a shared buffer
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TransactionView {
+    bytes: Bytes,
+    id: TxId,
+    inputs: Vec<InputView>,
+    data_inputs: Vec<DataInput>,
+    outputs: Vec<ErgoBoxView>,
+}
+
+impl TransactionView {
+    /// Parse the serialized transaction, `bytes` are kept as the backing buffer
+    pub fn sigma_parse_bytes(bytes: Bytes) -> Result<TransactionView, SigmaParsingError> {
+        TransactionView::parse_at(&bytes, 0)
+    }
+
+    /// Parse the transaction starting at `pos` in `buf` (e.g. block transactions), the end of
+    /// the transaction is `pos + bytes().len()`
+    pub fn parse_at(buf: &Bytes, pos: usize) -> Result<TransactionView, SigmaParsingError> {
+        let mut r = sigma_byte_reader::from_bytes(&buf[..]);
+        r.set_position(pos as u64);
+
+        let inputs_count = r.get_u16()?;
+        if inputs_count == 0 {
+            return Err(SigmaParsingError::ValueOutOfBounds(
+                "transaction has no inputs".to_string(),
+            ));
+        }
+        let mut inputs = Vec::with_capacity(inputs_count as usize);
+        for _ in 0..inputs_count {
+            let box_id = BoxId::sigma_parse(&mut r)?;
+            let proof_len = r.get_u16()? as usize;
+            let proof_start = r.position() as usize;
+            let proof_end = proof_start + proof_len;
+            if proof_end > buf.len() {
+                return Err(SigmaParsingError::Io(
+                    "spending proof is out of buffer".to_string(),
+                ));
+            }
+            r.set_position(proof_end as u64);
+            ContextExtension::sigma_parse(&mut r)?;
+            inputs.push(InputView {
+                box_id,
+                proof: buf.slice(proof_start..proof_end),
+                extension: buf.slice(proof_end..r.position() as usize),
+            });
+        }
+        let inputs_end = r.position() as usize;
+
+        let data_inputs_count = r.get_u16()?;
+        let mut data_inputs = Vec::with_capacity(data_inputs_count as usize);
+        for _ in 0..data_inputs_count {
+            data_inputs.push(DataInput::sigma_parse(&mut r)?);
+        }
+
+        let tokens_count = r.get_u32()? as usize;
+        if tokens_count > Transaction::MAX_OUTPUTS_COUNT * ErgoBox::MAX_TOKENS_COUNT {
+            return Err(SigmaParsingError::ValueOutOfBounds(
+                "too many tokens in transaction".to_string(),
+            ));
+        }
+        let token_ids_start = r.position() as usize;
+        let token_ids_end = token_ids_start + tokens_count * Digest32::SIZE;
+        if token_ids_end > buf.len() {
+            return Err(SigmaParsingError::Io(
+                "token ids are out of buffer".to_string(),
+            ));
+        }
+        let token_ids = buf.slice(token_ids_start..token_ids_end);
+        r.set_position(token_ids_end as u64);
+
+        let outputs_count = r.get_u16()?;
+        if outputs_count == 0 {
+            return Err(SigmaParsingError::ValueOutOfBounds(
+                "transaction has no outputs".to_string(),
+            ));
+        }
+        let mut candidates = Vec::with_capacity(outputs_count as usize);
+        let mut end = r.position() as usize;
+        for _ in 0..outputs_count {
+            let candidate = ErgoBoxCandidateView::parse_at(buf, end, Some(token_ids.clone()))?;
+            end += candidate.bytes().len();
+            candidates.push(candidate);
+        }
+
+        // tx id is the hash of the transaction bytes with empty proofs
+        let mut hasher = Blake2b256Writer::new();
+        hasher.put_u16(inputs_count)?;
+        for input in &inputs {
+            hasher.write_all(input.box_id.as_ref())?;
+            hasher.put_u16(0)?;
+            hasher.write_all(&input.extension)?;
+        }
+        hasher.write_all(&buf[inputs_end..end])?;
+        let id = TxId(Digest32::from(hasher.finalize()));
+
+        let outputs = candidates
+            .into_iter()
+            .enumerate()
+            .map(|(index, candidate)| ErgoBoxView::from_box_candidate(candidate, id, index as u16))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(TransactionView {
+            bytes: buf.slice(pos..end),
+            id,
+            inputs,
+            data_inputs,
+            outputs,
+        })
+    }
+
+    /// Serialized transaction
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Transaction id
+    pub fn id(&self) -> TxId {
+        self.id
+    }
+
+    /// Inputs
+    pub fn inputs(&self) -> &[InputView] {
+        &self.inputs
+    }
+
+    /// Data inputs
+    pub fn data_inputs(&self) -> &[DataInput] {
+        &self.data_inputs
+    }
+
+    /// Outputs
+    pub fn outputs(&self) -> &[ErgoBoxView] {
+        &self.outputs
+    }
+
+    /// Parse the transaction
+    pub fn to_transaction(&self) -> Result<Transaction, SigmaParsingError> {
+        Transaction::sigma_parse_bytes(&self.bytes)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn tx_view_roundtrip(tx in any::<Transaction>()) {
+            let view = TransactionView::sigma_parse_bytes(
+                Bytes::from(tx.sigma_serialize_bytes().unwrap())
+            ).unwrap();
+            prop_assert_eq![view.id(), tx.id()];
+            prop_assert_eq![
+                view.inputs().iter().map(|i| i.to_input().unwrap()).collect::<Vec<_>>(),
+                tx.inputs.clone().to_vec()
+            ];
+            prop_assert_eq![
+                view.outputs().iter().map(ErgoBoxView::box_id).collect::<Vec<_>>(),
+                tx.outputs.iter().map(ErgoBox::box_id).collect::<Vec<_>>()
+            ];
+            prop_assert_eq![
+                view.outputs().iter().map(|b| b.to_ergo_box().unwrap()).collect::<Vec<_>>(),
+                tx.outputs.clone().to_vec()
+            ];
+            prop_assert_eq![view.to_transaction().unwrap(), tx];
+        }
+    }
+}
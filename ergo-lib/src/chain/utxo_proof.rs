@@ -0,0 +1,168 @@
+//! Verification of the boxes presence in the UTXO set against the state root
+//!
+//! The node returns a batch AVL+ proof of the lookups of the given box ids in the UTXO set tree
+//! (`/utxo/getBoxesBinaryProof`). Replaying the lookups against the `Header::state_root` tells
+//! which of the boxes are unspent at this header without trusting the node.
+
+use bytes::Bytes;
+use ergo_avltree_rust::batch_avl_verifier::BatchAVLVerifier;
+use ergo_avltree_rust::batch_node::AVLTree;
+use ergo_avltree_rust::batch_node::Node;
+use ergo_avltree_rust::batch_node::NodeHeader;
+use ergo_avltree_rust::operation::Operation;
+use ergo_chain_types::ADDigest;
+use ergo_chain_types::Header;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::serialization::SigmaParsingError;
+use ergotree_ir::serialization::SigmaSerializable;
+use thiserror::Error;
+
+/// Length of the keys (box ids) in the UTXO set tree
+const KEY_LENGTH: usize = BoxId::SIZE;
+
+/// Errors on the UTXO set proof verification
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum UtxoProofError {
+    /// Proof does not match the state root or the looked up box ids
+    #[error("invalid proof: {0}")]
+    InvalidProof(String),
+    /// Value in the UTXO set is not a valid box
+    #[error("box parsing error: {0}")]
+    BoxParsing(#[from] SigmaParsingError),
+    /// Box in the UTXO set has a different id than the one looked up
+    #[error("box id {actual:?} differs from the looked up {expected:?}")]
+    BoxIdMismatch {
+        /// Looked up box id
+        expected: BoxId,
+        /// Id of the box in the UTXO set
+        actual: BoxId,
+    },
+}
+
+/// Check the presence of the boxes in the UTXO set with the state root `state_root`.
+/// `box_ids` must be in the same order as sent to the node for the `proof`.
+/// Returns the box from the UTXO set for each box id, `None` if the box is not in the UTXO set
+/// (spent or never existed).
+pub fn verify_utxo_proof(
+    state_root: &ADDigest,
+    box_ids: &[BoxId],
+    proof: &[u8],
+) -> Result<Vec<Option<ErgoBox>>, UtxoProofError> {
+    let starting_digest = Bytes::copy_from_slice(state_root.0.as_ref());
+    let proof = Bytes::copy_from_slice(proof);
+    let mut verifier = BatchAVLVerifier::new(
+        &starting_digest,
+        &proof,
+        AVLTree::new(
+            |digest| Node::LabelOnly(NodeHeader::new(Some(*digest), None)),
+            KEY_LENGTH,
+            None,
+        ),
+        None,
+        None,
+    )
+    .map_err(|e| UtxoProofError::InvalidProof(format!("{:?}", e)))?;
+    box_ids
+        .iter()
+        .map(|box_id| {
+            let key = Bytes::copy_from_slice(box_id.as_ref());
+            let value = verifier
+                .perform_one_operation(&Operation::Lookup(key))
+                .map_err(|e| UtxoProofError::InvalidProof(format!("{:?}", e)))?;
+            value
+                .map(|bytes| {
+                    let b = ErgoBox::sigma_parse_bytes(&bytes)?;
+                    if b.box_id() == *box_id {
+                        Ok(b)
+                    } else {
+                        Err(UtxoProofError::BoxIdMismatch {
+                            expected: *box_id,
+                            actual: b.box_id(),
+                        })
+                    }
+                })
+                .transpose()
+        })
+        .collect()
+}
+
+/// Check the presence of the box in the UTXO set at the given header (see [`verify_utxo_proof`])
+pub fn verify_box_in_utxo_set(
+    header: &Header,
+    box_id: &BoxId,
+    proof: &[u8],
+) -> Result<Option<ErgoBox>, UtxoProofError> {
+    let mut res = verify_utxo_proof(&header.state_root, std::slice::from_ref(box_id), proof)?;
+    res.pop().ok_or_else(|| {
+        UtxoProofError::InvalidProof(format!("no lookup result for box id {:?}", box_id))
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergo_avltree_rust::authenticated_tree_ops::AuthenticatedTreeOps;
+    use ergo_avltree_rust::batch_avl_prover::BatchAVLProver;
+    use ergo_avltree_rust::operation::KeyValue;
+    use sigma_ser::ScorexSerializable;
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_utxo_proof() {
+        let boxes: Vec<ErgoBox> = (0..3).map(|_| force_any_val::<ErgoBox>()).collect();
+        let mut prover = BatchAVLProver::new(
+            AVLTree::new(
+                |digest| Node::LabelOnly(NodeHeader::new(Some(*digest), None)),
+                KEY_LENGTH,
+                None,
+            ),
+            true,
+        );
+        for b in &boxes {
+            let op = Operation::Insert(KeyValue {
+                key: Bytes::copy_from_slice(b.box_id().as_ref()),
+                value: Bytes::from(b.sigma_serialize_bytes().unwrap()),
+            });
+            prover.perform_one_operation(&op).unwrap();
+        }
+        prover.generate_proof();
+        let state_root =
+            ADDigest::scorex_parse_bytes(&prover.digest().unwrap().into_iter().collect::<Vec<_>>())
+                .unwrap();
+
+        let missing = force_any_val::<BoxId>();
+        let box_ids = vec![boxes[1].box_id(), missing, boxes[0].box_id()];
+        for box_id in &box_ids {
+            prover
+                .perform_one_operation(&Operation::Lookup(Bytes::copy_from_slice(box_id.as_ref())))
+                .unwrap();
+        }
+        let proof = prover.generate_proof().to_vec();
+
+        assert_eq!(
+            verify_utxo_proof(&state_root, &box_ids, &proof).unwrap(),
+            vec![Some(boxes[1].clone()), None, Some(boxes[0].clone())]
+        );
+        assert!(verify_utxo_proof(&ADDigest::zero(), &box_ids, &proof).is_err());
+        let header = Header {
+            state_root,
+            ..force_any_val::<Header>()
+        };
+        prover
+            .perform_one_operation(&Operation::Lookup(Bytes::copy_from_slice(
+                boxes[2].box_id().as_ref(),
+            )))
+            .unwrap();
+        let proof = prover.generate_proof().to_vec();
+        assert_eq!(
+            verify_box_in_utxo_set(&header, &boxes[2].box_id(), &proof).unwrap(),
+            Some(boxes[2].clone())
+        );
+        // lookup of a different box than the proof is for
+        assert!(verify_box_in_utxo_set(&header, &boxes[0].box_id(), &proof).is_err());
+    }
+}
@@ -0,0 +1,646 @@
+//! UTXO set snapshot (node bootstrapping) manifest and chunks parsing
+//!
+//! The node serves the UTXO set AVL+ tree split into the manifest (top levels of the tree, down to
+//! the manifest depth) and the chunks (subtrees below the manifest). Each chunk is identified by
+//! the label of its root node, which is referenced by the lowest manifest nodes, and the manifest
+//! root label together with the tree height is the state root (`Header::state_root`).
+//! See https://github.com/ergoplatform/ergo/blob/a3bb4d8e70a37ef30b1e28bd41dec0d2a8c0fe08/src/main/scala/org/ergoplatform/nodeView/state/UtxoSetSnapshotPersistence.scala
+
+use std::collections::HashSet;
+use std::convert::TryInto;
+
+use ergo_chain_types::ADDigest;
+use ergo_chain_types::Digest32;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::serialization::SigmaParsingError;
+use ergotree_ir::serialization::SigmaSerializable;
+use sigma_util::hash::blake2b256_hash;
+use thiserror::Error;
+
+/// Length of the keys (box ids) in the UTXO set tree
+pub const KEY_LENGTH: usize = 32;
+
+/// Key of the leftmost (sentinel) leaf of the tree
+const NEGATIVE_INFINITY_KEY: [u8; KEY_LENGTH] = [0; KEY_LENGTH];
+
+/// Serialized node prefixes
+const INTERNAL_NODE_PREFIX: u8 = 0;
+const LEAF_PREFIX: u8 = 1;
+
+/// Node label hash prefixes
+const LEAF_LABEL_PREFIX: u8 = 0;
+const INTERNAL_NODE_LABEL_PREFIX: u8 = 1;
+
+/// Errors on UTXO snapshot manifest and chunks parsing and validation
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum UtxoSnapshotError {
+    /// Unexpected end of the serialized data
+    #[error("unexpected end of data at byte {0}")]
+    UnexpectedEof(usize),
+    /// Unknown serialized node prefix
+    #[error("invalid node prefix {prefix} at byte {offset}")]
+    InvalidNodePrefix {
+        /// Prefix byte
+        prefix: u8,
+        /// Offset of the prefix
+        offset: usize,
+    },
+    /// Bytes left after the tree
+    #[error("{0} trailing bytes after the tree")]
+    TrailingBytes(usize),
+    /// Label of the child node differs from the one stored in its parent
+    #[error("child node label {actual:?} differs from the one in its parent {expected:?}")]
+    LabelMismatch {
+        /// Label stored in the parent node
+        expected: Digest32,
+        /// Label of the parsed node
+        actual: Digest32,
+    },
+    /// Manifest digest differs from the expected state root
+    #[error("manifest digest {actual:?} differs from the state root {expected:?}")]
+    StateRootMismatch {
+        /// Expected state root
+        expected: ADDigest,
+        /// Manifest root label and tree height
+        actual: ADDigest,
+    },
+    /// Chunk is not referenced by the manifest
+    #[error("chunk {0:?} is not referenced by the manifest")]
+    UnknownChunk(Digest32),
+    /// Leaf value is not a valid box
+    #[error("box parsing error: {0}")]
+    BoxParsing(#[from] SigmaParsingError),
+    /// Tree has more levels than its height allows
+    #[error("tree is deeper than {0} levels")]
+    TooDeep(u8),
+    /// Leaf key differs from the id of the box in its value
+    #[error("leaf key {key:?} differs from the box id {box_id:?}")]
+    BoxIdMismatch {
+        /// Leaf key
+        key: Digest32,
+        /// Id of the box
+        box_id: Digest32,
+    },
+}
+
+/// Node of the serialized AVL+ tree
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum AvlNode {
+    /// Internal node, with its children given by labels
+    Internal {
+        /// Balance (height of the right subtree minus the left one)
+        balance: i8,
+        /// Key (the smallest key of the right subtree)
+        key: [u8; KEY_LENGTH],
+        /// Label of the left child
+        left_label: Digest32,
+        /// Label of the right child
+        right_label: Digest32,
+    },
+    /// Leaf
+    Leaf {
+        /// Key (box id)
+        key: [u8; KEY_LENGTH],
+        /// Value (serialized box)
+        value: Vec<u8>,
+        /// Key of the next leaf
+        next_leaf_key: [u8; KEY_LENGTH],
+    },
+}
+
+impl AvlNode {
+    /// Label (hash) of the node
+    pub fn label(&self) -> Digest32 {
+        let bytes = match self {
+            AvlNode::Internal {
+                balance,
+                left_label,
+                right_label,
+                ..
+            } => [
+                &[INTERNAL_NODE_LABEL_PREFIX, *balance as u8][..],
+                left_label.0.as_ref(),
+                right_label.0.as_ref(),
+            ]
+            .concat(),
+            AvlNode::Leaf {
+                key,
+                value,
+                next_leaf_key,
+            } => [
+                &[LEAF_LABEL_PREFIX][..],
+                &key[..],
+                &value[..],
+                &next_leaf_key[..],
+            ]
+            .concat(),
+        };
+        Digest32::from(blake2b256_hash(&bytes))
+    }
+}
+
+/// UTXO set snapshot manifest (top levels of the UTXO set tree)
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SnapshotManifest {
+    /// Height of the whole tree
+    pub root_height: u8,
+    /// Number of the tree levels in the manifest
+    pub manifest_depth: u8,
+    /// Manifest nodes in the pre-order (root first)
+    pub nodes: Vec<AvlNode>,
+    chunk_ids: Vec<Digest32>,
+}
+
+impl SnapshotManifest {
+    /// Parse the serialized manifest, checking that the child nodes match the labels stored in
+    /// their parents and that the tree is not deeper than its height
+    pub fn parse(bytes: &[u8]) -> Result<SnapshotManifest, UtxoSnapshotError> {
+        let mut r = TreeReader::new(bytes, u8::MAX);
+        let root_height = r.get_u8()?;
+        let manifest_depth = r.get_u8()?;
+        // leaves are one level below the lowest internal nodes
+        r.max_level = root_height.saturating_add(1);
+        let mut nodes = Vec::new();
+        let mut chunk_ids = Vec::new();
+        r.parse_subtree(1, Some(manifest_depth), &mut nodes, &mut chunk_ids)?;
+        r.finish()?;
+        Ok(SnapshotManifest {
+            root_height,
+            manifest_depth,
+            nodes,
+            chunk_ids,
+        })
+    }
+
+    /// Label of the tree root
+    pub fn root_label(&self) -> Digest32 {
+        // parse ensures there is at least the root
+        #[allow(clippy::unwrap_used)]
+        self.nodes.first().unwrap().label()
+    }
+
+    /// Digest of the tree (root label and tree height) to be checked against the state root in
+    /// the header
+    pub fn digest(&self) -> ADDigest {
+        let mut digest = [0u8; 33];
+        digest[..32].copy_from_slice(self.root_label().0.as_ref());
+        digest[32] = self.root_height;
+        ADDigest::from(digest)
+    }
+
+    /// Check the manifest against the state root of the snapshot height header
+    pub fn verify(&self, state_root: &ADDigest) -> Result<(), UtxoSnapshotError> {
+        let actual = self.digest();
+        if &actual == state_root {
+            Ok(())
+        } else {
+            Err(UtxoSnapshotError::StateRootMismatch {
+                expected: *state_root,
+                actual,
+            })
+        }
+    }
+
+    /// Ids (root labels) of the chunks referenced by the manifest, in the tree order
+    pub fn chunk_ids(&self) -> &[Digest32] {
+        &self.chunk_ids
+    }
+
+    /// Parse the serialized chunk (see [`SnapshotChunk::parse`]) not deeper than the tree height
+    /// allows below the manifest, and check that it is referenced by the manifest
+    pub fn parse_chunk(&self, bytes: &[u8]) -> Result<SnapshotChunk, UtxoSnapshotError> {
+        let max_level = self
+            .root_height
+            .saturating_add(1)
+            .saturating_sub(self.manifest_depth);
+        let chunk = SnapshotChunk::parse_bounded(bytes, max_level)?;
+        self.verify_chunk(&chunk)?;
+        Ok(chunk)
+    }
+
+    /// Check that the chunk is referenced by the manifest
+    pub fn verify_chunk(&self, chunk: &SnapshotChunk) -> Result<(), UtxoSnapshotError> {
+        let id = chunk.id();
+        if self.chunk_ids.contains(&id) {
+            Ok(())
+        } else {
+            Err(UtxoSnapshotError::UnknownChunk(id))
+        }
+    }
+}
+
+/// UTXO set snapshot chunk (subtree below the manifest)
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SnapshotChunk {
+    id: Digest32,
+    /// Chunk nodes in the pre-order (root first)
+    pub nodes: Vec<AvlNode>,
+}
+
+impl SnapshotChunk {
+    /// Parse the serialized chunk, checking that the child nodes match the labels stored in
+    /// their parents (at most `u8::MAX` levels deep, use [`SnapshotManifest::parse_chunk`] to
+    /// bound it by the tree height)
+    pub fn parse(bytes: &[u8]) -> Result<SnapshotChunk, UtxoSnapshotError> {
+        SnapshotChunk::parse_bounded(bytes, u8::MAX)
+    }
+
+    fn parse_bounded(bytes: &[u8], max_level: u8) -> Result<SnapshotChunk, UtxoSnapshotError> {
+        let mut r = TreeReader::new(bytes, max_level);
+        let mut nodes = Vec::new();
+        let id = r.parse_subtree(1, None, &mut nodes, &mut Vec::new())?;
+        r.finish()?;
+        Ok(SnapshotChunk { id, nodes })
+    }
+
+    /// Chunk id (label of the subtree root)
+    pub fn id(&self) -> Digest32 {
+        self.id
+    }
+
+    /// Boxes in the chunk leaves (the sentinel leaf is skipped), checking the box ids against the
+    /// leaf keys
+    pub fn boxes(&self) -> Result<Vec<ErgoBox>, UtxoSnapshotError> {
+        self.nodes
+            .iter()
+            .filter_map(|node| match node {
+                AvlNode::Leaf { key, value, .. } if *key != NEGATIVE_INFINITY_KEY => {
+                    Some((key, value))
+                }
+                AvlNode::Leaf { .. } | AvlNode::Internal { .. } => None,
+            })
+            .map(|(key, value)| {
+                let b = ErgoBox::sigma_parse_bytes(value)?;
+                let box_id: Digest32 = b.box_id().into();
+                let key = Digest32::from(*key);
+                if box_id == key {
+                    Ok(b)
+                } else {
+                    Err(UtxoSnapshotError::BoxIdMismatch { key, box_id })
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parse a chunk and check that it is referenced by the manifest, returning its boxes
+pub fn parse_chunk_boxes(
+    manifest: &SnapshotManifest,
+    bytes: &[u8],
+) -> Result<Vec<ErgoBox>, UtxoSnapshotError> {
+    manifest.parse_chunk(bytes)?.boxes()
+}
+
+/// Check that the chunks cover all the chunk ids of the manifest (each one exactly once)
+pub fn verify_chunks_complete(
+    manifest: &SnapshotManifest,
+    chunks: &[SnapshotChunk],
+) -> Result<(), UtxoSnapshotError> {
+    let mut left: HashSet<Digest32> = manifest.chunk_ids().iter().copied().collect();
+    for chunk in chunks {
+        if !left.remove(&chunk.id()) {
+            return Err(UtxoSnapshotError::UnknownChunk(chunk.id()));
+        }
+    }
+    match left.into_iter().next() {
+        Some(missing) => Err(UtxoSnapshotError::UnknownChunk(missing)),
+        None => Ok(()),
+    }
+}
+
+struct TreeReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// Deepest level of the nodes (bounds the recursion on malicious input)
+    max_level: u8,
+}
+
+impl<'a> TreeReader<'a> {
+    fn new(bytes: &'a [u8], max_level: u8) -> Self {
+        TreeReader {
+            bytes,
+            pos: 0,
+            max_level,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], UtxoSnapshotError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(UtxoSnapshotError::UnexpectedEof(self.pos))?;
+        let res = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(res)
+    }
+
+    fn get_u8(&mut self) -> Result<u8, UtxoSnapshotError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn get_key(&mut self) -> Result<[u8; KEY_LENGTH], UtxoSnapshotError> {
+        #[allow(clippy::unwrap_used)]
+        Ok(self.take(KEY_LENGTH)?.try_into().unwrap())
+    }
+
+    fn get_label(&mut self) -> Result<Digest32, UtxoSnapshotError> {
+        Ok(Digest32::from(self.get_key()?))
+    }
+
+    fn finish(&self) -> Result<(), UtxoSnapshotError> {
+        match self.bytes.len() - self.pos {
+            0 => Ok(()),
+            left => Err(UtxoSnapshotError::TrailingBytes(left)),
+        }
+    }
+
+    fn parse_node(&mut self) -> Result<AvlNode, UtxoSnapshotError> {
+        let offset = self.pos;
+        match self.get_u8()? {
+            INTERNAL_NODE_PREFIX => Ok(AvlNode::Internal {
+                balance: self.get_u8()? as i8,
+                key: self.get_key()?,
+                left_label: self.get_label()?,
+                right_label: self.get_label()?,
+            }),
+            LEAF_PREFIX => {
+                let key = self.get_key()?;
+                #[allow(clippy::unwrap_used)]
+                let value_len = u32::from_be_bytes(self.take(4)?.try_into().unwrap());
+                let value = self.take(value_len as usize)?.to_vec();
+                let next_leaf_key = self.get_key()?;
+                Ok(AvlNode::Leaf {
+                    key,
+                    value,
+                    next_leaf_key,
+                })
+            }
+            prefix => Err(UtxoSnapshotError::InvalidNodePrefix { prefix, offset }),
+        }
+    }
+
+    /// Parse the subtree in the pre-order down to `max_level` (children of the internal nodes at
+    /// `max_level` are collected into `pending` labels), returning the label of the subtree root
+    fn parse_subtree(
+        &mut self,
+        level: u8,
+        max_level: Option<u8>,
+        nodes: &mut Vec<AvlNode>,
+        pending: &mut Vec<Digest32>,
+    ) -> Result<Digest32, UtxoSnapshotError> {
+        if level > self.max_level {
+            return Err(UtxoSnapshotError::TooDeep(self.max_level));
+        }
+        let node = self.parse_node()?;
+        let label = node.label();
+        let children = match &node {
+            AvlNode::Internal {
+                left_label,
+                right_label,
+                ..
+            } => Some((*left_label, *right_label)),
+            AvlNode::Leaf { .. } => None,
+        };
+        nodes.push(node);
+        if let Some((left_label, right_label)) = children {
+            if max_level.map_or(true, |max_level| level < max_level) {
+                let child_level = level
+                    .checked_add(1)
+                    .ok_or(UtxoSnapshotError::TooDeep(self.max_level))?;
+                for expected in [left_label, right_label] {
+                    let actual = self.parse_subtree(child_level, max_level, nodes, pending)?;
+                    if actual != expected {
+                        return Err(UtxoSnapshotError::LabelMismatch { expected, actual });
+                    }
+                }
+            } else {
+                pending.push(left_label);
+                pending.push(right_label);
+            }
+        }
+        Ok(label)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use bytes::Bytes;
+    use ergo_avltree_rust::authenticated_tree_ops::AuthenticatedTreeOps;
+    use ergo_avltree_rust::batch_avl_prover::BatchAVLProver;
+    use ergo_avltree_rust::batch_node::AVLTree;
+    use ergo_avltree_rust::batch_node::Node;
+    use ergo_avltree_rust::batch_node::NodeHeader;
+    use ergo_avltree_rust::operation::KeyValue;
+    use ergo_avltree_rust::operation::Operation;
+    use ergotree_ir::chain::ergo_box::ErgoBox;
+    use sigma_ser::ScorexSerializable;
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+
+    fn leaf(b: &ErgoBox, next_leaf_key: [u8; KEY_LENGTH]) -> AvlNode {
+        AvlNode::Leaf {
+            key: b.box_id().into(),
+            value: b.sigma_serialize_bytes().unwrap(),
+            next_leaf_key,
+        }
+    }
+
+    fn internal(left: &AvlNode, right: &AvlNode) -> AvlNode {
+        AvlNode::Internal {
+            balance: 0,
+            key: [1; KEY_LENGTH],
+            left_label: left.label(),
+            right_label: right.label(),
+        }
+    }
+
+    fn serialize(node: &AvlNode) -> Vec<u8> {
+        match node {
+            AvlNode::Internal {
+                balance,
+                key,
+                left_label,
+                right_label,
+            } => [
+                &[INTERNAL_NODE_PREFIX, *balance as u8][..],
+                &key[..],
+                left_label.0.as_ref(),
+                right_label.0.as_ref(),
+            ]
+            .concat(),
+            AvlNode::Leaf {
+                key,
+                value,
+                next_leaf_key,
+            } => [
+                &[LEAF_PREFIX][..],
+                &key[..],
+                &(value.len() as u32).to_be_bytes()[..],
+                &value[..],
+                &next_leaf_key[..],
+            ]
+            .concat(),
+        }
+    }
+
+    #[test]
+    fn test_manifest_against_avl_prover_digest() {
+        // the UTXO set tree of the node is built by the same AVL+ prover, so inserting the boxes
+        // in the ascending id order gives the tree
+        // root(k1, balance 1) -> [sentinel(next k1), internal(k2) -> [leaf(k1), leaf(k2)]]
+        let mut boxes: Vec<ErgoBox> = (0..2).map(|_| force_any_val::<ErgoBox>()).collect();
+        boxes.sort_by_key(|b| b.box_id());
+        let mut prover = BatchAVLProver::new(
+            AVLTree::new(
+                |digest| Node::LabelOnly(NodeHeader::new(Some(*digest), None)),
+                KEY_LENGTH,
+                None,
+            ),
+            true,
+        );
+        for b in &boxes {
+            let op = Operation::Insert(KeyValue {
+                key: Bytes::copy_from_slice(b.box_id().as_ref()),
+                value: Bytes::from(b.sigma_serialize_bytes().unwrap()),
+            });
+            prover.perform_one_operation(&op).unwrap();
+        }
+        let state_root =
+            ADDigest::scorex_parse_bytes(&prover.digest().unwrap().into_iter().collect::<Vec<_>>())
+                .unwrap();
+
+        let k1: [u8; KEY_LENGTH] = boxes[0].box_id().into();
+        let k2: [u8; KEY_LENGTH] = boxes[1].box_id().into();
+        let sentinel = AvlNode::Leaf {
+            key: NEGATIVE_INFINITY_KEY,
+            value: Vec::new(),
+            next_leaf_key: k1,
+        };
+        let leaf1 = leaf(&boxes[0], k2);
+        let leaf2 = leaf(&boxes[1], [0xff; KEY_LENGTH]);
+        let right = AvlNode::Internal {
+            balance: 0,
+            key: k2,
+            left_label: leaf1.label(),
+            right_label: leaf2.label(),
+        };
+        let root = AvlNode::Internal {
+            balance: 1,
+            key: k1,
+            left_label: sentinel.label(),
+            right_label: right.label(),
+        };
+        let manifest =
+            SnapshotManifest::parse(&[&[2u8, 1][..], &serialize(&root)].concat()).unwrap();
+        assert_eq!(manifest.digest(), state_root);
+        manifest.verify(&state_root).unwrap();
+
+        let chunk = SnapshotChunk::parse(
+            &[serialize(&right), serialize(&leaf1), serialize(&leaf2)].concat(),
+        )
+        .unwrap();
+        manifest.verify_chunk(&chunk).unwrap();
+        assert_eq!(chunk.boxes().unwrap(), boxes);
+        let sentinel_chunk = SnapshotChunk::parse(&serialize(&sentinel)).unwrap();
+        assert_eq!(sentinel_chunk.boxes().unwrap(), vec![]);
+        verify_chunks_complete(&manifest, &[sentinel_chunk, chunk]).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_and_chunks() {
+        let boxes: Vec<ErgoBox> = (0..4).map(|_| force_any_val::<ErgoBox>()).collect();
+        let leaves: Vec<AvlNode> = boxes.iter().map(|b| leaf(b, [0xff; 32])).collect();
+        let left = internal(&leaves[0], &leaves[1]);
+        let right = internal(&leaves[2], &leaves[3]);
+        let root = internal(&left, &right);
+        let manifest_bytes = [&[2u8, 1][..], &serialize(&root)].concat();
+        let manifest = SnapshotManifest::parse(&manifest_bytes).unwrap();
+        assert_eq!(manifest.chunk_ids(), &[left.label(), right.label()]);
+        let mut state_root = [2u8; 33];
+        state_root[..32].copy_from_slice(root.label().0.as_ref());
+        manifest.verify(&ADDigest::from(state_root)).unwrap();
+        assert!(manifest.verify(&ADDigest::zero()).is_err());
+
+        let chunks: Vec<SnapshotChunk> = [(&left, &leaves[..2]), (&right, &leaves[2..])]
+            .iter()
+            .map(|(subtree_root, leaves)| {
+                let bytes = [
+                    serialize(subtree_root),
+                    serialize(&leaves[0]),
+                    serialize(&leaves[1]),
+                ]
+                .concat();
+                assert_eq!(
+                    parse_chunk_boxes(&manifest, &bytes).unwrap().len(),
+                    leaves.len()
+                );
+                SnapshotChunk::parse(&bytes).unwrap()
+            })
+            .collect();
+        assert_eq!(chunks[1].boxes().unwrap(), boxes[2..].to_vec());
+        verify_chunks_complete(&manifest, &chunks).unwrap();
+        assert_eq!(
+            verify_chunks_complete(&manifest, &chunks[..1]),
+            Err(UtxoSnapshotError::UnknownChunk(right.label()))
+        );
+
+        // leaves swapped
+        let bytes = [
+            serialize(&left),
+            serialize(&leaves[1]),
+            serialize(&leaves[0]),
+        ]
+        .concat();
+        assert!(matches!(
+            SnapshotChunk::parse(&bytes),
+            Err(UtxoSnapshotError::LabelMismatch { .. })
+        ));
+        // not in the manifest
+        let bytes = serialize(&leaves[0]);
+        assert_eq!(
+            parse_chunk_boxes(&manifest, &bytes),
+            Err(UtxoSnapshotError::UnknownChunk(leaves[0].label()))
+        );
+        assert_eq!(
+            SnapshotChunk::parse(&bytes[..40]),
+            Err(UtxoSnapshotError::UnexpectedEof(37))
+        );
+
+        // deeper than the tree height
+        assert_eq!(
+            SnapshotManifest::parse(
+                &[&[1u8, 3][..], &serialize(&root), &serialize(&left)].concat()
+            ),
+            Err(UtxoSnapshotError::TooDeep(2))
+        );
+        let short_manifest =
+            SnapshotManifest::parse(&[&[1u8, 1][..], &serialize(&root)].concat()).unwrap();
+        let bytes = [
+            serialize(&left),
+            serialize(&leaves[0]),
+            serialize(&leaves[1]),
+        ]
+        .concat();
+        assert_eq!(
+            short_manifest.parse_chunk(&bytes),
+            Err(UtxoSnapshotError::TooDeep(1))
+        );
+        // a chain of internal nodes longer than the u8 levels
+        let mut node = leaves[0].clone();
+        let mut chain = Vec::new();
+        for _ in 0..u8::MAX {
+            node = internal(&node, &leaves[1]);
+            chain.push(serialize(&node));
+        }
+        // pre-order: the top node first
+        chain.reverse();
+        assert_eq!(
+            SnapshotChunk::parse(&chain.concat()),
+            Err(UtxoSnapshotError::TooDeep(u8::MAX))
+        );
+    }
+}
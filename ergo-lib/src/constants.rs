@@ -1 +1,5 @@
 //! Constants specific to Ergo
+
+/// Default maximum cost of all transactions in a block (the node's `maxBlockCost` parameter
+/// default value)
+pub const MAX_BLOCK_COST: u64 = ergo_chain_types::parameters::MAX_BLOCK_COST_DEFAULT as u64;
@@ -0,0 +1,198 @@
+//! Loader of the mainnet reference data (blocks, boxes) for the regression tests.
+//! Fixtures are read from a local cache directory and, if missing there, downloaded from the
+//! node (with the extra indexing enabled for the spent boxes) and written to the cache, so the
+//! tests can run offline once the cache is populated (e.g. committed to the repository).
+//!
+//! Cache layout: `<dir>/blocks/<header_id>.json` (full blocks) and `<dir>/boxes/<box_id>.json`.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ergo_chain_types::BlockId;
+use ergo_rest::api::node::get_box_by_id;
+use ergo_rest::api::node::get_full_block;
+use ergo_rest::NodeConf;
+use ergo_rest::NodeError;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::tx_id::TxId;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::chain::block::FullBlock;
+use crate::chain::transaction::Transaction;
+use crate::wallet::signing::TransactionContext;
+use crate::wallet::tx_context::TransactionContextError;
+
+/// Errors on loading the fixtures
+#[derive(Error, Debug)]
+pub enum FixtureError {
+    /// Cache read/write error
+    #[error("Cache IO error: {0}")]
+    Io(#[from] io::Error),
+    /// Cached fixture is not a valid JSON
+    #[error("Cache JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// Node request failed
+    #[error("Node error: {0}")]
+    NodeError(#[from] NodeError),
+    /// Fixture is neither cached nor found on the node
+    #[error("Fixture not found: {0}")]
+    NotFound(String),
+    /// Fixture is not cached and the loader is offline
+    #[error("Fixture {0} is not cached and no node is set")]
+    NotCached(String),
+    /// Transaction is not in the block
+    #[error("Transaction {tx_id} is not in the block {block_id}")]
+    TxNotInBlock {
+        /// Transaction id
+        tx_id: TxId,
+        /// Block (header) id
+        block_id: BlockId,
+    },
+    /// Loaded boxes don't match the transaction inputs
+    #[error("TransactionContextError: {0}")]
+    TransactionContextError(#[from] TransactionContextError),
+}
+
+/// Transaction with the boxes it spends and reads, ready for the verification
+/// (see [`TransactionContext`])
+#[derive(Debug, Clone)]
+pub struct TxFixture {
+    /// Block (header) id the transaction is included in
+    pub block_id: BlockId,
+    /// Transaction with the input boxes and data input boxes
+    pub tx_context: TransactionContext<Transaction>,
+}
+
+/// Fixture loader over the local cache directory with an optional node to download the
+/// missing fixtures from
+#[derive(Debug, Clone)]
+pub struct FixtureLoader {
+    cache_dir: PathBuf,
+    node: Option<NodeConf>,
+}
+
+impl FixtureLoader {
+    /// Loader reading only the cached fixtures
+    pub fn offline(cache_dir: impl Into<PathBuf>) -> Self {
+        FixtureLoader {
+            cache_dir: cache_dir.into(),
+            node: None,
+        }
+    }
+
+    /// Loader downloading the missing fixtures from the node
+    pub fn with_node(cache_dir: impl Into<PathBuf>, node: NodeConf) -> Self {
+        FixtureLoader {
+            cache_dir: cache_dir.into(),
+            node: Some(node),
+        }
+    }
+
+    /// Cache directory
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Full block by header id
+    pub async fn block(&self, block_id: BlockId) -> Result<FullBlock, FixtureError> {
+        let path = self.path("blocks", &String::from(block_id.0));
+        self.load(&path, |node| get_full_block(node, block_id))
+            .await
+    }
+
+    /// Box by id (spent or not)
+    pub async fn ergo_box(&self, box_id: BoxId) -> Result<ErgoBox, FixtureError> {
+        let path = self.path("boxes", &box_id.to_string());
+        self.load(&path, |node| get_box_by_id(node, box_id)).await
+    }
+
+    /// Transaction from the block along with its input and data input boxes
+    pub async fn tx(&self, block_id: BlockId, tx_id: TxId) -> Result<TxFixture, FixtureError> {
+        let block = self.block(block_id).await?;
+        let tx = block
+            .block_transactions
+            .transactions
+            .iter()
+            .find(|tx| tx.id() == tx_id)
+            .cloned()
+            .ok_or(FixtureError::TxNotInBlock { tx_id, block_id })?;
+        let mut boxes_to_spend = Vec::new();
+        for input in tx.inputs.iter() {
+            boxes_to_spend.push(self.ergo_box(input.box_id).await?);
+        }
+        let mut data_boxes = Vec::new();
+        for data_input in tx.data_inputs.iter().flat_map(|d| d.iter()) {
+            data_boxes.push(self.ergo_box(data_input.box_id).await?);
+        }
+        Ok(TxFixture {
+            block_id,
+            tx_context: TransactionContext::new(tx, boxes_to_spend, data_boxes)?,
+        })
+    }
+
+    fn path(&self, kind: &str, id: &str) -> PathBuf {
+        self.cache_dir.join(kind).join(format!("{}.json", id))
+    }
+
+    async fn load<T, F, Fut>(&self, path: &Path, fetch: F) -> Result<T, FixtureError>
+    where
+        T: DeserializeOwned + Serialize,
+        F: FnOnce(NodeConf) -> Fut,
+        Fut: std::future::Future<Output = Result<Option<T>, NodeError>>,
+    {
+        if path.exists() {
+            return Ok(serde_json::from_str(&fs::read_to_string(path)?)?);
+        }
+        let name = path.display().to_string();
+        let node = self
+            .node
+            .ok_or_else(|| FixtureError::NotCached(name.clone()))?;
+        let value = fetch(node).await?.ok_or(FixtureError::NotFound(name))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&value)?)?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const BOX_JSON: &str = r#"{
+        "boxId": "e56847ed19b3dc6b72828fcfb992fdf7310828cf291221269b7ffc72fd66706e",
+        "value": 67500000000,
+        "ergoTree": "100204a00b08cd021dde34603426402615658f1d970cfa7c7bd92ac81a8b16eeebff264d59ce4604ea02d192a39a8cc7a70173007301",
+        "assets": [],
+        "creationHeight": 284761,
+        "additionalRegisters": {},
+        "transactionId": "9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9",
+        "index": 1
+    }"#;
+
+    #[test]
+    fn offline_cache() {
+        let dir = std::env::temp_dir().join("ergo-lib-fixtures-offline-cache");
+        let _ = fs::remove_dir_all(&dir);
+        let loader = FixtureLoader::offline(&dir);
+        let box_id: BoxId = "e56847ed19b3dc6b72828fcfb992fdf7310828cf291221269b7ffc72fd66706e"
+            .parse()
+            .unwrap();
+        assert!(matches!(
+            futures::executor::block_on(loader.ergo_box(box_id)),
+            Err(FixtureError::NotCached(_))
+        ));
+        fs::create_dir_all(dir.join("boxes")).unwrap();
+        fs::write(dir.join("boxes").join(format!("{}.json", box_id)), BOX_JSON).unwrap();
+        let b = futures::executor::block_on(loader.ergo_box(box_id)).unwrap();
+        assert_eq!(b.box_id(), box_id);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
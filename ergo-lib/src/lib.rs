@@ -22,6 +22,9 @@
 
 pub mod chain;
 pub mod constants;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+pub mod prelude;
 mod utils;
 pub mod wallet;
 
@@ -45,4 +48,5 @@ pub extern crate ergotree_interpreter;
 pub extern crate ergotree_ir;
 
 /// Selectively exposed types
+pub use sigma_util::BoundedVecIterExt;
 pub use utils::ArrLength;
@@ -0,0 +1,49 @@
+//! Commonly used types re-exported from `ergo-lib` and its dependencies
+//! (`ergotree-ir`, `ergotree-interpreter`, `ergo-chain-types`), so a single
+//! `use ergo_lib::prelude::*;` covers the typical app needs.
+//! The original paths stay available.
+
+pub use ergo_chain_types::BlockId;
+pub use ergo_chain_types::Digest32;
+pub use ergo_chain_types::Header;
+pub use ergo_chain_types::PreHeader;
+pub use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+pub use ergotree_ir::chain::address::Address;
+pub use ergotree_ir::chain::address::AddressEncoder;
+pub use ergotree_ir::chain::address::NetworkPrefix;
+pub use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+pub use ergotree_ir::chain::ergo_box::BoxId;
+pub use ergotree_ir::chain::ergo_box::ErgoBox;
+pub use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+pub use ergotree_ir::chain::ergo_box::NonMandatoryRegisterId;
+pub use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+pub use ergotree_ir::chain::token::Token;
+pub use ergotree_ir::chain::token::TokenAmount;
+pub use ergotree_ir::chain::token::TokenId;
+pub use ergotree_ir::chain::tx_id::TxId;
+pub use ergotree_ir::ergo_tree::ErgoTree;
+pub use ergotree_ir::mir::constant::Constant;
+pub use ergotree_ir::serialization::SigmaSerializable;
+pub use ergotree_ir::sigma_protocol::sigma_boolean::ProveDlog;
+pub use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProp;
+pub use ergotree_ir::types::stype::SType;
+
+pub use crate::chain::contract::Contract;
+pub use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+pub use crate::chain::ergo_state_context::ErgoStateContext;
+pub use crate::chain::transaction::unsigned::UnsignedTransaction;
+pub use crate::chain::transaction::DataInput;
+pub use crate::chain::transaction::Input;
+pub use crate::chain::transaction::Transaction;
+pub use crate::chain::transaction::TxIoVec;
+pub use crate::chain::transaction::UnsignedInput;
+pub use crate::wallet::box_selector::BoxSelection;
+pub use crate::wallet::box_selector::BoxSelector;
+pub use crate::wallet::box_selector::SimpleBoxSelector;
+pub use crate::wallet::derivation_path::DerivationPath;
+pub use crate::wallet::ext_secret_key::ExtSecretKey;
+pub use crate::wallet::mnemonic::Mnemonic;
+pub use crate::wallet::secret_key::SecretKey;
+pub use crate::wallet::signing::TransactionContext;
+pub use crate::wallet::tx_builder::TxBuilder;
+pub use crate::wallet::Wallet;
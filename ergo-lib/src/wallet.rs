@@ -1,16 +1,28 @@
 //! Wallet-related features for Ergo
 
+pub mod accounting;
 pub mod box_selector;
 pub mod derivation_path;
 pub mod ext_pub_key;
 pub mod ext_secret_key;
+#[cfg(feature = "external_signer")]
+pub mod external_signer;
+pub mod history;
+#[cfg(feature = "keystore")]
+pub mod keystore;
+pub mod mempool;
 pub mod miner_fee;
 pub mod mnemonic;
 #[cfg(feature = "mnemonic_gen")]
 pub mod mnemonic_generator;
 pub mod multi_sig;
+pub mod payments;
+pub mod scanner;
 pub mod secret_key;
 pub mod signing;
+#[cfg(feature = "keystore")]
+pub mod snapshot;
+pub mod token_holdings;
 pub mod tx_builder;
 pub mod tx_context;
 
@@ -19,7 +31,7 @@ use ergotree_interpreter::sigma_protocol::prover::Prover;
 use ergotree_interpreter::sigma_protocol::prover::ProverError;
 use ergotree_interpreter::sigma_protocol::prover::TestProver;
 use secret_key::SecretKey;
-use signing::{sign_transaction, TxSigningError};
+use signing::{sign_transaction, sign_transaction_with_cost, SignedTxWithCost, TxSigningError};
 use thiserror::Error;
 
 use crate::chain::ergo_state_context::ErgoStateContext;
@@ -28,11 +40,17 @@ use crate::chain::transaction::unsigned::UnsignedTransaction;
 use crate::chain::transaction::Input;
 use crate::chain::transaction::Transaction;
 use crate::ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+#[cfg(feature = "external_signer")]
+use crate::wallet::external_signer::{
+    sign_transaction_external, ExternalSigner, ExternalSignerError,
+};
 use crate::wallet::mnemonic::Mnemonic;
 use crate::wallet::multi_sig::{
     generate_commitments, generate_commitments_for, TransactionHintsBag,
 };
 
+#[cfg(feature = "external_signer")]
+use self::derivation_path::DerivationPath;
 use self::ext_secret_key::ExtSecretKey;
 use self::ext_secret_key::ExtSecretKeyError;
 use self::signing::sign_message;
@@ -100,6 +118,43 @@ impl Wallet {
             .map_err(WalletError::from)
     }
 
+    /// Signs a transaction returning the reduction and proof verification cost of the inputs
+    /// alongside the signed transaction
+    pub fn sign_transaction_with_cost(
+        &self,
+        tx_context: TransactionContext<UnsignedTransaction>,
+        state_context: &ErgoStateContext,
+        tx_hints: Option<&TransactionHintsBag>,
+    ) -> Result<SignedTxWithCost, WalletError> {
+        sign_transaction_with_cost(self.prover.as_ref(), tx_context, state_context, tx_hints)
+            .map_err(WalletError::from)
+    }
+
+    /// Signs a multi signature transaction with the hints (own commitments and the commitments
+    /// and partial proofs of the other signers) gathered by the signing coordinator
+    pub fn sign_transaction_multi(
+        &self,
+        tx_context: TransactionContext<UnsignedTransaction>,
+        state_context: &ErgoStateContext,
+        tx_hints: &TransactionHintsBag,
+    ) -> Result<Transaction, WalletError> {
+        self.sign_transaction(tx_context, state_context, Some(tx_hints))
+    }
+
+    /// Signs a transaction delegating the inputs with the given derivation path (`input_paths`
+    /// has an entry for each input) to the external signer (e.g. hardware wallet), the rest of the
+    /// inputs are signed with the wallet secrets
+    #[cfg(feature = "external_signer")]
+    pub async fn sign_transaction_with_external_signer<S: ExternalSigner + ?Sized>(
+        &self,
+        signer: &S,
+        tx_context: TransactionContext<UnsignedTransaction>,
+        state_context: &ErgoStateContext,
+        input_paths: &[Option<DerivationPath>],
+    ) -> Result<Transaction, ExternalSignerError> {
+        sign_transaction_external(signer, Some(self), tx_context, state_context, input_paths).await
+    }
+
     /// Signs a reduced transaction (generating proofs for inputs)
     pub fn sign_reduced_transaction(
         &self,
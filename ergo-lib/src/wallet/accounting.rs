@@ -0,0 +1,403 @@
+//! Per-transaction accounting records (balance changes of the owned addresses with the miner fee
+//! attribution), e.g. for the CSV exports of the exchanges and tax tools
+
+use std::collections::HashSet;
+
+use ergotree_ir::chain::address::Address;
+use ergotree_ir::chain::address::AddressEncoder;
+use ergotree_ir::chain::address::AddressError;
+use ergotree_ir::chain::address::NetworkPrefix;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::chain::tx_id::TxId;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::serialization::SigmaParsingError;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::serialization::SigmaSerializationError;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+use thiserror::Error;
+
+use crate::chain::scan_predicate::ScanningPredicate;
+use crate::chain::transaction::Transaction;
+
+use super::miner_fee::MINERS_FEE_BASE16_BYTES;
+use super::scanner::WalletScanner;
+
+/// Decides which boxes belong to the account the records are generated for
+pub trait OwnershipPredicate {
+    /// Returns true if the box is owned
+    fn is_owned(&self, b: &ErgoBox) -> bool;
+}
+
+impl<F: Fn(&ErgoBox) -> bool> OwnershipPredicate for F {
+    fn is_owned(&self, b: &ErgoBox) -> bool {
+        self(b)
+    }
+}
+
+impl OwnershipPredicate for WalletScanner {
+    fn is_owned(&self, b: &ErgoBox) -> bool {
+        self.is_tracked_script(&b.ergo_tree)
+    }
+}
+
+impl OwnershipPredicate for ScanningPredicate {
+    fn is_owned(&self, b: &ErgoBox) -> bool {
+        self.matches(b)
+    }
+}
+
+/// Boxes guarded by one of the given scripts
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct OwnedScripts {
+    /// Serialized ErgoTrees
+    scripts: HashSet<Vec<u8>>,
+}
+
+impl OwnedScripts {
+    /// Boxes guarded by the scripts of the given addresses
+    pub fn from_addresses(addresses: &[Address]) -> Result<Self, AccountingError> {
+        let mut scripts = HashSet::new();
+        for address in addresses {
+            scripts.insert(address.script()?.sigma_serialize_bytes()?);
+        }
+        Ok(OwnedScripts { scripts })
+    }
+
+    /// Boxes guarded by the given ErgoTrees
+    pub fn from_ergo_trees(trees: &[ErgoTree]) -> Result<Self, AccountingError> {
+        let mut scripts = HashSet::new();
+        for tree in trees {
+            scripts.insert(tree.sigma_serialize_bytes()?);
+        }
+        Ok(OwnedScripts { scripts })
+    }
+}
+
+impl OwnershipPredicate for OwnedScripts {
+    fn is_owned(&self, b: &ErgoBox) -> bool {
+        b.ergo_tree
+            .sigma_serialize_bytes()
+            .map(|bytes| self.scripts.contains(&bytes))
+            .unwrap_or(false)
+    }
+}
+
+/// Asset of the accounting record
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum AccountingAsset {
+    /// ERG (in nanoERGs)
+    Erg,
+    /// Token
+    Token(TokenId),
+}
+
+impl std::fmt::Display for AccountingAsset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountingAsset::Erg => write!(f, "ERG"),
+            AccountingAsset::Token(token_id) => write!(f, "{}", token_id),
+        }
+    }
+}
+
+/// Balance change of the asset on the address in the transaction
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct AccountingRecord {
+    /// Transaction id
+    pub tx_id: TxId,
+    /// Address (of the box guarding script)
+    pub address: Address,
+    /// Asset
+    pub asset: AccountingAsset,
+    /// Received (positive) or spent (negative) amount, including the fee
+    pub delta: i128,
+    /// Part of the miner fee paid by the address (always zero for tokens)
+    pub fee: u64,
+}
+
+/// Accounting errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum AccountingError {
+    /// Input box is not among the given boxes
+    #[error("Input box not found: {0:?}")]
+    InputBoxNotFound(BoxId),
+    /// Address error
+    #[error("Address error: {0}")]
+    AddressError(#[from] AddressError),
+    /// ErgoTree serialization error
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SigmaSerializationError),
+    /// ErgoTree parsing error (e.g. of the address script)
+    #[error("Parsing error: {0}")]
+    ParsingError(#[from] SigmaParsingError),
+}
+
+struct AddressBalance {
+    script: Vec<u8>,
+    address: Address,
+    owned: bool,
+    erg_spent: u64,
+    erg: i128,
+    fee: u64,
+    tokens: Vec<(TokenId, i128)>,
+}
+
+impl AddressBalance {
+    fn add_token(&mut self, token_id: TokenId, amount: i128) {
+        match self.tokens.iter_mut().find(|(id, _)| *id == token_id) {
+            Some((_, delta)) => *delta += amount,
+            None => self.tokens.push((token_id, amount)),
+        }
+    }
+}
+
+/// Balance changes of the owned addresses in the signed transaction, `input_boxes` should
+/// contain the boxes spent by the transaction. Records are ordered by the first appearance of
+/// the address in the transaction inputs (then outputs), with ERG going first for each address.
+///
+/// The miner fee (outputs to the miner fee script) is attributed to the input addresses
+/// proportionally to their spent ERG, the rounding remainder goes to the first input address.
+pub fn tx_accounting_records<P: OwnershipPredicate + ?Sized>(
+    tx: &Transaction,
+    input_boxes: &[ErgoBox],
+    owned: &P,
+) -> Result<Vec<AccountingRecord>, AccountingError> {
+    #[allow(clippy::unwrap_used)]
+    let fee_script = decode_base16(MINERS_FEE_BASE16_BYTES, DecodingMode::Strict).unwrap();
+    let mut balances: Vec<AddressBalance> = Vec::new();
+    let mut balance_of = |b: &ErgoBox| -> Result<usize, AccountingError> {
+        let script = b.ergo_tree.sigma_serialize_bytes()?;
+        if let Some(idx) = balances.iter().position(|a| a.script == script) {
+            return Ok(idx);
+        }
+        balances.push(AddressBalance {
+            script,
+            address: Address::recreate_from_ergo_tree(&b.ergo_tree)?,
+            owned: owned.is_owned(b),
+            erg_spent: 0,
+            erg: 0,
+            fee: 0,
+            tokens: Vec::new(),
+        });
+        Ok(balances.len() - 1)
+    };
+    let mut idxs = Vec::new();
+    for input in tx.inputs.iter() {
+        let b = input_boxes
+            .iter()
+            .find(|b| b.box_id() == input.box_id)
+            .ok_or(AccountingError::InputBoxNotFound(input.box_id))?;
+        idxs.push((balance_of(b)?, b, -1));
+    }
+    let mut fee: u64 = 0;
+    for b in tx.outputs.iter() {
+        if b.ergo_tree.sigma_serialize_bytes()? == fee_script {
+            fee = fee.saturating_add(*b.value.as_u64());
+        }
+        idxs.push((balance_of(b)?, b, 1));
+    }
+    for (idx, b, sign) in idxs {
+        #[allow(clippy::unwrap_used)]
+        let balance = balances.get_mut(idx).unwrap();
+        let value = *b.value.as_u64();
+        if sign < 0 {
+            balance.erg_spent = balance.erg_spent.saturating_add(value);
+        }
+        balance.erg += sign * value as i128;
+        for t in b.tokens.iter().flatten() {
+            balance.add_token(t.token_id, sign * *t.amount.as_u64() as i128);
+        }
+    }
+    let total_spent: u128 = balances.iter().map(|a| a.erg_spent as u128).sum();
+    if total_spent > 0 {
+        let mut attributed = 0;
+        for balance in balances.iter_mut() {
+            balance.fee = (fee as u128 * balance.erg_spent as u128 / total_spent) as u64;
+            attributed += balance.fee;
+        }
+        // inputs go first, so the first balance is of the first input address
+        if let Some(first) = balances.first_mut() {
+            first.fee += fee - attributed;
+        }
+    }
+    let tx_id = tx.id();
+    let mut records = Vec::new();
+    for balance in balances.into_iter().filter(|a| a.owned) {
+        if balance.erg != 0 || balance.fee != 0 {
+            records.push(AccountingRecord {
+                tx_id,
+                address: balance.address.clone(),
+                asset: AccountingAsset::Erg,
+                delta: balance.erg,
+                fee: balance.fee,
+            });
+        }
+        for (token_id, delta) in balance.tokens.into_iter().filter(|(_, d)| *d != 0) {
+            records.push(AccountingRecord {
+                tx_id,
+                address: balance.address.clone(),
+                asset: AccountingAsset::Token(token_id),
+                delta,
+                fee: 0,
+            });
+        }
+    }
+    Ok(records)
+}
+
+/// Encode the records as CSV (`tx_id,address,asset,delta,fee` with the header row)
+pub fn records_to_csv(records: &[AccountingRecord], network_prefix: NetworkPrefix) -> String {
+    let encoder = AddressEncoder::new(network_prefix);
+    let mut csv = String::from("tx_id,address,asset,delta,fee\n");
+    for r in records {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            r.tx_id,
+            encoder.address_to_str(&r.address),
+            r.asset,
+            r.delta,
+            r.fee
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+    use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::BoxTokens;
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::token::Token;
+    use ergotree_ir::chain::token::TokenAmount;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::transaction::input::prover_result::ProverResult;
+    use crate::chain::transaction::Input;
+    use crate::wallet::miner_fee::MINERS_FEE_ADDRESS;
+    use crate::wallet::secret_key::SecretKey;
+
+    use super::*;
+
+    fn candidate(address: &Address, value: u64, tokens: Vec<Token>) -> ErgoBoxCandidate {
+        ErgoBoxCandidate {
+            value: BoxValue::try_from(value).unwrap(),
+            ergo_tree: address.script().unwrap(),
+            tokens: BoxTokens::from_vec(tokens).ok(),
+            additional_registers: NonMandatoryRegisters::empty(),
+            creation_height: 0,
+        }
+    }
+
+    fn input_box(address: &Address, value: u64, tokens: Vec<Token>) -> ErgoBox {
+        ErgoBox::from_box_candidate(
+            &candidate(address, value, tokens),
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap()
+    }
+
+    fn token(token_id: TokenId, amount: u64) -> Token {
+        Token {
+            token_id,
+            amount: TokenAmount::try_from(amount).unwrap(),
+        }
+    }
+
+    #[test]
+    fn records_with_fee_attribution() {
+        let alice = SecretKey::random_dlog().get_address_from_public_image();
+        let bob = SecretKey::random_dlog().get_address_from_public_image();
+        let carol = SecretKey::random_dlog().get_address_from_public_image();
+        let token_id = force_any_val::<TokenId>();
+        let inputs = vec![
+            input_box(&alice, 3_000_000, vec![token(token_id, 10)]),
+            input_box(&bob, 2_000_001, vec![]),
+        ];
+        let tx = Transaction::new_from_vec(
+            inputs
+                .iter()
+                .map(|b| {
+                    Input::new(
+                        b.box_id(),
+                        ProverResult {
+                            proof: ProofBytes::Empty,
+                            extension: ContextExtension::empty(),
+                        },
+                    )
+                })
+                .collect(),
+            vec![],
+            vec![
+                candidate(&carol, 2_000_000, vec![token(token_id, 4)]),
+                candidate(&alice, 1_000_000, vec![token(token_id, 6)]),
+                candidate(&bob, 1_000_001, vec![]),
+                candidate(&MINERS_FEE_ADDRESS, 1_000_000, vec![]),
+            ],
+        )
+        .unwrap();
+        let owned = OwnedScripts::from_addresses(&[alice.clone(), carol.clone()]).unwrap();
+        let records = tx_accounting_records(&tx, &inputs, &owned).unwrap();
+        let expected = vec![
+            AccountingRecord {
+                tx_id: tx.id(),
+                address: alice.clone(),
+                asset: AccountingAsset::Erg,
+                delta: -2_000_000,
+                // 1_000_000 * 3/5 rounded down + remainder
+                fee: 600_000,
+            },
+            AccountingRecord {
+                tx_id: tx.id(),
+                address: alice.clone(),
+                asset: AccountingAsset::Token(token_id),
+                delta: -4,
+                fee: 0,
+            },
+            AccountingRecord {
+                tx_id: tx.id(),
+                address: carol.clone(),
+                asset: AccountingAsset::Erg,
+                delta: 2_000_000,
+                fee: 0,
+            },
+            AccountingRecord {
+                tx_id: tx.id(),
+                address: carol,
+                asset: AccountingAsset::Token(token_id),
+                delta: 4,
+                fee: 0,
+            },
+        ];
+        assert_eq!(records, expected);
+        let all = |_: &ErgoBox| true;
+        let all_records = tx_accounting_records(&tx, &inputs, &all).unwrap();
+        // balance changes of all the addresses sum up to zero
+        assert_eq!(all_records.iter().map(|r| r.delta).sum::<i128>(), 0);
+        assert_eq!(all_records.iter().map(|r| r.fee).sum::<u64>(), 1_000_000);
+        let csv = records_to_csv(&records, NetworkPrefix::Mainnet);
+        assert_eq!(csv.lines().count(), 5);
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            format!(
+                "{},{},ERG,-2000000,600000",
+                tx.id(),
+                AddressEncoder::new(NetworkPrefix::Mainnet).address_to_str(&alice)
+            )
+        );
+        assert_eq!(
+            tx_accounting_records(&tx, &inputs[..1], &owned),
+            Err(AccountingError::InputBoxNotFound(inputs[1].box_id()))
+        );
+    }
+}
@@ -10,6 +10,9 @@ use ergotree_ir::chain::ergo_box::BoxId;
 use ergotree_ir::chain::ergo_box::BoxTokens;
 use ergotree_ir::chain::ergo_box::ErgoBox;
 use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+use ergotree_ir::chain::token::add_token_amount;
+use ergotree_ir::chain::token::subtract_token_amounts;
+use ergotree_ir::chain::token::sum_token_amounts;
 use ergotree_ir::chain::token::Token;
 use ergotree_ir::chain::token::TokenAmount;
 use ergotree_ir::chain::token::TokenAmountError;
@@ -138,16 +141,7 @@ pub fn sum_value<T: ErgoBoxAssets>(bs: &[T]) -> u64 {
 
 /// Returns the total token amounts (all tokens combined)
 pub fn sum_tokens(ts: Option<&[Token]>) -> Result<HashMap<TokenId, TokenAmount>, TokenAmountError> {
-    let mut res: HashMap<TokenId, TokenAmount> = HashMap::new();
-    ts.into_iter().flatten().try_for_each(|t| {
-        if let Some(amt) = res.get_mut(&t.token_id) {
-            *amt = amt.checked_add(&t.amount)?;
-        } else {
-            res.insert(t.token_id, t.amount);
-        }
-        Ok(())
-    })?;
-    Ok(res)
+    sum_token_amounts(ts.into_iter().flatten())
 }
 
 /// Returns the total token amounts (all tokens combined) of the given boxes
@@ -156,15 +150,10 @@ pub fn sum_tokens_from_boxes<T: ErgoBoxAssets>(
 ) -> Result<HashMap<TokenId, TokenAmount>, TokenAmountError> {
     let mut res: HashMap<TokenId, TokenAmount> = HashMap::new();
     bs.iter().try_for_each(|b| {
-        b.tokens().into_iter().flatten().try_for_each(|t| {
-            if let Some(amt) = res.get_mut(&t.token_id) {
-                *amt = amt.checked_add(&t.amount)?;
-            } else {
-                res.insert(t.token_id, t.amount);
-            }
-
-            Ok(())
-        })
+        b.tokens()
+            .into_iter()
+            .flatten()
+            .try_for_each(|t| add_token_amount(&mut res, &t))
     })?;
     Ok(res)
 }
@@ -174,18 +163,10 @@ pub fn sum_tokens_from_hashmaps(
     tokens1: HashMap<TokenId, TokenAmount>,
     tokens2: HashMap<TokenId, TokenAmount>,
 ) -> Result<HashMap<TokenId, TokenAmount>, TokenAmountError> {
-    let mut res: HashMap<TokenId, TokenAmount> = HashMap::new();
-    tokens1
+    let mut res: HashMap<TokenId, TokenAmount> = tokens1;
+    tokens2
         .into_iter()
-        .chain(tokens2)
-        .try_for_each(|(id, t_amt)| {
-            if let Some(amt) = res.get_mut(&id) {
-                *amt = amt.checked_add(&t_amt)?;
-            } else {
-                res.insert(id, t_amt);
-            }
-            Ok(())
-        })?;
+        .try_for_each(|t| add_token_amount(&mut res, &t.into()))?;
     Ok(res)
 }
 
@@ -196,21 +177,7 @@ pub fn subtract_tokens(
     tokens1: &HashMap<TokenId, TokenAmount>,
     tokens2: &HashMap<TokenId, TokenAmount>,
 ) -> Result<HashMap<TokenId, TokenAmount>, TokenAmountError> {
-    let mut res: HashMap<TokenId, TokenAmount> = tokens1.clone();
-    tokens2.iter().try_for_each(|(id, t_amt)| {
-        if let Some(amt) = res.get_mut(id) {
-            if amt == t_amt {
-                res.remove(id);
-            } else {
-                *amt = amt.checked_sub(t_amt)?;
-            }
-        } else {
-            // trying to subtract a token not found in tokens1
-            return Err(TokenAmountError::OutOfBounds(-(*t_amt.as_u64() as i64)));
-        }
-        Ok(())
-    })?;
-    Ok(res)
+    subtract_token_amounts(tokens1, tokens2)
 }
 
 /// Arbitrary impl for ErgoBoxAssetsData
@@ -140,31 +140,11 @@ impl<T: ErgoBoxAssets + Clone> BoxSelector<T> for SimpleBoxSelector {
         let change_boxes: Vec<ErgoBoxAssetsData> = if !has_value_change && !has_token_change {
             vec![]
         } else {
-            let change_value: BoxValue = (selected_boxes_value - target_balance)
-                .try_into()
-                .map_err(|e| {
-                    NotEnoughCoinsForChangeBox(format!(
-                        "change box value {} is too small, error: {} ",
-                        selected_boxes_value - target_balance,
-                        e
-                    ))
-                })?;
-            let mut change_tokens = sum_tokens_from_boxes(selected_inputs.as_slice())?;
-            target_tokens.iter().try_for_each(|t| {
-                match change_tokens.get(&t.token_id).cloned() {
-                    Some(selected_boxes_t_amt) if selected_boxes_t_amt == t.amount => {
-                        change_tokens.remove(&t.token_id);
-                        Ok(())
-                    }
-                    Some(selected_boxes_t_amt) if selected_boxes_t_amt > t.amount => {
-                        change_tokens
-                            .insert(t.token_id, selected_boxes_t_amt.checked_sub(&t.amount)?);
-                        Ok(())
-                    }
-                    _ => Err(BoxSelectorError::NotEnoughTokens(vec![t.clone()])),
-                }
-            })?;
-            make_change_boxes(change_value, change_tokens)?
+            calc_change(
+                selected_inputs.as_slice(),
+                target_balance_original,
+                target_tokens,
+            )?
         };
         check_input_preservation(
             selected_inputs.as_slice(),
@@ -226,11 +206,57 @@ fn check_input_preservation<T: ErgoBoxAssets>(
 #[error("Not enough coins for change box(es)")]
 pub struct NotEnoughCoinsForChangeBox(String);
 
+/// Change (value and tokens left after the target balance and tokens are taken) for the given
+/// selected inputs, split into multiple boxes if there are more than [`ErgoBox::MAX_TOKENS_COUNT`]
+/// distinct tokens. Returns an empty list if the inputs exactly match the targets.
+/// Useful for the custom selection algorithms, so that no tokens are left out (burnt).
+pub fn calc_change<T: ErgoBoxAssets>(
+    selected_inputs: &[T],
+    target_balance: BoxValue,
+    target_tokens: &[Token],
+) -> Result<Vec<ErgoBoxAssetsData>, BoxSelectorError> {
+    let selected_boxes_value = sum_value(selected_inputs);
+    let target_balance: u64 = target_balance.into();
+    if selected_boxes_value < target_balance {
+        return Err(BoxSelectorError::NotEnoughCoins(
+            target_balance - selected_boxes_value,
+        ));
+    }
+    let mut change_tokens = sum_tokens_from_boxes(selected_inputs)?;
+    target_tokens
+        .iter()
+        .try_for_each(|t| match change_tokens.get(&t.token_id).cloned() {
+            Some(selected_boxes_t_amt) if selected_boxes_t_amt == t.amount => {
+                change_tokens.remove(&t.token_id);
+                Ok(())
+            }
+            Some(selected_boxes_t_amt) if selected_boxes_t_amt > t.amount => {
+                change_tokens.insert(t.token_id, selected_boxes_t_amt.checked_sub(&t.amount)?);
+                Ok(())
+            }
+            _ => Err(BoxSelectorError::NotEnoughTokens(vec![t.clone()])),
+        })?;
+    let change_value_amt = selected_boxes_value - target_balance;
+    if change_value_amt == 0 && change_tokens.is_empty() {
+        return Ok(vec![]);
+    }
+    let change_value: BoxValue = change_value_amt.try_into().map_err(|e| {
+        NotEnoughCoinsForChangeBox(format!(
+            "change box value {} is too small, error: {} ",
+            change_value_amt, e
+        ))
+    })?;
+    Ok(make_change_boxes(change_value, change_tokens)?)
+}
+
 /// Split change tokens into a multiple boxes if over ErgoBox::MAX_TOKENS_COUNT distinct tokens
+/// (ordered by the token id, so the same change gives the same boxes)
 fn make_change_boxes(
     change_value: BoxValue,
     change_tokens: HashMap<TokenId, TokenAmount>,
 ) -> Result<Vec<ErgoBoxAssetsData>, NotEnoughCoinsForChangeBox> {
+    let mut change_tokens: Vec<Token> = change_tokens.into_iter().map(Token::from).collect();
+    change_tokens.sort_by_key(|t| t.token_id);
     if change_tokens.is_empty() {
         Ok(vec![ErgoBoxAssetsData {
             value: change_value,
@@ -241,14 +267,11 @@ fn make_change_boxes(
         // unwrap_used is ok here because we checked that change_tokens.len() <= ErgoBox::MAX_TOKENS_COUNT
         Ok(vec![ErgoBoxAssetsData {
             value: change_value,
-            tokens: Some(
-                BoxTokens::from_vec(change_tokens.into_iter().map(Token::from).collect()).unwrap(),
-            ),
+            tokens: Some(BoxTokens::from_vec(change_tokens).unwrap()),
         }])
     } else {
         let mut change_boxes = vec![];
-        let mut change_tokens_left: Vec<Token> =
-            change_tokens.into_iter().map(Token::from).collect();
+        let mut change_tokens_left = change_tokens;
         let mut change_value_left = change_value;
         while !change_tokens_left.is_empty() {
             if change_tokens_left.len() <= ErgoBox::MAX_TOKENS_COUNT {
@@ -604,6 +627,27 @@ mod tests {
                             "all tokens from selected boxes should equal all tokens from the change boxes + target tokens");
         }
 
+        #[test]
+        fn test_calc_change_for_custom_selection(inputs in
+                                                 vec(any_with::<ErgoBoxAssetsData>(
+                                                     (BoxValue::MIN_RAW * 1000 .. BoxValue::MIN_RAW * 10000).into()), 1..10),
+                                                 target_balance in
+                                                 any_with::<BoxValue>((BoxValue::MIN_RAW * 100 .. BoxValue::MIN_RAW * 500).into())) {
+            // all inputs are "selected", the first token of the first box is targeted
+            let target_tokens: Vec<Token> = inputs[0].tokens().into_iter().take(1).map(|ts| ts.first().clone()).collect();
+            let change_boxes = calc_change(inputs.as_slice(), target_balance, target_tokens.as_slice()).unwrap();
+            let out_box = ErgoBoxAssetsData {value: target_balance, tokens: BoxTokens::from_vec(target_tokens.clone()).ok()};
+            let mut change_boxes_plus_out = vec![out_box];
+            change_boxes_plus_out.append(&mut change_boxes.clone());
+            prop_assert_eq!(sum_value(inputs.as_slice()),
+                            sum_value(change_boxes_plus_out.as_slice()));
+            prop_assert_eq!(sum_tokens_from_boxes(inputs.as_slice()).unwrap(),
+                            sum_tokens_from_boxes(change_boxes_plus_out.as_slice()).unwrap());
+            let all_value = checked_sum(inputs.iter().map(|b| b.value)).unwrap();
+            prop_assert_eq!(calc_change(inputs.as_slice(), all_value.checked_add(&BoxValue::SAFE_USER_MIN).unwrap(), &[]),
+                            Err(BoxSelectorError::NotEnoughCoins(*BoxValue::SAFE_USER_MIN.as_u64())));
+        }
+
         #[test]
         fn test_select_over_max_tokens(
             inputs in
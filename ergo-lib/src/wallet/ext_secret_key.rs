@@ -89,6 +89,11 @@ impl ExtSecretKey {
         self.private_input.to_bytes()
     }
 
+    /// Chain code
+    pub fn chain_code(&self) -> ChainCode {
+        self.chain_code
+    }
+
     /// Public image associated with the private input
     pub fn public_image(&self) -> ProveDlog {
         self.private_input.public_image()
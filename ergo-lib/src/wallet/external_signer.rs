@@ -0,0 +1,325 @@
+//! Signing with an external signer (e.g. hardware wallet)
+
+#[cfg(feature = "ledger")]
+pub mod ledger;
+
+use async_trait::async_trait;
+use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::serialization::SigmaParsingError;
+use ergotree_ir::serialization::SigmaSerializationError;
+use thiserror::Error;
+
+use crate::chain::ergo_state_context::ErgoStateContext;
+use crate::chain::transaction::input::prover_result::ProverResult;
+use crate::chain::transaction::unsigned::UnsignedTransaction;
+use crate::chain::transaction::Input;
+use crate::chain::transaction::Transaction;
+use crate::chain::transaction::TransactionError;
+
+use super::derivation_path::DerivationPath;
+use super::ext_pub_key::ExtPubKey;
+use super::signing::sign_tx_input;
+use super::signing::TransactionContext;
+use super::signing::TxSigningError;
+use super::tx_context::TransactionContextError;
+use super::Wallet;
+
+/// External signer errors
+#[derive(Error, Debug)]
+pub enum ExternalSignerError {
+    /// Device (or its transport) error
+    #[error("Device error: {0}")]
+    DeviceError(String),
+    /// User rejected the operation on the device
+    #[error("Rejected by user")]
+    Rejected,
+    /// Invalid arguments
+    #[error("Invalid arguments: {0}")]
+    InvalidArgs(String),
+    /// Unexpected device response
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+    /// Serialization error
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SigmaSerializationError),
+    /// Parsing error
+    #[error("Parsing error: {0}")]
+    ParsingError(#[from] SigmaParsingError),
+    /// Transaction context error
+    #[error("Transaction context error: {0}")]
+    TransactionContextError(#[from] TransactionContextError),
+    /// Transaction error
+    #[error("Transaction error: {0}")]
+    TransactionError(#[from] TransactionError),
+    /// Signing of the inputs with the wallet secrets failed
+    #[error("Transaction signing error: {0}")]
+    TxSigningError(#[from] TxSigningError),
+}
+
+/// Input box attested by the external signer (e.g. Ledger returns the box frames signed with the
+/// device session key, which are sent back on the transaction signing)
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct AttestedInput {
+    /// Attested box id
+    pub box_id: BoxId,
+    /// Signer-specific attestation data
+    pub frames: Vec<Vec<u8>>,
+}
+
+/// External signer holding the secrets (e.g. hardware wallet). Only P2PK inputs can be signed.
+#[async_trait(?Send)]
+pub trait ExternalSigner {
+    /// Extended public key for the given derivation path
+    async fn get_ext_pub_key(
+        &self,
+        path: &DerivationPath,
+    ) -> Result<ExtPubKey, ExternalSignerError>;
+
+    /// Attest the input box
+    async fn attest_input(&self, input_box: &ErgoBox)
+        -> Result<AttestedInput, ExternalSignerError>;
+
+    /// Sign the transaction (`attested_inputs` are in the transaction inputs order) with the
+    /// secret key of the given derivation path, returning the proof for the transaction bytes
+    async fn sign_tx(
+        &self,
+        tx: &UnsignedTransaction,
+        attested_inputs: &[AttestedInput],
+        path: &DerivationPath,
+    ) -> Result<ProofBytes, ExternalSignerError>;
+}
+
+/// Sign the transaction with the external signer. `input_paths` has the derivation path of the
+/// signing key for each input, inputs without the path are signed with the `wallet` secrets.
+pub async fn sign_transaction_external<S: ExternalSigner + ?Sized>(
+    signer: &S,
+    wallet: Option<&Wallet>,
+    tx_context: TransactionContext<UnsignedTransaction>,
+    state_context: &ErgoStateContext,
+    input_paths: &[Option<DerivationPath>],
+) -> Result<Transaction, ExternalSignerError> {
+    let tx = tx_context.spending_tx.clone();
+    if input_paths.len() != tx.inputs.len() {
+        return Err(ExternalSignerError::InvalidArgs(format!(
+            "expected {} input paths, got {}",
+            tx.inputs.len(),
+            input_paths.len()
+        )));
+    }
+    let mut attested_inputs = Vec::with_capacity(tx.inputs.len());
+    for (idx, input) in tx.inputs.iter().enumerate() {
+        let input_box = tx_context
+            .get_input_box(&input.box_id)
+            .ok_or(TransactionContextError::InputBoxNotFound(idx))?;
+        attested_inputs.push(signer.attest_input(&input_box).await?);
+    }
+    // the message is the same for all inputs, so one proof per key is enough
+    let mut proofs: Vec<(&DerivationPath, ProofBytes)> = Vec::new();
+    for path in input_paths.iter().flatten() {
+        if !proofs.iter().any(|(p, _)| *p == path) {
+            let proof = signer.sign_tx(&tx, &attested_inputs, path).await?;
+            proofs.push((path, proof));
+        }
+    }
+    let message_to_sign = tx.bytes_to_sign()?;
+    let mut signed_inputs = Vec::with_capacity(tx.inputs.len());
+    for (idx, (unsigned_input, path)) in tx.inputs.iter().zip(input_paths).enumerate() {
+        let input = match path {
+            Some(path) => Input::new(
+                unsigned_input.box_id,
+                ProverResult {
+                    proof: proofs
+                        .iter()
+                        .find(|(p, _)| *p == path)
+                        .map(|(_, proof)| proof.clone())
+                        .unwrap_or(ProofBytes::Empty),
+                    extension: unsigned_input.extension.clone(),
+                },
+            ),
+            None => {
+                let wallet = wallet.ok_or_else(|| {
+                    ExternalSignerError::InvalidArgs(format!(
+                        "no derivation path and no wallet to sign input {}",
+                        idx
+                    ))
+                })?;
+                sign_tx_input(
+                    wallet.prover.as_ref(),
+                    &tx_context,
+                    state_context,
+                    None,
+                    idx,
+                    message_to_sign.as_slice(),
+                )?
+            }
+        };
+        signed_inputs.push(input);
+    }
+    Ok(Transaction::new_from_vec(
+        signed_inputs,
+        tx.data_inputs
+            .map(|data_inputs| data_inputs.to_vec())
+            .unwrap_or_default(),
+        tx.output_candidates.to_vec(),
+    )?)
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+pub(crate) mod tests {
+    use std::rc::Rc;
+
+    use ergotree_interpreter::eval::env::Env;
+    use ergotree_interpreter::sigma_protocol::private_input::PrivateInput;
+    use ergotree_interpreter::sigma_protocol::prover::hint::HintsBag;
+    use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+    use ergotree_interpreter::sigma_protocol::prover::Prover;
+    use ergotree_interpreter::sigma_protocol::prover::TestProver;
+    use ergotree_interpreter::sigma_protocol::verifier::TestVerifier;
+    use ergotree_interpreter::sigma_protocol::verifier::Verifier;
+    use ergotree_ir::chain::address::Address;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::tx_id::TxId;
+    use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::transaction::UnsignedInput;
+    use crate::wallet::ext_secret_key::ExtSecretKey;
+    use crate::wallet::mnemonic::Mnemonic;
+    use crate::wallet::secret_key::SecretKey;
+    use crate::wallet::signing::make_context;
+
+    use super::*;
+
+    /// Signer deriving the keys from the master key in memory
+    pub(crate) struct InMemorySigner(pub(crate) ExtSecretKey);
+
+    #[async_trait(?Send)]
+    impl ExternalSigner for InMemorySigner {
+        async fn get_ext_pub_key(
+            &self,
+            path: &DerivationPath,
+        ) -> Result<ExtPubKey, ExternalSignerError> {
+            self.0
+                .derive(path.clone())
+                .and_then(|k| k.public_key())
+                .map_err(|e| ExternalSignerError::DeviceError(e.to_string()))
+        }
+
+        async fn attest_input(
+            &self,
+            input_box: &ErgoBox,
+        ) -> Result<AttestedInput, ExternalSignerError> {
+            Ok(AttestedInput {
+                box_id: input_box.box_id(),
+                frames: vec![],
+            })
+        }
+
+        async fn sign_tx(
+            &self,
+            tx: &UnsignedTransaction,
+            _attested_inputs: &[AttestedInput],
+            path: &DerivationPath,
+        ) -> Result<ProofBytes, ExternalSignerError> {
+            let key = self
+                .0
+                .derive(path.clone())
+                .map_err(|e| ExternalSignerError::DeviceError(e.to_string()))?;
+            let prover = TestProver {
+                secrets: vec![PrivateInput::from(key.secret_key())],
+            };
+            prover
+                .generate_proof(
+                    SigmaBoolean::from(key.public_image()),
+                    &tx.bytes_to_sign()?,
+                    &HintsBag::empty(),
+                )
+                .map_err(|e| ExternalSignerError::DeviceError(e.to_string()))
+        }
+    }
+
+    fn p2pk_box(secret: &SecretKey) -> ErgoBox {
+        ErgoBox::new(
+            BoxValue::SAFE_USER_MIN,
+            secret.get_address_from_public_image().script().unwrap(),
+            None,
+            NonMandatoryRegisters::empty(),
+            0,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sign_with_external_signer_and_wallet() {
+        let master = ExtSecretKey::derive_master(Mnemonic::to_seed("external signer", "")).unwrap();
+        let path = DerivationPath::master_path();
+        let signer = InMemorySigner(master.clone());
+        let local_secret = SecretKey::random_dlog();
+        let wallet = Wallet::from_secrets(vec![local_secret.clone()]);
+        let device_box = p2pk_box(&master.secret_key());
+        let local_box = p2pk_box(&local_secret);
+        let boxes = vec![device_box.clone(), local_box.clone()];
+        let tx = UnsignedTransaction::new_from_vec(
+            boxes
+                .iter()
+                .map(|b| UnsignedInput::new(b.box_id(), ContextExtension::empty()))
+                .collect(),
+            vec![],
+            vec![ErgoBoxCandidate {
+                value: BoxValue::SAFE_USER_MIN,
+                ergo_tree: Address::P2Pk(master.public_image()).script().unwrap(),
+                tokens: None,
+                additional_registers: NonMandatoryRegisters::empty(),
+                creation_height: 0,
+            }],
+        )
+        .unwrap();
+        let tx_context = TransactionContext::new(tx, boxes, vec![]).unwrap();
+        let state_context = force_any_val::<ErgoStateContext>();
+        let signed_tx = futures::executor::block_on(sign_transaction_external(
+            &signer,
+            Some(&wallet),
+            tx_context.clone(),
+            &state_context,
+            &[Some(path.clone()), None],
+        ))
+        .unwrap();
+        let message = signed_tx.bytes_to_sign().unwrap();
+        for (idx, b) in [device_box, local_box].iter().enumerate() {
+            let ctx = Rc::new(make_context(&state_context, &tx_context, idx).unwrap());
+            let res = TestVerifier
+                .verify(
+                    &b.ergo_tree,
+                    &Env::empty(),
+                    ctx,
+                    signed_tx
+                        .inputs
+                        .get(idx)
+                        .unwrap()
+                        .spending_proof
+                        .proof
+                        .clone(),
+                    &message,
+                )
+                .unwrap();
+            assert!(res.result);
+        }
+        // no wallet for the input without the path
+        assert!(futures::executor::block_on(sign_transaction_external(
+            &signer,
+            None,
+            tx_context,
+            &state_context,
+            &[Some(path), None],
+        ))
+        .is_err());
+    }
+}
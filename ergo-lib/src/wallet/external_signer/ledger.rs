@@ -0,0 +1,604 @@
+//! [`ExternalSigner`] implementation for the Ergo Ledger app (APDU protocol), the transport
+//! (USB HID, BLE, WebUSB, etc.) is provided via [`LedgerTransport`]
+
+use std::cell::RefCell;
+
+use async_trait::async_trait;
+use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+use ergotree_interpreter::sigma_protocol::SOUNDNESS_BYTES;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::serialization::SigmaSerializable;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+
+use crate::chain::transaction::distinct_token_ids;
+use crate::chain::transaction::unsigned::UnsignedTransaction;
+use crate::wallet::derivation_path::DerivationPath;
+use crate::wallet::ext_pub_key::ExtPubKey;
+use crate::wallet::miner_fee::MINERS_FEE_BASE16_BYTES;
+
+use super::AttestedInput;
+use super::ExternalSigner;
+use super::ExternalSignerError;
+
+/// APDU class of the Ergo app
+pub const CLA: u8 = 0xE0;
+
+const INS_GET_EXTENDED_PUBLIC_KEY: u8 = 0x10;
+const INS_ATTEST_INPUT_BOX: u8 = 0x20;
+const INS_SIGN_TX: u8 = 0x21;
+
+const P1_ATTEST_BOX_START: u8 = 0x01;
+const P1_ATTEST_ADD_ERGO_TREE_CHUNK: u8 = 0x02;
+const P1_ATTEST_ADD_TOKENS: u8 = 0x03;
+const P1_ATTEST_ADD_REGISTERS_CHUNK: u8 = 0x04;
+const P1_ATTEST_GET_BOX_FRAME: u8 = 0x05;
+
+const P1_SIGN_START_SIGNING: u8 = 0x01;
+const P1_SIGN_START_TX: u8 = 0x10;
+const P1_SIGN_ADD_TOKEN_IDS: u8 = 0x11;
+const P1_SIGN_ADD_INPUT_BOX_FRAME: u8 = 0x12;
+const P1_SIGN_ADD_INPUT_BOX_CONTEXT_EXTENSION_CHUNK: u8 = 0x13;
+const P1_SIGN_ADD_DATA_INPUTS: u8 = 0x14;
+const P1_SIGN_ADD_OUTPUT_BOX_START: u8 = 0x15;
+const P1_SIGN_ADD_OUTPUT_BOX_ERGO_TREE_CHUNK: u8 = 0x16;
+const P1_SIGN_ADD_OUTPUT_BOX_MINERS_FEE_TREE: u8 = 0x17;
+const P1_SIGN_ADD_OUTPUT_BOX_TOKENS: u8 = 0x19;
+const P1_SIGN_ADD_OUTPUT_BOX_REGISTERS_CHUNK: u8 = 0x1A;
+const P1_SIGN_CONFIRM_AND_SIGN: u8 = 0x20;
+
+const P_WITHOUT_TOKEN: u8 = 0x01;
+const P_WITH_TOKEN: u8 = 0x02;
+
+const SW_OK: u16 = 0x9000;
+const SW_DENIED: u16 = 0x6985;
+
+const MAX_DATA_LEN: usize = 255;
+const BOX_ID_LEN: usize = 32;
+const MAX_IDS_PER_CHUNK: usize = MAX_DATA_LEN / BOX_ID_LEN;
+// token id + amount (u64)
+const MAX_TOKENS_PER_CHUNK: usize = MAX_DATA_LEN / (BOX_ID_LEN + 8);
+// token index (u32) + amount (u64)
+const MAX_OUTPUT_TOKENS_PER_CHUNK: usize = MAX_DATA_LEN / (4 + 8);
+// P2PK proof: challenge and the response
+const SIGNATURE_LEN: usize = SOUNDNESS_BYTES + 32;
+
+/// Transport to the Ledger device
+#[async_trait(?Send)]
+pub trait LedgerTransport {
+    /// Send the APDU command and return the response (data followed by the 2-byte status word)
+    async fn exchange(&self, apdu: Vec<u8>) -> Result<Vec<u8>, String>;
+}
+
+/// Ledger device running the Ergo app
+pub struct LedgerSigner<T: LedgerTransport> {
+    transport: T,
+    auth_token: Option<u32>,
+    // exchanges with the device must not interleave
+    busy: RefCell<()>,
+}
+
+impl<T: LedgerTransport> LedgerSigner<T> {
+    /// Ledger signer using the given transport
+    pub fn new(transport: T) -> Self {
+        LedgerSigner {
+            transport,
+            auth_token: None,
+            busy: RefCell::new(()),
+        }
+    }
+
+    /// Use the auth token so that the device doesn't ask to confirm each operation of the session
+    pub fn with_auth_token(self, auth_token: u32) -> Self {
+        LedgerSigner {
+            auth_token: Some(auth_token),
+            ..self
+        }
+    }
+
+    fn token_p(&self) -> u8 {
+        if self.auth_token.is_some() {
+            P_WITH_TOKEN
+        } else {
+            P_WITHOUT_TOKEN
+        }
+    }
+
+    fn with_token(&self, mut data: Vec<u8>) -> Vec<u8> {
+        if let Some(auth_token) = self.auth_token {
+            data.extend_from_slice(&auth_token.to_be_bytes());
+        }
+        data
+    }
+
+    async fn send(
+        &self,
+        ins: u8,
+        p1: u8,
+        p2: u8,
+        data: &[u8],
+    ) -> Result<Vec<u8>, ExternalSignerError> {
+        if data.len() > MAX_DATA_LEN {
+            return Err(ExternalSignerError::InvalidArgs(format!(
+                "APDU data is too long: {}",
+                data.len()
+            )));
+        }
+        let mut apdu = vec![CLA, ins, p1, p2, data.len() as u8];
+        apdu.extend_from_slice(data);
+        let mut response = self
+            .transport
+            .exchange(apdu)
+            .await
+            .map_err(ExternalSignerError::DeviceError)?;
+        if response.len() < 2 {
+            return Err(ExternalSignerError::InvalidResponse(format!(
+                "response is too short: {}",
+                response.len()
+            )));
+        }
+        let sw_bytes = response.split_off(response.len() - 2);
+        match u16::from_be_bytes([sw_bytes[0], sw_bytes[1]]) {
+            SW_OK => Ok(response),
+            SW_DENIED => Err(ExternalSignerError::Rejected),
+            sw => Err(ExternalSignerError::DeviceError(format!(
+                "status word {:#06x}",
+                sw
+            ))),
+        }
+    }
+
+    async fn send_chunks(
+        &self,
+        ins: u8,
+        p1: u8,
+        p2: u8,
+        data: &[u8],
+        chunk_len: usize,
+    ) -> Result<Option<Vec<u8>>, ExternalSignerError> {
+        let mut last_response = None;
+        for chunk in data.chunks(chunk_len) {
+            last_response = Some(self.send(ins, p1, p2, chunk).await?);
+        }
+        Ok(last_response)
+    }
+
+    async fn attest_input_inner(
+        &self,
+        input_box: &ErgoBox,
+    ) -> Result<AttestedInput, ExternalSignerError> {
+        let tree_bytes = input_box.ergo_tree.sigma_serialize_bytes()?;
+        let registers_bytes = registers_bytes(&input_box.additional_registers)?;
+        let tokens: Vec<Token> = input_box.tokens.iter().flatten().cloned().collect();
+        let mut header = Vec::new();
+        header.extend_from_slice(input_box.transaction_id.as_ref());
+        header.extend_from_slice(&input_box.index.to_be_bytes());
+        header.extend_from_slice(&input_box.value.as_u64().to_be_bytes());
+        header.extend_from_slice(&(tree_bytes.len() as u32).to_be_bytes());
+        header.extend_from_slice(&input_box.creation_height.to_be_bytes());
+        header.push(tokens.len() as u8);
+        header.extend_from_slice(&(registers_bytes.len() as u32).to_be_bytes());
+        let session_id = first_byte(
+            self.send(
+                INS_ATTEST_INPUT_BOX,
+                P1_ATTEST_BOX_START,
+                self.token_p(),
+                &self.with_token(header),
+            )
+            .await?,
+        )?;
+        let mut last_response = self
+            .send_chunks(
+                INS_ATTEST_INPUT_BOX,
+                P1_ATTEST_ADD_ERGO_TREE_CHUNK,
+                session_id,
+                &tree_bytes,
+                MAX_DATA_LEN,
+            )
+            .await?;
+        for chunk in tokens.chunks(MAX_TOKENS_PER_CHUNK) {
+            let mut data = Vec::new();
+            for t in chunk {
+                data.extend_from_slice(t.token_id.as_ref());
+                data.extend_from_slice(&t.amount.as_u64().to_be_bytes());
+            }
+            last_response = Some(
+                self.send(
+                    INS_ATTEST_INPUT_BOX,
+                    P1_ATTEST_ADD_TOKENS,
+                    session_id,
+                    &data,
+                )
+                .await?,
+            );
+        }
+        if let Some(r) = self
+            .send_chunks(
+                INS_ATTEST_INPUT_BOX,
+                P1_ATTEST_ADD_REGISTERS_CHUNK,
+                session_id,
+                &registers_bytes,
+                MAX_DATA_LEN,
+            )
+            .await?
+        {
+            last_response = Some(r);
+        }
+        // the last chunk response contains the number of the attested box frames
+        let frames_count = first_byte(last_response.unwrap_or_default())?;
+        let mut frames = Vec::with_capacity(frames_count as usize);
+        for frame_idx in 0..frames_count {
+            frames.push(
+                self.send(
+                    INS_ATTEST_INPUT_BOX,
+                    P1_ATTEST_GET_BOX_FRAME,
+                    session_id,
+                    &[frame_idx],
+                )
+                .await?,
+            );
+        }
+        Ok(AttestedInput {
+            box_id: input_box.box_id(),
+            frames,
+        })
+    }
+
+    async fn sign_tx_inner(
+        &self,
+        tx: &UnsignedTransaction,
+        attested_inputs: &[AttestedInput],
+        path: &DerivationPath,
+    ) -> Result<ProofBytes, ExternalSignerError> {
+        if attested_inputs.len() != tx.inputs.len()
+            || tx
+                .inputs
+                .iter()
+                .zip(attested_inputs)
+                .any(|(i, a)| i.box_id != a.box_id)
+        {
+            return Err(ExternalSignerError::InvalidArgs(
+                "attested inputs do not match transaction inputs".to_string(),
+            ));
+        }
+        let session_id = first_byte(
+            self.send(
+                INS_SIGN_TX,
+                P1_SIGN_START_SIGNING,
+                self.token_p(),
+                &self.with_token(path.ledger_bytes()),
+            )
+            .await?,
+        )?;
+        let token_ids = distinct_token_ids(tx.output_candidates.clone());
+        let data_inputs: Vec<BoxId> = tx
+            .data_inputs
+            .as_ref()
+            .map(|d| d.iter().map(|d| d.box_id).collect())
+            .unwrap_or_default();
+        let mut start = Vec::new();
+        start.extend_from_slice(&(tx.inputs.len() as u16).to_be_bytes());
+        start.extend_from_slice(&(data_inputs.len() as u16).to_be_bytes());
+        start.push(token_ids.len() as u8);
+        start.extend_from_slice(&(tx.output_candidates.len() as u16).to_be_bytes());
+        self.send(INS_SIGN_TX, P1_SIGN_START_TX, session_id, &start)
+            .await?;
+        let token_ids_bytes: Vec<u8> = token_ids
+            .iter()
+            .flat_map(|id| id.as_ref().to_vec())
+            .collect();
+        self.send_chunks(
+            INS_SIGN_TX,
+            P1_SIGN_ADD_TOKEN_IDS,
+            session_id,
+            &token_ids_bytes,
+            MAX_IDS_PER_CHUNK * BOX_ID_LEN,
+        )
+        .await?;
+        for (input, attested) in tx.inputs.iter().zip(attested_inputs) {
+            for frame in &attested.frames {
+                self.send(INS_SIGN_TX, P1_SIGN_ADD_INPUT_BOX_FRAME, session_id, frame)
+                    .await?;
+            }
+            if !input.extension.values.is_empty() {
+                self.send_chunks(
+                    INS_SIGN_TX,
+                    P1_SIGN_ADD_INPUT_BOX_CONTEXT_EXTENSION_CHUNK,
+                    session_id,
+                    &input.extension.sigma_serialize_bytes()?,
+                    MAX_DATA_LEN,
+                )
+                .await?;
+            }
+        }
+        let data_inputs_bytes: Vec<u8> = data_inputs
+            .iter()
+            .flat_map(|id| id.as_ref().to_vec())
+            .collect();
+        self.send_chunks(
+            INS_SIGN_TX,
+            P1_SIGN_ADD_DATA_INPUTS,
+            session_id,
+            &data_inputs_bytes,
+            MAX_IDS_PER_CHUNK * BOX_ID_LEN,
+        )
+        .await?;
+        #[allow(clippy::unwrap_used)]
+        let miners_fee_tree = decode_base16(MINERS_FEE_BASE16_BYTES, DecodingMode::Strict).unwrap();
+        for output in tx.output_candidates.iter() {
+            let tree_bytes = output.ergo_tree.sigma_serialize_bytes()?;
+            let registers_bytes = registers_bytes(&output.additional_registers)?;
+            let tokens: Vec<&Token> = output.tokens.iter().flatten().collect();
+            let mut header = Vec::new();
+            header.extend_from_slice(&output.value.as_u64().to_be_bytes());
+            header.extend_from_slice(&(tree_bytes.len() as u32).to_be_bytes());
+            header.extend_from_slice(&output.creation_height.to_be_bytes());
+            header.push(tokens.len() as u8);
+            header.extend_from_slice(&(registers_bytes.len() as u32).to_be_bytes());
+            self.send(
+                INS_SIGN_TX,
+                P1_SIGN_ADD_OUTPUT_BOX_START,
+                session_id,
+                &header,
+            )
+            .await?;
+            if tree_bytes == miners_fee_tree {
+                self.send(
+                    INS_SIGN_TX,
+                    P1_SIGN_ADD_OUTPUT_BOX_MINERS_FEE_TREE,
+                    session_id,
+                    &[],
+                )
+                .await?;
+            } else {
+                self.send_chunks(
+                    INS_SIGN_TX,
+                    P1_SIGN_ADD_OUTPUT_BOX_ERGO_TREE_CHUNK,
+                    session_id,
+                    &tree_bytes,
+                    MAX_DATA_LEN,
+                )
+                .await?;
+            }
+            for chunk in tokens.chunks(MAX_OUTPUT_TOKENS_PER_CHUNK) {
+                let mut data = Vec::new();
+                for t in chunk {
+                    let idx = token_ids.get_index_of(&t.token_id).ok_or_else(|| {
+                        ExternalSignerError::InvalidArgs(format!(
+                            "token {} is not in the distinct token ids",
+                            t.token_id
+                        ))
+                    })?;
+                    data.extend_from_slice(&(idx as u32).to_be_bytes());
+                    data.extend_from_slice(&t.amount.as_u64().to_be_bytes());
+                }
+                self.send(
+                    INS_SIGN_TX,
+                    P1_SIGN_ADD_OUTPUT_BOX_TOKENS,
+                    session_id,
+                    &data,
+                )
+                .await?;
+            }
+            self.send_chunks(
+                INS_SIGN_TX,
+                P1_SIGN_ADD_OUTPUT_BOX_REGISTERS_CHUNK,
+                session_id,
+                &registers_bytes,
+                MAX_DATA_LEN,
+            )
+            .await?;
+        }
+        let signature = self
+            .send(INS_SIGN_TX, P1_SIGN_CONFIRM_AND_SIGN, session_id, &[])
+            .await?;
+        if signature.len() != SIGNATURE_LEN {
+            return Err(ExternalSignerError::InvalidResponse(format!(
+                "expected {} bytes signature, got {}",
+                SIGNATURE_LEN,
+                signature.len()
+            )));
+        }
+        Ok(ProofBytes::Some(signature))
+    }
+}
+
+// the `busy` guard is held across the device exchange on purpose: a concurrent call fails with
+// `DeviceError` instead of interleaving the APDUs
+#[allow(clippy::await_holding_refcell_ref)]
+#[async_trait(?Send)]
+impl<T: LedgerTransport> ExternalSigner for LedgerSigner<T> {
+    async fn get_ext_pub_key(
+        &self,
+        path: &DerivationPath,
+    ) -> Result<ExtPubKey, ExternalSignerError> {
+        let _guard = self.lock()?;
+        let response = self
+            .send(
+                INS_GET_EXTENDED_PUBLIC_KEY,
+                self.token_p(),
+                0x00,
+                &self.with_token(path.ledger_bytes()),
+            )
+            .await?;
+        // compressed public key followed by the chain code
+        if response.len() != 33 + 32 {
+            return Err(ExternalSignerError::InvalidResponse(format!(
+                "expected 65 bytes extended public key, got {}",
+                response.len()
+            )));
+        }
+        let mut pub_key = [0u8; 33];
+        let mut chain_code = [0u8; 32];
+        pub_key.copy_from_slice(&response[..33]);
+        chain_code.copy_from_slice(&response[33..]);
+        Ok(ExtPubKey::new(pub_key, chain_code, path.clone())?)
+    }
+
+    async fn attest_input(
+        &self,
+        input_box: &ErgoBox,
+    ) -> Result<AttestedInput, ExternalSignerError> {
+        let _guard = self.lock()?;
+        self.attest_input_inner(input_box).await
+    }
+
+    async fn sign_tx(
+        &self,
+        tx: &UnsignedTransaction,
+        attested_inputs: &[AttestedInput],
+        path: &DerivationPath,
+    ) -> Result<ProofBytes, ExternalSignerError> {
+        let _guard = self.lock()?;
+        self.sign_tx_inner(tx, attested_inputs, path).await
+    }
+}
+
+impl<T: LedgerTransport> LedgerSigner<T> {
+    fn lock(&self) -> Result<std::cell::RefMut<'_, ()>, ExternalSignerError> {
+        self.busy.try_borrow_mut().map_err(|_| {
+            ExternalSignerError::DeviceError("device is busy with another operation".to_string())
+        })
+    }
+}
+
+fn registers_bytes(registers: &NonMandatoryRegisters) -> Result<Vec<u8>, ExternalSignerError> {
+    Ok(if registers.is_empty() {
+        vec![]
+    } else {
+        registers.sigma_serialize_bytes()?
+    })
+}
+
+fn first_byte(response: Vec<u8>) -> Result<u8, ExternalSignerError> {
+    response
+        .first()
+        .copied()
+        .ok_or_else(|| ExternalSignerError::InvalidResponse("empty response".to_string()))
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::tx_id::TxId;
+    use sigma_test_util::force_any_val;
+
+    use crate::wallet::ext_secret_key::ExtSecretKey;
+    use crate::wallet::mnemonic::Mnemonic;
+
+    use super::*;
+
+    /// Records the commands and replies with the scripted responses
+    #[derive(Default)]
+    struct MockTransport {
+        commands: RefCell<Vec<Vec<u8>>>,
+        responses: RefCell<VecDeque<Vec<u8>>>,
+    }
+
+    impl MockTransport {
+        fn reply(&self, mut data: Vec<u8>, sw: u16) {
+            data.extend_from_slice(&sw.to_be_bytes());
+            self.responses.borrow_mut().push_back(data);
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl LedgerTransport for &MockTransport {
+        async fn exchange(&self, apdu: Vec<u8>) -> Result<Vec<u8>, String> {
+            self.commands.borrow_mut().push(apdu);
+            self.responses
+                .borrow_mut()
+                .pop_front()
+                .ok_or_else(|| "no response".to_string())
+        }
+    }
+
+    fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        futures::executor::block_on(f)
+    }
+
+    #[test]
+    fn get_ext_pub_key() {
+        let master = ExtSecretKey::derive_master(Mnemonic::to_seed("ledger", "")).unwrap();
+        let expected = master.public_key().unwrap();
+        let path = DerivationPath::master_path();
+        let transport = MockTransport::default();
+        let mut response = expected.pub_key_bytes().to_vec();
+        response.extend_from_slice(&expected.chain_code());
+        transport.reply(response, SW_OK);
+        let signer = LedgerSigner::new(&transport).with_auth_token(7);
+        let ext_pub_key = block_on(signer.get_ext_pub_key(&path)).unwrap();
+        assert_eq!(ext_pub_key, expected);
+        let mut expected_apdu = vec![
+            CLA,
+            INS_GET_EXTENDED_PUBLIC_KEY,
+            P_WITH_TOKEN,
+            0x00,
+            path.ledger_bytes().len() as u8 + 4,
+        ];
+        expected_apdu.extend(path.ledger_bytes());
+        expected_apdu.extend_from_slice(&7u32.to_be_bytes());
+        assert_eq!(transport.commands.borrow()[0], expected_apdu);
+    }
+
+    #[test]
+    fn rejected_by_user() {
+        let transport = MockTransport::default();
+        transport.reply(vec![], SW_DENIED);
+        let signer = LedgerSigner::new(&transport);
+        assert!(matches!(
+            block_on(signer.get_ext_pub_key(&DerivationPath::master_path())),
+            Err(ExternalSignerError::Rejected)
+        ));
+    }
+
+    #[test]
+    fn attest_input() {
+        let input_box = ErgoBox::new(
+            BoxValue::SAFE_USER_MIN,
+            force_any_val::<ErgoBox>().ergo_tree,
+            None,
+            NonMandatoryRegisters::empty(),
+            1,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let tree_len = input_box.ergo_tree.sigma_serialize_bytes().unwrap().len();
+        let chunks = (tree_len + MAX_DATA_LEN - 1) / MAX_DATA_LEN;
+        let transport = MockTransport::default();
+        // session id
+        transport.reply(vec![5], SW_OK);
+        for _ in 0..chunks - 1 {
+            transport.reply(vec![], SW_OK);
+        }
+        // frames count
+        transport.reply(vec![2], SW_OK);
+        transport.reply(vec![1, 2, 3], SW_OK);
+        transport.reply(vec![4, 5, 6], SW_OK);
+        let signer = LedgerSigner::new(&transport);
+        let attested = block_on(signer.attest_input(&input_box)).unwrap();
+        assert_eq!(attested.box_id, input_box.box_id());
+        assert_eq!(attested.frames, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let commands = transport.commands.borrow();
+        assert_eq!(commands.len(), 1 + chunks + 2);
+        assert_eq!(
+            &commands[0][..4],
+            &[
+                CLA,
+                INS_ATTEST_INPUT_BOX,
+                P1_ATTEST_BOX_START,
+                P_WITHOUT_TOKEN
+            ]
+        );
+        // the subsequent commands are in the session
+        assert!(commands[1..].iter().all(|c| c[3] == 5));
+    }
+}
@@ -0,0 +1,340 @@
+//! Wallet transaction history (incoming, outgoing and self-transfer entries with the token
+//! deltas and the fees paid) derived while scanning the blocks
+
+use ergo_chain_types::BlockId;
+use ergo_chain_types::Header;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::chain::tx_id::TxId;
+
+use crate::chain::block::FullBlock;
+use crate::chain::transaction::Transaction;
+
+use super::miner_fee::MINERS_FEE_ERGO_TREE;
+use super::scanner::WalletScanner;
+use super::scanner::WalletScannerError;
+
+/// Direction of the transaction relative to the wallet
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum HistoryEntryKind {
+    /// Wallet receives the assets without spending its boxes
+    Incoming,
+    /// Wallet spends its boxes and sends the assets to the other addresses
+    Outgoing,
+    /// Wallet spends its boxes and all the outputs (except the miner fee) are its own
+    SelfTransfer,
+}
+
+/// Transaction in the wallet history
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct HistoryEntry {
+    /// Transaction id
+    pub tx_id: TxId,
+    /// Block the transaction is included in
+    pub block_id: BlockId,
+    /// Height of the block
+    pub height: u32,
+    /// Timestamp of the block (ms)
+    pub timestamp: u64,
+    /// Direction of the transaction
+    pub kind: HistoryEntryKind,
+    /// Change of the wallet ERG balance (nanoERGs), including the paid fee
+    pub erg_delta: i128,
+    /// Changes of the wallet token balances (non-zero only), in the order of the first
+    /// appearance in the transaction inputs (then outputs)
+    pub token_deltas: Vec<(TokenId, i128)>,
+    /// Miner fee paid by the wallet (nanoERGs). Only counted when all the transaction inputs
+    /// belong to the wallet, otherwise the fee share can not be told apart from the other
+    /// outgoing value and it is only included in `erg_delta`.
+    pub fee: u64,
+}
+
+/// Wallet history ledger, built by applying the blocks in the chain order on top of the
+/// [`WalletScanner`] tracking the wallet boxes (the wallet scripts are tracked on the scanner).
+/// Entries of the rolled back blocks are removed.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct WalletHistory {
+    scanner: WalletScanner,
+    entries: Vec<HistoryEntry>,
+}
+
+impl WalletHistory {
+    /// History on top of the scanner tracking the wallet scripts. Boxes spent before the
+    /// scanner state (or pruned from it) are not known to belong to the wallet.
+    pub fn new(scanner: WalletScanner) -> Self {
+        WalletHistory {
+            scanner,
+            entries: Vec::new(),
+        }
+    }
+
+    /// The underlying scanner
+    pub fn scanner(&self) -> &WalletScanner {
+        &self.scanner
+    }
+
+    /// The underlying scanner, e.g. to track more scripts
+    pub fn scanner_mut(&mut self) -> &mut WalletScanner {
+        &mut self.scanner
+    }
+
+    /// History entries in the chain order
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Apply the full block, see [`WalletHistory::apply_block_transactions`]
+    pub fn apply_block(
+        &mut self,
+        block: &FullBlock,
+    ) -> Result<&[HistoryEntry], WalletScannerError> {
+        self.apply_block_transactions(
+            &block.header,
+            block.block_transactions.transactions.as_slice(),
+        )
+    }
+
+    /// Apply the block transactions to the scanner and add the history entries of the
+    /// transactions touching the wallet boxes. Returns the added entries.
+    pub fn apply_block_transactions(
+        &mut self,
+        header: &Header,
+        transactions: &[Transaction],
+    ) -> Result<&[HistoryEntry], WalletScannerError> {
+        self.scanner
+            .apply_block_transactions(header, transactions)?;
+        let first_new = self.entries.len();
+        for tx in transactions {
+            if let Some(entry) = self.entry(header, tx) {
+                self.entries.push(entry);
+            }
+        }
+        Ok(&self.entries[first_new..])
+    }
+
+    fn entry(&self, header: &Header, tx: &Transaction) -> Option<HistoryEntry> {
+        let owned_inputs: Vec<&ErgoBox> = tx
+            .inputs
+            .iter()
+            .filter_map(|input| self.scanner.get(&input.box_id))
+            .map(|tracked| &tracked.ergo_box)
+            .collect();
+        let owned_outputs: Vec<&ErgoBox> = tx
+            .outputs
+            .iter()
+            .filter(|b| self.scanner.is_tracked_script(&b.ergo_tree))
+            .collect();
+        if owned_inputs.is_empty() && owned_outputs.is_empty() {
+            return None;
+        }
+        let mut erg_delta: i128 = 0;
+        let mut token_deltas: Vec<(TokenId, i128)> = Vec::new();
+        let signed_boxes = owned_inputs
+            .iter()
+            .map(|b| (*b, -1))
+            .chain(owned_outputs.iter().map(|b| (*b, 1)));
+        for (b, sign) in signed_boxes {
+            erg_delta += sign * *b.value.as_u64() as i128;
+            for t in b.tokens.iter().flatten() {
+                let amount = sign * *t.amount.as_u64() as i128;
+                match token_deltas.iter_mut().find(|(id, _)| *id == t.token_id) {
+                    Some((_, delta)) => *delta += amount,
+                    None => token_deltas.push((t.token_id, amount)),
+                }
+            }
+        }
+        token_deltas.retain(|(_, delta)| *delta != 0);
+        let is_fee = |b: &ErgoBox| b.ergo_tree == *MINERS_FEE_ERGO_TREE;
+        let kind = if owned_inputs.is_empty() {
+            HistoryEntryKind::Incoming
+        } else if tx
+            .outputs
+            .iter()
+            .all(|b| is_fee(b) || self.scanner.is_tracked_script(&b.ergo_tree))
+        {
+            HistoryEntryKind::SelfTransfer
+        } else {
+            HistoryEntryKind::Outgoing
+        };
+        let fee = if owned_inputs.len() == tx.inputs.len() {
+            tx.outputs
+                .iter()
+                .filter(|b| is_fee(b))
+                .map(|b| *b.value.as_u64())
+                .sum()
+        } else {
+            0
+        };
+        Some(HistoryEntry {
+            tx_id: tx.id(),
+            block_id: header.id,
+            height: header.height,
+            timestamp: header.timestamp,
+            kind,
+            erg_delta,
+            token_deltas,
+            fee,
+        })
+    }
+
+    /// Undo the last applied block, removing its entries. Returns the id of the removed block.
+    pub fn rollback_last(&mut self) -> Option<BlockId> {
+        let block_id = self.scanner.rollback_last()?;
+        self.entries.retain(|e| e.block_id != block_id);
+        Some(block_id)
+    }
+
+    /// Undo the blocks applied after the given one, removing their entries. Returns the ids of
+    /// the removed blocks, starting from the old tip.
+    pub fn rollback_to(&mut self, block_id: &BlockId) -> Result<Vec<BlockId>, WalletScannerError> {
+        let removed = self.scanner.rollback_to(block_id)?;
+        self.entries.retain(|e| !removed.contains(&e.block_id));
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergo_chain_types::Digest32;
+    use ergotree_interpreter::sigma_protocol::private_input::DlogProverInput;
+    use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+    use ergotree_ir::chain::address::Address;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::BoxId;
+    use ergotree_ir::chain::ergo_box::BoxTokens;
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::token::Token;
+    use ergotree_ir::chain::token::TokenAmount;
+    use ergotree_ir::ergo_tree::ErgoTree;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::transaction::input::UnsignedInput;
+
+    use super::*;
+
+    fn header(height: u32, parent: &Header) -> Header {
+        Header {
+            id: BlockId(Digest32::from([height as u8; 32])),
+            parent_id: parent.id,
+            height,
+            ..parent.clone()
+        }
+    }
+
+    fn output(tree: &ErgoTree, value: u64, tokens: Vec<Token>) -> ErgoBoxCandidate {
+        ErgoBoxCandidate {
+            value: BoxValue::try_from(value).unwrap(),
+            ergo_tree: tree.clone(),
+            tokens: BoxTokens::from_vec(tokens).ok(),
+            additional_registers: NonMandatoryRegisters::empty(),
+            creation_height: 0,
+        }
+    }
+
+    fn tx(inputs: &[BoxId], outputs: Vec<ErgoBoxCandidate>) -> Transaction {
+        let inputs = inputs
+            .iter()
+            .map(|id| UnsignedInput::new(*id, ContextExtension::empty()).input_to_sign())
+            .collect();
+        Transaction::new_from_vec(inputs, vec![], outputs).unwrap()
+    }
+
+    #[test]
+    fn test_history_and_rollback() {
+        let mine = Address::P2Pk(force_any_val::<DlogProverInput>().public_image());
+        let other = Address::P2Pk(force_any_val::<DlogProverInput>().public_image())
+            .script()
+            .unwrap();
+        let mut scanner = WalletScanner::new(10);
+        scanner.track_address(&mine).unwrap();
+        let mine = mine.script().unwrap();
+        let mut history = WalletHistory::new(scanner);
+        let token_id = force_any_val::<TokenId>();
+        let token = |amount: u64| Token {
+            token_id,
+            amount: TokenAmount::try_from(amount).unwrap(),
+        };
+
+        let genesis = force_any_val::<Header>();
+        let h1 = header(1, &genesis);
+        let tx1 = tx(
+            &[force_any_val::<BoxId>()],
+            vec![
+                output(&mine, 5_000_000, vec![token(10)]),
+                output(&other, 1_000_000, vec![]),
+            ],
+        );
+        let received = tx1.outputs.first().box_id();
+        let entries = history.apply_block_transactions(&h1, &[tx1]).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kind, HistoryEntryKind::Incoming);
+        assert_eq!(entries[0].erg_delta, 5_000_000);
+        assert_eq!(entries[0].token_deltas, vec![(token_id, 10)]);
+        assert_eq!(entries[0].fee, 0);
+
+        // outgoing and a self-transfer of its change in the same block
+        let h2 = header(2, &h1);
+        let tx2 = tx(
+            &[received],
+            vec![
+                output(&other, 2_000_000, vec![token(4)]),
+                output(&mine, 1_900_000, vec![token(6)]),
+                output(&MINERS_FEE_ERGO_TREE, 1_100_000, vec![]),
+            ],
+        );
+        let change = tx2.outputs.as_vec()[1].box_id();
+        let tx3 = tx(
+            &[change],
+            vec![
+                output(&mine, 800_000, vec![token(6)]),
+                output(&MINERS_FEE_ERGO_TREE, 1_100_000, vec![]),
+            ],
+        );
+        let unrelated = tx(
+            &[force_any_val::<BoxId>()],
+            vec![output(&other, 1_000_000, vec![])],
+        );
+        let (tx2_id, tx3_id) = (tx2.id(), tx3.id());
+        let entries = history
+            .apply_block_transactions(&h2, &[tx2, unrelated, tx3])
+            .unwrap()
+            .to_vec();
+        assert_eq!(
+            entries,
+            vec![
+                HistoryEntry {
+                    tx_id: tx2_id,
+                    block_id: h2.id,
+                    height: 2,
+                    timestamp: h2.timestamp,
+                    kind: HistoryEntryKind::Outgoing,
+                    erg_delta: -3_100_000,
+                    token_deltas: vec![(token_id, -4)],
+                    fee: 1_100_000,
+                },
+                HistoryEntry {
+                    tx_id: tx3_id,
+                    block_id: h2.id,
+                    height: 2,
+                    timestamp: h2.timestamp,
+                    kind: HistoryEntryKind::SelfTransfer,
+                    erg_delta: -1_100_000,
+                    token_deltas: vec![],
+                    fee: 1_100_000,
+                },
+            ]
+        );
+        assert_eq!(history.entries().len(), 3);
+
+        assert_eq!(history.rollback_to(&h1.id).unwrap(), vec![h2.id]);
+        assert_eq!(history.entries().len(), 1);
+        assert!(!history.scanner().get(&received).unwrap().is_spent());
+        assert_eq!(history.rollback_last(), Some(h1.id));
+        assert!(history.entries().is_empty());
+    }
+}
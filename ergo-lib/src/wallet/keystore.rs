@@ -0,0 +1,598 @@
+//! Encrypted storage of the wallet secrets (mnemonic or extended secret key).
+//!
+//! The secret is encrypted with XChaCha20-Poly1305 using a key derived from the password with
+//! scrypt, and stored in a versioned JSON format:
+//! ```json
+//! {
+//!   "version": 1,
+//!   "kdf": { "name": "scrypt", "logN": 15, "r": 8, "p": 1, "salt": "<base16>" },
+//!   "cipher": { "name": "xchacha20poly1305", "nonce": "<base16>" },
+//!   "ciphertext": "<base16>"
+//! }
+//! ```
+//! Decrypted secrets (and intermediate buffers) are zeroized when dropped.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::aead::KeyInit;
+use chacha20poly1305::Key;
+use chacha20poly1305::XChaCha20Poly1305;
+use chacha20poly1305::XNonce;
+use rand::RngCore;
+use serde::Deserialize;
+use serde::Serialize;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::encode_base16;
+use sigma_util::encoding::DecodingError;
+use sigma_util::encoding::DecodingMode;
+use thiserror::Error;
+use zeroize::Zeroize;
+use zeroize::Zeroizing;
+
+use super::derivation_path::DerivationPath;
+use super::ext_secret_key::ChainCode;
+use super::ext_secret_key::ExtSecretKey;
+use super::ext_secret_key::ExtSecretKeyError;
+use super::ext_secret_key::SecretKeyBytes;
+use super::mnemonic::Mnemonic;
+
+/// Current keystore format version
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+
+/// Keystore errors
+#[derive(Error, Debug)]
+pub enum KeystoreError {
+    /// Unsupported keystore format version
+    #[error("Unsupported keystore version: {0}")]
+    UnsupportedVersion(u32),
+    /// Unsupported KDF or cipher
+    #[error("Unsupported algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+    /// Invalid scrypt parameters
+    #[error("Invalid KDF parameters: {0}")]
+    InvalidKdfParams(String),
+    /// Decryption failed (wrong password or corrupted data)
+    #[error("Decryption failed (wrong password or corrupted keystore)")]
+    DecryptionFailed,
+    /// Encryption failed
+    #[error("Encryption failed")]
+    EncryptionFailed,
+    /// Invalid base16 encoding
+    #[error("Base16 decoding error: {0}")]
+    Base16DecodingError(#[from] DecodingError),
+    /// Invalid JSON (keystore or decrypted secret)
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    /// Invalid secret
+    #[error("Invalid secret: {0}")]
+    InvalidSecret(String),
+    /// Extended secret key error
+    #[error("Extended secret key error: {0}")]
+    ExtSecretKeyError(#[from] ExtSecretKeyError),
+    /// File IO error
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// Wallet secret stored in the keystore, zeroized on drop (and redacted in the `Debug` output)
+#[derive(PartialEq, Eq, Clone)]
+pub enum KeystoreSecret {
+    /// Mnemonic phrase with the (optional, can be empty) mnemonic password
+    Mnemonic {
+        /// Mnemonic phrase
+        phrase: String,
+        /// Mnemonic password
+        password: String,
+    },
+    /// Extended secret key
+    ExtSecretKey {
+        /// Secret key bytes
+        secret_key: SecretKeyBytes,
+        /// Chain code
+        chain_code: ChainCode,
+        /// Derivation path
+        path: DerivationPath,
+    },
+}
+
+impl KeystoreSecret {
+    /// Master extended secret key (for the mnemonic) or the stored extended secret key
+    pub fn to_ext_secret_key(&self) -> Result<ExtSecretKey, KeystoreError> {
+        Ok(match self {
+            KeystoreSecret::Mnemonic { phrase, password } => {
+                let seed = Zeroizing::new(Mnemonic::to_seed(phrase, password));
+                ExtSecretKey::derive_master(*seed)?
+            }
+            KeystoreSecret::ExtSecretKey {
+                secret_key,
+                chain_code,
+                path,
+            } => ExtSecretKey::new(*secret_key, *chain_code, path.clone())?,
+        })
+    }
+}
+
+impl From<&ExtSecretKey> for KeystoreSecret {
+    fn from(k: &ExtSecretKey) -> Self {
+        KeystoreSecret::ExtSecretKey {
+            secret_key: k.secret_key_bytes(),
+            chain_code: k.chain_code(),
+            path: k.path(),
+        }
+    }
+}
+
+impl std::fmt::Debug for KeystoreSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const REDACTED: &str = "<redacted>";
+        match self {
+            KeystoreSecret::Mnemonic { .. } => f
+                .debug_struct("Mnemonic")
+                .field("phrase", &REDACTED)
+                .field("password", &REDACTED)
+                .finish(),
+            KeystoreSecret::ExtSecretKey { path, .. } => f
+                .debug_struct("ExtSecretKey")
+                .field("secret_key", &REDACTED)
+                .field("chain_code", &REDACTED)
+                .field("path", path)
+                .finish(),
+        }
+    }
+}
+
+impl Drop for KeystoreSecret {
+    fn drop(&mut self) {
+        match self {
+            KeystoreSecret::Mnemonic { phrase, password } => {
+                phrase.zeroize();
+                password.zeroize();
+            }
+            KeystoreSecret::ExtSecretKey {
+                secret_key,
+                chain_code,
+                ..
+            } => {
+                secret_key.zeroize();
+                chain_code.zeroize();
+            }
+        }
+    }
+}
+
+/// scrypt parameters
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct ScryptParams {
+    /// log2 of the CPU/memory cost
+    #[serde(rename = "logN")]
+    pub log_n: u8,
+    /// Block size
+    pub r: u32,
+    /// Parallelization
+    pub p: u32,
+}
+
+impl ScryptParams {
+    /// Maximum log2 of the CPU/memory cost
+    pub const MAX_LOG_N: u8 = 20;
+    /// Maximum block size
+    pub const MAX_R: u32 = 32;
+    /// Maximum parallelization
+    pub const MAX_P: u32 = 16;
+    /// Maximum memory used by scrypt (128 * r * N bytes)
+    pub const MAX_MEMORY_BYTES: u64 = 1 << 30;
+
+    /// Check that the parameters are within the limits, so that a crafted keystore can't make
+    /// the key derivation exhaust the memory or CPU
+    pub fn check_limits(&self) -> Result<(), KeystoreError> {
+        // the memory can't overflow once the log N and r are within the limits
+        let within_limits = self.log_n <= Self::MAX_LOG_N
+            && self.r <= Self::MAX_R
+            && self.p <= Self::MAX_P
+            && 128 * self.r as u64 * (1u64 << self.log_n) <= Self::MAX_MEMORY_BYTES;
+        if within_limits {
+            Ok(())
+        } else {
+            Err(KeystoreError::InvalidKdfParams(format!(
+                "{:?} exceed the limits (logN <= {}, r <= {}, p <= {}, memory <= {} bytes)",
+                self,
+                Self::MAX_LOG_N,
+                Self::MAX_R,
+                Self::MAX_P,
+                Self::MAX_MEMORY_BYTES
+            )))
+        }
+    }
+}
+
+impl Default for ScryptParams {
+    /// Interactive login parameters (N = 2^15, r = 8, p = 1)
+    fn default() -> Self {
+        ScryptParams {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+struct KdfJson {
+    name: String,
+    #[serde(flatten)]
+    params: ScryptParams,
+    salt: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+struct CipherJson {
+    name: String,
+    nonce: String,
+}
+
+/// Data encrypted with the password (KDF and cipher parameters with the ciphertext)
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub(crate) struct EncryptedPayload {
+    kdf: KdfJson,
+    cipher: CipherJson,
+    ciphertext: String,
+}
+
+impl EncryptedPayload {
+    const KDF_SCRYPT: &'static str = "scrypt";
+    const CIPHER_XCHACHA20POLY1305: &'static str = "xchacha20poly1305";
+
+    /// Encrypt with the password (random salt and nonce)
+    pub(crate) fn encrypt(
+        plaintext: &[u8],
+        password: &str,
+        params: ScryptParams,
+    ) -> Result<Self, KeystoreError> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let key = derive_key(password, &salt, params)?;
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+            .encrypt(XNonce::from_slice(&nonce), plaintext)
+            .map_err(|_| KeystoreError::EncryptionFailed)?;
+        Ok(EncryptedPayload {
+            kdf: KdfJson {
+                name: Self::KDF_SCRYPT.to_string(),
+                params,
+                salt: encode_base16(&salt),
+            },
+            cipher: CipherJson {
+                name: Self::CIPHER_XCHACHA20POLY1305.to_string(),
+                nonce: encode_base16(&nonce),
+            },
+            ciphertext: encode_base16(&ciphertext),
+        })
+    }
+
+    /// Decrypt with the password
+    pub(crate) fn decrypt(&self, password: &str) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+        if self.kdf.name != Self::KDF_SCRYPT {
+            return Err(KeystoreError::UnsupportedAlgorithm(self.kdf.name.clone()));
+        }
+        if self.cipher.name != Self::CIPHER_XCHACHA20POLY1305 {
+            return Err(KeystoreError::UnsupportedAlgorithm(
+                self.cipher.name.clone(),
+            ));
+        }
+        let salt = decode_base16(&self.kdf.salt, DecodingMode::Strict)?;
+        let nonce = decode_base16(&self.cipher.nonce, DecodingMode::Strict)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(KeystoreError::DecryptionFailed);
+        }
+        let ciphertext = decode_base16(&self.ciphertext, DecodingMode::Strict)?;
+        let key = derive_key(password, &salt, self.kdf.params)?;
+        Ok(Zeroizing::new(
+            XChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+                .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+                .map_err(|_| KeystoreError::DecryptionFailed)?,
+        ))
+    }
+
+    /// scrypt parameters used for the key derivation
+    pub(crate) fn kdf_params(&self) -> ScryptParams {
+        self.kdf.params
+    }
+}
+
+/// Encrypted wallet secret, see module docs for the format
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct EncryptedKeystore {
+    version: u32,
+    #[serde(flatten)]
+    payload: EncryptedPayload,
+}
+
+/// Decrypted secret JSON
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum SecretJson {
+    #[serde(rename = "mnemonic")]
+    Mnemonic { phrase: String, password: String },
+    #[serde(rename = "extSecretKey")]
+    ExtSecretKey {
+        #[serde(rename = "secretKey")]
+        secret_key: String,
+        #[serde(rename = "chainCode")]
+        chain_code: String,
+        path: String,
+    },
+}
+
+impl Drop for SecretJson {
+    fn drop(&mut self) {
+        match self {
+            SecretJson::Mnemonic { phrase, password } => {
+                phrase.zeroize();
+                password.zeroize();
+            }
+            SecretJson::ExtSecretKey {
+                secret_key,
+                chain_code,
+                ..
+            } => {
+                secret_key.zeroize();
+                chain_code.zeroize();
+            }
+        }
+    }
+}
+
+impl EncryptedKeystore {
+    /// Encrypt the secret with the password (random salt and nonce)
+    pub fn encrypt(
+        secret: &KeystoreSecret,
+        password: &str,
+        params: ScryptParams,
+    ) -> Result<Self, KeystoreError> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(&secret_to_json(secret))?);
+        Ok(EncryptedKeystore {
+            version: KEYSTORE_VERSION,
+            payload: EncryptedPayload::encrypt(plaintext.as_slice(), password, params)?,
+        })
+    }
+
+    /// Decrypt the secret with the password
+    pub fn decrypt(&self, password: &str) -> Result<KeystoreSecret, KeystoreError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(self.version));
+        }
+        let plaintext = self.payload.decrypt(password)?;
+        let secret_json: SecretJson = serde_json::from_slice(plaintext.as_slice())?;
+        secret_from_json(&secret_json)
+    }
+
+    /// Format version
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// scrypt parameters used for the key derivation
+    pub fn kdf_params(&self) -> ScryptParams {
+        self.payload.kdf_params()
+    }
+
+    /// Encode as JSON
+    pub fn to_json(&self) -> Result<String, KeystoreError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse from JSON
+    pub fn from_json(json: &str) -> Result<Self, KeystoreError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Save as JSON to the file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), KeystoreError> {
+        Ok(std::fs::write(path, self.to_json()?)?)
+    }
+
+    /// Load from the JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, KeystoreError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    params: ScryptParams,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, KeystoreError> {
+    params.check_limits()?;
+    let scrypt_params = scrypt::Params::new(params.log_n, params.r, params.p, KEY_LEN)
+        .map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    scrypt::scrypt(
+        password.as_bytes(),
+        salt,
+        &scrypt_params,
+        key.as_mut_slice(),
+    )
+    .map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+    Ok(key)
+}
+
+fn secret_to_json(secret: &KeystoreSecret) -> SecretJson {
+    match secret {
+        KeystoreSecret::Mnemonic { phrase, password } => SecretJson::Mnemonic {
+            phrase: phrase.clone(),
+            password: password.clone(),
+        },
+        KeystoreSecret::ExtSecretKey {
+            secret_key,
+            chain_code,
+            path,
+        } => SecretJson::ExtSecretKey {
+            secret_key: encode_base16(secret_key),
+            chain_code: encode_base16(chain_code),
+            path: path.to_string(),
+        },
+    }
+}
+
+fn secret_from_json(secret: &SecretJson) -> Result<KeystoreSecret, KeystoreError> {
+    Ok(match secret {
+        SecretJson::Mnemonic { phrase, password } => KeystoreSecret::Mnemonic {
+            phrase: phrase.clone(),
+            password: password.clone(),
+        },
+        SecretJson::ExtSecretKey {
+            secret_key,
+            chain_code,
+            path,
+        } => KeystoreSecret::ExtSecretKey {
+            secret_key: decode_32_bytes(secret_key)?,
+            chain_code: decode_32_bytes(chain_code)?,
+            path: DerivationPath::from_str(path)
+                .map_err(|e| KeystoreError::InvalidSecret(e.to_string()))?,
+        },
+    })
+}
+
+fn decode_32_bytes(s: &str) -> Result<[u8; 32], KeystoreError> {
+    let bytes = Zeroizing::new(decode_base16(s, DecodingMode::Strict)?);
+    let mut res = [0u8; 32];
+    if bytes.len() != res.len() {
+        return Err(KeystoreError::InvalidSecret(format!(
+            "expected 32 bytes, got {}",
+            bytes.len()
+        )));
+    }
+    res.copy_from_slice(bytes.as_slice());
+    Ok(res)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    // low cost parameters to keep the tests fast
+    const TEST_PARAMS: ScryptParams = ScryptParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    fn mnemonic_secret() -> KeystoreSecret {
+        KeystoreSecret::Mnemonic {
+            phrase: "change me do not use me change me do not use me".to_string(),
+            password: "mnemonic pass".to_string(),
+        }
+    }
+
+    #[test]
+    fn mnemonic_roundtrip() {
+        let secret = mnemonic_secret();
+        let keystore = EncryptedKeystore::encrypt(&secret, "password", TEST_PARAMS).unwrap();
+        let parsed = EncryptedKeystore::from_json(&keystore.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, keystore);
+        assert_eq!(parsed.version(), KEYSTORE_VERSION);
+        assert_eq!(parsed.kdf_params(), TEST_PARAMS);
+        assert_eq!(parsed.decrypt("password").unwrap(), secret);
+    }
+
+    #[test]
+    fn ext_secret_key_roundtrip() {
+        let master = mnemonic_secret().to_ext_secret_key().unwrap();
+        let secret = KeystoreSecret::from(&master);
+        let keystore = EncryptedKeystore::encrypt(&secret, "password", TEST_PARAMS).unwrap();
+        let decrypted = keystore.decrypt("password").unwrap();
+        assert_eq!(decrypted, secret);
+        assert_eq!(decrypted.to_ext_secret_key().unwrap(), master);
+    }
+
+    #[test]
+    fn wrong_password() {
+        let keystore =
+            EncryptedKeystore::encrypt(&mnemonic_secret(), "password", TEST_PARAMS).unwrap();
+        assert!(matches!(
+            keystore.decrypt("wrong"),
+            Err(KeystoreError::DecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn debug_is_redacted() {
+        let debug = format!("{:?}", mnemonic_secret());
+        assert!(!debug.contains("change me"));
+        assert!(!debug.contains("mnemonic pass"));
+        let master = mnemonic_secret().to_ext_secret_key().unwrap();
+        let debug = format!("{:?}", KeystoreSecret::from(&master));
+        assert!(!debug.contains(&format!("{:?}", master.secret_key_bytes())));
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[test]
+    fn scrypt_params_limits() {
+        assert!(ScryptParams::default().check_limits().is_ok());
+        for params in [
+            ScryptParams {
+                log_n: ScryptParams::MAX_LOG_N + 1,
+                ..TEST_PARAMS
+            },
+            ScryptParams {
+                r: ScryptParams::MAX_R + 1,
+                ..TEST_PARAMS
+            },
+            ScryptParams {
+                p: ScryptParams::MAX_P + 1,
+                ..TEST_PARAMS
+            },
+            // each within the limits, but too much memory
+            ScryptParams {
+                log_n: ScryptParams::MAX_LOG_N,
+                r: ScryptParams::MAX_R,
+                p: 1,
+            },
+            ScryptParams {
+                log_n: u8::MAX,
+                ..TEST_PARAMS
+            },
+        ] {
+            assert!(matches!(
+                params.check_limits(),
+                Err(KeystoreError::InvalidKdfParams(_))
+            ));
+        }
+        // rejected on decryption before the key derivation
+        let keystore =
+            EncryptedKeystore::encrypt(&mnemonic_secret(), "password", TEST_PARAMS).unwrap();
+        let json = keystore
+            .to_json()
+            .unwrap()
+            .replace("\"logN\": 4", "\"logN\": 64");
+        assert!(matches!(
+            EncryptedKeystore::from_json(&json)
+                .unwrap()
+                .decrypt("password"),
+            Err(KeystoreError::InvalidKdfParams(_))
+        ));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let keystore =
+            EncryptedKeystore::encrypt(&mnemonic_secret(), "password", TEST_PARAMS).unwrap();
+        let json = keystore
+            .to_json()
+            .unwrap()
+            .replace("\"version\": 1", "\"version\": 2");
+        let parsed = EncryptedKeystore::from_json(&json).unwrap();
+        assert!(matches!(
+            parsed.decrypt("password"),
+            Err(KeystoreError::UnsupportedVersion(2))
+        ));
+    }
+}
@@ -0,0 +1,315 @@
+//! Double-spend detection against the unconfirmed (mempool) transactions
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::tx_id::TxId;
+
+use crate::chain::transaction::unsigned::UnsignedTransaction;
+use crate::chain::transaction::Transaction;
+
+use super::box_selector::ErgoBoxId;
+
+/// Input of a transaction that is already spent by a mempool transaction
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct InputConflict {
+    /// Id of the box spent twice
+    pub box_id: BoxId,
+    /// Mempool transaction spending the box
+    pub mempool_tx_id: TxId,
+}
+
+/// Boxes spent by a set of unconfirmed transactions (e.g. collected from the node's mempool)
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct MempoolSnapshot {
+    /// Mempool transactions spending the box (in the order they were added)
+    spent: HashMap<BoxId, Vec<TxId>>,
+    /// Inputs of the mempool transactions (to remove a transaction without scanning all boxes)
+    tx_inputs: HashMap<TxId, Vec<BoxId>>,
+}
+
+impl MempoolSnapshot {
+    /// Snapshot of the given transactions
+    pub fn new(txs: &[Transaction]) -> Self {
+        let mut snapshot = MempoolSnapshot::default();
+        txs.iter().for_each(|tx| snapshot.add_transaction(tx));
+        snapshot
+    }
+
+    /// Add transaction (e.g. arrived from the mempool stream). The inputs already spent by other
+    /// mempool transactions are tracked with all their spending transactions.
+    pub fn add_transaction(&mut self, tx: &Transaction) {
+        let tx_id = tx.id();
+        if self.tx_inputs.contains_key(&tx_id) {
+            return;
+        }
+        let input_ids: Vec<BoxId> = tx.inputs.iter().map(|i| i.box_id).collect();
+        for box_id in &input_ids {
+            self.spent.entry(*box_id).or_default().push(tx_id);
+        }
+        self.tx_inputs.insert(tx_id, input_ids);
+    }
+
+    /// Remove transaction (e.g. included in a block or evicted from the mempool)
+    pub fn remove_transaction(&mut self, tx_id: &TxId) {
+        for box_id in self.tx_inputs.remove(tx_id).into_iter().flatten() {
+            if let Entry::Occupied(mut spending_tx_ids) = self.spent.entry(box_id) {
+                spending_tx_ids.get_mut().retain(|id| id != tx_id);
+                if spending_tx_ids.get().is_empty() {
+                    spending_tx_ids.remove();
+                }
+            }
+        }
+    }
+
+    /// Number of spent boxes
+    pub fn len(&self) -> usize {
+        self.spent.len()
+    }
+
+    /// Returns true if no boxes are spent
+    pub fn is_empty(&self) -> bool {
+        self.spent.is_empty()
+    }
+
+    /// Number of transactions
+    pub fn tx_count(&self) -> usize {
+        self.tx_inputs.len()
+    }
+
+    /// First added mempool transaction spending the box, if any
+    pub fn spending_tx_id(&self, box_id: &BoxId) -> Option<TxId> {
+        self.spending_tx_ids(box_id).first().copied()
+    }
+
+    /// All mempool transactions spending the box (in the order they were added)
+    pub fn spending_tx_ids(&self, box_id: &BoxId) -> &[TxId] {
+        self.spent
+            .get(box_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the box is spent by a mempool transaction
+    pub fn is_spent(&self, box_id: &BoxId) -> bool {
+        self.spent.contains_key(box_id)
+    }
+
+    /// Inputs (given by box ids) already spent by the mempool transactions, one conflict for
+    /// each spending transaction
+    pub fn conflicts<I: IntoIterator<Item = BoxId>>(&self, input_ids: I) -> Vec<InputConflict> {
+        input_ids
+            .into_iter()
+            .flat_map(|box_id| {
+                self.spending_tx_ids(&box_id)
+                    .iter()
+                    .map(move |mempool_tx_id| InputConflict {
+                        box_id,
+                        mempool_tx_id: *mempool_tx_id,
+                    })
+            })
+            .collect()
+    }
+
+    /// Inputs of the unsigned transaction already spent by the mempool transactions
+    pub fn unsigned_tx_conflicts(&self, tx: &UnsignedTransaction) -> Vec<InputConflict> {
+        self.conflicts(tx.inputs.iter().map(|i| i.box_id))
+    }
+
+    /// Inputs of the transaction already spent by other mempool transactions (the transaction
+    /// itself being in the snapshot is not a conflict)
+    pub fn tx_conflicts(&self, tx: &Transaction) -> Vec<InputConflict> {
+        let tx_id = tx.id();
+        self.conflicts(tx.inputs.iter().map(|i| i.box_id))
+            .into_iter()
+            .filter(|c| c.mempool_tx_id != tx_id)
+            .collect()
+    }
+
+    /// Boxes not spent by the mempool transactions (to be used in box selection)
+    pub fn filter_unspent<T: ErgoBoxId>(&self, boxes: Vec<T>) -> Vec<T> {
+        boxes
+            .into_iter()
+            .filter(|b| !self.is_spent(&b.box_id()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergotree_ir::chain::ergo_box::ErgoBox;
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::transaction::input::prover_result::ProverResult;
+    use crate::chain::transaction::Input;
+
+    use super::*;
+
+    #[test]
+    fn test_conflicts() {
+        let spent = force_any_val::<ErgoBox>();
+        let unspent = force_any_val::<ErgoBox>();
+        let tx = Transaction::new_from_vec(
+            vec![Input::new(spent.box_id(), force_any_val::<ProverResult>())],
+            vec![],
+            vec![force_any_val::<ErgoBoxCandidate>()],
+        )
+        .unwrap();
+        let other_tx = force_any_val::<Transaction>();
+        let mut snapshot = MempoolSnapshot::new(&[tx.clone()]);
+        assert!(snapshot.is_spent(&spent.box_id()));
+        assert_eq!(snapshot.spending_tx_id(&spent.box_id()), Some(tx.id()));
+        assert!(snapshot.tx_conflicts(&tx).is_empty());
+        assert!(snapshot.tx_conflicts(&other_tx).is_empty());
+        assert_eq!(
+            snapshot.conflicts(vec![unspent.box_id(), spent.box_id()]),
+            vec![InputConflict {
+                box_id: spent.box_id(),
+                mempool_tx_id: tx.id()
+            }]
+        );
+        assert_eq!(
+            snapshot.filter_unspent(vec![spent, unspent.clone()]),
+            vec![unspent]
+        );
+
+        snapshot.remove_transaction(&tx.id());
+        assert!(snapshot.is_empty());
+    }
+
+    #[test]
+    fn test_multiple_spenders() {
+        // distinct boxes
+        let candidate = force_any_val::<ErgoBoxCandidate>();
+        let tx_id = force_any_val::<TxId>();
+        let new_box = |index| ErgoBox::from_box_candidate(&candidate, tx_id, index).unwrap();
+        let (spent, first_extra, second_extra) = (new_box(0), new_box(1), new_box(2));
+        let new_tx = |extra_input: &ErgoBox| {
+            Transaction::new_from_vec(
+                vec![
+                    Input::new(spent.box_id(), force_any_val::<ProverResult>()),
+                    Input::new(extra_input.box_id(), force_any_val::<ProverResult>()),
+                ],
+                vec![],
+                vec![force_any_val::<ErgoBoxCandidate>()],
+            )
+            .unwrap()
+        };
+        let first_tx = new_tx(&first_extra);
+        let second_tx = new_tx(&second_extra);
+        let mut snapshot = MempoolSnapshot::new(&[first_tx.clone(), second_tx.clone()]);
+        // adding the same transaction again changes nothing
+        snapshot.add_transaction(&first_tx);
+        assert_eq!(snapshot.tx_count(), 2);
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(
+            snapshot.spending_tx_ids(&spent.box_id()),
+            &[first_tx.id(), second_tx.id()]
+        );
+        // both transactions double-spend the box
+        assert_eq!(
+            snapshot.tx_conflicts(&first_tx),
+            vec![InputConflict {
+                box_id: spent.box_id(),
+                mempool_tx_id: second_tx.id()
+            }]
+        );
+        assert_eq!(snapshot.conflicts(vec![spent.box_id()]).len(), 2);
+
+        snapshot.remove_transaction(&first_tx.id());
+        assert!(!snapshot.is_spent(&first_extra.box_id()));
+        assert_eq!(
+            snapshot.spending_tx_id(&spent.box_id()),
+            Some(second_tx.id())
+        );
+        assert!(snapshot.tx_conflicts(&second_tx).is_empty());
+
+        snapshot.remove_transaction(&second_tx.id());
+        assert!(snapshot.is_empty());
+        assert_eq!(snapshot.tx_count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_mainnet_unconfirmed_txs() {
+        use crate::chain::transaction::input::UnsignedInput;
+        use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+        use std::str::FromStr;
+
+        // node `/transactions/unconfirmed` response with the mainnet transactions
+        // 0e6acf3f18b95bdc5bb1b060baa1eafe53bd89fb08b0e86d6cc00fbdd9e43189 and
+        // 9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9 (emission)
+        let txs: Vec<Transaction> = serde_json::from_str(include_str!(
+            "../../tests/fixtures/node_unconfirmed_txs.json"
+        ))
+        .unwrap();
+        let snapshot = MempoolSnapshot::new(&txs);
+        let box_id = |id: &str| BoxId::from_str(id).unwrap();
+        let (oracle_tx, emission_tx) = (txs[0].id(), txs[1].id());
+        assert_eq!(snapshot.tx_count(), 2);
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(
+            snapshot.spending_tx_id(&box_id(
+                "56111b039b86f71004b768d2e8b4579f1d79e28e7a617fd5add57a5239498c26"
+            )),
+            Some(oracle_tx)
+        );
+        assert_eq!(
+            snapshot.spending_tx_id(&box_id(
+                "9126af0675056b80d1fda7af9bf658464dbfa0b128afca7bf7dae18c27fe8456"
+            )),
+            Some(emission_tx)
+        );
+        // data inputs are read, not spent
+        assert!(!snapshot.is_spent(&box_id(
+            "e26d41ed030a30cd563681e72f0b9c07825ac983f8c253a87a43c1da21958ece"
+        )));
+        assert!(txs.iter().all(|tx| snapshot.tx_conflicts(tx).is_empty()));
+        // outputs of the unconfirmed transactions are not spent yet
+        assert_eq!(
+            snapshot
+                .filter_unspent(
+                    txs.iter()
+                        .flat_map(|tx| tx.outputs.iter().cloned())
+                        .collect()
+                )
+                .len(),
+            5
+        );
+
+        // a transaction spending the emission box again (e.g. built by a lagging miner)
+        let double_spend = UnsignedTransaction::new_from_vec(
+            vec![
+                UnsignedInput::new(
+                    box_id("9126af0675056b80d1fda7af9bf658464dbfa0b128afca7bf7dae18c27fe8456"),
+                    ContextExtension::empty(),
+                ),
+                UnsignedInput::new(
+                    txs[1].outputs.get(1).unwrap().box_id(),
+                    ContextExtension::empty(),
+                ),
+            ],
+            vec![],
+            vec![force_any_val::<ErgoBoxCandidate>()],
+        )
+        .unwrap();
+        assert_eq!(
+            snapshot.unsigned_tx_conflicts(&double_spend),
+            vec![InputConflict {
+                box_id: box_id("9126af0675056b80d1fda7af9bf658464dbfa0b128afca7bf7dae18c27fe8456"),
+                mempool_tx_id: emission_tx,
+            }]
+        );
+
+        // the emission tx is included in a block
+        let mut snapshot = snapshot;
+        snapshot.remove_transaction(&emission_tx);
+        assert!(snapshot.unsigned_tx_conflicts(&double_spend).is_empty());
+        assert_eq!(snapshot.tx_count(), 1);
+        assert_eq!(snapshot.len(), 2);
+    }
+}
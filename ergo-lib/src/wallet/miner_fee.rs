@@ -3,7 +3,38 @@
 use ergotree_ir::chain::address::Address;
 use ergotree_ir::chain::address::AddressEncoder;
 use ergotree_ir::chain::address::NetworkPrefix;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::ergo_tree::ErgoTreeHeader;
+use ergotree_ir::mir::and::And;
+use ergotree_ir::mir::bin_op::ArithOp;
+use ergotree_ir::mir::bin_op::BinOp;
+use ergotree_ir::mir::bin_op::RelationOp;
+use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
+use ergotree_ir::mir::coll_by_index::ByIndex;
+use ergotree_ir::mir::coll_size::SizeOf;
+use ergotree_ir::mir::collection::Collection;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::create_provedlog::CreateProveDlog;
+use ergotree_ir::mir::decode_point::DecodePoint;
+use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::mir::extract_creation_info::ExtractCreationInfo;
+use ergotree_ir::mir::extract_script_bytes::ExtractScriptBytes;
+use ergotree_ir::mir::global_vars::GlobalVars;
+use ergotree_ir::mir::select_field::SelectField;
+use ergotree_ir::mir::select_field::TupleFieldIndex;
+use ergotree_ir::mir::sigma_and::SigmaAnd;
+use ergotree_ir::mir::subst_const::SubstConstants;
+use ergotree_ir::mir::unary_op::OneArgOpTryBuild;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::sigma_protocol::sigma_boolean::ProveDlog;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProp;
+use ergotree_ir::types::stype::SType;
 use lazy_static::lazy_static;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+
+/// Number of blocks the miner's reward is locked for (mainnet and testnet)
+pub const MINER_REWARD_DELAY: i32 = 720;
 
 /// Base16 encoded serialized ErgoTree of the miners fee (delay 720)
 pub const MINERS_FEE_BASE16_BYTES: &str = "1005040004000e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300000193c2b2a57301007473027303830108cdeeac93b1a57304";
@@ -12,7 +43,7 @@ lazy_static! {
     /// Miner fee P2S address on mainnet
     pub static ref MINERS_FEE_ADDRESS: Address =
         #[allow(clippy::unwrap_used)]
-        Address::P2S(base16::decode(MINERS_FEE_BASE16_BYTES).unwrap());
+        Address::P2S(decode_base16(MINERS_FEE_BASE16_BYTES, DecodingMode::Strict).unwrap());
 
     /// Miner fee Base58 encoded P2S address on mainnet
     pub static ref MINERS_FEE_MAINNET_ADDRESS_STR: String =
@@ -21,14 +52,121 @@ lazy_static! {
     /// Miner fee Base58 encoded P2S address on testnet
     pub static ref MINERS_FEE_TESTNET_ADDRESS_STR: String =
         AddressEncoder::new(NetworkPrefix::Testnet).address_to_str(&MINERS_FEE_ADDRESS);
+
+    /// Miner fee ErgoTree (delay 720)
+    pub static ref MINERS_FEE_ERGO_TREE: ErgoTree = fee_proposition(MINER_REWARD_DELAY);
+}
+
+/// Miner fee proposition (sigmastate's `ErgoTreePredef.feeProposition`): the box can be spent
+/// only by a transaction with a single output created at the current height and protected by
+/// the miner's reward script (see [`miner_reward_proposition`]) with the miner's key
+/// (`CONTEXT.minerPubKey`).
+/// With the default delay it is the tree of [`MINERS_FEE_BASE16_BYTES`].
+#[allow(clippy::unwrap_used)]
+pub fn fee_proposition(delay: i32) -> ErgoTree {
+    // all the nodes are built with the expected argument types, so unwraps never fail
+    let out: Expr = ByIndex::new(GlobalVars::Outputs.into(), 0i32.into(), None)
+        .unwrap()
+        .into();
+    let creation_height: Expr = SelectField::new(
+        ExtractCreationInfo::try_build(out.clone()).unwrap().into(),
+        TupleFieldIndex::try_from(1u8).unwrap(),
+    )
+    .unwrap()
+    .into();
+    let height_correct = BinOp {
+        kind: RelationOp::Eq.into(),
+        left: Box::new(GlobalVars::Height.into()),
+        right: Box::new(creation_height),
+    };
+    let miner_pk: Expr = CreateProveDlog::try_build(
+        DecodePoint::try_build(GlobalVars::MinerPubKey.into())
+            .unwrap()
+            .into(),
+    )
+    .unwrap()
+    .into();
+    let expected_script_bytes = SubstConstants::new(
+        miner_reward_proposition(
+            delay,
+            ProveDlog::new(ergo_chain_types::ec_point::generator()),
+        )
+        .sigma_serialize_bytes()
+        .unwrap()
+        .into(),
+        Constant::from(vec![1i32]).into(),
+        Collection::new(SType::SSigmaProp, vec![miner_pk])
+            .unwrap()
+            .into(),
+    )
+    .unwrap();
+    let script_correct = BinOp {
+        kind: RelationOp::Eq.into(),
+        left: Box::new(ExtractScriptBytes::try_build(out).unwrap().into()),
+        right: Box::new(expected_script_bytes.into()),
+    };
+    let one_output = BinOp {
+        kind: RelationOp::Eq.into(),
+        left: Box::new(
+            SizeOf::try_build(GlobalVars::Outputs.into())
+                .unwrap()
+                .into(),
+        ),
+        right: Box::new(1i32.into()),
+    };
+    let conditions = Collection::new(
+        SType::SBoolean,
+        vec![
+            height_correct.into(),
+            script_correct.into(),
+            one_output.into(),
+        ],
+    )
+    .unwrap();
+    let expr: Expr = BoolToSigmaProp::try_build(And::new(conditions.into()).unwrap().into())
+        .unwrap()
+        .into();
+    ErgoTree::new(ErgoTreeHeader::v0(true), &expr).unwrap()
+}
+
+/// Miner's reward proposition (sigmastate's `ErgoTreePredef.rewardOutputScript`): the box
+/// can be spent by the miner `delay` blocks after its creation
+#[allow(clippy::unwrap_used)]
+pub fn miner_reward_proposition(delay: i32, miner_pk: ProveDlog) -> ErgoTree {
+    // all the nodes are built with the expected argument types, so unwraps never fail
+    let creation_height: Expr = SelectField::new(
+        ExtractCreationInfo::try_build(GlobalVars::SelfBox.into())
+            .unwrap()
+            .into(),
+        TupleFieldIndex::try_from(1u8).unwrap(),
+    )
+    .unwrap()
+    .into();
+    let unlock_height = BinOp {
+        kind: ArithOp::Plus.into(),
+        left: Box::new(creation_height),
+        right: Box::new(delay.into()),
+    };
+    let height_reached = BinOp {
+        kind: RelationOp::Ge.into(),
+        left: Box::new(GlobalVars::Height.into()),
+        right: Box::new(unlock_height.into()),
+    };
+    let expr: Expr = SigmaAnd::new(vec![
+        BoolToSigmaProp::try_build(height_reached.into())
+            .unwrap()
+            .into(),
+        Constant::from(SigmaProp::from(miner_pk)).into(),
+    ])
+    .unwrap()
+    .into();
+    ErgoTree::new(ErgoTreeHeader::v0(true), &expr).unwrap()
 }
 
 #[allow(clippy::unwrap_used)]
 #[cfg(test)]
 mod tests {
 
-    use ergotree_ir::ergo_tree::ErgoTree;
-    use ergotree_ir::serialization::SigmaSerializable;
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -49,6 +187,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fee_proposition_bytes() {
+        assert_eq!(
+            MINERS_FEE_ERGO_TREE.to_base16_bytes().unwrap(),
+            MINERS_FEE_BASE16_BYTES
+        );
+    }
+
     #[test]
     fn parses_fee_address() {
         ErgoTree::sigma_parse_bytes(base16::decode(MINERS_FEE_BASE16_BYTES).unwrap().as_slice())
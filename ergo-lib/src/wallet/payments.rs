@@ -0,0 +1,466 @@
+//! Batch payments to multiple recipients, split into chained transactions to keep each of them
+//! within the size and cost limits
+
+use std::convert::TryInto;
+
+use ergotree_ir::chain::address::Address;
+use ergotree_ir::chain::ergo_box::box_value::checked_sum;
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::ergo_box::box_value::BoxValueError;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenAmountError;
+use thiserror::Error;
+
+use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilderError;
+use crate::chain::transaction::unsigned::UnsignedTransaction;
+use crate::chain::transaction::Transaction;
+
+use super::box_selector::calc_change;
+use super::box_selector::sum_tokens_from_boxes;
+use super::box_selector::BoxSelection;
+use super::box_selector::BoxSelector;
+use super::box_selector::BoxSelectorError;
+use super::tx_builder::TxBuilder;
+use super::tx_builder::TxBuilderError;
+
+/// Default maximum size of the signed transaction (node's mempool limit)
+pub const DEFAULT_MAX_TX_SIZE: usize = 96 * 1024;
+
+/// Payment to the recipient
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Payment {
+    /// Recipient address
+    pub address: Address,
+    /// Amount (nanoERGs)
+    pub value: BoxValue,
+    /// Tokens to send
+    pub tokens: Vec<Token>,
+}
+
+/// Transactions of the batch
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PaymentsBatch {
+    /// Unsigned transactions, each one (except the first) spending the change of the previous
+    /// one, so they should be submitted in this order
+    pub transactions: Vec<UnsignedTransaction>,
+    /// Total miner fee of the transactions (nanoERGs)
+    pub total_fee: u64,
+}
+
+/// Errors of PaymentsBuilder
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum PaymentsBuilderError {
+    /// No payments given
+    #[error("No payments")]
+    NoPayments,
+    /// Box selection error
+    #[error("Box selection error: {0}")]
+    BoxSelectorError(#[from] BoxSelectorError),
+    /// Transaction building error
+    #[error("TxBuilder error: {0}")]
+    TxBuilderError(#[from] TxBuilderError),
+    /// Payment box building error
+    #[error("ErgoBoxCandidateBuilder error: {0}")]
+    ErgoBoxCandidateBuilderError(#[from] ErgoBoxCandidateBuilderError),
+    /// Invalid total value
+    #[error("Invalid total value: {0}")]
+    BoxValueError(#[from] BoxValueError),
+    /// Invalid total token amounts
+    #[error("Token amount error: {0}")]
+    TokenAmountError(#[from] TokenAmountError),
+    /// Transaction with a single payment (with the given index) exceeds the size limit
+    #[error("Payment {0} does not fit into a transaction")]
+    PaymentTooLarge(usize),
+}
+
+/// Builds the unsigned transactions paying to multiple recipients. The wallet boxes are selected
+/// once for the whole batch and spent by the first transaction, the payments are split into
+/// chained transactions (each spending the change boxes of the previous one) so that every
+/// transaction fits into the size limit and has at most the given number of payments.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PaymentsBuilder {
+    payments: Vec<Payment>,
+    current_height: u32,
+    fee_per_tx: BoxValue,
+    change_address: Address,
+    max_tx_size: usize,
+    max_payments_per_tx: usize,
+}
+
+impl PaymentsBuilder {
+    /// Creates new PaymentsBuilder
+    /// `payments` - payments in the order they are put into the transactions,
+    /// `current_height` - chain height used for the created boxes,
+    /// `fee_per_tx` - miner's fee of each transaction,
+    /// `change_address` - change of each transaction is sent to this address
+    pub fn new(
+        payments: Vec<Payment>,
+        current_height: u32,
+        fee_per_tx: BoxValue,
+        change_address: Address,
+    ) -> PaymentsBuilder {
+        PaymentsBuilder {
+            payments,
+            current_height,
+            fee_per_tx,
+            change_address,
+            max_tx_size: DEFAULT_MAX_TX_SIZE,
+            // change and fee boxes
+            max_payments_per_tx: Transaction::MAX_OUTPUTS_COUNT - 2,
+        }
+    }
+
+    /// Set the maximum estimated size of the signed transaction (bytes)
+    pub fn set_max_tx_size(&mut self, max_tx_size: usize) {
+        self.max_tx_size = max_tx_size;
+    }
+
+    /// Set the maximum number of payments in a transaction. The verification cost of the
+    /// transaction grows with its outputs (every output box is checked and serialized), so
+    /// this keeps the transactions within the cost limit.
+    pub fn set_max_payments_per_tx(&mut self, max_payments_per_tx: usize) {
+        self.max_payments_per_tx = max_payments_per_tx.max(1);
+    }
+
+    /// Select the boxes out of `inputs` for the whole batch and build the transactions
+    pub fn build<S: BoxSelector<ErgoBox>>(
+        self,
+        inputs: Vec<ErgoBox>,
+        box_selector: &S,
+    ) -> Result<PaymentsBatch, PaymentsBuilderError> {
+        if self.payments.is_empty() {
+            return Err(PaymentsBuilderError::NoPayments);
+        }
+        let outputs = self
+            .payments
+            .iter()
+            .map(|p| {
+                let ergo_tree = p.address.script().map_err(TxBuilderError::from)?;
+                let mut builder =
+                    ErgoBoxCandidateBuilder::new(p.value, ergo_tree, self.current_height);
+                for token in &p.tokens {
+                    builder.add_token(token.clone());
+                }
+                Ok(builder.build()?)
+            })
+            .collect::<Result<Vec<_>, PaymentsBuilderError>>()?;
+        let payments_value = checked_sum(outputs.iter().map(|b| b.value))?;
+        let payments_tokens: Vec<Token> = sum_tokens_from_boxes(outputs.as_slice())?
+            .into_iter()
+            .map(Token::from)
+            .collect();
+        // the number of transactions depends on the selected inputs (the first transaction
+        // spends all of them), so re-select until the fees of all transactions are covered
+        // (starting with the number of transactions required by the payments count limit)
+        let mut txs_count =
+            ((outputs.len() + self.max_payments_per_tx - 1) / self.max_payments_per_tx) as u64;
+        loop {
+            let target = self
+                .fee_per_tx
+                .as_u64()
+                .checked_mul(txs_count)
+                .and_then(|fees| payments_value.as_u64().checked_add(fees))
+                .ok_or(BoxValueError::Overflow)?;
+            let selection =
+                box_selector.select(inputs.clone(), target.try_into()?, &payments_tokens)?;
+            let transactions =
+                self.chain_transactions(selection.boxes.as_vec().clone(), &outputs)?;
+            if transactions.len() as u64 <= txs_count {
+                let total_fee = self
+                    .fee_per_tx
+                    .as_u64()
+                    .checked_mul(transactions.len() as u64)
+                    .ok_or(BoxValueError::Overflow)?;
+                return Ok(PaymentsBatch {
+                    transactions,
+                    total_fee,
+                });
+            }
+            txs_count = transactions.len() as u64;
+        }
+    }
+
+    fn chain_transactions(
+        &self,
+        inputs: Vec<ErgoBox>,
+        outputs: &[ErgoBoxCandidate],
+    ) -> Result<Vec<UnsignedTransaction>, PaymentsBuilderError> {
+        let mut transactions = Vec::new();
+        let mut inputs = inputs;
+        let mut start = 0;
+        while start < outputs.len() {
+            let is_last = |end: usize| end == outputs.len();
+            // the largest number of payments fitting into the size limit
+            let max_count = self.max_payments_per_tx.min(outputs.len() - start);
+            let (mut lo, mut hi) = (0, max_count);
+            while lo < hi {
+                let count = (lo + hi + 1) / 2;
+                let end = start + count;
+                let builder = self.tx_builder(&inputs, &outputs[start..end], is_last(end))?;
+                if builder.estimate_tx_size_bytes()? <= self.max_tx_size {
+                    lo = count;
+                } else {
+                    hi = count - 1;
+                }
+            }
+            if lo == 0 {
+                return Err(PaymentsBuilderError::PaymentTooLarge(start));
+            }
+            let end = start + lo;
+            let tx = self
+                .tx_builder(&inputs, &outputs[start..end], is_last(end))?
+                .build()?;
+            if !is_last(end) {
+                // change boxes go right after the payments, followed by the fee box
+                let outputs = tx.output_candidates.as_vec();
+                inputs = (end - start..outputs.len() - 1)
+                    .map(|index| {
+                        ErgoBox::from_box_candidate(&outputs[index], tx.id(), index as u16)
+                            .map_err(TxBuilderError::from)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+            }
+            transactions.push(tx);
+            start = end;
+        }
+        Ok(transactions)
+    }
+
+    /// Transaction spending `inputs` for the `payments`, with everything left (except the fee)
+    /// sent to the change address (split into several boxes if there are more than
+    /// [`ErgoBox::MAX_TOKENS_COUNT`] tokens left, as the box selectors do)
+    fn tx_builder(
+        &self,
+        inputs: &[ErgoBox],
+        payments: &[ErgoBoxCandidate],
+        is_last: bool,
+    ) -> Result<TxBuilder<ErgoBox>, PaymentsBuilderError> {
+        let spent = checked_sum(payments.iter().map(|b| b.value))?.checked_add(&self.fee_per_tx)?;
+        let payments_tokens: Vec<Token> = sum_tokens_from_boxes(payments)?
+            .into_iter()
+            .map(Token::from)
+            .collect();
+        let change_boxes = calc_change(inputs, spent, &payments_tokens)?;
+        if change_boxes.is_empty() && !is_last {
+            // nothing left for the next transactions
+            return Err(BoxSelectorError::NotEnoughCoins(*self.fee_per_tx.as_u64()).into());
+        }
+        let selection = BoxSelection {
+            boxes: inputs.to_vec().try_into().map_err(TxBuilderError::from)?,
+            change_boxes,
+        };
+        Ok(TxBuilder::new(
+            selection,
+            payments.to_vec(),
+            self.current_height,
+            self.fee_per_tx,
+            self.change_address.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergotree_ir::chain::ergo_box::BoxTokens;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::token::TokenAmount;
+    use ergotree_ir::chain::token::TokenId;
+    use ergotree_ir::chain::tx_id::TxId;
+    use sigma_test_util::force_any_val;
+
+    use crate::wallet::box_selector::SimpleBoxSelector;
+    use crate::wallet::secret_key::SecretKey;
+
+    use super::*;
+
+    #[test]
+    fn test_batch_payments() {
+        let wallet = SecretKey::random_dlog().get_address_from_public_image();
+        let token_id = force_any_val::<TokenId>();
+        let input = ErgoBox::new(
+            BoxValue::new(100_000_000_000).unwrap(),
+            wallet.script().unwrap(),
+            BoxTokens::from_vec(vec![Token {
+                token_id,
+                amount: TokenAmount::try_from(1_000).unwrap(),
+            }])
+            .ok(),
+            NonMandatoryRegisters::empty(),
+            0,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let payments: Vec<Payment> = (0..25)
+            .map(|i| Payment {
+                address: SecretKey::random_dlog().get_address_from_public_image(),
+                value: BoxValue::SAFE_USER_MIN,
+                tokens: if i % 5 == 0 {
+                    vec![Token {
+                        token_id,
+                        amount: TokenAmount::try_from(10).unwrap(),
+                    }]
+                } else {
+                    vec![]
+                },
+            })
+            .collect();
+        let fee = BoxValue::SAFE_USER_MIN;
+
+        let batch = PaymentsBuilder::new(payments.clone(), 100, fee, wallet.clone())
+            .build(vec![input.clone()], &SimpleBoxSelector::new())
+            .unwrap();
+        assert_eq!(batch.transactions.len(), 1);
+        assert_eq!(batch.total_fee, *fee.as_u64());
+        assert_eq!(batch.transactions[0].output_candidates.len(), 27);
+
+        let mut builder = PaymentsBuilder::new(payments.clone(), 100, fee, wallet.clone());
+        builder.set_max_payments_per_tx(10);
+        let batch = builder
+            .build(vec![input.clone()], &SimpleBoxSelector::new())
+            .unwrap();
+        assert_eq!(batch.transactions.len(), 3);
+        assert_eq!(batch.total_fee, 3 * *fee.as_u64());
+        // chained through the change boxes
+        for (prev, next) in batch
+            .transactions
+            .iter()
+            .zip(batch.transactions.iter().skip(1))
+        {
+            let change =
+                ErgoBox::from_box_candidate(&prev.output_candidates.as_vec()[10], prev.id(), 10)
+                    .unwrap();
+            assert_eq!(next.inputs.first().box_id, change.box_id());
+        }
+        let paid: u64 = batch
+            .transactions
+            .iter()
+            .flat_map(|tx| tx.output_candidates.iter())
+            .filter(|b| {
+                payments
+                    .iter()
+                    .any(|p| p.address.script().unwrap() == b.ergo_tree)
+            })
+            .map(|b| *b.value.as_u64())
+            .sum();
+        assert_eq!(paid, 25 * *BoxValue::SAFE_USER_MIN.as_u64());
+
+        let mut builder = PaymentsBuilder::new(payments, 100, fee, wallet.clone());
+        builder.set_max_tx_size(1_000);
+        let batch = builder
+            .build(vec![input.clone()], &SimpleBoxSelector::new())
+            .unwrap();
+        assert!(batch.transactions.len() > 1);
+
+        let huge_payments: Vec<Payment> = (0..2)
+            .map(|_| Payment {
+                address: SecretKey::random_dlog().get_address_from_public_image(),
+                value: BoxValue::new(BoxValue::MAX_RAW).unwrap(),
+                tokens: vec![],
+            })
+            .collect();
+        assert!(matches!(
+            PaymentsBuilder::new(huge_payments, 100, fee, wallet.clone())
+                .build(vec![input.clone()], &SimpleBoxSelector::new()),
+            Err(PaymentsBuilderError::BoxValueError(_))
+        ));
+
+        assert_eq!(
+            PaymentsBuilder::new(vec![], 100, fee, wallet)
+                .build(vec![input], &SimpleBoxSelector::new()),
+            Err(PaymentsBuilderError::NoPayments)
+        );
+    }
+    #[test]
+    fn test_batch_payments_change_tokens_split() {
+        let wallet = SecretKey::random_dlog().get_address_from_public_image();
+        let inputs: Vec<ErgoBox> = (0..2u8)
+            .map(|box_index| {
+                let tokens = (0..70u8)
+                    .map(|token_index| {
+                        // distinct token ids
+                        let mut id = [0; 32];
+                        id[0] = box_index;
+                        id[1] = token_index;
+                        Token {
+                            token_id: TokenId::from(id),
+                            amount: TokenAmount::try_from(2).unwrap(),
+                        }
+                    })
+                    .collect();
+                ErgoBox::new(
+                    BoxValue::new(5_000_000).unwrap(),
+                    wallet.script().unwrap(),
+                    BoxTokens::from_vec(tokens).ok(),
+                    NonMandatoryRegisters::empty(),
+                    0,
+                    force_any_val::<TxId>(),
+                    0,
+                )
+                .unwrap()
+            })
+            .collect();
+        // neither box covers the payments and fees alone, so both are spent
+        let paid_token = Token {
+            token_id: inputs[1].tokens.as_ref().unwrap().first().token_id,
+            amount: TokenAmount::try_from(1).unwrap(),
+        };
+        let payments: Vec<Payment> = (0..3)
+            .map(|i| Payment {
+                address: SecretKey::random_dlog().get_address_from_public_image(),
+                value: BoxValue::SAFE_USER_MIN,
+                tokens: if i == 0 {
+                    vec![paid_token.clone()]
+                } else {
+                    vec![]
+                },
+            })
+            .collect();
+        let mut builder = PaymentsBuilder::new(payments, 100, BoxValue::SAFE_USER_MIN, wallet);
+        builder.set_max_payments_per_tx(1);
+        let batch = builder
+            .build(inputs.clone(), &SimpleBoxSelector::new())
+            .unwrap();
+        assert_eq!(batch.transactions.len(), 3);
+        for tx in &batch.transactions {
+            // payment, two change boxes (140 tokens) and fee
+            assert_eq!(tx.output_candidates.len(), 4);
+            assert!(tx
+                .output_candidates
+                .iter()
+                .all(|b| b.tokens.as_ref().map_or(0, |t| t.len()) <= ErgoBox::MAX_TOKENS_COUNT));
+        }
+        // chained through all the change boxes
+        for (prev, next) in batch
+            .transactions
+            .iter()
+            .zip(batch.transactions.iter().skip(1))
+        {
+            let change_ids: Vec<_> = (1..3u16)
+                .map(|i| {
+                    ErgoBox::from_box_candidate(
+                        &prev.output_candidates.as_vec()[i as usize],
+                        prev.id(),
+                        i,
+                    )
+                    .unwrap()
+                    .box_id()
+                })
+                .collect();
+            let input_ids: Vec<_> = next.inputs.iter().map(|i| i.box_id).collect();
+            assert_eq!(input_ids, change_ids);
+        }
+        let last = batch.transactions.last().unwrap();
+        assert_eq!(
+            sum_tokens_from_boxes(&last.output_candidates.as_vec()[1..3])
+                .unwrap()
+                .len(),
+            140
+        );
+    }
+}
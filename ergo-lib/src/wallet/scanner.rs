@@ -0,0 +1,595 @@
+//! Scanning blocks for the boxes belonging to the wallet (UTXO tracking, rescan)
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use ergo_chain_types::BlockId;
+use ergo_chain_types::Header;
+use ergotree_ir::chain::address::Address;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::tx_id::TxId;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::ergo_tree::ErgoTreeCache;
+use ergotree_ir::serialization::SigmaParsingError;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::serialization::SigmaSerializationError;
+use sigma_ser::ScorexSerializable;
+use thiserror::Error;
+
+use crate::chain::block::FullBlock;
+use crate::chain::transaction::view::TransactionView;
+use crate::chain::transaction::Transaction;
+
+use super::derivation_path::ChildIndexError;
+use super::derivation_path::ChildIndexNormal;
+use super::ext_pub_key::ExtPubKey;
+
+/// Transaction spending a tracked box
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SpendingInfo {
+    /// Id of the spending transaction
+    pub tx_id: TxId,
+    /// Block the spending transaction is included in
+    pub block_id: BlockId,
+    /// Height of the block
+    pub height: u32,
+}
+
+/// Box belonging to the wallet
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TrackedBox {
+    /// The box
+    pub ergo_box: ErgoBox,
+    /// Block the box was created in
+    pub block_id: BlockId,
+    /// Height of the block the box was created in
+    pub inclusion_height: u32,
+    /// `None` if the box is unspent
+    pub spent: Option<SpendingInfo>,
+}
+
+impl TrackedBox {
+    /// Number of blocks on top of (and including) the block the box was created in
+    pub fn confirmations(&self, tip_height: u32) -> u32 {
+        (tip_height + 1).saturating_sub(self.inclusion_height)
+    }
+
+    /// Returns true if the box is spent
+    pub fn is_spent(&self) -> bool {
+        self.spent.is_some()
+    }
+}
+
+/// Changes made to the tracked boxes by the applied block
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct BlockScanResult {
+    /// Newly tracked boxes
+    pub received: Vec<BoxId>,
+    /// Tracked boxes spent in the block
+    pub spent: Vec<BoxId>,
+}
+
+/// Applied block kept to undo it on rollback
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct AppliedBlock {
+    id: BlockId,
+    height: u32,
+    changes: BlockScanResult,
+}
+
+/// Wallet scanner errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum WalletScannerError {
+    /// Block does not connect to the last applied block
+    #[error("Block {block_id} (parent {parent_id}) does not connect to the tip {tip_id}")]
+    NotConnected {
+        /// Applied block id
+        block_id: BlockId,
+        /// Parent of the applied block
+        parent_id: BlockId,
+        /// Last applied block
+        tip_id: BlockId,
+    },
+    /// Block to roll back to is not among the recent applied blocks
+    #[error("Block {0} is not among the recent applied blocks")]
+    UnknownBlock(BlockId),
+    /// Script serialization error
+    #[error("Script serialization error: {0}")]
+    SigmaSerializationError(#[from] SigmaSerializationError),
+    /// Address script parsing error
+    #[error("Address script parsing error: {0}")]
+    SigmaParsingError(#[from] SigmaParsingError),
+    /// Invalid derivation index
+    #[error("Invalid derivation index: {0}")]
+    ChildIndexError(#[from] ChildIndexError),
+}
+
+/// Tracks the boxes guarded by the given scripts (addresses, ErgoTrees, keys derived from an
+/// extended public key) by scanning the blocks in the chain order. Keeps the last
+/// `max_rollback_depth` applied blocks to undo them on a chain reorganization.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct WalletScanner {
+    /// Serialized tracked ErgoTrees
+    scripts: HashSet<Vec<u8>>,
+    boxes: HashMap<BoxId, TrackedBox>,
+    /// Recent applied blocks, ordered by height
+    applied: VecDeque<AppliedBlock>,
+    max_rollback_depth: usize,
+}
+
+impl WalletScanner {
+    /// Scanner keeping `max_rollback_depth` last applied blocks for the rollbacks
+    pub fn new(max_rollback_depth: usize) -> Self {
+        WalletScanner {
+            scripts: HashSet::new(),
+            boxes: HashMap::new(),
+            applied: VecDeque::new(),
+            max_rollback_depth,
+        }
+    }
+
+    /// Track the boxes guarded by the given ErgoTree. Boxes in the already applied blocks are not
+    /// rescanned.
+    pub fn track_ergo_tree(&mut self, tree: &ErgoTree) -> Result<(), WalletScannerError> {
+        self.scripts.insert(tree.sigma_serialize_bytes()?);
+        Ok(())
+    }
+
+    /// Track the boxes guarded by the address script
+    pub fn track_address(&mut self, address: &Address) -> Result<(), WalletScannerError> {
+        self.track_ergo_tree(&address.script()?)
+    }
+
+    /// Track the P2PK addresses of the first `count` children of the extended public key
+    pub fn track_ext_pub_key(
+        &mut self,
+        ext_pub_key: &ExtPubKey,
+        count: u32,
+    ) -> Result<(), WalletScannerError> {
+        for i in 0..count {
+            let child = ext_pub_key.child(ChildIndexNormal::normal(i)?);
+            self.track_address(&child.into())?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the box is guarded by one of the tracked scripts
+    pub fn is_tracked_script(&self, tree: &ErgoTree) -> bool {
+        tree.sigma_serialize_bytes()
+            .map(|bytes| self.scripts.contains(&bytes))
+            .unwrap_or(false)
+    }
+
+    /// Last applied block id and height
+    pub fn tip(&self) -> Option<(BlockId, u32)> {
+        self.applied.back().map(|b| (b.id, b.height))
+    }
+
+    /// Tracked box by id
+    pub fn get(&self, box_id: &BoxId) -> Option<&TrackedBox> {
+        self.boxes.get(box_id)
+    }
+
+    /// All tracked boxes (spent and unspent)
+    pub fn boxes(&self) -> impl Iterator<Item = &TrackedBox> {
+        self.boxes.values()
+    }
+
+    /// Unspent boxes with at least `min_confirmations` confirmations
+    pub fn unspent_boxes(&self, min_confirmations: u32) -> Vec<&ErgoBox> {
+        let tip_height = self.tip().map(|(_, h)| h).unwrap_or(0);
+        self.boxes
+            .values()
+            .filter(|b| !b.is_spent() && b.confirmations(tip_height) >= min_confirmations)
+            .map(|b| &b.ergo_box)
+            .collect()
+    }
+
+    /// Apply the full block, see [`WalletScanner::apply_block_transactions`]
+    pub fn apply_block(
+        &mut self,
+        block: &FullBlock,
+    ) -> Result<BlockScanResult, WalletScannerError> {
+        self.apply_block_transactions(
+            &block.header,
+            block.block_transactions.transactions.as_slice(),
+        )
+    }
+
+    /// Apply the block given as the serialized header and transactions (e.g. received from the
+    /// p2p network)
+    pub fn apply_serialized_block(
+        &mut self,
+        header_bytes: &[u8],
+        transactions_bytes: &[Vec<u8>],
+    ) -> Result<BlockScanResult, WalletScannerError> {
+        let header = Header::scorex_parse_bytes(header_bytes).map_err(SigmaParsingError::from)?;
+        let transactions = transactions_bytes
+            .iter()
+            .map(|bytes| Transaction::sigma_parse_bytes(bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.apply_block_transactions(&header, &transactions)
+    }
+
+    /// Apply the block transactions: track the new boxes guarded by the tracked scripts and mark
+    /// the spent ones. The block should be the child of the last applied block (any block is
+    /// accepted as the first one).
+    pub fn apply_block_transactions(
+        &mut self,
+        header: &Header,
+        transactions: &[Transaction],
+    ) -> Result<BlockScanResult, WalletScannerError> {
+        self.check_connected(header)?;
+        let mut changes = BlockScanResult::default();
+        for tx in transactions {
+            let tx_id = tx.id();
+            for input in tx.inputs.iter() {
+                self.mark_spent(input.box_id, tx_id, header, &mut changes);
+            }
+            for output in tx.outputs.iter() {
+                if self.is_tracked_script(&output.ergo_tree) {
+                    self.track_box(output.clone(), header, &mut changes);
+                }
+            }
+        }
+        Ok(self.push_applied(header, changes))
+    }
+
+    /// Same as [`WalletScanner::apply_block_transactions`] for the zero-copy transaction views:
+    /// outputs are matched by the serialized guarding script and only the tracked ones are
+    /// parsed, with the guarding script taken from `tree_cache`
+    pub fn apply_transaction_views(
+        &mut self,
+        header: &Header,
+        transactions: &[TransactionView],
+        tree_cache: &mut ErgoTreeCache,
+    ) -> Result<BlockScanResult, WalletScannerError> {
+        self.check_connected(header)?;
+        let mut changes = BlockScanResult::default();
+        for tx in transactions {
+            let tx_id = tx.id();
+            for input in tx.inputs() {
+                self.mark_spent(input.box_id(), tx_id, header, &mut changes);
+            }
+            for output in tx.outputs() {
+                if self.scripts.contains(output.ergo_tree_bytes()) {
+                    let ergo_tree = tree_cache.get_or_parse(output.ergo_tree_bytes())?;
+                    let ergo_box = output.to_ergo_box_with_tree(ergo_tree.as_ref().clone())?;
+                    self.track_box(ergo_box, header, &mut changes);
+                }
+            }
+        }
+        Ok(self.push_applied(header, changes))
+    }
+
+    fn check_connected(&self, header: &Header) -> Result<(), WalletScannerError> {
+        if let Some((tip_id, _)) = self.tip() {
+            if header.parent_id != tip_id {
+                return Err(WalletScannerError::NotConnected {
+                    block_id: header.id,
+                    parent_id: header.parent_id,
+                    tip_id,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn mark_spent(
+        &mut self,
+        box_id: BoxId,
+        tx_id: TxId,
+        header: &Header,
+        changes: &mut BlockScanResult,
+    ) {
+        if let Some(tracked) = self.boxes.get_mut(&box_id) {
+            if tracked.spent.is_none() {
+                tracked.spent = Some(SpendingInfo {
+                    tx_id,
+                    block_id: header.id,
+                    height: header.height,
+                });
+                changes.spent.push(box_id);
+            }
+        }
+    }
+
+    fn track_box(&mut self, ergo_box: ErgoBox, header: &Header, changes: &mut BlockScanResult) {
+        changes.received.push(ergo_box.box_id());
+        self.boxes.insert(
+            ergo_box.box_id(),
+            TrackedBox {
+                ergo_box,
+                block_id: header.id,
+                inclusion_height: header.height,
+                spent: None,
+            },
+        );
+    }
+
+    fn push_applied(&mut self, header: &Header, changes: BlockScanResult) -> BlockScanResult {
+        self.applied.push_back(AppliedBlock {
+            id: header.id,
+            height: header.height,
+            changes: changes.clone(),
+        });
+        while self.applied.len() > self.max_rollback_depth.max(1) {
+            self.applied.pop_front();
+        }
+        changes
+    }
+
+    /// Undo the last applied block: forget the boxes created in it and mark the boxes spent in it
+    /// as unspent. Returns the id of the removed block.
+    pub fn rollback_last(&mut self) -> Option<BlockId> {
+        let block = self.applied.pop_back()?;
+        for box_id in &block.changes.received {
+            self.boxes.remove(box_id);
+        }
+        for box_id in &block.changes.spent {
+            if let Some(tracked) = self.boxes.get_mut(box_id) {
+                tracked.spent = None;
+            }
+        }
+        Some(block.id)
+    }
+
+    /// Undo the blocks applied after the given one. Returns the ids of the removed blocks,
+    /// starting from the old tip.
+    pub fn rollback_to(&mut self, block_id: &BlockId) -> Result<Vec<BlockId>, WalletScannerError> {
+        if !self.applied.iter().any(|b| &b.id == block_id) {
+            return Err(WalletScannerError::UnknownBlock(*block_id));
+        }
+        let mut removed = Vec::new();
+        while self.tip().map(|(id, _)| &id != block_id).unwrap_or(false) {
+            removed.extend(self.rollback_last());
+        }
+        Ok(removed)
+    }
+
+    /// Forget the boxes spent at least `min_confirmations` blocks ago. The boxes spent in the
+    /// kept applied blocks (see `max_rollback_depth`) are never pruned, so that a rollback can
+    /// unspend them.
+    pub fn prune_spent(&mut self, min_confirmations: u32) {
+        let tip_height = self.tip().map(|(_, h)| h).unwrap_or(0);
+        let rollback_depth = self.applied.len() as u32;
+        let depth = min_confirmations.max(rollback_depth + 1);
+        self.boxes.retain(|_, b| match &b.spent {
+            Some(s) => (tip_height + 1).saturating_sub(s.height) < depth,
+            None => true,
+        });
+    }
+}
+
+#[cfg(feature = "rest")]
+mod rest {
+    use ergo_rest::api::node::get_blocks_at_height;
+    use ergo_rest::api::node::get_full_block;
+    use ergo_rest::api::node::get_info;
+    use ergo_rest::NodeConf;
+    use ergo_rest::NodeError;
+
+    use super::*;
+
+    /// Errors on syncing the scanner with the node
+    #[derive(Error, Debug)]
+    pub enum WalletSyncError {
+        /// Node request failed
+        #[error("Node error: {0}")]
+        NodeError(#[from] NodeError),
+        /// Scanner error
+        #[error("Scanner error: {0}")]
+        ScannerError(#[from] WalletScannerError),
+        /// Block is missing on the node
+        #[error("No block at height {0}")]
+        BlockNotFound(u32),
+        /// Chain reorganization is deeper than the kept applied blocks
+        #[error("No common block found with the node's chain")]
+        NoCommonBlock,
+    }
+
+    impl WalletScanner {
+        /// Apply the node's best chain blocks up to its current height, starting after the last
+        /// applied block (or at `start_height` on the first sync). Blocks no longer in the
+        /// node's best chain are rolled back first.
+        pub async fn sync_with_node(
+            &mut self,
+            node: NodeConf,
+            start_height: u32,
+        ) -> Result<Vec<BlockScanResult>, WalletSyncError> {
+            while let Some((tip_id, tip_height)) = self.tip() {
+                if get_blocks_at_height(node, tip_height)
+                    .await?
+                    .first()
+                    .map(|id| id == &tip_id)
+                    .unwrap_or(false)
+                {
+                    break;
+                }
+                self.rollback_last();
+                if self.tip().is_none() {
+                    return Err(WalletSyncError::NoCommonBlock);
+                }
+            }
+            let from = self.tip().map(|(_, h)| h + 1).unwrap_or(start_height);
+            let to = get_info(node).await?.full_height.unwrap_or(0);
+            let mut results = Vec::new();
+            for height in from..=to {
+                let block_id = *get_blocks_at_height(node, height)
+                    .await?
+                    .first()
+                    .ok_or(WalletSyncError::BlockNotFound(height))?;
+                let block: FullBlock = get_full_block(node, block_id)
+                    .await?
+                    .ok_or(WalletSyncError::BlockNotFound(height))?;
+                results.push(self.apply_block(&block)?);
+            }
+            Ok(results)
+        }
+    }
+}
+
+#[cfg(feature = "rest")]
+pub use rest::WalletSyncError;
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use ergo_chain_types::Digest32;
+    use ergotree_interpreter::sigma_protocol::private_input::DlogProverInput;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+    use crate::chain::transaction::input::UnsignedInput;
+    use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+
+    use super::*;
+
+    fn header(height: u32, parent: &Header) -> Header {
+        Header {
+            id: BlockId(Digest32::from([height as u8; 32])),
+            parent_id: parent.id,
+            height,
+            ..parent.clone()
+        }
+    }
+
+    fn output(tree: &ErgoTree) -> ErgoBoxCandidate {
+        ErgoBoxCandidateBuilder::new(BoxValue::SAFE_USER_MIN, tree.clone(), 0)
+            .build()
+            .unwrap()
+    }
+
+    fn tx(inputs: &[BoxId], outputs: Vec<ErgoBoxCandidate>) -> Transaction {
+        let inputs = inputs
+            .iter()
+            .map(|id| UnsignedInput::new(*id, ContextExtension::empty()).input_to_sign())
+            .collect();
+        Transaction::new_from_vec(inputs, vec![], outputs).unwrap()
+    }
+
+    #[test]
+    fn test_scan_spend_and_rollback() {
+        let mine = Address::P2Pk(force_any_val::<DlogProverInput>().public_image());
+        let other = Address::P2Pk(force_any_val::<DlogProverInput>().public_image())
+            .script()
+            .unwrap();
+        let mut scanner = WalletScanner::new(10);
+        scanner.track_address(&mine).unwrap();
+        let mine = mine.script().unwrap();
+
+        let genesis = force_any_val::<Header>();
+        let h1 = header(1, &genesis);
+        let tx1 = tx(
+            &[force_any_val::<BoxId>()],
+            vec![output(&mine), output(&other)],
+        );
+        let my_box = tx1.outputs.first().box_id();
+        let res = scanner.apply_block_transactions(&h1, &[tx1]).unwrap();
+        assert_eq!(res.received, vec![my_box]);
+        assert_eq!(scanner.unspent_boxes(1).len(), 1);
+        assert_eq!(scanner.unspent_boxes(2).len(), 0);
+
+        // block not connected to the tip
+        assert!(matches!(
+            scanner.apply_block_transactions(&header(3, &genesis), &[]),
+            Err(WalletScannerError::NotConnected { .. })
+        ));
+
+        let h2 = header(2, &h1);
+        let tx2 = tx(&[my_box], vec![output(&other)]);
+        let res = scanner.apply_block_transactions(&h2, &[tx2]).unwrap();
+        assert_eq!(res.spent, vec![my_box]);
+        assert!(scanner.get(&my_box).unwrap().is_spent());
+        assert!(scanner.unspent_boxes(0).is_empty());
+
+        assert_eq!(scanner.rollback_to(&h1.id).unwrap(), vec![h2.id]);
+        assert!(!scanner.get(&my_box).unwrap().is_spent());
+        assert_eq!(scanner.rollback_last(), Some(h1.id));
+        assert!(scanner.get(&my_box).is_none());
+        assert_eq!(scanner.tip(), None);
+        assert!(matches!(
+            scanner.rollback_to(&h1.id),
+            Err(WalletScannerError::UnknownBlock(_))
+        ));
+    }
+
+    #[test]
+    fn test_prune_spent() {
+        let mine = Address::P2Pk(force_any_val::<DlogProverInput>().public_image());
+        let mut scanner = WalletScanner::new(2);
+        scanner.track_address(&mine).unwrap();
+        let mine = mine.script().unwrap();
+        let h1 = header(1, &force_any_val::<Header>());
+        let tx1 = tx(&[force_any_val::<BoxId>()], vec![output(&mine)]);
+        let my_box = tx1.outputs.first().box_id();
+        scanner.apply_block_transactions(&h1, &[tx1]).unwrap();
+        let h2 = header(2, &h1);
+        scanner
+            .apply_block_transactions(&h2, &[tx(&[my_box], vec![output(&mine)])])
+            .unwrap();
+        scanner.prune_spent(2);
+        assert!(scanner.get(&my_box).is_some());
+        let h3 = header(3, &h2);
+        scanner.apply_block_transactions(&h3, &[]).unwrap();
+        // spent in the kept applied block, can be unspent by a rollback
+        scanner.prune_spent(2);
+        assert!(scanner.get(&my_box).is_some());
+        scanner
+            .apply_block_transactions(&header(4, &h3), &[])
+            .unwrap();
+        scanner.prune_spent(2);
+        assert!(scanner.get(&my_box).is_none());
+        assert_eq!(scanner.unspent_boxes(0).len(), 1);
+    }
+
+    #[test]
+    fn test_apply_serialized_block_parse_error() {
+        let mut scanner = WalletScanner::new(10);
+        assert!(matches!(
+            scanner.apply_serialized_block(&[1, 2, 3], &[]),
+            Err(WalletScannerError::SigmaParsingError(_))
+        ));
+        assert_eq!(scanner.tip(), None);
+    }
+
+    #[test]
+    fn test_apply_transaction_views() {
+        let mine = Address::P2Pk(force_any_val::<DlogProverInput>().public_image());
+        let other = Address::P2Pk(force_any_val::<DlogProverInput>().public_image())
+            .script()
+            .unwrap();
+        let mut scanner = WalletScanner::new(10);
+        scanner.track_address(&mine).unwrap();
+        let mine = mine.script().unwrap();
+        let mut by_views = scanner.clone();
+        let mut tree_cache = ErgoTreeCache::new(4);
+
+        let h1 = header(1, &force_any_val::<Header>());
+        let tx1 = tx(
+            &[force_any_val::<BoxId>()],
+            vec![output(&mine), output(&other), output(&mine)],
+        );
+        let my_box = tx1.outputs.first().box_id();
+        let h2 = header(2, &h1);
+        let tx2 = tx(&[my_box], vec![output(&other)]);
+        for (h, tx) in [(h1, tx1), (h2, tx2)] {
+            let view =
+                TransactionView::sigma_parse_bytes(tx.sigma_serialize_bytes().unwrap().into())
+                    .unwrap();
+            assert_eq!(
+                by_views
+                    .apply_transaction_views(&h, &[view], &mut tree_cache)
+                    .unwrap(),
+                scanner.apply_block_transactions(&h, &[tx]).unwrap()
+            );
+        }
+        assert_eq!(by_views, scanner);
+        assert!(by_views.get(&my_box).unwrap().is_spent());
+    }
+}
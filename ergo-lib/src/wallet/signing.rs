@@ -150,7 +150,7 @@ pub fn make_context<T: ErgoTransaction>(
         .spending_tx
         .context_extension(self_index)
         .ok_or(TransactionError::InputNofFound(self_index))?;
-    Ok(Context {
+    let ctx = Context {
         height,
         self_box: self_box_ir,
         outputs: outputs_ir,
@@ -159,7 +159,21 @@ pub fn make_context<T: ErgoTransaction>(
         pre_header: state_ctx.pre_header.clone(),
         extension,
         headers: state_ctx.headers.clone(),
-    })
+    };
+    ctx.validate()?;
+    Ok(ctx)
+}
+
+/// Signed transaction with the cost of its inputs: the script reduction and the proof verification
+/// (the same as the verification cost, see [`crate::chain::transaction::InputVerificationResult::cost`])
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SignedTxWithCost {
+    /// Signed transaction
+    pub tx: Transaction,
+    /// Reduction and proof verification cost of each input (in the order of inputs)
+    pub input_costs: Vec<u64>,
+    /// Total reduction and proof verification cost of the inputs
+    pub total_cost: u64,
 }
 
 /// Signs a transaction (generating proofs for inputs)
@@ -169,10 +183,22 @@ pub fn sign_transaction(
     state_context: &ErgoStateContext,
     tx_hints: Option<&TransactionHintsBag>,
 ) -> Result<Transaction, TxSigningError> {
+    sign_transaction_with_cost(prover, tx_context, state_context, tx_hints).map(|res| res.tx)
+}
+
+/// Signs a transaction (generating proofs for inputs) returning the reduction and proof verification
+/// cost of the inputs, e.g. to check it against the block cost limit before broadcasting
+pub fn sign_transaction_with_cost(
+    prover: &dyn Prover,
+    tx_context: TransactionContext<UnsignedTransaction>,
+    state_context: &ErgoStateContext,
+    tx_hints: Option<&TransactionHintsBag>,
+) -> Result<SignedTxWithCost, TxSigningError> {
     let tx = tx_context.spending_tx.clone();
     let message_to_sign = tx.bytes_to_sign()?;
+    let tx_id = tx.id();
     let signed_inputs = tx.inputs.enumerated().try_mapped(|(idx, _)| {
-        sign_tx_input(
+        sign_tx_input_with_cost(
             prover,
             &tx_context,
             state_context,
@@ -181,11 +207,21 @@ pub fn sign_transaction(
             message_to_sign.as_slice(),
         )
     })?;
-    Ok(Transaction::new(
-        signed_inputs,
+    let input_costs: Vec<u64> = signed_inputs.iter().map(|(_, cost)| *cost).collect();
+    let total_cost = input_costs
+        .iter()
+        .fold(0u64, |acc, c| acc.saturating_add(*c));
+    let tx = Transaction::with_tx_id(
+        tx_id,
+        signed_inputs.mapped(|(input, _)| input),
         tx.data_inputs,
         tx.output_candidates,
-    )?)
+    )?;
+    Ok(SignedTxWithCost {
+        tx,
+        input_costs,
+        total_cost,
+    })
 }
 
 /// Signs a reduced transaction (generating proofs for inputs)
@@ -196,6 +232,7 @@ pub fn sign_reduced_transaction(
 ) -> Result<Transaction, TxSigningError> {
     let tx = reduced_tx.unsigned_tx.clone();
     let message_to_sign = tx.bytes_to_sign()?;
+    let tx_id = tx.id();
     let signed_inputs = tx.inputs.enumerated().try_mapped(|(idx, input)| {
         let inputs = reduced_tx.reduced_inputs();
 
@@ -219,7 +256,8 @@ pub fn sign_reduced_transaction(
             .map(|proof| Input::new(input.box_id, proof.into()))
             .map_err(|e| TxSigningError::ProverError(e, idx))
     })?;
-    Ok(Transaction::new(
+    Ok(Transaction::with_tx_id(
+        tx_id,
         signed_inputs,
         tx.data_inputs,
         tx.output_candidates,
@@ -247,6 +285,26 @@ pub fn sign_tx_input(
     input_idx: usize,
     message_to_sign: &[u8],
 ) -> Result<Input, TxSigningError> {
+    sign_tx_input_with_cost(
+        prover,
+        tx_context,
+        state_context,
+        tx_hints,
+        input_idx,
+        message_to_sign,
+    )
+    .map(|(input, _)| input)
+}
+
+/// Sign a transaction input, returning the signed input and the reduction and proof verification cost
+pub fn sign_tx_input_with_cost(
+    prover: &dyn Prover,
+    tx_context: &TransactionContext<UnsignedTransaction>,
+    state_context: &ErgoStateContext,
+    tx_hints: Option<&TransactionHintsBag>,
+    input_idx: usize,
+    message_to_sign: &[u8],
+) -> Result<(Input, u64), TxSigningError> {
     let unsigned_input = tx_context
         .spending_tx
         .inputs
@@ -261,14 +319,14 @@ pub fn sign_tx_input(
         hints_bag = bag.all_hints_for_input(input_idx);
     }
     prover
-        .prove(
+        .prove_with_cost(
             &input_box.ergo_tree,
             &Env::empty(),
             ctx,
             message_to_sign,
             &hints_bag,
         )
-        .map(|proof| Input::new(unsigned_input.box_id, proof.into()))
+        .map(|(proof, cost)| (Input::new(unsigned_input.box_id, proof.into()), cost))
         .map_err(|e| TxSigningError::ProverError(e, input_idx))
 }
 
@@ -297,6 +355,7 @@ mod tests {
     use sigma_test_util::force_any_val;
 
     use crate::chain::transaction::reduced::reduce_tx;
+    use crate::chain::transaction::verify_transaction;
     use crate::chain::transaction::DataInput;
     use crate::chain::{
         ergo_box::box_builder::ErgoBoxCandidateBuilder, transaction::UnsignedInput,
@@ -363,9 +422,26 @@ mod tests {
             let tx = UnsignedTransaction::new_from_vec(inputs, vec![], output_candidates).unwrap();
             let tx_context = TransactionContext::new(tx, boxes_to_spend.clone(), vec![]).unwrap();
             let tx_hint_bag=TransactionHintsBag::empty();
-            let res = sign_transaction(prover.as_ref(), tx_context.clone(), &force_any_val::<ErgoStateContext>(), Some(&tx_hint_bag));
-            let signed_tx = res.unwrap();
+            let state_context = force_any_val::<ErgoStateContext>();
+            let res = sign_transaction_with_cost(prover.as_ref(), tx_context.clone(), &state_context, Some(&tx_hint_bag));
+            let SignedTxWithCost { tx: signed_tx, input_costs, total_cost } = res.unwrap();
+            prop_assert_eq!(input_costs.len(), signed_tx.inputs.len());
+            prop_assert_eq!(total_cost, input_costs.iter().sum::<u64>());
+            // P2PK inputs: interpreter setup, root constant and the ProveDlog verification
+            prop_assert!(input_costs.iter().all(|c| *c == 1 + 1 + 10));
             prop_assert!(verify_tx_proofs(&signed_tx, &boxes_to_spend).unwrap());
+            // the same cost as charged by the verification
+            let verified = verify_transaction(
+                &TransactionContext::new(signed_tx.clone(), boxes_to_spend.clone(), vec![]).unwrap(),
+                &state_context,
+            ).unwrap();
+            prop_assert!(verified.is_valid());
+            prop_assert_eq!(verified.inputs.iter().map(|i| i.cost).collect::<Vec<_>>(), input_costs);
+            prop_assert_eq!(verified.total_cost, total_cost);
+            // the id of the signed tx is not re-calculated, but must match the one calculated from scratch
+            let recalculated = Transaction::new(signed_tx.inputs.clone(), signed_tx.data_inputs.clone(), signed_tx.output_candidates.clone()).unwrap();
+            prop_assert_eq!(signed_tx.id(), recalculated.id());
+            prop_assert_eq!(signed_tx.outputs(), recalculated.outputs());
             let reduced_tx = reduce_tx(tx_context, &force_any_val::<ErgoStateContext>()).unwrap();
             let signed_reduced_tx = sign_reduced_transaction(prover.as_ref(), reduced_tx,None).unwrap();
             prop_assert!(verify_tx_proofs(&signed_reduced_tx, &boxes_to_spend).unwrap());
@@ -0,0 +1,325 @@
+//! Encrypted, versioned snapshot of the wallet state (derived addresses, known boxes, transaction
+//! history cursors) for the applications embedding the wallet.
+//!
+//! The state is stored as JSON encrypted the same way as [`super::keystore`]:
+//! ```json
+//! {
+//!   "version": 1,
+//!   "kdf": { "name": "scrypt", "logN": 15, "r": 8, "p": 1, "salt": "<base16>" },
+//!   "cipher": { "name": "xchacha20poly1305", "nonce": "<base16>" },
+//!   "ciphertext": "<base16>"
+//! }
+//! ```
+//! The decrypted state has its own schema version (`stateVersion`), states saved with the older
+//! schema versions are migrated on decryption.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use ergo_chain_types::BlockId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::tx_id::TxId;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_with::serde_as;
+use serde_with::DisplayFromStr;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use super::derivation_path::DerivationPath;
+use super::keystore::EncryptedPayload;
+use super::keystore::KeystoreError;
+use super::keystore::ScryptParams;
+
+/// Wallet snapshot errors
+#[derive(Error, Debug)]
+pub enum WalletSnapshotError {
+    /// Unsupported snapshot (encryption envelope) format version
+    #[error("Unsupported wallet snapshot version: {0}")]
+    UnsupportedVersion(u32),
+    /// Wallet state schema version is unknown (see [`WALLET_STATE_VERSION`])
+    #[error("Unsupported wallet state version: {0}")]
+    UnsupportedStateVersion(u32),
+    /// Invalid wallet state
+    #[error("Invalid wallet state: {0}")]
+    InvalidState(String),
+    /// Encryption or decryption error
+    #[error("Keystore error: {0}")]
+    KeystoreError(#[from] KeystoreError),
+    /// Invalid JSON (snapshot or decrypted state)
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    /// File IO error
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// Current snapshot (encryption envelope) format version
+pub const WALLET_SNAPSHOT_VERSION: u32 = 1;
+
+/// Current wallet state schema version
+pub const WALLET_STATE_VERSION: u32 = 1;
+
+/// Migrations of the state JSON (`{"stateVersion": .., "state": {..}}`), the migration at index `i`
+/// upgrades the state from the schema version `i + 1` to `i + 2`
+const STATE_MIGRATIONS: &[StateMigration] = &[];
+
+type StateMigration = fn(serde_json::Value) -> Result<serde_json::Value, WalletSnapshotError>;
+
+/// Address derived by the wallet
+#[serde_as]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct DerivedAddress {
+    /// Derivation path
+    #[serde_as(as = "DisplayFromStr")]
+    pub path: DerivationPath,
+    /// Encoded address
+    pub address: String,
+}
+
+/// Position of the transaction history scan (e.g. for an address or a scan)
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TxHistoryCursor {
+    /// Height of the last processed block
+    pub height: u32,
+    /// Id of the last processed block (to detect the rollbacks)
+    #[serde(rename = "blockId", default, skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<BlockId>,
+    /// Last processed transaction in the block
+    #[serde(rename = "lastTxId", default, skip_serializing_if = "Option::is_none")]
+    pub last_tx_id: Option<TxId>,
+}
+
+/// Wallet state
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct WalletState {
+    /// Derived addresses
+    #[serde(rename = "derivedAddresses", default)]
+    pub derived_addresses: Vec<DerivedAddress>,
+    /// Next address index to derive for the account index
+    #[serde(rename = "nextAddressIndices", default)]
+    pub next_address_indices: BTreeMap<u32, u32>,
+    /// Boxes known to the wallet (e.g. unspent boxes of the derived addresses)
+    #[serde(rename = "knownBoxes", default)]
+    pub known_boxes: Vec<ErgoBox>,
+    /// Transaction history cursors by the name chosen by the application (e.g. address)
+    #[serde(rename = "txHistoryCursors", default)]
+    pub tx_history_cursors: BTreeMap<String, TxHistoryCursor>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VersionedState {
+    #[serde(rename = "stateVersion")]
+    state_version: u32,
+    state: WalletState,
+}
+
+/// Encrypted wallet state, see module docs for the format
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct WalletSnapshot {
+    version: u32,
+    #[serde(flatten)]
+    payload: EncryptedPayload,
+}
+
+impl WalletSnapshot {
+    /// Encrypt the state with the password
+    pub fn encrypt(
+        state: &WalletState,
+        password: &str,
+        params: ScryptParams,
+    ) -> Result<Self, WalletSnapshotError> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(&VersionedState {
+            state_version: WALLET_STATE_VERSION,
+            state: state.clone(),
+        })?);
+        Ok(WalletSnapshot {
+            version: WALLET_SNAPSHOT_VERSION,
+            payload: EncryptedPayload::encrypt(plaintext.as_slice(), password, params)?,
+        })
+    }
+
+    /// Decrypt the state with the password, migrating it to the current schema version
+    pub fn decrypt(&self, password: &str) -> Result<WalletState, WalletSnapshotError> {
+        if self.version != WALLET_SNAPSHOT_VERSION {
+            return Err(WalletSnapshotError::UnsupportedVersion(self.version));
+        }
+        let plaintext = self.payload.decrypt(password)?;
+        let json: serde_json::Value = serde_json::from_slice(plaintext.as_slice())?;
+        let versioned: VersionedState = serde_json::from_value(migrate_state(json)?)?;
+        Ok(versioned.state)
+    }
+
+    /// Format version
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// scrypt parameters used for the key derivation
+    pub fn kdf_params(&self) -> ScryptParams {
+        self.payload.kdf_params()
+    }
+
+    /// Encode as JSON
+    pub fn to_json(&self) -> Result<String, WalletSnapshotError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse from JSON
+    pub fn from_json(json: &str) -> Result<Self, WalletSnapshotError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Save as JSON to the file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), WalletSnapshotError> {
+        Ok(std::fs::write(path, self.to_json()?)?)
+    }
+
+    /// Load from the JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, WalletSnapshotError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// Upgrade the state JSON to the current schema version
+fn migrate_state(mut json: serde_json::Value) -> Result<serde_json::Value, WalletSnapshotError> {
+    let version = json
+        .get("stateVersion")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| WalletSnapshotError::InvalidState("missing state version".to_string()))?;
+    let version = u32::try_from(version).map_err(|_| {
+        WalletSnapshotError::InvalidState(format!("invalid state version: {}", version))
+    })?;
+    if version == 0 || version > WALLET_STATE_VERSION {
+        return Err(WalletSnapshotError::UnsupportedStateVersion(version));
+    }
+    for migration in STATE_MIGRATIONS.iter().skip((version - 1) as usize) {
+        json = migration(json)?;
+    }
+    if let Some(obj) = json.as_object_mut() {
+        obj.insert(
+            "stateVersion".to_string(),
+            serde_json::Value::from(WALLET_STATE_VERSION),
+        );
+    }
+    Ok(json)
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergo_chain_types::Digest32;
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+
+    // low cost parameters to keep the tests fast
+    const TEST_PARAMS: ScryptParams = ScryptParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    fn state() -> WalletState {
+        let mut state = WalletState {
+            derived_addresses: vec![DerivedAddress {
+                path: DerivationPath::master_path(),
+                address: "9gmNsqrqdSppLUBqg2UzREmmivgqh1r3jmNcLAc53hk3YCvAGWE".to_string(),
+            }],
+            known_boxes: vec![force_any_val::<ErgoBox>()],
+            ..Default::default()
+        };
+        state.next_address_indices.insert(0, 1);
+        state.tx_history_cursors.insert(
+            "9gmNsqrqdSppLUBqg2UzREmmivgqh1r3jmNcLAc53hk3YCvAGWE".to_string(),
+            TxHistoryCursor {
+                height: 1000,
+                block_id: Some(BlockId(force_any_val::<Digest32>())),
+                last_tx_id: Some(force_any_val::<TxId>()),
+            },
+        );
+        state
+    }
+
+    #[test]
+    fn roundtrip() {
+        let state = state();
+        let snapshot = WalletSnapshot::encrypt(&state, "password", TEST_PARAMS).unwrap();
+        let parsed = WalletSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, snapshot);
+        assert_eq!(parsed.version(), WALLET_SNAPSHOT_VERSION);
+        assert_eq!(parsed.decrypt("password").unwrap(), state);
+        assert!(matches!(
+            parsed.decrypt("wrong"),
+            Err(WalletSnapshotError::KeystoreError(
+                KeystoreError::DecryptionFailed
+            ))
+        ));
+    }
+
+    #[test]
+    fn migrate() {
+        // missing optional fields
+        let json = serde_json::json!({ "stateVersion": 1, "state": {} });
+        let state: VersionedState = serde_json::from_value(migrate_state(json).unwrap()).unwrap();
+        assert_eq!(state.state, WalletState::default());
+        assert!(matches!(
+            migrate_state(serde_json::json!({ "stateVersion": WALLET_STATE_VERSION + 1 })),
+            Err(WalletSnapshotError::UnsupportedStateVersion(v)) if v == WALLET_STATE_VERSION + 1
+        ));
+        assert!(matches!(
+            migrate_state(serde_json::json!({ "stateVersion": 0 })),
+            Err(WalletSnapshotError::UnsupportedStateVersion(0))
+        ));
+        assert!(matches!(
+            migrate_state(serde_json::json!({})),
+            Err(WalletSnapshotError::InvalidState(_))
+        ));
+        assert!(matches!(
+            migrate_state(serde_json::json!({ "stateVersion": u64::from(u32::MAX) + 1 })),
+            Err(WalletSnapshotError::InvalidState(_))
+        ));
+        // every older schema version has a migration to the next one
+        assert_eq!(STATE_MIGRATIONS.len() as u32, WALLET_STATE_VERSION - 1);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let snapshot = WalletSnapshot::encrypt(&state(), "password", TEST_PARAMS).unwrap();
+        let mut json: serde_json::Value =
+            serde_json::from_str(&snapshot.to_json().unwrap()).unwrap();
+        json["version"] = serde_json::Value::from(WALLET_SNAPSHOT_VERSION + 1);
+        let err = WalletSnapshot::from_json(&json.to_string())
+            .unwrap()
+            .decrypt("password")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Unsupported wallet snapshot version: {}",
+                WALLET_SNAPSHOT_VERSION + 1
+            )
+        );
+
+        // state saved by a newer version of the library
+        let plaintext = serde_json::json!({
+            "stateVersion": WALLET_STATE_VERSION + 1,
+            "state": {}
+        })
+        .to_string();
+        let snapshot = WalletSnapshot {
+            version: WALLET_SNAPSHOT_VERSION,
+            payload: EncryptedPayload::encrypt(plaintext.as_bytes(), "password", TEST_PARAMS)
+                .unwrap(),
+        };
+        let err = snapshot.decrypt("password").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Unsupported wallet state version: {}",
+                WALLET_STATE_VERSION + 1
+            )
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Sorting and grouping of the token holdings for the wallet UIs. The ordering is total (ties are
+//! broken by token id), so the result does not depend on the order of the given tokens.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenAmountError;
+use ergotree_ir::chain::token::TokenId;
+
+use super::box_selector::sum_tokens;
+
+/// Token info for display (e.g. from EIP-4 token issuing box registers)
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TokenInfo {
+    /// Token name
+    pub name: String,
+    /// Number of decimals
+    pub decimals: u8,
+}
+
+/// Resolves the token info by token id
+pub trait TokenInfoResolver {
+    /// Token info, `None` if unknown
+    fn token_info(&self, token_id: &TokenId) -> Option<TokenInfo>;
+}
+
+impl TokenInfoResolver for HashMap<TokenId, TokenInfo> {
+    fn token_info(&self, token_id: &TokenId) -> Option<TokenInfo> {
+        self.get(token_id).cloned()
+    }
+}
+
+/// Token amount with the given number of decimals (`amount = 150, decimals = 2` is 1.50),
+/// compared by the value
+#[derive(Debug, Clone, Copy)]
+pub struct DecimalAmount {
+    /// Raw token amount
+    pub amount: u64,
+    /// Number of decimals
+    pub decimals: u8,
+}
+
+impl DecimalAmount {
+    /// Raw amount with the given number of decimals
+    pub fn new(amount: u64, decimals: u8) -> Self {
+        DecimalAmount { amount, decimals }
+    }
+
+    /// Amount of the token with the decimals from the resolver (no decimals if unknown)
+    pub fn of_token<R: TokenInfoResolver + ?Sized>(token: &Token, resolver: &R) -> Self {
+        let decimals = resolver
+            .token_info(&token.token_id)
+            .map(|info| info.decimals)
+            .unwrap_or(0);
+        DecimalAmount::new(*token.amount.as_u64(), decimals)
+    }
+}
+
+impl PartialEq for DecimalAmount {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DecimalAmount {}
+
+impl PartialOrd for DecimalAmount {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DecimalAmount {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // zero is the same with any decimals (and scaling it up never overflows)
+        if self.amount == 0 || other.amount == 0 {
+            return self.amount.cmp(&other.amount);
+        }
+        // scale the non-zero amount with fewer decimals up, overflow (of either the power of ten
+        // or the product) means it's larger than any u64
+        let scale = |amount: u64, shift: u8| -> Option<u128> {
+            10u128
+                .checked_pow(shift as u32)
+                .and_then(|m| (amount as u128).checked_mul(m))
+        };
+        match self.decimals.cmp(&other.decimals) {
+            Ordering::Equal => self.amount.cmp(&other.amount),
+            Ordering::Less => match scale(self.amount, other.decimals - self.decimals) {
+                Some(scaled) => scaled.cmp(&(other.amount as u128)),
+                None => Ordering::Greater,
+            },
+            Ordering::Greater => match scale(other.amount, self.decimals - other.decimals) {
+                Some(scaled) => (self.amount as u128).cmp(&scaled),
+                None => Ordering::Less,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for DecimalAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.amount.to_string();
+        let decimals = self.decimals as usize;
+        if decimals == 0 {
+            write!(f, "{}", digits)
+        } else if digits.len() > decimals {
+            let (int, frac) = digits.split_at(digits.len() - decimals);
+            write!(f, "{}.{}", int, frac)
+        } else {
+            write!(f, "0.{:0>width$}", digits, width = decimals)
+        }
+    }
+}
+
+/// Token list sort key
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TokenSortKey {
+    /// Token id bytes
+    Id,
+    /// Resolved token name (case-insensitive), tokens without a name go last
+    Name,
+    /// Amount taking the token decimals into account
+    Amount,
+}
+
+/// Sort direction (applies to the sort key, ties are always broken by ascending token id)
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SortDirection {
+    /// Smallest first
+    Ascending,
+    /// Largest first
+    Descending,
+}
+
+/// Compare tokens by the given key
+pub fn compare_tokens<R: TokenInfoResolver + ?Sized>(
+    a: &Token,
+    b: &Token,
+    key: TokenSortKey,
+    direction: SortDirection,
+    resolver: &R,
+) -> Ordering {
+    let by_key = match key {
+        TokenSortKey::Id => Ordering::Equal,
+        TokenSortKey::Name => {
+            let name = |t: &Token| resolver.token_info(&t.token_id).map(|info| info.name);
+            match (name(a), name(b)) {
+                (Some(a_name), Some(b_name)) => a_name
+                    .to_lowercase()
+                    .cmp(&b_name.to_lowercase())
+                    .then_with(|| a_name.cmp(&b_name)),
+                // unnamed tokens go last in both directions
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }
+        TokenSortKey::Amount => {
+            DecimalAmount::of_token(a, resolver).cmp(&DecimalAmount::of_token(b, resolver))
+        }
+    };
+    let by_key = match direction {
+        SortDirection::Ascending => by_key,
+        SortDirection::Descending => by_key.reverse(),
+    };
+    by_key
+        .then_with(|| compare_ids(&a.token_id, &b.token_id, key, direction))
+        .then_with(|| a.amount.cmp(&b.amount))
+}
+
+fn compare_ids(a: &TokenId, b: &TokenId, key: TokenSortKey, direction: SortDirection) -> Ordering {
+    let ord = a.as_ref().cmp(b.as_ref());
+    if key == TokenSortKey::Id && direction == SortDirection::Descending {
+        ord.reverse()
+    } else {
+        ord
+    }
+}
+
+/// Sort tokens by the given key
+pub fn sort_tokens<R: TokenInfoResolver + ?Sized>(
+    tokens: &mut [Token],
+    key: TokenSortKey,
+    direction: SortDirection,
+    resolver: &R,
+) {
+    tokens.sort_by(|a, b| compare_tokens(a, b, key, direction, resolver));
+}
+
+/// Sum the amounts of the same tokens (e.g. held in different boxes), sorted by token id
+pub fn group_by_id(tokens: &[Token]) -> Result<Vec<Token>, TokenAmountError> {
+    let mut grouped: Vec<Token> = sum_tokens(Some(tokens))?
+        .into_iter()
+        .map(Token::from)
+        .collect();
+    grouped.sort_by(|a, b| a.token_id.as_ref().cmp(b.token_id.as_ref()));
+    Ok(grouped)
+}
+
+/// Group tokens by the resolved name (case-insensitive), e.g. to highlight different tokens with
+/// the same name. Groups are sorted by name, tokens without a name are in the last group (`None`),
+/// tokens in a group are sorted by token id.
+pub fn group_by_name<R: TokenInfoResolver + ?Sized>(
+    tokens: &[Token],
+    resolver: &R,
+) -> Vec<(Option<String>, Vec<Token>)> {
+    let mut sorted = tokens.to_vec();
+    sort_tokens(
+        &mut sorted,
+        TokenSortKey::Name,
+        SortDirection::Ascending,
+        resolver,
+    );
+    let mut groups: Vec<(Option<String>, Vec<Token>)> = Vec::new();
+    for token in sorted {
+        let name = resolver
+            .token_info(&token.token_id)
+            .map(|info| info.name.to_lowercase());
+        match groups.last_mut() {
+            Some((group_name, group)) if *group_name == name => group.push(token),
+            _ => groups.push((name, vec![token])),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergotree_ir::chain::token::TokenAmount;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn token(token_id: TokenId, amount: u64) -> Token {
+        Token {
+            token_id,
+            amount: TokenAmount::try_from(amount).unwrap(),
+        }
+    }
+
+    #[test]
+    fn decimal_amount_cmp() {
+        assert!(DecimalAmount::new(150, 2) > DecimalAmount::new(1, 0));
+        assert!(DecimalAmount::new(150, 2) < DecimalAmount::new(2, 0));
+        assert_eq!(DecimalAmount::new(100, 2), DecimalAmount::new(1, 0));
+        assert!(DecimalAmount::new(1, 0) > DecimalAmount::new(u64::MAX, 200));
+        assert!(DecimalAmount::new(0, 0) < DecimalAmount::new(1, 200));
+        assert_eq!(DecimalAmount::new(0, 0), DecimalAmount::new(0, 255));
+        assert!(DecimalAmount::new(0, 255) < DecimalAmount::new(1, 0));
+        assert!(DecimalAmount::new(1, 255) > DecimalAmount::new(0, 0));
+        // the power of ten fits into u128, the product doesn't
+        assert!(DecimalAmount::new(u64::MAX, 0) > DecimalAmount::new(u64::MAX, 38));
+        assert!(DecimalAmount::new(u64::MAX, 38) < DecimalAmount::new(u64::MAX, 0));
+    }
+
+    #[test]
+    fn decimal_amount_display() {
+        assert_eq!(DecimalAmount::new(150, 2).to_string(), "1.50");
+        assert_eq!(DecimalAmount::new(5, 3).to_string(), "0.005");
+        assert_eq!(DecimalAmount::new(42, 0).to_string(), "42");
+    }
+
+    #[test]
+    fn sort_and_group() {
+        let (id1, id2, id3) = (
+            TokenId::from([1; 32]),
+            TokenId::from([2; 32]),
+            TokenId::from([3; 32]),
+        );
+        let mut resolver = HashMap::new();
+        resolver.insert(
+            id1,
+            TokenInfo {
+                name: "beta".to_string(),
+                decimals: 2,
+            },
+        );
+        resolver.insert(
+            id2,
+            TokenInfo {
+                name: "Alpha".to_string(),
+                decimals: 0,
+            },
+        );
+        // 1.5 of id1, 1 of id2, 100 of unnamed id3
+        let mut tokens = vec![token(id3, 100), token(id1, 150), token(id2, 1)];
+        sort_tokens(
+            &mut tokens,
+            TokenSortKey::Name,
+            SortDirection::Ascending,
+            &resolver,
+        );
+        let ids: Vec<TokenId> = tokens.iter().map(|t| t.token_id).collect();
+        assert_eq!(ids, vec![id2, id1, id3]);
+        sort_tokens(
+            &mut tokens,
+            TokenSortKey::Amount,
+            SortDirection::Descending,
+            &resolver,
+        );
+        let ids: Vec<TokenId> = tokens.iter().map(|t| t.token_id).collect();
+        assert_eq!(ids, vec![id3, id1, id2]);
+
+        let groups = group_by_name(&tokens, &resolver);
+        assert_eq!(
+            groups.into_iter().map(|(n, _)| n).collect::<Vec<_>>(),
+            vec![Some("alpha".to_string()), Some("beta".to_string()), None]
+        );
+        let grouped = group_by_id(&[token(id1, 1), token(id2, 1), token(id1, 2)]).unwrap();
+        assert_eq!(grouped.len(), 2);
+        assert!(grouped.contains(&token(id1, 3)));
+    }
+
+    proptest! {
+
+        #[test]
+        fn sort_does_not_depend_on_input_order(tokens in vec(any::<Token>(), 0..10), seed in any::<u64>()) {
+            let resolver: HashMap<TokenId, TokenInfo> = tokens
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| i % 3 != 0)
+                .map(|(i, t)| (t.token_id, TokenInfo { name: format!("t{}", i % 2), decimals: (i % 4) as u8 }))
+                .collect();
+            let mut shuffled = tokens.clone();
+            shuffled.rotate_left(if tokens.is_empty() { 0 } else { (seed as usize) % tokens.len() });
+            for key in [TokenSortKey::Id, TokenSortKey::Name, TokenSortKey::Amount] {
+                for direction in [SortDirection::Ascending, SortDirection::Descending] {
+                    let mut a = tokens.clone();
+                    let mut b = shuffled.clone();
+                    sort_tokens(&mut a, key, direction, &resolver);
+                    sort_tokens(&mut b, key, direction, &resolver);
+                    prop_assert_eq![a, b];
+                }
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 use ergotree_interpreter::eval::context::TxIoVec;
 use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+use ergotree_ir::chain::token::sum_token_amounts;
 use ergotree_ir::chain::token::TokenAmount;
 use ergotree_ir::chain::token::TokenAmountError;
 use ergotree_ir::ergo_tree::ErgoTree;
@@ -19,12 +20,14 @@ use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
 use ergotree_ir::chain::token::Token;
 use ergotree_ir::chain::token::TokenId;
 use ergotree_ir::serialization::{SigmaParsingError, SigmaSerializable, SigmaSerializationError};
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
 use thiserror::Error;
 
 use crate::chain::contract::Contract;
 use crate::chain::ergo_box::box_builder::{ErgoBoxCandidateBuilder, ErgoBoxCandidateBuilderError};
 use crate::chain::transaction::unsigned::UnsignedTransaction;
-use crate::chain::transaction::{DataInput, Input, Transaction, UnsignedInput};
+use crate::chain::transaction::{distinct_token_ids, DataInput, Input, Transaction, UnsignedInput};
 
 use super::box_selector::subtract_tokens;
 use super::box_selector::sum_tokens_from_boxes;
@@ -32,6 +35,8 @@ use super::box_selector::sum_value;
 use super::box_selector::BoxSelection;
 use super::box_selector::ErgoBoxAssets;
 use super::box_selector::ErgoBoxId;
+use super::mempool::InputConflict;
+use super::mempool::MempoolSnapshot;
 use super::miner_fee::MINERS_FEE_BASE16_BYTES;
 
 /// Unsigned transaction builder
@@ -45,6 +50,8 @@ pub struct TxBuilder<S: ErgoBoxAssets> {
     change_address: Address,
     context_extensions: HashMap<BoxId, ContextExtension>,
     token_burn_permit: Vec<Token>,
+    mempool_snapshot: Option<MempoolSnapshot>,
+    output_metadata: HashMap<usize, OutputMetadata>,
 }
 
 impl<S: ErgoBoxAssets + ErgoBoxId + Clone> TxBuilder<S> {
@@ -71,6 +78,8 @@ impl<S: ErgoBoxAssets + ErgoBoxId + Clone> TxBuilder<S> {
             change_address,
             context_extensions: HashMap::new(),
             token_burn_permit: Vec::new(),
+            mempool_snapshot: None,
+            output_metadata: HashMap::new(),
         }
     }
 
@@ -138,6 +147,38 @@ impl<S: ErgoBoxAssets + ErgoBoxId + Clone> TxBuilder<S> {
         self.token_burn_permit = tokens;
     }
 
+    /// Reject the inputs already spent by the given mempool transactions on build (the
+    /// transaction would be rejected by the node as a double-spend) with
+    /// [`TxBuilderError::InputsSpentInMempool`]
+    pub fn set_mempool_snapshot(&mut self, mempool_snapshot: MempoolSnapshot) {
+        self.mempool_snapshot = Some(mempool_snapshot);
+    }
+
+    /// Inputs already spent by the given mempool transactions (the transaction would be rejected
+    /// by the node as a double-spend). Exclude such boxes with
+    /// [`MempoolSnapshot::filter_unspent`] and select the inputs again.
+    pub fn mempool_conflicts(&self, mempool_snapshot: &MempoolSnapshot) -> Vec<InputConflict> {
+        mempool_snapshot.conflicts(self.box_selection.boxes.iter().map(|b| b.box_id()))
+    }
+
+    /// Attach the user metadata to the output candidate at `output_index` (in `output_candidates`).
+    /// The metadata is never serialized into the transaction, it's returned in the summary built
+    /// with [`TxBuilder::build_with_summary`].
+    pub fn set_output_metadata(
+        &mut self,
+        output_index: usize,
+        metadata: OutputMetadata,
+    ) -> Result<(), TxBuilderError> {
+        if output_index >= self.output_candidates.len() {
+            return Err(TxBuilderError::InvalidArgs(format!(
+                "output index {} is out of bounds",
+                output_index
+            )));
+        }
+        self.output_metadata.insert(output_index, metadata);
+        Ok(())
+    }
+
     fn build_tx(&self) -> Result<UnsignedTransaction, TxBuilderError> {
         if self.box_selection.boxes.is_empty() {
             return Err(TxBuilderError::InvalidArgs("inputs are empty".to_string()));
@@ -162,6 +203,12 @@ impl<S: ErgoBoxAssets + ErgoBoxId + Clone> TxBuilder<S> {
                 "duplicate inputs found".to_string(),
             ));
         }
+        if let Some(mempool_snapshot) = &self.mempool_snapshot {
+            let conflicts = self.mempool_conflicts(mempool_snapshot);
+            if !conflicts.is_empty() {
+                return Err(TxBuilderError::InputsSpentInMempool(conflicts));
+            }
+        }
         if self.data_inputs.len() > u16::MAX as usize {
             return Err(TxBuilderError::InvalidArgs(
                 "too many data inputs".to_string(),
@@ -224,12 +271,22 @@ impl<S: ErgoBoxAssets + ErgoBoxId + Clone> TxBuilder<S> {
                 "cannot mint more than one token".to_string(),
             ));
         }
-        output_tokens_without_minted
-            .iter()
-            .try_for_each(|(id, amt)| match input_tokens.get(id).cloned() {
-                Some(input_token_amount) if input_token_amount >= *amt => Ok(()),
-                _ => Err(TxBuilderError::NotEnoughTokens(vec![(*id, *amt).into()])),
-            })?;
+        let missing_tokens: Vec<Token> = distinct_token_ids(output_candidates.clone())
+            .into_iter()
+            .filter_map(|id| {
+                let output_amount = output_tokens_without_minted.get(&id)?;
+                match input_tokens.get(&id) {
+                    Some(input_amount) => output_amount
+                        .checked_sub(input_amount)
+                        .ok()
+                        .map(|missing| (id, missing).into()),
+                    None => Some((id, *output_amount).into()),
+                }
+            })
+            .collect();
+        if !missing_tokens.is_empty() {
+            return Err(TxBuilderError::NotEnoughTokens(missing_tokens));
+        }
 
         // check token burn
         let burned_tokens = subtract_tokens(&input_tokens, &output_tokens_without_minted)
@@ -258,6 +315,77 @@ impl<S: ErgoBoxAssets + ErgoBoxId + Clone> TxBuilder<S> {
     pub fn build(self) -> Result<UnsignedTransaction, TxBuilderError> {
         self.build_tx()
     }
+
+    /// Build the unsigned transaction and the summary of its outputs with the attached metadata
+    pub fn build_with_summary(
+        self,
+    ) -> Result<(UnsignedTransaction, TxBuildSummary), TxBuilderError> {
+        let tx = self.build_tx()?;
+        let payments_count = self.output_candidates.len();
+        let change_count = self.box_selection.change_boxes.len();
+        let outputs = tx
+            .output_candidates
+            .iter()
+            .enumerate()
+            .map(|(index, b)| OutputSummary {
+                index,
+                kind: if index < payments_count {
+                    OutputKind::Payment
+                } else if index < payments_count + change_count {
+                    OutputKind::Change
+                } else {
+                    OutputKind::MinerFee
+                },
+                value: b.value,
+                tokens: b.tokens.clone().map(|t| t.to_vec()).unwrap_or_default(),
+                metadata: self.output_metadata.get(&index).cloned(),
+            })
+            .collect();
+        Ok((tx, TxBuildSummary { outputs }))
+    }
+}
+
+/// Opaque user metadata attached to an output in [`TxBuilder`] (e.g. to correlate the built
+/// outputs with the user intent in wallet UIs). Never serialized on-chain.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct OutputMetadata {
+    /// User label (e.g. address book entry name)
+    pub label: Option<String>,
+    /// Purpose tags
+    pub tags: Vec<String>,
+}
+
+/// Kind of the transaction output created by [`TxBuilder`]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum OutputKind {
+    /// Output candidate given to the builder
+    Payment,
+    /// Change output
+    Change,
+    /// Miner fee output
+    MinerFee,
+}
+
+/// Output of the transaction built by [`TxBuilder`]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct OutputSummary {
+    /// Index in the transaction outputs
+    pub index: usize,
+    /// Kind of the output
+    pub kind: OutputKind,
+    /// Value
+    pub value: BoxValue,
+    /// Tokens
+    pub tokens: Vec<Token>,
+    /// Metadata set with [`TxBuilder::set_output_metadata`]
+    pub metadata: Option<OutputMetadata>,
+}
+
+/// Summary of the transaction built by [`TxBuilder::build_with_summary`]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TxBuildSummary {
+    /// Outputs in the transaction order
+    pub outputs: Vec<OutputSummary>,
 }
 
 /// Suggested transaction fee (1100000 nanoERGs, semi-default value used across wallets and dApps as of Oct 2020)
@@ -272,9 +400,10 @@ pub fn new_miner_fee_box(
     fee_amount: BoxValue,
     creation_height: u32,
 ) -> Result<ErgoBoxCandidate, ErgoBoxCandidateBuilderError> {
-    let ergo_tree =
-        ErgoTree::sigma_parse_bytes(base16::decode(MINERS_FEE_BASE16_BYTES).unwrap().as_slice())
-            .unwrap();
+    let ergo_tree = ErgoTree::sigma_parse_bytes(
+        &decode_base16(MINERS_FEE_BASE16_BYTES, DecodingMode::Strict).unwrap(),
+    )
+    .unwrap();
     ErgoBoxCandidateBuilder::new(fee_amount, ergo_tree, creation_height).build()
 }
 
@@ -288,7 +417,8 @@ pub enum TxBuilderError {
     InvalidArgs(String),
     #[error("ErgoBoxCandidateBuilder error: {0}")]
     ErgoBoxCandidateBuilderError(#[from] ErgoBoxCandidateBuilderError),
-    #[error("Not enougn tokens: {0:?}")]
+    /// Tokens (with missing amounts) spent in outputs, but not present in inputs in full
+    #[error("Not enough tokens in inputs: {}", fmt_suggestions(&self.suggestions()))]
     NotEnoughTokens(Vec<Token>),
     #[error("Not enough coins({0} nanoERGs are missing)")]
     NotEnoughCoinsInInputs(u64),
@@ -298,11 +428,11 @@ pub enum TxBuilderError {
     InvalidInputsCount(#[from] BoundedVecOutOfBounds),
     #[error("Empty input box")]
     EmptyInputBoxSelection,
-    #[error("Token burn permit exceeded. Permitted limit: {permit:?}, trying to burn: {try_to_burn:?}. Revisit the input to `set_token_burn_permit()` to increase the limit")]
+    #[error("Token burn permit exceeded. Permitted limit: {permit:?}, trying to burn: {try_to_burn:?}. Suggestions: {}", fmt_suggestions(&self.suggestions()))]
     TokenBurnPermitExceeded { permit: Token, try_to_burn: Token },
-    #[error("Token burn permit is missing. Trying to burn: {try_to_burn:?}. Call `set_token_burn_permit()` to set the limit")]
+    #[error("Token burn permit is missing. Trying to burn: {try_to_burn:?}. Suggestions: {}", fmt_suggestions(&self.suggestions()))]
     TokenBurnPermitMissing { try_to_burn: Token },
-    #[error("Unused token burn permit: token id {token_id:?}, amount {amount:?}")]
+    #[error("Unused token burn permit: token id {token_id:?}, amount {amount:?}. Suggestions: {}", fmt_suggestions(&self.suggestions()))]
     TokenBurnPermitUnused { token_id: TokenId, amount: u64 },
     #[error("Too many tokens in burn permit: {0}")]
     TooManyTokensInBurnPermit(TokenAmountError),
@@ -314,22 +444,146 @@ pub enum TxBuilderError {
     TokensInOutputsExceedInputs(TokenAmountError),
     #[error("Coins in outputs are less than coins in inputs for {0} nanoERGs")]
     NotEnoughCoinsInOutputs(u64),
+    /// Inputs already spent by the transactions in the mempool snapshot
+    #[error("Inputs are already spent in mempool: {0:?}")]
+    InputsSpentInMempool(Vec<InputConflict>),
+}
+
+impl TxBuilderError {
+    /// Actionable suggestions on how to fix the token balance of the transaction (empty for the
+    /// errors not related to token balance)
+    pub fn suggestions(&self) -> Vec<TokenBalanceSuggestion> {
+        match self {
+            TxBuilderError::NotEnoughTokens(missing_tokens) => missing_tokens
+                .iter()
+                .map(|t| TokenBalanceSuggestion::AddInputsWithToken {
+                    token_id: t.token_id,
+                    missing_amount: *t.amount.as_u64(),
+                })
+                .collect(),
+            TxBuilderError::TokenBurnPermitExceeded {
+                permit,
+                try_to_burn,
+            } => vec![
+                TokenBalanceSuggestion::AddChangeOutput {
+                    token_id: try_to_burn.token_id,
+                    amount: try_to_burn.amount.as_u64() - permit.amount.as_u64(),
+                },
+                TokenBalanceSuggestion::PermitTokenBurn {
+                    token_id: try_to_burn.token_id,
+                    amount: *try_to_burn.amount.as_u64(),
+                },
+            ],
+            TxBuilderError::TokenBurnPermitMissing { try_to_burn } => vec![
+                TokenBalanceSuggestion::AddChangeOutput {
+                    token_id: try_to_burn.token_id,
+                    amount: *try_to_burn.amount.as_u64(),
+                },
+                TokenBalanceSuggestion::PermitTokenBurn {
+                    token_id: try_to_burn.token_id,
+                    amount: *try_to_burn.amount.as_u64(),
+                },
+            ],
+            TxBuilderError::TokenBurnPermitUnused { token_id, amount } => {
+                vec![TokenBalanceSuggestion::ReduceTokenBurnPermit {
+                    token_id: *token_id,
+                    amount: *amount,
+                }]
+            }
+            TxBuilderError::ParsingError(_)
+            | TxBuilderError::InvalidArgs(_)
+            | TxBuilderError::ErgoBoxCandidateBuilderError(_)
+            | TxBuilderError::NotEnoughCoinsInInputs(_)
+            | TxBuilderError::SerializationError(_)
+            | TxBuilderError::InvalidInputsCount(_)
+            | TxBuilderError::EmptyInputBoxSelection
+            | TxBuilderError::TooManyTokensInBurnPermit(_)
+            | TxBuilderError::TooManyTokensInInputBoxes(_)
+            | TxBuilderError::TooManyTokensInOutputCandidates(_)
+            | TxBuilderError::TokensInOutputsExceedInputs(_)
+            | TxBuilderError::NotEnoughCoinsInOutputs(_)
+            | TxBuilderError::InputsSpentInMempool(_) => vec![],
+        }
+    }
+}
+
+/// Actionable suggestion on how to fix the token balance of the transaction being built.
+/// Structured (token id and amount) so it can be rendered by UIs.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum TokenBalanceSuggestion {
+    /// Add input boxes holding the missing amount of the token
+    AddInputsWithToken {
+        /// Token id
+        token_id: TokenId,
+        /// Amount of the token missing in inputs
+        missing_amount: u64,
+    },
+    /// Add a change output with the token amount left unspent in inputs
+    AddChangeOutput {
+        /// Token id
+        token_id: TokenId,
+        /// Amount of the token left unspent
+        amount: u64,
+    },
+    /// Permit burning of the token amount via [`TxBuilder::set_token_burn_permit`]
+    PermitTokenBurn {
+        /// Token id
+        token_id: TokenId,
+        /// Amount of the token to be burned
+        amount: u64,
+    },
+    /// Reduce the token burn permit set via [`TxBuilder::set_token_burn_permit`]
+    ReduceTokenBurnPermit {
+        /// Token id
+        token_id: TokenId,
+        /// Amount of the token permitted to burn, but not burned
+        amount: u64,
+    },
+}
+
+impl std::fmt::Display for TokenBalanceSuggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenBalanceSuggestion::AddInputsWithToken {
+                token_id,
+                missing_amount,
+            } => write!(
+                f,
+                "add inputs with token {}: {} units missing",
+                token_id, missing_amount
+            ),
+            TokenBalanceSuggestion::AddChangeOutput { token_id, amount } => write!(
+                f,
+                "add change output for token {}: {} units unspent",
+                token_id, amount
+            ),
+            TokenBalanceSuggestion::PermitTokenBurn { token_id, amount } => write!(
+                f,
+                "permit burning {} units of token {} with `set_token_burn_permit()`",
+                amount, token_id
+            ),
+            TokenBalanceSuggestion::ReduceTokenBurnPermit { token_id, amount } => write!(
+                f,
+                "reduce burn permit for token {} by {} units with `set_token_burn_permit()`",
+                token_id, amount
+            ),
+        }
+    }
+}
+
+fn fmt_suggestions(suggestions: &[TokenBalanceSuggestion]) -> String {
+    suggestions
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 /// Sums up the tokens into a hash map
 pub(crate) fn vec_tokens_to_map(
     tokens: Vec<Token>,
 ) -> Result<HashMap<TokenId, TokenAmount>, TokenAmountError> {
-    let mut res: HashMap<TokenId, TokenAmount> = HashMap::new();
-    tokens.iter().try_for_each(|b| {
-        if let Some(amt) = res.get_mut(&b.token_id) {
-            *amt = amt.checked_add(&b.amount)?;
-        } else {
-            res.insert(b.token_id, b.amount);
-        }
-        Ok(())
-    })?;
-    Ok(res)
+    sum_token_amounts(&tokens)
 }
 
 fn check_enough_token_burn_permit(
@@ -413,6 +667,50 @@ mod tests {
         assert!(matches!(r.build(), Err(TxBuilderError::InvalidArgs(_))));
     }
 
+    #[test]
+    fn test_mempool_conflicts() {
+        let input_box = force_any_val::<ErgoBox>();
+        let mempool_tx = Transaction::new_from_vec(
+            vec![Input::new(
+                input_box.box_id(),
+                force_any_val::<crate::chain::transaction::input::prover_result::ProverResult>(),
+            )],
+            vec![],
+            vec![force_any_val::<ErgoBoxCandidate>()],
+        )
+        .unwrap();
+        let box_selection: BoxSelection<ErgoBox> = BoxSelection {
+            boxes: vec![input_box.clone()].try_into().unwrap(),
+            change_boxes: vec![],
+        };
+        let mut r = TxBuilder::new(
+            box_selection,
+            vec![force_any_val::<ErgoBoxCandidate>()],
+            1,
+            force_any_val::<BoxValue>(),
+            force_any_val::<Address>(),
+        );
+        let snapshot = MempoolSnapshot::new(&[mempool_tx.clone()]);
+        assert_eq!(
+            r.mempool_conflicts(&snapshot),
+            vec![InputConflict {
+                box_id: input_box.box_id(),
+                mempool_tx_id: mempool_tx.id(),
+            }]
+        );
+        assert!(r.mempool_conflicts(&MempoolSnapshot::default()).is_empty());
+        assert!(snapshot.filter_unspent(vec![input_box.clone()]).is_empty());
+
+        r.set_mempool_snapshot(snapshot);
+        assert_eq!(
+            r.build(),
+            Err(TxBuilderError::InputsSpentInMempool(vec![InputConflict {
+                box_id: input_box.box_id(),
+                mempool_tx_id: mempool_tx.id(),
+            }]))
+        );
+    }
+
     #[test]
     fn test_empty_outputs() {
         let inputs = vec![force_any_val::<ErgoBox>()];
@@ -472,9 +770,22 @@ mod tests {
         assert_eq!(
             res,
             Err(TxBuilderError::TokenBurnPermitMissing {
-                try_to_burn: target_token
+                try_to_burn: target_token.clone()
             })
         );
+        assert_eq!(
+            res.unwrap_err().suggestions(),
+            vec![
+                TokenBalanceSuggestion::AddChangeOutput {
+                    token_id: target_token.token_id,
+                    amount: 10
+                },
+                TokenBalanceSuggestion::PermitTokenBurn {
+                    token_id: target_token.token_id,
+                    amount: 10
+                },
+            ]
+        );
     }
 
     #[test]
@@ -711,6 +1022,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_not_enough_tokens_suggestion() {
+        let input_token = Token {
+            token_id: force_any_val_with::<TokenId>(ArbTokenIdParam::Arbitrary),
+            amount: 100.try_into().unwrap(),
+        };
+        let input_box = ErgoBox::new(
+            10000000i64.try_into().unwrap(),
+            force_any_val::<ErgoTree>(),
+            vec![input_token.clone()].try_into().ok(),
+            NonMandatoryRegisters::empty(),
+            1,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let tx_fee = BoxValue::SAFE_USER_MIN;
+        let out_box_value = BoxValue::SAFE_USER_MIN;
+        let target_balance = out_box_value.checked_add(&tx_fee).unwrap();
+        let box_selection = SimpleBoxSelector::new()
+            .select(vec![input_box], target_balance, &[input_token.clone()])
+            .unwrap();
+        let mut box_builder =
+            ErgoBoxCandidateBuilder::new(out_box_value, force_any_val::<ErgoTree>(), 0);
+        box_builder.add_token(Token {
+            amount: 250.try_into().unwrap(),
+            ..input_token.clone()
+        });
+        let tx_builder = TxBuilder::new(
+            box_selection,
+            vec![box_builder.build().unwrap()],
+            0,
+            tx_fee,
+            force_any_val::<Address>(),
+        );
+        let missing = Token {
+            amount: 150.try_into().unwrap(),
+            ..input_token.clone()
+        };
+        let err = tx_builder.build().unwrap_err();
+        assert_eq!(err, TxBuilderError::NotEnoughTokens(vec![missing]));
+        assert_eq!(
+            err.suggestions(),
+            vec![TokenBalanceSuggestion::AddInputsWithToken {
+                token_id: input_token.token_id,
+                missing_amount: 150
+            }]
+        );
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Not enough tokens in inputs: add inputs with token {}: 150 units missing",
+                input_token.token_id
+            )
+        );
+    }
+
     #[test]
     fn test_balance_error_not_enough_inputs() {
         let input_box = force_any_val_with::<ErgoBox>(
@@ -822,6 +1190,62 @@ mod tests {
         assert!(tx_builder.estimate_tx_size_bytes().unwrap() > 0);
     }
 
+    #[test]
+    fn test_output_metadata_in_summary() {
+        let input = ErgoBox::new(
+            10000000i64.try_into().unwrap(),
+            force_any_val::<ErgoTree>(),
+            None,
+            NonMandatoryRegisters::empty(),
+            1,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let tx_fee = super::SUGGESTED_TX_FEE();
+        let out_box_value = input.value.checked_sub(&tx_fee).unwrap();
+        let out_box = ErgoBoxCandidateBuilder::new(out_box_value, force_any_val::<ErgoTree>(), 0)
+            .build()
+            .unwrap();
+        let mut tx_builder = TxBuilder::new(
+            BoxSelection {
+                boxes: vec![input].try_into().unwrap(),
+                change_boxes: vec![],
+            },
+            vec![out_box.clone()],
+            0,
+            tx_fee,
+            force_any_val::<Address>(),
+        );
+        let metadata = OutputMetadata {
+            label: Some("Alice".to_string()),
+            tags: vec!["rent".to_string()],
+        };
+        assert!(tx_builder.set_output_metadata(1, metadata.clone()).is_err());
+        tx_builder.set_output_metadata(0, metadata.clone()).unwrap();
+        let (tx, summary) = tx_builder.clone().build_with_summary().unwrap();
+        assert_eq!(tx, tx_builder.build().unwrap());
+        assert_eq!(
+            summary.outputs,
+            vec![
+                OutputSummary {
+                    index: 0,
+                    kind: OutputKind::Payment,
+                    value: out_box_value,
+                    tokens: vec![],
+                    metadata: Some(metadata),
+                },
+                OutputSummary {
+                    index: 1,
+                    kind: OutputKind::MinerFee,
+                    value: tx_fee,
+                    tokens: vec![],
+                    metadata: None,
+                },
+            ]
+        );
+    }
+
     proptest! {
 
         #![proptest_config(ProptestConfig::with_cases(16))]
@@ -5,6 +5,7 @@ use thiserror::Error;
 
 use crate::chain::transaction::TransactionError;
 use crate::ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_interpreter::eval::context::ContextError;
 use ergotree_interpreter::eval::context::TxIoVec;
 
 use super::signing::ErgoTransaction;
@@ -75,6 +76,36 @@ impl<T: ErgoTransaction> TransactionContext<T> {
             .find(|b| b.box_id() == *box_id)
             .cloned()
     }
+
+    /// Returns data input box with given id, if it exists.
+    pub fn get_data_input_box(&self, box_id: &BoxId) -> Option<ErgoBox> {
+        self.data_boxes
+            .as_ref()?
+            .iter()
+            .find(|b| b.box_id() == *box_id)
+            .cloned()
+    }
+
+    /// Check that the data inputs are only read, i.e. no data input box is spent by an input of
+    /// the same transaction. This is not a consensus rule (it is not checked on
+    /// [`crate::chain::transaction::verify_transaction`]), but such a transaction usually means
+    /// the box was meant to be either read or spent.
+    pub fn validate_data_inputs(&self) -> Result<(), TransactionContextError> {
+        let inputs_ids = self.spending_tx.inputs_ids();
+        self.spending_tx
+            .data_inputs()
+            .iter()
+            .flat_map(|data_inputs| data_inputs.iter().enumerate())
+            .try_for_each(|(data_input_idx, data_input)| {
+                match inputs_ids.iter().position(|id| *id == data_input.box_id) {
+                    Some(input_idx) => Err(TransactionContextError::DataInputSpent {
+                        data_input_idx,
+                        input_idx,
+                    }),
+                    None => Ok(()),
+                }
+            })
+    }
 }
 
 /// Transaction context errors
@@ -87,15 +118,26 @@ pub enum TransactionContextError {
     #[error("No input boxes")]
     NoInputBoxes,
     /// Too many input boxes
-    #[error("Too many input boxes: {0}")]
+    #[error("Too many input boxes: got {0} (expected <= {})", u16::MAX)]
     TooManyInputBoxes(usize),
     /// Input box not found
     #[error("Input box not found: {0}")]
     InputBoxNotFound(usize),
     /// Too many data input boxes
-    #[error("Too many data input boxes: {0}")]
+    #[error("Too many data input boxes: got {0} (expected <= {})", u16::MAX)]
     TooManyDataInputBoxes(usize),
     /// Data input box not found
     #[error("Data input box not found: {0}")]
     DataInputBoxNotFound(usize),
+    /// Data input box is spent by the input of the same transaction
+    #[error("Data input {data_input_idx} is spent by the input {input_idx}")]
+    DataInputSpent {
+        /// Index of the data input
+        data_input_idx: usize,
+        /// Index of the input spending the data input box
+        input_idx: usize,
+    },
+    /// Interpreter context is inconsistent
+    #[error("Invalid context: {0}")]
+    InvalidContext(#[from] ContextError),
 }
@@ -1,6 +1,7 @@
 use crate::batchmerkleproof::{BatchMerkleProof, BatchMerkleProofIndex};
 use crate::{LevelNode, MerkleProof, NodeSide};
 use serde::{Deserialize, Serialize};
+use sigma_util::encoding::{decode_base16, encode_base16, DecodingError, DecodingMode};
 use thiserror::Error;
 /// Json Representation of a LevelNode. First field must be valid base16
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -9,7 +10,7 @@ pub struct LevelNodeJson(String, NodeSide);
 impl std::convert::TryFrom<LevelNodeJson> for LevelNode {
     type Error = MerkleProofFromJsonError;
     fn try_from(node: LevelNodeJson) -> Result<Self, Self::Error> {
-        let hash = base16::decode(&node.0)?;
+        let hash = decode_base16(&node.0, DecodingMode::Strict)?;
         Ok(LevelNode {
             hash: match hash.len() {
                 0 => None,
@@ -27,7 +28,7 @@ impl From<LevelNode> for LevelNodeJson {
     fn from(node: LevelNode) -> Self {
         Self(
             node.hash
-                .map(|hash| base16::encode_lower(&hash))
+                .map(|hash| encode_base16(hash.as_ref()))
                 .unwrap_or_else(String::new),
             node.side,
         )
@@ -49,7 +50,7 @@ pub struct MerkleProofJson {
 pub enum MerkleProofFromJsonError {
     /// Base16 decoding has failed
     #[error("Failed to decode base16 string")]
-    DecodeError(#[from] base16::DecodeError),
+    DecodeError(#[from] DecodingError),
     /// Invalid Length (expected 32 bytes)
     #[error("Invalid Length. Hashes and Leaf data must be 32 bytes in size")]
     LengthError,
@@ -59,7 +60,7 @@ pub enum MerkleProofFromJsonError {
 impl std::convert::TryFrom<crate::json::MerkleProofJson> for MerkleProof {
     type Error = MerkleProofFromJsonError;
     fn try_from(proof: crate::json::MerkleProofJson) -> Result<Self, Self::Error> {
-        let leaf_data = base16::decode(&proof.leaf_data)?;
+        let leaf_data = decode_base16(&proof.leaf_data, DecodingMode::Strict)?;
         let levels: Result<Vec<LevelNode>, Self::Error> =
             proof.levels.into_iter().map(LevelNode::try_from).collect();
         Ok(Self {
@@ -73,7 +74,7 @@ impl From<MerkleProof> for MerkleProofJson {
     fn from(proof: MerkleProof) -> Self {
         let levels: Vec<crate::json::LevelNodeJson> =
             proof.levels.into_iter().map(Into::into).collect();
-        let leaf_data = base16::encode_lower(&proof.leaf_data);
+        let leaf_data = encode_base16(&proof.leaf_data);
         Self { leaf_data, levels }
     }
 }
@@ -96,7 +97,7 @@ impl From<BatchMerkleProofIndex> for IndexJson {
 impl TryFrom<IndexJson> for BatchMerkleProofIndex {
     type Error = MerkleProofFromJsonError;
     fn try_from(index: IndexJson) -> Result<BatchMerkleProofIndex, Self::Error> {
-        let digest = base16::decode(&index.digest)?
+        let digest = decode_base16(&index.digest, DecodingMode::Strict)?
             .try_into()
             .map_err(|_| MerkleProofFromJsonError::LengthError)?;
         Ok(BatchMerkleProofIndex {
@@ -116,7 +117,7 @@ pub struct BatchLevelNodeJson {
 impl std::convert::TryFrom<BatchLevelNodeJson> for LevelNode {
     type Error = MerkleProofFromJsonError;
     fn try_from(node: BatchLevelNodeJson) -> Result<Self, Self::Error> {
-        let hash = base16::decode(&node.digest)?;
+        let hash = decode_base16(&node.digest, DecodingMode::Strict)?;
         Ok(LevelNode {
             hash: match hash.len() {
                 0 => None,
@@ -135,7 +136,7 @@ impl From<LevelNode> for BatchLevelNodeJson {
         Self {
             digest: node
                 .hash
-                .map(|hash| base16::encode_lower(&hash))
+                .map(|hash| encode_base16(hash.as_ref()))
                 .unwrap_or_else(String::new),
             side: node.side,
         }
@@ -104,9 +104,15 @@ impl MerkleProof {
     }
     #[cfg(feature = "json")]
     /// Validates the MerkleProof against a base16 hash
-    pub fn valid_base16(&self, expected_root: &str) -> Result<bool, base16::DecodeError> {
+    pub fn valid_base16(
+        &self,
+        expected_root: &str,
+    ) -> Result<bool, sigma_util::encoding::DecodingError> {
         // The rationale for adding this function is mainly to make using MerkleProof in Swift easier, without resorting to add a new dependency to base16
-        let expected_root = base16::decode(expected_root)?;
+        let expected_root = sigma_util::encoding::decode_base16(
+            expected_root,
+            sigma_util::encoding::DecodingMode::Strict,
+        )?;
         Ok(self.valid(&expected_root))
     }
 
@@ -1,18 +1,16 @@
 use ergo_chain_types::Header;
 use ergotree_ir::sigma_protocol::dlog_group::order;
-use num_bigint::BigInt;
 use num_traits::ToPrimitive;
 use std::convert::TryInto;
 
-use crate::{
-    autolykos_pow_scheme::{AutolykosPowScheme, AutolykosPowSchemeError},
-    nipopow_proof::PoPowHeader,
-    NipopowProof, NipopowProofError,
+use crate::{nipopow_proof::PoPowHeader, NipopowProof, NipopowProofError};
+use ergo_chain_types::autolykos_pow_scheme::{
+    decode_compact_bits, AutolykosPowScheme, AutolykosPowSchemeError,
 };
-use ergo_chain_types::{BlockId, Digest32, ExtensionCandidate};
+use ergo_chain_types::extension::pack_interlinks;
+pub use ergo_chain_types::extension::INTERLINK_VECTOR_PREFIX;
+use ergo_chain_types::{BlockId, ExtensionCandidate};
 
-/// Prefix for Block Interlinks
-pub const INTERLINK_VECTOR_PREFIX: u8 = 0x01;
 /// A set of utilities for working with NiPoPoW protocol.
 ///
 /// Based on papers:
@@ -198,59 +196,13 @@ impl NipopowAlgos {
     }
     /// Packs interlinks into key-value format of the block extension.
     pub fn pack_interlinks(interlinks: Vec<BlockId>) -> Vec<([u8; 2], Vec<u8>)> {
-        let mut res = vec![];
-        let mut ix_distinct_block_ids = 0;
-        let mut curr_block_id_count = 1;
-        let mut curr_block_id = interlinks[0];
-        for id in interlinks.into_iter().skip(1) {
-            if id == curr_block_id {
-                curr_block_id_count += 1;
-            } else {
-                let block_id_bytes: Vec<u8> = curr_block_id.0.into();
-                let packed_value = std::iter::once(curr_block_id_count)
-                    .chain(block_id_bytes)
-                    .collect();
-                res.push((
-                    [INTERLINK_VECTOR_PREFIX, ix_distinct_block_ids],
-                    packed_value,
-                ));
-                curr_block_id = id;
-                curr_block_id_count = 1;
-                ix_distinct_block_ids += 1;
-            }
-        }
-        let block_id_bytes: Vec<u8> = curr_block_id.0.into();
-        let packed_value = std::iter::once(curr_block_id_count)
-            .chain(block_id_bytes)
-            .collect();
-        res.push((
-            [INTERLINK_VECTOR_PREFIX, ix_distinct_block_ids],
-            packed_value,
-        ));
-        res
+        pack_interlinks(&interlinks)
     }
     /// Unpacks interlinks from key-value format of block extension.
     pub fn unpack_interlinks(extension: &ExtensionCandidate) -> Result<Vec<BlockId>, &'static str> {
-        let mut res = vec![];
-        let entries = extension
-            .fields()
-            .iter()
-            .filter(|&(key, _)| key[0] == INTERLINK_VECTOR_PREFIX);
-        for (_, bytes) in entries {
-            // Each interlink is packed as [qty | blockId], which qty is a single-byte value
-            // representing the number of duplicates of `blockId`. Every `BlockId` is 32 bytes which
-            // implies that `bytes` is 33 bytes.
-            if bytes.len() != 33 {
-                return Err("Interlinks must be 33 bytes in size");
-            }
-            let qty = bytes[0];
-            let block_id_bytes: [u8; 32] = bytes[1..]
-                .try_into()
-                .map_err(|_| "Expected 32 byte BlockId")?;
-            let block_id = BlockId(Digest32::from(block_id_bytes));
-            res.extend(std::iter::repeat(block_id).take(qty as usize));
-        }
-        Ok(res)
+        extension
+            .interlinks()
+            .map_err(|_| "Interlinks must be 33 bytes in size")
     }
 
     /// Computes interlinks vector for a header next to `prevHeader`.
@@ -349,112 +301,3 @@ fn extension_merkletree(kv: &[([u8; 2], Vec<u8>)]) -> ergo_merkle_tree::MerkleTr
         .collect::<Vec<ergo_merkle_tree::MerkleNode>>();
     ergo_merkle_tree::MerkleTree::new(leafs)
 }
-
-/// The "compact" format is an encoding of a whole number `N` using an unsigned 32 bit number.
-/// This number encodes a base-256 scientific notation representation of `N` (similar to a floating
-/// point format):
-///  - The most significant 8 bits represent the number of bytes necessary to represent `N` in
-///    two's-complement form; denote it by `exponent`.
-///  - The lower 23 bits are the mantissa(significand).
-///  - Bit number 24 (0x800000) represents the sign of N.
-///
-/// There are 2 cases to consider:
-///  - If `exponent >= 3` then `N` is represented by
-///      `(-1^sign) * mantissa * 256^(exponent-3)`
-///    E.g. suppose the compact form is given in hex-format by `0x04123456`. Mantissa is `0x123456`
-///    and `exponent == 4`. So `N == 0x123456 * 265^1`. Now note that we need exactly 4 bytes to
-///    represent `N`; 3 bytes for the mantissa and 1 byte for the rest. In base-256:
-///      `N == B(0x12)B(0x34)B(0x56)0`
-///    where `B(y)` denotes the base-256 representation of a hex-number `y` (note how each base-256
-///    digit is represented by a single-byte).
-///  - If `exponent < 3` then `N` is represented by the `exponent`-most-significant-bytes of the
-///    mantissa. E.g. suppose the compact form is given in hex-format by `0x01003456`. Noting that
-///    each hex-digit is represented by 4-bits, our `exponent == 0x01` which is `1` base-10.  The
-///    mantissa is represented by `0x003456` and it's most signficant byte is `0x00`. Therefore
-///    `N == 0`.
-///
-/// Satoshi's original implementation used BN_bn2mpi() and BN_mpi2bn(). MPI uses the most
-/// significant bit of the first byte as sign. Thus 0x1234560000 is compact 0x05123456 and
-/// 0xc0de000000 is compact 0x0600c0de. Compact 0x05c0de00 would be -0x40de000000.
-///
-/// Bitcoin only uses this "compact" format for encoding difficulty targets, which are unsigned
-/// 256bit quantities.  Thus, all the complexities of the sign bit and using base 256 are probably
-/// an implementation accident.
-pub fn decode_compact_bits(n_bits: u64) -> BigInt {
-    let compact = n_bits as i64;
-    let size = ((compact >> 24) as i32) & 0xFF;
-    if size == 0 {
-        return BigInt::from(0);
-    }
-    let mut buf: Vec<i8> = std::iter::repeat(0).take(size as usize).collect();
-    if size >= 1 {
-        // Store the first byte of the mantissa
-        buf[0] = (((compact >> 16) as i32) & 0xFF) as i8;
-    }
-    if size >= 2 {
-        buf[1] = (((compact >> 8) as i32) & 0xFF) as i8;
-    }
-    if size >= 3 {
-        buf[2] = ((compact as i32) & 0xFF) as i8;
-    }
-
-    let is_negative = (buf[0] as i32) & 0x80 == 0x80;
-    if is_negative {
-        buf[0] &= 0x7f;
-        let buf: Vec<_> = buf.into_iter().map(|x| x as u8).collect();
-        -BigInt::from_signed_bytes_be(&buf)
-    } else {
-        let buf: Vec<_> = buf.into_iter().map(|x| x as u8).collect();
-        BigInt::from_signed_bytes_be(&buf)
-    }
-}
-
-#[allow(clippy::unwrap_used)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use num_bigint::ToBigInt;
-
-    #[test]
-    fn test_decode_n_bits() {
-        // Following example taken from https://btcinformation.org/en/developer-reference#target-nbits
-        let n_bits = 0x181bc330;
-        assert_eq!(
-            decode_compact_bits(n_bits),
-            BigInt::parse_bytes(b"1bc330000000000000000000000000000000000000000000", 16).unwrap()
-        );
-
-        let n_bits = 0x01003456;
-        assert_eq!(
-            decode_compact_bits(n_bits),
-            ToBigInt::to_bigint(&0x00).unwrap()
-        );
-
-        let n_bits = 0x01123456;
-        assert_eq!(
-            decode_compact_bits(n_bits),
-            ToBigInt::to_bigint(&0x12).unwrap()
-        );
-
-        let n_bits = 0x04923456;
-        assert_eq!(
-            decode_compact_bits(n_bits),
-            ToBigInt::to_bigint(&-0x12345600).unwrap()
-        );
-
-        let n_bits = 0x04123456;
-        assert_eq!(
-            decode_compact_bits(n_bits),
-            ToBigInt::to_bigint(&0x12345600).unwrap()
-        );
-
-        let n_bits = 0x05123456;
-        assert_eq!(
-            decode_compact_bits(n_bits),
-            ToBigInt::to_bigint(&0x1234560000i64).unwrap()
-        );
-
-        let n_bits = 16842752;
-        assert_eq!(decode_compact_bits(n_bits), BigInt::from(1_u8));
-    }
-}
@@ -7,7 +7,8 @@ use sigma_ser::{
     ScorexParsingError, ScorexSerializable, ScorexSerializeResult,
 };
 
-use crate::{autolykos_pow_scheme, nipopow_algos::NipopowAlgos};
+use crate::nipopow_algos::NipopowAlgos;
+use ergo_chain_types::autolykos_pow_scheme;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 /// A structure representing NiPoPow proof as a persistent modifier.
@@ -82,10 +83,21 @@ impl NipopowProof {
         }
     }
 
-    fn is_valid(&self) -> bool {
+    /// Checks the proof structure: connections, heights and interlinks proofs of the headers
+    pub fn is_valid(&self) -> bool {
         self.has_valid_connections() && self.has_valid_heights() && self.has_valid_proofs()
     }
 
+    /// Checks Autolykos PoW solutions of all the headers in the proof
+    pub fn has_valid_pow(&self) -> Result<bool, NipopowProofError> {
+        for h in self.headers_chain() {
+            if !self.popow_algos.pow_scheme.check_pow(h)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     /// Checks the connections of the blocks in the proof. Adjacent blocks should be linked either
     /// via interlink or parent block id. Returns true if all adjacent blocks are correctly
     /// connected.
@@ -124,9 +136,15 @@ impl NipopowProof {
             .all(PoPowHeader::check_interlinks_proof)
     }
 
+    /// Returns an iterator over the suffix (last `k` headers) of the proof, i.e. `self.suffix_head`
+    /// and `self.suffix_tail`
+    pub fn suffix_headers(&self) -> impl Iterator<Item = &Header> {
+        std::iter::once(&self.suffix_head.header).chain(self.suffix_tail.iter())
+    }
+
     /// Returns an iterator representing a chain of `Headers` from `self.prefix`, to
     /// `self.suffix_head` and `self.suffix_tail`.
-    pub(crate) fn headers_chain(&self) -> impl Iterator<Item = &Header> {
+    pub fn headers_chain(&self) -> impl Iterator<Item = &Header> {
         self.prefix
             .iter()
             .map(|p| &p.header)
@@ -170,7 +188,7 @@ impl ScorexSerializable for NipopowProof {
         let num_suffix_tail = r.get_u32()? as usize;
         let mut suffix_tail = Vec::with_capacity(num_suffix_tail);
         for _ in 0..num_suffix_tail {
-            let _size = r.get_u32();
+            let _size = r.get_u32()?;
             suffix_tail.push(Header::scorex_parse(r)?);
         }
         Ok(NipopowProof {
@@ -284,7 +302,7 @@ impl ScorexSerializable for PoPowHeader {
 #[cfg(feature = "arbitrary")]
 #[allow(clippy::unwrap_used)]
 mod arbitrary {
-    use crate::autolykos_pow_scheme::AutolykosPowScheme;
+    use ergo_chain_types::autolykos_pow_scheme::AutolykosPowScheme;
 
     use super::*;
     use ergo_chain_types::Digest32;
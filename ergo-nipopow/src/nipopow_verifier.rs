@@ -3,6 +3,19 @@ use ergo_chain_types::Header;
 
 use crate::nipopow_proof::{NipopowProof, NipopowProofError};
 
+/// Outcome of processing a proof by [`NipopowVerifier`]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum NipopowProofVerificationResult {
+    /// Proof is valid and better than the previous best proof, now it's the best proof
+    BetterChain,
+    /// Proof is valid, but not better than the current best proof
+    NoBetterChain,
+    /// Proof is invalid (broken connections, heights, interlinks proofs or PoW)
+    ValidationError,
+    /// First header of the proof is not the expected genesis
+    WrongGenesis,
+}
+
 /// A verifier for PoPoW proofs. During its lifetime, it processes many proofs with the aim of
 /// deducing at any given point what is the best (sub)chain rooted at the specified genesis.
 #[derive(Debug)]
@@ -33,20 +46,34 @@ impl NipopowVerifier {
             .map_or_else(Vec::new, |p| p.headers_chain().cloned().collect())
     }
 
+    /// Returns suffix `Header`s (the most recent `k` headers) of the best proof.
+    pub fn best_suffix(&self) -> Vec<Header> {
+        self.best_proof
+            .as_ref()
+            .map_or_else(Vec::new, |p| p.suffix_headers().cloned().collect())
+    }
+
     /// Process given proof
-    pub fn process(&mut self, new_proof: NipopowProof) -> Result<(), NipopowProofError> {
-        let h = new_proof.headers_chain().next();
-        if let Some(h) = h {
-            if h.id == self.genesis_block_id {
-                if let Some(p) = &self.best_proof {
-                    if new_proof.is_better_than(p)? {
-                        self.best_proof = Some(new_proof);
-                    }
-                } else {
-                    self.best_proof = Some(new_proof);
-                }
-            }
+    pub fn process(
+        &mut self,
+        new_proof: NipopowProof,
+    ) -> Result<NipopowProofVerificationResult, NipopowProofError> {
+        match new_proof.headers_chain().next() {
+            Some(h) if h.id == self.genesis_block_id => (),
+            _ => return Ok(NipopowProofVerificationResult::WrongGenesis),
+        }
+        if !new_proof.is_valid() || !new_proof.has_valid_pow()? {
+            return Ok(NipopowProofVerificationResult::ValidationError);
+        }
+        let is_better = match &self.best_proof {
+            Some(p) => new_proof.is_better_than(p)?,
+            None => true,
+        };
+        if is_better {
+            self.best_proof = Some(new_proof);
+            Ok(NipopowProofVerificationResult::BetterChain)
+        } else {
+            Ok(NipopowProofVerificationResult::NoBetterChain)
         }
-        Ok(())
     }
 }
@@ -1,17 +1,339 @@
+use bytes::Buf;
+use bytes::BufMut;
 use bytes::BytesMut;
-use sigma_ser::ScorexSerializationError;
+use sigma_ser::ScorexSerializable;
+use sigma_util::hash::blake2b256_hash;
+use std::io::Read;
+use tokio_util::codec::Decoder;
 use tokio_util::codec::Encoder;
 
-use crate::message::Request;
+use crate::constants::MAX_HANDSHAKE_SIZE;
+use crate::constants::MAX_MESSAGE_SIZE;
+use crate::error::CodecError;
+use crate::message::Handshake;
+use crate::message::Message;
 
-/// Encoder/Decoder for network messages from/to bytes
-#[derive(Default)]
-pub struct Codec {}
+/// Size of the message header: magic (4 bytes), code (1 byte), body length (4 bytes)
+const HEADER_SIZE: usize = 9;
+/// Size of the body checksum
+const CHECKSUM_SIZE: usize = 4;
 
-impl Encoder<Request> for Codec {
-    type Error = ScorexSerializationError;
+/// Item decoded by [`Codec`]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Packet {
+    /// Handshake (always the first item received from the peer)
+    Handshake(Handshake),
+    /// Network message
+    Message(Message),
+}
+
+/// Encoder/Decoder for network messages from/to bytes.
+///
+/// The handshake is sent as is (without framing). Every message after it is framed as: network
+/// magic (4 bytes), message code (1 byte), body length (4 bytes, big-endian) and, if the body is
+/// not empty, the first 4 bytes of the body's Blake2b256 hash followed by the body.
+pub struct Codec {
+    magic: [u8; 4],
+    handshake_received: bool,
+}
+
+impl Codec {
+    /// Codec for the network with the given magic bytes (see [`crate::MAINNET_MAGIC`] and
+    /// [`crate::TESTNET_MAGIC`])
+    pub fn new(magic: [u8; 4]) -> Self {
+        Codec {
+            magic,
+            handshake_received: false,
+        }
+    }
+
+    fn decode_handshake(&mut self, src: &mut BytesMut) -> Result<Option<Packet>, CodecError> {
+        if src.is_empty() {
+            return Ok(None);
+        }
+        let mut reader = EofTrackingReader {
+            remaining: &src[..],
+            eof: false,
+        };
+        match Handshake::scorex_parse(&mut reader) {
+            Ok(handshake) => {
+                let consumed = src.len() - reader.remaining.len();
+                src.advance(consumed);
+                self.handshake_received = true;
+                Ok(Some(Packet::Handshake(handshake)))
+            }
+            // the handshake is not framed, so running out of bytes means it's incomplete
+            Err(_) if reader.eof && src.len() < MAX_HANDSHAKE_SIZE => Ok(None),
+            Err(e) => Err(CodecError::Parsing(e)),
+        }
+    }
+
+    fn decode_message(&mut self, src: &mut BytesMut) -> Result<Option<Packet>, CodecError> {
+        if src.len() < HEADER_SIZE {
+            return Ok(None);
+        }
+        let magic = [src[0], src[1], src[2], src[3]];
+        if magic != self.magic {
+            return Err(CodecError::InvalidMagic {
+                expected: self.magic,
+                actual: magic,
+            });
+        }
+        let code = src[4];
+        let len = u32::from_be_bytes([src[5], src[6], src[7], src[8]]) as usize;
+        if len > MAX_MESSAGE_SIZE {
+            return Err(CodecError::MessageTooLarge(len));
+        }
+        if len == 0 {
+            src.advance(HEADER_SIZE);
+            return Ok(Some(Packet::Message(Message::parse_body(code, &[])?)));
+        }
+        let frame_size = HEADER_SIZE + CHECKSUM_SIZE + len;
+        if src.len() < frame_size {
+            src.reserve(frame_size - src.len());
+            return Ok(None);
+        }
+        let frame = src.split_to(frame_size);
+        let checksum = &frame[HEADER_SIZE..HEADER_SIZE + CHECKSUM_SIZE];
+        let body = &frame[HEADER_SIZE + CHECKSUM_SIZE..];
+        if checksum != &blake2b256_hash(body)[..CHECKSUM_SIZE] {
+            return Err(CodecError::InvalidChecksum);
+        }
+        Ok(Some(Packet::Message(Message::parse_body(code, body)?)))
+    }
+}
+
+/// Reader over the received bytes remembering if the parser asked for more bytes than available
+struct EofTrackingReader<'a> {
+    remaining: &'a [u8],
+    eof: bool,
+}
+
+impl Read for EofTrackingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.len() > self.remaining.len() {
+            self.eof = true;
+        }
+        self.remaining.read(buf)
+    }
+}
+
+impl Encoder<Handshake> for Codec {
+    type Error = CodecError;
+
+    fn encode(&mut self, item: Handshake, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&item.scorex_serialize_bytes()?);
+        Ok(())
+    }
+}
+
+impl Encoder<Message> for Codec {
+    type Error = CodecError;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let body = item.body_bytes()?;
+        if body.len() > MAX_MESSAGE_SIZE {
+            return Err(CodecError::MessageTooLarge(body.len()));
+        }
+        dst.reserve(HEADER_SIZE + CHECKSUM_SIZE + body.len());
+        dst.put_slice(&self.magic);
+        dst.put_u8(item.code());
+        dst.put_u32(body.len() as u32);
+        if !body.is_empty() {
+            dst.put_slice(&blake2b256_hash(&body)[..CHECKSUM_SIZE]);
+            dst.put_slice(&body);
+        }
+        Ok(())
+    }
+}
+
+impl Decoder for Codec {
+    type Item = Packet;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.handshake_received {
+            self.decode_message(src)
+        } else {
+            self.decode_handshake(src)
+        }
+    }
+}
+
+#[allow(clippy::panic)]
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+mod tests {
+    use ergo_chain_types::Digest32;
+    use proptest::prelude::*;
+
+    use crate::constants::MAINNET_MAGIC;
+    use crate::constants::TESTNET_MAGIC;
+    use crate::message::InvData;
+    use crate::message::ModifierTypeId;
+    use crate::message::ModifiersData;
+    use crate::PeerSpec;
+    use crate::ProtocolVersion;
+
+    use super::*;
+
+    fn codec_after_handshake() -> Codec {
+        Codec {
+            magic: MAINNET_MAGIC,
+            handshake_received: true,
+        }
+    }
+
+    fn roundtrip(msg: Message) -> Message {
+        let mut buf = BytesMut::new();
+        codec_after_handshake().encode(msg, &mut buf).unwrap();
+        match codec_after_handshake().decode(&mut buf).unwrap() {
+            Some(Packet::Message(m)) => {
+                assert!(buf.is_empty());
+                m
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    fn inv() -> InvData {
+        InvData {
+            type_id: ModifierTypeId::HEADER,
+            ids: vec![Digest32::zero(), Digest32::from([1; 32])],
+        }
+    }
+
+    #[test]
+    fn test_get_peers_frame() {
+        let mut buf = BytesMut::new();
+        codec_after_handshake()
+            .encode(Message::GetPeers, &mut buf)
+            .unwrap();
+        assert_eq!(&buf[..], &[1, 0, 2, 4, 1, 0, 0, 0, 0]);
+        assert_eq!(roundtrip(Message::GetPeers), Message::GetPeers);
+    }
+
+    #[test]
+    fn test_inv_and_modifiers_roundtrip() {
+        let msgs = vec![
+            Message::Inv(inv()),
+            Message::RequestModifier(inv()),
+            Message::Modifier(ModifiersData {
+                type_id: ModifierTypeId::TRANSACTION,
+                modifiers: vec![
+                    (Digest32::zero(), vec![1, 2, 3]),
+                    (Digest32::zero(), vec![]),
+                ],
+            }),
+            Message::Unknown {
+                code: 65,
+                body: vec![0, 1],
+            },
+        ];
+        for msg in msgs {
+            assert_eq!(roundtrip(msg.clone()), msg);
+        }
+    }
+
+    #[test]
+    fn test_partial_frame() {
+        let mut buf = BytesMut::new();
+        codec_after_handshake()
+            .encode(Message::Inv(inv()), &mut buf)
+            .unwrap();
+        let mut partial = BytesMut::from(&buf[..buf.len() - 1]);
+        let mut codec = codec_after_handshake();
+        assert_eq!(codec.decode(&mut partial).unwrap(), None);
+        partial.extend_from_slice(&buf[buf.len() - 1..]);
+        assert_eq!(
+            codec.decode(&mut partial).unwrap(),
+            Some(Packet::Message(Message::Inv(inv())))
+        );
+    }
+
+    #[test]
+    fn test_invalid_checksum_and_magic() {
+        let mut buf = BytesMut::new();
+        codec_after_handshake()
+            .encode(Message::Inv(inv()), &mut buf)
+            .unwrap();
+        let mut corrupted = buf.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 1;
+        assert!(matches!(
+            codec_after_handshake().decode(&mut corrupted),
+            Err(CodecError::InvalidChecksum)
+        ));
+        let mut testnet_codec = Codec {
+            magic: TESTNET_MAGIC,
+            handshake_received: true,
+        };
+        assert!(matches!(
+            testnet_codec.decode(&mut buf),
+            Err(CodecError::InvalidMagic { .. })
+        ));
+    }
+
+    #[test]
+    fn test_malformed_handshake_rejected() {
+        // time, then an agent name with invalid UTF-8
+        let mut buf = BytesMut::from(&[0u8, 2, 0xff, 0xfe][..]);
+        assert!(matches!(
+            Codec::new(MAINNET_MAGIC).decode(&mut buf),
+            Err(CodecError::Parsing(_))
+        ));
+        // time, then an empty agent name
+        let mut buf = BytesMut::from(&[0u8, 0][..]);
+        assert!(matches!(
+            Codec::new(MAINNET_MAGIC).decode(&mut buf),
+            Err(CodecError::Parsing(_))
+        ));
+    }
+
+    #[test]
+    fn test_partial_handshake() {
+        let spec = PeerSpec::new(
+            "ergoref",
+            ProtocolVersion::new(5, 0, 12),
+            "node",
+            None,
+            None,
+        );
+        let mut buf = BytesMut::new();
+        Codec::new(MAINNET_MAGIC)
+            .encode(Handshake::new(spec.clone()), &mut buf)
+            .unwrap();
+        let mut codec = Codec::new(MAINNET_MAGIC);
+        for len in 1..buf.len() {
+            let mut partial = BytesMut::from(&buf[..len]);
+            assert_eq!(codec.decode(&mut partial).unwrap(), None);
+        }
+        let mut partial = BytesMut::from(&buf[..buf.len() - 1]);
+        partial.extend_from_slice(&buf[buf.len() - 1..]);
+        match codec.decode(&mut partial).unwrap() {
+            Some(Packet::Handshake(h)) => assert_eq!(h.peer_spec, spec),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
 
-    fn encode(&mut self, _item: Request, _dst: &mut BytesMut) -> Result<(), Self::Error> {
-        todo!()
+        #[test]
+        fn test_handshake_then_peers(spec in any::<PeerSpec>(), peers in proptest::collection::vec(any::<PeerSpec>(), 0..4)) {
+            let handshake = Handshake::new(spec);
+            let mut buf = BytesMut::new();
+            let mut codec = Codec::new(MAINNET_MAGIC);
+            codec.encode(handshake.clone(), &mut buf).unwrap();
+            codec.encode(Message::Peers(peers.clone()), &mut buf).unwrap();
+            let mut decoder = Codec::new(MAINNET_MAGIC);
+            match decoder.decode(&mut buf).unwrap() {
+                Some(Packet::Handshake(h)) => prop_assert_eq!(h.peer_spec, handshake.peer_spec),
+                other => panic!("unexpected {:?}", other),
+            }
+            prop_assert_eq!(decoder.decode(&mut buf).unwrap(), Some(Packet::Message(Message::Peers(peers))));
+            prop_assert!(buf.is_empty());
+        }
     }
 }
@@ -2,3 +2,15 @@ use std::time::Duration;
 
 /// The timeout for handshakes when connecting to new peers.
 pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Network magic bytes of the mainnet (first bytes of every message)
+pub const MAINNET_MAGIC: [u8; 4] = [1, 0, 2, 4];
+
+/// Network magic bytes of the testnet (first bytes of every message)
+pub const TESTNET_MAGIC: [u8; 4] = [2, 0, 2, 3];
+
+/// Maximum size of the message body
+pub const MAX_MESSAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Maximum size of the handshake
+pub const MAX_HANDSHAKE_SIZE: usize = 8096;
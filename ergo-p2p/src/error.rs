@@ -1,3 +1,5 @@
+use sigma_ser::ScorexParsingError;
+use sigma_ser::ScorexSerializationError;
 use std::sync::Arc;
 use thiserror::Error;
 use tracing_error::TracedError;
@@ -33,6 +35,43 @@ pub enum HandshakeError {
     /// Sending or receiving a message timed out.
     #[error("Timeout when sending or receiving a message to peer")]
     Timeout,
+    /// Failed to encode or decode the handshake
+    #[error("Codec error: {0}")]
+    Codec(#[from] CodecError),
+    /// The connection was closed before the handshake was received
+    #[error("Connection closed by peer")]
+    ConnectionClosed,
+    /// Peer sent a message instead of the handshake
+    #[error("Unexpected message instead of the handshake")]
+    UnexpectedMessage,
+}
+
+/// An error during encoding/decoding network messages
+#[derive(Error, Debug)]
+pub enum CodecError {
+    /// IO error
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Failed to parse the handshake or message body
+    #[error("Parsing error: {0}")]
+    Parsing(#[from] ScorexParsingError),
+    /// Failed to serialize the handshake or message body
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] ScorexSerializationError),
+    /// Message is from another network
+    #[error("Invalid network magic: expected {expected:?}, got {actual:?}")]
+    InvalidMagic {
+        /// Magic of our network
+        expected: [u8; 4],
+        /// Received magic
+        actual: [u8; 4],
+    },
+    /// Message body checksum does not match
+    #[error("Invalid message checksum")]
+    InvalidChecksum,
+    /// Message body exceeds the maximum size
+    #[error("Message size {0} exceeds the maximum")]
+    MessageTooLarge(usize),
 }
 
 impl From<tokio::time::error::Elapsed> for HandshakeError {
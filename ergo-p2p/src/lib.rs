@@ -31,6 +31,13 @@ mod peer_spec;
 mod protocol_version;
 
 pub use client::Client;
+pub use codec::{Codec, Packet};
+pub use constants::{MAINNET_MAGIC, TESTNET_MAGIC};
+pub use error::{CodecError, HandshakeError};
+pub use message::{
+    Handshake, InvData, Message, ModifierTypeId, ModifiersData, MAX_INV_OBJECTS, MAX_PEERS,
+};
+pub use peer_connector::{OutboundConnectorRequest, PeerConnector};
 pub use peer_database::{in_memory::InMemoryPeerDatabase, PeerDatabase, PeerDatabaseError};
 pub use peer_feature::{LocalAddressPeerFeature, PeerFeature, PeerFeatureId};
 pub use peer_info::PeerInfo;
@@ -1,7 +1,126 @@
 mod handshake;
+mod inv;
+mod modifiers;
 mod request;
 mod response;
 
+use sigma_ser::vlq_encode::WriteSigmaVlqExt;
+use sigma_ser::ScorexParsingError;
+use sigma_ser::ScorexSerializable;
+use sigma_ser::ScorexSerializationError;
+
 pub use handshake::Handshake;
+pub use inv::InvData;
+pub use inv::ModifierTypeId;
+pub use inv::MAX_INV_OBJECTS;
+pub use modifiers::ModifiersData;
 pub use request::Request;
 pub use response::Response;
+
+use crate::PeerSpec;
+
+/// Maximum number of the peers in the Peers message
+pub const MAX_PEERS: usize = 64;
+
+/// Network message exchanged after the handshake (see [`crate::Codec`] for the framing)
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Message {
+    /// Request for the known peers
+    GetPeers,
+    /// Known peers (response to GetPeers)
+    Peers(Vec<PeerSpec>),
+    /// Announcement of the modifiers the sender has
+    Inv(InvData),
+    /// Request for the modifiers
+    RequestModifier(InvData),
+    /// Requested modifiers
+    Modifier(ModifiersData),
+    /// Message not supported by this library (e.g. SyncInfo), with the raw body
+    Unknown {
+        /// Message code
+        code: u8,
+        /// Message body
+        body: Vec<u8>,
+    },
+}
+
+impl Message {
+    /// GetPeers message code
+    pub const GET_PEERS_CODE: u8 = 1;
+    /// Peers message code
+    pub const PEERS_CODE: u8 = 2;
+    /// RequestModifier message code
+    pub const REQUEST_MODIFIER_CODE: u8 = 22;
+    /// Modifier message code
+    pub const MODIFIER_CODE: u8 = 33;
+    /// Inv message code
+    pub const INV_CODE: u8 = 55;
+
+    /// Message code
+    pub fn code(&self) -> u8 {
+        match self {
+            Message::GetPeers => Self::GET_PEERS_CODE,
+            Message::Peers(_) => Self::PEERS_CODE,
+            Message::Inv(_) => Self::INV_CODE,
+            Message::RequestModifier(_) => Self::REQUEST_MODIFIER_CODE,
+            Message::Modifier(_) => Self::MODIFIER_CODE,
+            Message::Unknown { code, .. } => *code,
+        }
+    }
+
+    /// Serialize the message body (without the framing)
+    pub fn body_bytes(&self) -> Result<Vec<u8>, ScorexSerializationError> {
+        match self {
+            Message::GetPeers => Ok(vec![]),
+            Message::Peers(peers) => {
+                let mut w = vec![];
+                w.put_u32(peers.len() as u32)?;
+                peers.iter().try_for_each(|p| p.scorex_serialize(&mut w))?;
+                Ok(w)
+            }
+            Message::Inv(inv) | Message::RequestModifier(inv) => inv.scorex_serialize_bytes(),
+            Message::Modifier(modifiers) => modifiers.scorex_serialize_bytes(),
+            Message::Unknown { body, .. } => Ok(body.clone()),
+        }
+    }
+
+    /// Parse the message body (without the framing) for the given message code
+    pub fn parse_body(code: u8, body: &[u8]) -> Result<Self, ScorexParsingError> {
+        use sigma_ser::vlq_encode::ReadSigmaVlqExt;
+        let r = &mut &body[..];
+        let msg = match code {
+            Self::GET_PEERS_CODE => Message::GetPeers,
+            Self::PEERS_CODE => {
+                let count = r.get_u32()? as usize;
+                if count > MAX_PEERS {
+                    return Err(ScorexParsingError::ValueOutOfBounds(format!(
+                        "too many peers: {}",
+                        count
+                    )));
+                }
+                let mut peers = Vec::with_capacity(count);
+                for _ in 0..count {
+                    peers.push(PeerSpec::scorex_parse(r)?);
+                }
+                Message::Peers(peers)
+            }
+            Self::INV_CODE => Message::Inv(InvData::scorex_parse(r)?),
+            Self::REQUEST_MODIFIER_CODE => Message::RequestModifier(InvData::scorex_parse(r)?),
+            Self::MODIFIER_CODE => Message::Modifier(ModifiersData::scorex_parse(r)?),
+            code => {
+                return Ok(Message::Unknown {
+                    code,
+                    body: body.to_vec(),
+                })
+            }
+        };
+        if !r.is_empty() {
+            return Err(ScorexParsingError::Misc(format!(
+                "{} unparsed bytes left in the message with code {}",
+                r.len(),
+                code
+            )));
+        }
+        Ok(msg)
+    }
+}
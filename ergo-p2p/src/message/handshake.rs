@@ -1,4 +1,12 @@
+use std::time::Duration;
 use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use sigma_ser::vlq_encode::ReadSigmaVlqExt;
+use sigma_ser::vlq_encode::WriteSigmaVlqExt;
+use sigma_ser::ScorexParsingError;
+use sigma_ser::ScorexSerializable;
+use sigma_ser::ScorexSerializeResult;
 
 use crate::PeerSpec;
 
@@ -8,7 +16,69 @@ use crate::PeerSpec;
 /// No further communication is possible until both peers have exchanged their handshakes.
 /// peerSpec - general (declared) information about peer
 /// time     - handshake time
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Handshake {
+    /// General (declared) information about the peer
     pub peer_spec: PeerSpec,
+    /// Handshake time (serialized with millisecond precision)
     pub time: SystemTime,
 }
+
+impl Handshake {
+    /// Handshake with the current time
+    pub fn new(peer_spec: PeerSpec) -> Self {
+        Handshake {
+            peer_spec,
+            time: SystemTime::now(),
+        }
+    }
+
+    /// Handshake time in milliseconds since UNIX epoch
+    pub fn time_millis(&self) -> u64 {
+        self.time
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+impl ScorexSerializable for Handshake {
+    fn scorex_serialize<W: WriteSigmaVlqExt>(&self, w: &mut W) -> ScorexSerializeResult {
+        w.put_u64(self.time_millis())?;
+        self.peer_spec.scorex_serialize(w)
+    }
+
+    fn scorex_parse<R: ReadSigmaVlqExt>(r: &mut R) -> Result<Self, ScorexParsingError> {
+        let millis = r.get_u64()?;
+        let time = UNIX_EPOCH
+            .checked_add(Duration::from_millis(millis))
+            .ok_or_else(|| {
+                ScorexParsingError::ValueOutOfBounds(format!(
+                    "handshake time {} ms is out of the system time range",
+                    millis
+                ))
+            })?;
+        let peer_spec = PeerSpec::scorex_parse(r)?;
+        Ok(Handshake { peer_spec, time })
+    }
+}
+
+#[allow(clippy::panic)]
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use sigma_ser::scorex_serialize_roundtrip;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn ser_roundtrip(spec in any::<PeerSpec>(), millis in 0..u64::MAX / 1000) {
+            let v = Handshake { peer_spec: spec, time: UNIX_EPOCH + Duration::from_millis(millis) };
+            assert_eq![scorex_serialize_roundtrip(&v), v]
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use ergo_chain_types::Digest32;
+use sigma_ser::vlq_encode::ReadSigmaVlqExt;
+use sigma_ser::vlq_encode::WriteSigmaVlqExt;
+use sigma_ser::ScorexParsingError;
+use sigma_ser::ScorexSerializable;
+use sigma_ser::ScorexSerializeResult;
+
+/// Maximum number of the modifier ids in [`InvData`]
+pub const MAX_INV_OBJECTS: usize = 400;
+
+/// Type of the modifier (block section or transaction)
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct ModifierTypeId(pub u8);
+
+impl ModifierTypeId {
+    /// Transaction
+    pub const TRANSACTION: ModifierTypeId = ModifierTypeId(2);
+    /// Block header
+    pub const HEADER: ModifierTypeId = ModifierTypeId(101);
+    /// Block transactions
+    pub const BLOCK_TRANSACTIONS: ModifierTypeId = ModifierTypeId(102);
+    /// AD proofs (UTXO set transformation proofs)
+    pub const AD_PROOFS: ModifierTypeId = ModifierTypeId(104);
+    /// Extension section
+    pub const EXTENSION: ModifierTypeId = ModifierTypeId(108);
+}
+
+/// Ids of the modifiers of the same type, used in Inv (announcement) and RequestModifier
+/// messages
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct InvData {
+    /// Type of the modifiers
+    pub type_id: ModifierTypeId,
+    /// Modifier ids
+    pub ids: Vec<Digest32>,
+}
+
+impl ScorexSerializable for InvData {
+    fn scorex_serialize<W: WriteSigmaVlqExt>(&self, w: &mut W) -> ScorexSerializeResult {
+        w.put_u8(self.type_id.0)?;
+        w.put_u32(self.ids.len() as u32)?;
+        self.ids.iter().try_for_each(|id| id.scorex_serialize(w))
+    }
+
+    fn scorex_parse<R: ReadSigmaVlqExt>(r: &mut R) -> Result<Self, ScorexParsingError> {
+        let type_id = ModifierTypeId(r.get_u8()?);
+        let count = r.get_u32()? as usize;
+        if count == 0 || count > MAX_INV_OBJECTS {
+            return Err(ScorexParsingError::ValueOutOfBounds(format!(
+                "Inv ids count {} is not in 1..={}",
+                count, MAX_INV_OBJECTS
+            )));
+        }
+        let mut ids = Vec::with_capacity(count);
+        for _ in 0..count {
+            ids.push(Digest32::scorex_parse(r)?);
+        }
+        Ok(InvData { type_id, ids })
+    }
+}
@@ -0,0 +1,59 @@
+use ergo_chain_types::Digest32;
+use sigma_ser::vlq_encode::ReadSigmaVlqExt;
+use sigma_ser::vlq_encode::WriteSigmaVlqExt;
+use sigma_ser::ScorexParsingError;
+use sigma_ser::ScorexSerializable;
+use sigma_ser::ScorexSerializeResult;
+
+use crate::constants::MAX_MESSAGE_SIZE;
+
+use super::ModifierTypeId;
+
+/// Serialized modifiers of the same type (response to RequestModifier message)
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ModifiersData {
+    /// Type of the modifiers
+    pub type_id: ModifierTypeId,
+    /// Modifier ids with the serialized modifiers
+    pub modifiers: Vec<(Digest32, Vec<u8>)>,
+}
+
+impl ScorexSerializable for ModifiersData {
+    fn scorex_serialize<W: WriteSigmaVlqExt>(&self, w: &mut W) -> ScorexSerializeResult {
+        w.put_u8(self.type_id.0)?;
+        w.put_u32(self.modifiers.len() as u32)?;
+        for (id, bytes) in &self.modifiers {
+            id.scorex_serialize(w)?;
+            w.put_u32(bytes.len() as u32)?;
+            w.write_all(bytes)?;
+        }
+        Ok(())
+    }
+
+    fn scorex_parse<R: ReadSigmaVlqExt>(r: &mut R) -> Result<Self, ScorexParsingError> {
+        let type_id = ModifierTypeId(r.get_u8()?);
+        let count = r.get_u32()? as usize;
+        // every modifier takes at least 33 bytes (id and length)
+        if count > MAX_MESSAGE_SIZE / 33 {
+            return Err(ScorexParsingError::ValueOutOfBounds(format!(
+                "too many modifiers: {}",
+                count
+            )));
+        }
+        let mut modifiers = Vec::with_capacity(count);
+        for _ in 0..count {
+            let id = Digest32::scorex_parse(r)?;
+            let len = r.get_u32()? as usize;
+            if len > MAX_MESSAGE_SIZE {
+                return Err(ScorexParsingError::ValueOutOfBounds(format!(
+                    "modifier size {} exceeds the maximum message size",
+                    len
+                )));
+            }
+            let mut bytes = vec![0; len];
+            r.read_exact(&mut bytes)?;
+            modifiers.push((id, bytes));
+        }
+        Ok(ModifiersData { type_id, modifiers })
+    }
+}
@@ -3,6 +3,8 @@ use ergo_chain_types::ConnectionDirection;
 use ergo_chain_types::PeerAddr;
 use futures::Future;
 use futures::FutureExt;
+use futures::SinkExt;
+use futures::StreamExt;
 use std::pin::Pin;
 use std::task::Context;
 use std::task::Poll;
@@ -17,16 +19,29 @@ use tracing::debug_span;
 use tracing::Instrument;
 
 use crate::codec::Codec;
+use crate::codec::Packet;
 use crate::constants;
 use crate::error::BoxError;
 use crate::error::HandshakeError;
 use crate::message::Handshake;
 use crate::Client;
 use crate::PeerInfo;
+use crate::PeerSpec;
 
 /// A service that handshakes with a remote peer and constructs a client/server pair.
 #[derive(Clone)]
-pub struct PeerConnectionHandler {}
+pub struct PeerConnectionHandler {
+    /// Our spec sent in the handshake
+    local_spec: PeerSpec,
+    /// Network magic bytes
+    magic: [u8; 4],
+}
+
+impl PeerConnectionHandler {
+    pub(crate) fn new(local_spec: PeerSpec, magic: [u8; 4]) -> Self {
+        PeerConnectionHandler { local_spec, magic }
+    }
+}
 
 impl Service<HandshakeRequest> for PeerConnectionHandler {
     type Response = Client;
@@ -40,13 +55,15 @@ impl Service<HandshakeRequest> for PeerConnectionHandler {
 
     fn call(&mut self, req: HandshakeRequest) -> Self::Future {
         let negotiator_span = debug_span!("negotiator", conn = ?req.connection_id);
+        let local_spec = self.local_spec.clone();
+        let magic = self.magic;
         let fut = async move {
             let (server_tx, _server_rx) = mpsc::channel(0);
             debug!( conn = ?req.connection_id, "handshake with remote peer");
-            let mut peer_conn = Framed::new(req.tcp_stream, Codec::default());
+            let mut peer_conn = Framed::new(req.tcp_stream, Codec::new(magic));
             let peer_handshake = timeout(
                 constants::HANDSHAKE_TIMEOUT,
-                send_receive_handshake(&mut peer_conn),
+                send_receive_handshake(&mut peer_conn, local_spec),
             )
             .await??;
             let last_handshake = Utc::now().timestamp();
@@ -70,10 +87,16 @@ impl Service<HandshakeRequest> for PeerConnectionHandler {
 }
 
 async fn send_receive_handshake(
-    _peer_conn: &mut Framed<TcpStream, Codec>,
+    peer_conn: &mut Framed<TcpStream, Codec>,
+    local_spec: PeerSpec,
 ) -> Result<Handshake, HandshakeError> {
-    // TODO: do a handshake
-    todo!()
+    peer_conn.send(Handshake::new(local_spec)).await?;
+    match peer_conn.next().await {
+        Some(Ok(Packet::Handshake(handshake))) => Ok(handshake),
+        Some(Ok(Packet::Message(_))) => Err(HandshakeError::UnexpectedMessage),
+        Some(Err(e)) => Err(e.into()),
+        None => Err(HandshakeError::ConnectionClosed),
+    }
 }
 
 pub struct HandshakeRequest {
@@ -89,7 +112,10 @@ pub struct ConnectionId {
     direction: ConnectionDirection,
 }
 impl ConnectionId {
-    pub(crate) fn new_outbound_direct(_addr: PeerAddr) -> Self {
-        todo!()
+    pub(crate) fn new_outbound_direct(addr: PeerAddr) -> Self {
+        ConnectionId {
+            remote_address: addr,
+            direction: ConnectionDirection::Outgoing,
+        }
     }
 }
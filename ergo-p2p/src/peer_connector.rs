@@ -16,12 +16,22 @@ use crate::peer_connection_handler::ConnectionId;
 use crate::peer_connection_handler::HandshakeRequest;
 use crate::peer_connection_handler::PeerConnectionHandler;
 use crate::Client;
+use crate::PeerSpec;
 
 /// Opens a TCP connection before forwarding to the inner peer connection handling service for a handshake.
 pub struct PeerConnector {
     handshaker: PeerConnectionHandler,
 }
 
+impl PeerConnector {
+    /// Connector sending `local_spec` in the handshake on the network with the given magic bytes
+    pub fn new(local_spec: PeerSpec, magic: [u8; 4]) -> Self {
+        PeerConnector {
+            handshaker: PeerConnectionHandler::new(local_spec, magic),
+        }
+    }
+}
+
 /// A connector request.
 /// Contains the information needed to make an outbound connection to the peer.
 pub struct OutboundConnectorRequest {
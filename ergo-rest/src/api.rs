@@ -1,12 +1,27 @@
 //! REST API for the services in Ergo ecosystem (node, explorer, etc.)
 
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::NodeApiErrorJson;
 use crate::reqwest;
-use crate::reqwest::{header::CONTENT_TYPE, Client, RequestBuilder};
+use crate::reqwest::{header::CONTENT_TYPE, Client, RequestBuilder, Response};
 
+use crate::NodeApiError;
 use crate::NodeConf;
+use crate::NodeError;
+use retry::with_retry;
+use retry::RetryPolicy;
 
+pub mod chain_follower;
+#[cfg(feature = "explorer")]
+pub mod explorer;
+pub mod failover;
+pub mod mempool;
 pub mod node;
 mod peer_discovery_internals;
+pub mod retry;
 
 fn set_req_headers(rb: RequestBuilder, node: NodeConf) -> RequestBuilder {
     rb.header("accept", "application/json")
@@ -15,10 +30,71 @@ fn set_req_headers(rb: RequestBuilder, node: NodeConf) -> RequestBuilder {
 }
 
 fn build_client(node_conf: &NodeConf) -> Result<Client, reqwest::Error> {
+    build_client_with_timeout(node_conf.timeout)
+}
+
+fn build_client_with_timeout(timeout: Option<Duration>) -> Result<Client, reqwest::Error> {
     let builder = reqwest::Client::builder();
-    if let Some(t) = node_conf.timeout {
+    if let Some(t) = timeout {
         builder.timeout(t).build()
     } else {
         builder.build()
     }
 }
+
+/// Send the request built by `make_request` (retrying according to `policy`) and parse the JSON
+/// response. Non-success HTTP statuses are mapped to [`NodeError::ApiError`]
+async fn send_and_parse<T: DeserializeOwned>(
+    policy: &RetryPolicy,
+    make_request: impl Fn() -> RequestBuilder,
+) -> Result<T, NodeError> {
+    let make_request = &make_request;
+    with_retry(policy, || async move {
+        let response = check_status(make_request().send().await?).await?;
+        Ok(response.json::<T>().await?)
+    })
+    .await
+}
+
+/// Same as [`send_and_parse`], but returns `None` if the node responded with HTTP 404 (Not Found)
+async fn send_and_parse_opt<T: DeserializeOwned>(
+    policy: &RetryPolicy,
+    make_request: impl Fn() -> RequestBuilder,
+) -> Result<Option<T>, NodeError> {
+    let make_request = &make_request;
+    with_retry(policy, || async move {
+        let response = make_request().send().await?;
+        if response.status().as_u16() == 404 {
+            return Ok(None);
+        }
+        let response = check_status(response).await?;
+        Ok(Some(response.json::<T>().await?))
+    })
+    .await
+}
+
+async fn check_status(response: Response) -> Result<Response, NodeError> {
+    let status = response.status();
+    if status.is_success() {
+        Ok(response)
+    } else {
+        let body = response.json::<NodeApiErrorJson>().await.ok();
+        Err(NodeError::ApiError(NodeApiError::new(
+            status.as_u16(),
+            status.canonical_reason().unwrap_or_default(),
+            body,
+        )))
+    }
+}
+
+/// Cross-platform (tokio/wasm) async sleep
+async fn sleep(duration: Duration) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = crate::wasm_timer::Delay::new(duration).await;
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        tokio::time::sleep(duration).await;
+    }
+}
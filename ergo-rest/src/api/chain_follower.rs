@@ -0,0 +1,416 @@
+//! Following the node's best chain: reporting the applied blocks and detecting chain
+//! reorganizations (blocks rolled back when the node switches to a better fork)
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use ergo_chain_types::BlockId;
+use ergo_chain_types::Header;
+use futures::Stream;
+use thiserror::Error;
+
+use crate::NodeConf;
+use crate::NodeError;
+
+use super::node::get_info;
+use super::node::get_last_headers;
+use super::sleep;
+
+/// Chain follower settings
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct ChainFollowerConfig {
+    /// Delay between the node polls
+    pub poll_interval: Duration,
+    /// Maximum depth of the chain reorganization that can be followed (number of the recent
+    /// headers kept to find the fork point)
+    pub max_rollback_depth: u32,
+}
+
+impl Default for ChainFollowerConfig {
+    fn default() -> Self {
+        ChainFollowerConfig {
+            poll_interval: Duration::from_secs(10),
+            max_rollback_depth: 100,
+        }
+    }
+}
+
+/// Change of the node's best chain
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ChainEvent {
+    /// Block header appended to the best chain
+    Applied(Header),
+    /// Blocks removed from the best chain on reorganization (reported before the blocks of the
+    /// new fork are applied)
+    RolledBack(ChainReorg),
+}
+
+/// Blocks removed from the best chain on reorganization
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ChainReorg {
+    /// Last block common to the old and the new chain
+    pub fork_point: BlockId,
+    /// Height of the fork point
+    pub fork_height: u32,
+    /// Ids of the removed blocks, starting from the old tip
+    pub removed: Vec<BlockId>,
+}
+
+impl ChainReorg {
+    /// Number of the removed blocks
+    pub fn depth(&self) -> usize {
+        self.removed.len()
+    }
+}
+
+/// Chain follower errors
+#[derive(Error, Debug)]
+pub enum ChainFollowerError {
+    /// Node request failed
+    #[error("Node error: {0}")]
+    NodeError(#[from] NodeError),
+    /// Headers returned by the node are not a chain
+    #[error("Header at height {height} does not follow the previous one")]
+    NotAChain {
+        /// Height of the offending header
+        height: u32,
+    },
+    /// Headers do not connect to the followed chain
+    #[error("No common block found with the followed chain")]
+    NoCommonBlock,
+    /// Reorganization is deeper than [`ChainFollowerConfig::max_rollback_depth`]
+    #[error("Chain reorganization is deeper than {0} blocks")]
+    ReorgTooDeep(u32),
+}
+
+/// Tracks the node's best chain, keeping the recent headers to detect the reorganizations
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ChainFollower {
+    config: ChainFollowerConfig,
+    /// Recent best chain headers, ordered by height
+    headers: VecDeque<Header>,
+}
+
+impl ChainFollower {
+    /// Follower starting at the current node's best header (reported as applied on the first poll)
+    pub fn new(config: ChainFollowerConfig) -> Self {
+        ChainFollower {
+            config,
+            headers: VecDeque::new(),
+        }
+    }
+
+    /// Follower resuming from the previously applied headers (ordered by height), e.g. persisted
+    /// by an indexer. Reorganizations that happened meanwhile are reported on the next poll.
+    pub fn with_headers(
+        config: ChainFollowerConfig,
+        headers: Vec<Header>,
+    ) -> Result<Self, ChainFollowerError> {
+        check_chain(&headers)?;
+        let mut follower = ChainFollower::new(config);
+        follower.headers.extend(headers);
+        follower.trim();
+        Ok(follower)
+    }
+
+    /// The last applied header
+    pub fn tip(&self) -> Option<&Header> {
+        self.headers.back()
+    }
+
+    /// Recent applied headers, ordered by height
+    pub fn headers(&self) -> impl Iterator<Item = &Header> {
+        self.headers.iter()
+    }
+
+    /// Update with the last headers of the node's best chain (ordered by height), returning the
+    /// rolled back and the applied blocks.
+    /// Fails with [`ChainFollowerError::NoCommonBlock`] if the headers do not reach back to the
+    /// followed chain (more headers are needed), in which case the follower is not changed.
+    pub fn update(
+        &mut self,
+        new_headers: Vec<Header>,
+    ) -> Result<Vec<ChainEvent>, ChainFollowerError> {
+        check_chain(&new_headers)?;
+        let (first_new, last_common) = match (self.tip(), new_headers.first()) {
+            (_, None) => return Ok(vec![]),
+            (None, Some(_)) => (0, None),
+            (Some(_), Some(first)) => self
+                .headers
+                .iter()
+                .enumerate()
+                .rev()
+                .find_map(|(idx, h)| {
+                    if first.parent_id == h.id {
+                        Some((0, Some(idx)))
+                    } else {
+                        new_headers
+                            .iter()
+                            .position(|nh| nh.id == h.id)
+                            .map(|pos| (pos + 1, Some(idx)))
+                    }
+                })
+                .ok_or(ChainFollowerError::NoCommonBlock)?,
+        };
+        let mut events = Vec::new();
+        if let Some(last_common) = last_common {
+            let removed: Vec<BlockId> = self
+                .headers
+                .drain(last_common + 1..)
+                .rev()
+                .map(|h| h.id)
+                .collect();
+            if !removed.is_empty() {
+                #[allow(clippy::unwrap_used)]
+                // the fork point is not removed
+                let fork_point = self.headers.back().unwrap();
+                events.push(ChainEvent::RolledBack(ChainReorg {
+                    fork_point: fork_point.id,
+                    fork_height: fork_point.height,
+                    removed,
+                }));
+            }
+        }
+        for h in new_headers.into_iter().skip(first_new) {
+            self.headers.push_back(h.clone());
+            events.push(ChainEvent::Applied(h));
+        }
+        self.trim();
+        Ok(events)
+    }
+
+    /// Poll the node's best chain and update the follower (see [`ChainFollower::update`]).
+    /// Requests more headers if needed to find the fork point, up to
+    /// [`ChainFollowerConfig::max_rollback_depth`] blocks below the followed tip.
+    pub async fn poll(&mut self, node: NodeConf) -> Result<Vec<ChainEvent>, ChainFollowerError> {
+        let tip_height = match self.tip() {
+            Some(tip) => tip.height,
+            None => {
+                let headers = get_last_headers(node, 1).await?;
+                return self.update(headers);
+            }
+        };
+        let info = get_info(node).await?;
+        let node_height = info
+            .headers_height
+            .or(info.full_height)
+            .unwrap_or(tip_height);
+        let new_blocks = node_height.saturating_sub(tip_height);
+        let max_count = new_blocks + self.config.max_rollback_depth + 1;
+        // the tip and a block below it, to detect the shallow forks with one request
+        let mut count = new_blocks + 2;
+        loop {
+            let headers = get_last_headers(node, count.min(max_count)).await?;
+            match self.update(headers) {
+                Err(ChainFollowerError::NoCommonBlock) if count < max_count => count *= 2,
+                Err(ChainFollowerError::NoCommonBlock) => {
+                    return Err(ChainFollowerError::ReorgTooDeep(
+                        self.config.max_rollback_depth,
+                    ))
+                }
+                res => return res,
+            }
+        }
+    }
+
+    fn trim(&mut self) {
+        let max_len = self.config.max_rollback_depth as usize + 1;
+        while self.headers.len() > max_len {
+            self.headers.pop_front();
+        }
+    }
+}
+
+fn check_chain(headers: &[Header]) -> Result<(), ChainFollowerError> {
+    headers.windows(2).try_for_each(|w| match w {
+        [prev, next] if next.parent_id == prev.id && next.height == prev.height + 1 => Ok(()),
+        [_, next] => Err(ChainFollowerError::NotAChain {
+            height: next.height,
+        }),
+        _ => Ok(()),
+    })
+}
+
+/// Stream of the node's best chain changes (polled every `config.poll_interval`), starting at the
+/// given follower state (e.g. `ChainFollower::new(config)` to start at the current best header).
+/// Polling errors are reported as stream items and the polling continues.
+pub fn chain_events_stream(
+    node: NodeConf,
+    follower: ChainFollower,
+) -> impl Stream<Item = Result<ChainEvent, ChainFollowerError>> {
+    struct State {
+        follower: ChainFollower,
+        pending: VecDeque<ChainEvent>,
+        polls: u64,
+    }
+    let init = State {
+        follower,
+        pending: VecDeque::new(),
+        polls: 0,
+    };
+    futures::stream::unfold(init, move |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                return Some((Ok(event), state));
+            }
+            if state.polls > 0 {
+                sleep(state.follower.config.poll_interval).await;
+            }
+            state.polls += 1;
+            match state.follower.poll(node).await {
+                Ok(events) => state.pending.extend(events),
+                Err(e) => return Some((Err(e), state)),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use super::*;
+    use ergo_chain_types::Digest32;
+
+    fn genesis() -> Header {
+        let json = r#"{
+            "extensionId": "d16f25b14457186df4c5f6355579cc769261ce1aebc8209949ca6feadbac5a3f",
+            "difficulty": "626412390187008",
+            "votes": "040000",
+            "timestamp": 1618929697400,
+            "size": 221,
+            "stateRoot": "8ad868627ea4f7de6e2a2fe3f98fafe57f914e0f2ef3331c006def36c697f92713",
+            "height": 471746,
+            "nBits": 117586360,
+            "version": 2,
+            "id": "4caa17e62fe66ba7bd69597afdc996ae35b1ff12e0ba90c22ff288a4de10e91b",
+            "adProofsRoot": "d882aaf42e0a95eb95fcce5c3705adf758e591532f733efe790ac3c404730c39",
+            "transactionsRoot": "63eaa9aff76a1de3d71c81e4b2d92e8d97ae572a8e9ab9e66599ed0912dd2f8b",
+            "extensionHash": "3f91f3c680beb26615fdec251aee3f81aaf5a02740806c167c0f3c929471df44",
+            "powSolutions": {
+              "pk": "02b3a06d6eaa8671431ba1db4dd427a77f75a5c2acbd71bfb725d38adc2b55f669",
+              "w": "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+              "n": "5939ecfee6b0d7f4",
+              "d": 1234000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000
+            },
+            "adProofsId": "86eaa41f328bee598e33e52c9e515952ad3b7874102f762847f17318a776a7ae",
+            "transactionsId": "ac80245714f25aa2fafe5494ad02a26d46e7955b8f5709f3659f1b9440797b3e",
+            "parentId": "6481752bace5fa5acba5d5ef7124d48826664742d46c974c98a2d60ace229a34"
+        }"#;
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// Chain of `len` headers on top of `parent`, ids are made unique with `fork`
+    fn chain(parent: &Header, len: u8, fork: u8) -> Vec<Header> {
+        let mut res: Vec<Header> = Vec::new();
+        for i in 0..len {
+            let prev = res.last().unwrap_or(parent).clone();
+            let mut id = [fork; 32];
+            id[0] = i;
+            id[1..5].copy_from_slice(&prev.height.to_be_bytes());
+            res.push(Header {
+                id: BlockId(Digest32::from(id)),
+                parent_id: prev.id,
+                height: prev.height + 1,
+                ..prev
+            });
+        }
+        res
+    }
+
+    fn applied(events: &[ChainEvent]) -> Vec<BlockId> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                ChainEvent::Applied(h) => Some(h.id),
+                ChainEvent::RolledBack(_) => None,
+            })
+            .collect()
+    }
+
+    fn ids(headers: &[Header]) -> Vec<BlockId> {
+        headers.iter().map(|h| h.id).collect()
+    }
+
+    #[test]
+    fn test_apply_new_blocks() {
+        let g = genesis();
+        let main = chain(&g, 5, 1);
+        let mut follower = ChainFollower::new(ChainFollowerConfig::default());
+        let events = follower.update(vec![g.clone()]).unwrap();
+        assert_eq!(applied(&events), vec![g.id]);
+        // overlapping with the followed chain
+        let events = follower.update(main[0..3].to_vec()).unwrap();
+        assert_eq!(applied(&events), ids(&main[0..3]));
+        // starting right after the tip
+        let events = follower.update(main[3..5].to_vec()).unwrap();
+        assert_eq!(applied(&events), ids(&main[3..5]));
+        assert!(follower.update(main[2..5].to_vec()).unwrap().is_empty());
+        assert_eq!(follower.tip().unwrap().id, main[4].id);
+    }
+
+    #[test]
+    fn test_reorg() {
+        let g = genesis();
+        let main = chain(&g, 5, 1);
+        let mut follower = ChainFollower::with_headers(
+            ChainFollowerConfig::default(),
+            [vec![g], main.clone()].concat(),
+        )
+        .unwrap();
+        // fork from main[1], longer than the followed chain
+        let fork = chain(&main[1], 4, 2);
+        let events = follower
+            .update([main[0..2].to_vec(), fork.clone()].concat())
+            .unwrap();
+        assert_eq!(
+            events[0],
+            ChainEvent::RolledBack(ChainReorg {
+                fork_point: main[1].id,
+                fork_height: main[1].height,
+                removed: vec![main[4].id, main[3].id, main[2].id],
+            })
+        );
+        assert_eq!(applied(&events), ids(&fork));
+        assert_eq!(follower.tip().unwrap().id, fork[3].id);
+    }
+
+    #[test]
+    fn test_no_common_block() {
+        let g = genesis();
+        let main = chain(&g, 5, 1);
+        let mut follower =
+            ChainFollower::with_headers(ChainFollowerConfig::default(), main[0..2].to_vec())
+                .unwrap();
+        // gap in between
+        assert!(matches!(
+            follower.update(main[3..5].to_vec()),
+            Err(ChainFollowerError::NoCommonBlock)
+        ));
+        assert_eq!(follower.tip().unwrap().id, main[1].id);
+        assert!(matches!(
+            follower.update(vec![main[2].clone(), main[4].clone()]),
+            Err(ChainFollowerError::NotAChain { .. })
+        ));
+    }
+
+    #[test]
+    fn test_max_rollback_depth() {
+        let g = genesis();
+        let config = ChainFollowerConfig {
+            max_rollback_depth: 2,
+            ..Default::default()
+        };
+        let main = chain(&g, 5, 1);
+        let mut follower = ChainFollower::with_headers(config, main.clone()).unwrap();
+        assert_eq!(
+            ids(&follower.headers().cloned().collect::<Vec<_>>()),
+            ids(&main[2..5])
+        );
+        // fork below the kept headers
+        let fork = chain(&main[0], 6, 2);
+        assert!(matches!(
+            follower.update([main[0..1].to_vec(), fork].concat()),
+            Err(ChainFollowerError::NoCommonBlock)
+        ));
+    }
+}
@@ -0,0 +1,317 @@
+//! Ergo Explorer REST API (v1) endpoints
+
+use std::time::Duration;
+
+use ergo_chain_types::BlockId;
+use ergo_chain_types::Digest32;
+use ergotree_ir::chain::address::NetworkAddress;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::chain::tx_id::TxId;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::Url;
+
+use crate::NodeError;
+
+use super::build_client_with_timeout;
+use super::retry::RetryPolicy;
+use super::send_and_parse;
+use super::send_and_parse_opt;
+
+/// Explorer API configuration
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ExplorerConf {
+    /// Explorer API base URL (e.g. `https://api.ergoplatform.com/`)
+    pub url: Url,
+    /// Request timeout (of each attempt)
+    pub timeout: Option<Duration>,
+    /// Retry policy
+    pub retry: RetryPolicy,
+}
+
+impl ExplorerConf {
+    /// Mainnet public Explorer API
+    #[allow(clippy::unwrap_used)]
+    pub fn mainnet() -> Self {
+        ExplorerConf {
+            url: Url::parse("https://api.ergoplatform.com/").unwrap(),
+            timeout: None,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Testnet public Explorer API
+    #[allow(clippy::unwrap_used)]
+    pub fn testnet() -> Self {
+        ExplorerConf {
+            url: Url::parse("https://api-testnet.ergoplatform.com/").unwrap(),
+            timeout: None,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    // joining a relative path to the http(s) url never fails
+    #[allow(clippy::unwrap_used)]
+    fn endpoint(&self, path: &str) -> Url {
+        self.url.join(path).unwrap()
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, NodeError> {
+        let client = build_client_with_timeout(self.timeout)?;
+        let url = self.endpoint(path);
+        send_and_parse(&self.retry, || {
+            client.get(url.clone()).header("accept", "application/json")
+        })
+        .await
+    }
+
+    async fn get_opt<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>, NodeError> {
+        let client = build_client_with_timeout(self.timeout)?;
+        let url = self.endpoint(path);
+        send_and_parse_opt(&self.retry, || {
+            client.get(url.clone()).header("accept", "application/json")
+        })
+        .await
+    }
+}
+
+/// Page of the items with the total number of the items
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Items<T> {
+    /// Items on the requested page
+    pub items: Vec<T>,
+    /// Total number of the items
+    pub total: u64,
+}
+
+/// Confirmed balance of the address
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct AddressBalance {
+    /// Amount of nanoERGs
+    #[serde(rename = "nanoErgs")]
+    pub nano_ergs: u64,
+    /// Token amounts
+    pub tokens: Vec<TokenBalance>,
+}
+
+/// Amount of the token held by the address
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TokenBalance {
+    /// Token id
+    #[serde(rename = "tokenId")]
+    pub token_id: TokenId,
+    /// Token amount
+    pub amount: u64,
+    /// Number of decimals (EIP-4)
+    #[serde(default)]
+    pub decimals: Option<u32>,
+    /// Token name (EIP-4)
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Token info
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TokenInfo {
+    /// Token id
+    pub id: TokenId,
+    /// Id of the box where the token was issued
+    #[serde(rename = "boxId")]
+    pub box_id: BoxId,
+    /// Number of the issued tokens
+    #[serde(rename = "emissionAmount")]
+    pub emission_amount: u64,
+    /// Token name (EIP-4)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Token description (EIP-4)
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Token type (EIP-4)
+    #[serde(rename = "type", default)]
+    pub token_type: Option<String>,
+    /// Number of decimals (EIP-4)
+    #[serde(default)]
+    pub decimals: Option<u32>,
+}
+
+/// Transaction input info
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct InputInfo {
+    /// Id of the spent box
+    #[serde(rename = "boxId")]
+    pub box_id: BoxId,
+    /// Value of the spent box
+    pub value: u64,
+    /// Address of the spent box
+    pub address: String,
+    /// Id of the transaction which created the spent box
+    #[serde(rename = "outputTransactionId")]
+    pub output_transaction_id: TxId,
+}
+
+/// Confirmed transaction info
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TransactionInfo {
+    /// Transaction id
+    pub id: TxId,
+    /// Id of the block the transaction is included in
+    #[serde(rename = "blockId")]
+    pub block_id: BlockId,
+    /// Height of the block the transaction is included in
+    #[serde(rename = "inclusionHeight")]
+    pub inclusion_height: u32,
+    /// Timestamp of the block the transaction is included in
+    pub timestamp: u64,
+    /// Number of the confirmations
+    #[serde(rename = "numConfirmations")]
+    pub num_confirmations: u32,
+    /// Inputs
+    pub inputs: Vec<InputInfo>,
+    /// Outputs
+    pub outputs: Vec<ErgoBox>,
+}
+
+/// GET on /api/v1/addresses/{address}/balance/confirmed endpoint
+pub async fn get_address_balance(
+    explorer: &ExplorerConf,
+    address: &NetworkAddress,
+) -> Result<AddressBalance, NodeError> {
+    let path = format!("api/v1/addresses/{}/balance/confirmed", address.to_base58());
+    explorer.get(&path).await
+}
+
+/// GET on /api/v1/boxes/unspent/byAddress/{address} endpoint
+pub async fn get_unspent_boxes_by_address(
+    explorer: &ExplorerConf,
+    address: &NetworkAddress,
+    offset: u32,
+    limit: u32,
+) -> Result<Items<ErgoBox>, NodeError> {
+    let path = format!(
+        "api/v1/boxes/unspent/byAddress/{}?offset={}&limit={}",
+        address.to_base58(),
+        offset,
+        limit
+    );
+    explorer.get(&path).await
+}
+
+/// GET on /api/v1/boxes/unspent/byErgoTreeTemplateHash/{hash} endpoint, where `template_hash` is
+/// SHA-256 hash of the ErgoTree template bytes
+pub async fn get_unspent_boxes_by_template_hash(
+    explorer: &ExplorerConf,
+    template_hash: Digest32,
+    offset: u32,
+    limit: u32,
+) -> Result<Items<ErgoBox>, NodeError> {
+    let path = format!(
+        "api/v1/boxes/unspent/byErgoTreeTemplateHash/{}?offset={}&limit={}",
+        template_hash, offset, limit
+    );
+    explorer.get(&path).await
+}
+
+/// GET on /api/v1/boxes/unspent/byTokenId/{id} endpoint
+pub async fn get_unspent_boxes_by_token_id(
+    explorer: &ExplorerConf,
+    token_id: TokenId,
+    offset: u32,
+    limit: u32,
+) -> Result<Items<ErgoBox>, NodeError> {
+    let path = format!(
+        "api/v1/boxes/unspent/byTokenId/{}?offset={}&limit={}",
+        token_id, offset, limit
+    );
+    explorer.get(&path).await
+}
+
+/// GET on /api/v1/tokens/{id} endpoint. Returns `None` if the token is not found
+pub async fn get_token_info(
+    explorer: &ExplorerConf,
+    token_id: TokenId,
+) -> Result<Option<TokenInfo>, NodeError> {
+    let path = format!("api/v1/tokens/{}", token_id);
+    explorer.get_opt(&path).await
+}
+
+/// GET on /api/v1/transactions/{id} endpoint. Returns `None` if the transaction is not found
+pub async fn get_transaction(
+    explorer: &ExplorerConf,
+    tx_id: TxId,
+) -> Result<Option<TransactionInfo>, NodeError> {
+    let path = format!("api/v1/transactions/{}", tx_id);
+    explorer.get_opt(&path).await
+}
+
+#[cfg(test)]
+#[cfg(feature = "json")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address_balance() {
+        let json = r#"{
+            "nanoErgs": 1000000000,
+            "tokens": [
+                {
+                    "tokenId": "03faf2cb329f2e90d6d23b58d91bbb6c046aa143261cc21f52fbe2824bfcbf04",
+                    "amount": 150,
+                    "decimals": 2,
+                    "name": "SigUSD",
+                    "tokenType": "EIP-004"
+                }
+            ]
+        }"#;
+        let balance: AddressBalance = serde_json::from_str(json).unwrap();
+        assert_eq!(balance.nano_ergs, 1000000000);
+        assert_eq!(balance.tokens[0].amount, 150);
+        assert_eq!(balance.tokens[0].name, Some("SigUSD".to_string()));
+    }
+
+    #[test]
+    fn test_parse_token_info() {
+        let json = r#"{
+            "id": "03faf2cb329f2e90d6d23b58d91bbb6c046aa143261cc21f52fbe2824bfcbf04",
+            "boxId": "60e39e5bd7d1c2f3f4b4efe1a62b2bd2a9e6c4b4f9a0ae3c3d0b8d2d4eb2e9b1",
+            "emissionAmount": 10000001,
+            "name": "SigUSD",
+            "description": "SigmaUSD - V2",
+            "type": "EIP-004",
+            "decimals": 2
+        }"#;
+        let info: TokenInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.emission_amount, 10000001);
+        assert_eq!(info.token_type, Some("EIP-004".to_string()));
+    }
+
+    #[test]
+    fn test_parse_unspent_boxes_page() {
+        let json = r#"{
+            "items": [
+                {
+                    "boxId": "e56847ed19b3dc6b72828fcfb992fdf7310828cf291221269b7ffc72fd66706e",
+                    "transactionId": "9148408c04c2e38a6402a7950d6157730fa7d49e9ab3b9cadec481d7769918e9",
+                    "blockId": "4caa17e62fe66ba7bd69597afdc996ae35b1ff12e0ba90c22ff288a4de10e91b",
+                    "value": 67500000000,
+                    "index": 1,
+                    "creationHeight": 284761,
+                    "settlementHeight": 284762,
+                    "ergoTree": "100204a00b08cd021dde34603426402615658f1d970cfa7c7bd92ac81a8b16eeebff264d59ce4604ea02d192a39a8cc7a70173007301",
+                    "address": "88dhgzEuTXaRQTX5KNdnaWTTX7fEZVEQRn6qP4MJotPuRnS3QpoJxYpSaXoU1y7SHp8ZXMp92TH22DBY",
+                    "assets": [],
+                    "additionalRegisters": {},
+                    "spentTransactionId": null,
+                    "mainChain": true
+                }
+            ],
+            "total": 1
+        }"#;
+        let page: Items<ErgoBox> = serde_json::from_str(json).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].creation_height, 284761);
+    }
+}
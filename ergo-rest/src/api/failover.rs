@@ -0,0 +1,214 @@
+//! Failover between multiple nodes, preferring the synced nodes with the lowest latency
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::wasm_timer::Instant;
+use crate::NodeConf;
+use crate::NodeError;
+
+use super::node::get_info;
+use super::retry::is_retryable;
+use super::retry::RetryPolicy;
+
+/// Node with the stats collected on the last [`NodePool::rank`]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct RankedNode {
+    /// Node configuration
+    pub node: NodeConf,
+    /// `/info` response time, `None` if the node was not reachable (or not ranked yet)
+    pub latency: Option<Duration>,
+    /// Full height reported by the node
+    pub height: Option<u32>,
+}
+
+/// Set of the nodes to send the requests to. The request is sent to the next node if it fails
+/// with a retryable error (see [`is_retryable`]) on the previous one.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct NodePool {
+    nodes: Vec<RankedNode>,
+    max_height_lag: u32,
+}
+
+impl NodePool {
+    /// Nodes are tried in the given order until [`NodePool::rank`] is called
+    pub fn new(nodes: Vec<NodeConf>) -> Self {
+        NodePool {
+            nodes: nodes
+                .into_iter()
+                .map(|node| RankedNode {
+                    node,
+                    latency: None,
+                    height: None,
+                })
+                .collect(),
+            max_height_lag: 1,
+        }
+    }
+
+    /// Number of blocks a node can be behind the highest one to still be considered synced
+    /// (default is 1)
+    pub fn with_max_height_lag(self, max_height_lag: u32) -> Self {
+        NodePool {
+            max_height_lag,
+            ..self
+        }
+    }
+
+    /// Nodes in the order they are tried
+    pub fn nodes(&self) -> &[RankedNode] {
+        &self.nodes
+    }
+
+    /// Query `/info` on every node (without retries) and order the nodes: synced nodes (at most
+    /// `max_height_lag` blocks behind the highest one) by latency first, then the lagging nodes
+    /// by height, then the unreachable ones
+    pub async fn rank(&mut self) {
+        let probes = self.nodes.iter().map(|n| probe(n.node));
+        self.nodes = futures::future::join_all(probes).await;
+        sort_ranked(&mut self.nodes, self.max_height_lag);
+    }
+
+    /// Call `f` with the nodes in order until it succeeds or fails with a non-retryable error.
+    /// Returns the error of the last tried node if all of them failed. Since a failed request
+    /// is sent again (to the next node), use it for the idempotent requests, or for the
+    /// transaction submission only if a repeated submission is acceptable.
+    pub async fn request<T, F, Fut>(&self, mut f: F) -> Result<T, NodeError>
+    where
+        F: FnMut(NodeConf) -> Fut,
+        Fut: Future<Output = Result<T, NodeError>>,
+    {
+        let mut last_err = NodeError::NoNodesAvailable;
+        for ranked in &self.nodes {
+            match f(ranked.node).await {
+                Err(e) if is_retryable(&e) => last_err = e,
+                res => return res,
+            }
+        }
+        Err(last_err)
+    }
+}
+
+async fn probe(node: NodeConf) -> RankedNode {
+    let probe_conf = NodeConf {
+        retry: RetryPolicy::no_retry(),
+        ..node
+    };
+    let start = Instant::now();
+    match get_info(probe_conf).await {
+        Ok(info) => RankedNode {
+            node,
+            latency: Some(start.elapsed()),
+            height: info.full_height,
+        },
+        Err(_) => RankedNode {
+            node,
+            latency: None,
+            height: None,
+        },
+    }
+}
+
+fn sort_ranked(nodes: &mut [RankedNode], max_height_lag: u32) {
+    let best_height = nodes.iter().filter_map(|n| n.height).max().unwrap_or(0);
+    // (unreachable, lagging, blocks behind, latency)
+    nodes.sort_by_key(|n| match (n.latency, n.height) {
+        (Some(latency), Some(height)) => {
+            let lag = best_height - height;
+            if lag <= max_height_lag {
+                (false, false, 0, latency)
+            } else {
+                (false, true, lag, latency)
+            }
+        }
+        (Some(latency), None) => (false, true, u32::MAX, latency),
+        (None, _) => (true, true, u32::MAX, Duration::MAX),
+    });
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::cell::Cell;
+    use std::str::FromStr;
+
+    use ergo_chain_types::PeerAddr;
+
+    use crate::NodeApiError;
+
+    use super::*;
+
+    fn ranked(port: u16, latency_ms: Option<u64>, height: Option<u32>) -> RankedNode {
+        RankedNode {
+            node: NodeConf {
+                addr: PeerAddr::from_str(&format!("127.0.0.1:{}", port)).unwrap(),
+                api_key: None,
+                timeout: None,
+                retry: RetryPolicy::no_retry(),
+            },
+            latency: latency_ms.map(Duration::from_millis),
+            height,
+        }
+    }
+
+    fn ports(nodes: &[RankedNode]) -> Vec<u16> {
+        nodes.iter().map(|n| n.node.addr.0.port()).collect()
+    }
+
+    #[test]
+    fn test_sort_ranked() {
+        let mut nodes = vec![
+            ranked(1, None, None),
+            ranked(2, Some(50), Some(90)),
+            ranked(3, Some(300), Some(100)),
+            ranked(4, Some(100), Some(99)),
+            ranked(5, Some(10), Some(95)),
+            ranked(6, Some(5), None),
+        ];
+        sort_ranked(&mut nodes, 1);
+        assert_eq!(ports(&nodes), vec![4, 3, 5, 2, 6, 1]);
+    }
+
+    #[test]
+    fn test_request_failover() {
+        let pool = NodePool {
+            nodes: vec![
+                ranked(1, None, None),
+                ranked(2, None, None),
+                ranked(3, None, None),
+            ],
+            max_height_lag: 1,
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let tried = Cell::new(0);
+        let res = runtime.block_on(pool.request(|node| {
+            tried.set(tried.get() + 1);
+            let port = node.addr.0.port();
+            async move {
+                match port {
+                    1 => Err(NodeError::ApiError(NodeApiError {
+                        status: 503,
+                        reason: String::new(),
+                        detail: None,
+                    })),
+                    2 => Err(NodeError::ApiError(NodeApiError {
+                        status: 400,
+                        reason: String::new(),
+                        detail: None,
+                    })),
+                    _ => Ok(port),
+                }
+            }
+        }));
+        // non-retryable error on the second node is returned without trying the third one
+        assert!(matches!(res, Err(NodeError::ApiError(e)) if e.status == 400));
+        assert_eq!(tried.get(), 2);
+
+        let empty = NodePool::new(vec![]);
+        let res: Result<(), _> = runtime.block_on(empty.request(|_| async { Ok(()) }));
+        assert!(matches!(res, Err(NodeError::NoNodesAvailable)));
+    }
+}
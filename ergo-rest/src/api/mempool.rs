@@ -0,0 +1,175 @@
+//! Mempool monitoring: polling the node for unconfirmed transactions and reporting the new ones
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use ergotree_ir::chain::tx_id::TxId;
+use futures::Stream;
+use serde::de::DeserializeOwned;
+
+use crate::NodeConf;
+use crate::NodeError;
+
+use super::node::get_unconfirmed_transactions;
+use super::sleep;
+
+/// Mempool polling settings
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct MempoolStreamConfig {
+    /// Delay between the mempool polls
+    pub poll_interval: Duration,
+    /// Number of transactions requested from the node at once (`limit` of
+    /// `/transactions/unconfirmed`)
+    pub page_size: u32,
+    /// Do not report the transactions already in the mempool on the first poll
+    pub skip_existing: bool,
+}
+
+impl Default for MempoolStreamConfig {
+    fn default() -> Self {
+        MempoolStreamConfig {
+            poll_interval: Duration::from_secs(5),
+            page_size: 100,
+            skip_existing: false,
+        }
+    }
+}
+
+/// Tracks the transactions seen in the mempool to report only the new ones on every poll.
+/// Only the transactions currently in the mempool are remembered.
+#[derive(Debug, Clone, Default)]
+pub struct MempoolTracker {
+    seen: HashSet<TxId>,
+}
+
+impl MempoolTracker {
+    /// Tracker which has not seen any transactions yet
+    pub fn new() -> Self {
+        MempoolTracker::default()
+    }
+
+    /// Update with the current mempool transactions, returning the ones not seen on the previous
+    /// update (in the mempool order)
+    pub fn update<T, F>(&mut self, mempool: Vec<T>, tx_id: F) -> Vec<T>
+    where
+        F: Fn(&T) -> TxId,
+    {
+        let mut current = HashSet::with_capacity(mempool.len());
+        let new_txs = mempool
+            .into_iter()
+            .filter(|tx| {
+                let id = tx_id(tx);
+                current.insert(id) && !self.seen.contains(&id)
+            })
+            .collect();
+        self.seen = current;
+        new_txs
+    }
+
+    /// Returns `true` if the transaction was in the mempool on the last update
+    pub fn contains(&self, tx_id: &TxId) -> bool {
+        self.seen.contains(tx_id)
+    }
+}
+
+/// Fetch all the mempool transactions (e.g. `ergo_lib::chain::transaction::Transaction`),
+/// requesting `/transactions/unconfirmed` page by page
+pub async fn get_all_unconfirmed_transactions<T: DeserializeOwned>(
+    node: NodeConf,
+    page_size: u32,
+) -> Result<Vec<T>, NodeError> {
+    if page_size == 0 {
+        return Err(NodeError::InvalidNumericalUrlSegment);
+    }
+    let mut res = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page: Vec<T> = get_unconfirmed_transactions(node, offset, page_size).await?;
+        let last_page = (page.len() as u32) < page_size;
+        res.extend(page);
+        if last_page {
+            return Ok(res);
+        }
+        offset += page_size;
+    }
+}
+
+/// Stream of the transactions newly seen in the node's mempool (e.g.
+/// `mempool_stream(node, config, Transaction::id)` with `ergo_lib` transactions).
+/// The mempool is polled every `config.poll_interval`, but only when all the transactions from the
+/// previous poll are consumed, so a slow consumer is not flooded. Polling errors are reported as
+/// stream items and the polling continues.
+pub fn mempool_stream<T, F>(
+    node: NodeConf,
+    config: MempoolStreamConfig,
+    tx_id: F,
+) -> impl Stream<Item = Result<T, NodeError>>
+where
+    T: DeserializeOwned,
+    F: Fn(&T) -> TxId,
+{
+    struct State<T, F> {
+        tracker: MempoolTracker,
+        pending: VecDeque<T>,
+        polls: u64,
+        tx_id: F,
+    }
+    let init = State {
+        tracker: MempoolTracker::new(),
+        pending: VecDeque::new(),
+        polls: 0,
+        tx_id,
+    };
+    futures::stream::unfold(init, move |mut state| async move {
+        loop {
+            if let Some(tx) = state.pending.pop_front() {
+                return Some((Ok(tx), state));
+            }
+            if state.polls > 0 {
+                sleep(config.poll_interval).await;
+            }
+            state.polls += 1;
+            match get_all_unconfirmed_transactions::<T>(node, config.page_size).await {
+                Ok(mempool) => {
+                    let new_txs = state.tracker.update(mempool, &state.tx_id);
+                    if !(state.polls == 1 && config.skip_existing) {
+                        state.pending.extend(new_txs);
+                    }
+                }
+                Err(e) => return Some((Err(e), state)),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ergo_chain_types::Digest32;
+
+    fn tx_id(i: u8) -> TxId {
+        TxId(Digest32::from([i; 32]))
+    }
+
+    #[test]
+    fn test_tracker_reports_new_txs() {
+        let ids: Vec<TxId> = (0..4).map(tx_id).collect();
+        let mut tracker = MempoolTracker::new();
+        assert_eq!(tracker.update(ids[0..2].to_vec(), |id| *id), ids[0..2]);
+        assert!(tracker.contains(&ids[0]));
+        // ids[0] left the mempool, ids[2..4] are new
+        assert_eq!(tracker.update(ids[1..4].to_vec(), |id| *id), ids[2..4]);
+        assert!(!tracker.contains(&ids[0]));
+        assert!(tracker.update(ids[1..4].to_vec(), |id| *id).is_empty());
+        // re-entered the mempool
+        assert_eq!(tracker.update(ids[0..1].to_vec(), |id| *id), ids[0..1]);
+    }
+
+    #[test]
+    fn test_tracker_skips_duplicates() {
+        let id = tx_id(0);
+        let mut tracker = MempoolTracker::new();
+        assert_eq!(tracker.update(vec![id, id], |id| *id), vec![id]);
+    }
+}
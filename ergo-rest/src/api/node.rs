@@ -6,7 +6,12 @@ use ergo_chain_types::BlockId;
 use ergo_chain_types::Header;
 use ergo_merkle_tree::MerkleProof;
 use ergo_nipopow::NipopowProof;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::token::TokenId;
 use ergotree_ir::chain::tx_id::TxId;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::time::Duration;
 use url::Url;
 
@@ -16,6 +21,8 @@ use crate::NodeError;
 use crate::NodeInfo;
 
 use super::build_client;
+use super::send_and_parse;
+use super::send_and_parse_opt;
 use super::set_req_headers;
 
 #[cfg(target_arch = "wasm32")]
@@ -23,15 +30,12 @@ pub use crate::api::peer_discovery_internals::ChromePeerDiscoveryScan;
 
 /// GET on /info endpoint
 pub async fn get_info(node: NodeConf) -> Result<NodeInfo, NodeError> {
-    #[allow(clippy::unwrap_used)]
-    let url = node.addr.as_http_url().join("info").unwrap();
+    let url = node_url(&node, "info");
     let client = build_client(&node)?;
-    let rb = client.get(url);
-    Ok(set_req_headers(rb, node)
-        .send()
-        .await?
-        .json::<NodeInfo>()
-        .await?)
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
 }
 
 /// GET on /blocks/{header_id}/header endpoint
@@ -40,15 +44,12 @@ pub async fn get_header(node: NodeConf, header_id: BlockId) -> Result<Header, No
     let mut path = "blocks/".to_owned();
     path.push_str(&header_str);
     path.push_str("/header");
-    #[allow(clippy::unwrap_used)]
-    let url = node.addr.as_http_url().join(&path).unwrap();
+    let url = node_url(&node, &path);
     let client = build_client(&node)?;
-    let rb = client.get(url);
-    Ok(set_req_headers(rb, node)
-        .send()
-        .await?
-        .json::<Header>()
-        .await?)
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
 }
 
 /// Given a list of seed nodes, search for peer nodes with an active REST API on port 9053.
@@ -134,15 +135,12 @@ pub async fn get_nipopow_proof_by_header_id(
     path.push_str(&suffix_len.to_string());
     path.push('/');
     path.push_str(&header_str);
-    #[allow(clippy::unwrap_used)]
-    let url = node.addr.as_http_url().join(&path).unwrap();
+    let url = node_url(&node, &path);
     let client = build_client(&node)?;
-    let rb = client.get(url);
-    Ok(set_req_headers(rb, node)
-        .send()
-        .await?
-        .json::<NipopowProof>()
-        .await?)
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
 }
 
 /// GET on /blocks/{header_id}/proofFor/{tx_id} to request the merkle proof for a given transaction
@@ -158,15 +156,199 @@ pub async fn get_blocks_header_id_proof_for_tx_id(
     path.push_str("/proofFor/");
     let tx_id_str = String::from(tx_id);
     path.push_str(&tx_id_str);
-    #[allow(clippy::unwrap_used)]
-    let url = node.addr.as_http_url().join(&path).unwrap();
+    let url = node_url(&node, &path);
+    let client = build_client(&node)?;
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// GET on /blocks/at/{height} endpoint. Returns ids of the blocks at the given height (the first
+/// one is on the best chain)
+pub async fn get_blocks_at_height(node: NodeConf, height: u32) -> Result<Vec<BlockId>, NodeError> {
+    let url = node_url(&node, &format!("blocks/at/{}", height));
+    let client = build_client(&node)?;
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// GET on /blocks/lastHeaders/{count} endpoint. Returns the last `count` headers of the best chain
+pub async fn get_last_headers(node: NodeConf, count: u32) -> Result<Vec<Header>, NodeError> {
+    if count == 0 {
+        return Err(NodeError::InvalidNumericalUrlSegment);
+    }
+    let url = node_url(&node, &format!("blocks/lastHeaders/{}", count));
+    let client = build_client(&node)?;
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// GET on /blocks/{header_id} endpoint. Returns the full block (e.g. parsed into
+/// `ergo_lib::chain::block::FullBlock`) or `None` if the block is not found
+pub async fn get_full_block<T: DeserializeOwned>(
+    node: NodeConf,
+    header_id: BlockId,
+) -> Result<Option<T>, NodeError> {
+    let url = node_url(&node, &format!("blocks/{}", String::from(header_id.0)));
+    let client = build_client(&node)?;
+    send_and_parse_opt(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// GET on /blocks/{header_id}/transactions endpoint. Returns the block transactions (e.g. parsed
+/// into `ergo_lib::chain::block::BlockTransactions`) or `None` if the block is not found
+pub async fn get_block_transactions<T: DeserializeOwned>(
+    node: NodeConf,
+    header_id: BlockId,
+) -> Result<Option<T>, NodeError> {
+    let url = node_url(
+        &node,
+        &format!("blocks/{}/transactions", String::from(header_id.0)),
+    );
+    let client = build_client(&node)?;
+    send_and_parse_opt(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// GET on /utxo/byId/{box_id} endpoint. Returns `None` if the box is not in the UTXO set
+pub async fn get_utxo_by_id(node: NodeConf, box_id: BoxId) -> Result<Option<ErgoBox>, NodeError> {
+    let url = node_url(&node, &format!("utxo/byId/{}", box_id));
+    let client = build_client(&node)?;
+    send_and_parse_opt(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// GET on /utxo/withPool/byId/{box_id} endpoint. Same as [`get_utxo_by_id`], but also looks up
+/// the boxes created by the transactions in the mempool
+pub async fn get_utxo_with_pool_by_id(
+    node: NodeConf,
+    box_id: BoxId,
+) -> Result<Option<ErgoBox>, NodeError> {
+    let url = node_url(&node, &format!("utxo/withPool/byId/{}", box_id));
+    let client = build_client(&node)?;
+    send_and_parse_opt(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// GET on /blockchain/box/byId/{box_id} endpoint (requires the node with the extra indexing
+/// enabled). Unlike [`get_utxo_by_id`] also returns the spent boxes. Returns `None` if the box is
+/// not found
+pub async fn get_box_by_id(node: NodeConf, box_id: BoxId) -> Result<Option<ErgoBox>, NodeError> {
+    let url = node_url(&node, &format!("blockchain/box/byId/{}", box_id));
+    let client = build_client(&node)?;
+    send_and_parse_opt(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// GET on /blockchain/box/unspent/byTokenId/{token_id} endpoint (requires the node with the extra
+/// indexing enabled). Returns the unspent boxes holding the token
+pub async fn get_unspent_boxes_by_token_id(
+    node: NodeConf,
+    token_id: TokenId,
+    offset: u32,
+    limit: u32,
+) -> Result<Vec<ErgoBox>, NodeError> {
+    let url = node_url(
+        &node,
+        &format!(
+            "blockchain/box/unspent/byTokenId/{}?offset={}&limit={}",
+            token_id, offset, limit
+        ),
+    );
+    let client = build_client(&node)?;
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// POST on /transactions endpoint to submit the signed transaction (e.g.
+/// `ergo_lib::chain::transaction::Transaction`). Returns the id of the accepted transaction, the
+/// node's rejection reason is in [`crate::NodeApiError::detail`]. Not retried unless
+/// [`super::retry::RetryPolicy::retry_submission`] is set.
+pub async fn submit_transaction<T: Serialize + ?Sized>(
+    node: NodeConf,
+    tx: &T,
+) -> Result<TxId, NodeError> {
+    let url = node_url(&node, "transactions");
+    let client = build_client(&node)?;
+    send_and_parse(&node.retry.for_submission(), || {
+        set_req_headers(client.post(url.clone()), node).json(tx)
+    })
+    .await
+}
+
+/// POST on /transactions/check endpoint to validate the signed transaction against the node's
+/// UTXO set and mempool without submitting it. Returns the transaction id if it's valid
+pub async fn check_transaction<T: Serialize + ?Sized>(
+    node: NodeConf,
+    tx: &T,
+) -> Result<TxId, NodeError> {
+    let url = node_url(&node, "transactions/check");
+    let client = build_client(&node)?;
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.post(url.clone()), node).json(tx)
+    })
+    .await
+}
+
+/// GET on /transactions/unconfirmed endpoint. Returns up to `limit` mempool transactions
+/// starting from `offset`
+pub async fn get_unconfirmed_transactions<T: DeserializeOwned>(
+    node: NodeConf,
+    offset: u32,
+    limit: u32,
+) -> Result<Vec<T>, NodeError> {
+    let url = node_url(
+        &node,
+        &format!("transactions/unconfirmed?offset={}&limit={}", offset, limit),
+    );
+    let client = build_client(&node)?;
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+/// GET on /transactions/unconfirmed/byTransactionId/{tx_id} endpoint. Returns `None` if the
+/// transaction is not in the mempool
+pub async fn get_unconfirmed_transaction_by_id<T: DeserializeOwned>(
+    node: NodeConf,
+    tx_id: TxId,
+) -> Result<Option<T>, NodeError> {
+    let url = node_url(
+        &node,
+        &format!(
+            "transactions/unconfirmed/byTransactionId/{}",
+            String::from(tx_id)
+        ),
+    );
     let client = build_client(&node)?;
-    let rb = client.get(url);
-    Ok(set_req_headers(rb, node)
-        .send()
-        .await?
-        .json::<Option<MerkleProof>>()
-        .await?)
+    send_and_parse_opt(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
+}
+
+// joining a relative path to the node http url never fails
+#[allow(clippy::unwrap_used)]
+fn node_url(node: &NodeConf, path: &str) -> Url {
+    node.addr.as_http_url().join(path).unwrap()
 }
 
 #[allow(clippy::unwrap_used)]
@@ -190,11 +372,53 @@ mod tests {
             addr: PeerAddr::from_str("213.239.193.208:9053").unwrap(),
             api_key: None,
             timeout: Some(Duration::from_secs(5)),
+            retry: Default::default(),
         };
         let res = runtime_inner.block_on(async { get_info(node_conf).await.unwrap() });
         assert_ne!(res.name, "");
     }
 
+    #[test]
+    fn test_get_last_headers() {
+        let runtime_inner = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let node_conf = NodeConf {
+            addr: PeerAddr::from_str("213.239.193.208:9053").unwrap(),
+            api_key: None,
+            timeout: Some(Duration::from_secs(5)),
+            retry: Default::default(),
+        };
+        let (headers, ids_at_height) = runtime_inner.block_on(async {
+            let headers = get_last_headers(node_conf, 2).await.unwrap();
+            let ids = get_blocks_at_height(node_conf, headers[0].height)
+                .await
+                .unwrap();
+            (headers, ids)
+        });
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers[0].height + 1, headers[1].height);
+        assert!(ids_at_height.contains(&headers[0].id));
+    }
+
+    #[test]
+    fn test_get_utxo_by_id_not_found() {
+        let runtime_inner = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let node_conf = NodeConf {
+            addr: PeerAddr::from_str("213.239.193.208:9053").unwrap(),
+            api_key: None,
+            timeout: Some(Duration::from_secs(5)),
+            retry: Default::default(),
+        };
+        let res = runtime_inner
+            .block_on(async { get_utxo_by_id(node_conf, BoxId::zero()).await.unwrap() });
+        assert_eq!(res, None);
+    }
+
     #[test]
     fn test_get_nipopow_proof_by_header_id() {
         use ergo_chain_types::{BlockId, Digest32};
@@ -212,6 +436,7 @@ mod tests {
             addr: PeerAddr::from_str("213.239.193.208:9053").unwrap(),
             api_key: None,
             timeout: Some(Duration::from_secs(5)),
+            retry: Default::default(),
         };
         let m = 7;
         let k = 6;
@@ -17,16 +17,17 @@ pub(crate) use non_chrome::peer_discovery_inner;
 
 use crate::{NodeConf, NodeError, PeerInfo};
 
-use super::{build_client, set_req_headers};
+use super::{build_client, send_and_parse, set_req_headers};
 
 /// GET on /peers/all endpoint
 async fn get_peers_all(node: NodeConf) -> Result<Vec<PeerInfo>, NodeError> {
     #[allow(clippy::unwrap_used)]
     let url = node.addr.as_http_url().join("peers/all").unwrap();
     let client = build_client(&node)?;
-    let rb = client.get(url);
-    let response = set_req_headers(rb, node).send().await?;
-    Ok(response.json::<Vec<PeerInfo>>().await?)
+    send_and_parse(&node.retry, || {
+        set_req_headers(client.get(url.clone()), node)
+    })
+    .await
 }
 
 struct PeerDiscoverySettings {
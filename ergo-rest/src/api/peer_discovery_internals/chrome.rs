@@ -30,6 +30,7 @@
 //! [`this issue`]: https://github.com/ergoplatform/sigma-rust/issues/581#issuecomment-1160564378
 use super::PeerDiscoverySettings;
 use crate::api::peer_discovery_internals::get_peers_all;
+use crate::api::retry::RetryPolicy;
 use crate::error::PeerDiscoveryError;
 use crate::{api::node::get_info, NodeConf, NodeError, PeerInfo};
 use bounded_integer::BoundedU16;
@@ -397,6 +398,7 @@ fn spawn_http_request_task_chrome(
                         addr: PeerAddr(url.socket_addrs(|| Some(9053)).unwrap()[0]),
                         api_key: None,
                         timeout: Some(request_timeout_duration),
+                        retry: RetryPolicy::no_retry(),
                     };
 
                     // When a timeout is initiated, the message below is how
@@ -19,6 +19,7 @@
 //! ```
 use super::PeerDiscoverySettings;
 use crate::api::peer_discovery_internals::get_peers_all;
+use crate::api::retry::RetryPolicy;
 use crate::error::PeerDiscoveryError;
 use crate::{api::node::get_info, NodeConf, PeerInfo};
 use async_trait::async_trait;
@@ -296,6 +297,7 @@ fn spawn_http_request_task<
                         addr: PeerAddr(url.socket_addrs(|| Some(9053)).unwrap()[0]),
                         api_key: None,
                         timeout: Some(request_timeout_duration),
+                        retry: RetryPolicy::no_retry(),
                     };
 
                     // If active, look up its peers.
@@ -435,6 +437,7 @@ mod tests {
             addr: PeerAddr::from_str("213.239.193.208:9053").unwrap(),
             api_key: None,
             timeout: Some(Duration::from_secs(5)),
+            retry: Default::default(),
         };
         let res = runtime_inner.block_on(async { get_peers_all(node_conf).await.unwrap() });
         assert!(!res.is_empty())
@@ -0,0 +1,201 @@
+//! Retry policy for the REST API requests
+
+use std::future::Future;
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::NodeError;
+
+use super::sleep;
+
+/// Retry policy with exponential backoff and full jitter. The timeout of each attempt is
+/// configured with [`crate::NodeConf::timeout`]. The policy applies to the idempotent requests,
+/// the transaction submission is retried only if [`RetryPolicy::retry_submission`] is set.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first failed attempt (0 means a single attempt)
+    pub max_retries: u32,
+    /// Backoff delay before the first retry, doubled for every following retry
+    pub base_delay: Duration,
+    /// Upper bound for the backoff delay
+    pub max_delay: Duration,
+    /// Retry the transaction submission (POST /transactions) as well. Off by default, since the
+    /// node might have accepted the transaction of the timed out (or failed) attempt
+    pub retry_submission: bool,
+}
+
+impl RetryPolicy {
+    /// Single attempt, no retries
+    pub fn no_retry() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before the retry following the given (0-based) failed attempt. A random value in
+    /// `[0, min(max_delay, base_delay * 2^attempt)]`
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(attempt))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        let max_millis = exp.as_millis() as u64;
+        if max_millis == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(rand::thread_rng().gen_range(0..=max_millis))
+    }
+
+    /// Policy for the non-idempotent requests (transaction submission): this policy if
+    /// [`RetryPolicy::retry_submission`] is set, a single attempt otherwise
+    pub fn for_submission(&self) -> Self {
+        if self.retry_submission {
+            *self
+        } else {
+            Self::no_retry()
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(5),
+            retry_submission: false,
+        }
+    }
+}
+
+/// Returns true if the request failed due to a transient condition (connection error, timeout,
+/// node overload or internal error) and can be retried (or sent to another node)
+pub fn is_retryable(error: &NodeError) -> bool {
+    match error {
+        NodeError::ReqwestError(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+        NodeError::ApiError(e) => e.status == 408 || e.status == 429 || e.status >= 500,
+        NodeError::InvalidNumericalUrlSegment | NodeError::NoNodesAvailable => false,
+    }
+}
+
+/// Run `f` until it succeeds, fails with a non-retryable error or the retries are exhausted
+pub async fn with_retry<T, F, Fut>(policy: &RetryPolicy, mut f: F) -> Result<T, NodeError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, NodeError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Err(e) if attempt < policy.max_retries && is_retryable(&e) => {
+                sleep(policy.backoff_delay(attempt)).await;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::cell::Cell;
+
+    use crate::NodeApiError;
+
+    use super::*;
+
+    fn api_error(status: u16) -> NodeError {
+        NodeError::ApiError(NodeApiError {
+            status,
+            reason: String::new(),
+            detail: None,
+        })
+    }
+
+    fn zero_delay_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            retry_submission: false,
+        }
+    }
+
+    #[test]
+    fn test_backoff_delay_bounds() {
+        let policy = RetryPolicy::default();
+        for attempt in 0..40 {
+            let delay = policy.backoff_delay(attempt);
+            assert!(delay <= policy.max_delay);
+            assert!(delay <= policy.base_delay * 2u32.saturating_pow(attempt));
+        }
+    }
+
+    #[test]
+    fn test_for_submission() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.for_submission().max_retries, 0);
+        let policy = RetryPolicy {
+            retry_submission: true,
+            ..policy
+        };
+        assert_eq!(policy.for_submission(), policy);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&api_error(503)));
+        assert!(is_retryable(&api_error(429)));
+        assert!(!is_retryable(&api_error(400)));
+        assert!(!is_retryable(&api_error(404)));
+        assert!(!is_retryable(&NodeError::InvalidNumericalUrlSegment));
+    }
+
+    #[test]
+    fn test_with_retry_until_success() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let calls = Cell::new(0);
+        let res = runtime.block_on(with_retry(&zero_delay_policy(3), || {
+            calls.set(calls.get() + 1);
+            let n = calls.get();
+            async move {
+                if n < 3 {
+                    Err(api_error(503))
+                } else {
+                    Ok(n)
+                }
+            }
+        }));
+        assert_eq!(res.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_with_retry_gives_up() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let calls = Cell::new(0);
+        let res: Result<(), _> = runtime.block_on(with_retry(&zero_delay_policy(2), || {
+            calls.set(calls.get() + 1);
+            async { Err(api_error(502)) }
+        }));
+        assert!(res.is_err());
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let res: Result<(), _> = runtime.block_on(with_retry(&zero_delay_policy(2), || {
+            calls.set(calls.get() + 1);
+            async { Err(api_error(400)) }
+        }));
+        assert!(res.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -11,6 +11,48 @@ pub enum NodeError {
     /// Invalid numerical URL segment
     #[error("Invalid numerical URL segment")]
     InvalidNumericalUrlSegment,
+    /// Node responded with an error (non-success HTTP status)
+    #[error("{0}")]
+    ApiError(NodeApiError),
+    /// No node is available to send the request to
+    #[error("No nodes available")]
+    NoNodesAvailable,
+}
+
+/// Error returned by the node API (non-success HTTP status)
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+#[error("Node API error (HTTP {status}): {reason}")]
+pub struct NodeApiError {
+    /// HTTP status code
+    pub status: u16,
+    /// Error reason
+    pub reason: String,
+    /// Error details (e.g. why the transaction was rejected)
+    pub detail: Option<String>,
+}
+
+/// Error response body of the node API
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct NodeApiErrorJson {
+    pub(crate) reason: String,
+    pub(crate) detail: Option<String>,
+}
+
+impl NodeApiError {
+    pub(crate) fn new(status: u16, canonical_reason: &str, body: Option<NodeApiErrorJson>) -> Self {
+        match body {
+            Some(body) => NodeApiError {
+                status,
+                reason: body.reason,
+                detail: body.detail,
+            },
+            None => NodeApiError {
+                status,
+                reason: canonical_reason.to_string(),
+                detail: None,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Error, From)]
@@ -37,4 +79,7 @@ pub enum PeerDiscoveryError {
     /// There aren't any node requests to be made
     #[error("There aren't any node requests to be made")]
     NoPendingNodeRequests,
+    /// No seed nodes to start the discovery from
+    #[error("No seed nodes")]
+    NoSeeds,
 }
@@ -27,6 +27,7 @@ mod known_nodes;
 mod node_conf;
 mod node_info;
 mod node_response;
+mod peer_db;
 mod peer_info;
 mod wasm_timer;
 
@@ -38,4 +39,7 @@ pub use known_nodes::KnownNodes;
 pub use node_conf::NodeConf;
 pub use node_info::NodeInfo;
 pub use node_response::NodeResponse;
+pub use peer_db::PeerDb;
+pub use peer_db::PeerRecord;
+pub use peer_db::PeerStore;
 pub use peer_info::PeerInfo;
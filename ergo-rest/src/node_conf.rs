@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use crate::api::retry::RetryPolicy;
 use crate::reqwest::header::HeaderValue;
 use ergo_chain_types::PeerAddr;
 
@@ -10,8 +11,10 @@ pub struct NodeConf {
     pub addr: PeerAddr,
     /// Node API key
     pub api_key: Option<&'static str>,
-    /// Request timeout
+    /// Request timeout (of each attempt)
     pub timeout: Option<Duration>,
+    /// Retry policy for the requests to this node
+    pub retry: RetryPolicy,
 }
 
 impl NodeConf {
@@ -1,5 +1,7 @@
 use std::cmp::Ordering;
 
+use ergo_chain_types::BlockId;
+use ergo_chain_types::Parameters;
 use serde::{Deserialize, Serialize};
 
 use crate::NodeResponse;
@@ -13,6 +15,39 @@ pub struct NodeInfo {
     /// Ergo node app version
     #[serde(rename = "appVersion")]
     pub app_version: String,
+    /// Height of the best full block (`None` if the node has no full blocks yet)
+    #[serde(rename = "fullHeight", default)]
+    pub full_height: Option<u32>,
+    /// Height of the best header (`None` if the node has no headers yet)
+    #[serde(rename = "headersHeight", default)]
+    pub headers_height: Option<u32>,
+    /// Max height seen among the peers
+    #[serde(rename = "maxPeerHeight", default)]
+    pub max_peer_height: Option<u32>,
+    /// Id of the best full block
+    #[serde(rename = "bestFullHeaderId", default)]
+    pub best_full_header_id: Option<BlockId>,
+    /// Id of the best header
+    #[serde(rename = "bestHeaderId", default)]
+    pub best_header_id: Option<BlockId>,
+    /// State type ("utxo" or "digest")
+    #[serde(rename = "stateType", default)]
+    pub state_type: Option<String>,
+    /// Number of the connected peers
+    #[serde(rename = "peersCount", default)]
+    pub peers_count: Option<u32>,
+    /// Number of the transactions in the mempool
+    #[serde(rename = "unconfirmedCount", default)]
+    pub unconfirmed_count: Option<u32>,
+    /// Whether the node is mining
+    #[serde(rename = "isMining", default)]
+    pub is_mining: Option<bool>,
+    /// Network type ("mainnet" or "testnet")
+    #[serde(default)]
+    pub network: Option<String>,
+    /// Current system parameters
+    #[serde(default)]
+    pub parameters: Option<Parameters>,
 }
 
 impl NodeInfo {
@@ -24,3 +59,41 @@ impl NodeInfo {
 }
 
 impl NodeResponse for NodeInfo {}
+
+#[cfg(test)]
+#[cfg(feature = "json")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node_info() {
+        // Shaped as the node /info response (some fields omitted)
+        let json = r#"{
+            "currentTime": 1666090496530,
+            "network": "mainnet",
+            "name": "ergo-mainnet-4.0.45",
+            "stateType": "utxo",
+            "difficulty": 1115063704174592,
+            "bestFullHeaderId": "2e4c4ab5c1fc9bd4f1cf2b6a0a4b4fd6a3d5c8b5e3e9f8c5b4a1b2c3d4e5f6a7",
+            "bestHeaderId": "2e4c4ab5c1fc9bd4f1cf2b6a0a4b4fd6a3d5c8b5e3e9f8c5b4a1b2c3d4e5f6a7",
+            "peersCount": 30,
+            "unconfirmedCount": 4,
+            "appVersion": "4.0.45",
+            "headersHeight": 867315,
+            "fullHeight": 867315,
+            "maxPeerHeight": 867316,
+            "isMining": false
+        }"#;
+        let info: NodeInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.app_version, "4.0.45");
+        assert_eq!(info.full_height, Some(867315));
+        assert_eq!(info.best_full_header_id, info.best_header_id);
+        assert_eq!(info.network.as_deref(), Some("mainnet"));
+        let minimal: NodeInfo =
+            serde_json::from_str(r#"{"name": "node", "appVersion": "5.0.0", "fullHeight": null}"#)
+                .unwrap();
+        assert_eq!(minimal.full_height, None);
+        assert!(minimal.is_at_least_version_4_0_100());
+    }
+}
@@ -0,0 +1,399 @@
+//! Persistent database of the discovered peers (nodes serving REST API)
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bounded_integer::BoundedU16;
+use bounded_vec::NonEmptyVec;
+use ergo_chain_types::PeerAddr;
+use serde::Deserialize;
+use serde::Serialize;
+use url::Url;
+
+use crate::api::node::get_info;
+use crate::api::node::peer_discovery;
+use crate::api::retry::RetryPolicy;
+use crate::wasm_timer::SystemTime;
+use crate::wasm_timer::UNIX_EPOCH;
+use crate::NodeConf;
+use crate::PeerDiscoveryError;
+
+/// REST API port used by the peer discovery
+const REST_API_PORT: u16 = 9053;
+
+/// Stats of the peer
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct PeerRecord {
+    /// Peer address (REST API)
+    pub addr: PeerAddr,
+    /// Number of the successful requests (probes)
+    pub successes: u32,
+    /// Number of the failed requests (probes)
+    pub failures: u32,
+    /// Last measured `/info` response time (in milliseconds)
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: Option<u64>,
+    /// Last reported full height
+    pub height: Option<u32>,
+    /// Time of the last successful request (milliseconds since UNIX epoch)
+    #[serde(rename = "lastSeen")]
+    pub last_seen: Option<u64>,
+    /// The peer is banned until this time (milliseconds since UNIX epoch)
+    #[serde(rename = "bannedUntil")]
+    pub banned_until: Option<u64>,
+}
+
+impl PeerRecord {
+    /// New peer without any stats
+    pub fn new(addr: PeerAddr) -> Self {
+        PeerRecord {
+            addr,
+            successes: 0,
+            failures: 0,
+            latency_ms: None,
+            height: None,
+            last_seen: None,
+            banned_until: None,
+        }
+    }
+
+    /// Returns true if the peer is banned at the given time (milliseconds since UNIX epoch)
+    pub fn is_banned_at(&self, now: u64) -> bool {
+        self.banned_until.map(|t| t > now).unwrap_or(false)
+    }
+
+    /// Score of the peer (higher is better) given the best known height:
+    /// - reachability (smoothed success rate) gives up to 1000 points;
+    /// - every 10ms of latency costs a point (unknown latency costs 100 points);
+    /// - every block behind `best_height` costs 10 points (unknown height costs 100 points).
+    pub fn score(&self, best_height: u32) -> i64 {
+        let reachability =
+            1000 * (self.successes as i64 + 1) / (self.successes as i64 + self.failures as i64 + 2);
+        let latency_penalty = self.latency_ms.map(|l| (l / 10) as i64).unwrap_or(100);
+        let height_penalty = self
+            .height
+            .map(|h| 10 * best_height.saturating_sub(h) as i64)
+            .unwrap_or(100);
+        reachability - latency_penalty - height_penalty
+    }
+}
+
+/// Storage backend for [`PeerDb`] (file, key-value store, browser local storage, etc.)
+pub trait PeerStore {
+    /// Storage error
+    type Error;
+    /// Load all stored peers
+    fn load(&self) -> Result<Vec<PeerRecord>, Self::Error>;
+    /// Replace the stored peers
+    fn save(&mut self, peers: &[PeerRecord]) -> Result<(), Self::Error>;
+}
+
+/// Database of the known peers with their scores. Discovery can be resumed from the stored peers
+/// with [`PeerDb::discover`]
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct PeerDb {
+    peers: HashMap<PeerAddr, PeerRecord>,
+}
+
+impl PeerDb {
+    /// Empty database
+    pub fn new() -> Self {
+        PeerDb::default()
+    }
+
+    /// Database with the given peers
+    pub fn from_records(records: Vec<PeerRecord>) -> Self {
+        PeerDb {
+            peers: records.into_iter().map(|r| (r.addr, r)).collect(),
+        }
+    }
+
+    /// Load the peers from the store
+    pub fn load<S: PeerStore>(store: &S) -> Result<Self, S::Error> {
+        Ok(PeerDb::from_records(store.load()?))
+    }
+
+    /// Save the peers to the store
+    pub fn save<S: PeerStore>(&self, store: &mut S) -> Result<(), S::Error> {
+        store.save(&self.records())
+    }
+
+    /// Parse the peers from JSON (previously exported with [`PeerDb::to_json`])
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(PeerDb::from_records(serde_json::from_str(json)?))
+    }
+
+    /// Export the peers as JSON
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.records())
+    }
+
+    /// All peers (including banned), best score first
+    pub fn records(&self) -> Vec<PeerRecord> {
+        let best_height = self.best_height();
+        let mut records: Vec<PeerRecord> = self.peers.values().cloned().collect();
+        records.sort_by_key(|r| (std::cmp::Reverse(r.score(best_height)), r.addr.to_string()));
+        records
+    }
+
+    /// Peer stats
+    pub fn get(&self, addr: &PeerAddr) -> Option<&PeerRecord> {
+        self.peers.get(addr)
+    }
+
+    /// Number of the known peers
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Returns true if there are no known peers
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// Highest height reported by the peers
+    pub fn best_height(&self) -> u32 {
+        self.peers
+            .values()
+            .filter_map(|r| r.height)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Add the peer (if it's not known yet)
+    pub fn add(&mut self, addr: PeerAddr) {
+        self.entry(addr);
+    }
+
+    /// Record the successful request to the peer
+    pub fn record_success(&mut self, addr: PeerAddr, latency: Duration, height: Option<u32>) {
+        let now = now_millis();
+        let record = self.entry(addr);
+        record.successes = record.successes.saturating_add(1);
+        record.latency_ms = Some(latency.as_millis() as u64);
+        if height.is_some() {
+            record.height = height;
+        }
+        record.last_seen = Some(now);
+    }
+
+    /// Record the failed request to the peer
+    pub fn record_failure(&mut self, addr: PeerAddr) {
+        let record = self.entry(addr);
+        record.failures = record.failures.saturating_add(1);
+    }
+
+    /// Ban the peer for the given duration (the peer is added if it's not known yet)
+    pub fn ban(&mut self, addr: PeerAddr, duration: Duration) {
+        let until = now_millis().saturating_add(duration.as_millis() as u64);
+        self.entry(addr).banned_until = Some(until);
+    }
+
+    /// Lift the ban
+    pub fn unban(&mut self, addr: &PeerAddr) {
+        if let Some(record) = self.peers.get_mut(addr) {
+            record.banned_until = None;
+        }
+    }
+
+    /// Returns true if the peer is currently banned
+    pub fn is_banned(&self, addr: &PeerAddr) -> bool {
+        let now = now_millis();
+        self.peers
+            .get(addr)
+            .map(|r| r.is_banned_at(now))
+            .unwrap_or(false)
+    }
+
+    /// Remove the peers that were never reachable and failed at least `min_failures` times
+    pub fn prune_unreachable(&mut self, min_failures: u32) {
+        self.peers
+            .retain(|_, r| r.successes > 0 || r.failures < min_failures);
+    }
+
+    /// Not banned peers, best score first, at most `limit`
+    pub fn best_peers(&self, limit: usize) -> Vec<PeerAddr> {
+        let now = now_millis();
+        self.records()
+            .into_iter()
+            .filter(|r| !r.is_banned_at(now))
+            .take(limit)
+            .map(|r| r.addr)
+            .collect()
+    }
+
+    /// Query `/info` on every not banned peer (in parallel, without retries) to update the stats
+    pub async fn refresh(&mut self, timeout: Duration) {
+        let now = now_millis();
+        let addrs: Vec<PeerAddr> = self
+            .peers
+            .values()
+            .filter(|r| !r.is_banned_at(now))
+            .map(|r| r.addr)
+            .collect();
+        let probes = addrs.into_iter().map(|addr| async move {
+            let node = NodeConf {
+                addr,
+                api_key: None,
+                timeout: Some(timeout),
+                retry: RetryPolicy::no_retry(),
+            };
+            let start = crate::wasm_timer::Instant::now();
+            let res = get_info(node).await;
+            (addr, start.elapsed(), res)
+        });
+        for (addr, latency, res) in futures::future::join_all(probes).await {
+            match res {
+                Ok(info) => self.record_success(addr, latency, info.full_height),
+                Err(_) => self.record_failure(addr),
+            }
+        }
+    }
+
+    /// Run the peer discovery starting from the best known peers (`seeds` are used when there are
+    /// no known peers) and add the discovered peers (banned peers stay banned). Returns the
+    /// discovered peers.
+    pub async fn discover(
+        &mut self,
+        seeds: Vec<Url>,
+        max_parallel_tasks: BoundedU16<1, { u16::MAX }>,
+        timeout: Duration,
+    ) -> Result<Vec<PeerAddr>, PeerDiscoveryError> {
+        let known_seeds: Vec<Url> = self
+            .best_peers(self.peers.len())
+            .into_iter()
+            .map(|addr| addr.as_http_url())
+            .collect();
+        let start_from = if known_seeds.is_empty() {
+            seeds
+        } else {
+            known_seeds
+        };
+        let start_from =
+            NonEmptyVec::from_vec(start_from).map_err(|_| PeerDiscoveryError::NoSeeds)?;
+        let urls = peer_discovery(start_from, max_parallel_tasks, timeout).await?;
+        let discovered: Vec<PeerAddr> = urls.iter().filter_map(url_to_peer_addr).collect();
+        let now = now_millis();
+        for addr in &discovered {
+            self.entry(*addr).last_seen = Some(now);
+        }
+        Ok(discovered)
+    }
+
+    fn entry(&mut self, addr: PeerAddr) -> &mut PeerRecord {
+        self.peers
+            .entry(addr)
+            .or_insert_with(|| PeerRecord::new(addr))
+    }
+}
+
+fn url_to_peer_addr(url: &Url) -> Option<PeerAddr> {
+    let ip: std::net::IpAddr = url
+        .host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()?;
+    let port = url.port().unwrap_or(REST_API_PORT);
+    Some(PeerAddr(std::net::SocketAddr::new(ip, port)))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn addr(s: &str) -> PeerAddr {
+        PeerAddr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_score_prefers_reachable_fast_synced() {
+        let a = addr("127.0.0.1:9053");
+        let b = addr("127.0.0.2:9053");
+        let c = addr("127.0.0.3:9053");
+        let mut db = PeerDb::new();
+        db.record_success(a, Duration::from_millis(50), Some(100));
+        db.record_success(b, Duration::from_millis(500), Some(100));
+        db.record_success(c, Duration::from_millis(50), Some(80));
+        db.record_failure(c);
+        assert_eq!(db.best_peers(10), vec![a, b, c]);
+        assert_eq!(db.best_height(), 100);
+    }
+
+    #[test]
+    fn test_ban() {
+        let a = addr("127.0.0.1:9053");
+        let b = addr("127.0.0.2:9053");
+        let mut db = PeerDb::new();
+        db.add(a);
+        db.add(b);
+        db.ban(a, Duration::from_secs(3600));
+        assert!(db.is_banned(&a));
+        assert_eq!(db.best_peers(10), vec![b]);
+        db.unban(&a);
+        assert!(!db.is_banned(&a));
+        assert_eq!(db.best_peers(10).len(), 2);
+    }
+
+    #[test]
+    fn test_prune_unreachable() {
+        let a = addr("127.0.0.1:9053");
+        let b = addr("127.0.0.2:9053");
+        let mut db = PeerDb::new();
+        db.record_failure(a);
+        db.record_failure(a);
+        db.record_success(b, Duration::from_millis(10), None);
+        db.record_failure(b);
+        db.record_failure(b);
+        db.prune_unreachable(2);
+        assert_eq!(db.get(&a), None);
+        assert!(db.get(&b).is_some());
+    }
+
+    #[test]
+    fn test_url_to_peer_addr() {
+        let url = Url::parse("http://213.239.193.208:9030/").unwrap();
+        assert_eq!(url_to_peer_addr(&url), Some(addr("213.239.193.208:9030")));
+        let url = Url::parse("http://213.239.193.208/").unwrap();
+        assert_eq!(url_to_peer_addr(&url), Some(addr("213.239.193.208:9053")));
+        let url = Url::parse("http://[::1]:9053/").unwrap();
+        assert_eq!(url_to_peer_addr(&url), Some(addr("[::1]:9053")));
+    }
+
+    struct MemStore(Vec<PeerRecord>);
+
+    impl PeerStore for MemStore {
+        type Error = ();
+        fn load(&self) -> Result<Vec<PeerRecord>, ()> {
+            Ok(self.0.clone())
+        }
+        fn save(&mut self, peers: &[PeerRecord]) -> Result<(), ()> {
+            self.0 = peers.to_vec();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_store_roundtrip() {
+        let mut db = PeerDb::new();
+        db.record_success(addr("127.0.0.1:9053"), Duration::from_millis(10), Some(5));
+        db.ban(addr("127.0.0.2:9053"), Duration::from_secs(60));
+        let mut store = MemStore(vec![]);
+        db.save(&mut store).unwrap();
+        assert_eq!(PeerDb::load(&store).unwrap(), db);
+        #[cfg(feature = "json")]
+        assert_eq!(PeerDb::from_json(&db.to_json().unwrap()).unwrap(), db);
+    }
+}
@@ -63,6 +63,8 @@ pub enum Expr {
     Ident(Ident),
     BinaryExpr(BinaryExpr),
     Literal(Literal),
+    TupleExpr(TupleExpr),
+    SelectExpr(SelectExpr),
     // ParenExpr(ParenExpr),
     // UnaryExpr(UnaryExpr),
 }
@@ -74,6 +76,8 @@ impl Expr {
             SyntaxKind::InfixExpr => Self::BinaryExpr(BinaryExpr(node)),
             SyntaxKind::IntNumber => Self::Literal(Literal(node)),
             SyntaxKind::LongNumber => Self::Literal(Literal(node)),
+            SyntaxKind::TupleExpr => Self::TupleExpr(TupleExpr(node)),
+            SyntaxKind::SelectExpr => Self::SelectExpr(SelectExpr(node)),
             // SyntaxKind::ParenExpr => Self::ParenExpr(ParenExpr(node)),
             // SyntaxKind::PrefixExpr => Self::UnaryExpr(UnaryExpr(node)),
             _ => return None,
@@ -143,6 +147,50 @@ impl BinaryExpr {
     }
 }
 
+#[derive(Debug)]
+pub struct TupleExpr(SyntaxNode);
+
+impl TupleExpr {
+    pub fn items(&self) -> impl Iterator<Item = Expr> {
+        self.0.children().filter_map(Expr::cast)
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+#[derive(Debug)]
+pub struct SelectExpr(SyntaxNode);
+
+impl SelectExpr {
+    pub fn obj(&self) -> Result<Expr, AstError> {
+        self.0.children().find_map(Expr::cast).ok_or_else(|| {
+            AstError::new(
+                format!("Cannot find select object in {:?}", self.0.children()),
+                self.0.text_range(),
+            )
+        })
+    }
+
+    pub fn field(&self) -> Result<SyntaxToken, AstError> {
+        self.0
+            .children_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .find(|token| token.kind() == SyntaxKind::Ident)
+            .ok_or_else(|| {
+                AstError::new(
+                    format!("Cannot find select field in {:?}", self.0),
+                    self.0.text_range(),
+                )
+            })
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
 #[derive(Debug)]
 pub enum LiteralValue {
     Int(i32),
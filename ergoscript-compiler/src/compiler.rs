@@ -99,6 +99,15 @@ fn check(input: &str, expected_tree: expect_test::Expect) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ergotree_ir::mir::bin_op::ArithOp;
+    use ergotree_ir::mir::bin_op::BinOp;
+    use ergotree_ir::mir::downcast::Downcast;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::global_vars::GlobalVars;
+    use ergotree_ir::mir::select_field::SelectField;
+    use ergotree_ir::mir::tuple::Tuple;
+    use ergotree_ir::mir::upcast::Upcast;
+    use ergotree_ir::types::stype::SType;
     use expect_test::expect;
 
     #[test]
@@ -115,12 +124,66 @@ mod tests {
     #[test]
     fn test_parser_error() {
         check(
-            "HSB.HEIGHT",
+            "HSB$HEIGHT",
             expect![[r#"
-                error: expected ‘+’, ‘-’, ‘*’, ‘/’, ‘val’, number, number, identifier, ‘-’ or ‘(’, but found an unrecognized token
+                error: expected ‘.’, ‘+’, ‘-’, ‘*’, ‘/’, ‘val’, number, number, identifier, ‘-’ or ‘(’, but found an unrecognized token
                 line: 1
-                HSB.HEIGHT
+                HSB$HEIGHT
                   ^^"#]],
         );
     }
+
+    #[test]
+    fn test_tuple_select_field() {
+        let expr = compile_expr("(1, 2L, HEIGHT)._3", ScriptEnv::new()).unwrap();
+        let tuple = Tuple::new(vec![1i32.into(), 2i64.into(), GlobalVars::Height.into()]).unwrap();
+        let expected: Expr = SelectField::new(tuple.into(), 3u8.try_into().unwrap())
+            .unwrap()
+            .into();
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_tuple_field_out_of_bounds() {
+        check(
+            "(HEIGHT,1)._3",
+            expect![[r#"
+                Field _3 is not found in tuple (Int, Int)
+                line: 1
+                (HEIGHT,1)._3
+                         ^^^"#]],
+        );
+    }
+
+    #[test]
+    fn test_numeric_upcast() {
+        let expr = compile_expr("HEIGHT + 1L", ScriptEnv::new()).unwrap();
+        let expected: Expr = BinOp {
+            kind: ArithOp::Plus.into(),
+            left: Box::new(
+                Upcast::new(GlobalVars::Height.into(), SType::SLong)
+                    .unwrap()
+                    .into(),
+            ),
+            right: Box::new(1i64.into()),
+        }
+        .into();
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_numeric_cast_methods() {
+        let expr = compile_expr("HEIGHT.toLong.toByte", ScriptEnv::new()).unwrap();
+        let to_long: Expr = Upcast::new(GlobalVars::Height.into(), SType::SLong)
+            .unwrap()
+            .into();
+        let expected: Expr = Downcast::new(to_long, SType::SByte).unwrap().into();
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_numeric_cast_from_bigint() {
+        assert!(compile_expr("HEIGHT.toBigInt.toBigInt", ScriptEnv::new()).is_ok());
+        assert!(compile_expr("HEIGHT.toBigInt.toLong", ScriptEnv::new()).is_err());
+    }
 }
@@ -90,6 +90,16 @@ impl Expr {
                 };
                 Ok(expr)
             }
+            ast::Expr::TupleExpr(ast) => Ok(Expr {
+                kind: Tuple::lower(ast)?.into(),
+                span: ast.span(),
+                tpe: None,
+            }),
+            ast::Expr::SelectExpr(ast) => Ok(Expr {
+                kind: Select::lower(ast)?.into(),
+                span: ast.span(),
+                tpe: None,
+            }),
         }
     }
 
@@ -143,16 +153,74 @@ impl Binary {
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tuple {
+    pub items: Vec<Expr>,
+}
+
+impl Tuple {
+    fn lower(ast: &ast::TupleExpr) -> Result<Tuple, HirLoweringError> {
+        let items = ast
+            .items()
+            .map(|item| Expr::lower(&item))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Tuple { items })
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Select {
+    pub obj: Box<Expr>,
+    pub field: Spanned<String>,
+}
+
+impl Select {
+    fn lower(ast: &ast::SelectExpr) -> Result<Select, HirLoweringError> {
+        let field = ast.field()?;
+        let obj = Expr::lower(&ast.obj()?)?;
+        Ok(Select {
+            obj: Box::new(obj),
+            field: Spanned {
+                node: field.text().to_string(),
+                span: field.text_range(),
+            },
+        })
+    }
+
+    /// 1-based tuple field index if the field is a tuple field selector (`_1`, `_2`, ...)
+    pub fn tuple_field_index(&self) -> Option<u8> {
+        self.field
+            .node
+            .strip_prefix('_')
+            .and_then(|idx| idx.parse().ok())
+            .filter(|idx| *idx >= 1)
+    }
+
+    /// Target type if the field is a numeric conversion method (`toByte`, `toShort`, `toInt`,
+    /// `toLong`, `toBigInt`)
+    pub fn numeric_cast_tpe(&self) -> Option<SType> {
+        match self.field.node.as_str() {
+            "toByte" => Some(SType::SByte),
+            "toShort" => Some(SType::SShort),
+            "toInt" => Some(SType::SInt),
+            "toLong" => Some(SType::SLong),
+            "toBigInt" => Some(SType::SBigInt),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, From, Clone)]
 pub enum ExprKind {
     Ident(String),
     Binary(Binary),
     GlobalVars(GlobalVars),
     Literal(Literal),
+    Tuple(Tuple),
+    Select(Select),
     // ...
     // Block
     // ValNode
-    // Select
     // ApplyTypes
     // MethodCallLike
     // Lambda
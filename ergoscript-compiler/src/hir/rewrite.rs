@@ -1,6 +1,8 @@
 use super::Binary;
 use super::Expr;
 use super::ExprKind;
+use super::Select;
+use super::Tuple;
 
 // pub fn hir_rewrite_safe<F: Fn(&Expr) -> Option<Expr>>(e: Expr, f: F) -> Expr {
 //     let f_wrap = |e| Result::<Option<Expr>, BinderError>::Ok(f(e));
@@ -8,6 +10,10 @@ use super::ExprKind;
 // }
 
 pub fn rewrite<E, F: Fn(&Expr) -> Result<Option<Expr>, E>>(e: Expr, f: F) -> Result<Expr, E> {
+    rewrite_with(e, &f)
+}
+
+fn rewrite_with<E, F: Fn(&Expr) -> Result<Option<Expr>, E>>(e: Expr, f: &F) -> Result<Expr, E> {
     let e = f(&e)?.unwrap_or(e);
     Ok(match &e.kind {
         ExprKind::Binary(binary) => match (f(&binary.lhs)?, f(&binary.rhs)?) {
@@ -22,6 +28,25 @@ pub fn rewrite<E, F: Fn(&Expr) -> Result<Option<Expr>, E>>(e: Expr, f: F) -> Res
                 ..e
             },
         },
+        ExprKind::Tuple(tuple) => Expr {
+            kind: Tuple {
+                items: tuple
+                    .items
+                    .iter()
+                    .map(|item| rewrite_with(item.clone(), f))
+                    .collect::<Result<Vec<_>, _>>()?,
+            }
+            .into(),
+            ..e
+        },
+        ExprKind::Select(select) => Expr {
+            kind: Select {
+                obj: Box::new(rewrite_with(*select.obj.clone(), f)?),
+                field: select.field.clone(),
+            }
+            .into(),
+            ..e
+        },
         ExprKind::Ident(_) => f(&e)?.unwrap_or(e), // TODO: duplicate call to f?
         ExprKind::GlobalVars(_) => f(&e)?.unwrap_or(e),
         ExprKind::Literal(_) => f(&e)?.unwrap_or(e),
@@ -14,7 +14,7 @@ pub enum TokenKind {
     #[token("val")]
     ValKw,
 
-    #[regex("[A-Za-z][A-Za-z0-9]*")]
+    #[regex("[A-Za-z_][A-Za-z0-9_]*")]
     Ident,
 
     #[regex("[0-9]+")]
@@ -41,6 +41,12 @@ pub enum TokenKind {
     #[token("=")]
     Equals,
 
+    #[token(",")]
+    Comma,
+
+    #[token(".")]
+    Dot,
+
     #[token("(")]
     LParen,
 
@@ -81,6 +87,8 @@ impl fmt::Display for TokenKind {
             Self::Slash => "‘/’",
             Self::And => "‘&&’",
             Self::Equals => "‘=’",
+            Self::Comma => "‘,’",
+            Self::Dot => "‘.’",
             Self::LParen => "‘(’",
             Self::RParen => "‘)’",
             Self::LBrace => "‘{’",
@@ -129,6 +137,11 @@ mod tests {
         check("ab123cde456", TokenKind::Ident);
     }
 
+    #[test]
+    fn lex_underscore_identifier() {
+        check("_1", TokenKind::Ident);
+    }
+
     #[test]
     fn lex_mixed_case_identifier() {
         check("ABCdef", TokenKind::Ident);
@@ -169,6 +182,16 @@ mod tests {
         check("=", TokenKind::Equals);
     }
 
+    #[test]
+    fn lex_comma() {
+        check(",", TokenKind::Comma);
+    }
+
+    #[test]
+    fn lex_dot() {
+        check(".", TokenKind::Dot);
+    }
+
     #[test]
     fn lex_left_parenthesis() {
         check("(", TokenKind::LParen);
@@ -2,8 +2,15 @@ use ergotree_ir::mir::bin_op::ArithOp;
 use ergotree_ir::mir::bin_op::BinOp;
 use ergotree_ir::mir::bin_op::BinOpKind;
 use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::downcast::Downcast;
 use ergotree_ir::mir::expr::Expr;
 use ergotree_ir::mir::global_vars::GlobalVars;
+use ergotree_ir::mir::select_field::SelectField;
+use ergotree_ir::mir::select_field::TupleFieldIndex;
+use ergotree_ir::mir::tuple::Tuple;
+use ergotree_ir::mir::upcast::Upcast;
+use ergotree_ir::types::stype::unify;
+use ergotree_ir::types::stype::SType;
 use hir::BinaryOp;
 use rowan::TextRange;
 
@@ -40,6 +47,11 @@ pub fn lower(hir_expr: hir::Expr) -> Result<Expr, MirLoweringError> {
         hir::ExprKind::Binary(hir) => {
             let l = lower(*hir.lhs.clone())?;
             let r = lower(*hir.rhs.clone())?;
+            // operands of different numeric types are upcast to the wider one (see `unify`)
+            let (l, r) = match unify(&l.tpe(), &r.tpe()) {
+                Some(tpe) => (upcast_to(l, &tpe), upcast_to(r, &tpe)),
+                None => (l, r),
+            };
             BinOp {
                 kind: hir.op.node.clone().into(),
                 left: l.into(),
@@ -54,6 +66,39 @@ pub fn lower(hir_expr: hir::Expr) -> Result<Expr, MirLoweringError> {
             };
             constant.into()
         }
+        hir::ExprKind::Tuple(hir) => {
+            let items = hir
+                .items
+                .iter()
+                .cloned()
+                .map(lower)
+                .collect::<Result<Vec<_>, _>>()?;
+            Tuple::new(items)
+                .map_err(|e| MirLoweringError::new(format!("MIR error: {0}", e.0), hir_expr.span))?
+                .into()
+        }
+        hir::ExprKind::Select(hir) if hir.numeric_cast_tpe().is_some() => {
+            let obj = lower(*hir.obj.clone())?;
+            match hir.numeric_cast_tpe() {
+                Some(tpe) if obj.tpe() != tpe => numeric_cast(obj, tpe),
+                _ => obj,
+            }
+        }
+        hir::ExprKind::Select(hir) => {
+            let obj = lower(*hir.obj.clone())?;
+            let field_index = hir
+                .tuple_field_index()
+                .and_then(|idx| TupleFieldIndex::try_from(idx).ok())
+                .ok_or_else(|| {
+                    MirLoweringError::new(
+                        format!("MIR error: unsupported field {0}", hir.field.node),
+                        hir.field.span,
+                    )
+                })?;
+            SelectField::new(obj, field_index)
+                .map_err(|e| MirLoweringError::new(format!("MIR error: {0}", e.0), hir_expr.span))?
+                .into()
+        }
     };
     let hir_tpe = hir_expr.tpe.clone().ok_or_else(|| {
         MirLoweringError::new(
@@ -75,6 +120,36 @@ pub fn lower(hir_expr: hir::Expr) -> Result<Expr, MirLoweringError> {
     }
 }
 
+/// Wrap the numeric expression in `Upcast` if its type is narrower than `tpe`
+fn upcast_to(e: Expr, tpe: &SType) -> Expr {
+    if e.tpe() != *tpe && e.tpe().can_upcast_to(tpe) {
+        Upcast {
+            input: e.into(),
+            tpe: tpe.clone(),
+        }
+        .into()
+    } else {
+        e
+    }
+}
+
+/// `Upcast` or `Downcast` of the numeric expression to `tpe`
+fn numeric_cast(e: Expr, tpe: SType) -> Expr {
+    if e.tpe().can_upcast_to(&tpe) {
+        Upcast {
+            input: e.into(),
+            tpe,
+        }
+        .into()
+    } else {
+        Downcast {
+            input: e.into(),
+            tpe,
+        }
+        .into()
+    }
+}
+
 impl From<hir::BinaryOp> for BinOpKind {
     fn from(op: hir::BinaryOp) -> Self {
         match op {
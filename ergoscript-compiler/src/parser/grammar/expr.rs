@@ -122,7 +122,7 @@ fn lhs(p: &mut Parser) -> Option<CompletedMarker> {
         return None;
     };
 
-    Some(cm)
+    Some(select_expr(p, cm))
 }
 
 enum BinaryOp {
@@ -199,15 +199,37 @@ fn prefix_expr(p: &mut Parser) -> CompletedMarker {
     m.complete(p, SyntaxKind::PrefixExpr)
 }
 
+/// Parenthesized expression or a tuple (two or more comma-separated items)
 fn paren_expr(p: &mut Parser) -> CompletedMarker {
     assert!(p.at(TokenKind::LParen));
 
     let m = p.start();
     p.bump();
     expr_binding_power(p, 0);
+    let mut is_tuple = false;
+    while p.at(TokenKind::Comma) {
+        is_tuple = true;
+        p.bump();
+        expr_binding_power(p, 0);
+    }
     p.expect(TokenKind::RParen);
 
-    m.complete(p, SyntaxKind::ParenExpr)
+    if is_tuple {
+        m.complete(p, SyntaxKind::TupleExpr)
+    } else {
+        m.complete(p, SyntaxKind::ParenExpr)
+    }
+}
+
+/// Field selection (`obj.field`), binds tighter than any operator
+fn select_expr(p: &mut Parser, mut lhs: CompletedMarker) -> CompletedMarker {
+    while p.at(TokenKind::Dot) {
+        let m = lhs.precede(p);
+        p.bump();
+        p.expect(TokenKind::Ident);
+        lhs = m.complete(p, SyntaxKind::SelectExpr);
+    }
+    lhs
 }
 
 #[cfg(test)]
@@ -388,7 +410,7 @@ mod tests {
                         IntNumber@1..2 "2"
                       Plus@2..3 "+"
                 error: expected number, number, identifier, ‘-’ or ‘(’
-                error: expected ‘)’"#]],
+                error: expected ‘,’ or ‘)’"#]],
         );
     }
 
@@ -472,4 +494,42 @@ mod tests {
                       RParen@6..7 ")""#]],
         );
     }
+
+    #[test]
+    fn parse_tuple() {
+        check(
+            "(1,2,3)",
+            expect![[r#"
+                Root@0..7
+                  TupleExpr@0..7
+                    LParen@0..1 "("
+                    IntNumber@1..2
+                      IntNumber@1..2 "1"
+                    Comma@2..3 ","
+                    IntNumber@3..4
+                      IntNumber@3..4 "2"
+                    Comma@4..5 ","
+                    IntNumber@5..6
+                      IntNumber@5..6 "3"
+                    RParen@6..7 ")""#]],
+        );
+    }
+
+    #[test]
+    fn parse_select_field() {
+        check(
+            "x._2+1",
+            expect![[r#"
+                Root@0..6
+                  InfixExpr@0..6
+                    SelectExpr@0..4
+                      Ident@0..1
+                        Ident@0..1 "x"
+                      Dot@1..2 "."
+                      Ident@2..4 "_2"
+                    Plus@4..5 "+"
+                    IntNumber@5..6
+                      IntNumber@5..6 "1""#]],
+        );
+    }
 }
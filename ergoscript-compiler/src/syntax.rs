@@ -18,6 +18,8 @@ pub enum SyntaxKind {
     Slash,
     And,
     Equals,
+    Comma,
+    Dot,
     LParen,
     RParen,
     LBrace,
@@ -28,6 +30,8 @@ pub enum SyntaxKind {
     InfixExpr,
     ParenExpr,
     PrefixExpr,
+    TupleExpr,
+    SelectExpr,
     VariableDef,
 }
 
@@ -45,6 +49,8 @@ impl From<TokenKind> for SyntaxKind {
             TokenKind::Star => Self::Star,
             TokenKind::Slash => Self::Slash,
             TokenKind::Equals => Self::Equals,
+            TokenKind::Comma => Self::Comma,
+            TokenKind::Dot => Self::Dot,
             TokenKind::LParen => Self::LParen,
             TokenKind::RParen => Self::RParen,
             TokenKind::LBrace => Self::LBrace,
@@ -1,3 +1,8 @@
+use std::convert::TryFrom;
+
+use ergotree_ir::types::stuple::STuple;
+use ergotree_ir::types::stype::unify;
+use ergotree_ir::types::stype::SType;
 use rowan::TextRange;
 
 use crate::error::pretty_error_desc;
@@ -5,6 +10,8 @@ use crate::hir;
 use crate::hir::Binary;
 use crate::hir::Expr;
 use crate::hir::ExprKind;
+use crate::hir::Select;
+use crate::hir::Tuple;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct TypeInferenceError {
@@ -25,24 +32,99 @@ impl TypeInferenceError {
 pub fn assign_type(expr: Expr) -> Result<Expr, TypeInferenceError> {
     hir::rewrite(expr, |e| {
         Ok(match &e.kind {
-            ExprKind::Binary(Binary { op, lhs, rhs }) => match op.node {
-                hir::BinaryOp::Plus => {
-                    let l = assign_type(*lhs.clone())?;
-                    let r = assign_type(*rhs.clone())?;
-                    let tpe = l.tpe.clone();
-                    Some(Expr {
-                        kind: Binary {
-                            op: op.clone(),
-                            lhs: l.into(),
-                            rhs: r.into(),
-                        }
-                        .into(),
-                        span: e.span,
-                        tpe,
-                    })
-                }
-                _ => todo!(),
-            },
+            ExprKind::Binary(Binary { op, lhs, rhs }) => {
+                let l = assign_type(*lhs.clone())?;
+                let r = assign_type(*rhs.clone())?;
+                let tpe = match (&l.tpe, &r.tpe) {
+                    (Some(l_tpe), Some(r_tpe)) => Some(unify(l_tpe, r_tpe).ok_or_else(|| {
+                        TypeInferenceError::new(
+                            format!(
+                                "Type mismatch: cannot apply {0:?} to {1} and {2}",
+                                op.node, l_tpe, r_tpe
+                            ),
+                            e.span,
+                        )
+                    })?),
+                    _ => None,
+                };
+                Some(Expr {
+                    kind: Binary {
+                        op: op.clone(),
+                        lhs: l.into(),
+                        rhs: r.into(),
+                    }
+                    .into(),
+                    span: e.span,
+                    tpe,
+                })
+            }
+            ExprKind::Tuple(Tuple { items }) => {
+                let items = items
+                    .iter()
+                    .cloned()
+                    .map(assign_type)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let tpe = match items
+                    .iter()
+                    .map(|item| item.tpe.clone())
+                    .collect::<Option<Vec<_>>>()
+                {
+                    Some(types) => Some(SType::STuple(STuple::try_from(types).map_err(|err| {
+                        TypeInferenceError::new(format!("Invalid tuple: {0}", err), e.span)
+                    })?)),
+                    None => None,
+                };
+                Some(Expr {
+                    kind: Tuple { items }.into(),
+                    span: e.span,
+                    tpe,
+                })
+            }
+            ExprKind::Select(select) => {
+                let obj = assign_type(*select.obj.clone())?;
+                let tpe = match &obj.tpe {
+                    Some(SType::STuple(tuple)) => Some(
+                        select
+                            .tuple_field_index()
+                            .and_then(|idx| tuple.items.get(idx as usize - 1))
+                            .cloned()
+                            .ok_or_else(|| {
+                                TypeInferenceError::new(
+                                    format!(
+                                        "Field {0} is not found in tuple {1}",
+                                        select.field.node, tuple
+                                    ),
+                                    select.field.span,
+                                )
+                            })?,
+                    ),
+                    // v5 has no BigInt to Byte/Short/Int/Long conversion
+                    Some(tpe)
+                        if tpe.is_numeric()
+                            && select.numeric_cast_tpe().map_or(false, |cast_tpe| {
+                                *tpe != SType::SBigInt || cast_tpe == SType::SBigInt
+                            }) =>
+                    {
+                        select.numeric_cast_tpe()
+                    }
+                    Some(tpe) => {
+                        return Err(TypeInferenceError::new(
+                            format!("Field {0} is not found in {1:?}", select.field.node, tpe),
+                            select.field.span,
+                        ))
+                    }
+                    None => None,
+                };
+                Some(Expr {
+                    kind: Select {
+                        obj: obj.into(),
+                        field: select.field.clone(),
+                    }
+                    .into(),
+                    span: e.span,
+                    tpe,
+                })
+            }
             _ => None,
         })
     })
@@ -4,11 +4,15 @@ use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProp;
 use std::fmt::Display;
 use std::rc::Rc;
 
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::ergo_tree::ErgoTreeClass;
+use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
 use ergotree_ir::mir::expr::Expr;
 use ergotree_ir::mir::value::Value;
 use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
 
 use cost_accum::CostAccumulator;
+use costs::Costs;
 use ergotree_ir::types::smethod::SMethod;
 
 use self::context::Context;
@@ -86,6 +90,7 @@ pub(crate) mod sigma_or;
 pub(crate) mod sigma_prop_bytes;
 pub(crate) mod soption;
 pub(crate) mod spreheader;
+pub(crate) mod ssigmaprop;
 pub(crate) mod subst_const;
 pub(crate) mod tree_lookup;
 pub(crate) mod tuple;
@@ -119,7 +124,7 @@ impl Display for ReductionDiagnosticInfo {
 pub struct ReductionResult {
     /// value of SigmaProp type which represents a statement verifiable via sigma protocol.
     pub sigma_prop: SigmaBoolean,
-    /// estimated cost of expression evaluation
+    /// estimated cost of expression evaluation and of the proof verification for `sigma_prop`
     pub cost: u64,
     /// Diagnostic information about the reduction (pretty printed expr and/or env)
     pub diag: ReductionDiagnosticInfo,
@@ -130,37 +135,47 @@ pub fn reduce_to_crypto(
     expr: &Expr,
     env: &Env,
     ctx: Rc<Context>,
+) -> Result<ReductionResult, EvalError> {
+    reduce_to_crypto_with_cost_limit(expr, env, ctx, None)
+}
+
+/// Same as [`reduce_to_crypto`], but fails with [`EvalError::CostError`] as soon as the
+/// accumulated cost exceeds `cost_limit`
+pub fn reduce_to_crypto_with_cost_limit(
+    expr: &Expr,
+    env: &Env,
+    ctx: Rc<Context>,
+    cost_limit: Option<u64>,
 ) -> Result<ReductionResult, EvalError> {
     let ctx_clone = ctx.clone();
-    fn inner(expr: &Expr, env: &Env, ctx: Rc<Context>) -> Result<ReductionResult, EvalError> {
-        let cost_accum = CostAccumulator::new(0, None);
+    fn inner(
+        expr: &Expr,
+        env: &Env,
+        ctx: Rc<Context>,
+        cost_limit: Option<u64>,
+    ) -> Result<ReductionResult, EvalError> {
+        let cost_accum = CostAccumulator::new(0, cost_limit);
         let mut ectx = EvalContext::new(ctx, cost_accum);
+        ectx.cost_accum.add(Costs::DEFAULT.interpreter_init)?;
         let mut env_mut = env.clone();
-        expr.eval(&mut env_mut, &mut ectx)
-            .and_then(|v| -> Result<ReductionResult, EvalError> {
-                match v {
-                    Value::Boolean(b) => Ok(ReductionResult {
-                        sigma_prop: SigmaBoolean::TrivialProp(b),
-                        cost: 0,
-                        diag: ReductionDiagnosticInfo {
-                            env: env_mut.clone(),
-                            pretty_printed_expr: None,
-                        },
-                    }),
-                    Value::SigmaProp(sp) => Ok(ReductionResult {
-                        sigma_prop: sp.value().clone(),
-                        cost: 0,
-                        diag: ReductionDiagnosticInfo {
-                            env: env_mut.clone(),
-                            pretty_printed_expr: None,
-                        },
-                    }),
-                    _ => Err(EvalError::InvalidResultType),
-                }
-            })
+        let sigma_prop = match expr.eval(&mut env_mut, &mut ectx)? {
+            Value::Boolean(b) => SigmaBoolean::TrivialProp(b),
+            Value::SigmaProp(sp) => sp.value().clone(),
+            _ => return Err(EvalError::InvalidResultType),
+        };
+        ectx.cost_accum
+            .add(Costs::DEFAULT.sigma_boolean_verify_cost(&sigma_prop))?;
+        Ok(ReductionResult {
+            sigma_prop,
+            cost: ectx.cost_accum.total(),
+            diag: ReductionDiagnosticInfo {
+                env: env_mut,
+                pretty_printed_expr: None,
+            },
+        })
     }
 
-    let res = inner(expr, env, ctx);
+    let res = inner(expr, env, ctx, cost_limit);
     if let Ok(reduction) = res {
         if reduction.sigma_prop == SigmaBoolean::TrivialProp(false) {
             let (_, printed_expr_str) = expr
@@ -182,8 +197,50 @@ pub fn reduce_to_crypto(
     let (spanned_expr, printed_expr_str) = expr
         .pretty_print()
         .map_err(|e| EvalError::Misc(e.to_string()))?;
-    inner(&spanned_expr, env, ctx_clone)
-        .map_err(|e| e.wrap_spanned_with_src(printed_expr_str.to_string()))
+    inner(&spanned_expr, env, ctx_clone, cost_limit).map_err(|e| match e {
+        EvalError::Spanned(_) => e.wrap_spanned_with_src(printed_expr_str.to_string()),
+        // raised outside of the expression evaluation (result type, setup and verification cost)
+        e => e,
+    })
+}
+
+/// Reduce the trivial tree (constant TRUE/FALSE/SigmaProp root, e.g. P2PK, see
+/// [`ErgoTree::classify`]) without setting up the evaluation. Returns `None` for the trees that
+/// have to be evaluated with [`reduce_to_crypto`].
+/// The cost is the same as of the evaluation of the root constant (wrapped in `sigmaProp` for the
+/// boolean trees) with [`reduce_to_crypto`]: the interpreter setup, the cost model costs of the
+/// root nodes and the proof verification.
+pub fn reduce_trivial_tree(tree: &ErgoTree, env: &Env) -> Option<ReductionResult> {
+    let costs = &Costs::DEFAULT;
+    let cost_of = |expr: &Expr| u32::from(costs.cost_of(expr)) as u64;
+    let (sigma_prop, nodes_cost) = match tree.classify() {
+        ErgoTreeClass::TrivialBool(b) => {
+            let input = Expr::Const(b.into());
+            let root: Expr = BoolToSigmaProp {
+                input: Box::new(input.clone()),
+            }
+            .into();
+            (
+                SigmaBoolean::TrivialProp(b),
+                cost_of(&root) + cost_of(&input),
+            )
+        }
+        ErgoTreeClass::ConstSigmaProp(sb) => {
+            let root = Expr::Const(SigmaProp::new(sb.clone()).into());
+            (sb, cost_of(&root))
+        }
+        ErgoTreeClass::NonTrivial => return None,
+    };
+    let init_cost = u32::from(costs.interpreter_init.clone()) as u64;
+    let verify_cost = u32::from(costs.sigma_boolean_verify_cost(&sigma_prop)) as u64;
+    Some(ReductionResult {
+        sigma_prop,
+        cost: init_cost + nodes_cost + verify_cost,
+        diag: ReductionDiagnosticInfo {
+            env: env.clone(),
+            pretty_printed_expr: None,
+        },
+    })
 }
 
 /// Expects SigmaProp constant value and returns it's value. Otherwise, returns an error.
@@ -247,6 +304,11 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
             scontext::SELF_BOX_INDEX_PROPERTY_METHOD_ID => self::scontext::SELF_BOX_INDEX_EVAL_FN,
             scontext::HEADERS_PROPERTY_METHOD_ID => self::scontext::HEADERS_EVAL_FN,
             scontext::PRE_HEADER_PROPERTY_METHOD_ID => self::scontext::PRE_HEADER_EVAL_FN,
+            scontext::INPUTS_PROPERTY_METHOD_ID => self::scontext::INPUTS_EVAL_FN,
+            scontext::OUTPUTS_PROPERTY_METHOD_ID => self::scontext::OUTPUTS_EVAL_FN,
+            scontext::HEIGHT_PROPERTY_METHOD_ID => self::scontext::HEIGHT_EVAL_FN,
+            scontext::SELF_PROPERTY_METHOD_ID => self::scontext::SELF_EVAL_FN,
+            scontext::MINER_PUBKEY_PROPERTY_METHOD_ID => self::scontext::MINER_PUBKEY_EVAL_FN,
             scontext::LAST_BLOCK_UTXO_ROOT_HASH_PROPERTY_METHOD_ID => {
                 self::scontext::LAST_BLOCK_UTXO_ROOT_HASH_EVAL_FN
             }
@@ -269,6 +331,8 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
             }
         },
         scoll::TYPE_CODE => match method.method_id() {
+            scoll::SLICE_METHOD_ID => self::scoll::SLICE_EVAL_FN,
+            scoll::APPEND_METHOD_ID => self::scoll::APPEND_EVAL_FN,
             scoll::INDEX_OF_METHOD_ID => self::scoll::INDEX_OF_EVAL_FN,
             scoll::FLATMAP_METHOD_ID => self::scoll::FLATMAP_EVAL_FN,
             scoll::ZIP_METHOD_ID => self::scoll::ZIP_EVAL_FN,
@@ -285,6 +349,8 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
         },
         sgroup_elem::TYPE_CODE => match method.method_id() {
             sgroup_elem::GET_ENCODED_METHOD_ID => self::sgroup_elem::GET_ENCODED_EVAL_FN,
+            sgroup_elem::EXPONENTIATE_METHOD_ID => self::sgroup_elem::EXPONENTIATE_EVAL_FN,
+            sgroup_elem::MULTIPLY_METHOD_ID => self::sgroup_elem::MULTIPLY_EVAL_FN,
             sgroup_elem::NEGATE_METHOD_ID => self::sgroup_elem::NEGATE_EVAL_FN,
             method_id => {
                 return Err(EvalError::NotFound(format!(
@@ -294,6 +360,9 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
             }
         },
         soption::TYPE_CODE => match method.method_id() {
+            soption::IS_DEFINED_METHOD_ID => self::soption::IS_DEFINED_EVAL_FN,
+            soption::GET_METHOD_ID => self::soption::GET_EVAL_FN,
+            soption::GET_OR_ELSE_METHOD_ID => self::soption::GET_OR_ELSE_EVAL_FN,
             soption::MAP_METHOD_ID => self::soption::MAP_EVAL_FN,
             soption::FILTER_METHOD_ID => self::soption::FILTER_EVAL_FN,
             method_id => {
@@ -343,6 +412,15 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
                 )))
             }
         },
+        ssigmaprop::TYPE_CODE => match method.method_id() {
+            ssigmaprop::PROP_BYTES_METHOD_ID => self::ssigmaprop::PROP_BYTES_EVAL_FN,
+            method_id => {
+                return Err(EvalError::NotFound(format!(
+                    "Eval fn: unknown method id in SSigmaProp: {:?}",
+                    method_id
+                )))
+            }
+        },
         sglobal::TYPE_CODE => match method.method_id() {
             sglobal::GROUP_GENERATOR_METHOD_ID => self::sglobal::GROUP_GENERATOR_EVAL_FN,
             sglobal::XOR_METHOD_ID => self::sglobal::XOR_EVAL_FN,
@@ -373,14 +451,18 @@ pub(crate) mod tests {
 
     use super::env::Env;
     use super::*;
+    use ergotree_ir::ergo_tree::ErgoTreeHeader;
     use ergotree_ir::mir::bin_op::BinOp;
     use ergotree_ir::mir::bin_op::BinOpKind;
     use ergotree_ir::mir::bin_op::RelationOp;
     use ergotree_ir::mir::block::BlockValue;
+    use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
     use ergotree_ir::mir::constant::TryExtractFrom;
     use ergotree_ir::mir::constant::TryExtractInto;
+    use ergotree_ir::mir::global_vars::GlobalVars;
     use ergotree_ir::mir::val_def::ValDef;
     use ergotree_ir::mir::val_use::ValUse;
+    use ergotree_ir::sigma_protocol::sigma_boolean::ProveDlog;
     use ergotree_ir::types::stype::SType;
     use expect_test::expect;
     use sigma_test_util::force_any_val;
@@ -456,4 +538,67 @@ pub(crate) mod tests {
         "#]]
         .assert_eq(&res.diag.to_string());
     }
+
+    #[test]
+    fn reduce_with_cost_limit() {
+        let expr: Expr = BinOp::new(
+            RelationOp::Gt.into(),
+            GlobalVars::Height.into(),
+            Expr::Const(1i32.into()),
+        )
+        .unwrap()
+        .into();
+        let ctx = Rc::new(force_any_val::<Context>());
+        let res = reduce_to_crypto(&expr, &Env::empty(), ctx.clone()).unwrap();
+        assert!(res.cost > 0);
+        assert_eq!(
+            reduce_to_crypto_with_cost_limit(&expr, &Env::empty(), ctx.clone(), Some(res.cost))
+                .unwrap(),
+            res
+        );
+        let err = reduce_to_crypto_with_cost_limit(&expr, &Env::empty(), ctx, Some(res.cost - 1))
+            .unwrap_err();
+        assert!(matches!(err.root_cause(), EvalError::CostError(_)));
+    }
+
+    #[test]
+    fn trivial_tree_fast_path() {
+        let pk = force_any_val::<ProveDlog>();
+        let ctx = Rc::new(force_any_val::<Context>());
+        let trees = vec![
+            ErgoTree::try_from(Expr::Const(SigmaProp::from(pk).into())).unwrap(),
+            ErgoTree::new(
+                ErgoTreeHeader::v0(true),
+                &BoolToSigmaProp {
+                    input: Box::new(Expr::Const(true.into())),
+                }
+                .into(),
+            )
+            .unwrap(),
+        ];
+        for tree in trees {
+            let fast = reduce_trivial_tree(&tree, &Env::empty()).unwrap();
+            let full =
+                reduce_to_crypto(&tree.proposition().unwrap(), &Env::empty(), ctx.clone()).unwrap();
+            assert_eq!(fast.sigma_prop, full.sigma_prop);
+            assert_eq!(fast.cost, full.cost);
+        }
+        let non_trivial = ErgoTree::new(
+            ErgoTreeHeader::v0(true),
+            &BoolToSigmaProp {
+                input: Box::new(
+                    BinOp::new(
+                        RelationOp::Gt.into(),
+                        GlobalVars::Height.into(),
+                        Expr::Const(1i32.into()),
+                    )
+                    .unwrap()
+                    .into(),
+                ),
+            }
+            .into(),
+        )
+        .unwrap();
+        assert!(reduce_trivial_tree(&non_trivial, &Env::empty()).is_none());
+    }
 }
@@ -1,7 +1,6 @@
 use ergotree_ir::mir::apply::Apply;
-use ergotree_ir::mir::val_def::ValId;
+use ergotree_ir::mir::value::Lambda;
 use ergotree_ir::mir::value::Value;
-use hashbrown::HashMap;
 
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
@@ -15,30 +14,7 @@ impl Evaluable for Apply {
             self.args.iter().map(|arg| arg.eval(env, ctx)).collect();
         let args_v = args_v_res?;
         match func_v {
-            Value::Lambda(fv) => {
-                let arg_ids: Vec<ValId> = fv.args.iter().map(|a| a.idx).collect();
-                let mut existing_variables = HashMap::new();
-                let mut new_variables = vec![];
-                arg_ids.iter().zip(args_v).for_each(|(idx, arg_v)| {
-                    if let Some(old_val) = env.get(*idx) {
-                        existing_variables.insert(idx, old_val.clone());
-                    } else {
-                        new_variables.push(*idx);
-                    }
-                    env.insert(*idx, arg_v);
-                });
-                let res = fv.body.eval(env, ctx);
-                new_variables.into_iter().for_each(|idx| {
-                    env.remove(&idx);
-                });
-                existing_variables
-                    .into_iter()
-                    .for_each(|(idx, orig_value)| {
-                        env.insert(*idx, orig_value);
-                    });
-
-                res
-            }
+            Value::Lambda(fv) => apply_lambda(ctx, &fv, args_v),
             _ => Err(EvalError::UnexpectedValue(format!(
                 "expected func_v to be Value::FuncValue got: {0:?}",
                 func_v
@@ -47,11 +23,32 @@ impl Evaluable for Apply {
     }
 }
 
+/// Evaluate the lambda body in the captured environment with the arguments bound
+pub(crate) fn apply_lambda(
+    ctx: &mut EvalContext,
+    lambda: &Lambda,
+    args: Vec<Value>,
+) -> Result<Value, EvalError> {
+    if lambda.args.len() != args.len() {
+        return Err(EvalError::Misc(format!(
+            "Apply: expected {0} arguments, got {1}",
+            lambda.args.len(),
+            args.len()
+        )));
+    }
+    let mut body_env = Env::from(lambda.captured_env().clone());
+    for (arg, v) in lambda.args.iter().zip(args) {
+        body_env.insert(arg.idx, v);
+    }
+    lambda.body.eval(&mut body_env, ctx)
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
-mod tests {
+pub(crate) mod tests {
     use std::rc::Rc;
 
+    use ergotree_ir::mir::bin_op::ArithOp;
     use ergotree_ir::mir::bin_op::BinOp;
     use ergotree_ir::mir::bin_op::RelationOp;
     use ergotree_ir::mir::block::BlockValue;
@@ -64,10 +61,47 @@ mod tests {
     use sigma_test_util::force_any_val;
 
     use crate::eval::context::Context;
+    use crate::eval::cost_accum::CostAccumulator;
     use crate::eval::tests::eval_out;
 
     use super::*;
 
+    /// `{ (y: Int) => { (x: <arg_tpe>) => body(x, y) } }(captured)`, the returned lambda refers
+    /// to `y` which is only bound in its captured environment
+    pub(crate) fn closure_over(
+        captured: i32,
+        arg_tpe: SType,
+        body: impl FnOnce(Expr, Expr) -> Expr,
+    ) -> Expr {
+        let x: Expr = ValUse {
+            val_id: 1.into(),
+            tpe: arg_tpe.clone(),
+        }
+        .into();
+        let y: Expr = ValUse {
+            val_id: 2.into(),
+            tpe: SType::SInt,
+        }
+        .into();
+        let inner = FuncValue::new(
+            vec![FuncArg {
+                idx: 1.into(),
+                tpe: arg_tpe,
+            }],
+            body(x, y),
+        );
+        let outer = FuncValue::new(
+            vec![FuncArg {
+                idx: 2.into(),
+                tpe: SType::SInt,
+            }],
+            inner.into(),
+        );
+        Apply::new(outer.into(), vec![Expr::Const(captured.into())])
+            .unwrap()
+            .into()
+    }
+
     #[test]
     fn eval_user_defined_func_call() {
         let arg = Expr::Const(1i32.into());
@@ -118,4 +152,64 @@ mod tests {
         let ctx = Rc::new(force_any_val::<Context>());
         assert!(eval_out::<bool>(&apply, ctx));
     }
+
+    #[test]
+    fn eval_returned_closure() {
+        // { (x: Int) => { (y: Int) => x + y } }(1)(2)
+        let inner = FuncValue::new(
+            vec![FuncArg {
+                idx: 2.into(),
+                tpe: SType::SInt,
+            }],
+            BinOp {
+                kind: ArithOp::Plus.into(),
+                left: Box::new(
+                    ValUse {
+                        val_id: 1.into(),
+                        tpe: SType::SInt,
+                    }
+                    .into(),
+                ),
+                right: Box::new(
+                    ValUse {
+                        val_id: 2.into(),
+                        tpe: SType::SInt,
+                    }
+                    .into(),
+                ),
+            }
+            .into(),
+        );
+        let outer = FuncValue::new(
+            vec![FuncArg {
+                idx: 1.into(),
+                tpe: SType::SInt,
+            }],
+            inner.into(),
+        );
+        let apply_outer: Expr = Apply::new(outer.into(), vec![Expr::Const(1i32.into())])
+            .unwrap()
+            .into();
+        let apply: Expr = Apply::new(apply_outer, vec![Expr::Const(2i32.into())])
+            .unwrap()
+            .into();
+        let ctx = Rc::new(force_any_val::<Context>());
+        assert_eq!(eval_out::<i32>(&apply, ctx), 3);
+    }
+
+    #[test]
+    fn eval_arity_mismatch() {
+        let lambda = Lambda::new(
+            vec![FuncArg {
+                idx: 1.into(),
+                tpe: SType::SInt,
+            }],
+            Box::new(Expr::Const(1i32.into())),
+        );
+        let mut ectx = EvalContext::new(
+            Rc::new(force_any_val::<Context>()),
+            CostAccumulator::new(0, None),
+        );
+        assert!(apply_lambda(&mut ectx, &lambda, vec![]).is_err());
+    }
 }
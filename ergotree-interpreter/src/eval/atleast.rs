@@ -35,22 +35,31 @@ impl Evaluable for Atleast {
             })
             .collect::<Result<Vec<SigmaBoolean>, TryExtractFromError>>()?;
 
-        let bound_u8: u8 = bound.try_into().map_err(|_| {
-            EvalError::Misc(format!("Atleast: bound is ({}) greater than 255", bound))
-        })?;
-        if bound > input.len() as i32 {
+        if input.len() > MAX_CHILDREN_COUNT {
             return Err(EvalError::Misc(format!(
-                "Atleast: bound {} > input size {}",
-                bound,
+                "Atleast: expected input elements count should not exceed {}, actual: {}",
+                MAX_CHILDREN_COUNT,
                 input.len()
             )));
         }
-        Ok(Value::SigmaProp(Box::new(SigmaProp::new(
-            Cthreshold::reduce(bound_u8, input.try_into()?),
-        ))))
+        // same as AtLeast.reduce in sigmastate
+        let sigma = if bound <= 0 {
+            true.into()
+        } else if bound as usize > input.len() {
+            false.into()
+        } else if let [single] = input.as_slice() {
+            single.clone()
+        } else {
+            // bound <= input.len() <= 255
+            Cthreshold::reduce(bound as u8, input.try_into()?)
+        };
+        Ok(Value::SigmaProp(Box::new(SigmaProp::new(sigma))))
     }
 }
 
+/// Maximum number of the sigma propositions in the input collection
+const MAX_CHILDREN_COUNT: usize = 255;
+
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::panic)]
 #[cfg(test)]
@@ -92,7 +101,7 @@ mod tests {
     }
 
     #[test]
-    fn bound_error() {
+    fn bound_out_of_range() {
         let sigmaprops = vec![force_any_val::<SigmaProp>(), force_any_val::<SigmaProp>()];
         let items = Literal::Coll(
             CollKind::from_vec(
@@ -118,8 +127,41 @@ mod tests {
             .into()
         };
         // more than input size
-        assert!(try_eval_out_wo_ctx::<SigmaProp>(&make_atleast(3)).is_err());
-        // more than u8 (see [`Cthreshold`])
-        assert!(try_eval_out_wo_ctx::<SigmaProp>(&make_atleast(256)).is_err());
+        assert_eq!(
+            SigmaBoolean::from(try_eval_out_wo_ctx::<SigmaProp>(&make_atleast(3)).unwrap()),
+            false.into()
+        );
+        assert_eq!(
+            SigmaBoolean::from(try_eval_out_wo_ctx::<SigmaProp>(&make_atleast(256)).unwrap()),
+            false.into()
+        );
+        // non-positive bound
+        assert_eq!(
+            SigmaBoolean::from(try_eval_out_wo_ctx::<SigmaProp>(&make_atleast(0)).unwrap()),
+            true.into()
+        );
+        assert_eq!(
+            SigmaBoolean::from(try_eval_out_wo_ctx::<SigmaProp>(&make_atleast(-1)).unwrap()),
+            true.into()
+        );
+    }
+
+    #[test]
+    fn single_input() {
+        let sigmaprop = force_any_val::<SigmaProp>();
+        let items = Literal::Coll(
+            CollKind::from_vec(SType::SSigmaProp, vec![sigmaprop.clone().into()]).unwrap(),
+        );
+        let expr: Expr = Atleast::new(
+            1i32.into(),
+            Constant {
+                tpe: SType::SColl(SType::SSigmaProp.into()),
+                v: items,
+            }
+            .into(),
+        )
+        .unwrap()
+        .into();
+        assert_eq!(try_eval_out_wo_ctx::<SigmaProp>(&expr).unwrap(), sigmaprop);
     }
 }
@@ -3,7 +3,6 @@ use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::val_def::ValDef;
 use ergotree_ir::mir::value::Value;
 use ergotree_ir::source_span::Spanned;
-use hashbrown::HashMap;
 
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
@@ -27,32 +26,16 @@ impl Evaluable for BlockValue {
             // Keep all `ValDef`s introduced in this block
             self.result.eval(env, ctx)
         } else {
-            let mut existing_variables = HashMap::new();
-            let mut new_variables = vec![];
-
+            // `ValDef`s are only visible in this block
+            let mut block_env = env.clone();
             for i in &self.items {
                 // TODO: new try_extract_spanned_into?
                 let spanned_val_def = &i.clone().try_extract_into::<Spanned<ValDef>>()?;
                 let val_def = spanned_val_def.expr();
-                let idx = val_def.id;
-                let v: Value = val_def.rhs.eval(env, ctx)?;
-                if let Some(old_val) = env.get(idx) {
-                    existing_variables.insert(idx, old_val.clone());
-                } else {
-                    new_variables.push(idx);
-                }
-                env.insert(idx, v);
+                let v: Value = val_def.rhs.eval(&mut block_env, ctx)?;
+                block_env.insert(val_def.id, v);
             }
-            let res = self.result.eval(env, ctx);
-            new_variables.into_iter().for_each(|idx| {
-                env.remove(&idx);
-            });
-            existing_variables
-                .into_iter()
-                .for_each(|(idx, orig_value)| {
-                    env.insert(idx, orig_value);
-                });
-            res
+            self.result.eval(&mut block_env, ctx)
         }
     }
 }
@@ -10,21 +10,14 @@ use ergotree_ir::mir::constant::TryExtractInto;
 
 impl Evaluable for ByteArrayToLong {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
-        let input = self.input.eval(env, ctx)?.try_extract_into::<Vec<i8>>()?;
-        if input.len() < 8 {
-            return Err(UnexpectedValue(
+        let input = self.input.eval(env, ctx)?.try_extract_into::<Vec<u8>>()?;
+        // big-endian, the bytes after the first 8 are ignored (Guava's `Longs.fromByteArray`)
+        match input.get(..8).and_then(|b| <[u8; 8]>::try_from(b).ok()) {
+            Some(bytes) => Ok(i64::from_be_bytes(bytes).into()),
+            None => Err(UnexpectedValue(
                 "byteArrayToLong: array must contain at least 8 elements".into(),
-            ));
+            )),
         }
-        Ok(((input[0] as i64) << 56
-            | (input[1] as i64) << 48
-            | (input[2] as i64) << 40
-            | (input[3] as i64) << 32
-            | (input[4] as i64) << 24
-            | (input[5] as i64) << 16
-            | (input[6] as i64) << 8
-            | (input[7] as i64))
-            .into())
     }
 }
 
@@ -61,6 +54,12 @@ mod tests {
         assert_eq!(res, Ok(-1));
     }
 
+    #[test]
+    fn eval_unsigned_bytes() {
+        let res = eval_node(vec![0, 0, 0, 0, 0, 0, -1, -1]);
+        assert_eq!(res, Ok(0xFFFF));
+    }
+
     #[test]
     fn fails_for_short() {
         let res = eval_node(vec![0; 7]);
@@ -2,9 +2,9 @@ use ergotree_ir::mir::calc_blake2b256::CalcBlake2b256;
 use ergotree_ir::mir::value::CollKind;
 use ergotree_ir::mir::value::NativeColl;
 use ergotree_ir::mir::value::Value;
-use sigma_util::hash::blake2b256_hash;
-use sigma_util::AsVecU8;
+use sigma_util::hash::blake2b256_hash_i8;
 
+use crate::eval::costs::Costs;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -13,11 +13,12 @@ use crate::eval::Evaluable;
 impl Evaluable for CalcBlake2b256 {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let input_v = self.input.eval(env, ctx)?;
-        match input_v.clone() {
+        match input_v {
             Value::Coll(CollKind::NativeColl(NativeColl::CollByte(coll_byte))) => {
-                let expected_hash: Vec<u8> =
-                    blake2b256_hash(coll_byte.as_vec_u8().as_slice()).to_vec();
-                Ok(expected_hash.into())
+                ctx.cost_accum
+                    .add(Costs::DEFAULT.calc_blake2b256.cost(coll_byte.len()))?;
+                let hash: Vec<u8> = blake2b256_hash_i8(&coll_byte).to_vec();
+                Ok(hash.into())
             }
             _ => Err(EvalError::UnexpectedValue(format!(
                 "expected CalcBlake2b256 input to be byte array, got: {0:?}",
@@ -36,6 +37,8 @@ mod tests {
     use ergotree_ir::mir::expr::Expr;
     use proptest::prelude::*;
     use sigma_test_util::force_any_val;
+    use sigma_util::hash::blake2b256_hash;
+    use sigma_util::AsVecU8;
     use std::rc::Rc;
 
     proptest! {
@@ -2,9 +2,9 @@ use ergotree_ir::mir::calc_sha256::CalcSha256;
 use ergotree_ir::mir::value::CollKind;
 use ergotree_ir::mir::value::NativeColl;
 use ergotree_ir::mir::value::Value;
-use sigma_util::hash::sha256_hash;
-use sigma_util::AsVecU8;
+use sigma_util::hash::sha256_hash_i8;
 
+use crate::eval::costs::Costs;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -13,10 +13,12 @@ use crate::eval::Evaluable;
 impl Evaluable for CalcSha256 {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let input_v = self.input.eval(env, ctx)?;
-        match input_v.clone() {
+        match input_v {
             Value::Coll(CollKind::NativeColl(NativeColl::CollByte(coll_byte))) => {
-                let expected_hash: Vec<u8> = sha256_hash(coll_byte.as_vec_u8().as_slice()).to_vec();
-                Ok(expected_hash.into())
+                ctx.cost_accum
+                    .add(Costs::DEFAULT.calc_sha256.cost(coll_byte.len()))?;
+                let hash: Vec<u8> = sha256_hash_i8(&coll_byte).to_vec();
+                Ok(hash.into())
             }
             _ => Err(EvalError::UnexpectedValue(format!(
                 "expected Sha256 input to be byte array, got: {0:?}",
@@ -35,6 +37,8 @@ mod tests {
     use ergotree_ir::mir::expr::Expr;
     use proptest::prelude::*;
     use sigma_test_util::force_any_val;
+    use sigma_util::hash::sha256_hash;
+    use sigma_util::AsVecU8;
     use std::rc::Rc;
 
     proptest! {
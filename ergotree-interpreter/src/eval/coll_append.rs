@@ -38,22 +38,27 @@ impl Evaluable for Append {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let input_v = self.input.eval(env, ctx)?;
         let col2_v = self.col_2.eval(env, ctx)?;
-        let input_elem_tpe = extract_elem_tpe(&input_v)?;
-        let col2_elem_tpe = extract_elem_tpe(&col2_v)?;
-        if input_elem_tpe != col2_elem_tpe {
-            return Err(EvalError::UnexpectedValue(format!(
-                "Append: expected the same elem tpe, got {0:?} and {1:?}",
-                input_elem_tpe, col2_elem_tpe
-            )));
-        }
-        let input_vecval: Vec<Value> = extract_vecval(input_v)?;
-        let col_2_vecval: Vec<Value> = extract_vecval(col2_v)?;
-        let concat_vecval: Vec<Value> = concat(input_vecval, col_2_vecval);
-        Ok(Value::Coll(CollKind::from_vec(
-            input_elem_tpe,
-            concat_vecval,
-        )?))
+        append(input_v, col2_v)
+    }
+}
+
+/// Concatenation of the collections (used for `Coll.append` method as well)
+pub(crate) fn append(input_v: Value, col2_v: Value) -> Result<Value, EvalError> {
+    let input_elem_tpe = extract_elem_tpe(&input_v)?;
+    let col2_elem_tpe = extract_elem_tpe(&col2_v)?;
+    if input_elem_tpe != col2_elem_tpe {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Append: expected the same elem tpe, got {0:?} and {1:?}",
+            input_elem_tpe, col2_elem_tpe
+        )));
     }
+    let input_vecval: Vec<Value> = extract_vecval(input_v)?;
+    let col_2_vecval: Vec<Value> = extract_vecval(col2_v)?;
+    let concat_vecval: Vec<Value> = concat(input_vecval, col_2_vecval);
+    Ok(Value::Coll(CollKind::from_vec(
+        input_elem_tpe,
+        concat_vecval,
+    )?))
 }
 
 #[allow(clippy::unwrap_used)]
@@ -1,6 +1,9 @@
 use ergotree_ir::mir::coll_by_index::ByIndex;
 use ergotree_ir::mir::constant::TryExtractInto;
+use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::mir::global_vars::GlobalVars;
 use ergotree_ir::mir::value::Value;
+use ergotree_ir::types::scontext;
 
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
@@ -26,20 +29,41 @@ impl Evaluable for ByIndex {
                     .cloned()
                     .unwrap_or(default_v))
             }
-            None => normalized_input_vals
-                .get(index_v.clone().try_extract_into::<i32>()? as usize)
-                .cloned()
-                .ok_or_else(|| {
-                    EvalError::Misc(format!(
-                        "ByIndex: index {0:?} out of bounds for collection size {1:?}",
-                        index_v,
-                        normalized_input_vals.len()
-                    ))
-                }),
+            None => {
+                let index = index_v.clone().try_extract_into::<i32>()?;
+                normalized_input_vals
+                    .get(index as usize)
+                    .cloned()
+                    .ok_or_else(|| {
+                        let len = normalized_input_vals.len();
+                        match &*self.input {
+                            Expr::GlobalVars(
+                                collection @ (GlobalVars::Inputs | GlobalVars::Outputs),
+                            ) => EvalError::IndexOutOfBounds {
+                                index,
+                                len,
+                                collection: collection.to_string(),
+                            },
+                            input if is_data_inputs(input) => {
+                                EvalError::DataInputNotFound { index, count: len }
+                            }
+                            _ => EvalError::Misc(format!(
+                                "ByIndex: index {0:?} out of bounds for collection size {1:?}",
+                                index_v, len
+                            )),
+                        }
+                    })
+            }
         }
     }
 }
 
+/// `CONTEXT.dataInputs`
+fn is_data_inputs(expr: &Expr) -> bool {
+    matches!(expr, Expr::PropertyCall(pc)
+        if *pc.expr.obj == Expr::Context && pc.expr.method == *scontext::DATA_INPUTS_PROPERTY)
+}
+
 #[allow(clippy::unwrap_used)]
 #[cfg(test)]
 mod tests {
@@ -52,6 +76,8 @@ mod tests {
     use crate::eval::context::Context;
     use crate::eval::tests::eval_out;
     use crate::eval::tests::eval_out_wo_ctx;
+    use crate::eval::tests::try_eval_out;
+    use ergotree_ir::mir::property_call::PropertyCall;
     use std::rc::Rc;
     use std::sync::Arc;
 
@@ -67,6 +93,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn eval_out_of_bounds() {
+        let ctx = Rc::new(force_any_val::<Context>());
+        let expr: Expr = ByIndex::new(
+            GlobalVars::Inputs.into(),
+            Expr::Const((ctx.inputs.len() as i32).into()),
+            None,
+        )
+        .unwrap()
+        .into();
+        assert_eq!(
+            try_eval_out::<Arc<ErgoBox>>(&expr, ctx.clone()).map_err(|e| e.root_cause().clone()),
+            Err(EvalError::IndexOutOfBounds {
+                index: ctx.inputs.len() as i32,
+                len: ctx.inputs.len(),
+                collection: "INPUTS".to_string(),
+            })
+        );
+        let expr: Expr = ByIndex::new(
+            GlobalVars::Outputs.into(),
+            Expr::Const((-1i32).into()),
+            None,
+        )
+        .unwrap()
+        .into();
+        assert_eq!(
+            try_eval_out::<Arc<ErgoBox>>(&expr, ctx.clone()).map_err(|e| e.root_cause().clone()),
+            Err(EvalError::IndexOutOfBounds {
+                index: -1,
+                len: ctx.outputs.len(),
+                collection: "OUTPUTS".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn eval_data_input() {
+        let data_inputs: Expr =
+            PropertyCall::new(Expr::Context, scontext::DATA_INPUTS_PROPERTY.clone())
+                .unwrap()
+                .into();
+        let ctx = Rc::new(force_any_val::<Context>());
+        let count = ctx.data_inputs.as_ref().map_or(0, |d| d.len());
+        if let Some(data_inputs_boxes) = ctx.data_inputs.as_ref() {
+            let expr: Expr = ByIndex::new(data_inputs.clone(), Expr::Const(0i32.into()), None)
+                .unwrap()
+                .into();
+            assert_eq!(
+                eval_out::<Arc<ErgoBox>>(&expr, ctx.clone()).box_id(),
+                data_inputs_boxes.first().box_id()
+            );
+        }
+        let expr: Expr = ByIndex::new(data_inputs, Expr::Const((count as i32).into()), None)
+            .unwrap()
+            .into();
+        assert_eq!(
+            try_eval_out::<Arc<ErgoBox>>(&expr, ctx).map_err(|e| e.root_cause().clone()),
+            Err(EvalError::DataInputNotFound {
+                index: count as i32,
+                count
+            })
+        );
+    }
+
     #[test]
     fn eval_with_default() {
         let expr: Expr = ByIndex::new(
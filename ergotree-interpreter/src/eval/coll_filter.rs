@@ -3,6 +3,7 @@ use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::value::CollKind;
 use ergotree_ir::mir::value::Value;
 
+use crate::eval::apply::apply_lambda;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -14,22 +15,7 @@ impl Evaluable for Filter {
         let condition_v = self.condition.eval(env, ctx)?;
         let input_v_clone = input_v.clone();
         let mut condition_call = |arg: Value| match &condition_v {
-            Value::Lambda(func_value) => {
-                let func_arg = func_value.args.first().ok_or_else(|| {
-                    EvalError::NotFound(
-                        "Filter: evaluated condition has empty arguments list".to_string(),
-                    )
-                })?;
-                let orig_val = env.get(func_arg.idx).cloned();
-                env.insert(func_arg.idx, arg);
-                let res = func_value.body.eval(env, ctx);
-                if let Some(orig_val) = orig_val {
-                    env.insert(func_arg.idx, orig_val);
-                } else {
-                    env.remove(&func_arg.idx);
-                }
-                res
-            }
+            Value::Lambda(func_value) => apply_lambda(ctx, func_value, vec![arg]),
             _ => Err(EvalError::UnexpectedValue(format!(
                 "expected Filter::condition to be Value::FuncValue got: {0:?}",
                 input_v_clone
@@ -82,8 +68,10 @@ mod tests {
     use std::rc::Rc;
     use std::sync::Arc;
 
+    use crate::eval::apply::tests::closure_over;
     use crate::eval::context::Context;
     use crate::eval::tests::eval_out;
+    use crate::eval::tests::eval_out_wo_ctx;
 
     use super::*;
 
@@ -155,4 +143,20 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn eval_closure_capture() {
+        let condition = closure_over(1, SType::SInt, |x, y| {
+            BinOp {
+                kind: RelationOp::Gt.into(),
+                left: Box::new(x),
+                right: Box::new(y),
+            }
+            .into()
+        });
+        let expr: Expr = Filter::new(Expr::Const(vec![0i32, 1, 2, 3].into()), condition)
+            .unwrap()
+            .into();
+        assert_eq!(eval_out_wo_ctx::<Vec<i32>>(&expr), vec![2, 3]);
+    }
 }
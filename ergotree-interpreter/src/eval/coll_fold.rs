@@ -3,6 +3,7 @@ use ergotree_ir::mir::value::CollKind;
 use ergotree_ir::mir::value::NativeColl;
 use ergotree_ir::mir::value::Value;
 
+use crate::eval::apply::apply_lambda;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -15,21 +16,7 @@ impl Evaluable for Fold {
         let fold_op_v = self.fold_op.eval(env, ctx)?;
         let input_v_clone = input_v.clone();
         let mut fold_op_call = |arg: Value| match &fold_op_v {
-            Value::Lambda(func_value) => {
-                let func_arg = func_value
-                    .args
-                    .first()
-                    .ok_or_else(|| EvalError::NotFound("empty argument for fold op".to_string()))?;
-                let orig_val = env.get(func_arg.idx).cloned();
-                env.insert(func_arg.idx, arg);
-                let res = func_value.body.eval(env, ctx);
-                if let Some(orig_val) = orig_val {
-                    env.insert(func_arg.idx, orig_val);
-                } else {
-                    env.remove(&func_arg.idx);
-                }
-                res
-            }
+            Value::Lambda(func_value) => apply_lambda(ctx, func_value, vec![arg]),
             _ => Err(EvalError::UnexpectedValue(format!(
                 "expected fold_op to be Value::FuncValue got: {0:?}",
                 input_v_clone
@@ -66,8 +53,10 @@ mod tests {
     use std::convert::TryInto;
     use std::rc::Rc;
 
+    use crate::eval::apply::tests::closure_over;
     use crate::eval::context::Context;
     use crate::eval::tests::eval_out;
+    use crate::eval::tests::eval_out_wo_ctx;
     use ergotree_ir::mir::bin_op::ArithOp;
     use ergotree_ir::mir::bin_op::BinOp;
     use ergotree_ir::mir::expr::Expr;
@@ -139,4 +128,45 @@ mod tests {
         }
 
     }
+
+    #[test]
+    fn eval_closure_capture() {
+        // (acc, item) => acc + item + y
+        let fold_op = closure_over(
+            10,
+            SType::STuple(STuple {
+                items: [SType::SInt, SType::SInt].into(),
+            }),
+            |tuple, y| {
+                let acc_plus_item: Expr = BinOp {
+                    kind: ArithOp::Plus.into(),
+                    left: Box::new(Expr::SelectField(
+                        SelectField::new(tuple.clone(), 1.try_into().unwrap())
+                            .unwrap()
+                            .into(),
+                    )),
+                    right: Box::new(Expr::SelectField(
+                        SelectField::new(tuple, 2.try_into().unwrap())
+                            .unwrap()
+                            .into(),
+                    )),
+                }
+                .into();
+                BinOp {
+                    kind: ArithOp::Plus.into(),
+                    left: Box::new(acc_plus_item),
+                    right: Box::new(y),
+                }
+                .into()
+            },
+        );
+        let expr: Expr = Fold::new(
+            Expr::Const(vec![1i32, 2, 3].into()),
+            Expr::Const(0i32.into()),
+            fold_op,
+        )
+        .unwrap()
+        .into();
+        assert_eq!(eval_out_wo_ctx::<i32>(&expr), 36);
+    }
 }
@@ -2,6 +2,7 @@ use ergotree_ir::mir::coll_forall::ForAll;
 use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::value::Value;
 
+use crate::eval::apply::apply_lambda;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -13,22 +14,7 @@ impl Evaluable for ForAll {
         let condition_v = self.condition.eval(env, ctx)?;
         let input_v_clone = input_v.clone();
         let mut condition_call = |arg: Value| match &condition_v {
-            Value::Lambda(func_value) => {
-                let func_arg = func_value.args.first().ok_or_else(|| {
-                    EvalError::NotFound(
-                        "ForAll: evaluated condition has empty arguments list".to_string(),
-                    )
-                })?;
-                let orig_val = env.get(func_arg.idx).cloned();
-                env.insert(func_arg.idx, arg);
-                let res = func_value.body.eval(env, ctx);
-                if let Some(orig_val) = orig_val {
-                    env.insert(func_arg.idx, orig_val);
-                } else {
-                    env.remove(&func_arg.idx);
-                }
-                res
-            }
+            Value::Lambda(func_value) => apply_lambda(ctx, func_value, vec![arg]),
             _ => Err(EvalError::UnexpectedValue(format!(
                 "expected ForAll::condition to be Value::FuncValue got: {0:?}",
                 input_v_clone
@@ -65,6 +51,7 @@ impl Evaluable for ForAll {
 #[cfg(test)]
 mod tests {
 
+    use crate::eval::apply::tests::closure_over;
     use crate::eval::tests::eval_out_wo_ctx;
 
     use super::*;
@@ -123,4 +110,21 @@ mod tests {
     fn eval_false() {
         check(vec![1, 2]);
     }
+
+    #[test]
+    fn eval_closure_capture() {
+        let forall_gt_0 = |coll: Vec<i32>| -> Expr {
+            let condition = closure_over(0, SType::SInt, |x, y| {
+                BinOp {
+                    kind: RelationOp::Gt.into(),
+                    left: Box::new(x),
+                    right: Box::new(y),
+                }
+                .into()
+            });
+            ForAll::new(coll.into(), condition).unwrap().into()
+        };
+        assert!(eval_out_wo_ctx::<bool>(&forall_gt_0(vec![1, 2])));
+        assert!(!eval_out_wo_ctx::<bool>(&forall_gt_0(vec![0, 1])));
+    }
 }
@@ -2,6 +2,7 @@ use ergotree_ir::mir::coll_map::Map;
 use ergotree_ir::mir::value::CollKind;
 use ergotree_ir::mir::value::Value;
 
+use crate::eval::apply::apply_lambda;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -13,22 +14,7 @@ impl Evaluable for Map {
         let mapper_v = self.mapper.eval(env, ctx)?;
         let input_v_clone = input_v.clone();
         let mut mapper_call = |arg: Value| match &mapper_v {
-            Value::Lambda(func_value) => {
-                let func_arg = func_value.args.first().ok_or_else(|| {
-                    EvalError::NotFound(
-                        "Map: evaluated mapper has empty arguments list".to_string(),
-                    )
-                })?;
-                let orig_val = env.get(func_arg.idx).cloned();
-                env.insert(func_arg.idx, arg);
-                let res = func_value.body.eval(env, ctx);
-                if let Some(orig_val) = orig_val {
-                    env.insert(func_arg.idx, orig_val);
-                } else {
-                    env.remove(&func_arg.idx);
-                }
-                res
-            }
+            Value::Lambda(func_value) => apply_lambda(ctx, func_value, vec![arg]),
             _ => Err(EvalError::UnexpectedValue(format!(
                 "expected mapper to be Value::FuncValue got: {0:?}",
                 input_v_clone
@@ -78,9 +64,11 @@ impl Evaluable for Map {
 mod tests {
     use std::rc::Rc;
 
+    use crate::eval::apply::tests::closure_over;
     use crate::eval::context::Context;
     use crate::eval::context::TxIoVec;
     use crate::eval::tests::eval_out;
+    use crate::eval::tests::eval_out_wo_ctx;
     use ergotree_ir::mir::bin_op::ArithOp;
     use ergotree_ir::mir::bin_op::BinOp;
     use ergotree_ir::mir::expr::Expr;
@@ -149,4 +137,20 @@ mod tests {
         }
 
     }
+
+    #[test]
+    fn eval_closure_capture() {
+        let mapper = closure_over(10, SType::SInt, |x, y| {
+            BinOp {
+                kind: ArithOp::Plus.into(),
+                left: Box::new(x),
+                right: Box::new(y),
+            }
+            .into()
+        });
+        let expr: Expr = Map::new(Expr::Const(vec![1i32, 2, 3].into()), mapper)
+            .unwrap()
+            .into();
+        assert_eq!(eval_out_wo_ctx::<Vec<i32>>(&expr), vec![11, 12, 13]);
+    }
 }
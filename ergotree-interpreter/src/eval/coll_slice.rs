@@ -13,24 +13,29 @@ impl Evaluable for Slice {
         let input_v = self.input.eval(env, ctx)?;
         let from_v = self.from.eval(env, ctx)?;
         let until_v = self.until.eval(env, ctx)?;
-        let (input_vec, elem_tpe) = match input_v {
-            Value::Coll(coll) => Ok((coll.as_vec(), coll.elem_tpe().clone())),
-            _ => Err(EvalError::UnexpectedValue(format!(
-                "Slice: expected input to be Value::Coll, got: {0:?}",
-                input_v
-            ))),
-        }?;
-        let from = from_v.try_extract_into::<i32>()?;
-        let until = until_v.try_extract_into::<i32>()?;
-        // intersection of the range with collection bounds
-        // to preserve the Scala version semantics of slice op
-        // see https://github.com/ergoplatform/sigma-rust/issues/724
-        let range = from.max(0) as usize..until.min(input_vec.len() as i32) as usize;
-        match input_vec.get(range) {
-            Some(slice) => Ok(Value::Coll(CollKind::from_vec(elem_tpe, slice.to_vec())?)),
-            // Scala version returns empty collection if the range is out of bounds
-            None => Ok(Value::Coll(CollKind::from_vec(elem_tpe, vec![])?)),
-        }
+        slice(input_v, from_v, until_v)
+    }
+}
+
+/// Elements of the collection in the `from..until` range (used for `Coll.slice` method as well)
+pub(crate) fn slice(input_v: Value, from_v: Value, until_v: Value) -> Result<Value, EvalError> {
+    let (input_vec, elem_tpe) = match input_v {
+        Value::Coll(coll) => Ok((coll.as_vec(), coll.elem_tpe().clone())),
+        _ => Err(EvalError::UnexpectedValue(format!(
+            "Slice: expected input to be Value::Coll, got: {0:?}",
+            input_v
+        ))),
+    }?;
+    let from = from_v.try_extract_into::<i32>()?;
+    let until = until_v.try_extract_into::<i32>()?;
+    // intersection of the range with collection bounds
+    // to preserve the Scala version semantics of slice op
+    // see https://github.com/ergoplatform/sigma-rust/issues/724
+    let range = from.max(0) as usize..until.min(input_vec.len() as i32) as usize;
+    match input_vec.get(range) {
+        Some(slice) => Ok(Value::Coll(CollKind::from_vec(elem_tpe, slice.to_vec())?)),
+        // Scala version returns empty collection if the range is out of bounds
+        None => Ok(Value::Coll(CollKind::from_vec(elem_tpe, vec![])?)),
     }
 }
 
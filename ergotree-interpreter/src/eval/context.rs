@@ -3,7 +3,9 @@ use std::sync::Arc;
 use crate::sigma_protocol::prover::ContextExtension;
 use bounded_vec::BoundedVec;
 use ergo_chain_types::{Header, PreHeader};
+use ergotree_ir::chain::ergo_box::BoxId;
 use ergotree_ir::chain::ergo_box::ErgoBox;
+use thiserror::Error;
 
 /// BoundedVec type for Tx inputs, output_candidates and outputs
 pub type TxIoVec<T> = BoundedVec<T, 1, { u16::MAX as usize }>;
@@ -37,25 +39,210 @@ impl Context {
             ..self
         }
     }
+
+    /// Index of the `self_box` in the inputs, `None` if it is not among the inputs
+    pub fn self_box_index(&self) -> Option<usize> {
+        self.inputs.iter().position(|it| it == &self.self_box)
+    }
+
+    /// Check that the context is consistent (`self_box` is one of the inputs)
+    pub fn validate(&self) -> Result<(), ContextError> {
+        match self.self_box_index() {
+            Some(_) => Ok(()),
+            None => Err(ContextError::SelfBoxNotInInputs(self.self_box.box_id())),
+        }
+    }
 }
 
+/// Inconsistent interpreter context
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum ContextError {
+    /// `self_box` is not one of the inputs
+    #[error("Self box {0} is not among the inputs")]
+    SelfBoxNotInInputs(BoxId),
+}
+
+/// Arbitrary
 #[cfg(feature = "arbitrary")]
 #[allow(clippy::unwrap_used)]
-mod arbitrary {
+pub mod arbitrary {
+
+    use std::ops::Range;
 
     use super::*;
-    use proptest::{collection::vec, option::of, prelude::*};
+    use ergo_chain_types::arb_header_chain;
+    use ergo_chain_types::ArbHeaderParams;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use ergotree_ir::chain::tx_id::TxId;
+    use proptest::{collection::vec, prelude::*, sample::Index};
+
+    /// Total ERG supply (nanoERG)
+    const ERG_SUPPLY: u64 = 97_739_925 * 1_000_000_000;
+
+    /// Parameters for the arbitrary [`Context`]
+    #[derive(Debug, Clone)]
+    pub struct ArbContextParams {
+        /// Current height (the oldest of the last headers for the consistent context)
+        pub height: Range<u32>,
+        /// Number of the inputs
+        pub inputs_count: Range<usize>,
+        /// Number of the data inputs (`None` if zero)
+        pub data_inputs_count: Range<usize>,
+        /// Number of the outputs
+        pub outputs_count: Range<usize>,
+        /// Box values (nanoERG)
+        pub box_value: Range<u64>,
+        /// Generate the consistent context: `SELF` is one of the inputs, the inputs and data
+        /// inputs are created before the current height, the outputs are created at the current
+        /// height and do not spend more than the inputs have, the last headers are linked by the
+        /// parent ids and the pre-header (and `HEIGHT`) is the next block. Tokens and registers
+        /// are random.
+        pub consistent: bool,
+    }
+
+    impl ArbContextParams {
+        /// Consistent context with the mainnet-like heights and box values within the ERG supply
+        pub fn realistic() -> Self {
+            ArbContextParams {
+                height: 1..5_000_000,
+                inputs_count: 1..5,
+                data_inputs_count: 0..3,
+                outputs_count: 1..5,
+                box_value: *BoxValue::SAFE_USER_MIN.as_u64()..ERG_SUPPLY / 4,
+                consistent: true,
+            }
+        }
+    }
+
+    impl Default for ArbContextParams {
+        /// Unrelated random boxes, headers and height
+        fn default() -> Self {
+            ArbContextParams {
+                height: 0..i32::MAX as u32,
+                inputs_count: 1..3,
+                data_inputs_count: 0..3,
+                outputs_count: 1..3,
+                box_value: BoxValue::MIN_RAW..(BoxValue::MAX_RAW / 10),
+                consistent: false,
+            }
+        }
+    }
+
+    fn arb_boxes(count: Range<usize>, value: Range<u64>) -> impl Strategy<Value = Vec<ErgoBox>> {
+        vec(any_with::<ErgoBox>(value.into()), count)
+    }
+
+    fn to_tx_io_vec(boxes: Vec<ErgoBox>) -> Option<TxIoVec<Arc<ErgoBox>>> {
+        TxIoVec::from_vec(boxes.into_iter().map(Arc::new).collect()).ok()
+    }
+
+    /// The box created at `height` or earlier (box id is recalculated)
+    fn created_before(b: ErgoBox, height: u32) -> ErgoBox {
+        let (tx_id, index) = (b.transaction_id, b.index);
+        let mut candidate = ErgoBoxCandidate::from(b);
+        candidate.creation_height %= height.max(1);
+        ErgoBox::from_box_candidate(&candidate, tx_id, index).unwrap()
+    }
+
+    fn arb_consistent_context(args: ArbContextParams) -> BoxedStrategy<Context> {
+        (
+            arb_header_chain(
+                10,
+                ArbHeaderParams {
+                    height: args.height.clone(),
+                    ..Default::default()
+                },
+            ),
+            arb_boxes(args.inputs_count.clone(), args.box_value.clone()),
+            any::<Index>(),
+            arb_boxes(args.data_inputs_count.clone(), args.box_value.clone()),
+            vec(
+                any_with::<ErgoBoxCandidate>(args.box_value.clone().into()),
+                args.outputs_count.clone(),
+            ),
+            any::<TxId>(),
+            any::<PreHeader>(),
+            any::<ContextExtension>(),
+        )
+            .prop_map(
+                |(
+                    headers,
+                    inputs,
+                    self_index,
+                    data_inputs,
+                    output_candidates,
+                    tx_id,
+                    pre_header,
+                    extension,
+                )| {
+                    let last_header = &headers[0];
+                    let pre_header = PreHeader {
+                        version: last_header.version,
+                        parent_id: last_header.id,
+                        timestamp: last_header.timestamp + 120_000,
+                        n_bits: last_header.n_bits,
+                        height: last_header.height + 1,
+                        ..pre_header
+                    };
+                    let height = pre_header.height;
+                    let inputs: Vec<ErgoBox> = inputs
+                        .into_iter()
+                        .map(|b| created_before(b, height))
+                        .collect();
+                    let data_inputs: Vec<ErgoBox> = data_inputs
+                        .into_iter()
+                        .map(|b| created_before(b, height))
+                        .collect();
+                    let inputs_value: u64 = inputs.iter().map(|b| *b.value.as_u64()).sum();
+                    let output_value = BoxValue::try_from(
+                        (inputs_value / output_candidates.len() as u64).max(BoxValue::MIN_RAW),
+                    )
+                    .unwrap();
+                    let outputs = output_candidates
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, candidate)| {
+                            let candidate = ErgoBoxCandidate {
+                                value: output_value,
+                                creation_height: height,
+                                ..candidate
+                            };
+                            Arc::new(
+                                ErgoBox::from_box_candidate(&candidate, tx_id, index as u16)
+                                    .unwrap(),
+                            )
+                        })
+                        .collect();
+                    let self_box = Arc::new(self_index.get(&inputs).clone());
+                    Context {
+                        height,
+                        self_box,
+                        outputs,
+                        data_inputs: to_tx_io_vec(data_inputs),
+                        inputs: to_tx_io_vec(inputs).unwrap(),
+                        pre_header,
+                        extension,
+                        headers: headers.try_into().unwrap(),
+                    }
+                },
+            )
+            .boxed()
+    }
 
     impl Arbitrary for Context {
-        type Parameters = ();
+        type Parameters = ArbContextParams;
 
-        fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+            if args.consistent {
+                return arb_consistent_context(args);
+            }
             (
-                0..i32::MAX as u32,
-                any::<ErgoBox>(),
-                vec(any::<ErgoBox>(), 1..3),
-                vec(any::<ErgoBox>(), 1..3),
-                of(vec(any::<ErgoBox>(), 1..3)),
+                args.height.clone(),
+                any_with::<ErgoBox>(args.box_value.clone().into()),
+                arb_boxes(args.outputs_count.clone(), args.box_value.clone()),
+                arb_boxes(args.inputs_count.clone(), args.box_value.clone()),
+                arb_boxes(args.data_inputs_count.clone(), args.box_value.clone()),
                 any::<PreHeader>(),
                 any::<ContextExtension>(),
                 any::<[Header; 10]>(),
@@ -75,11 +262,8 @@ mod arbitrary {
                             height,
                             self_box: Arc::new(self_box),
                             outputs: outputs.into_iter().map(Arc::new).collect(),
-                            data_inputs: data_inputs.map(|v| {
-                                TxIoVec::from_vec(v.into_iter().map(Arc::new).collect()).unwrap()
-                            }),
-                            inputs: TxIoVec::from_vec(inputs.into_iter().map(Arc::new).collect())
-                                .unwrap(),
+                            data_inputs: to_tx_io_vec(data_inputs),
+                            inputs: to_tx_io_vec(inputs).unwrap(),
                             pre_header,
                             extension,
                             headers,
@@ -93,5 +277,30 @@ mod arbitrary {
     }
 }
 
+#[allow(clippy::unwrap_used)]
 #[cfg(test)]
-mod tests {}
+#[cfg(feature = "arbitrary")]
+mod tests {
+    use super::arbitrary::ArbContextParams;
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn consistent_context(ctx in any_with::<Context>(ArbContextParams::realistic())) {
+            prop_assert!(ctx.inputs.iter().any(|b| b == &ctx.self_box));
+            prop_assert_eq!(ctx.validate(), Ok(()));
+            prop_assert_eq!(ctx.height, ctx.pre_header.height);
+            prop_assert_eq!(ctx.pre_header.parent_id, ctx.headers[0].id);
+            prop_assert!(ctx.headers.windows(2).all(|w| w[0].parent_id == w[1].id
+                && w[0].height == w[1].height + 1));
+            prop_assert!(ctx.inputs.iter().all(|b| b.creation_height < ctx.height));
+            prop_assert!(ctx.outputs.iter().all(|b| b.creation_height == ctx.height));
+            let value = |boxes: &[Arc<ErgoBox>]| boxes.iter().map(|b| *b.value.as_u64()).sum::<u64>();
+            prop_assert!(value(&ctx.outputs) <= value(ctx.inputs.as_slice()));
+        }
+    }
+}
@@ -11,8 +11,8 @@ pub struct CostAccumulator {
 
 #[derive(Error, PartialEq, Eq, Debug, Clone)]
 pub enum CostError {
-    #[error("Limit ({0}) exceeded")]
-    LimitExceeded(u64),
+    #[error("Cost {cost} exceeded the limit ({limit})")]
+    LimitExceeded { limit: u64, cost: u64 },
 }
 
 impl CostAccumulator {
@@ -30,12 +30,20 @@ impl CostAccumulator {
     }
 
     pub fn add(&mut self, cost: Cost) -> Result<(), CostError> {
-        self.accum += u32::from(cost) as u64;
+        self.accum = self.accum.saturating_add(u32::from(cost) as u64);
         if let Some(limit) = self.limit {
             if self.accum > limit {
-                return Err(CostError::LimitExceeded(limit));
+                return Err(CostError::LimitExceeded {
+                    limit,
+                    cost: self.accum,
+                });
             }
         }
         Ok(())
     }
+
+    /// Accumulated cost
+    pub fn total(&self) -> u64 {
+        self.accum
+    }
 }
@@ -1,4 +1,7 @@
 use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaConjecture;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProofOfKnowledgeTree;
 
 extern crate derive_more;
 use derive_more::{From, Into};
@@ -6,17 +9,131 @@ use derive_more::{From, Into};
 #[derive(PartialEq, Eq, Debug, Clone, From, Into)]
 pub struct Cost(u32);
 
+/// Cost depending on the number of the processed items (as `PerItemCost` in sigmastate),
+/// items are processed in chunks of `chunk_size`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PerItemCost {
+    pub base: u32,
+    pub per_chunk: u32,
+    pub chunk_size: usize,
+}
+
+impl PerItemCost {
+    /// Number of the chunks for `n_items` (at least one chunk even for no items)
+    pub fn chunks(&self, n_items: usize) -> usize {
+        n_items.saturating_sub(1) / self.chunk_size + 1
+    }
+
+    /// Cost of processing `n_items`
+    pub fn cost(&self, n_items: usize) -> Cost {
+        let chunks = u32::try_from(self.chunks(n_items)).unwrap_or(u32::MAX);
+        Cost(
+            self.base
+                .saturating_add(self.per_chunk.saturating_mul(chunks)),
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct Costs {
+    /// Setup of the script evaluation (charged once per reduced script, also for the trivial
+    /// trees reduced without the evaluation)
+    pub interpreter_init: Cost,
     pub eq_const_size: Cost,
+    pub calc_blake2b256: PerItemCost,
+    pub calc_sha256: PerItemCost,
+    /// Verification of the ProveDlog proof
+    pub prove_dlog_verify: Cost,
+    /// Verification of the ProveDHTuple proof
+    pub prove_dh_tuple_verify: Cost,
+    /// AND/OR/THRESHOLD node of the proof tree (per child)
+    pub conjecture_verify: PerItemCost,
 }
 
 impl Costs {
     pub const DEFAULT: Costs = Costs {
+        interpreter_init: Cost(1),
         eq_const_size: Cost(1),
+        calc_blake2b256: PerItemCost {
+            base: 20,
+            per_chunk: 7,
+            chunk_size: 128,
+        },
+        calc_sha256: PerItemCost {
+            base: 80,
+            per_chunk: 8,
+            chunk_size: 64,
+        },
+        prove_dlog_verify: Cost(10),
+        prove_dh_tuple_verify: Cost(20),
+        conjecture_verify: PerItemCost {
+            base: 1,
+            per_chunk: 1,
+            chunk_size: 1,
+        },
     };
 
     pub fn cost_of(&self, _: &Expr) -> Cost {
         Cost(1)
     }
+
+    /// Cost of the proof verification for the reduced proposition (none for the trivial ones)
+    pub fn sigma_boolean_verify_cost(&self, sb: &SigmaBoolean) -> Cost {
+        let cost = match sb {
+            SigmaBoolean::TrivialProp(_) => 0,
+            SigmaBoolean::ProofOfKnowledge(SigmaProofOfKnowledgeTree::ProveDlog(_)) => {
+                self.prove_dlog_verify.0
+            }
+            SigmaBoolean::ProofOfKnowledge(SigmaProofOfKnowledgeTree::ProveDhTuple(_)) => {
+                self.prove_dh_tuple_verify.0
+            }
+            SigmaBoolean::SigmaConjecture(conj) => {
+                let children = match conj {
+                    SigmaConjecture::Cand(c) => c.items.as_slice(),
+                    SigmaConjecture::Cor(c) => c.items.as_slice(),
+                    SigmaConjecture::Cthreshold(c) => c.children.as_slice(),
+                };
+                children.iter().fold(
+                    self.conjecture_verify.cost(children.len()).0,
+                    |acc, child| acc.saturating_add(self.sigma_boolean_verify_cost(child).0),
+                )
+            }
+        };
+        Cost(cost)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ergotree_ir::sigma_protocol::sigma_boolean::cand::Cand;
+    use ergotree_ir::sigma_protocol::sigma_boolean::ProveDlog;
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+
+    #[test]
+    fn per_item_cost() {
+        let cost = &Costs::DEFAULT.calc_sha256;
+        assert_eq!(cost.chunks(0), 1);
+        assert_eq!(cost.chunks(64), 1);
+        assert_eq!(cost.chunks(65), 2);
+        assert_eq!(cost.cost(0), Cost(88));
+        assert_eq!(cost.cost(1000), Cost(80 + 8 * 16));
+    }
+
+    #[test]
+    fn sigma_boolean_verify_cost() {
+        let costs = &Costs::DEFAULT;
+        let dlog: SigmaBoolean = force_any_val::<ProveDlog>().into();
+        let dlog2: SigmaBoolean = force_any_val::<ProveDlog>().into();
+        assert_eq!(
+            costs.sigma_boolean_verify_cost(&SigmaBoolean::TrivialProp(true)),
+            Cost(0)
+        );
+        assert_eq!(costs.sigma_boolean_verify_cost(&dlog), Cost(10));
+        let cand = Cand::normalized(vec![dlog.clone(), dlog2].try_into().unwrap());
+        // base + one per child + two dlogs
+        assert_eq!(costs.sigma_boolean_verify_cost(&cand), Cost(1 + 2 + 20));
+    }
 }
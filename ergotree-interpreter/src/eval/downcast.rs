@@ -9,114 +9,49 @@ use crate::eval::EvalError;
 use crate::eval::Evaluable;
 use std::convert::TryFrom;
 
-fn downcast_to_bigint(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Byte(v) => Ok(BigInt256::from(v).into()),
-        Value::Short(v) => Ok(BigInt256::from(v).into()),
-        Value::Int(v) => Ok(BigInt256::from(v).into()),
-        Value::Long(v) => Ok(BigInt256::from(v).into()),
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Downcast: cannot downcast {0:?} to BigInt",
-            in_v
-        ))),
-    }
-}
-
-fn downcast_to_long(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Byte(v) => Ok((v as i64).into()),
-        Value::Short(v) => Ok((v as i64).into()),
-        Value::Int(v) => Ok((v as i64).into()),
-        Value::Long(_) => Ok(in_v),
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Downcast: cannot downcast {0:?} to Long",
-            in_v
-        ))),
-    }
-}
-
-fn downcast_to_int(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Byte(x) => Ok((x as i32).into()),
-        Value::Short(s) => Ok((s as i32).into()),
-        Value::Int(_) => Ok(in_v),
-        Value::Long(l) => match i32::try_from(l).ok() {
-            Some(v) => Ok(v.into()),
-            _ => Err(EvalError::UnexpectedValue(
-                "Downcast: Int overflow".to_string(),
-            )),
-        },
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Downcast: cannot downcast {0:?} to Int",
-            in_v
-        ))),
-    }
-}
-
-fn downcast_to_short(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Short(_) => Ok(in_v),
-        Value::Int(i) => match i16::try_from(i).ok() {
-            Some(v) => Ok(v.into()),
-            _ => Err(EvalError::UnexpectedValue(
-                "Downcast: Short overflow".to_string(),
-            )),
-        },
-        Value::Long(l) => match i16::try_from(l).ok() {
-            Some(v) => Ok(v.into()),
-            _ => Err(EvalError::UnexpectedValue(
-                "Downcast: Short overflow".to_string(),
-            )),
-        },
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Downcast: cannot downcast {0:?} to Short",
-            in_v
-        ))),
-    }
-}
-
-fn downcast_to_byte(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Byte(_) => Ok(in_v),
-        Value::Short(s) => match i8::try_from(s).ok() {
-            Some(v) => Ok(v.into()),
-            _ => Err(EvalError::UnexpectedValue(
-                "Downcast: Byte overflow".to_string(),
-            )),
-        },
-        Value::Int(i) => match i8::try_from(i).ok() {
-            Some(v) => Ok(v.into()),
-            _ => Err(EvalError::UnexpectedValue(
-                "Downcast: Byte overflow".to_string(),
-            )),
-        },
-        Value::Long(l) => match i8::try_from(l).ok() {
-            Some(v) => Ok(v.into()),
-            _ => Err(EvalError::UnexpectedValue(
-                "Downcast: Byte overflow".to_string(),
-            )),
-        },
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Downcast: cannot downcast {0:?} to Byte",
-            in_v
-        ))),
-    }
+/// Numeric value converted to the numeric type, fails if the value is out of the type range
+pub(crate) fn cast_numeric(op: &str, in_v: Value, tpe: &SType) -> Result<Value, EvalError> {
+    let overflow = || EvalError::UnexpectedValue(format!("{0}: {1} overflow", op, tpe));
+    let v: i64 = match in_v {
+        Value::Byte(v) => v as i64,
+        Value::Short(v) => v as i64,
+        Value::Int(v) => v as i64,
+        Value::Long(v) => v,
+        Value::BigInt(_) if *tpe == SType::SBigInt => return Ok(in_v),
+        // v5 (sigmastate) does not support BigInt to Byte/Short/Int/Long conversion (it comes with
+        // v6), a range-checked conversion here would accept the scripts the node rejects
+        Value::BigInt(_) => {
+            return Err(EvalError::UnexpectedValue(format!(
+                "{0}: cannot cast BigInt to {1} in ErgoTree v5",
+                op, tpe
+            )))
+        }
+        _ => {
+            return Err(EvalError::UnexpectedValue(format!(
+                "{0}: cannot cast {1:?} to {2}",
+                op, in_v, tpe
+            )))
+        }
+    };
+    Ok(match tpe {
+        SType::SByte => i8::try_from(v).map_err(|_| overflow())?.into(),
+        SType::SShort => i16::try_from(v).map_err(|_| overflow())?.into(),
+        SType::SInt => i32::try_from(v).map_err(|_| overflow())?.into(),
+        SType::SLong => v.into(),
+        SType::SBigInt => BigInt256::from(v).into(),
+        _ => {
+            return Err(EvalError::UnexpectedValue(format!(
+                "{0}: expected numeric type, got {1:?}",
+                op, tpe
+            )))
+        }
+    })
 }
 
 impl Evaluable for Downcast {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let input_v = self.input.eval(env, ctx)?;
-        match self.tpe {
-            SType::SBigInt => downcast_to_bigint(input_v),
-            SType::SLong => downcast_to_long(input_v),
-            SType::SInt => downcast_to_int(input_v),
-            SType::SShort => downcast_to_short(input_v),
-            SType::SByte => downcast_to_byte(input_v),
-            _ => Err(EvalError::UnexpectedValue(format!(
-                "Downcast: expected numeric value, got {0:?}",
-                input_v
-            ))),
-        }
+        cast_numeric("Downcast", input_v, &self.tpe)
     }
 }
 
@@ -251,6 +186,10 @@ mod tests {
                 eval_out_wo_ctx::<i16>(&Downcast::new(c_short.into(), SType::SShort).unwrap().into()),
                 v_short
             );
+            assert_eq!(
+                eval_out_wo_ctx::<i16>(&Downcast::new((v_short as i8).into(), SType::SShort).unwrap().into()),
+                v_short as i8 as i16
+            );
             assert_eq!(
                 eval_out_wo_ctx::<i16>(&Downcast::new(c_int.into(), SType::SShort).unwrap().into()),
                 v_int as i16
@@ -338,6 +277,22 @@ mod tests {
             .is_err());
         }
         #[test]
+        fn from_bigint(v_long in any::<i64>()) {
+            let c_bigint: Constant = BigInt256::from(v_long).into();
+            assert_eq!(
+                eval_out_wo_ctx::<BigInt256>(&Downcast::new(c_bigint.clone().into(), SType::SBigInt).unwrap().into()),
+                v_long.into()
+            );
+            for tpe in [SType::SByte, SType::SShort, SType::SInt, SType::SLong] {
+                assert!(try_eval_out_wo_ctx::<Value>(
+                    &Downcast::new(c_bigint.clone().into(), tpe)
+                        .unwrap()
+                        .into()
+                )
+                .is_err());
+            }
+        }
+        #[test]
         fn test_overflow(v_short_oob in (i8::MAX as i16 + 1..i16::MAX).prop_union(i16::MIN..i8::MIN as i16),
                          v_int_oob in (i16::MAX as i32 + 1..i32::MAX).prop_union(i32::MIN..i16::MIN as i32),
                          v_long_oob in (i32::MAX as i64 + 1..i64::MAX).prop_union(i64::MIN..i32::MIN as i64)) {
@@ -1,20 +1,23 @@
-use std::collections::HashMap;
 use std::fmt::Display;
 
 use ergotree_ir::mir::val_def::ValId;
+use ergotree_ir::mir::value::Bindings;
 use ergotree_ir::mir::value::Value;
 
 /// Environment for the interpreter
+///
+/// Cloning and extending the environment is cheap (bindings are shared), so nested scopes
+/// evaluate in an extended copy instead of mutating and restoring the outer environment.
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Env {
-    store: HashMap<ValId, Value>,
+    store: Bindings,
 }
 
 impl Env {
     /// Empty environment
     pub fn empty() -> Env {
         Env {
-            store: HashMap::new(),
+            store: Bindings::empty(),
         }
     }
 
@@ -25,33 +28,44 @@ impl Env {
 
     /// Extend this environment (create new) with added element
     pub fn extend(&self, idx: ValId, v: Value) -> Env {
-        let mut new_store = self.store.clone();
-        new_store.insert(idx, v);
-        Env { store: new_store }
+        Env {
+            store: self.store.extend(idx, v),
+        }
     }
 
     /// Insert a Value for the given ValId
     pub fn insert(&mut self, idx: ValId, v: Value) {
-        self.store.insert(idx, v);
-    }
-
-    /// Remove a Value for the given ValId
-    pub fn remove(&mut self, idx: &ValId) {
-        self.store.remove(idx);
+        self.store = self.store.extend(idx, v);
     }
 
     /// Get an element
     pub fn get(&self, idx: ValId) -> Option<&Value> {
-        self.store.get(&idx)
+        self.store.get(idx)
+    }
+
+    /// All the elements (in no particular order)
+    pub fn to_vec(&self) -> Vec<(ValId, Value)> {
+        self.store.to_vec()
+    }
+
+    /// Bindings of this environment (shared, not copied)
+    pub fn bindings(&self) -> Bindings {
+        self.store.clone()
+    }
+}
+
+impl From<Bindings> for Env {
+    fn from(store: Bindings) -> Self {
+        Env { store }
     }
 }
 
 impl Display for Env {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut keys: Vec<&ValId> = self.store.keys().collect();
-        keys.sort();
-        for k in keys {
-            writeln!(f, "v{}: {}", k, self.store[k])?;
+        let mut items = self.to_vec();
+        items.sort_by_key(|(idx, _)| *idx);
+        for (idx, v) in items {
+            writeln!(f, "v{}: {}", idx, v)?;
         }
         Ok(())
     }
@@ -10,6 +10,7 @@ use ergotree_ir::mir::constant::TryExtractFromError;
 use ergotree_ir::serialization::SigmaParsingError;
 use ergotree_ir::serialization::SigmaSerializationError;
 use ergotree_ir::source_span::SourceSpan;
+use ergotree_ir::types::stype::SType;
 use sigma_ser::ScorexParsingError;
 use sigma_ser::ScorexSerializationError;
 use thiserror::Error;
@@ -41,6 +42,34 @@ pub enum EvalError {
     /// Register id out of bounds
     #[error("{0}")]
     RegisterIdOutOfBounds(String),
+    /// Register holds a value of a type other than the script expects
+    #[error("Register R{register_id} holds a value of type {actual}, expected {expected}")]
+    RegisterTypeMismatch {
+        /// Register id (0 is R0 .. 9 for R9)
+        register_id: i8,
+        /// Type expected by the script
+        expected: SType,
+        /// Type of the value in the register
+        actual: SType,
+    },
+    /// INPUTS or OUTPUTS accessed by an index out of bounds
+    #[error("Index {index} is out of bounds of {collection} (length {len})")]
+    IndexOutOfBounds {
+        /// Index the script accessed
+        index: i32,
+        /// Length of the collection
+        len: usize,
+        /// Collection name (INPUTS, OUTPUTS)
+        collection: String,
+    },
+    /// Data input is accessed by an index out of the data inputs bounds
+    #[error("Data input {index} not found (data inputs count: {count})")]
+    DataInputNotFound {
+        /// Index the script accessed
+        index: i32,
+        /// Number of the data inputs in the context
+        count: usize,
+    },
     /// Unexpected value
     #[error("Unexpected value: {0}")]
     UnexpectedValue(String),
@@ -102,8 +131,8 @@ pub struct SpannedWithSourceEvalError {
 
 impl Display for SpannedWithSourceEvalError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        #[allow(clippy::unwrap_used)]
-        miette::set_hook(Box::new(|_| {
+        // the hook is global and can only be installed once, ignore the error on the next calls
+        let _ = miette::set_hook(Box::new(|_| {
             Box::new(
                 miette::MietteHandlerOpts::new()
                     .terminal_links(false)
@@ -113,8 +142,7 @@ impl Display for SpannedWithSourceEvalError {
                     .tab_width(2)
                     .build(),
             )
-        }))
-        .unwrap();
+        }));
         let err_msg = self.error.to_string();
         let report = miette!(
             labels = vec![LabeledSpan::at(self.source_span, err_msg,)],
@@ -128,8 +156,8 @@ impl Display for SpannedWithSourceEvalError {
 
 impl Debug for SpannedWithSourceEvalError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        #[allow(clippy::unwrap_used)]
-        miette::set_hook(Box::new(|_| {
+        // the hook is global and can only be installed once, ignore the error on the next calls
+        let _ = miette::set_hook(Box::new(|_| {
             Box::new(
                 miette::MietteHandlerOpts::new()
                     .terminal_links(false)
@@ -139,8 +167,7 @@ impl Debug for SpannedWithSourceEvalError {
                     .tab_width(2)
                     .build(),
             )
-        }))
-        .unwrap();
+        }));
         let err_msg = self.error.to_string();
         let report = miette!(
             labels = vec![LabeledSpan::at(self.source_span, err_msg,)],
@@ -176,6 +203,15 @@ impl EvalError {
             e => panic!("Expected Spanned, got {:?}", e),
         }
     }
+
+    /// Underlying error without the source span (and source code) wrappers
+    pub fn root_cause(&self) -> &EvalError {
+        match self {
+            EvalError::Spanned(e) => e.error.root_cause(),
+            EvalError::SpannedWithSource(e) => e.error.root_cause(),
+            e => e,
+        }
+    }
 }
 
 pub trait ExtResultEvalError<T> {
@@ -28,7 +28,14 @@ impl Evaluable for ExtractRegisterAs {
                 "Error getting the register id {id} with error {e:?}"
             ))
         })?;
-        Ok(Value::Opt(Box::new(reg_val_opt.map(|c| Value::from(c.v)))))
+        match reg_val_opt {
+            Some(c) if c.tpe != self.elem_tpe => Err(EvalError::RegisterTypeMismatch {
+                register_id: self.register_id,
+                expected: self.elem_tpe.clone(),
+                actual: c.tpe,
+            }),
+            reg_val_opt => Ok(Value::Opt(Box::new(reg_val_opt.map(|c| Value::from(c.v))))),
+        }
     }
 }
 
@@ -38,6 +45,7 @@ mod tests {
     use super::*;
     use crate::eval::context::Context;
     use crate::eval::tests::eval_out;
+    use crate::eval::tests::try_eval_out;
     use ergotree_ir::mir::expr::Expr;
     use ergotree_ir::mir::global_vars::GlobalVars;
     use ergotree_ir::mir::option_get::OptionGet;
@@ -60,4 +68,24 @@ mod tests {
         let v = eval_out::<i64>(&option_get_expr, ctx.clone());
         assert_eq!(v, ctx.self_box.value.as_i64());
     }
+
+    #[test]
+    fn eval_box_get_reg_type_mismatch() {
+        let get_reg_expr: Expr = ExtractRegisterAs::new(
+            GlobalVars::SelfBox.into(),
+            0,
+            SType::SOption(SType::SInt.into()),
+        )
+        .unwrap()
+        .into();
+        let ctx = Rc::new(force_any_val::<Context>());
+        assert_eq!(
+            try_eval_out::<Option<i32>>(&get_reg_expr, ctx).map_err(|e| e.root_cause().clone()),
+            Err(EvalError::RegisterTypeMismatch {
+                register_id: 0,
+                expected: SType::SInt,
+                actual: SType::SLong,
+            })
+        );
+    }
 }
@@ -8,10 +8,11 @@ use crate::eval::EvalError;
 use crate::eval::Evaluable;
 
 impl Evaluable for FuncValue {
-    fn eval(&self, _env: &mut Env, _ctx: &mut EvalContext) -> Result<Value, EvalError> {
-        Ok(Value::Lambda(Lambda {
-            args: self.args().to_vec(),
-            body: self.body().clone().into(),
-        }))
+    fn eval(&self, env: &mut Env, _ctx: &mut EvalContext) -> Result<Value, EvalError> {
+        Ok(Value::Lambda(Lambda::with_captured_env(
+            self.args().to_vec(),
+            self.body().clone().into(),
+            env.bindings(),
+        )))
     }
 }
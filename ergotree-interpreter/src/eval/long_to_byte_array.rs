@@ -9,14 +9,8 @@ use ergotree_ir::mir::constant::TryExtractInto;
 
 impl Evaluable for LongToByteArray {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
-        let mut val = self.input.eval(env, ctx)?.try_extract_into::<i64>()?;
-        let mut buf = vec![42_i8; 8];
-        for i in (0..8).rev() {
-            println!("{} {}", i, val);
-            buf[i] = (val & 0xFF) as i8;
-            val >>= 8;
-        }
-        Ok(buf.into())
+        let val = self.input.eval(env, ctx)?.try_extract_into::<i64>()?;
+        Ok(val.to_be_bytes().to_vec().into())
     }
 }
 
@@ -10,14 +10,18 @@ impl Evaluable for MultiplyGroup {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let left_v = self.left.eval(env, ctx)?;
         let right_v = self.right.eval(env, ctx)?;
+        multiply_group(left_v, right_v)
+    }
+}
 
-        match (left_v.clone(), right_v.clone()) {
-            (Value::GroupElement(left), Value::GroupElement(right)) => Ok((*left * &*right).into()),
-            _ => Err(EvalError::UnexpectedValue(format!(
-                "Expected MultiplyGroup input to be GroupElement, got: {0:?}",
-                (left_v, right_v)
-            ))),
-        }
+/// Product of two GroupElements (used for `GroupElement.multiply` method as well)
+pub(crate) fn multiply_group(left_v: Value, right_v: Value) -> Result<Value, EvalError> {
+    match (left_v.clone(), right_v.clone()) {
+        (Value::GroupElement(left), Value::GroupElement(right)) => Ok((*left * &*right).into()),
+        _ => Err(EvalError::UnexpectedValue(format!(
+            "Expected MultiplyGroup input to be GroupElement, got: {0:?}",
+            (left_v, right_v)
+        ))),
     }
 }
 
@@ -9,6 +9,8 @@ use crate::eval::Evaluable;
 impl Evaluable for OptionGetOrElse {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let v = self.input.eval(env, ctx)?;
+        // the default is evaluated even if the option is defined (as in sigmastate),
+        // so an error in the default expression fails the script
         let default_v = self.default.eval(env, ctx)?;
         match v {
             Value::Opt(opt_v) => Ok(opt_v.unwrap_or(default_v)),
@@ -26,11 +28,14 @@ mod tests {
     use super::OptionGetOrElse;
     use crate::eval::context::Context;
     use crate::eval::tests::eval_out;
+    use crate::eval::tests::try_eval_out;
     use ergotree_ir::mir::constant::Constant;
     use ergotree_ir::mir::expr::Expr;
     use ergotree_ir::mir::extract_reg_as::ExtractRegisterAs;
     use ergotree_ir::mir::get_var::GetVar;
     use ergotree_ir::mir::global_vars::GlobalVars;
+    use ergotree_ir::mir::option_get::OptionGet;
+    use ergotree_ir::mir::unary_op::OneArgOpTryBuild;
     use ergotree_ir::types::stype::SType;
     use sigma_test_util::force_any_val;
     use std::rc::Rc;
@@ -68,4 +73,20 @@ mod tests {
         let v = eval_out::<i64>(&option_get_expr, ctx);
         assert_eq!(v, 1i64);
     }
+
+    #[test]
+    fn eval_default_error_non_empty() {
+        let input: Constant = Some(2i64).into();
+        let get_var_expr: Expr = GetVar {
+            var_id: 99,
+            var_tpe: SType::SLong,
+        }
+        .into();
+        let default_expr: Expr = OptionGet::try_build(get_var_expr).unwrap().into();
+        let option_get_expr: Expr = OptionGetOrElse::new(input.into(), default_expr)
+            .unwrap()
+            .into();
+        let ctx = Rc::new(force_any_val::<Context>());
+        assert!(try_eval_out::<i64>(&option_get_expr, ctx).is_err());
+    }
 }
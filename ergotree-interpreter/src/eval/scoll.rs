@@ -1,5 +1,5 @@
+use crate::eval::apply::apply_lambda;
 use crate::eval::EvalError;
-use crate::eval::Evaluable;
 
 use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::expr::Expr;
@@ -8,9 +8,31 @@ use ergotree_ir::mir::value::Value;
 use ergotree_ir::types::stuple::STuple;
 use ergotree_ir::types::stype::SType::SInt;
 
+use super::coll_append;
+use super::coll_slice;
 use super::EvalFn;
 use std::convert::TryFrom;
 
+pub(crate) static SLICE_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let from_val = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("slice: missing first arg (from)".to_string()))?;
+    let until_val = args
+        .get(1)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("slice: missing second arg (until)".to_string()))?;
+    coll_slice::slice(obj, from_val, until_val)
+};
+
+pub(crate) static APPEND_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let col2_val = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("append: missing first arg".to_string()))?;
+    coll_append::append(obj, col2_val)
+};
+
 pub(crate) static INDEX_OF_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
     Ok(Value::Int({
         let normalized_input_vals: Vec<Value> = match obj {
@@ -36,7 +58,7 @@ pub(crate) static INDEX_OF_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
     }))
 };
 
-pub(crate) static FLATMAP_EVAL_FN: EvalFn = |env, ctx, obj, args| {
+pub(crate) static FLATMAP_EVAL_FN: EvalFn = |_env, ctx, obj, args| {
     let input_v = obj;
     let lambda_v = args
         .get(0)
@@ -71,20 +93,7 @@ pub(crate) static FLATMAP_EVAL_FN: EvalFn = |env, ctx, obj, args| {
         Expr::ExtractBytesWithNoRef(_) => (),
         _ => return Err(EvalError::UnexpectedValue(unsupported_msg)),
     }
-    let mut lambda_call = |arg: Value| {
-        let func_arg = lambda.args.first().ok_or_else(|| {
-            EvalError::NotFound("flatmap: lambda has empty arguments list".to_string())
-        })?;
-        let orig_val = env.get(func_arg.idx).cloned();
-        env.insert(func_arg.idx, arg);
-        let res = lambda.body.eval(env, ctx);
-        if let Some(orig_val) = orig_val {
-            env.insert(func_arg.idx, orig_val);
-        } else {
-            env.remove(&func_arg.idx);
-        }
-        res
-    };
+    let mut lambda_call = |arg: Value| apply_lambda(ctx, lambda, vec![arg]);
     let mapper_input_tpe = lambda
         .args
         .first()
@@ -348,6 +357,55 @@ mod tests {
 
     use crate::eval::tests::{eval_out_wo_ctx, try_eval_out_wo_ctx};
 
+    #[test]
+    fn eval_slice() {
+        let coll_const: Constant = vec![1i64, 2i64, 3i64, 4i64].into();
+        let expr: Expr = MethodCall::new(
+            coll_const.into(),
+            scoll::SLICE_METHOD
+                .clone()
+                .with_concrete_types(&[(STypeVar::t(), SType::SLong)].iter().cloned().collect()),
+            vec![1i32.into(), 3i32.into()],
+        )
+        .unwrap()
+        .into();
+        let res = eval_out_wo_ctx::<Vec<i64>>(&expr);
+        assert_eq!(res, vec![2i64, 3i64]);
+    }
+
+    #[test]
+    fn eval_slice_out_of_bounds() {
+        let coll_const: Constant = vec![1i64, 2i64, 3i64, 4i64].into();
+        let expr: Expr = MethodCall::new(
+            coll_const.into(),
+            scoll::SLICE_METHOD
+                .clone()
+                .with_concrete_types(&[(STypeVar::t(), SType::SLong)].iter().cloned().collect()),
+            vec![(-1i32).into(), 10i32.into()],
+        )
+        .unwrap()
+        .into();
+        let res = eval_out_wo_ctx::<Vec<i64>>(&expr);
+        assert_eq!(res, vec![1i64, 2i64, 3i64, 4i64]);
+    }
+
+    #[test]
+    fn eval_append() {
+        let coll_const: Constant = vec![1i64, 2i64].into();
+        let col2: Vec<i64> = vec![3i64];
+        let expr: Expr = MethodCall::new(
+            coll_const.into(),
+            scoll::APPEND_METHOD
+                .clone()
+                .with_concrete_types(&[(STypeVar::t(), SType::SLong)].iter().cloned().collect()),
+            vec![col2.into()],
+        )
+        .unwrap()
+        .into();
+        let res = eval_out_wo_ctx::<Vec<i64>>(&expr);
+        assert_eq!(res, vec![1i64, 2i64, 3i64]);
+    }
+
     #[test]
     fn eval_index_of() {
         let coll_const: Constant = vec![1i64, 2i64].into();
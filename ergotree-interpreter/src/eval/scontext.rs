@@ -1,11 +1,14 @@
+use ergo_chain_types::ADDigest;
 use ergotree_ir::mir::avl_tree_data::AvlTreeData;
 use ergotree_ir::mir::avl_tree_data::AvlTreeFlags;
+use ergotree_ir::mir::global_vars::GlobalVars;
 use ergotree_ir::mir::value::CollKind;
 use ergotree_ir::mir::value::Value;
 use ergotree_ir::types::stype::SType;
 
 use super::EvalError;
 use super::EvalFn;
+use super::Evaluable;
 
 pub(crate) static DATA_INPUTS_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
     if obj != Value::Context {
@@ -33,10 +36,7 @@ pub(crate) static SELF_BOX_INDEX_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
     }
     let box_index = ctx
         .ctx
-        .inputs
-        .clone()
-        .iter()
-        .position(|it| it == &ctx.ctx.self_box)
+        .self_box_index()
         .ok_or_else(|| EvalError::NotFound("Context.selfBoxIndex: box not found".to_string()))?;
     Ok(Value::Int(box_index as i32))
 };
@@ -70,6 +70,56 @@ pub(crate) static PRE_HEADER_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
     Ok(Box::from(ctx.ctx.pre_header.clone()).into())
 };
 
+pub(crate) static INPUTS_EVAL_FN: EvalFn = |env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.INPUTS: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    GlobalVars::Inputs.eval(env, ctx)
+};
+
+pub(crate) static OUTPUTS_EVAL_FN: EvalFn = |env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.OUTPUTS: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    GlobalVars::Outputs.eval(env, ctx)
+};
+
+pub(crate) static HEIGHT_EVAL_FN: EvalFn = |env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.HEIGHT: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    GlobalVars::Height.eval(env, ctx)
+};
+
+pub(crate) static SELF_EVAL_FN: EvalFn = |env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.SELF: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    GlobalVars::SelfBox.eval(env, ctx)
+};
+
+pub(crate) static MINER_PUBKEY_EVAL_FN: EvalFn = |env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.minerPubKey: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    GlobalVars::MinerPubKey.eval(env, ctx)
+};
+
 pub(crate) static LAST_BLOCK_UTXO_ROOT_HASH_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
     if obj != Value::Context {
         return Err(EvalError::UnexpectedValue(format!(
@@ -77,15 +127,20 @@ pub(crate) static LAST_BLOCK_UTXO_ROOT_HASH_EVAL_FN: EvalFn = |_env, ctx, obj, _
             obj
         )));
     }
-    let digest = ctx.ctx.headers[0].state_root;
-    let tree_flags = AvlTreeFlags::new(true, true, true);
-    Ok(Value::AvlTree(Box::from(AvlTreeData {
+    Ok(Value::AvlTree(Box::from(utxo_state_tree(
+        ctx.ctx.headers[0].state_root,
+    ))))
+};
+
+/// UTXO set AVL tree with the given root digest (as in sigmastate, all operations are allowed)
+pub(crate) fn utxo_state_tree(digest: ADDigest) -> AvlTreeData {
+    AvlTreeData {
         digest,
-        tree_flags,
+        tree_flags: AvlTreeFlags::new(true, true, true),
         key_length: 32,
         value_length_opt: None,
-    })))
-};
+    }
+}
 
 #[cfg(test)]
 #[cfg(feature = "arbitrary")]
@@ -97,8 +152,11 @@ mod tests {
     use ergotree_ir::chain::ergo_box::ErgoBox;
     use ergotree_ir::mir::avl_tree_data::{AvlTreeData, AvlTreeFlags};
     use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::global_vars::GlobalVars;
     use ergotree_ir::mir::property_call::PropertyCall;
+    use ergotree_ir::mir::value::Value;
     use ergotree_ir::types::scontext;
+    use ergotree_ir::types::smethod::SMethod;
     use sigma_test_util::force_any_val;
     use std::rc::Rc;
 
@@ -128,6 +186,33 @@ mod tests {
         assert_eq!(eval_out::<i32>(&expr, rc), 1);
     }
 
+    #[test]
+    fn eval_global_var_properties() {
+        let ctx = Rc::new(make_ctx_inputs_includes_self_box());
+        let property_expr = |method: &SMethod| -> Expr {
+            PropertyCall::new(Expr::Context, method.clone())
+                .unwrap()
+                .into()
+        };
+        let global_var_expr = |var: GlobalVars| -> Expr { var.into() };
+        for (method_id, var) in [
+            (scontext::INPUTS_PROPERTY_METHOD_ID, GlobalVars::Inputs),
+            (scontext::OUTPUTS_PROPERTY_METHOD_ID, GlobalVars::Outputs),
+            (scontext::HEIGHT_PROPERTY_METHOD_ID, GlobalVars::Height),
+            (scontext::SELF_PROPERTY_METHOD_ID, GlobalVars::SelfBox),
+            (
+                scontext::MINER_PUBKEY_PROPERTY_METHOD_ID,
+                GlobalVars::MinerPubKey,
+            ),
+        ] {
+            let method = SMethod::from_ids(scontext::TYPE_CODE, method_id).unwrap();
+            assert_eq!(
+                eval_out::<Value>(&property_expr(&method), ctx.clone()),
+                eval_out::<Value>(&global_var_expr(var), ctx.clone())
+            );
+        }
+    }
+
     #[test]
     fn eval_headers() {
         let expr: Expr = PropertyCall::new(Expr::Context, scontext::HEADERS_PROPERTY.clone())
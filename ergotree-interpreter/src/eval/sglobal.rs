@@ -4,13 +4,9 @@ use ergotree_ir::mir::value::{CollKind, NativeColl, Value};
 
 use ergo_chain_types::ec_point::generator;
 
+use super::xor::helper_xor;
 use super::EvalFn;
 
-fn helper_xor(mut x: Vec<i8>, y: Vec<i8>) -> Vec<i8> {
-    x.iter_mut().zip(y.iter()).for_each(|(x1, x2)| *x1 ^= *x2);
-    x
-}
-
 pub(crate) static GROUP_GENERATOR_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
     if obj != Value::Global {
         return Err(EvalError::UnexpectedValue(format!(
@@ -28,14 +24,14 @@ pub(crate) static XOR_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
             obj
         )));
     }
-    let right_v = args
+    let left_v = args
         .get(0)
         .cloned()
-        .ok_or_else(|| EvalError::NotFound("xor: missing right arg".to_string()))?;
-    let left_v = args
+        .ok_or_else(|| EvalError::NotFound("xor: missing left arg".to_string()))?;
+    let right_v = args
         .get(1)
         .cloned()
-        .ok_or_else(|| EvalError::NotFound("xor: missing left arg".to_string()))?;
+        .ok_or_else(|| EvalError::NotFound("xor: missing right arg".to_string()))?;
 
     match (left_v.clone(), right_v.clone()) {
         (
@@ -88,7 +84,7 @@ mod tests {
         let expr: Expr = MethodCall::new(
             Expr::Global,
             sglobal::XOR_METHOD.clone(),
-            vec![right.into(), left.into()],
+            vec![left.into(), right.into()],
         )
         .unwrap()
         .into();
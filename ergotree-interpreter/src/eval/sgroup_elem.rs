@@ -4,6 +4,8 @@ use ergo_chain_types::EcPoint;
 use ergotree_ir::mir::value::Value;
 use ergotree_ir::serialization::SigmaSerializable;
 
+use super::exponentiate::exponentiate;
+use super::multiply_group::multiply_group;
 use super::EvalFn;
 
 pub(crate) static GET_ENCODED_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
@@ -18,6 +20,22 @@ pub(crate) static GET_ENCODED_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
     Ok(Value::from(encoded))
 };
 
+pub(crate) static EXPONENTIATE_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let exp_v = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("exp: eval is missing first arg".to_string()))?;
+    exponentiate(obj, exp_v)
+};
+
+pub(crate) static MULTIPLY_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let other_v = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("multiply: eval is missing first arg".to_string()))?;
+    multiply_group(obj, other_v)
+};
+
 pub(crate) static NEGATE_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
     let negated: EcPoint = match obj {
         Value::GroupElement(ec_point) => Ok(-(*ec_point)),
@@ -33,9 +51,11 @@ pub(crate) static NEGATE_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
 #[cfg(test)]
 #[cfg(feature = "arbitrary")]
 mod tests {
+    use ergotree_ir::bigint256::BigInt256;
     use ergotree_ir::mir::expr::Expr;
     use ergotree_ir::mir::method_call::MethodCall;
     use ergotree_ir::types::sgroup_elem;
+    use k256::Scalar;
 
     use crate::eval::tests::eval_out_wo_ctx;
     use ergo_chain_types::EcPoint;
@@ -60,6 +80,36 @@ mod tests {
         assert_eq!(input, roundtrip_res)
     }
 
+    #[test]
+    fn eval_exp() {
+        let input = force_any_val::<EcPoint>();
+        let expr: Expr = MethodCall::new(
+            input.clone().into(),
+            sgroup_elem::EXPONENTIATE_METHOD.clone(),
+            vec![BigInt256::from(3i64).into()],
+        )
+        .unwrap()
+        .into();
+        assert_eq!(
+            ergo_chain_types::ec_point::exponentiate(&input, &Scalar::from(3u32)),
+            eval_out_wo_ctx::<EcPoint>(&expr)
+        )
+    }
+
+    #[test]
+    fn eval_multiply() {
+        let left = force_any_val::<EcPoint>();
+        let right = force_any_val::<EcPoint>();
+        let expr: Expr = MethodCall::new(
+            left.clone().into(),
+            sgroup_elem::MULTIPLY_METHOD.clone(),
+            vec![right.clone().into()],
+        )
+        .unwrap()
+        .into();
+        assert_eq!(left * &right, eval_out_wo_ctx::<EcPoint>(&expr))
+    }
+
     #[test]
     fn eval_negate() {
         let input = force_any_val::<EcPoint>();
@@ -3,9 +3,9 @@
 use std::convert::TryInto;
 
 use ergo_chain_types::Header;
-use ergotree_ir::{bigint256::BigInt256, mir::constant::TryExtractInto};
+use ergotree_ir::{bigint256::BigInt256, mir::constant::TryExtractInto, mir::value::Value};
 
-use super::{EvalError, EvalFn};
+use super::{scontext::utxo_state_tree, EvalError, EvalFn};
 
 pub(crate) static VERSION_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
     let header = obj.try_extract_into::<Header>()?;
@@ -29,7 +29,7 @@ pub(crate) static AD_PROOFS_ROOT_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
 
 pub(crate) static STATE_ROOT_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
     let header = obj.try_extract_into::<Header>()?;
-    Ok(Into::<Vec<i8>>::into(header.state_root).into())
+    Ok(Value::AvlTree(Box::new(utxo_state_tree(header.state_root))))
 };
 
 pub(crate) static TRANSACTION_ROOT_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
@@ -102,7 +102,10 @@ mod tests {
     use ergo_chain_types::{BlockId, Digest, Digest32, EcPoint, Votes};
     use ergotree_ir::{
         bigint256::BigInt256,
-        mir::{coll_by_index::ByIndex, expr::Expr, property_call::PropertyCall},
+        mir::{
+            avl_tree_data::AvlTreeData, coll_by_index::ByIndex, expr::Expr,
+            property_call::PropertyCall,
+        },
         types::{scontext, sheader, smethod::SMethod},
     };
     use sigma_test_util::force_any_val;
@@ -232,8 +235,8 @@ mod tests {
         let expr = create_get_header_property_expr(sheader::STATE_ROOT_PROPERTY.clone());
         let ctx = Rc::new(force_any_val::<Context>());
         let expected = ctx.headers[HEADER_INDEX].state_root;
-        let actual = digest_from_bytes_signed::<33>(eval_out::<Vec<i8>>(&expr, ctx));
-        assert_eq!(expected, actual);
+        let actual = eval_out::<AvlTreeData>(&expr, ctx);
+        assert_eq!(expected, actual.digest);
     }
 
     #[test]
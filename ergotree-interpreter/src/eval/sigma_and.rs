@@ -25,13 +25,23 @@ impl Evaluable for SigmaAnd {
 #[allow(clippy::panic)]
 #[cfg(test)]
 mod tests {
+    use ergotree_ir::mir::atleast::Atleast;
+    use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
+    use ergotree_ir::mir::constant::Constant;
+    use ergotree_ir::mir::constant::Literal;
+    use ergotree_ir::mir::value::CollKind;
+    use ergotree_ir::sigma_protocol::sigma_boolean::cthreshold::Cthreshold;
+    use ergotree_ir::sigma_protocol::sigma_boolean::ProveDlog;
     use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
     use ergotree_ir::sigma_protocol::sigma_boolean::SigmaConjecture;
+    use ergotree_ir::types::stype::SType;
     use std::convert::TryInto;
     use std::rc::Rc;
 
     use crate::eval::context::Context;
     use crate::eval::tests::eval_out;
+    use crate::eval::tests::try_eval_out_wo_ctx;
+    use crate::sigma_protocol::private_input::DlogProverInput;
 
     use super::*;
 
@@ -57,4 +67,69 @@ mod tests {
             }
         }
     }
+
+    fn bool_to_sigma(b: bool) -> Expr {
+        BoolToSigmaProp {
+            input: Box::new(Expr::Const(b.into())),
+        }
+        .into()
+    }
+
+    fn pk_const(pk: &ProveDlog) -> Expr {
+        Expr::Const(SigmaProp::from(pk.clone()).into())
+    }
+
+    #[test]
+    fn eval_with_bool_and_threshold() {
+        let pks: Vec<ProveDlog> = (0..3)
+            .map(|_| DlogProverInput::random().public_image())
+            .collect();
+        let eval = |items: Vec<Expr>| {
+            SigmaBoolean::from(
+                try_eval_out_wo_ctx::<SigmaProp>(&SigmaAnd::new(items).unwrap().into()).unwrap(),
+            )
+        };
+        let pk0: SigmaBoolean = pks[0].clone().into();
+        // sigmaProp(true) && pk0
+        assert_eq!(eval(vec![bool_to_sigma(true), pk_const(&pks[0])]), pk0);
+        // sigmaProp(false) && pk0
+        assert_eq!(
+            eval(vec![bool_to_sigma(false), pk_const(&pks[0])]),
+            SigmaBoolean::TrivialProp(false)
+        );
+        // pk0 && atLeast(2, Coll(pk0, pk1, pk2))
+        let at_least: Expr = Atleast::new(
+            2i32.into(),
+            Expr::Const(Constant {
+                tpe: SType::SColl(SType::SSigmaProp.into()),
+                v: Literal::Coll(
+                    CollKind::from_vec(
+                        SType::SSigmaProp,
+                        pks.iter()
+                            .map(|pk| SigmaProp::from(pk.clone()).into())
+                            .collect(),
+                    )
+                    .unwrap(),
+                ),
+            }),
+        )
+        .unwrap()
+        .into();
+        let threshold = Cthreshold::reduce(
+            2,
+            pks.iter()
+                .map(|pk| pk.clone().into())
+                .collect::<Vec<SigmaBoolean>>()
+                .try_into()
+                .unwrap(),
+        );
+        assert!(matches!(
+            threshold,
+            SigmaBoolean::SigmaConjecture(SigmaConjecture::Cthreshold(_))
+        ));
+        assert_eq!(
+            eval(vec![pk_const(&pks[0]), bool_to_sigma(true), at_least]),
+            Cand::normalized(vec![pk0, threshold].try_into().unwrap())
+        );
+    }
 }
@@ -1,11 +1,39 @@
+use crate::eval::apply::apply_lambda;
 use crate::eval::EvalError;
-use crate::eval::Evaluable;
 
 use ergotree_ir::mir::value::Value;
 
 use super::EvalFn;
 
-pub(crate) static MAP_EVAL_FN: EvalFn = |env, ctx, obj, args| {
+fn extract_opt(obj: Value, method_name: &str) -> Result<Option<Value>, EvalError> {
+    match obj {
+        Value::Opt(opt) => Ok(*opt),
+        _ => Err(EvalError::UnexpectedValue(format!(
+            "expected {0} input to be Value::Opt, got: {1:?}",
+            method_name, obj
+        ))),
+    }
+}
+
+pub(crate) static IS_DEFINED_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, _args| Ok(Value::Boolean(extract_opt(obj, "isDefined")?.is_some()));
+
+pub(crate) static GET_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    extract_opt(obj, "get")?
+        .ok_or_else(|| EvalError::NotFound("calling Option.get on None".to_string()))
+};
+
+/// The default value is evaluated (as any other method argument) before the call regardless of
+/// the option being defined, the same as `OptionGetOrElse` and the reference implementation
+pub(crate) static GET_OR_ELSE_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let default_v = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("getOrElse: eval is missing first arg".to_string()))?;
+    Ok(extract_opt(obj, "getOrElse")?.unwrap_or(default_v))
+};
+
+pub(crate) static MAP_EVAL_FN: EvalFn = |_env, ctx, obj, args| {
     let input_v = obj;
     let lambda_v = args
         .get(0)
@@ -19,20 +47,7 @@ pub(crate) static MAP_EVAL_FN: EvalFn = |env, ctx, obj, args| {
             input_v_clone
         ))),
     }?;
-    let mut lambda_call = |arg: Value| {
-        let func_arg = lambda.args.first().ok_or_else(|| {
-            EvalError::NotFound("map: lambda has empty arguments list".to_string())
-        })?;
-        let orig_val = env.get(func_arg.idx).cloned();
-        env.insert(func_arg.idx, arg);
-        let res = lambda.body.eval(env, ctx);
-        if let Some(orig_val) = orig_val {
-            env.insert(func_arg.idx, orig_val);
-        } else {
-            env.remove(&func_arg.idx);
-        }
-        res
-    };
+    let mut lambda_call = |arg: Value| apply_lambda(ctx, lambda, vec![arg]);
     let normalized_input_val: Option<Value> = match input_v {
         Value::Opt(opt) => Ok(*opt),
         _ => Err(EvalError::UnexpectedValue(format!(
@@ -47,7 +62,7 @@ pub(crate) static MAP_EVAL_FN: EvalFn = |env, ctx, obj, args| {
     }
 };
 
-pub(crate) static FILTER_EVAL_FN: EvalFn = |env, ctx, obj, args| {
+pub(crate) static FILTER_EVAL_FN: EvalFn = |_env, ctx, obj, args| {
     let input_v = obj;
     let lambda_v = args
         .get(0)
@@ -61,20 +76,7 @@ pub(crate) static FILTER_EVAL_FN: EvalFn = |env, ctx, obj, args| {
             input_v_clone
         ))),
     }?;
-    let mut predicate_call = |arg: Value| {
-        let func_arg = lambda.args.first().ok_or_else(|| {
-            EvalError::NotFound("filter: lambda has empty arguments list".to_string())
-        })?;
-        let orig_val = env.get(func_arg.idx).cloned();
-        env.insert(func_arg.idx, arg);
-        let res = lambda.body.eval(env, ctx);
-        if let Some(orig_val) = orig_val {
-            env.insert(func_arg.idx, orig_val);
-        } else {
-            env.remove(&func_arg.idx);
-        }
-        res
-    };
+    let mut predicate_call = |arg: Value| apply_lambda(ctx, lambda, vec![arg]);
     let normalized_input_val: Option<Value> = match input_v {
         Value::Opt(opt) => Ok(*opt),
         _ => Err(EvalError::UnexpectedValue(format!(
@@ -114,9 +116,58 @@ mod tests {
     use ergotree_ir::types::stype::SType;
     use ergotree_ir::types::stype_param::STypeVar;
 
+    use crate::eval::apply::tests::closure_over;
     use crate::eval::tests::eval_out_wo_ctx;
+    use crate::eval::tests::try_eval_out_wo_ctx;
     use ergotree_ir::mir::value::Value;
 
+    #[test]
+    fn eval_is_defined() {
+        let opt_const: Constant = Some(1i64).into();
+        let expr: Expr = MethodCall::new(
+            opt_const.into(),
+            soption::IS_DEFINED_METHOD
+                .clone()
+                .with_concrete_types(&[(STypeVar::t(), SType::SLong)].iter().cloned().collect()),
+            vec![],
+        )
+        .unwrap()
+        .into();
+        assert!(eval_out_wo_ctx::<bool>(&expr));
+    }
+
+    #[test]
+    fn eval_get_none() {
+        let typed_none: Option<i64> = None;
+        let opt_const: Constant = typed_none.into();
+        let expr: Expr = MethodCall::new(
+            opt_const.into(),
+            soption::GET_METHOD
+                .clone()
+                .with_concrete_types(&[(STypeVar::t(), SType::SLong)].iter().cloned().collect()),
+            vec![],
+        )
+        .unwrap()
+        .into();
+        assert!(try_eval_out_wo_ctx::<i64>(&expr).is_err());
+    }
+
+    #[test]
+    fn eval_get_or_else() {
+        let typed_none: Option<i64> = None;
+        let opt_const: Constant = typed_none.into();
+        let expr: Expr = MethodCall::new(
+            opt_const.into(),
+            soption::GET_OR_ELSE_METHOD
+                .clone()
+                .with_concrete_types(&[(STypeVar::t(), SType::SLong)].iter().cloned().collect()),
+            vec![2i64.into()],
+        )
+        .unwrap()
+        .into();
+        assert_eq!(eval_out_wo_ctx::<i64>(&expr), 2i64);
+    }
+
     #[test]
     fn eval_map_some() {
         let opt_const: Constant = Some(1i64).into();
@@ -164,6 +215,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn eval_map_closure_capture() {
+        let opt_const: Constant = Some(1i32).into();
+        let mapper = closure_over(10, SType::SInt, |x, y| {
+            BinOp {
+                kind: ArithOp::Plus.into(),
+                left: Box::new(x),
+                right: Box::new(y),
+            }
+            .into()
+        });
+        let expr: Expr = MethodCall::new(
+            opt_const.into(),
+            soption::MAP_METHOD.clone().with_concrete_types(
+                &[(STypeVar::iv(), SType::SInt), (STypeVar::ov(), SType::SInt)]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            vec![mapper],
+        )
+        .unwrap()
+        .into();
+        assert_eq!(eval_out_wo_ctx::<Option<i32>>(&expr), Some(11));
+    }
+
     #[test]
     fn eval_map_none() {
         let typed_none: Option<i64> = None;
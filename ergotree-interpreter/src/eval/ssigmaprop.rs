@@ -0,0 +1,41 @@
+use crate::eval::EvalError;
+
+use ergotree_ir::mir::value::Value;
+
+use super::EvalFn;
+
+pub(crate) static PROP_BYTES_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| match obj {
+    Value::SigmaProp(sigma_prop) => Ok(sigma_prop.prop_bytes()?.into()),
+    _ => Err(EvalError::UnexpectedValue(format!(
+        "expected obj to be Value::SigmaProp, got: {0:?}",
+        obj
+    ))),
+};
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+mod tests {
+    use ergotree_ir::mir::constant::Constant;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::method_call::MethodCall;
+    use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProp;
+    use ergotree_ir::types::ssigmaprop;
+    use sigma_test_util::force_any_val;
+
+    use crate::eval::tests::eval_out_wo_ctx;
+
+    #[test]
+    fn eval_prop_bytes() {
+        let input = force_any_val::<SigmaProp>();
+        let expected_bytes = input.prop_bytes().unwrap();
+        let expr: Expr = MethodCall::new(
+            Constant::from(input).into(),
+            ssigmaprop::PROP_BYTES_METHOD.clone(),
+            vec![],
+        )
+        .unwrap()
+        .into();
+        assert_eq!(eval_out_wo_ctx::<Vec<u8>>(&expr), expected_bytes);
+    }
+}
@@ -1,87 +1,23 @@
-use ergotree_ir::bigint256::BigInt256;
 use ergotree_ir::mir::upcast::Upcast;
 use ergotree_ir::mir::value::Value;
-use ergotree_ir::types::stype::SType;
 
+use crate::eval::downcast::cast_numeric;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
 use crate::eval::Evaluable;
 
-fn upcast_to_bigint(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Byte(v) => Ok(BigInt256::from(v).into()),
-        Value::Short(v) => Ok(BigInt256::from(v).into()),
-        Value::Int(v) => Ok(BigInt256::from(v).into()),
-        Value::Long(v) => Ok(BigInt256::from(v).into()),
-        Value::BigInt(_) => Ok(in_v),
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Upcast: cannot upcast {0:?} to BigInt",
-            in_v
-        ))),
-    }
-}
-
-fn upcast_to_long(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Byte(v) => Ok((v as i64).into()),
-        Value::Short(v) => Ok((v as i64).into()),
-        Value::Int(v) => Ok((v as i64).into()),
-        Value::Long(_) => Ok(in_v),
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Upcast: cannot upcast {0:?} to Long",
-            in_v
-        ))),
-    }
-}
-
-fn upcast_to_int(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Byte(v) => Ok((v as i32).into()),
-        Value::Short(v) => Ok((v as i32).into()),
-        Value::Int(_) => Ok(in_v),
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Upcast: cannot upcast {0:?} to Int",
-            in_v
-        ))),
-    }
-}
-
-fn upcast_to_short(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Byte(v) => Ok((v as i16).into()),
-        Value::Short(_) => Ok(in_v),
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Upcast: cannot upcast {0:?} to Short",
-            in_v
-        ))),
-    }
-}
-
-fn upcast_to_byte(in_v: Value) -> Result<Value, EvalError> {
-    match in_v {
-        Value::Byte(_) => Ok(in_v),
-        _ => Err(EvalError::UnexpectedValue(format!(
-            "Upcast: cannot upcast {0:?} to Byte",
-            in_v
-        ))),
-    }
-}
-
 impl Evaluable for Upcast {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let input_v = self.input.eval(env, ctx)?;
-        match self.tpe {
-            SType::SBigInt => upcast_to_bigint(input_v),
-            SType::SLong => upcast_to_long(input_v),
-            SType::SInt => upcast_to_int(input_v),
-            SType::SShort => upcast_to_short(input_v),
-            SType::SByte => upcast_to_byte(input_v),
-            _ => Err(EvalError::UnexpectedValue(format!(
-                "Upcast: expected numeric value, got {0:?}",
-                input_v
-            ))),
+        let input_tpe = self.input.post_eval_tpe();
+        if !input_tpe.can_upcast_to(&self.tpe) {
+            return Err(EvalError::UnexpectedValue(format!(
+                "Upcast: cannot upcast {0:?} to {1}",
+                input_v, self.tpe
+            )));
         }
+        cast_numeric("Upcast", input_v, &self.tpe)
     }
 }
 
@@ -89,9 +25,12 @@ impl Evaluable for Upcast {
 #[allow(clippy::unwrap_used)]
 #[cfg(test)]
 mod tests {
+    use ergotree_ir::bigint256::BigInt256;
     use ergotree_ir::mir::constant::Constant;
+    use ergotree_ir::types::stype::SType;
 
     use crate::eval::tests::eval_out_wo_ctx;
+    use crate::eval::tests::try_eval_out_wo_ctx;
 
     use super::*;
     use proptest::prelude::*;
@@ -172,6 +111,12 @@ mod tests {
             );
         }
 
+        #[test]
+        fn to_narrower_type(v in any::<i64>()) {
+            let c: Constant = v.into();
+            assert!(try_eval_out_wo_ctx::<i32>(&Upcast::new(c.into(), SType::SInt).unwrap().into()).is_err());
+        }
+
         #[test]
         fn from_bigint(v in any::<i64>()) {
             let v: BigInt256 = v.into();
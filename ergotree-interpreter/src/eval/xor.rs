@@ -8,7 +8,9 @@ use crate::eval::EvalContext;
 use crate::eval::EvalError;
 use crate::eval::Evaluable;
 
-fn helper_xor(mut x: Vec<i8>, y: Vec<i8>) -> Vec<i8> {
+/// Byte-wise XOR, the result has the length of the shorter array (as in sigmastate)
+pub(crate) fn helper_xor(mut x: Vec<i8>, y: Vec<i8>) -> Vec<i8> {
+    x.truncate(y.len());
     x.iter_mut().zip(y.iter()).for_each(|(x1, x2)| *x1 ^= *x2);
     x
 }
@@ -126,6 +128,22 @@ mod tests {
         assert_eq!(eval_out::<Vec<i8>>(&expr, ctx), expected_xor);
     }
 
+    #[test]
+    fn eval_different_lengths() {
+        let left = vec![1_i8, 2, 3];
+        let right = vec![1_i8, 3];
+        let expected_xor = vec![0_i8, 1];
+
+        let expr: Expr = Xor {
+            left: Box::new(Expr::Const(left.into())),
+            right: Box::new(Expr::Const(right.into())),
+        }
+        .into();
+
+        let ctx = Rc::new(force_any_val::<Context>());
+        assert_eq!(eval_out::<Vec<i8>>(&expr, ctx), expected_xor);
+    }
+
     proptest! {
 
         #[test]
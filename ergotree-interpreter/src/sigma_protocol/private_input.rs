@@ -15,6 +15,7 @@ use derive_more::From;
 use k256::elliptic_curve::PrimeField;
 use num_bigint::BigUint;
 use num_traits::ToPrimitive;
+use sigma_util::encoding::decode_base16_exact;
 
 use super::crypto_utils;
 use super::wscalar::Wscalar;
@@ -57,10 +58,9 @@ impl DlogProverInput {
     /// Attempts to parse the given Base16-encoded byte array as an SEC-1-encoded scalar(secret key).
     /// Returns None if the byte array does not contain a big-endian integer in the range [0, modulus).
     pub fn from_base16_str(str: String) -> Option<DlogProverInput> {
-        base16::decode(&str)
+        decode_base16_exact::<{ DlogProverInput::SIZE_BYTES }>(&str)
             .ok()
-            .and_then(|bytes| bytes.as_slice().try_into().ok().map(Self::from_bytes))
-            .flatten()
+            .and_then(|bytes| Self::from_bytes(&bytes))
     }
 
     /// Attempts to create DlogProverInput from BigUint
@@ -6,6 +6,7 @@ mod prover_result;
 pub mod hint;
 
 use crate::eval::reduce_to_crypto;
+use crate::eval::reduce_trivial_tree;
 use crate::eval::ReductionDiagnosticInfo;
 use crate::sigma_protocol::crypto_utils::secure_random_bytes;
 use crate::sigma_protocol::fiat_shamir::fiat_shamir_hash_fn;
@@ -146,13 +147,37 @@ pub trait Prover {
         message: &[u8],
         hints_bag: &HintsBag,
     ) -> Result<ProverResult, ProverError> {
-        let expr = tree.proposition()?;
+        self.prove_with_cost(tree, env, ctx, message, hints_bag)
+            .map(|(res, _)| res)
+    }
+
+    /// Same as [`Prover::prove`], also returns the cost of the tree reduction and of the proof
+    /// verification (see [`crate::eval::ReductionResult::cost`])
+    fn prove_with_cost(
+        &self,
+        tree: &ErgoTree,
+        env: &Env,
+        ctx: Rc<Context>,
+        message: &[u8],
+        hints_bag: &HintsBag,
+    ) -> Result<(ProverResult, u64), ProverError> {
         let ctx_ext = ctx.extension.clone();
-        let reduction_result = reduce_to_crypto(&expr, env, ctx).map_err(ProverError::EvalError)?;
+        let reduction_result = match reduce_trivial_tree(tree, env) {
+            Some(r) => r,
+            None => {
+                reduce_to_crypto(&tree.proposition()?, env, ctx).map_err(ProverError::EvalError)?
+            }
+        };
+        let cost = reduction_result.cost;
         self.generate_proof(reduction_result.sigma_prop, message, hints_bag)
-            .map(|p| ProverResult {
-                proof: p,
-                extension: ctx_ext,
+            .map(|p| {
+                (
+                    ProverResult {
+                        proof: p,
+                        extension: ctx_ext,
+                    },
+                    cost,
+                )
             })
             .map_err(|e| match e {
                 ProverError::ReducedToFalse => {
@@ -1286,6 +1311,39 @@ mod tests {
         assert_ne!(res.unwrap().proof, ProofBytes::Empty);
     }
 
+    #[test]
+    fn test_prove_with_cost() {
+        let secret1 = DlogProverInput::random();
+        let secret2 = DlogProverInput::random();
+        let pk1 = secret1.public_image();
+        let pk2 = secret2.public_image();
+        let prover = TestProver {
+            secrets: vec![secret1.into(), secret2.into()],
+        };
+        let message = vec![0u8; 100];
+        let cost_of = |tree: &ErgoTree| {
+            prover
+                .prove_with_cost(
+                    tree,
+                    &Env::empty(),
+                    Rc::new(force_any_val::<Context>()),
+                    message.as_slice(),
+                    &HintsBag::empty(),
+                )
+                .unwrap()
+                .1
+        };
+        // interpreter setup, root constant and the ProveDlog verification
+        let p2pk = ErgoTree::try_from(Expr::Const(pk1.clone().into())).unwrap();
+        assert_eq!(cost_of(&p2pk), 1 + 1 + 10);
+        // interpreter setup, SigmaAnd with two constants, CAND node with two children and two ProveDlog verifications
+        let expr: Expr = SigmaAnd::new(vec![Expr::Const(pk1.into()), Expr::Const(pk2.into())])
+            .unwrap()
+            .into();
+        let tree: ErgoTree = expr.try_into().unwrap();
+        assert_eq!(cost_of(&tree), 1 + 3 + (1 + 2) + 2 * 10);
+    }
+
     #[test]
     fn test_prove_pk_and_pk() {
         let secret1 = DlogProverInput::random();
@@ -6,12 +6,20 @@ use ergotree_ir::serialization::SigmaParsingError;
 use ergotree_ir::serialization::SigmaSerializable;
 use ergotree_ir::serialization::SigmaSerializeResult;
 use indexmap::IndexMap;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
 use std::convert::TryFrom;
 use std::fmt;
 use thiserror::Error;
 
-/// User-defined variables to be put into context
+/// User-defined variables to be put into context.
+/// JSON representation is a map of the variable id to the serialized constant (base16).
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Deserialize),
+    serde(try_from = "IndexMap<String, String>")
+)]
 pub struct ContextExtension {
     /// key-value pairs of variable id and it's value
     pub values: IndexMap<u8, Constant>,
@@ -54,12 +62,31 @@ impl SigmaSerializable for ContextExtension {
     }
 }
 
+#[cfg(feature = "json")]
+impl serde::Serialize for ContextExtension {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(self.values.len()))?;
+        for (k, v) in &self.values {
+            map.serialize_entry(
+                &format!("{}", k),
+                &base16::encode_lower(&v.sigma_serialize_bytes().map_err(Error::custom)?),
+            )?;
+        }
+        map.end()
+    }
+}
+
 /// Error parsing Constant from base16-encoded string
 #[derive(Error, Eq, PartialEq, Debug, Clone)]
 #[error("Error parsing constant: {0}")]
 pub struct ConstantParsingError(pub String);
 
-// for JSON encoding in ergo-lib
+// for JSON encoding
 impl TryFrom<IndexMap<String, String>> for ContextExtension {
     type Error = ConstantParsingError;
     fn try_from(values_str: IndexMap<String, String>) -> Result<Self, Self::Error> {
@@ -69,7 +96,7 @@ impl TryFrom<IndexMap<String, String>> for ContextExtension {
                 let idx: u8 = pair.0.parse().map_err(|_| {
                     ConstantParsingError(format!("cannot parse index from {0:?}", pair.0))
                 })?;
-                let constant_bytes = base16::decode(pair.1).map_err(|_| {
+                let constant_bytes = decode_base16(pair.1, DecodingMode::Strict).map_err(|_| {
                     ConstantParsingError(format!(
                         "cannot decode base16 constant bytes from {0:?}",
                         pair.1
@@ -117,17 +144,60 @@ mod arbitrary {
 
 #[cfg(test)]
 #[cfg(feature = "arbitrary")]
-#[allow(clippy::panic)]
+#[allow(clippy::panic, clippy::unwrap_used)]
 mod tests {
     use super::*;
     use ergotree_ir::serialization::sigma_serialize_roundtrip;
     use proptest::prelude::*;
 
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_node_format() {
+        // spending proof extension of the transaction
+        // c8520befd345ff40fcf244b44ffe8cea29c8b116b174cfaf4f2a521604d531a4 in the node JSON
+        let node_json = r#"{
+          "0": "04c60f",
+          "5": "0514",
+          "10": "0eee03101808cd0279aed8dea2b2a25316d5d49d13bf51c0b2c1dc696974bb4b0c07b5894e998e56040005e0e0a447040404060402040004000e201d5afc59838920bb5ef2a8f9d63825a55b1d48e269d7cecee335d637c3ff5f3f0e20003bd19d0187117f130b62e1bcab0939929ff5c7709f843c5c4dd158949285d005e201058c85a2010514040404c60f06010104d00f05e0e0a44704c60f0e691005040004000e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300000193c2b2a57301007473027303830108cdeeac93b1a57304050005000580ade2040100d803d6017300d602b2a4730100d6037302eb027201d195ed93b1a4730393b1db630872027304d804d604db63087202d605b2a5730500d606b2db63087205730600d6077e8c72060206edededededed938cb2720473070001730893c27205d07201938c72060173099272077e730a06927ec172050699997ec1a7069d9c72077e730b067e730c067e720306909c9c7e8cb27204730d0002067e7203067e730e069c9a7207730f9a9c7ec17202067e7310067e9c73117e7312050690b0ada5d90108639593c272087313c1720873147315d90108599a8c7208018c72080273167317",
+          "1": "0e20003bd19d0187117f130b62e1bcab0939929ff5c7709f843c5c4dd158949285d0",
+          "6": "0580ade204",
+          "9": "0580b48913",
+          "2": "05e201",
+          "7": "0e201d5afc59838920bb5ef2a8f9d63825a55b1d48e269d7cecee335d637c3ff5f3f",
+          "3": "05e0e0a447",
+          "8": "0580ade204",
+          "4": "058c85a201"
+        }"#;
+        let ext: ContextExtension = serde_json::from_str(node_json).unwrap();
+        assert_eq!(
+            ext.values.keys().copied().collect::<Vec<u8>>(),
+            vec![0, 5, 10, 1, 6, 9, 2, 7, 3, 8, 4]
+        );
+        assert_eq!(ext.values[&0], Constant::from(995i32));
+        assert_eq!(ext.values[&5], Constant::from(10i64));
+        assert_eq!(ext.values[&6], Constant::from(5_000_000i64));
+        assert_eq!(ext.values[&2], Constant::from(113i64));
+        assert_eq!(
+            serde_json::to_value(&ext).unwrap(),
+            serde_json::from_str::<serde_json::Value>(node_json).unwrap()
+        );
+        // variable ids are JSON object keys, values are base16-encoded serialized constants
+        let json = serde_json::to_string(&ext).unwrap();
+        assert!(json.starts_with(r#"{"0":"04c60f","5":"0514","10":"0eee03"#));
+    }
+
     proptest! {
 
         #[test]
         fn ser_roundtrip(v in any::<ContextExtension>()) {
             prop_assert_eq![sigma_serialize_roundtrip(&v), v];
         }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn json_roundtrip(v in any::<ContextExtension>()) {
+            let json = serde_json::to_string(&v).unwrap();
+            prop_assert_eq![serde_json::from_str::<ContextExtension>(&json).unwrap(), v];
+        }
     }
 }
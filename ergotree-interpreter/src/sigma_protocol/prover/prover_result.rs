@@ -4,6 +4,10 @@ use ergotree_ir::serialization::sigma_byte_writer::SigmaByteWrite;
 use ergotree_ir::serialization::SigmaParsingError;
 use ergotree_ir::serialization::SigmaSerializable;
 use ergotree_ir::serialization::SigmaSerializeResult;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::encode_base16;
+use sigma_util::encoding::DecodingError;
+use sigma_util::encoding::DecodingMode;
 
 use super::ContextExtension;
 use std::convert::TryFrom;
@@ -51,17 +55,17 @@ impl From<ProofBytes> for String {
     fn from(v: ProofBytes) -> Self {
         match v {
             ProofBytes::Empty => "".to_string(),
-            ProofBytes::Some(bytes) => base16::encode_lower(&bytes),
+            ProofBytes::Some(bytes) => encode_base16(&bytes),
         }
     }
 }
 
 // for JSON encoding in ergo-lib
 impl TryFrom<String> for ProofBytes {
-    type Error = base16::DecodeError;
+    type Error = DecodingError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        base16::decode(&value).map(|bytes| bytes.into())
+        decode_base16(&value, DecodingMode::Strict).map(|bytes| bytes.into())
     }
 }
 
@@ -1,13 +1,25 @@
 //! Verifier
 
+use std::borrow::Borrow;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
 
+use super::challenge::Challenge;
 use super::dht_protocol;
 use super::dht_protocol::FirstDhTupleProverMessage;
+use super::dlog_protocol::SecondDlogProverMessage;
+use super::fiat_shamir::FiatShamirHash;
 use super::fiat_shamir::FiatShamirTreeSerializationError;
 use super::prover::ProofBytes;
 use super::sig_serializer::SigParsingError;
 use super::unchecked_tree::UncheckedDhTuple;
+use super::wscalar::Wscalar;
+use super::GroupSizedBytes;
+use super::ProverMessage;
+use super::GROUP_SIZE;
+use super::SOUNDNESS_BYTES;
 use super::{
     dlog_protocol,
     fiat_shamir::{fiat_shamir_hash_fn, fiat_shamir_tree_to_bytes},
@@ -16,14 +28,28 @@ use super::{
     SigmaBoolean, UncheckedTree,
 };
 use crate::eval::context::Context;
+use crate::eval::cost_accum::CostError;
 use crate::eval::env::Env;
 use crate::eval::EvalError;
-use crate::eval::{reduce_to_crypto, ReductionDiagnosticInfo};
+use crate::eval::{
+    reduce_to_crypto, reduce_to_crypto_with_cost_limit, reduce_trivial_tree,
+    ReductionDiagnosticInfo,
+};
 use dlog_protocol::FirstDlogProverMessage;
 use ergotree_ir::ergo_tree::ErgoTree;
 use ergotree_ir::ergo_tree::ErgoTreeError;
+use ergotree_ir::ergo_tree::ErgoTreeHeader;
+use ergotree_ir::ergo_tree::SharedErgoTreeCache;
+use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::sigma_protocol::sigma_boolean::ProveDlog;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProofOfKnowledgeTree;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProp;
+use sigma_ser::ScorexSerializable;
 
 use derive_more::From;
+use lazy_static::lazy_static;
+use sigma_util::lru::LruCache;
 use thiserror::Error;
 
 /// Errors on proof verification
@@ -53,6 +79,60 @@ pub struct VerificationResult {
     pub diag: ReductionDiagnosticInfo,
 }
 
+/// Reason of the failed verification (see [`VerificationReport`])
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum VerificationFailureReason {
+    /// Script was reduced to `false`
+    ScriptFalse,
+    /// Proof is missing, malformed or does not satisfy the reduced SigmaProp
+    InvalidProof,
+    /// Cost limit exceeded during the script evaluation
+    CostExceeded,
+    /// Failed to parse the script (or a value used in the script)
+    DeserializationError(String),
+    /// Script evaluation failed
+    EvaluationError(String),
+}
+
+/// Structured result of the verification (see [`Verifier::verify_with_report`])
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct VerificationReport {
+    /// `true` if the proof satisfies the reduced script
+    pub verified: bool,
+    /// estimated cost of contract execution (0 if the script evaluation failed, the cost
+    /// accumulated so far if the cost limit is exceeded)
+    pub cost: u64,
+    /// SigmaBoolean the script was reduced to, `None` if the script evaluation failed
+    pub reduced: Option<SigmaBoolean>,
+    /// `None` if verified
+    pub failure_reason: Option<VerificationFailureReason>,
+}
+
+impl VerificationReport {
+    fn failed(cost: u64, reduced: Option<SigmaBoolean>, reason: VerificationFailureReason) -> Self {
+        VerificationReport {
+            verified: false,
+            cost,
+            reduced,
+            failure_reason: Some(reason),
+        }
+    }
+}
+
+impl From<&EvalError> for VerificationFailureReason {
+    fn from(e: &EvalError) -> Self {
+        match e.root_cause() {
+            EvalError::CostError(_) => VerificationFailureReason::CostExceeded,
+            e @ (EvalError::ErgoTreeError(_)
+            | EvalError::SigmaParsingError(_)
+            | EvalError::ScorexParsingError(_)) => {
+                VerificationFailureReason::DeserializationError(e.to_string())
+            }
+            e => VerificationFailureReason::EvaluationError(e.to_string()),
+        }
+    }
+}
+
 /// Verifier for the proofs generater by [`super::prover::Prover`]
 pub trait Verifier {
     /// Executes the script in a given context.
@@ -67,28 +147,177 @@ pub trait Verifier {
         proof: ProofBytes,
         message: &[u8],
     ) -> Result<VerificationResult, VerifierError> {
-        let expr = tree.proposition()?;
-        let reduction_result = reduce_to_crypto(&expr, env, ctx)?;
+        let reduction_result = match reduce_trivial_tree(tree, env) {
+            Some(r) => r,
+            None => reduce_to_crypto(&tree.proposition()?, env, ctx)?,
+        };
         let res: bool = match reduction_result.sigma_prop {
             SigmaBoolean::TrivialProp(b) => b,
-            sb => {
-                match proof {
-                    ProofBytes::Empty => false,
-                    ProofBytes::Some(proof_bytes) => {
-                        // Perform Verifier Steps 1-3
-                        let unchecked_tree = parse_sig_compute_challenges(&sb, proof_bytes)?;
-                        // Perform Verifier Steps 4-6
-                        check_commitments(unchecked_tree, message)?
-                    }
-                }
-            }
+            sb => match proof {
+                ProofBytes::Empty => false,
+                ProofBytes::Some(proof_bytes) => check_proof(&sb, proof_bytes, message)?,
+            },
         };
         Ok(VerificationResult {
             result: res,
-            cost: 0,
+            cost: reduction_result.cost,
             diag: reduction_result.diag,
         })
     }
+
+    /// Same as [`Verifier::verify`], but instead of failing with an error returns a report with
+    /// the reason of the rejection, the reduced SigmaBoolean and the cost.
+    fn verify_with_report(
+        &self,
+        tree: &ErgoTree,
+        env: &Env,
+        ctx: Rc<Context>,
+        proof: ProofBytes,
+        message: &[u8],
+    ) -> VerificationReport {
+        verify_with_report_impl(tree, env, ctx, proof, message, None, ErgoTree::proposition)
+    }
+
+    /// Same as [`Verifier::verify_with_report`] for the serialized tree, but the parsed tree and
+    /// its proposition are taken from the cache (see [`SharedErgoTreeCache`]), so a repeated
+    /// script is neither re-parsed nor re-serialized
+    fn verify_with_report_cached(
+        &self,
+        tree_bytes: &[u8],
+        env: &Env,
+        ctx: Rc<Context>,
+        proof: ProofBytes,
+        message: &[u8],
+        cache: &SharedErgoTreeCache,
+    ) -> VerificationReport {
+        let tree = match cache.get_or_parse(tree_bytes) {
+            Ok(tree) => tree,
+            Err(e) => {
+                return VerificationReport::failed(
+                    0,
+                    None,
+                    VerificationFailureReason::DeserializationError(e.to_string()),
+                )
+            }
+        };
+        verify_with_report_impl(&tree, env, ctx, proof, message, None, |_| {
+            cache.proposition(tree_bytes)
+        })
+    }
+
+    /// Same as [`Verifier::verify_with_report`], but the script evaluation is stopped as soon as
+    /// its cost exceeds `cost_limit` (rejected with [`VerificationFailureReason::CostExceeded`])
+    fn verify_with_report_limited(
+        &self,
+        tree: &ErgoTree,
+        env: &Env,
+        ctx: Rc<Context>,
+        proof: ProofBytes,
+        message: &[u8],
+        cost_limit: u64,
+    ) -> VerificationReport {
+        verify_with_report_impl(
+            tree,
+            env,
+            ctx,
+            proof,
+            message,
+            Some(cost_limit),
+            ErgoTree::proposition,
+        )
+    }
+
+    /// Same as [`Verifier::verify_with_report`], but the operand types of the tree proposition are
+    /// checked before the evaluation (see [`ErgoTree::type_checked_proposition`]), a malformed
+    /// tree is rejected with [`VerificationFailureReason::DeserializationError`]
+    fn verify_with_report_type_checked(
+        &self,
+        tree: &ErgoTree,
+        env: &Env,
+        ctx: Rc<Context>,
+        proof: ProofBytes,
+        message: &[u8],
+    ) -> VerificationReport {
+        verify_with_report_impl(
+            tree,
+            env,
+            ctx,
+            proof,
+            message,
+            None,
+            ErgoTree::type_checked_proposition,
+        )
+    }
+}
+
+fn verify_with_report_impl<E, F>(
+    tree: &ErgoTree,
+    env: &Env,
+    ctx: Rc<Context>,
+    proof: ProofBytes,
+    message: &[u8],
+    cost_limit: Option<u64>,
+    proposition: F,
+) -> VerificationReport
+where
+    E: Borrow<Expr>,
+    F: FnOnce(&ErgoTree) -> Result<E, ErgoTreeError>,
+{
+    let reduction_result = match reduce_trivial_tree(tree, env) {
+        Some(r) => match cost_limit {
+            Some(limit) if r.cost > limit => {
+                return VerificationReport::failed(
+                    r.cost,
+                    None,
+                    VerificationFailureReason::CostExceeded,
+                )
+            }
+            _ => r,
+        },
+        None => {
+            let expr = match proposition(tree) {
+                Ok(expr) => expr,
+                Err(e) => {
+                    return VerificationReport::failed(
+                        0,
+                        None,
+                        VerificationFailureReason::DeserializationError(e.to_string()),
+                    )
+                }
+            };
+            match reduce_to_crypto_with_cost_limit(expr.borrow(), env, ctx, cost_limit) {
+                Ok(r) => r,
+                Err(e) => {
+                    let cost = match e.root_cause() {
+                        EvalError::CostError(CostError::LimitExceeded { cost, .. }) => *cost,
+                        _ => 0,
+                    };
+                    return VerificationReport::failed(cost, None, (&e).into());
+                }
+            }
+        }
+    };
+    let cost = reduction_result.cost;
+    let verified = match &reduction_result.sigma_prop {
+        SigmaBoolean::TrivialProp(false) => {
+            return VerificationReport::failed(
+                cost,
+                Some(reduction_result.sigma_prop),
+                VerificationFailureReason::ScriptFalse,
+            )
+        }
+        SigmaBoolean::TrivialProp(true) => true,
+        sb => match proof {
+            ProofBytes::Empty => false,
+            ProofBytes::Some(proof_bytes) => check_proof(sb, proof_bytes, message).unwrap_or(false),
+        },
+    };
+    VerificationReport {
+        verified,
+        cost,
+        reduced: Some(reduction_result.sigma_prop),
+        failure_reason: (!verified).then_some(VerificationFailureReason::InvalidProof),
+    }
 }
 
 /// Verify that the signature is presented to satisfy SigmaProp conditions.
@@ -99,21 +328,140 @@ pub fn verify_signature(
 ) -> Result<bool, VerifierError> {
     let res: bool = match sigma_tree {
         SigmaBoolean::TrivialProp(b) => b,
-        sb => {
-            match signature {
-                [] => false,
-                _ => {
-                    // Perform Verifier Steps 1-3
-                    let unchecked_tree = parse_sig_compute_challenges(&sb, signature.to_vec())?;
-                    // Perform Verifier Steps 4-6
-                    check_commitments(unchecked_tree, message)?
-                }
-            }
-        }
+        sb => match signature {
+            [] => false,
+            _ => check_proof(&sb, signature.to_vec(), message)?,
+        },
     };
     Ok(res)
 }
 
+/// Verify the proof for the non-trivial SigmaBoolean, P2PK propositions are verified via
+/// the cached [`PrecomputedProveDlog`] without building the proof tree
+fn check_proof(
+    sb: &SigmaBoolean,
+    proof_bytes: Vec<u8>,
+    message: &[u8],
+) -> Result<bool, VerifierError> {
+    match sb {
+        SigmaBoolean::ProofOfKnowledge(SigmaProofOfKnowledgeTree::ProveDlog(pk)) => {
+            PrecomputedProveDlog::cached(pk).verify(message, &proof_bytes)
+        }
+        _ => {
+            // Perform Verifier Steps 1-3
+            let unchecked_tree = parse_sig_compute_challenges(sb, proof_bytes)?;
+            // Perform Verifier Steps 4-6
+            check_commitments(unchecked_tree, message)
+        }
+    }
+}
+
+/// Number of the public keys kept in the [`PrecomputedProveDlog::cached`] cache
+pub const PRECOMPUTED_PROVE_DLOG_CACHE_CAPACITY: usize = 4096;
+
+lazy_static! {
+    static ref PRECOMPUTED_PROVE_DLOG_CACHE: Mutex<LruCache<Vec<u8>, Arc<PrecomputedProveDlog>>> =
+        Mutex::new(LruCache::new(PRECOMPUTED_PROVE_DLOG_CACHE_CAPACITY));
+}
+
+/// `ProveDlog` (P2PK) proposition with the precomputed Fiat-Shamir serialization for the Schnorr
+/// signature verification without building the proof tree. Can be reused to verify the
+/// signatures of the boxes protected by the same key.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PrecomputedProveDlog {
+    proposition: ProveDlog,
+    // Fiat-Shamir serialization of the leaf up to the commitment bytes
+    fiat_shamir_prefix: Vec<u8>,
+}
+
+impl PrecomputedProveDlog {
+    /// Prepare the proposition for the verification
+    pub fn new(proposition: ProveDlog) -> Self {
+        #[allow(clippy::unwrap_used)]
+        // Since expr is fairly simple it can only fail on OOM
+        let prop_bytes = ErgoTree::new(
+            ErgoTreeHeader::v0(true),
+            &Expr::Const(SigmaProp::new(proposition.clone().into()).into()),
+        )
+        .unwrap()
+        .sigma_serialize_bytes()
+        .unwrap();
+        // same layout as the leaf in `fiat_shamir_tree_to_bytes`
+        let mut fiat_shamir_prefix = Vec::with_capacity(prop_bytes.len() + 3);
+        fiat_shamir_prefix.push(1u8);
+        fiat_shamir_prefix.extend_from_slice(&(prop_bytes.len() as i16).to_be_bytes());
+        fiat_shamir_prefix.extend_from_slice(&prop_bytes);
+        PrecomputedProveDlog {
+            proposition,
+            fiat_shamir_prefix,
+        }
+    }
+
+    /// Prepared proposition for the public key taken from the process-wide LRU cache keyed by
+    /// the serialized key (see [`PRECOMPUTED_PROVE_DLOG_CACHE_CAPACITY`]), so the boxes protected
+    /// by the same key are verified without preparing the proposition again
+    pub fn cached(proposition: &ProveDlog) -> Arc<PrecomputedProveDlog> {
+        let key = match proposition.h.scorex_serialize_bytes() {
+            Ok(key) => key,
+            Err(_) => return Arc::new(PrecomputedProveDlog::new(proposition.clone())),
+        };
+        // the cache is always in a consistent state, so it's safe to ignore the poisoning
+        let lock = || {
+            PRECOMPUTED_PROVE_DLOG_CACHE
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+        };
+        if let Some(precomputed) = lock().get(&key) {
+            return precomputed.clone();
+        }
+        // the lock is not held while preparing the proposition on a cache miss
+        let precomputed = Arc::new(PrecomputedProveDlog::new(proposition.clone()));
+        lock().insert(key, precomputed.clone());
+        precomputed
+    }
+
+    /// Proposition
+    pub fn proposition(&self) -> &ProveDlog {
+        &self.proposition
+    }
+
+    /// Verify the signature (challenge followed by the response `z`, trailing bytes are ignored)
+    /// of the message. Same result as [`verify_signature`] for the `ProveDlog` proposition.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<bool, VerifierError> {
+        if signature.len() < SOUNDNESS_BYTES + GROUP_SIZE {
+            // let the general parser report the error
+            let sb = SigmaBoolean::from(self.proposition.clone());
+            let unchecked_tree = parse_sig_compute_challenges(&sb, signature.to_vec())?;
+            return check_commitments(unchecked_tree, message);
+        }
+        let mut challenge_bytes = [0u8; SOUNDNESS_BYTES];
+        challenge_bytes.copy_from_slice(&signature[..SOUNDNESS_BYTES]);
+        let mut z_bytes = [0u8; GROUP_SIZE];
+        z_bytes.copy_from_slice(&signature[SOUNDNESS_BYTES..SOUNDNESS_BYTES + GROUP_SIZE]);
+        let challenge = Challenge::from(FiatShamirHash(Box::new(challenge_bytes)));
+        let second_message = SecondDlogProverMessage {
+            z: Wscalar::from(GroupSizedBytes(z_bytes.into())),
+        };
+        // Verifier Step 4
+        let a = dlog_protocol::interactive_prover::compute_commitment(
+            &self.proposition,
+            &challenge,
+            &second_message,
+        );
+        let commitment_bytes = FirstDlogProverMessage { a: a.into() }.bytes();
+        // Verifier Steps 5-6
+        let mut s = Vec::with_capacity(
+            self.fiat_shamir_prefix.len() + 2 + commitment_bytes.len() + message.len(),
+        );
+        s.extend_from_slice(&self.fiat_shamir_prefix);
+        s.extend_from_slice(&(commitment_bytes.len() as i16).to_be_bytes());
+        s.extend_from_slice(&commitment_bytes);
+        s.extend_from_slice(message);
+        let expected_challenge: [u8; SOUNDNESS_BYTES] = fiat_shamir_hash_fn(s.as_slice()).into();
+        Ok(expected_challenge == challenge_bytes)
+    }
+}
+
 /// Perform Verifier Steps 4-6
 fn check_commitments(sp: UncheckedTree, message: &[u8]) -> Result<bool, VerifierError> {
     // Perform Verifier Step 4
@@ -184,8 +532,13 @@ mod tests {
 
     use super::*;
     use ergotree_ir::mir::atleast::Atleast;
+    use ergotree_ir::mir::bin_op::BinOp;
+    use ergotree_ir::mir::bin_op::RelationOp;
+    use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
     use ergotree_ir::mir::constant::{Constant, Literal};
     use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::global_vars::GlobalVars;
+    use ergotree_ir::mir::if_op::If;
     use ergotree_ir::mir::sigma_and::SigmaAnd;
     use ergotree_ir::mir::sigma_or::SigmaOr;
     use ergotree_ir::mir::value::CollKind;
@@ -251,6 +604,91 @@ mod tests {
                             false);
         }
 
+        #[test]
+        fn test_p2pk_fast_path_equivalence(secret in any::<DlogProverInput>(),
+                                           message in vec(any::<u8>(), 0..200),
+                                           idx in any::<usize>(),
+                                           tamper in 1u8..=255,
+                                           random_sig in vec(any::<u8>(), 0..80)) {
+            let pk = secret.public_image();
+            let sb = SigmaBoolean::from(pk.clone());
+            let tree = ErgoTree::try_from(Expr::Const(pk.clone().into())).unwrap();
+            let prover = TestProver {
+                secrets: vec![PrivateInput::DlogProverInput(secret)],
+            };
+            let proof = match prover.prove(&tree,
+                &Env::empty(),
+                Rc::new(force_any_val::<Context>()),
+                message.as_slice(),
+                &HintsBag::empty()).unwrap().proof {
+                ProofBytes::Some(bytes) => bytes,
+                ProofBytes::Empty => panic!(),
+            };
+            let precomputed = PrecomputedProveDlog::new(pk);
+            let general = |sig: &[u8], msg: &[u8]| {
+                parse_sig_compute_challenges(&sb, sig.to_vec())
+                    .map_err(VerifierError::from)
+                    .and_then(|unchecked_tree| check_commitments(unchecked_tree, msg))
+                    .ok()
+            };
+            let fast = |sig: &[u8], msg: &[u8]| precomputed.verify(msg, sig).ok();
+            let cached = PrecomputedProveDlog::cached(precomputed.proposition());
+            prop_assert_eq!(&*cached, &precomputed);
+            prop_assert!(Arc::ptr_eq(&cached, &PrecomputedProveDlog::cached(precomputed.proposition())));
+
+            prop_assert_eq!(fast(&proof, &message), Some(true));
+            let mut tampered = proof.clone();
+            tampered[idx % proof.len()] ^= tamper;
+            let mut appended = proof.clone();
+            appended.push(tamper);
+            let truncated = &proof[..idx % proof.len()];
+            for sig in [proof.as_slice(), tampered.as_slice(), appended.as_slice(), truncated, random_sig.as_slice()] {
+                prop_assert_eq!(fast(sig, &message), general(sig, &message));
+                prop_assert_eq!(fast(sig, &[1u8; 100]), general(sig, &[1u8; 100]));
+            }
+        }
+
+        #[test]
+        fn test_verify_with_report_p2pk(secret in any::<DlogProverInput>(), message in vec(any::<u8>(), 100..200)) {
+            let pk = secret.public_image();
+            let tree = ErgoTree::try_from(Expr::Const(pk.clone().into())).unwrap();
+            let prover = TestProver {
+                secrets: vec![PrivateInput::DlogProverInput(secret)],
+            };
+            let proof = prover.prove(&tree,
+                &Env::empty(),
+                Rc::new(force_any_val::<Context>()),
+                message.as_slice(),
+                &HintsBag::empty()).unwrap().proof;
+            let verifier = TestVerifier;
+            let report = verifier.verify_with_report(&tree,
+                                                     &Env::empty(),
+                                                     Rc::new(force_any_val::<Context>()),
+                                                     proof.clone(),
+                                                     message.as_slice());
+            prop_assert!(report.verified);
+            prop_assert_eq!(report.failure_reason, None);
+            prop_assert_eq!(report.reduced, Some(SigmaBoolean::from(pk.clone())));
+
+            // wrong message
+            let report = verifier.verify_with_report(&tree,
+                                                     &Env::empty(),
+                                                     Rc::new(force_any_val::<Context>()),
+                                                     proof,
+                                                     vec![1u8; 100].as_slice());
+            prop_assert!(!report.verified);
+            prop_assert_eq!(report.failure_reason, Some(VerificationFailureReason::InvalidProof));
+
+            // no proof
+            let report = verifier.verify_with_report(&tree,
+                                                     &Env::empty(),
+                                                     Rc::new(force_any_val::<Context>()),
+                                                     ProofBytes::Empty,
+                                                     message.as_slice());
+            prop_assert_eq!(report.failure_reason, Some(VerificationFailureReason::InvalidProof));
+            prop_assert_eq!(report.reduced, Some(SigmaBoolean::from(pk)));
+        }
+
         #[test]
         fn test_prover_verifier_dht(secret in any::<DhTupleProverInput>(), message in vec(any::<u8>(), 100..200)) {
             let pk = secret.public_image().clone();
@@ -464,4 +902,170 @@ mod tests {
             prop_assert_eq!(ver_res.unwrap().result, true)
         }
     }
+
+    #[test]
+    fn test_verify_with_report_script_false() {
+        let tree = ErgoTree::try_from(Expr::Const(false.into())).unwrap();
+        let report = TestVerifier.verify_with_report(
+            &tree,
+            &Env::empty(),
+            Rc::new(force_any_val::<Context>()),
+            ProofBytes::Empty,
+            &[],
+        );
+        assert!(!report.verified);
+        assert_eq!(report.reduced, Some(SigmaBoolean::TrivialProp(false)));
+        assert_eq!(
+            report.failure_reason,
+            Some(VerificationFailureReason::ScriptFalse)
+        );
+    }
+
+    #[test]
+    fn test_verify_with_report_eval_error() {
+        let tree = ErgoTree::try_from(Expr::Const(1i32.into())).unwrap();
+        let report = TestVerifier.verify_with_report(
+            &tree,
+            &Env::empty(),
+            Rc::new(force_any_val::<Context>()),
+            ProofBytes::Empty,
+            &[],
+        );
+        assert!(!report.verified);
+        assert_eq!(report.reduced, None);
+        assert!(matches!(
+            report.failure_reason,
+            Some(VerificationFailureReason::EvaluationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_with_report_cost_exceeded() {
+        let p2pk = ErgoTree::try_from(Expr::Const(
+            SigmaProp::from(force_any_val::<ProveDlog>()).into(),
+        ))
+        .unwrap();
+        let non_trivial = ErgoTree::try_from(Expr::BoolToSigmaProp(BoolToSigmaProp {
+            input: Box::new(
+                BinOp::new(
+                    RelationOp::Gt.into(),
+                    GlobalVars::Height.into(),
+                    Expr::Const(0i32.into()),
+                )
+                .unwrap()
+                .into(),
+            ),
+        }))
+        .unwrap();
+        for tree in [p2pk, non_trivial] {
+            let ctx = Rc::new(force_any_val::<Context>());
+            let report = TestVerifier.verify_with_report(
+                &tree,
+                &Env::empty(),
+                ctx.clone(),
+                ProofBytes::Empty,
+                &[],
+            );
+            assert!(report.cost > 0);
+            let within_limit = TestVerifier.verify_with_report_limited(
+                &tree,
+                &Env::empty(),
+                ctx.clone(),
+                ProofBytes::Empty,
+                &[],
+                report.cost,
+            );
+            assert_eq!(within_limit, report);
+            let exceeded = TestVerifier.verify_with_report_limited(
+                &tree,
+                &Env::empty(),
+                ctx.clone(),
+                ProofBytes::Empty,
+                &[],
+                report.cost - 1,
+            );
+            assert!(!exceeded.verified);
+            assert_eq!(exceeded.reduced, None);
+            assert_eq!(
+                exceeded.failure_reason,
+                Some(VerificationFailureReason::CostExceeded)
+            );
+            assert!(exceeded.cost > report.cost - 1);
+        }
+    }
+
+    #[test]
+    fn test_verify_with_report_cached() {
+        let tree = ErgoTree::try_from(Expr::BoolToSigmaProp(BoolToSigmaProp {
+            input: Box::new(
+                BinOp::new(
+                    RelationOp::Gt.into(),
+                    GlobalVars::Height.into(),
+                    Expr::Const(0i32.into()),
+                )
+                .unwrap()
+                .into(),
+            ),
+        }))
+        .unwrap();
+        let tree_bytes = tree.sigma_serialize_bytes().unwrap();
+        let cache = SharedErgoTreeCache::new(4);
+        let ctx = Rc::new(force_any_val::<Context>());
+        let report = TestVerifier.verify_with_report(
+            &tree,
+            &Env::empty(),
+            ctx.clone(),
+            ProofBytes::Empty,
+            &[],
+        );
+        for _ in 0..2 {
+            let cached = TestVerifier.verify_with_report_cached(
+                &tree_bytes,
+                &Env::empty(),
+                ctx.clone(),
+                ProofBytes::Empty,
+                &[],
+                &cache,
+            );
+            assert_eq!(cached, report);
+        }
+        let malformed = TestVerifier.verify_with_report_cached(
+            &[],
+            &Env::empty(),
+            ctx,
+            ProofBytes::Empty,
+            &[],
+            &cache,
+        );
+        assert!(!malformed.verified);
+        assert!(matches!(
+            malformed.failure_reason,
+            Some(VerificationFailureReason::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_with_report_type_checked() {
+        let tree = ErgoTree::try_from(Expr::BoolToSigmaProp(BoolToSigmaProp {
+            input: Box::new(Expr::If(If {
+                condition: Box::new(Expr::Const(1i32.into())),
+                true_branch: Box::new(Expr::Const(true.into())),
+                false_branch: Box::new(Expr::Const(false.into())),
+            })),
+        }))
+        .unwrap();
+        let report = TestVerifier.verify_with_report_type_checked(
+            &tree,
+            &Env::empty(),
+            Rc::new(force_any_val::<Context>()),
+            ProofBytes::Empty,
+            &[],
+        );
+        assert!(!report.verified);
+        assert_eq!(report.reduced, None);
+        assert!(matches!(
+            report.failure_reason,
+            Some(VerificationFailureReason::DeserializationError(_))
+        ));
+    }
 }
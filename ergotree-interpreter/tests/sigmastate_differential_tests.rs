@@ -0,0 +1,224 @@
+//! Differential tests against the reference implementation (sigmastate-interpreter).
+//! Every JSON file in `tests/sigmastate_fixtures` is a list of cases (ErgoTree, context and the
+//! reduction result of sigmastate), see `tests/sigmastate_fixtures/README.md` for the format.
+//! All divergences are collected and reported at once.
+#![cfg(feature = "json")]
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use ergo_chain_types::BlockId;
+use ergo_chain_types::EcPoint;
+use ergo_chain_types::Header;
+use ergo_chain_types::PreHeader;
+use ergo_chain_types::Votes;
+use ergotree_interpreter::eval::context::Context;
+use ergotree_interpreter::eval::context::TxIoVec;
+use ergotree_interpreter::eval::env::Env;
+use ergotree_interpreter::eval::reduce_to_crypto;
+use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+use serde::Deserialize;
+use sigma_ser::ScorexSerializable;
+use sigma_test_util::force_any_val;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Fixture {
+    name: String,
+    /// Serialized ErgoTree (base16)
+    tree: String,
+    #[serde(default)]
+    context: ContextFixture,
+    expected: Expected,
+    /// Reduction cost reported by sigmastate
+    cost: Option<u64>,
+    /// Reason of the known (reported) divergence, such fixture is expected to diverge
+    known_divergence: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Expected {
+    /// Serialized SigmaBoolean (base16)
+    Reduced(String),
+    /// Error message of sigmastate (only the failure itself is compared)
+    Error(String),
+}
+
+/// Fields that are not set are taken from the random context
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ContextFixture {
+    height: Option<u32>,
+    self_index: usize,
+    inputs: Vec<ErgoBox>,
+    data_inputs: Vec<ErgoBox>,
+    outputs: Vec<ErgoBox>,
+    /// Last 10 headers, the newest first
+    headers: Vec<Header>,
+    pre_header: Option<PreHeaderFixture>,
+    /// Context variables as serialized constants (base16)
+    extension: BTreeMap<u8, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PreHeaderFixture {
+    version: u8,
+    parent_id: BlockId,
+    timestamp: u64,
+    n_bits: u64,
+    height: u32,
+    /// Serialized miner public key (base16)
+    miner_pk: String,
+    votes: Votes,
+}
+
+fn decode(hex: &str) -> Result<Vec<u8>, String> {
+    base16::decode(hex).map_err(|e| format!("invalid base16 {}: {}", hex, e))
+}
+
+fn build_context(f: &ContextFixture) -> Result<Context, String> {
+    let mut ctx = force_any_val::<Context>();
+    if let Some(height) = f.height {
+        ctx.height = height;
+    }
+    if !f.inputs.is_empty() {
+        let inputs: Vec<Arc<ErgoBox>> = f.inputs.iter().cloned().map(Arc::new).collect();
+        ctx.self_box = inputs
+            .get(f.self_index)
+            .cloned()
+            .ok_or_else(|| format!("no input at self index {}", f.self_index))?;
+        ctx.inputs = TxIoVec::from_vec(inputs).map_err(|e| e.to_string())?;
+    }
+    ctx.data_inputs = if f.data_inputs.is_empty() {
+        None
+    } else {
+        Some(
+            TxIoVec::from_vec(f.data_inputs.iter().cloned().map(Arc::new).collect())
+                .map_err(|e| e.to_string())?,
+        )
+    };
+    ctx.outputs = f.outputs.iter().cloned().map(Arc::new).collect();
+    if !f.headers.is_empty() {
+        ctx.headers = f
+            .headers
+            .clone()
+            .try_into()
+            .map_err(|h: Vec<Header>| format!("expected 10 headers, got {}", h.len()))?;
+    }
+    if let Some(p) = &f.pre_header {
+        let miner_pk = EcPoint::scorex_parse_bytes(&decode(&p.miner_pk)?)
+            .map_err(|e| format!("invalid miner pk: {}", e))?;
+        ctx.pre_header = PreHeader {
+            version: p.version,
+            parent_id: p.parent_id,
+            timestamp: p.timestamp,
+            n_bits: p.n_bits,
+            height: p.height,
+            miner_pk: Box::new(miner_pk),
+            votes: p.votes.clone(),
+        };
+    }
+    let mut extension = ContextExtension::empty();
+    for (id, value) in &f.extension {
+        let constant = Constant::sigma_parse_bytes(&decode(value)?)
+            .map_err(|e| format!("invalid context var {}: {}", id, e))?;
+        extension.values.insert(*id, constant);
+    }
+    ctx.extension = extension;
+    Ok(ctx)
+}
+
+/// Returns the divergence description
+fn check(f: &Fixture) -> Result<(), String> {
+    let ctx = Rc::new(build_context(&f.context)?);
+    let reduced = ErgoTree::sigma_parse_bytes(&decode(&f.tree)?)
+        .map_err(|e| e.to_string())
+        .and_then(|tree| tree.proposition().map_err(|e| e.to_string()))
+        .and_then(|expr| reduce_to_crypto(&expr, &Env::empty(), ctx).map_err(|e| e.to_string()));
+    match (&f.expected, reduced) {
+        (Expected::Reduced(hex), Ok(res)) => {
+            let expected = SigmaBoolean::sigma_parse_bytes(&decode(hex)?)
+                .map_err(|e| format!("invalid expected sigma prop: {}", e))?;
+            if res.sigma_prop != expected {
+                return Err(format!(
+                    "reduced to {:?}, sigmastate: {:?}",
+                    res.sigma_prop, expected
+                ));
+            }
+            match f.cost {
+                Some(cost) if cost != res.cost => {
+                    Err(format!("cost {}, sigmastate: {}", res.cost, cost))
+                }
+                _ => Ok(()),
+            }
+        }
+        (Expected::Reduced(_), Err(e)) => Err(format!("failed with {}, sigmastate reduced", e)),
+        (Expected::Error(_), Ok(res)) => Err(format!(
+            "reduced to {:?}, sigmastate failed",
+            res.sigma_prop
+        )),
+        (Expected::Error(_), Err(_)) => Ok(()),
+    }
+}
+
+#[test]
+fn cost_divergence_is_reported() {
+    let fixture: Fixture = serde_json::from_str(
+        r#"{
+            "name": "height_above_threshold",
+            "tree": "00d191a304c801",
+            "context": { "height": 500 },
+            "expected": { "reduced": "d3" },
+            "cost": 0
+        }"#,
+    )
+    .unwrap();
+    let err = check(&fixture).unwrap_err();
+    assert!(err.starts_with("cost "), "{}", err);
+}
+
+#[test]
+fn sigmastate_differential() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/sigmastate_fixtures");
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().map_or(false, |ext| ext == "json"))
+        .collect();
+    files.sort();
+    let mut cases_count = 0;
+    let mut divergences = Vec::new();
+    for file in files {
+        let file_name = file.file_name().unwrap().to_string_lossy().to_string();
+        let fixtures: Vec<Fixture> =
+            serde_json::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        for f in fixtures {
+            cases_count += 1;
+            match (check(&f), &f.known_divergence) {
+                (Err(e), None) => divergences.push(format!("{}/{}: {}", file_name, f.name, e)),
+                (Ok(()), Some(reason)) => divergences.push(format!(
+                    "{}/{}: matches sigmastate, remove the known divergence ({})",
+                    file_name, f.name, reason
+                )),
+                _ => (),
+            }
+        }
+    }
+    assert!(cases_count > 0, "no fixtures in {}", dir.display());
+    assert!(
+        divergences.is_empty(),
+        "{} of {} cases diverge from sigmastate:\n{}",
+        divergences.len(),
+        cases_count,
+        divergences.join("\n")
+    );
+}
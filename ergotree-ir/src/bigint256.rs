@@ -16,6 +16,14 @@ use num_traits::{
 
 /// 256-bit signed integer type
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Zero, One)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "String",
+        try_from = "crate::chain::json::bigint256::BigInt256Json"
+    )
+)]
 pub struct BigInt256(Int256);
 
 impl TryFrom<BigInt> for BigInt256 {
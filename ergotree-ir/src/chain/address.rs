@@ -12,7 +12,9 @@ use crate::mir::constant::{Constant, Literal};
 use crate::mir::deserialize_context::DeserializeContext;
 use crate::mir::expr::Expr;
 use crate::mir::get_var::GetVar;
+use crate::mir::option_get::OptionGet;
 use crate::mir::sigma_and::SigmaAnd;
+use crate::mir::unary_op::OneArgOpTryBuild;
 use crate::mir::value::CollKind;
 use crate::mir::value::NativeColl::CollByte;
 use crate::serialization::SigmaParsingError;
@@ -26,8 +28,13 @@ use crate::source_span::Spanned;
 use crate::types::stype::SType;
 use ergo_chain_types::EcPoint;
 
+use sigma_util::encoding::decode_base58;
+use sigma_util::encoding::encode_base58;
+use sigma_util::encoding::DecodingError;
+use sigma_util::encoding::DecodingMode;
 use sigma_util::hash::blake2b256_hash;
 use sigma_util::AsVecU8;
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use thiserror::Error;
 
@@ -217,8 +224,9 @@ impl Address {
                     }
                     .into(),
                 );
+                let script_bytes_expr: Expr = OptionGet::try_build(get_var_expr)?.into();
                 let hash_expr = Expr::CalcBlake2b256(CalcBlake2b256 {
-                    input: Box::new(get_var_expr),
+                    input: Box::new(script_bytes_expr),
                 });
                 let slice_expr = Expr::Slice(
                     Slice {
@@ -400,6 +408,12 @@ impl From<bs58::decode::Error> for AddressEncoderError {
     }
 }
 
+impl From<DecodingError> for AddressEncoderError {
+    fn from(err: DecodingError) -> Self {
+        AddressEncoderError::Base58DecodingError(err.to_string())
+    }
+}
+
 impl From<SigmaParsingError> for AddressEncoderError {
     fn from(err: SigmaParsingError) -> Self {
         AddressEncoderError::DeserializationFailed(err)
@@ -458,7 +472,7 @@ impl AddressEncoder {
 
     /// parse address from Base58 encoded string
     pub fn parse_address_from_str(&self, str: &str) -> Result<Address, AddressEncoderError> {
-        let bytes = bs58::decode(str).into_vec()?;
+        let bytes = decode_base58(str, DecodingMode::Strict)?;
         if bytes.len() < AddressEncoder::MIN_ADDRESS_LENGTH {
             return Err(AddressEncoderError::InvalidSize);
         };
@@ -470,7 +484,7 @@ impl AddressEncoder {
     pub fn unchecked_parse_network_address_from_str(
         str: &str,
     ) -> Result<NetworkAddress, AddressEncoderError> {
-        let bytes = bs58::decode(str).into_vec()?;
+        let bytes = decode_base58(str, DecodingMode::Strict)?;
         AddressEncoder::unchecked_parse_network_address_from_bytes(&bytes)
     }
 
@@ -491,7 +505,7 @@ impl AddressEncoder {
 
     /// parse address from Base58 encoded string
     pub fn unchecked_parse_address_from_str(str: &str) -> Result<Address, AddressEncoderError> {
-        let bytes = bs58::decode(str).into_vec()?;
+        let bytes = decode_base58(str, DecodingMode::Strict)?;
         AddressEncoder::unchecked_parse_address_from_bytes(&bytes)
     }
 
@@ -541,11 +555,53 @@ impl AddressEncoder {
 
     /// encode address as Base58 encoded string
     pub fn encode_address_as_string(network_prefix: NetworkPrefix, address: &Address) -> String {
-        bs58::encode(AddressEncoder::encode_address_as_bytes(
+        encode_base58(&AddressEncoder::encode_address_as_bytes(
             network_prefix,
             address,
         ))
-        .into_string()
+    }
+
+    /// Encode the addresses of the given serialized trees (e.g. `ergoTree` of the boxes, see
+    /// [`Address::recreate_from_ergo_tree`]) in the same order.
+    /// Meant for the bulk conversion (e.g. in explorers), every distinct tree is parsed and
+    /// converted once (the given bytes are the cache key).
+    pub fn encode_many<'a, I>(&self, trees_bytes: I) -> Vec<Result<String, AddressError>>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let mut cache: HashMap<&'a [u8], Result<String, AddressError>> = HashMap::new();
+        trees_bytes
+            .into_iter()
+            .map(|tree_bytes| {
+                cache
+                    .entry(tree_bytes)
+                    .or_insert_with(|| {
+                        let tree = ErgoTree::sigma_parse_bytes(tree_bytes)
+                            .map_err(ErgoTreeError::SigmaParsingError)?;
+                        Address::recreate_from_ergo_tree(&tree)
+                            .map(|address| self.address_to_str(&address))
+                    })
+                    .clone()
+            })
+            .collect()
+    }
+
+    /// Parse the given addresses (checking the network) into their scripts in the same order.
+    /// Meant for the bulk conversion (e.g. in explorers), every distinct address is parsed once.
+    pub fn decode_many<'a, I>(&self, addresses: I) -> Vec<Result<ErgoTree, AddressEncoderError>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut cache: HashMap<&'a str, Result<ErgoTree, AddressEncoderError>> = HashMap::new();
+        addresses
+            .into_iter()
+            .map(|address| {
+                cache
+                    .entry(address)
+                    .or_insert_with(|| Ok(self.parse_address_from_str(address)?.script()?))
+                    .clone()
+            })
+            .collect()
     }
 }
 
@@ -554,6 +610,7 @@ impl AddressEncoder {
 pub(crate) mod arbitrary {
     use super::*;
     use proptest::prelude::*;
+    use sigma_util::encoding::decode_base16;
 
     impl Arbitrary for Address {
         type Parameters = ();
@@ -571,7 +628,9 @@ pub(crate) mod arbitrary {
                     let address: [u8; 24] = blake2b256_hash(&bytes)[0..24].try_into().unwrap();
                     Address::P2SH(address)
                 }),
-                Just(Address::P2S(base16::decode(non_parseable_tree).unwrap()))
+                Just(Address::P2S(
+                    decode_base16(non_parseable_tree, DecodingMode::Strict).unwrap()
+                ))
             ]
             .boxed()
         }
@@ -586,6 +645,19 @@ mod tests {
     use super::*;
     use proptest::prelude::*;
 
+    #[test]
+    fn p2sh_script_bytes() {
+        // P2SH script as built by sigmastate (ErgoAddress.scala): the script bytes are read with
+        // `getVar[Coll[Byte]](1).get`
+        let address = Address::P2SH([7u8; 24]);
+        let expected = format!(
+            "00ea02d193b4cbe4e3010e040004300e18{}d40801",
+            "07".repeat(24)
+        );
+        let script_bytes = address.script().unwrap().sigma_serialize_bytes().unwrap();
+        assert_eq!(base16::encode_lower(&script_bytes), expected);
+    }
+
     proptest! {
 
         #[test]
@@ -603,6 +675,35 @@ mod tests {
             prop_assert_eq![recreated, v];
         }
 
+        #[test]
+        fn recreate_from_parsed_bytes_roundtrip(v in any::<Address>()) {
+            let tree_bytes = v.script().unwrap().sigma_serialize_bytes().unwrap();
+            let tree = ErgoTree::sigma_parse_bytes(&tree_bytes).unwrap();
+            prop_assert_eq![Address::recreate_from_ergo_tree(&tree).unwrap(), v];
+        }
+
+        #[test]
+        fn many_roundtrip(v in proptest::collection::vec(any::<Address>(), 1..10)) {
+            let encoder = AddressEncoder::new(NetworkPrefix::Mainnet);
+            // with repeated items
+            let trees: Vec<ErgoTree> = v.iter().chain(v.iter()).map(|a| a.script().unwrap()).collect();
+            let trees_bytes: Vec<Vec<u8>> = trees.iter().map(|t| t.sigma_serialize_bytes().unwrap()).collect();
+            let mut encoded = encoder.encode_many(trees_bytes.iter().map(Vec::as_slice).chain([&[0u8][..]]));
+            // unparseable trees are P2S as well
+            prop_assert_eq![encoded.pop().unwrap().unwrap(), encoder.address_to_str(&Address::P2S(vec![0]))];
+            let encoded: Vec<String> = encoded.into_iter().collect::<Result<_, _>>().unwrap();
+            for (tree, encoded_addr) in trees.iter().zip(encoded.iter()) {
+                let addr = Address::recreate_from_ergo_tree(tree).unwrap();
+                prop_assert_eq![encoder.address_to_str(&addr), encoded_addr.clone()];
+            }
+            let decoded = encoder.decode_many(encoded.iter().map(String::as_str).chain(["invalid"]));
+            prop_assert_eq![decoded.len(), trees.len() + 1];
+            for (tree, decoded_tree) in trees.iter().zip(decoded.iter()) {
+                prop_assert_eq![decoded_tree.clone().unwrap(), tree.clone()];
+            }
+            prop_assert![decoded.last().unwrap().is_err()];
+        }
+
         #[test]
         fn doesnt_crash_on_invalid_input(s in "\\w+") {
             let encoder = AddressEncoder::new(NetworkPrefix::Testnet);
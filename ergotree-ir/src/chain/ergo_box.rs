@@ -3,6 +3,7 @@
 mod box_id;
 pub mod box_value;
 mod register;
+mod view;
 
 use crate::ergo_tree::ErgoTree;
 use crate::mir::constant::Constant;
@@ -16,6 +17,7 @@ use crate::serialization::SigmaSerializeResult;
 pub use box_id::*;
 use ergo_chain_types::Digest32;
 pub use register::*;
+pub use view::*;
 
 use bounded_vec::BoundedVec;
 use indexmap::IndexSet;
@@ -190,6 +192,12 @@ impl ErgoBox {
         let candidate: ErgoBoxCandidate = self.clone().into();
         Ok(candidate.sigma_serialize_bytes()?.as_vec_i8())
     }
+
+    /// Size of the serialized box in bytes (the size the min box value and the storage rent are
+    /// calculated from), counted without allocating a buffer
+    pub fn serialized_size(&self) -> Result<usize, SigmaSerializationError> {
+        self.sigma_serialized_size()
+    }
 }
 
 impl SigmaSerializable for ErgoBox {
@@ -267,6 +275,28 @@ impl ErgoBoxCandidate {
     ) -> Result<ErgoBoxCandidate, SigmaParsingError> {
         parse_box_with_indexed_digests(digests_in_tx, r)
     }
+
+    /// Size of the serialized box candidate in bytes, counted without allocating a buffer
+    pub fn serialized_size(&self) -> Result<usize, SigmaSerializationError> {
+        self.sigma_serialized_size()
+    }
+
+    /// Size of the tokens in the serialized box (tokens count and the tokens with full ids) in
+    /// bytes
+    pub fn tokens_serialized_size(&self) -> usize {
+        1 + self
+            .tokens
+            .iter()
+            .flat_map(|tokens| tokens.iter())
+            .map(Token::serialized_size)
+            .sum::<usize>()
+    }
+
+    /// Size of the additional registers in the serialized box (registers count and the values)
+    /// in bytes
+    pub fn registers_serialized_size(&self) -> Result<usize, SigmaSerializationError> {
+        self.additional_registers.sigma_serialized_size()
+    }
 }
 
 impl SigmaSerializable for ErgoBoxCandidate {
@@ -341,29 +371,35 @@ pub fn parse_box_with_indexed_digests<R: SigmaByteRead>(
 ) -> Result<ErgoBoxCandidate, SigmaParsingError> {
     // reference implementation -https://github.com/ScorexFoundation/sigmastate-interpreter/blob/9b20cb110effd1987ff76699d637174a4b2fb441/sigmastate/src/main/scala/org/ergoplatform/ErgoBoxCandidate.scala#L144-L144
 
-    let value = BoxValue::sigma_parse(r)?;
-    let ergo_tree = ErgoTree::sigma_parse(r)?;
+    let value = r.parse_in_context(|| "value".to_string(), BoxValue::sigma_parse)?;
+    let ergo_tree = r.parse_in_context(|| "ergo_tree".to_string(), ErgoTree::sigma_parse)?;
     let creation_height = r.get_u32()?;
     let tokens_count = r.get_u8()?;
     let mut tokens = Vec::with_capacity(tokens_count as usize);
-    for _ in 0..tokens_count {
-        let token_id = match digests_in_tx {
-            None => TokenId::sigma_parse(r)?,
-            Some(digests) => {
-                let digest_index = r.get_u32()?;
-                match digests.get_index(digest_index as usize) {
-                    Some(i) => Ok(*i),
-                    None => Err(SigmaParsingError::Misc(
-                        "failed to find token id in tx digests".to_string(),
-                    )),
-                }?
-            }
-        };
-        let amount = r.get_u64()?;
-        tokens.push(Token {
-            token_id,
-            amount: amount.try_into()?,
-        })
+    for i in 0..tokens_count {
+        let token = r.parse_in_context(
+            || format!("token[{}]", i),
+            |r| {
+                let token_id = match digests_in_tx {
+                    None => TokenId::sigma_parse(r)?,
+                    Some(digests) => {
+                        let digest_index = r.get_u32()?;
+                        match digests.get_index(digest_index as usize) {
+                            Some(i) => Ok(*i),
+                            None => Err(SigmaParsingError::Misc(
+                                "failed to find token id in tx digests".to_string(),
+                            )),
+                        }?
+                    }
+                };
+                let amount = r.get_u64()?;
+                Ok(Token {
+                    token_id,
+                    amount: amount.try_into()?,
+                })
+            },
+        )?;
+        tokens.push(token)
     }
     let tokens = if tokens.is_empty() {
         None
@@ -402,12 +438,24 @@ pub mod arbitrary {
                 any::<NonMandatoryRegisters>(),
             )
                 .prop_map(
-                    |(value, ergo_tree, tokens, creation_height, additional_registers)| Self {
-                        value,
-                        ergo_tree,
-                        tokens: tokens.map(BoundedVec::from_vec).map(Result::unwrap),
-                        additional_registers,
-                        creation_height,
+                    |(value, ergo_tree, tokens, creation_height, additional_registers)| {
+                        // token ids are picked from a small predefined set, keep them unique in a box
+                        let tokens = tokens.map(|mut tokens: Vec<Token>| {
+                            let mut seen = Vec::new();
+                            tokens.retain(|t| {
+                                let is_new = !seen.contains(&t.token_id);
+                                seen.push(t.token_id);
+                                is_new
+                            });
+                            tokens
+                        });
+                        Self {
+                            value,
+                            ergo_tree,
+                            tokens: tokens.map(BoundedVec::from_vec).map(Result::unwrap),
+                            additional_registers,
+                            creation_height,
+                        }
                     },
                 )
                 .boxed()
@@ -518,6 +566,26 @@ mod tests {
         fn ergo_box_ser_roundtrip(v in any::<ErgoBox>()) {
             prop_assert_eq![sigma_serialize_roundtrip(&v), v];
         }
+
+        #[test]
+        fn serialized_size(b in any::<ErgoBox>()) {
+            prop_assert_eq![b.serialized_size().unwrap(), b.sigma_serialize_bytes().unwrap().len()];
+            let c: ErgoBoxCandidate = b.into();
+            let size = c.serialized_size().unwrap();
+            prop_assert_eq![size, c.sigma_serialize_bytes().unwrap().len()];
+            let stripped = ErgoBoxCandidate {
+                tokens: None,
+                additional_registers: NonMandatoryRegisters::empty(),
+                ..c.clone()
+            };
+            // empty tokens and registers take a byte each (zero count)
+            prop_assert_eq![
+                size,
+                stripped.serialized_size().unwrap() - 2
+                    + c.tokens_serialized_size()
+                    + c.registers_serialized_size().unwrap()
+            ];
+        }
     }
 }
 // += a + b
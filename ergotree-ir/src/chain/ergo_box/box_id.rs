@@ -1,5 +1,6 @@
 //! Box id type
 use std::convert::TryFrom;
+use std::str::FromStr;
 
 use crate::serialization::SigmaSerializeResult;
 use sigma_ser::ScorexSerializable;
@@ -27,6 +28,11 @@ impl BoxId {
     pub fn zero() -> BoxId {
         BoxId(Digest32::zero())
     }
+
+    /// Constant-time equality check
+    pub fn ct_eq(&self, other: &BoxId) -> bool {
+        self.0.ct_eq(&other.0)
+    }
 }
 
 impl AsRef<[u8]> for BoxId {
@@ -41,11 +47,41 @@ impl From<BoxId> for String {
     }
 }
 
+impl FromStr for BoxId {
+    type Err = DigestNError;
+
+    /// Parse from base16-encoded string (exactly 32 bytes, decoded in constant time)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Digest32::from_str(s)?.into())
+    }
+}
+
 impl TryFrom<String> for BoxId {
     type Error = DigestNError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        Ok(Digest32::try_from(value)?.into())
+        value.parse()
+    }
+}
+
+impl From<[u8; 32]> for BoxId {
+    fn from(bytes: [u8; 32]) -> Self {
+        BoxId(Digest32::from(bytes))
+    }
+}
+
+impl From<BoxId> for [u8; 32] {
+    fn from(v: BoxId) -> Self {
+        v.0.into()
+    }
+}
+
+impl TryFrom<&[u8]> for BoxId {
+    type Error = DigestNError;
+
+    /// Parse from the bytes (exactly 32 bytes)
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Digest32::try_from(bytes).map(Self)
     }
 }
 
@@ -86,5 +122,17 @@ mod tests {
         fn ser_roundtrip(v in any::<BoxId>()) {
             prop_assert_eq![sigma_serialize_roundtrip(&v), v];
         }
+
+        #[test]
+        fn str_roundtrip(v in any::<BoxId>()) {
+            prop_assert_eq![BoxId::from_str(&v.to_string()).unwrap(), v];
+            prop_assert_eq![BoxId::try_from(String::from(v)).unwrap(), v];
+        }
+
+        #[test]
+        fn bytes_roundtrip(v in any::<BoxId>()) {
+            prop_assert_eq![BoxId::from(<[u8; 32]>::from(v)), v];
+            prop_assert_eq![BoxId::try_from(v.as_ref()).unwrap(), v];
+        }
     }
 }
@@ -29,7 +29,8 @@ pub struct BoxValue(pub(crate) u64);
 
 impl BoxValue {
     /// Minimal box value per byte of the serialized box that was set on on launch
-    pub const MIN_VALUE_PER_BOX_BYTE: u32 = 360;
+    pub const MIN_VALUE_PER_BOX_BYTE: u32 =
+        ergo_chain_types::parameters::MIN_VALUE_PER_BYTE_DEFAULT;
     /// Minimal theoretical box size (smallest tree, no tokens, no registers, etc.)
     const MIN_BOX_SIZE_BYTES: usize = 30;
 
@@ -49,6 +50,9 @@ impl BoxValue {
     /// Number of units inside one ERGO (i.e. one ERG using nano ERG representation)
     pub const UNITS_PER_ERGO: u32 = 1000000000;
 
+    /// Number of decimals in the ERG amount (see [`BoxValue::UNITS_PER_ERGO`])
+    pub const ERG_DECIMALS: usize = 9;
+
     /// create from u64 with bounds check
     pub fn new(v: u64) -> Result<BoxValue, BoxValueError> {
         BoxValue::try_from(v)
@@ -111,6 +115,50 @@ impl BoxValue {
             Ok(Self(raw))
         }
     }
+
+    /// Parse the ERG amount with up to 9 decimals, optionally followed by the "ERG" unit
+    /// (e.g. "1.234 ERG" is 1234000000 nanoERGs)
+    pub fn from_erg_str(s: &str) -> Result<BoxValue, BoxValueError> {
+        let invalid = || BoxValueError::InvalidErgAmount(s.to_string());
+        let amount = s.trim();
+        let amount = amount.strip_suffix("ERG").unwrap_or(amount).trim_end();
+        let (int_part, frac_part) = amount.split_once('.').unwrap_or((amount, ""));
+        let is_digits = |p: &str| p.bytes().all(|b| b.is_ascii_digit());
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !is_digits(int_part)
+            || !is_digits(frac_part)
+            || frac_part.len() > BoxValue::ERG_DECIMALS
+        {
+            return Err(invalid());
+        }
+        let parse = |p: &str| -> Result<u64, BoxValueError> {
+            if p.is_empty() {
+                Ok(0)
+            } else {
+                p.parse().map_err(|_| BoxValueError::Overflow)
+            }
+        };
+        let frac_units =
+            parse(frac_part)? * 10u64.pow((BoxValue::ERG_DECIMALS - frac_part.len()) as u32);
+        let raw = parse(int_part)?
+            .checked_mul(BoxValue::UNITS_PER_ERGO as u64)
+            .and_then(|v| v.checked_add(frac_units))
+            .ok_or(BoxValueError::Overflow)?;
+        BoxValue::try_from(raw)
+    }
+
+    /// ERG amount with the trailing zero decimals omitted (e.g. "1.234 ERG" for 1234000000
+    /// nanoERGs)
+    pub fn to_erg_string(&self) -> String {
+        let units = BoxValue::UNITS_PER_ERGO as u64;
+        let (int_part, frac_part) = (self.0 / units, self.0 % units);
+        if frac_part == 0 {
+            format!("{} ERG", int_part)
+        } else {
+            let frac = format!("{:0width$}", frac_part, width = BoxValue::ERG_DECIMALS);
+            format!("{}.{} ERG", int_part, frac.trim_end_matches('0'))
+        }
+    }
 }
 
 impl PartialOrd for BoxValue {
@@ -180,6 +228,9 @@ pub enum BoxValueError {
     /// Overflow
     #[error("Overflow")]
     Overflow,
+    /// Invalid ERG amount string
+    #[error("Invalid ERG amount: {0}")]
+    InvalidErgAmount(String),
 }
 
 impl From<BoxValueError> for SigmaParsingError {
@@ -196,8 +247,9 @@ impl From<TokenAmountError> for SigmaParsingError {
 
 /// Sums up all iterator's box values
 /// Returns Err on overflow
-pub fn checked_sum<I: Iterator<Item = BoxValue>>(mut iter: I) -> Result<BoxValue, BoxValueError> {
-    iter.try_fold(BoxValue(0), |acc, v| acc.checked_add(&v))
+pub fn checked_sum<I: IntoIterator<Item = BoxValue>>(iter: I) -> Result<BoxValue, BoxValueError> {
+    iter.into_iter()
+        .try_fold(BoxValue(0), |acc, v| acc.checked_add(&v))
         .map_or_else(Err, |v| {
             if v.0 == 0 {
                 // input list was empty (sum is zero)
@@ -330,4 +382,37 @@ pub mod tests {
             assert_eq!(*checked_sum.as_u64(), expected_sum);
         }
     }
+
+    #[test]
+    fn test_erg_str_roundtrip() {
+        let v = BoxValue::from_erg_str("1.234 ERG").unwrap();
+        assert_eq!(*v.as_u64(), 1234000000);
+        assert_eq!(v.to_erg_string(), "1.234 ERG");
+        assert_eq!(
+            BoxValue::from_erg_str(".001").unwrap().to_erg_string(),
+            "0.001 ERG"
+        );
+        assert_eq!(
+            BoxValue::from_erg_str("2ERG").unwrap().to_erg_string(),
+            "2 ERG"
+        );
+        assert_eq!(
+            BoxValue::from_erg_str("0.000000001 ERG"),
+            Err(BoxValueError::OutOfBounds(1))
+        );
+        assert_eq!(
+            BoxValue::from_erg_str("10000000000 ERG"),
+            Err(BoxValueError::OutOfBounds(10000000000000000000))
+        );
+        assert_eq!(
+            BoxValue::from_erg_str("100000000000 ERG"),
+            Err(BoxValueError::Overflow)
+        );
+        for invalid in ["", "ERG", ".", "1.2.3", "-1", "1.0000000001", "1,5 ERG"] {
+            assert_eq!(
+                BoxValue::from_erg_str(invalid),
+                Err(BoxValueError::InvalidErgAmount(invalid.to_string()))
+            );
+        }
+    }
 }
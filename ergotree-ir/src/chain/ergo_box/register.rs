@@ -132,7 +132,15 @@ impl SigmaSerializable for NonMandatoryRegisters {
         let regs_num = r.get_u8()?;
         let mut additional_regs = Vec::with_capacity(regs_num as usize);
         for idx in 0..regs_num {
-            let expr = Expr::sigma_parse(r)?;
+            let expr = r.parse_in_context(
+                || {
+                    format!(
+                        "register R{}",
+                        NonMandatoryRegisterId::START_INDEX + idx as usize
+                    )
+                },
+                Expr::sigma_parse,
+            )?;
             let reg_val = match expr {
                 Expr::Const(c) => RegisterValue::Parsed(c),
                 Expr::Tuple(t) => {
@@ -282,11 +290,65 @@ pub(crate) mod arbitrary {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mir::constant::arbitrary::ArbConstantParams;
+    use crate::mir::constant::Literal;
     use crate::serialization::sigma_serialize_roundtrip;
+    use crate::types::stuple::STuple;
+    use crate::types::stype::SType;
+    use proptest::collection::vec;
     use proptest::prelude::*;
 
+    fn coll_coll_byte() -> SType {
+        SType::SColl(SType::SColl(SType::SByte.into()).into())
+    }
+
+    /// Register layouts used by dApps
+    fn composite_type() -> impl Strategy<Value = SType> {
+        prop_oneof![
+            Just(coll_coll_byte()),
+            Just(SType::STuple(STuple::pair(
+                SType::SInt,
+                SType::SColl(SType::SByte.into())
+            ))),
+            Just(SType::SColl(
+                SType::STuple(STuple::pair(
+                    SType::SColl(SType::SByte.into()),
+                    SType::SLong
+                ))
+                .into()
+            )),
+            Just(SType::STuple(STuple::pair(
+                SType::STuple(STuple::pair(SType::SInt, SType::SLong)),
+                coll_coll_byte()
+            ))),
+            Just(SType::SColl(coll_coll_byte().into())),
+        ]
+    }
+
     proptest! {
 
+        #[test]
+        fn ser_roundtrip_composite(
+            regs in vec(
+                composite_type().prop_flat_map(|tpe| any_with::<Constant>(ArbConstantParams::Exact(tpe))),
+                0..=NonMandatoryRegisterId::NUM_REGS
+            )
+        ) {
+            let regs = NonMandatoryRegisters::try_from(regs).unwrap();
+            prop_assert_eq![sigma_serialize_roundtrip(&regs), regs];
+        }
+
+        #[test]
+        fn ser_roundtrip_nested(
+            regs in vec(
+                any_with::<Constant>(ArbConstantParams::AnyWithDepth(3)),
+                0..=NonMandatoryRegisterId::NUM_REGS
+            )
+        ) {
+            let regs = NonMandatoryRegisters::try_from(regs).unwrap();
+            prop_assert_eq![sigma_serialize_roundtrip(&regs), regs];
+        }
+
         #[test]
         fn hash_map_roundtrip(regs in any::<NonMandatoryRegisters>()) {
             let hash_map: HashMap<NonMandatoryRegisterId, RegisterValue> = regs.clone().into();
@@ -315,6 +377,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn composite_register_bytes() {
+        // Coll[Coll[Byte]] and (Int, Coll[Byte]) as serialized by sigmastate
+        let coll_coll: Constant = vec![vec![1u8, 2], vec![3u8]].into();
+        let pair = Constant {
+            tpe: SType::STuple(STuple::pair(SType::SInt, SType::SColl(SType::SByte.into()))),
+            v: Literal::Tup([Literal::Int(5), vec![0xffu8].into()].into()),
+        };
+        let regs = NonMandatoryRegisters::try_from(vec![coll_coll.clone(), pair.clone()]).unwrap();
+        assert_eq!(
+            base16::encode_lower(&regs.sigma_serialize_bytes().unwrap()),
+            "021a020201020103400e0a01ff"
+        );
+        let parsed = sigma_serialize_roundtrip(&regs);
+        assert_eq!(
+            parsed.get_constant(NonMandatoryRegisterId::R4).unwrap(),
+            Some(coll_coll)
+        );
+        assert_eq!(
+            parsed.get_constant(NonMandatoryRegisterId::R5).unwrap(),
+            Some(pair)
+        );
+    }
+
+    #[test]
+    fn option_register_not_serializable() {
+        // Option data serialization is not supported by the protocol (before v6)
+        let c: Constant = Some(1i32).into();
+        let regs = NonMandatoryRegisters::try_from(vec![c]).unwrap();
+        assert!(regs.sigma_serialize_bytes().is_err());
+        // Option[Int] type followed by Some(1)
+        let reg_value = RegisterValue::sigma_parse_bytes(&[0x28, 0x01, 0x02]);
+        assert!(reg_value.as_constant().is_err());
+        assert_eq!(reg_value.sigma_serialize_bytes(), vec![0x28, 0x01, 0x02]);
+    }
+
     #[test]
     fn test_empty() {
         assert!(NonMandatoryRegisters::empty().is_empty());
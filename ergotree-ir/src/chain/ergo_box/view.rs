@@ -0,0 +1,321 @@
+//! Zero-copy views of the serialized boxes.
+//! On parsing only the positions of the fields in the shared buffer are stored, while the
+//! guarding script, tokens and registers are parsed on demand. Box id is computed from the
+//! serialized bytes without building an `ErgoBox`.
+
+use std::io::Write;
+use std::ops::Range;
+
+use bytes::Bytes;
+use ergo_chain_types::Digest32;
+use sigma_ser::vlq_encode::ReadSigmaVlqExt;
+use sigma_ser::vlq_encode::WriteSigmaVlqExt;
+use sigma_util::hash::Blake2b256Writer;
+
+use crate::chain::token::Token;
+use crate::chain::token::TokenAmount;
+use crate::chain::token::TokenId;
+use crate::chain::tx_id::TxId;
+use crate::ergo_tree::ErgoTree;
+use crate::ergo_tree::ErgoTreeHeader;
+use crate::serialization::sigma_byte_reader;
+use crate::serialization::SigmaParsingError;
+use crate::serialization::SigmaSerializable;
+
+use super::box_value::BoxValue;
+use super::BoxId;
+use super::BoxTokens;
+use super::ErgoBox;
+use super::ErgoBoxCandidate;
+use super::NonMandatoryRegisters;
+
+/// View of the serialized box candidate (box without transaction id and index) over a shared
+/// buffer
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ErgoBoxCandidateView {
+    bytes: Bytes,
+    value: BoxValue,
+    creation_height: u32,
+    ergo_tree: Range<usize>,
+    tokens: Range<usize>,
+    tokens_count: u8,
+    registers: Range<usize>,
+    token_ids: Option<Bytes>,
+}
+
+impl ErgoBoxCandidateView {
+    /// Parse the box candidate starting at `pos` in `buf`.
+    /// `token_ids` are the serialized distinct token ids of the transaction if the box tokens
+    /// are stored as indices (transaction outputs), `None` for the standalone box.
+    ///
+    /// ErgoTree without the size in the header and the registers have no length prefix, so they
+    /// are parsed (and dropped) to find the end.
+    pub fn parse_at(
+        buf: &Bytes,
+        pos: usize,
+        token_ids: Option<Bytes>,
+    ) -> Result<ErgoBoxCandidateView, SigmaParsingError> {
+        let mut r = sigma_byte_reader::from_bytes(&buf[..]);
+        r.set_position(pos as u64);
+        let value = BoxValue::sigma_parse(&mut r)?;
+        let tree_start = r.position();
+        let header = ErgoTreeHeader::sigma_parse(&mut r)?;
+        if header.has_size() {
+            let tree_size = r.get_u32()?;
+            r.set_position(r.position() + tree_size as u64);
+        } else {
+            r.set_position(tree_start);
+            ErgoTree::sigma_parse(&mut r)?;
+        }
+        let tree_end = r.position();
+        let creation_height = r.get_u32()?;
+        let tokens_count = r.get_u8()?;
+        if tokens_count as usize > ErgoBox::MAX_TOKENS_COUNT {
+            return Err(SigmaParsingError::ValueOutOfBounds(
+                "too many tokens in box".to_string(),
+            ));
+        }
+        let tokens_start = r.position();
+        for _ in 0..tokens_count {
+            match &token_ids {
+                Some(ids) => {
+                    token_id_at(ids, r.get_u32()?)?;
+                }
+                None => r.set_position(r.position() + Digest32::SIZE as u64),
+            }
+            TokenAmount::try_from(r.get_u64()?)?;
+        }
+        let registers_start = r.position();
+        NonMandatoryRegisters::sigma_parse(&mut r)?;
+        let end = r.position() as usize;
+        let offset = |p: u64| p as usize - pos;
+        Ok(ErgoBoxCandidateView {
+            bytes: buf.slice(pos..end),
+            value,
+            creation_height,
+            ergo_tree: offset(tree_start)..offset(tree_end),
+            tokens: offset(tokens_start)..offset(registers_start),
+            tokens_count,
+            registers: offset(registers_start)..end - pos,
+            token_ids,
+        })
+    }
+
+    /// Serialized box candidate (with token indices if the box is a transaction output)
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Amount of money associated with the box
+    pub fn value(&self) -> BoxValue {
+        self.value
+    }
+
+    /// Height when a transaction containing the box was created
+    pub fn creation_height(&self) -> u32 {
+        self.creation_height
+    }
+
+    /// Serialized guarding script
+    pub fn ergo_tree_bytes(&self) -> &[u8] {
+        &self.bytes[self.ergo_tree.clone()]
+    }
+
+    /// Parse the guarding script
+    pub fn ergo_tree(&self) -> Result<ErgoTree, SigmaParsingError> {
+        ErgoTree::sigma_parse_bytes(self.ergo_tree_bytes())
+    }
+
+    /// Parse the tokens
+    pub fn tokens(&self) -> Result<Option<BoxTokens>, SigmaParsingError> {
+        let mut tokens = Vec::with_capacity(self.tokens_count as usize);
+        self.for_each_token(|id, amount, _| {
+            tokens.push(Token {
+                token_id: TokenId::sigma_parse_bytes(id)?,
+                amount: amount.try_into()?,
+            });
+            Ok(())
+        })?;
+        Ok(if tokens.is_empty() {
+            None
+        } else {
+            Some(BoxTokens::from_vec(tokens)?)
+        })
+    }
+
+    /// Parse the additional registers
+    pub fn additional_registers(&self) -> Result<NonMandatoryRegisters, SigmaParsingError> {
+        NonMandatoryRegisters::sigma_parse_bytes(&self.bytes[self.registers.clone()])
+    }
+
+    /// Parse the box candidate
+    pub fn to_box_candidate(&self) -> Result<ErgoBoxCandidate, SigmaParsingError> {
+        Ok(ErgoBoxCandidate {
+            value: self.value,
+            ergo_tree: self.ergo_tree()?,
+            tokens: self.tokens()?,
+            additional_registers: self.additional_registers()?,
+            creation_height: self.creation_height,
+        })
+    }
+
+    /// Write the standalone box candidate serialization (full token ids)
+    fn write_standalone<W: Write>(&self, w: &mut W) -> Result<(), SigmaParsingError> {
+        if self.token_ids.is_none() {
+            w.write_all(&self.bytes)?;
+            return Ok(());
+        }
+        w.write_all(&self.bytes[..self.tokens.start])?;
+        self.for_each_token(|id, _, amount_bytes| {
+            w.write_all(id)?;
+            w.write_all(amount_bytes)?;
+            Ok(())
+        })?;
+        w.write_all(&self.bytes[self.registers.clone()])?;
+        Ok(())
+    }
+
+    /// Call `f` with token id bytes, amount and serialized amount for every token
+    fn for_each_token<F>(&self, mut f: F) -> Result<(), SigmaParsingError>
+    where
+        F: FnMut(&[u8], u64, &[u8]) -> Result<(), SigmaParsingError>,
+    {
+        let tokens = &self.bytes[self.tokens.clone()];
+        let mut r = sigma_byte_reader::from_bytes(tokens);
+        for _ in 0..self.tokens_count {
+            let id = match &self.token_ids {
+                Some(ids) => token_id_at(ids, r.get_u32()?)?,
+                None => {
+                    let start = r.position() as usize;
+                    r.set_position((start + Digest32::SIZE) as u64);
+                    &tokens[start..start + Digest32::SIZE]
+                }
+            };
+            let amount_start = r.position() as usize;
+            let amount = r.get_u64()?;
+            f(id, amount, &tokens[amount_start..r.position() as usize])?;
+        }
+        Ok(())
+    }
+}
+
+/// View of the serialized box over a shared buffer
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ErgoBoxView {
+    candidate: ErgoBoxCandidateView,
+    box_id: BoxId,
+    transaction_id: TxId,
+    index: u16,
+}
+
+impl ErgoBoxView {
+    /// Parse the serialized box (see [`ErgoBox`] serialization), `bytes` are kept as the
+    /// backing buffer
+    pub fn sigma_parse_bytes(bytes: Bytes) -> Result<ErgoBoxView, SigmaParsingError> {
+        let candidate = ErgoBoxCandidateView::parse_at(&bytes, 0, None)?;
+        let mut r = sigma_byte_reader::from_bytes(&bytes[candidate.bytes.len()..]);
+        let transaction_id = TxId::sigma_parse(&mut r)?;
+        let index = r.get_u16()?;
+        ErgoBoxView::from_box_candidate(candidate, transaction_id, index)
+    }
+
+    /// Create the box view from the box candidate view by adding transaction id
+    /// and index of the box in the transaction (box id is calculated)
+    pub fn from_box_candidate(
+        candidate: ErgoBoxCandidateView,
+        transaction_id: TxId,
+        index: u16,
+    ) -> Result<ErgoBoxView, SigmaParsingError> {
+        let mut hasher = Blake2b256Writer::new();
+        candidate.write_standalone(&mut hasher)?;
+        hasher.write_all(transaction_id.0.as_ref())?;
+        hasher.put_u16(index)?;
+        Ok(ErgoBoxView {
+            candidate,
+            box_id: Digest32::from(hasher.finalize()).into(),
+            transaction_id,
+            index,
+        })
+    }
+
+    /// Box id
+    pub fn box_id(&self) -> BoxId {
+        self.box_id
+    }
+
+    /// Id of transaction which created the box
+    pub fn transaction_id(&self) -> TxId {
+        self.transaction_id
+    }
+
+    /// Index of the box in the transaction outputs
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    /// Box candidate view (value, guarding script, tokens and registers)
+    pub fn candidate(&self) -> &ErgoBoxCandidateView {
+        &self.candidate
+    }
+
+    /// Amount of money associated with the box
+    pub fn value(&self) -> BoxValue {
+        self.candidate.value
+    }
+
+    /// Serialized guarding script
+    pub fn ergo_tree_bytes(&self) -> &[u8] {
+        self.candidate.ergo_tree_bytes()
+    }
+
+    /// Parse the box
+    pub fn to_ergo_box(&self) -> Result<ErgoBox, SigmaParsingError> {
+        self.to_ergo_box_with_tree(self.candidate.ergo_tree()?)
+    }
+
+    /// Parse the box with the already parsed guarding script (e.g. taken from
+    /// [`crate::ergo_tree::ErgoTreeCache`]), which is expected to match [`Self::ergo_tree_bytes`]
+    pub fn to_ergo_box_with_tree(&self, ergo_tree: ErgoTree) -> Result<ErgoBox, SigmaParsingError> {
+        Ok(ErgoBox {
+            box_id: self.box_id,
+            value: self.candidate.value,
+            ergo_tree,
+            tokens: self.candidate.tokens()?,
+            additional_registers: self.candidate.additional_registers()?,
+            creation_height: self.candidate.creation_height,
+            transaction_id: self.transaction_id,
+            index: self.index,
+        })
+    }
+}
+
+fn token_id_at(token_ids: &Bytes, index: u32) -> Result<&[u8], SigmaParsingError> {
+    let start = index as usize * Digest32::SIZE;
+    token_ids
+        .get(start..start + Digest32::SIZE)
+        .ok_or_else(|| SigmaParsingError::Misc("failed to find token id in tx digests".to_string()))
+}
+
+#[allow(clippy::panic)]
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #[test]
+        fn ergo_box_view_roundtrip(b in any::<ErgoBox>()) {
+            let bytes = Bytes::from(b.sigma_serialize_bytes().unwrap());
+            let view = ErgoBoxView::sigma_parse_bytes(bytes).unwrap();
+            prop_assert_eq![view.box_id(), b.box_id()];
+            prop_assert_eq![view.value(), b.value];
+            prop_assert_eq![
+                view.ergo_tree_bytes(),
+                &b.ergo_tree.sigma_serialize_bytes().unwrap()[..]
+            ];
+            prop_assert_eq![view.to_ergo_box().unwrap(), b];
+        }
+    }
+}
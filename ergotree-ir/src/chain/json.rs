@@ -11,6 +11,7 @@ use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serializer;
 
+pub(crate) mod bigint256;
 pub(crate) mod box_value;
 pub(crate) mod ergo_box;
 pub mod ergo_tree;
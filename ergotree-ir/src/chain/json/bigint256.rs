@@ -0,0 +1,94 @@
+//! Code to implement `BigInt256` JSON encoding
+
+use std::convert::TryFrom;
+
+use num_bigint::BigInt;
+use num_traits::Num;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+
+use crate::bigint256::BigInt256;
+
+/// Helper enum to deserialize `BigInt256` from the forms found in the wild: decimal string
+/// (node's `renderedValue`), `0x`-prefixed base16 of the two's complement big-endian bytes (Java's
+/// `BigInteger.toByteArray`) or JSON number. Encoded as a decimal string always, since JSON numbers
+/// are not precise enough for 256-bit values in most parsers.
+///
+/// We use `serde_json::Number` due to a known `serde_json` bug with the untagged enums
+/// (see `BoxValueJson`).
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+pub(crate) enum BigInt256Json {
+    Number(serde_json::Number),
+    Str(String),
+}
+
+impl TryFrom<BigInt256Json> for BigInt256 {
+    type Error = String;
+
+    fn try_from(value: BigInt256Json) -> Result<Self, Self::Error> {
+        match value {
+            BigInt256Json::Number(n) => parse_decimal(&n.to_string()),
+            BigInt256Json::Str(s) => {
+                let s = s.trim();
+                match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+                    Some(hex) => {
+                        let bytes =
+                            decode_base16(hex, DecodingMode::Strict).map_err(|e| e.to_string())?;
+                        BigInt256::try_from(bytes.as_slice())
+                    }
+                    None => parse_decimal(s),
+                }
+            }
+        }
+    }
+}
+
+impl From<BigInt256> for String {
+    fn from(value: BigInt256) -> Self {
+        value.to_string()
+    }
+}
+
+fn parse_decimal(s: &str) -> Result<BigInt256, String> {
+    let n = BigInt::from_str_radix(s, 10)
+        .map_err(|e| format!("BigInt256: cannot parse {:?}: {}", s, e))?;
+    BigInt256::try_from(n)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use num_traits::Bounded;
+
+    use super::*;
+
+    #[test]
+    fn parse_forms() {
+        let expected = BigInt256::from(-1i64);
+        for json in [r#""-1""#, r#""0xff""#, "-1", r#"" -1 ""#] {
+            let parsed: BigInt256 = serde_json::from_str(json).unwrap();
+            assert_eq!(parsed, expected, "{}", json);
+        }
+        let parsed: BigInt256 = serde_json::from_str(r#""0x010000000000000000""#).unwrap();
+        assert_eq!(parsed.to_string(), "18446744073709551616");
+        assert!(serde_json::from_str::<BigInt256>("1.5").is_err());
+        assert!(serde_json::from_str::<BigInt256>(r#""0xzz""#).is_err());
+        // out of 256-bit range
+        let too_large = format!(r#""{}0""#, BigInt256::max_value());
+        assert!(serde_json::from_str::<BigInt256>(&too_large).is_err());
+    }
+
+    #[test]
+    fn roundtrip() {
+        for v in [
+            BigInt256::min_value(),
+            BigInt256::max_value(),
+            BigInt256::from(0i64),
+        ] {
+            let json = serde_json::to_string(&v).unwrap();
+            assert_eq!(json, format!("\"{}\"", v));
+            assert_eq!(serde_json::from_str::<BigInt256>(&json).unwrap(), v);
+        }
+    }
+}
@@ -11,6 +11,7 @@ use crate::ergo_tree::ErgoTree;
 use crate::serialization::SigmaParsingError;
 use crate::serialization::SigmaSerializationError;
 use ergo_chain_types::Base16DecodedBytes;
+use sigma_util::encoding::DecodingError;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::str::FromStr;
@@ -215,7 +216,7 @@ struct ConstantWrapper(Vec<u8>);
 #[derive(Error, PartialEq, Eq, Debug, Clone, From)]
 pub enum ConstantParsingError {
     #[error("Base16 decoding error: {0}")]
-    DecodeError(base16::DecodeError),
+    DecodeError(DecodingError),
     #[error("Deserialization error: {0}")]
     DeserializationError(SigmaParsingError),
 }
@@ -244,10 +245,17 @@ impl FromStr for RichConstant {
 mod tests {
     use std::convert::TryInto;
 
+    use crate::bigint256::BigInt256;
     use crate::chain::ergo_box::ErgoBox;
     use crate::chain::ergo_box::NonMandatoryRegisterId;
     use crate::chain::ergo_box::NonMandatoryRegisters;
     use crate::chain::token::Token;
+    use crate::mir::constant::Constant;
+    use crate::mir::constant::Literal;
+    use crate::mir::value::CollKind;
+    use crate::types::stuple::STuple;
+    use crate::types::stype::SType;
+    use num_traits::Num;
     use pretty_assertions::assert_eq;
     use proptest::prelude::*;
 
@@ -313,6 +321,93 @@ mod tests {
         assert_eq!(regs.len(), 1)
     }
 
+    #[test]
+    fn parse_registers_bigint() {
+        // node API (plain hex) and explorer API (rendered as decimal string) forms
+        let json = r#"
+            {
+                "R4": "0609010000000000000000",
+                "R5": {"serializedValue":"0601ff","sigmaType":"SBigInt","renderedValue":"-1"},
+                "R6": {"rawValue":"06020100","valueType":"BigInt","decodedValue":"256"}
+            }
+        "#;
+        let regs: NonMandatoryRegisters = serde_json::from_str(json).unwrap();
+        let expected: [(NonMandatoryRegisterId, BigInt256); 3] = [
+            (
+                NonMandatoryRegisterId::R4,
+                BigInt256::from_str_radix("18446744073709551616", 10).unwrap(),
+            ),
+            (NonMandatoryRegisterId::R5, BigInt256::from(-1i64)),
+            (NonMandatoryRegisterId::R6, BigInt256::from(256i64)),
+        ];
+        for (reg_id, v) in expected {
+            assert_eq!(
+                regs.get_constant(reg_id).unwrap().unwrap(),
+                Constant::from(v)
+            );
+        }
+        let encoded = serde_json::to_value(&regs).unwrap();
+        assert_eq!(encoded["R4"], "0609010000000000000000");
+    }
+
+    #[test]
+    fn parse_registers_composite() {
+        // node API register hex of Coll[Coll[Byte]], (Int, Coll[Byte]) and Coll[(Coll[Byte], Long)]
+        let json = r#"
+            {
+                "R4": "1a020201020103",
+                "R5": "400e0a01ff",
+                "R6": "0c4d0e0101abc801"
+            }
+        "#;
+        let regs: NonMandatoryRegisters = serde_json::from_str(json).unwrap();
+        let expected: [(NonMandatoryRegisterId, Constant); 3] = [
+            (
+                NonMandatoryRegisterId::R4,
+                vec![vec![1u8, 2], vec![3u8]].into(),
+            ),
+            (
+                NonMandatoryRegisterId::R5,
+                Constant {
+                    tpe: SType::STuple(STuple::pair(
+                        SType::SInt,
+                        SType::SColl(SType::SByte.into()),
+                    )),
+                    v: Literal::Tup([Literal::Int(5), vec![0xffu8].into()].into()),
+                },
+            ),
+            (
+                NonMandatoryRegisterId::R6,
+                Constant {
+                    tpe: SType::SColl(
+                        SType::STuple(STuple::pair(
+                            SType::SColl(SType::SByte.into()),
+                            SType::SLong,
+                        ))
+                        .into(),
+                    ),
+                    v: Literal::Coll(CollKind::WrappedColl {
+                        elem_tpe: SType::STuple(STuple::pair(
+                            SType::SColl(SType::SByte.into()),
+                            SType::SLong,
+                        )),
+                        items: vec![Literal::Tup(
+                            [vec![0xabu8].into(), Literal::Long(100)].into(),
+                        )],
+                    }),
+                },
+            ),
+        ];
+        for (reg_id, c) in expected {
+            assert_eq!(regs.get_constant(reg_id).unwrap().unwrap(), c);
+        }
+        // encoded back to the same node API hex
+        let encoded = serde_json::to_value(&regs).unwrap();
+        assert_eq!(encoded["R4"], "1a020201020103");
+        assert_eq!(encoded["R5"], "400e0a01ff");
+        assert_eq!(encoded["R6"], "0c4d0e0101abc801");
+    }
+
     #[test]
     fn parse_ergo_box() {
         let box_json = r#"{
@@ -3,6 +3,7 @@
 use crate::ergo_tree::ErgoTree;
 use crate::serialization::SigmaSerializable;
 use serde::{Deserialize, Deserializer, Serializer};
+use sigma_util::encoding::{decode_base16, DecodingMode};
 
 use super::serialize_bytes;
 
@@ -19,13 +20,16 @@ where
     serialize_bytes(&bytes[..], serializer)
 }
 
-pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<ErgoTree, D::Error>
+/// Deserializer from the Base16-encoded serialized ErgoTree (used in the EIP-12 JSON of ergo-lib)
+pub fn deserialize<'de, D>(deserializer: D) -> Result<ErgoTree, D::Error>
 where
     D: Deserializer<'de>,
 {
     use serde::de::Error;
     String::deserialize(deserializer)
-        .and_then(|str| base16::decode(&str).map_err(|err| Error::custom(err.to_string())))
+        .and_then(|str| {
+            decode_base16(&str, DecodingMode::Strict).map_err(|err| Error::custom(err.to_string()))
+        })
         .and_then(|bytes| {
             ErgoTree::sigma_parse_bytes(&bytes).map_err(|error| Error::custom(error.to_string()))
         })
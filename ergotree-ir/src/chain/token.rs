@@ -5,17 +5,22 @@ use crate::serialization::{
     sigma_byte_reader::SigmaByteRead, sigma_byte_writer::SigmaByteWrite, SigmaParsingError,
     SigmaSerializable,
 };
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::str::FromStr;
 
 use super::ergo_box::BoxId;
 use derive_more::From;
 use derive_more::Into;
 use ergo_chain_types::{Digest32, DigestNError};
+use sigma_ser::byte_counter::ByteCounter;
+use sigma_ser::vlq_encode::WriteSigmaVlqExt;
 use sigma_ser::ScorexSerializable;
 use thiserror::Error;
 
 /// newtype for token id
-#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, From, Into)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy, Clone, From, Into)]
 #[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TokenId(Digest32);
 
@@ -27,6 +32,55 @@ impl TokenId {
     pub fn from_base64(s: &str) -> Result<TokenId, DigestNError> {
         Digest32::from_base64(s).map(Into::into)
     }
+
+    /// Constant-time equality check
+    pub fn ct_eq(&self, other: &TokenId) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl std::fmt::Display for TokenId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for TokenId {
+    type Err = DigestNError;
+
+    /// Parse from base16-encoded string (exactly 32 bytes, decoded in constant time)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(TokenId(Digest32::from_str(s)?))
+    }
+}
+
+impl TryFrom<String> for TokenId {
+    type Error = DigestNError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<[u8; 32]> for TokenId {
+    fn from(bytes: [u8; 32]) -> Self {
+        TokenId(Digest32::from(bytes))
+    }
+}
+
+impl From<TokenId> for [u8; 32] {
+    fn from(v: TokenId) -> Self {
+        v.0.into()
+    }
+}
+
+impl TryFrom<&[u8]> for TokenId {
+    type Error = DigestNError;
+
+    /// Parse from the bytes (exactly 32 bytes)
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Digest32::try_from(bytes).map(Self)
+    }
 }
 
 impl From<BoxId> for TokenId {
@@ -134,6 +188,29 @@ pub enum TokenAmountError {
     /// Overflow
     #[error("Overflow")]
     Overflow,
+    /// Sum of the token amounts is out of bounds
+    #[error("Token {0} amount overflow")]
+    TokenOverflow(TokenId),
+    /// Not enough tokens to subtract
+    #[error("Not enough tokens {token_id}: {available} available, {required} required")]
+    NotEnoughTokens {
+        /// Token id
+        token_id: TokenId,
+        /// Available amount (0 if the token is missing)
+        available: u64,
+        /// Required amount
+        required: u64,
+    },
+    /// Token amount is less than the number of parts
+    #[error("Token {token_id} amount {amount} cannot be split into {parts} parts")]
+    CannotSplit {
+        /// Token id
+        token_id: TokenId,
+        /// Token amount
+        amount: u64,
+        /// Number of parts
+        parts: u64,
+    },
 }
 
 impl TryFrom<u64> for TokenAmount {
@@ -178,6 +255,94 @@ pub struct Token {
     pub amount: TokenAmount,
 }
 
+impl Token {
+    /// Size of the token in the serialized box (token id and VLQ-encoded amount) in bytes
+    pub fn serialized_size(&self) -> usize {
+        let mut counter = ByteCounter::new();
+        // writing to the counter does not fail
+        #[allow(clippy::unwrap_used)]
+        counter.put_u64(self.amount.0).unwrap();
+        TokenId::SIZE + counter.count()
+    }
+
+    /// Split the token into `parts` tokens with the amounts differing by at most one (the first
+    /// ones get the remainder), e.g. to spread the token across the outputs
+    pub fn split(&self, parts: u64) -> Result<Vec<Token>, TokenAmountError> {
+        let amount = self.amount.0;
+        if parts == 0 || parts > amount {
+            return Err(TokenAmountError::CannotSplit {
+                token_id: self.token_id,
+                amount,
+                parts,
+            });
+        }
+        let (part, remainder) = (amount / parts, amount % parts);
+        Ok((0..parts)
+            .map(|i| Token {
+                token_id: self.token_id,
+                amount: TokenAmount(part + u64::from(i < remainder)),
+            })
+            .collect())
+    }
+}
+
+/// Add the token to the token amounts (summing the amounts of the same token)
+pub fn add_token_amount(
+    amounts: &mut HashMap<TokenId, TokenAmount>,
+    token: &Token,
+) -> Result<(), TokenAmountError> {
+    match amounts.get_mut(&token.token_id) {
+        Some(amt) => {
+            *amt = amt
+                .checked_add(&token.amount)
+                .map_err(|_| TokenAmountError::TokenOverflow(token.token_id))?
+        }
+        None => {
+            amounts.insert(token.token_id, token.amount);
+        }
+    }
+    Ok(())
+}
+
+/// Total amounts of the tokens (e.g. of all the boxes) by token id
+pub fn sum_token_amounts<'a, I: IntoIterator<Item = &'a Token>>(
+    tokens: I,
+) -> Result<HashMap<TokenId, TokenAmount>, TokenAmountError> {
+    let mut res = HashMap::new();
+    tokens
+        .into_iter()
+        .try_for_each(|t| add_token_amount(&mut res, t))?;
+    Ok(res)
+}
+
+/// Subtract the token amounts, the token is removed if the whole amount is subtracted.
+/// Returns an error naming the token if there is not enough of it.
+pub fn subtract_token_amounts(
+    amounts: &HashMap<TokenId, TokenAmount>,
+    to_subtract: &HashMap<TokenId, TokenAmount>,
+) -> Result<HashMap<TokenId, TokenAmount>, TokenAmountError> {
+    let mut res = amounts.clone();
+    to_subtract.iter().try_for_each(|(id, sub_amt)| {
+        let available = res.get(id).map(|amt| amt.0).unwrap_or(0);
+        match available.cmp(&sub_amt.0) {
+            Ordering::Less => Err(TokenAmountError::NotEnoughTokens {
+                token_id: *id,
+                available,
+                required: sub_amt.0,
+            }),
+            Ordering::Equal => {
+                res.remove(id);
+                Ok(())
+            }
+            Ordering::Greater => {
+                res.insert(*id, TokenAmount(available - sub_amt.0));
+                Ok(())
+            }
+        }
+    })?;
+    Ok(res)
+}
+
 impl From<(TokenId, TokenAmount)> for Token {
     fn from(token_pair: (TokenId, TokenAmount)) -> Self {
         Token {
@@ -279,13 +444,14 @@ pub mod arbitrary {
     }
 }
 
-#[allow(clippy::panic)]
+#[allow(clippy::panic, clippy::unwrap_used)]
 #[cfg(test)]
 mod tests {
 
-    use crate::chain::token::TokenId;
+    use crate::chain::token::*;
     use crate::serialization::sigma_serialize_roundtrip;
     use proptest::prelude::*;
+    use std::str::FromStr;
 
     proptest! {
 
@@ -293,5 +459,74 @@ mod tests {
         fn token_id_roundtrip(v in any::<TokenId>()) {
             prop_assert_eq![sigma_serialize_roundtrip(&v), v];
         }
+
+        #[test]
+        fn token_id_str_roundtrip(v in any::<TokenId>()) {
+            prop_assert_eq![TokenId::from_str(&v.to_string()).unwrap(), v];
+            prop_assert_eq![v.to_string(), String::from(v)];
+        }
+
+        #[test]
+        fn token_id_bytes_roundtrip(v in any::<TokenId>()) {
+            prop_assert_eq![TokenId::from(<[u8; 32]>::from(v)), v];
+            prop_assert_eq![TokenId::try_from(v.as_ref()).unwrap(), v];
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn token_id_json_matches_display() {
+        let token_id =
+            TokenId::from_str("3130a82e45842aebb888742868e055e2f554ab7d92f233f2c828ed4a43793710")
+                .unwrap();
+        let json = serde_json::to_string(&token_id).unwrap();
+        assert_eq!(json, format!("\"{}\"", token_id));
+        assert_eq!(serde_json::from_str::<TokenId>(&json).unwrap(), token_id);
+        assert!(serde_json::from_str::<TokenId>("\"3130a82e\"").is_err());
+    }
+
+    proptest! {
+
+        #[test]
+        fn split_and_sum_tokens(token in any::<Token>(), parts in 1u64..10) {
+            prop_assume!(parts <= *token.amount.as_u64());
+            let split = token.split(parts).unwrap();
+            prop_assert_eq!(split.len() as u64, parts);
+            let sum = sum_token_amounts(&split).unwrap();
+            prop_assert_eq!(sum.get(&token.token_id), Some(&token.amount));
+        }
+    }
+
+    #[test]
+    fn token_amounts_errors_name_token() {
+        let token_id =
+            TokenId::from_str("3130a82e45842aebb888742868e055e2f554ab7d92f233f2c828ed4a43793710")
+                .unwrap();
+        let max = Token {
+            token_id,
+            amount: TokenAmount::try_from(TokenAmount::MAX_RAW).unwrap(),
+        };
+        assert_eq!(
+            sum_token_amounts(&[max.clone(), max.clone()]),
+            Err(TokenAmountError::TokenOverflow(token_id))
+        );
+        let one: HashMap<TokenId, TokenAmount> =
+            vec![(token_id, TokenAmount::MIN)].into_iter().collect();
+        let two = sum_token_amounts(&[
+            (token_id, TokenAmount::MIN).into(),
+            (token_id, TokenAmount::MIN).into(),
+        ])
+        .unwrap();
+        assert_eq!(subtract_token_amounts(&two, &one).unwrap(), one);
+        assert!(subtract_token_amounts(&one, &one).unwrap().is_empty());
+        assert_eq!(
+            subtract_token_amounts(&one, &two),
+            Err(TokenAmountError::NotEnoughTokens {
+                token_id,
+                available: 1,
+                required: 2
+            })
+        );
+        assert!(max.split(0).is_err());
     }
 }
@@ -1,8 +1,12 @@
 //! Transaction id type
+use std::convert::TryFrom;
+use std::str::FromStr;
+
 use derive_more::Display;
 
 use derive_more::From;
 use ergo_chain_types::Digest32;
+use ergo_chain_types::DigestNError;
 use sigma_ser::ScorexSerializable;
 
 use crate::serialization::sigma_byte_reader::SigmaByteRead;
@@ -22,6 +26,11 @@ impl TxId {
     pub fn zero() -> TxId {
         TxId(Digest32::zero())
     }
+
+    /// Constant-time equality check
+    pub fn ct_eq(&self, other: &TxId) -> bool {
+        self.0.ct_eq(&other.0)
+    }
 }
 
 impl SigmaSerializable for TxId {
@@ -45,3 +54,41 @@ impl AsRef<[u8]> for TxId {
         self.0.as_ref()
     }
 }
+
+impl FromStr for TxId {
+    type Err = DigestNError;
+
+    /// Parse from base16-encoded string (exactly 32 bytes, decoded in constant time)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(TxId(Digest32::from_str(s)?))
+    }
+}
+
+impl TryFrom<String> for TxId {
+    type Error = DigestNError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<[u8; 32]> for TxId {
+    fn from(bytes: [u8; 32]) -> Self {
+        TxId(Digest32::from(bytes))
+    }
+}
+
+impl From<TxId> for [u8; 32] {
+    fn from(v: TxId) -> Self {
+        v.0.into()
+    }
+}
+
+impl TryFrom<&[u8]> for TxId {
+    type Error = DigestNError;
+
+    /// Parse from the bytes (exactly 32 bytes)
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Digest32::try_from(bytes).map(Self)
+    }
+}
@@ -1,5 +1,6 @@
 //! ErgoTree
 use crate::mir::constant::Constant;
+use crate::mir::constant::Literal;
 use crate::mir::constant::TryExtractFromError;
 use crate::mir::expr::Expr;
 use crate::serialization::SigmaSerializationError;
@@ -10,6 +11,9 @@ use crate::serialization::{
     SigmaParsingError, SigmaSerializable,
 };
 use crate::sigma_protocol::sigma_boolean::ProveDlog;
+use crate::sigma_protocol::sigma_boolean::SigmaBoolean;
+use crate::type_check::check_expr;
+use crate::type_check::TypeCheckError;
 use crate::types::stype::SType;
 use io::Cursor;
 use sigma_ser::vlq_encode::ReadSigmaVlqExt;
@@ -22,7 +26,9 @@ use std::io;
 use std::io::Read;
 use thiserror::Error;
 
+mod cache;
 mod tree_header;
+pub use cache::*;
 pub use tree_header::*;
 
 /// Parsed ErgoTree
@@ -108,6 +114,9 @@ pub enum ErgoTreeError {
     /// IO error
     #[error("IO error: {0:?}")]
     IoError(String),
+    /// Root expr type check error
+    #[error("Type check error: {0}")]
+    TypeCheckError(TypeCheckError),
 }
 
 /// The root of ErgoScript IR. Serialized instances of this class are self sufficient and can be passed around.
@@ -227,6 +236,15 @@ impl ErgoTree {
         }
     }
 
+    /// Same as [`Self::proposition`], but the operand types of every node are checked (see
+    /// [`crate::type_check::check_expr`]), so that the malformed tree is rejected before the
+    /// evaluation
+    pub fn type_checked_proposition(&self) -> Result<Expr, ErgoTreeError> {
+        let expr = self.proposition()?;
+        check_expr(&expr)?;
+        Ok(expr)
+    }
+
     /// Prints with newlines
     pub fn debug_tree(&self) -> String {
         let tree = format!("{:#?}", self);
@@ -265,6 +283,14 @@ impl ErgoTree {
         self.parsed_tree().map(|tree| tree.constants.clone())
     }
 
+    /// Returns all constants (as stored in serialized ErgoTree) paired with their indices, which
+    /// can be used to replace them with [`ErgoTree::with_constant`],
+    /// or error if constants parsing were failed
+    pub fn constants(&self) -> Result<Vec<(usize, Constant)>, ErgoTreeError> {
+        self.parsed_tree()
+            .map(|tree| tree.constants.iter().cloned().enumerate().collect())
+    }
+
     /// Returns new ErgoTree with a new constant value for a given index in constants list (as
     /// stored in serialized ErgoTree), or an error. Note that the type of the new constant must
     /// coincide with that of the constant being replaced, or an error is returned too.
@@ -277,11 +303,66 @@ impl ErgoTree {
         ))
     }
 
+    /// Returns new ErgoTree with the constants replaced at the given indices (see
+    /// [`ErgoTree::with_constant`]). Fails on the first index out of bounds or type mismatch.
+    pub fn with_constants(
+        self,
+        constants: impl IntoIterator<Item = (usize, Constant)>,
+    ) -> Result<Self, ErgoTreeError> {
+        let parsed_tree = constants
+            .into_iter()
+            .try_fold(self.parsed_tree()?.clone(), |tree, (index, constant)| {
+                tree.with_constant(index, constant)
+            });
+        Ok(Self::Parsed(
+            parsed_tree.map_err(ErgoTreeConstantError::from)?,
+        ))
+    }
+
     /// Serialized proposition expression of SigmaProp type with
     /// ConstantPlaceholder nodes instead of Constant nodes
     pub fn template_bytes(&self) -> Result<Vec<u8>, ErgoTreeError> {
         self.clone().parsed_tree()?.template_bytes()
     }
+
+    /// Classify the tree by its root expression. Trivial trees (e.g. P2PK) can be reduced to
+    /// SigmaBoolean without the evaluation.
+    pub fn classify(&self) -> ErgoTreeClass {
+        let tree = match self.parsed_tree() {
+            Ok(tree) => tree,
+            Err(_) => return ErgoTreeClass::NonTrivial,
+        };
+        let resolve = |expr: &Expr| -> Option<Constant> {
+            match expr {
+                Expr::Const(c) => Some(c.clone()),
+                Expr::ConstPlaceholder(ph) => tree.constants.get(ph.id as usize).cloned(),
+                _ => None,
+            }
+        };
+        let root_const = match &tree.root {
+            Expr::BoolToSigmaProp(b) => resolve(&b.input),
+            root => resolve(root),
+        };
+        match root_const.map(|c| c.v) {
+            Some(Literal::Boolean(b)) => ErgoTreeClass::TrivialBool(b),
+            Some(Literal::SigmaProp(sp)) => match sp.value() {
+                SigmaBoolean::TrivialProp(b) => ErgoTreeClass::TrivialBool(*b),
+                sb => ErgoTreeClass::ConstSigmaProp(sb.clone()),
+            },
+            _ => ErgoTreeClass::NonTrivial,
+        }
+    }
+}
+
+/// Classification of the tree by its root expression (see [`ErgoTree::classify`])
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ErgoTreeClass {
+    /// Root is a boolean (or `sigmaProp` of a boolean) constant
+    TrivialBool(bool),
+    /// Root is a SigmaProp constant (e.g. P2PK tree)
+    ConstSigmaProp(SigmaBoolean),
+    /// Root has to be evaluated
+    NonTrivial,
 }
 
 /// Constants related errors
@@ -504,7 +585,11 @@ mod tests {
     use super::*;
     use crate::chain::address::AddressEncoder;
     use crate::chain::address::NetworkPrefix;
-    use crate::mir::constant::Literal;
+    use crate::mir::bin_op::BinOp;
+    use crate::mir::bin_op::RelationOp;
+    use crate::mir::bool_to_sigma::BoolToSigmaProp;
+    use crate::mir::global_vars::GlobalVars;
+    use crate::sigma_protocol::sigma_boolean::SigmaProp;
     use proptest::prelude::*;
 
     proptest! {
@@ -651,6 +736,50 @@ mod tests {
         assert_eq!(parsed_expr, expr)
     }
 
+    #[test]
+    fn test_classify() {
+        let encoder = AddressEncoder::new(NetworkPrefix::Mainnet);
+        let address = encoder
+            .parse_address_from_str("9hzP24a2q8KLPVCUk7gdMDXYc7vinmGuxmLp5KU7k9UwptgYBYV")
+            .unwrap();
+        let pk = match &address {
+            crate::chain::address::Address::P2Pk(pk) => pk.clone(),
+            _ => panic!("expected P2PK address"),
+        };
+        assert_eq!(
+            address.script().unwrap().classify(),
+            ErgoTreeClass::ConstSigmaProp(pk.into())
+        );
+        // sigmaProp(true) with the segregated constant
+        let expr: Expr = BoolToSigmaProp {
+            input: Box::new(Expr::Const(true.into())),
+        }
+        .into();
+        let tree = ErgoTree::new(ErgoTreeHeader::v0(true), &expr).unwrap();
+        assert_eq!(tree.classify(), ErgoTreeClass::TrivialBool(true));
+        let tree = ErgoTree::new(ErgoTreeHeader::v0(false), &Expr::Const(false.into())).unwrap();
+        assert_eq!(tree.classify(), ErgoTreeClass::TrivialBool(false));
+        let tree = ErgoTree::new(
+            ErgoTreeHeader::v0(true),
+            &Expr::Const(SigmaProp::new(SigmaBoolean::TrivialProp(true)).into()),
+        )
+        .unwrap();
+        assert_eq!(tree.classify(), ErgoTreeClass::TrivialBool(true));
+        // sigmaProp(HEIGHT > 1)
+        let height_gt = BinOp::new(
+            RelationOp::Gt.into(),
+            GlobalVars::Height.into(),
+            Expr::Const(1i32.into()),
+        )
+        .unwrap();
+        let expr: Expr = BoolToSigmaProp {
+            input: Box::new(height_gt.into()),
+        }
+        .into();
+        let tree = ErgoTree::new(ErgoTreeHeader::v0(true), &expr).unwrap();
+        assert_eq!(tree.classify(), ErgoTreeClass::NonTrivial);
+    }
+
     #[test]
     fn test_constant_len() {
         let expr = Expr::Const(Constant {
@@ -683,6 +812,33 @@ mod tests {
         assert_eq!(new_ergo_tree.get_constant(0).unwrap().unwrap(), true.into());
     }
 
+    #[test]
+    fn test_constants_with_indices() {
+        let expr = Expr::Const(Constant {
+            tpe: SType::SBoolean,
+            v: Literal::Boolean(false),
+        });
+        let ergo_tree = ErgoTree::new(ErgoTreeHeader::v0(true), &expr).unwrap();
+        assert_eq!(ergo_tree.constants().unwrap(), vec![(0, false.into())]);
+        let new_ergo_tree = ergo_tree
+            .clone()
+            .with_constants(vec![(0, true.into())])
+            .unwrap();
+        assert_eq!(new_ergo_tree.constants().unwrap(), vec![(0, true.into())]);
+        assert!(matches!(
+            ergo_tree.clone().with_constants(vec![(0, 1i32.into())]),
+            Err(ErgoTreeError::ConstantsError(
+                ErgoTreeConstantError::SetConstantError(SetConstantError::TypeMismatch(_))
+            ))
+        ));
+        assert!(matches!(
+            ergo_tree.with_constants(vec![(0, true.into()), (1, true.into())]),
+            Err(ErgoTreeError::ConstantsError(
+                ErgoTreeConstantError::SetConstantError(SetConstantError::OutOfBounds(_))
+            ))
+        ));
+    }
+
     #[test]
     fn dex_t2tpool_parse() {
         let base16_str = "19a3030f0400040204020404040404060406058080a0f6f4acdbe01b058080a0f6f4acdbe01b050004d00f0400040005000500d81ad601b2a5730000d602e4c6a70405d603db63087201d604db6308a7d605b27203730100d606b27204730200d607b27203730300d608b27204730400d609b27203730500d60ab27204730600d60b9973078c720602d60c999973088c720502720bd60d8c720802d60e998c720702720dd60f91720e7309d6108c720a02d6117e721006d6127e720e06d613998c7209027210d6147e720d06d615730ad6167e721306d6177e720c06d6187e720b06d6199c72127218d61a9c72167218d1edededededed93c27201c2a793e4c672010405720292c17201c1a793b27203730b00b27204730c00938c7205018c720601ed938c7207018c720801938c7209018c720a019593720c730d95720f929c9c721172127e7202069c7ef07213069a9c72147e7215067e9c720e720206929c9c721472167e7202069c7ef0720e069a9c72117e7215067e9c721372020695ed720f917213730e907217a19d721972149d721a7211ed9272199c7217721492721a9c72177211";
@@ -0,0 +1,150 @@
+//! Cache of the parsed ErgoTrees and their propositions keyed by the serialized tree.
+//! The same scripts (P2PK, popular contracts) are repeated a lot on chain, so parsing
+//! and constant substitution can be skipped for most of the boxes.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use sigma_util::lru::LruCache;
+
+use crate::mir::expr::Expr;
+use crate::serialization::SigmaParsingError;
+use crate::serialization::SigmaSerializable;
+
+use super::ErgoTree;
+use super::ErgoTreeError;
+
+/// LRU cache of the parsed ErgoTrees and their propositions (see [`ErgoTree::proposition`])
+/// keyed by the serialized tree
+#[derive(Debug, Clone)]
+pub struct ErgoTreeCache {
+    trees: LruCache<Vec<u8>, Arc<ErgoTree>>,
+    propositions: LruCache<Vec<u8>, Arc<Expr>>,
+}
+
+impl ErgoTreeCache {
+    /// Cache keeping up to `capacity` parsed trees and `capacity` propositions
+    pub fn new(capacity: usize) -> Self {
+        ErgoTreeCache {
+            trees: LruCache::new(capacity),
+            propositions: LruCache::new(capacity),
+        }
+    }
+
+    /// Parsed tree for the serialized tree
+    pub fn get_or_parse(&mut self, tree_bytes: &[u8]) -> Result<Arc<ErgoTree>, SigmaParsingError> {
+        if let Some(tree) = self.trees.get(tree_bytes) {
+            return Ok(tree.clone());
+        }
+        let tree = Arc::new(ErgoTree::sigma_parse_bytes(tree_bytes)?);
+        self.trees.insert(tree_bytes.to_vec(), tree.clone());
+        Ok(tree)
+    }
+
+    /// Proposition (root expression with the segregated constants substituted) of the serialized
+    /// tree, the tree is parsed (see [`Self::get_or_parse`]) only on a cache miss
+    pub fn proposition(&mut self, tree_bytes: &[u8]) -> Result<Arc<Expr>, ErgoTreeError> {
+        if let Some(expr) = self.propositions.get(tree_bytes) {
+            return Ok(expr.clone());
+        }
+        let expr = Arc::new(self.get_or_parse(tree_bytes)?.proposition()?);
+        self.propositions.insert(tree_bytes.to_vec(), expr.clone());
+        Ok(expr)
+    }
+
+    /// Number of the cached trees and propositions
+    pub fn len(&self) -> usize {
+        self.trees.len() + self.propositions.len()
+    }
+
+    /// Returns true if nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.trees.is_empty() && self.propositions.is_empty()
+    }
+
+    /// Remove all cached trees and propositions
+    pub fn clear(&mut self) {
+        self.trees.clear();
+        self.propositions.clear();
+    }
+}
+
+/// Thread-safe [`ErgoTreeCache`], clones share the same cache.
+/// The lock is not held while parsing on a cache miss.
+#[derive(Debug, Clone)]
+pub struct SharedErgoTreeCache(Arc<Mutex<ErgoTreeCache>>);
+
+impl SharedErgoTreeCache {
+    /// Cache keeping up to `capacity` parsed trees and `capacity` propositions
+    pub fn new(capacity: usize) -> Self {
+        ErgoTreeCache::new(capacity).into()
+    }
+
+    /// Parsed tree for the serialized tree
+    pub fn get_or_parse(&self, tree_bytes: &[u8]) -> Result<Arc<ErgoTree>, SigmaParsingError> {
+        if let Some(tree) = self.lock().trees.get(tree_bytes) {
+            return Ok(tree.clone());
+        }
+        let tree = Arc::new(ErgoTree::sigma_parse_bytes(tree_bytes)?);
+        self.lock().trees.insert(tree_bytes.to_vec(), tree.clone());
+        Ok(tree)
+    }
+
+    /// Proposition (root expression with the segregated constants substituted) of the serialized
+    /// tree, the tree is parsed (see [`Self::get_or_parse`]) only on a cache miss
+    pub fn proposition(&self, tree_bytes: &[u8]) -> Result<Arc<Expr>, ErgoTreeError> {
+        if let Some(expr) = self.lock().propositions.get(tree_bytes) {
+            return Ok(expr.clone());
+        }
+        let expr = Arc::new(self.get_or_parse(tree_bytes)?.proposition()?);
+        self.lock()
+            .propositions
+            .insert(tree_bytes.to_vec(), expr.clone());
+        Ok(expr)
+    }
+
+    /// Remove all cached trees and propositions
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ErgoTreeCache> {
+        // the cache is always in a consistent state, so it's safe to ignore the poisoning
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl From<ErgoTreeCache> for SharedErgoTreeCache {
+    fn from(cache: ErgoTreeCache) -> Self {
+        SharedErgoTreeCache(Arc::new(Mutex::new(cache)))
+    }
+}
+
+#[allow(clippy::panic)]
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #[test]
+        fn cached_tree_and_proposition(tree in any::<ErgoTree>()) {
+            let bytes = tree.sigma_serialize_bytes().unwrap();
+            let cache = SharedErgoTreeCache::new(1);
+            let parsed = cache.get_or_parse(&bytes).unwrap();
+            prop_assert_eq![&*parsed, &tree];
+            prop_assert!(Arc::ptr_eq(&parsed, &cache.get_or_parse(&bytes).unwrap()));
+            let expr = cache.proposition(&bytes).unwrap();
+            prop_assert_eq![&*expr, &tree.proposition().unwrap()];
+            prop_assert!(Arc::ptr_eq(&expr, &cache.proposition(&bytes).unwrap()));
+            // the proposition is not re-parsed once cached
+            cache.lock().trees.clear();
+            prop_assert!(Arc::ptr_eq(&expr, &cache.proposition(&bytes).unwrap()));
+            prop_assert!(cache.lock().trees.is_empty());
+        }
+    }
+}
@@ -9,6 +9,7 @@ use crate::serialization::SigmaSerializeResult;
 use crate::types::stype::SType;
 
 use super::expr::Expr;
+use super::expr::InvalidArgumentError;
 use crate::has_opcode::HasStaticOpCode;
 
 /// AND logical conjunction
@@ -19,6 +20,14 @@ pub struct And {
 }
 
 impl And {
+    /// Create new object, returns an error if the input is not a collection of SBoolean
+    pub fn new(input: Expr) -> Result<Self, InvalidArgumentError> {
+        input.check_post_eval_tpe(&SType::SColl(SType::SBoolean.into()))?;
+        Ok(Self {
+            input: input.into(),
+        })
+    }
+
     /// Type
     pub fn tpe(&self) -> SType {
         SType::SBoolean
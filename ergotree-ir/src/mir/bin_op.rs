@@ -3,6 +3,7 @@
 use std::fmt::Display;
 
 use super::expr::Expr;
+use super::expr::InvalidArgumentError;
 use crate::has_opcode::HasOpCode;
 use crate::serialization::op_code::OpCode;
 use crate::types::stype::SType;
@@ -217,6 +218,42 @@ pub struct BinOp {
 }
 
 impl BinOp {
+    /// Create new object, returns an error if operand types are not valid for the operation:
+    /// arithmetic and bitwise operations require numeric operands of the same type, ordering
+    /// (`>`, `<`, etc.) requires numeric operands of the same type, equality requires operands of
+    /// the same type and logical operations require SBoolean operands.
+    pub fn new(kind: BinOpKind, left: Expr, right: Expr) -> Result<Self, InvalidArgumentError> {
+        let left_tpe = left.post_eval_tpe();
+        let right_tpe = right.post_eval_tpe();
+        match kind {
+            BinOpKind::Logical(_) => {
+                left.check_post_eval_tpe(&SType::SBoolean)?;
+                right.check_post_eval_tpe(&SType::SBoolean)?;
+            }
+            BinOpKind::Relation(RelationOp::Eq) | BinOpKind::Relation(RelationOp::NEq) => {
+                if left_tpe != right_tpe {
+                    return Err(InvalidArgumentError(format!(
+                        "BinOp({}): expected operands of the same type, got {:?} and {:?}",
+                        kind, left_tpe, right_tpe
+                    )));
+                }
+            }
+            BinOpKind::Arith(_) | BinOpKind::Bit(_) | BinOpKind::Relation(_) => {
+                if !left_tpe.is_numeric() || left_tpe != right_tpe {
+                    return Err(InvalidArgumentError(format!(
+                        "BinOp({}): expected numeric operands of the same type, got {:?} and {:?}",
+                        kind, left_tpe, right_tpe
+                    )));
+                }
+            }
+        }
+        Ok(BinOp {
+            kind,
+            left: left.into(),
+            right: right.into(),
+        })
+    }
+
     /// Type
     pub fn tpe(&self) -> SType {
         match self.kind {
@@ -346,6 +383,30 @@ mod tests {
 
     }
 
+    #[test]
+    fn new_checks_operand_types() {
+        let add: BinOpKind = ArithOp::Plus.into();
+        assert!(BinOp::new(add, Expr::from(1i32), Expr::from(2i32)).is_ok());
+        assert!(BinOp::new(add, Expr::from(1i32), Expr::from(2i64)).is_err());
+        assert!(BinOp::new(add, Expr::from(true), Expr::from(false)).is_err());
+
+        let gt: BinOpKind = RelationOp::Gt.into();
+        assert!(BinOp::new(gt, Expr::from(1i64), Expr::from(2i64)).is_ok());
+        assert!(BinOp::new(gt, Expr::from(true), Expr::from(false)).is_err());
+
+        let eq: BinOpKind = RelationOp::Eq.into();
+        assert!(BinOp::new(eq, Expr::from(true), Expr::from(false)).is_ok());
+        assert!(BinOp::new(eq, Expr::from(1i32), Expr::from(true)).is_err());
+
+        let and: BinOpKind = LogicalOp::And.into();
+        assert!(BinOp::new(and, Expr::from(true), Expr::from(false)).is_ok());
+        assert!(BinOp::new(and, Expr::from(1i32), Expr::from(false)).is_err());
+
+        let bit_or: BinOpKind = BitOp::BitOr.into();
+        assert!(BinOp::new(bit_or, Expr::from(1i8), Expr::from(2i8)).is_ok());
+        assert!(BinOp::new(bit_or, Expr::from(1i8), Expr::from(2i16)).is_err());
+    }
+
     // Test that binop with boolean literals serialized correctly
     #[test]
     fn regression_249() {
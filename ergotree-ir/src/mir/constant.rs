@@ -29,8 +29,10 @@ use std::fmt::Formatter;
 use std::sync::Arc;
 
 mod constant_placeholder;
+mod literal_string;
 
 pub use constant_placeholder::*;
+pub use literal_string::*;
 
 use super::avl_tree_data::AvlTreeData;
 use super::avl_tree_data::AvlTreeFlags;
@@ -977,9 +979,34 @@ pub(crate) mod arbitrary {
                 SType::SInt => vec(any::<i32>(), 0..400).prop_map_into().boxed(),
                 SType::SLong => vec(any::<i64>(), 0..400).prop_map_into().boxed(),
                 SType::SSigmaProp => vec(any::<SigmaProp>(), 0..3).prop_map_into().boxed(),
-                _ => todo!(),
+                elem_tpe => vec(const_with_type(elem_tpe.clone()), 0..4)
+                    .prop_map(move |items| Constant {
+                        tpe: SType::SColl(Box::new(elem_tpe.clone())),
+                        v: Literal::Coll(CollKind::WrappedColl {
+                            elem_tpe: elem_tpe.clone(),
+                            items: items.into_iter().map(|c| c.v).collect(),
+                        }),
+                    })
+                    .boxed(),
             },
-            // SType::STuple(_) => {}
+            SType::STuple(STuple { items }) => items
+                .iter()
+                .map(|item_tpe| const_with_type(item_tpe.clone()))
+                .collect::<Vec<_>>()
+                .prop_map(move |constants| Constant {
+                    tpe: SType::STuple(STuple {
+                        items: items.clone(),
+                    }),
+                    v: Literal::Tup(
+                        constants
+                            .into_iter()
+                            .map(|c| c.v)
+                            .collect::<Vec<Literal>>()
+                            .try_into()
+                            .unwrap(),
+                    ),
+                })
+                .boxed(),
             _ => todo!("{0:?} not yet implemented", tpe),
         }
     }
@@ -0,0 +1,403 @@
+//! Human-editable text form of constants, e.g. `Coll(1, 2, 3): Coll[Int]`.
+//!
+//! Numbers are decimal, `GroupElement`, `SigmaProp`, `Box` and `AvlTree` values are base16-encoded
+//! bytes of their serialized form (compressed point for `GroupElement`, serialized
+//! `SigmaBoolean` for `SigmaProp`). The value is followed by its type, so that the text can be
+//! parsed back into exactly the same constant.
+
+use std::sync::Arc;
+
+use ergo_chain_types::EcPoint;
+use num_traits::Num;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+use thiserror::Error;
+
+use crate::bigint256::BigInt256;
+use crate::chain::ergo_box::ErgoBox;
+use crate::mir::avl_tree_data::AvlTreeData;
+use crate::mir::value::CollKind;
+use crate::mir::value::NativeColl;
+use crate::serialization::SigmaSerializable;
+use crate::serialization::SigmaSerializationError;
+use crate::sigma_protocol::sigma_boolean::SigmaBoolean;
+use crate::sigma_protocol::sigma_boolean::SigmaProp;
+use crate::types::stuple::STuple;
+use crate::types::stype::SType;
+
+use super::Constant;
+use super::Literal;
+
+/// Error on parsing the constant from the text form
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+#[error("Cannot parse constant at {pos}: {msg}")]
+pub struct LiteralParsingError {
+    /// Position (in bytes) in the input string
+    pub pos: usize,
+    /// Error description
+    pub msg: String,
+}
+
+impl Constant {
+    /// Text form of the constant (`<value>: <type>`), can be parsed back with
+    /// [`Constant::from_literal`]
+    pub fn to_literal_string(&self) -> Result<String, SigmaSerializationError> {
+        let mut s = String::new();
+        write_value(&self.v, &self.tpe, &mut s)?;
+        s.push_str(": ");
+        s.push_str(&self.tpe.to_string());
+        Ok(s)
+    }
+
+    /// Parse the constant from the text form (`<value>: <type>`, e.g. `Coll(1, 2, 3): Coll[Int]`)
+    pub fn from_literal(s: &str) -> Result<Constant, LiteralParsingError> {
+        let (value_str, type_str) = s.split_once(':').ok_or_else(|| LiteralParsingError {
+            pos: s.len(),
+            msg: "expected `: <type>` after the value".to_string(),
+        })?;
+        let type_offset = value_str.len() + 1;
+        let mut p = Parser::new(type_str, type_offset);
+        let tpe = p.parse_type()?;
+        p.expect_end()?;
+        let mut p = Parser::new(value_str, 0);
+        let v = p.parse_value(&tpe)?;
+        p.expect_end()?;
+        Ok(Constant { tpe, v })
+    }
+}
+
+fn write_hex<T: SigmaSerializable>(v: &T, s: &mut String) -> Result<(), SigmaSerializationError> {
+    s.push_str(&base16::encode_lower(&v.sigma_serialize_bytes()?));
+    Ok(())
+}
+
+fn write_items<'a, I>(items: I, s: &mut String) -> Result<(), SigmaSerializationError>
+where
+    I: Iterator<Item = (&'a Literal, &'a SType)>,
+{
+    s.push('(');
+    for (i, (item, item_tpe)) in items.enumerate() {
+        if i > 0 {
+            s.push_str(", ");
+        }
+        write_value(item, item_tpe, s)?;
+    }
+    s.push(')');
+    Ok(())
+}
+
+fn write_value(v: &Literal, tpe: &SType, s: &mut String) -> Result<(), SigmaSerializationError> {
+    match (v, tpe) {
+        (Literal::Unit, SType::SUnit) => s.push_str("()"),
+        (Literal::Boolean(v), SType::SBoolean) => s.push_str(&v.to_string()),
+        (Literal::Byte(v), SType::SByte) => s.push_str(&v.to_string()),
+        (Literal::Short(v), SType::SShort) => s.push_str(&v.to_string()),
+        (Literal::Int(v), SType::SInt) => s.push_str(&v.to_string()),
+        (Literal::Long(v), SType::SLong) => s.push_str(&v.to_string()),
+        (Literal::BigInt(v), SType::SBigInt) => s.push_str(&v.to_string()),
+        (Literal::GroupElement(v), SType::SGroupElement) => write_hex(v.as_ref(), s)?,
+        (Literal::SigmaProp(v), SType::SSigmaProp) => write_hex(v.value(), s)?,
+        (Literal::AvlTree(v), SType::SAvlTree) => write_hex(v.as_ref(), s)?,
+        (Literal::CBox(v), SType::SBox) => write_hex(v.as_ref(), s)?,
+        (Literal::Coll(CollKind::NativeColl(NativeColl::CollByte(bytes))), SType::SColl(_)) => {
+            let bytes: Vec<String> = bytes.iter().map(i8::to_string).collect();
+            s.push_str(&format!("Coll({})", bytes.join(", ")));
+        }
+        (Literal::Coll(CollKind::WrappedColl { items, .. }), SType::SColl(elem_tpe)) => {
+            s.push_str("Coll");
+            write_items(items.iter().zip(std::iter::repeat(&**elem_tpe)), s)?
+        }
+        (Literal::Opt(opt), SType::SOption(elem_tpe)) => match &**opt {
+            Some(v) => {
+                s.push_str("Some(");
+                write_value(v, elem_tpe, s)?;
+                s.push(')');
+            }
+            None => s.push_str("None"),
+        },
+        (Literal::Tup(items), SType::STuple(STuple { items: types })) => {
+            write_items(items.iter().zip(types.iter()), s)?
+        }
+        _ => {
+            return Err(SigmaSerializationError::NotSupported(format!(
+                "value {v} does not match the type {tpe}"
+            )))
+        }
+    }
+    Ok(())
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str, offset: usize) -> Self {
+        Parser { s, pos: 0, offset }
+    }
+
+    fn error(&self, msg: impl Into<String>) -> LiteralParsingError {
+        LiteralParsingError {
+            pos: self.offset + self.pos,
+            msg: msg.into(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), LiteralParsingError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{c}`")))
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), LiteralParsingError> {
+        self.skip_ws();
+        if self.rest().is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("unexpected trailing input"))
+        }
+    }
+
+    /// Alphanumeric token (identifier, number or hex string)
+    fn token(&mut self) -> &'a str {
+        self.skip_ws();
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Comma-separated items in parentheses
+    fn parse_items<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self, usize) -> Result<T, LiteralParsingError>,
+    ) -> Result<Vec<T>, LiteralParsingError> {
+        self.expect('(')?;
+        let mut items = Vec::new();
+        if self.eat(')') {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self, items.len())?);
+            if self.eat(')') {
+                return Ok(items);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<SType, LiteralParsingError> {
+        self.skip_ws();
+        if self.rest().starts_with('(') {
+            let items = self.parse_items(|p, _| p.parse_type())?;
+            return STuple::try_from(items)
+                .map(SType::STuple)
+                .map_err(|e| self.error(format!("invalid tuple type: {e}")));
+        }
+        let start = self.pos;
+        Ok(match self.token() {
+            "Unit" => SType::SUnit,
+            "Boolean" => SType::SBoolean,
+            "Byte" => SType::SByte,
+            "Short" => SType::SShort,
+            "Int" => SType::SInt,
+            "Long" => SType::SLong,
+            "BigInt" => SType::SBigInt,
+            "GroupElement" => SType::SGroupElement,
+            "SigmaProp" => SType::SSigmaProp,
+            "Box" => SType::SBox,
+            "AvlTree" => SType::SAvlTree,
+            "Coll" => SType::SColl(self.parse_type_arg()?.into()),
+            "Option" => SType::SOption(self.parse_type_arg()?.into()),
+            t => {
+                self.pos = start;
+                return Err(self.error(format!("unsupported type `{t}`")));
+            }
+        })
+    }
+
+    fn parse_type_arg(&mut self) -> Result<SType, LiteralParsingError> {
+        self.expect('[')?;
+        let tpe = self.parse_type()?;
+        self.expect(']')?;
+        Ok(tpe)
+    }
+
+    fn parse_number<T: std::str::FromStr>(&mut self) -> Result<T, LiteralParsingError>
+    where
+        T::Err: std::fmt::Display,
+    {
+        self.skip_ws();
+        let start = self.pos;
+        let token = self.token();
+        token.parse().map_err(|e| {
+            self.pos = start;
+            self.error(format!("invalid number `{token}`: {e}"))
+        })
+    }
+
+    fn parse_hex<T: SigmaSerializable>(&mut self) -> Result<T, LiteralParsingError> {
+        self.skip_ws();
+        let start = self.pos;
+        let token = self.token();
+        decode_base16(token, DecodingMode::Strict)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| T::sigma_parse_bytes(&bytes).map_err(|e| e.to_string()))
+            .map_err(|e| {
+                self.pos = start;
+                self.error(format!("invalid base16-encoded value `{token}`: {e}"))
+            })
+    }
+
+    fn parse_value(&mut self, tpe: &SType) -> Result<Literal, LiteralParsingError> {
+        self.skip_ws();
+        let start = self.pos;
+        Ok(match tpe {
+            SType::SUnit => {
+                self.expect('(')?;
+                self.expect(')')?;
+                Literal::Unit
+            }
+            SType::SBoolean => match self.token() {
+                "true" => Literal::Boolean(true),
+                "false" => Literal::Boolean(false),
+                t => {
+                    self.pos = start;
+                    return Err(self.error(format!("expected boolean, got `{t}`")));
+                }
+            },
+            SType::SByte => Literal::Byte(self.parse_number()?),
+            SType::SShort => Literal::Short(self.parse_number()?),
+            SType::SInt => Literal::Int(self.parse_number()?),
+            SType::SLong => Literal::Long(self.parse_number()?),
+            SType::SBigInt => {
+                let token = self.token();
+                Literal::BigInt(BigInt256::from_str_radix(token, 10).map_err(|e| {
+                    self.pos = start;
+                    self.error(format!("invalid BigInt `{token}`: {e}"))
+                })?)
+            }
+            SType::SGroupElement => Literal::GroupElement(Box::new(self.parse_hex::<EcPoint>()?)),
+            SType::SSigmaProp => {
+                Literal::SigmaProp(Box::new(SigmaProp::new(self.parse_hex::<SigmaBoolean>()?)))
+            }
+            SType::SAvlTree => Literal::AvlTree(Box::new(self.parse_hex::<AvlTreeData>()?)),
+            SType::SBox => Literal::CBox(Arc::new(self.parse_hex::<ErgoBox>()?)),
+            SType::SColl(elem_tpe) => {
+                if self.token() != "Coll" {
+                    self.pos = start;
+                    return Err(self.error("expected `Coll(...)`"));
+                }
+                Literal::Coll(match **elem_tpe {
+                    SType::SByte => CollKind::NativeColl(NativeColl::CollByte(
+                        self.parse_items(|p, _| p.parse_number::<i8>())?,
+                    )),
+                    _ => CollKind::WrappedColl {
+                        elem_tpe: *elem_tpe.clone(),
+                        items: self.parse_items(|p, _| p.parse_value(elem_tpe))?,
+                    },
+                })
+            }
+            SType::SOption(elem_tpe) => match self.token() {
+                "None" => Literal::Opt(Box::new(None)),
+                "Some" => {
+                    self.expect('(')?;
+                    let v = self.parse_value(elem_tpe)?;
+                    self.expect(')')?;
+                    Literal::Opt(Box::new(Some(v)))
+                }
+                _ => {
+                    self.pos = start;
+                    return Err(self.error("expected `Some(...)` or `None`"));
+                }
+            },
+            SType::STuple(STuple { items: types }) => {
+                let items = self.parse_items(|p, i| match types.get(i) {
+                    Some(item_tpe) => p.parse_value(item_tpe),
+                    None => Err(p.error(format!("expected {} tuple items", types.len()))),
+                })?;
+                if items.len() != types.len() {
+                    return Err(self.error(format!("expected {} tuple items", types.len())));
+                }
+                // items count is checked against the tuple type
+                #[allow(clippy::unwrap_used)]
+                Literal::Tup(items.try_into().unwrap())
+            }
+            _ => return Err(self.error(format!("values of type {tpe} are not supported"))),
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn parse_coll_int() {
+        let c = Constant::from_literal("Coll(1,2, 3): Coll[Int]").unwrap();
+        assert_eq!(c, vec![1i32, 2, 3].into());
+        assert_eq!(c.to_literal_string().unwrap(), "Coll(1, 2, 3): Coll[Int]");
+    }
+
+    #[test]
+    fn parse_nested() {
+        let c = Constant::from_literal(
+            "(Some(-5), Coll(Coll(1), Coll())): (Option[Long], Coll[Coll[Byte]])",
+        )
+        .unwrap();
+        let expected: Constant = (Some(-5i64), vec![vec![1i8], vec![]]).into();
+        assert_eq!(c, expected);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Constant::from_literal("1").is_err());
+        assert!(Constant::from_literal("1: Foo").is_err());
+        assert!(Constant::from_literal("300: Byte").is_err());
+        assert!(Constant::from_literal("(1, 2, 3): (Int, Int)").is_err());
+        assert_eq!(
+            Constant::from_literal("Coll(1, x): Coll[Int]")
+                .unwrap_err()
+                .pos,
+            8
+        );
+    }
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn literal_string_roundtrip(c in any::<Constant>()) {
+            let s = c.to_literal_string().unwrap();
+            prop_assert_eq![Constant::from_literal(&s).unwrap(), c];
+        }
+    }
+}
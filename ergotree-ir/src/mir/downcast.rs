@@ -81,12 +81,23 @@ mod arbitrary {
         type Strategy = BoxedStrategy<Self>;
 
         fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
-            any_with::<Expr>(ArbExprParams {
-                tpe: SType::SLong,
-                depth: 2,
-            })
-            .prop_map(|input| Downcast::new(input, SType::SInt).unwrap())
-            .boxed()
+            let numeric_types = [
+                SType::SByte,
+                SType::SShort,
+                SType::SInt,
+                SType::SLong,
+                SType::SBigInt,
+            ];
+            (0..numeric_types.len(), 0..numeric_types.len())
+                .prop_flat_map(move |(i, j)| {
+                    let target_tpe = numeric_types[i.min(j)].clone();
+                    any_with::<Expr>(ArbExprParams {
+                        tpe: numeric_types[i.max(j)].clone(),
+                        depth: 2,
+                    })
+                    .prop_map(move |input| Downcast::new(input, target_tpe.clone()).unwrap())
+                })
+                .boxed()
         }
     }
 }
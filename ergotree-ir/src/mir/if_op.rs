@@ -1,4 +1,5 @@
 use super::expr::Expr;
+use super::expr::InvalidArgumentError;
 use crate::has_opcode::HasStaticOpCode;
 use crate::serialization::op_code::OpCode;
 use crate::serialization::sigma_byte_reader::SigmaByteRead;
@@ -20,6 +21,28 @@ pub struct If {
 }
 
 impl If {
+    /// Create new object, returns an error if the condition is not SBoolean or the branches are of
+    /// different types
+    pub fn new(
+        condition: Expr,
+        true_branch: Expr,
+        false_branch: Expr,
+    ) -> Result<Self, InvalidArgumentError> {
+        condition.check_post_eval_tpe(&SType::SBoolean)?;
+        if true_branch.tpe() != false_branch.tpe() {
+            return Err(InvalidArgumentError(format!(
+                "If: expected branches of the same type, got {:?} and {:?}",
+                true_branch.tpe(),
+                false_branch.tpe()
+            )));
+        }
+        Ok(If {
+            condition: condition.into(),
+            true_branch: true_branch.into(),
+            false_branch: false_branch.into(),
+        })
+    }
+
     /// Type
     pub fn tpe(&self) -> SType {
         self.true_branch.tpe()
@@ -96,4 +119,11 @@ mod tests {
         }
 
     }
+
+    #[test]
+    fn new_checks_types() {
+        assert!(If::new(Expr::from(true), Expr::from(1i32), Expr::from(2i32)).is_ok());
+        assert!(If::new(Expr::from(1i32), Expr::from(1i32), Expr::from(2i32)).is_err());
+        assert!(If::new(Expr::from(true), Expr::from(1i32), Expr::from(2i64)).is_err());
+    }
 }
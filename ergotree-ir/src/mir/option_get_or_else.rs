@@ -9,7 +9,7 @@ use crate::serialization::SigmaSerializable;
 use crate::serialization::SigmaSerializeResult;
 use crate::types::stype::SType;
 
-/// Returns the Option's value or error if no value
+/// Returns the Option's value or the default if no value (the default is evaluated in any case)
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct OptionGetOrElse {
     /// Object of SOption type
@@ -1,4 +1,5 @@
 use super::expr::Expr;
+use super::expr::InvalidArgumentError;
 use crate::has_opcode::HasStaticOpCode;
 use crate::serialization::op_code::OpCode;
 use crate::serialization::sigma_byte_reader::SigmaByteRead;
@@ -16,6 +17,14 @@ pub struct Or {
 }
 
 impl Or {
+    /// Create new object, returns an error if the input is not a collection of SBoolean
+    pub fn new(input: Expr) -> Result<Self, InvalidArgumentError> {
+        input.check_post_eval_tpe(&SType::SColl(SType::SBoolean.into()))?;
+        Ok(Self {
+            input: input.into(),
+        })
+    }
+
     /// Type
     pub fn tpe(&self) -> SType {
         SType::SBoolean
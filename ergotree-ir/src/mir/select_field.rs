@@ -125,6 +125,7 @@ impl SigmaSerializable for SelectField {
 mod tests {
     use std::convert::TryInto;
 
+    use crate::mir::tuple::Tuple;
     use crate::serialization::sigma_serialize_roundtrip;
 
     use super::*;
@@ -136,4 +137,21 @@ mod tests {
             .into();
         assert_eq![sigma_serialize_roundtrip(&e), e];
     }
+
+    #[test]
+    fn ser_roundtrip_quintuple() {
+        let tuple = Tuple::new(vec![
+            1i32.into(),
+            2i64.into(),
+            true.into(),
+            3i32.into(),
+            4i64.into(),
+        ])
+        .unwrap();
+        let e: Expr = SelectField::new(tuple.into(), 5u8.try_into().unwrap())
+            .unwrap()
+            .into();
+        assert_eq!(e.tpe(), SType::SLong);
+        assert_eq![sigma_serialize_roundtrip(&e), e];
+    }
 }
@@ -75,8 +75,8 @@ mod arbitrary {
 
         fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
             prop_oneof![
-                vec(any::<Expr>(), 2..4),
-                vec(any::<Constant>().prop_map_into(), 2..4)
+                vec(any::<Expr>(), 2..6),
+                vec(any::<Constant>().prop_map_into(), 2..6)
             ]
             .prop_map(move |items| Self::new(items).unwrap())
             .boxed()
@@ -81,12 +81,23 @@ mod arbitrary {
         type Strategy = BoxedStrategy<Self>;
 
         fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
-            any_with::<Expr>(ArbExprParams {
-                tpe: SType::SInt,
-                depth: 2,
-            })
-            .prop_map(|input| Upcast::new(input, SType::SLong).unwrap())
-            .boxed()
+            let numeric_types = [
+                SType::SByte,
+                SType::SShort,
+                SType::SInt,
+                SType::SLong,
+                SType::SBigInt,
+            ];
+            (0..numeric_types.len(), 0..numeric_types.len())
+                .prop_flat_map(move |(i, j)| {
+                    let target_tpe = numeric_types[i.max(j)].clone();
+                    any_with::<Expr>(ArbExprParams {
+                        tpe: numeric_types[i.min(j)].clone(),
+                        depth: 2,
+                    })
+                    .prop_map(move |input| Upcast::new(input, target_tpe.clone()).unwrap())
+                })
+                .boxed()
         }
     }
 }
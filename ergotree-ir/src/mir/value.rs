@@ -22,6 +22,7 @@ use super::constant::TryExtractFromError;
 use super::constant::TryExtractInto;
 use super::expr::Expr;
 use super::func_value::FuncArg;
+use super::val_def::ValId;
 
 extern crate derive_more;
 use derive_more::From;
@@ -124,6 +125,58 @@ where
     }
 }
 
+/// Persistent variable bindings, extending shares the existing bindings instead of copying them
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Bindings(Option<Arc<BindingsNode>>);
+
+#[derive(PartialEq, Eq, Debug)]
+struct BindingsNode {
+    idx: ValId,
+    value: Value,
+    parent: Bindings,
+}
+
+impl Bindings {
+    /// No bindings
+    pub fn empty() -> Bindings {
+        Bindings(None)
+    }
+
+    /// Returns `true` if there are no bindings
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// New bindings with `idx` bound to `v` (shadowing the previous binding of `idx`, if any)
+    pub fn extend(&self, idx: ValId, v: Value) -> Bindings {
+        Bindings(Some(Arc::new(BindingsNode {
+            idx,
+            value: v,
+            parent: self.clone(),
+        })))
+    }
+
+    /// Value bound to `idx`
+    pub fn get(&self, idx: ValId) -> Option<&Value> {
+        self.nodes().find(|n| n.idx == idx).map(|n| &n.value)
+    }
+
+    /// All the bound values (shadowed bindings are skipped), the latest binding first
+    pub fn to_vec(&self) -> Vec<(ValId, Value)> {
+        let mut res: Vec<(ValId, Value)> = vec![];
+        for n in self.nodes() {
+            if !res.iter().any(|(idx, _)| *idx == n.idx) {
+                res.push((n.idx, n.value.clone()));
+            }
+        }
+        res
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = &BindingsNode> {
+        std::iter::successors(self.0.as_deref(), |n| n.parent.0.as_deref())
+    }
+}
+
 /// Lambda
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Lambda {
@@ -131,6 +184,30 @@ pub struct Lambda {
     pub args: Vec<FuncArg>,
     /// Body
     pub body: Box<Expr>,
+    /// Variables in scope when the lambda was created (closure environment), the body is
+    /// evaluated with them and the arguments bound
+    captured_env: Bindings,
+}
+
+impl Lambda {
+    /// Lambda without captured variables
+    pub fn new(args: Vec<FuncArg>, body: Box<Expr>) -> Self {
+        Lambda::with_captured_env(args, body, Bindings::empty())
+    }
+
+    /// Lambda closing over the given variables
+    pub fn with_captured_env(args: Vec<FuncArg>, body: Box<Expr>, captured_env: Bindings) -> Self {
+        Lambda {
+            args,
+            body,
+            captured_env,
+        }
+    }
+
+    /// Variables in scope when the lambda was created
+    pub fn captured_env(&self) -> &Bindings {
+        &self.captured_env
+    }
 }
 
 /// Runtime value
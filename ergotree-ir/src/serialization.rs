@@ -11,10 +11,12 @@ mod property_call;
 mod sigmaboolean;
 mod val_def_type_store;
 
-pub(crate) mod op_code;
+pub mod op_code;
 pub(crate) mod types;
 
 pub mod constant_store;
+#[cfg(feature = "arbitrary")]
+pub mod differential;
 pub mod sigma_byte_reader;
 pub mod sigma_byte_writer;
 
@@ -76,6 +76,9 @@ mod tests {
         let c_res = Constant::sigma_parse_bytes(&constant_bytes);
         //dbg!(&c_res);
         assert!(c_res.is_err());
-        assert!(matches!(c_res, Err(SigmaParsingError::ValueOutOfBounds(_))));
+        assert!(matches!(
+            c_res.unwrap_err().root_cause(),
+            SigmaParsingError::ValueOutOfBounds(_)
+        ));
     }
 }
@@ -0,0 +1,220 @@
+//! Differential testing of the ErgoTree serialization against a reference implementation
+//! (e.g. a sigmastate CLI wrapper) to discover consensus divergences.
+//!
+//! The reference is plugged in via [`ReferenceSerializer`]. [`CommandReferenceSerializer`] runs an
+//! external command for every tree: the base16-encoded serialized tree is written to the command's
+//! stdin (one line), and the command is expected to parse it, serialize it back and print the
+//! base16-encoded bytes to stdout. Set `SIGMA_REFERENCE_SERIALIZER` environment variable to the
+//! command line to run the differential test of this crate against it.
+
+use std::io::Write;
+use std::process::Command;
+use std::process::Stdio;
+
+use proptest::prelude::*;
+use proptest::test_runner::Config;
+use proptest::test_runner::TestCaseError;
+use proptest::test_runner::TestError;
+use proptest::test_runner::TestRunner;
+use sigma_util::encoding::decode_base16;
+use sigma_util::encoding::DecodingMode;
+use thiserror::Error;
+
+use crate::ergo_tree::ErgoTree;
+
+use super::SigmaSerializable;
+use super::SigmaSerializationError;
+
+/// Environment variable with the reference serializer command line (program and arguments
+/// separated by whitespace)
+pub const REFERENCE_SERIALIZER_ENV_VAR: &str = "SIGMA_REFERENCE_SERIALIZER";
+
+/// Reference implementation of the ErgoTree serialization
+pub trait ReferenceSerializer {
+    /// Parse the serialized ErgoTree and return it serialized back
+    fn roundtrip(&self, ergo_tree_bytes: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+impl<F: Fn(&[u8]) -> Result<Vec<u8>, String>> ReferenceSerializer for F {
+    fn roundtrip(&self, ergo_tree_bytes: &[u8]) -> Result<Vec<u8>, String> {
+        self(ergo_tree_bytes)
+    }
+}
+
+/// Reference serializer running an external command (see module docs for the protocol)
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct CommandReferenceSerializer {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandReferenceSerializer {
+    /// Command with the given program and arguments
+    pub fn new(program: String, args: Vec<String>) -> Self {
+        CommandReferenceSerializer { program, args }
+    }
+
+    /// Command from [`REFERENCE_SERIALIZER_ENV_VAR`] environment variable, `None` if not set
+    pub fn from_env() -> Option<Self> {
+        let cmd_line = std::env::var(REFERENCE_SERIALIZER_ENV_VAR).ok()?;
+        let mut parts = cmd_line.split_whitespace().map(String::from);
+        let program = parts.next()?;
+        Some(CommandReferenceSerializer::new(program, parts.collect()))
+    }
+}
+
+impl ReferenceSerializer for CommandReferenceSerializer {
+    fn roundtrip(&self, ergo_tree_bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("failed to run {}: {}", self.program, e))?;
+        child
+            .stdin
+            .take()
+            .ok_or_else(|| "stdin is not available".to_string())?
+            .write_all(format!("{}\n", base16::encode_lower(ergo_tree_bytes)).as_bytes())
+            .map_err(|e| e.to_string())?;
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} exited with {}: {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        decode_base16(
+            &String::from_utf8_lossy(&output.stdout),
+            DecodingMode::Lenient,
+        )
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Differential testing errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum DifferentialError {
+    /// Tree serialization failed on our side
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] SigmaSerializationError),
+    /// Reference serializer failed (e.g. rejected the tree)
+    #[error("Reference serializer failed on {tree_hex}: {reason}")]
+    ReferenceFailed {
+        /// Base16-encoded serialized tree
+        tree_hex: String,
+        /// Reference error
+        reason: String,
+    },
+    /// Reference serializer produced different bytes
+    #[error("Serialization mismatch: ours {ours_hex}, reference {reference_hex}")]
+    Mismatch {
+        /// Base16-encoded bytes serialized by this implementation
+        ours_hex: String,
+        /// Base16-encoded bytes serialized by the reference
+        reference_hex: String,
+    },
+    /// Generated (and shrunk to the minimal) tree failed the check
+    #[error("Divergence found on {tree:?}: {reason}")]
+    Divergence {
+        /// Minimal failing tree
+        tree: Box<ErgoTree>,
+        /// Failure reason
+        reason: String,
+    },
+    /// Test run aborted (e.g. too many rejected cases)
+    #[error("Aborted: {0}")]
+    Aborted(String),
+}
+
+/// Check that the reference serializer roundtrips the tree to the same bytes
+pub fn check_ergo_tree<R: ReferenceSerializer + ?Sized>(
+    reference: &R,
+    tree: &ErgoTree,
+) -> Result<(), DifferentialError> {
+    let ours = tree.sigma_serialize_bytes()?;
+    let reference_bytes =
+        reference
+            .roundtrip(&ours)
+            .map_err(|reason| DifferentialError::ReferenceFailed {
+                tree_hex: base16::encode_lower(&ours),
+                reason,
+            })?;
+    if reference_bytes == ours {
+        Ok(())
+    } else {
+        Err(DifferentialError::Mismatch {
+            ours_hex: base16::encode_lower(&ours),
+            reference_hex: base16::encode_lower(&reference_bytes),
+        })
+    }
+}
+
+/// Check `cases` arbitrary generated trees against the reference. On divergence the failing tree
+/// is shrunk to the minimal one.
+pub fn run_differential<R: ReferenceSerializer + ?Sized>(
+    reference: &R,
+    cases: u32,
+) -> Result<(), DifferentialError> {
+    let mut runner = TestRunner::new(Config {
+        cases,
+        ..Config::default()
+    });
+    runner
+        .run(&any::<ErgoTree>(), |tree| {
+            check_ergo_tree(reference, &tree).map_err(|e| TestCaseError::fail(e.to_string()))
+        })
+        .map_err(|e| match e {
+            TestError::Fail(reason, tree) => DifferentialError::Divergence {
+                tree: Box::new(tree),
+                reason: reason.to_string(),
+            },
+            TestError::Abort(reason) => DifferentialError::Aborted(reason.to_string()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+
+    #[test]
+    fn differential_against_own_roundtrip() {
+        let own = |bytes: &[u8]| -> Result<Vec<u8>, String> {
+            let tree = ErgoTree::sigma_parse_bytes(bytes).map_err(|e| e.to_string())?;
+            tree.sigma_serialize_bytes().map_err(|e| e.to_string())
+        };
+        assert_eq!(run_differential(&own, 16), Ok(()));
+    }
+
+    #[test]
+    fn divergence_is_reported() {
+        let broken = |bytes: &[u8]| -> Result<Vec<u8>, String> {
+            let mut bytes = bytes.to_vec();
+            bytes.push(0);
+            Ok(bytes)
+        };
+        assert!(matches!(
+            run_differential(&broken, 4),
+            Err(DifferentialError::Divergence { .. })
+        ));
+        let failing = |_: &[u8]| -> Result<Vec<u8>, String> { Err("unsupported".to_string()) };
+        assert!(matches!(
+            check_ergo_tree(&failing, &force_any_val::<ErgoTree>()),
+            Err(DifferentialError::ReferenceFailed { .. })
+        ));
+    }
+
+    /// Runs against the external reference if configured (see module docs)
+    #[test]
+    fn differential_against_reference() {
+        if let Some(reference) = CommandReferenceSerializer::from_env() {
+            let res = run_differential(&reference, 256);
+            assert_eq!(res, Ok(()));
+        }
+    }
+}
@@ -34,10 +34,18 @@ impl SigmaSerializable for MethodCall {
 #[cfg(feature = "arbitrary")]
 #[allow(clippy::unwrap_used)]
 mod tests {
+    use crate::mir::bin_op::BinOp;
+    use crate::mir::bin_op::RelationOp;
     use crate::mir::expr::Expr;
+    use crate::mir::extract_reg_as::ExtractRegisterAs;
+    use crate::mir::func_value::FuncArg;
+    use crate::mir::func_value::FuncValue;
+    use crate::mir::global_vars::GlobalVars;
     use crate::mir::method_call::MethodCall;
+    use crate::mir::val_use::ValUse;
     use crate::serialization::sigma_serialize_roundtrip;
     use crate::types::scoll;
+    use crate::types::soption;
     use crate::types::stype::SType;
     use crate::types::stype_param::STypeVar;
 
@@ -54,4 +62,73 @@ mod tests {
         .into();
         assert_eq![sigma_serialize_roundtrip(&mc), mc];
     }
+
+    #[test]
+    fn ser_roundtrip_option_map() {
+        // Option constants are not serializable, take the Option from the register
+        let opt: Expr = ExtractRegisterAs::new(
+            GlobalVars::SelfBox.into(),
+            4,
+            SType::SOption(SType::SLong.into()),
+        )
+        .unwrap()
+        .into();
+        let body: Expr = BinOp {
+            kind: RelationOp::Gt.into(),
+            left: Box::new(
+                ValUse {
+                    val_id: 1.into(),
+                    tpe: SType::SLong,
+                }
+                .into(),
+            ),
+            right: Box::new(Expr::Const(0i64.into())),
+        }
+        .into();
+        let mc: Expr = MethodCall::new(
+            opt,
+            soption::MAP_METHOD.clone().with_concrete_types(
+                &[
+                    (STypeVar::iv(), SType::SLong),
+                    (STypeVar::ov(), SType::SBoolean),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            vec![FuncValue::new(
+                vec![FuncArg {
+                    idx: 1.into(),
+                    tpe: SType::SLong,
+                }],
+                body,
+            )
+            .into()],
+        )
+        .unwrap()
+        .into();
+        assert_eq![sigma_serialize_roundtrip(&mc), mc];
+    }
+
+    #[test]
+    fn ser_roundtrip_option_get_or_else() {
+        // Option constants are not serializable, take the Option from the register
+        let opt: Expr = ExtractRegisterAs::new(
+            GlobalVars::SelfBox.into(),
+            4,
+            SType::SOption(SType::SLong.into()),
+        )
+        .unwrap()
+        .into();
+        let mc: Expr = MethodCall::new(
+            opt,
+            soption::GET_OR_ELSE_METHOD
+                .clone()
+                .with_concrete_types(&[(STypeVar::t(), SType::SLong)].iter().cloned().collect()),
+            vec![2i64.into()],
+        )
+        .unwrap()
+        .into();
+        assert_eq![sigma_serialize_roundtrip(&mc), mc];
+    }
 }
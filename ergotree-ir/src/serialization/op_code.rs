@@ -202,6 +202,226 @@ impl OpCode {
     }
 }
 
+/// Number of the operands (child expressions) of the operation
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum OpArity {
+    /// Fixed number of operands
+    Fixed(u8),
+    /// Number of operands is encoded in the serialized node (collections, blocks, method
+    /// calls, optional arguments, etc.)
+    Variable,
+}
+
+/// How the evaluation cost of the operation is calculated
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum CostKind {
+    /// Same cost for every evaluation
+    Fixed,
+    /// Depends on the number of the processed items (collection elements, bytes, etc.)
+    PerItem,
+    /// Depends on the type of the operands (e.g. BigInt arithmetic)
+    TypeBased,
+    /// Depends on the operands values (e.g. deep equality, method being called)
+    Dynamic,
+}
+
+/// Operation metadata for the tooling (disassemblers, profilers, docs)
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct OpCodeInfo {
+    /// Op code
+    pub code: OpCode,
+    /// Name (same as the [`OpCode`] constant)
+    pub name: &'static str,
+    /// Number of the operands
+    pub arity: OpArity,
+    /// ErgoTree version the operation is supported since, `None` if the operation is reserved
+    /// (or not implemented) and can not be parsed
+    pub since_version: Option<u8>,
+    /// How the evaluation cost is calculated
+    pub cost_kind: CostKind,
+}
+
+macro_rules! arity {
+    (var) => {
+        OpArity::Variable
+    };
+    ($n:literal) => {
+        OpArity::Fixed($n)
+    };
+}
+
+macro_rules! op {
+    (@ $name:ident, $arity:tt, $cost:ident, $since:expr) => {
+        OpCodeInfo {
+            code: OpCode::$name,
+            name: stringify!($name),
+            arity: arity!($arity),
+            since_version: $since,
+            cost_kind: CostKind::$cost,
+        }
+    };
+    ($name:ident, $arity:tt, $cost:ident) => {
+        op!(@ $name, $arity, $cost, Some(0))
+    };
+    ($name:ident, $arity:tt, $cost:ident, reserved) => {
+        op!(@ $name, $arity, $cost, None)
+    };
+}
+
+/// All operations (non-constant op codes), ordered by code. Reserved (or not implemented)
+/// operations are marked with `reserved`.
+const OP_CODE_TABLE: &[OpCodeInfo] = &[
+    op!(VAL_USE, 0, Fixed),
+    op!(CONSTANT_PLACEHOLDER, 0, Fixed),
+    op!(SUBST_CONSTANTS, 3, PerItem),
+    op!(LONG_TO_BYTE_ARRAY, 1, Fixed),
+    op!(BYTE_ARRAY_TO_BIGINT, 1, Fixed),
+    op!(BYTE_ARRAY_TO_LONG, 1, Fixed),
+    op!(DOWNCAST, 1, TypeBased),
+    op!(UPCAST, 1, TypeBased),
+    op!(TRUE, 0, Fixed, reserved),
+    op!(FALSE, 0, Fixed, reserved),
+    op!(UNIT_CONSTANT, 0, Fixed, reserved),
+    op!(GROUP_GENERATOR, 0, Fixed),
+    op!(COLL, var, PerItem),
+    op!(COLL_OF_BOOL_CONST, 0, Fixed),
+    op!(TUPLE, var, Fixed),
+    op!(SELECT_1, 1, Fixed, reserved),
+    op!(SELECT_2, 1, Fixed, reserved),
+    op!(SELECT_3, 1, Fixed, reserved),
+    op!(SELECT_4, 1, Fixed, reserved),
+    op!(SELECT_5, 1, Fixed, reserved),
+    op!(SELECT_FIELD, 1, Fixed),
+    op!(LT, 2, TypeBased),
+    op!(LE, 2, TypeBased),
+    op!(GT, 2, TypeBased),
+    op!(GE, 2, TypeBased),
+    op!(EQ, 2, Dynamic),
+    op!(NEQ, 2, Dynamic),
+    op!(IF, 3, Fixed),
+    op!(AND, 1, PerItem),
+    op!(OR, 1, PerItem),
+    op!(ATLEAST, 2, PerItem),
+    op!(MINUS, 2, TypeBased),
+    op!(PLUS, 2, TypeBased),
+    op!(XOR, 2, PerItem),
+    op!(MULTIPLY, 2, TypeBased),
+    op!(DIVISION, 2, TypeBased),
+    op!(MODULO, 2, TypeBased),
+    op!(EXPONENTIATE, 2, Fixed),
+    op!(MULTIPLY_GROUP, 2, Fixed),
+    op!(MIN, 2, TypeBased),
+    op!(MAX, 2, TypeBased),
+    op!(HEIGHT, 0, Fixed),
+    op!(INPUTS, 0, Fixed),
+    op!(OUTPUTS, 0, Fixed),
+    op!(LAST_BLOCK_UTXO_ROOT_HASH, 0, Fixed, reserved),
+    op!(SELF_BOX, 0, Fixed),
+    op!(MINER_PUBKEY, 0, Fixed),
+    op!(MAP, 2, PerItem),
+    op!(EXISTS, 2, PerItem),
+    op!(FOR_ALL, 2, PerItem),
+    op!(FOLD, 3, PerItem),
+    op!(SIZE_OF, 1, Fixed),
+    op!(BY_INDEX, var, Fixed),
+    op!(APPEND, 2, PerItem),
+    op!(SLICE, 3, PerItem),
+    op!(FILTER, 2, PerItem),
+    op!(AVL_TREE, 4, Fixed),
+    op!(AVT_TREE_GET, 3, Dynamic),
+    op!(FLAT_MAP, 2, PerItem, reserved),
+    op!(EXTRACT_AMOUNT, 1, Fixed),
+    op!(EXTRACT_SCRIPT_BYTES, 1, Fixed),
+    op!(EXTRACT_BYTES, 1, Fixed),
+    op!(EXTRACT_BYTES_WITH_NO_REF, 1, Fixed),
+    op!(EXTRACT_ID, 1, Fixed),
+    op!(EXTRACT_REGISTER_AS, 1, Fixed),
+    op!(EXTRACT_CREATION_INFO, 1, Fixed),
+    op!(CALC_BLAKE2B256, 1, PerItem),
+    op!(CALC_SHA256, 1, PerItem),
+    op!(PROVE_DLOG, 1, Fixed),
+    op!(PROVE_DIFFIE_HELLMAN_TUPLE, 4, Fixed),
+    op!(SIGMA_PROP_IS_PROVEN, 1, Fixed, reserved),
+    op!(SIGMA_PROP_BYTES, 1, Fixed),
+    op!(BOOL_TO_SIGMA_PROP, 1, Fixed),
+    op!(TRIVIAL_PROP_FALSE, 0, Fixed),
+    op!(TRIVIAL_PROP_TRUE, 0, Fixed),
+    op!(DESERIALIZE_CONTEXT, 0, Dynamic),
+    op!(DESERIALIZE_REGISTER, var, Dynamic),
+    op!(VAL_DEF, 1, Fixed),
+    op!(FUN_DEF, 1, Fixed, reserved),
+    op!(BLOCK_VALUE, var, Fixed),
+    op!(FUNC_VALUE, 1, Fixed),
+    op!(APPLY, var, Fixed),
+    op!(PROPERTY_CALL, 1, Dynamic),
+    op!(METHOD_CALL, var, Dynamic),
+    op!(GLOBAL, 0, Fixed),
+    op!(SOME_VALUE, 1, Fixed, reserved),
+    op!(NONE_VALUE, 0, Fixed, reserved),
+    op!(GET_VAR, 0, Fixed),
+    op!(OPTION_GET, 1, Fixed),
+    op!(OPTION_GET_OR_ELSE, 2, Fixed),
+    op!(OPTION_IS_DEFINED, 1, Fixed),
+    op!(MOD_Q, 1, Fixed, reserved),
+    op!(PLUS_MOD_Q, 2, Fixed, reserved),
+    op!(MINUS_MOD_Q, 2, Fixed, reserved),
+    op!(SIGMA_AND, var, PerItem),
+    op!(SIGMA_OR, var, PerItem),
+    op!(BIN_OR, 2, Fixed),
+    op!(BIN_AND, 2, Fixed),
+    op!(DECODE_POINT, 1, Fixed),
+    op!(LOGICAL_NOT, 1, Fixed),
+    op!(NEGATION, 1, TypeBased),
+    op!(BIT_INVERSION, 1, TypeBased),
+    op!(BIT_OR, 2, TypeBased),
+    op!(BIT_AND, 2, TypeBased),
+    op!(BIN_XOR, 2, Fixed),
+    op!(BIT_XOR, 2, TypeBased),
+    op!(BIT_SHIFT_RIGHT, 2, TypeBased, reserved),
+    op!(BIT_SHIFT_LEFT, 2, TypeBased, reserved),
+    op!(BIT_SHIFT_RIGHT_ZEROED, 2, TypeBased, reserved),
+    op!(COLL_SHIFT_RIGHT, 2, PerItem, reserved),
+    op!(COLL_SHIFT_LEFT, 2, PerItem, reserved),
+    op!(COLL_SHIFT_RIGHT_ZEROED, 2, PerItem, reserved),
+    op!(COLL_ROTATE_LEFT, 2, PerItem, reserved),
+    op!(COLL_ROTATE_RIGHT, 2, PerItem, reserved),
+    op!(CONTEXT, 0, Fixed),
+    op!(XOR_OF, 1, PerItem),
+];
+
+impl OpCode {
+    /// All operations (op codes of the non-constant nodes) ordered by code
+    pub fn all() -> &'static [OpCodeInfo] {
+        OP_CODE_TABLE
+    }
+
+    /// Operation metadata, `None` for the constant codes (and unassigned codes)
+    pub fn info(self) -> Option<&'static OpCodeInfo> {
+        OP_CODE_TABLE
+            .binary_search_by_key(&self.0, |i| i.code.0)
+            .ok()
+            .and_then(|idx| OP_CODE_TABLE.get(idx))
+    }
+
+    /// Operation name, `None` for the constant codes (and unassigned codes)
+    pub fn name(self) -> Option<&'static str> {
+        self.info().map(|i| i.name)
+    }
+
+    /// Find the operation by name
+    pub fn from_name(name: &str) -> Option<OpCode> {
+        OP_CODE_TABLE
+            .iter()
+            .find(|i| i.name == name)
+            .map(|i| i.code)
+    }
+
+    /// Returns true if the code is a constant (type code of the serialized constant)
+    pub fn is_constant(self) -> bool {
+        self.0 <= Self::LAST_CONSTANT_CODE.0
+    }
+}
+
 impl SigmaSerializable for OpCode {
     fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
         w.put_u8(self.0)?;
@@ -212,3 +432,33 @@ impl SigmaSerializable for OpCode {
         Ok(OpCode::parse(code))
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_ordered_by_code() {
+        assert!(OpCode::all()
+            .windows(2)
+            .all(|w| w[0].code.value() < w[1].code.value()));
+        assert!(OpCode::all().iter().all(|i| !i.code.is_constant()));
+    }
+
+    #[test]
+    fn lookup() {
+        let info = OpCode::PLUS.info().unwrap();
+        assert_eq!(info.name, "PLUS");
+        assert_eq!(info.arity, OpArity::Fixed(2));
+        assert_eq!(info.cost_kind, CostKind::TypeBased);
+        assert_eq!(OpCode::from_name("XOR_OF"), Some(OpCode::XOR_OF));
+        assert_eq!(OpCode::from_name("UNKNOWN"), None);
+        assert_eq!(OpCode::parse(1).info(), None);
+        assert_eq!(OpCode::MOD_Q.info().unwrap().since_version, None);
+        for info in OpCode::all() {
+            assert_eq!(info.code.info(), Some(info));
+            assert_eq!(OpCode::from_name(info.name), Some(info.code));
+        }
+    }
+}
@@ -14,6 +14,7 @@ use crate::types::smethod::MethodId;
 use bounded_vec::BoundedVec;
 use bounded_vec::BoundedVecOutOfBounds;
 use io::Cursor;
+use sigma_ser::byte_counter::{ByteCounter, CountingWriter};
 use sigma_ser::{vlq_encode, ScorexParsingError, ScorexSerializationError};
 use std::convert::TryInto;
 use std::io;
@@ -105,6 +106,26 @@ pub enum SigmaParsingError {
     /// Invalid register value
     #[error("Invalid register value: {0}")]
     InvalidRegisterValue(#[from] RegisterValueError),
+    /// Error in a nested structure (see [`SigmaByteRead::parse_in_context`])
+    #[error("{} (at byte {offset}): {error}", .path.join("→"))]
+    WithContext {
+        /// Offset of the reader when the error occurred
+        offset: u64,
+        /// Path to the item being parsed, outermost first (e.g. `["tx", "output[3]", "register R5"]`)
+        path: Vec<String>,
+        /// Underlying error
+        error: Box<SigmaParsingError>,
+    },
+}
+
+impl SigmaParsingError {
+    /// Underlying error without the nested item context wrappers
+    pub fn root_cause(&self) -> &SigmaParsingError {
+        match self {
+            SigmaParsingError::WithContext { error, .. } => error.root_cause(),
+            e => e,
+        }
+    }
 }
 
 impl From<io::Error> for SigmaParsingError {
@@ -149,6 +170,26 @@ pub trait SigmaSerializable: Sized {
         Ok(data)
     }
 
+    /// Size of the serialized value in bytes, counted without allocating a buffer
+    fn sigma_serialized_size(&self) -> Result<usize, SigmaSerializationError> {
+        let mut counter = ByteCounter::new();
+        let mut w = SigmaByteWriter::new(&mut counter, None);
+        self.sigma_serialize(&mut w)?;
+        Ok(counter.count())
+    }
+
+    /// Write `self` to any writer (e.g. caller's buffer `&mut [u8]`, `&mut dyn Write` or a
+    /// hasher) without the intermediate `Vec`. Returns the number of bytes written
+    fn sigma_serialize_to<W: io::Write + ?Sized>(
+        &self,
+        w: &mut W,
+    ) -> Result<usize, SigmaSerializationError> {
+        let mut counting = CountingWriter::new(w);
+        let mut w = SigmaByteWriter::new(&mut counting, None);
+        self.sigma_serialize(&mut w)?;
+        Ok(counting.bytes_written())
+    }
+
     /// Parse `self` from the bytes
     fn sigma_parse_bytes(bytes: &[u8]) -> Result<Self, SigmaParsingError> {
         let cursor = Cursor::new(bytes);
@@ -228,3 +269,31 @@ pub fn sigma_serialize_roundtrip<T: SigmaSerializable>(v: &T) -> T {
     let mut sr = SigmaByteReader::new(cursor, ConstantStore::empty());
     T::sigma_parse(&mut sr).expect("parse failed")
 }
+
+#[allow(clippy::panic)]
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::ergo_box::ErgoBox;
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #[test]
+        fn serialized_size_and_serialize_to(b in any::<ErgoBox>()) {
+            let bytes = b.sigma_serialize_bytes().unwrap();
+            prop_assert_eq![b.sigma_serialized_size().unwrap(), bytes.len()];
+            // caller's buffer
+            let mut buf = vec![0u8; bytes.len()];
+            let written = b.sigma_serialize_to(&mut buf.as_mut_slice()).unwrap();
+            prop_assert_eq![written, bytes.len()];
+            prop_assert_eq![&buf, &bytes];
+            // trait object
+            let mut out = Vec::new();
+            let w: &mut dyn io::Write = &mut out;
+            prop_assert_eq![b.sigma_serialize_to(w).unwrap(), bytes.len()];
+            prop_assert_eq![out, bytes];
+        }
+    }
+}
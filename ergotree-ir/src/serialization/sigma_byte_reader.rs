@@ -1,6 +1,7 @@
 //! Sigma byte stream writer
 use super::constant_store::ConstantStore;
 use super::val_def_type_store::ValDefTypeStore;
+use super::SigmaParsingError;
 use sigma_ser::vlq_encode::ReadSigmaVlqExt;
 use std::io::Cursor;
 use std::io::Read;
@@ -8,6 +9,7 @@ use std::io::Read;
 /// Implementation of SigmaByteRead
 pub struct SigmaByteReader<R> {
     inner: R,
+    position: u64,
     constant_store: ConstantStore,
     substitute_placeholders: bool,
     val_def_type_store: ValDefTypeStore,
@@ -18,6 +20,7 @@ impl<R: Read> SigmaByteReader<R> {
     pub fn new(pr: R, constant_store: ConstantStore) -> SigmaByteReader<R> {
         SigmaByteReader {
             inner: pr,
+            position: 0,
             constant_store,
             substitute_placeholders: false,
             val_def_type_store: ValDefTypeStore::new(),
@@ -32,6 +35,7 @@ impl<R: Read> SigmaByteReader<R> {
     ) -> SigmaByteReader<R> {
         SigmaByteReader {
             inner: pr,
+            position: 0,
             constant_store,
             substitute_placeholders: true,
             val_def_type_store: ValDefTypeStore::new(),
@@ -39,10 +43,24 @@ impl<R: Read> SigmaByteReader<R> {
     }
 }
 
+impl<T: AsRef<[u8]>> SigmaByteReader<Cursor<T>> {
+    /// Current position in the underlying byte array
+    pub fn position(&self) -> u64 {
+        self.inner.position()
+    }
+
+    /// Move to the given position in the underlying byte array
+    pub fn set_position(&mut self, pos: u64) {
+        self.inner.set_position(pos);
+        self.position = pos;
+    }
+}
+
 /// Create SigmaByteReader from a byte array (with empty constant store)
 pub fn from_bytes<T: AsRef<[u8]>>(bytes: T) -> SigmaByteReader<Cursor<T>> {
     SigmaByteReader {
         inner: Cursor::new(bytes),
+        position: 0,
         constant_store: ConstantStore::empty(),
         substitute_placeholders: false,
         val_def_type_store: ValDefTypeStore::new(),
@@ -62,11 +80,46 @@ pub trait SigmaByteRead: ReadSigmaVlqExt {
 
     /// ValDef types store (resolves tpe on ValUse parsing)
     fn val_def_type_store(&mut self) -> &mut ValDefTypeStore;
+
+    /// Number of bytes read so far (offset of the next byte to be read)
+    fn position(&self) -> u64;
+
+    /// Run `parse` and on failure attach the current offset and `context` (e.g. "output[3]") to
+    /// the error. Nested calls build up the path to the failed item
+    /// (see [`SigmaParsingError::WithContext`])
+    fn parse_in_context<T, C, F>(&mut self, context: C, parse: F) -> Result<T, SigmaParsingError>
+    where
+        Self: Sized,
+        C: FnOnce() -> String,
+        F: FnOnce(&mut Self) -> Result<T, SigmaParsingError>,
+    {
+        parse(self).map_err(|e| match e {
+            SigmaParsingError::WithContext {
+                offset,
+                mut path,
+                error,
+            } => {
+                path.insert(0, context());
+                SigmaParsingError::WithContext {
+                    offset,
+                    path,
+                    error,
+                }
+            }
+            e => SigmaParsingError::WithContext {
+                offset: self.position(),
+                path: vec![context()],
+                error: Box::new(e),
+            },
+        })
+    }
 }
 
 impl<R: Read> Read for SigmaByteReader<R> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.inner.read(buf)
+        let n = self.inner.read(buf)?;
+        self.position += n as u64;
+        Ok(n)
     }
 }
 
@@ -86,4 +139,8 @@ impl<R: ReadSigmaVlqExt> SigmaByteRead for SigmaByteReader<R> {
     fn val_def_type_store(&mut self) -> &mut ValDefTypeStore {
         &mut self.val_def_type_store
     }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
 }
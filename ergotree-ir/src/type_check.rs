@@ -1,10 +1,16 @@
 //! Type checking
 
+use crate::mir::bin_op::BinOpKind;
+use crate::mir::bin_op::RelationOp;
+use crate::mir::collection::Collection;
 use crate::mir::expr::Expr;
-use crate::source_span::Spanned;
+use crate::types::stuple::STuple;
+use crate::types::stype::SType;
+use thiserror::Error;
 
 /// Typecheck error
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+#[error("{msg}")]
 pub struct TypeCheckError {
     msg: String,
 }
@@ -21,23 +27,480 @@ impl TypeCheckError {
     }
 }
 
-/// Type checks the given expression
+/// Type checks the given expression (see [`check_expr`])
 pub fn type_check(e: Expr) -> Result<Expr, TypeCheckError> {
-    // not really a relevant check, since such kind of check should be in BinOp::new()
-    match &e {
-        Expr::BinOp(Spanned {
-            source_span: _,
-            expr: bin,
-        }) => {
-            if bin.left.tpe() == bin.right.tpe() {
-                Ok(e)
-            } else {
-                Err(TypeCheckError::new(format!(
-                    "Type check error: binary op operands types do not match: {0:?}",
-                    bin
-                )))
+    check_expr(&e)?;
+    Ok(e)
+}
+
+/// Checks the operand types of every node in the expression tree, so that the malformed trees
+/// (e.g. parsed from bytes) are rejected before the evaluation
+pub fn check_expr(e: &Expr) -> Result<(), TypeCheckError> {
+    check_node(e)?;
+    children(e).into_iter().try_for_each(check_expr)
+}
+
+fn mismatch(node: &str, operand: &str, expected: &str, e: &Expr) -> TypeCheckError {
+    TypeCheckError::new(format!(
+        "Type check error: {0} expected {1} to be {2}, got {3:?}",
+        node,
+        operand,
+        expected,
+        e.post_eval_tpe()
+    ))
+}
+
+fn expect_tpe(node: &str, operand: &str, e: &Expr, expected: &SType) -> Result<(), TypeCheckError> {
+    if &e.post_eval_tpe() == expected {
+        Ok(())
+    } else {
+        Err(mismatch(node, operand, &format!("{:?}", expected), e))
+    }
+}
+
+fn expect_numeric(node: &str, operand: &str, e: &Expr) -> Result<(), TypeCheckError> {
+    if e.post_eval_tpe().is_numeric() {
+        Ok(())
+    } else {
+        Err(mismatch(node, operand, "numeric", e))
+    }
+}
+
+/// Returns the collection element type
+fn expect_coll(node: &str, operand: &str, e: &Expr) -> Result<SType, TypeCheckError> {
+    match e.post_eval_tpe() {
+        SType::SColl(elem_tpe) => Ok(*elem_tpe),
+        _ => Err(mismatch(node, operand, "SColl", e)),
+    }
+}
+
+fn expect_same_tpe(node: &str, left: &Expr, right: &Expr) -> Result<(), TypeCheckError> {
+    if left.post_eval_tpe() == right.post_eval_tpe() {
+        Ok(())
+    } else {
+        Err(TypeCheckError::new(format!(
+            "Type check error: {0} operands types do not match: {1:?} and {2:?}",
+            node,
+            left.post_eval_tpe(),
+            right.post_eval_tpe()
+        )))
+    }
+}
+
+fn expect_func(
+    node: &str,
+    operand: &str,
+    e: &Expr,
+    t_dom: Vec<SType>,
+    t_range: Option<&SType>,
+) -> Result<(), TypeCheckError> {
+    match e.tpe() {
+        SType::SFunc(sfunc)
+            if sfunc.t_dom == t_dom
+                && match t_range {
+                    Some(t) => *sfunc.t_range == *t,
+                    None => true,
+                } =>
+        {
+            Ok(())
+        }
+        tpe => Err(TypeCheckError::new(format!(
+            "Type check error: {0} expected {1} to be a function of {2:?}, got {3:?}",
+            node, operand, t_dom, tpe
+        ))),
+    }
+}
+
+fn coll_byte() -> SType {
+    SType::SColl(SType::SByte.into())
+}
+
+fn check_node(e: &Expr) -> Result<(), TypeCheckError> {
+    match e {
+        Expr::BinOp(op) => {
+            let op = op.expr();
+            let node = format!("binary op {}", op.kind);
+            expect_same_tpe(&node, &op.left, &op.right)?;
+            match op.kind {
+                BinOpKind::Logical(_) => expect_tpe(&node, "left", &op.left, &SType::SBoolean),
+                BinOpKind::Relation(RelationOp::Eq) | BinOpKind::Relation(RelationOp::NEq) => {
+                    Ok(())
+                }
+                BinOpKind::Arith(_) | BinOpKind::Bit(_) | BinOpKind::Relation(_) => {
+                    expect_numeric(&node, "left", &op.left)
+                }
             }
         }
-        _ => Ok(e),
+        Expr::And(op) => expect_tpe(
+            "And",
+            "input",
+            &op.expr().input,
+            &SType::SColl(SType::SBoolean.into()),
+        ),
+        Expr::Or(op) => expect_tpe(
+            "Or",
+            "input",
+            &op.expr().input,
+            &SType::SColl(SType::SBoolean.into()),
+        ),
+        Expr::XorOf(op) => expect_tpe(
+            "XorOf",
+            "input",
+            &op.input,
+            &SType::SColl(SType::SBoolean.into()),
+        ),
+        Expr::Xor(op) => {
+            expect_tpe("Xor", "left", &op.left, &coll_byte())?;
+            expect_tpe("Xor", "right", &op.right, &coll_byte())
+        }
+        Expr::Atleast(op) => {
+            expect_tpe("Atleast", "bound", &op.bound, &SType::SInt)?;
+            expect_tpe(
+                "Atleast",
+                "input",
+                &op.input,
+                &SType::SColl(SType::SSigmaProp.into()),
+            )
+        }
+        Expr::LogicalNot(op) => {
+            expect_tpe("LogicalNot", "input", &op.expr().input, &SType::SBoolean)
+        }
+        Expr::Negation(op) => expect_numeric("Negation", "input", &op.expr().input),
+        Expr::BitInversion(op) => expect_numeric("BitInversion", "input", &op.input),
+        Expr::Upcast(op) => expect_numeric("Upcast", "input", &op.input),
+        Expr::Downcast(op) => expect_numeric("Downcast", "input", &op.input),
+        Expr::BoolToSigmaProp(op) => {
+            expect_tpe("BoolToSigmaProp", "input", &op.input, &SType::SBoolean)
+        }
+        Expr::SigmaPropBytes(op) => {
+            expect_tpe("SigmaPropBytes", "input", &op.input, &SType::SSigmaProp)
+        }
+        Expr::SigmaAnd(op) => op
+            .items
+            .iter()
+            .try_for_each(|i| expect_tpe("SigmaAnd", "item", i, &SType::SSigmaProp)),
+        Expr::SigmaOr(op) => op
+            .items
+            .iter()
+            .try_for_each(|i| expect_tpe("SigmaOr", "item", i, &SType::SSigmaProp)),
+        Expr::If(op) => {
+            expect_tpe("If", "condition", &op.condition, &SType::SBoolean)?;
+            expect_same_tpe("If", &op.true_branch, &op.false_branch)
+        }
+        Expr::ByteArrayToLong(op) => {
+            expect_tpe("ByteArrayToLong", "input", &op.expr().input, &coll_byte())
+        }
+        Expr::ByteArrayToBigInt(op) => {
+            expect_tpe("ByteArrayToBigInt", "input", &op.expr().input, &coll_byte())
+        }
+        Expr::LongToByteArray(op) => {
+            expect_tpe("LongToByteArray", "input", &op.input, &SType::SLong)
+        }
+        Expr::CalcBlake2b256(op) => expect_tpe("CalcBlake2b256", "input", &op.input, &coll_byte()),
+        Expr::CalcSha256(op) => expect_tpe("CalcSha256", "input", &op.input, &coll_byte()),
+        Expr::DecodePoint(op) => expect_tpe("DecodePoint", "input", &op.input, &coll_byte()),
+        Expr::CreateProveDlog(op) => {
+            expect_tpe("CreateProveDlog", "input", &op.input, &SType::SGroupElement)
+        }
+        Expr::CreateProveDhTuple(op) => [&op.g, &op.h, &op.u, &op.v]
+            .into_iter()
+            .try_for_each(|i| expect_tpe("CreateProveDhTuple", "item", i, &SType::SGroupElement)),
+        Expr::Exponentiate(op) => {
+            expect_tpe("Exponentiate", "left", &op.left, &SType::SGroupElement)?;
+            expect_tpe("Exponentiate", "right", &op.right, &SType::SBigInt)
+        }
+        Expr::MultiplyGroup(op) => {
+            expect_tpe("MultiplyGroup", "left", &op.left, &SType::SGroupElement)?;
+            expect_tpe("MultiplyGroup", "right", &op.right, &SType::SGroupElement)
+        }
+        Expr::ExtractAmount(op) => expect_tpe("ExtractAmount", "input", &op.input, &SType::SBox),
+        Expr::ExtractBytes(op) => expect_tpe("ExtractBytes", "input", &op.input, &SType::SBox),
+        Expr::ExtractBytesWithNoRef(op) => {
+            expect_tpe("ExtractBytesWithNoRef", "input", &op.input, &SType::SBox)
+        }
+        Expr::ExtractScriptBytes(op) => {
+            expect_tpe("ExtractScriptBytes", "input", &op.input, &SType::SBox)
+        }
+        Expr::ExtractCreationInfo(op) => {
+            expect_tpe("ExtractCreationInfo", "input", &op.input, &SType::SBox)
+        }
+        Expr::ExtractId(op) => expect_tpe("ExtractId", "input", &op.input, &SType::SBox),
+        Expr::ExtractRegisterAs(op) => {
+            expect_tpe("ExtractRegisterAs", "input", &op.expr().input, &SType::SBox)
+        }
+        Expr::OptionGet(op) => expect_option("OptionGet", &op.expr().input).map(|_| ()),
+        Expr::OptionIsDefined(op) => expect_option("OptionIsDefined", &op.expr().input).map(|_| ()),
+        Expr::OptionGetOrElse(op) => {
+            let op = op.expr();
+            let elem_tpe = expect_option("OptionGetOrElse", &op.input)?;
+            expect_tpe("OptionGetOrElse", "default", &op.default, &elem_tpe)
+        }
+        Expr::SizeOf(op) => expect_coll("SizeOf", "input", &op.input).map(|_| ()),
+        Expr::Append(op) => {
+            let op = op.expr();
+            expect_coll("Append", "input", &op.input)?;
+            expect_same_tpe("Append", &op.input, &op.col_2)
+        }
+        Expr::ByIndex(op) => {
+            let op = op.expr();
+            let elem_tpe = expect_coll("ByIndex", "input", &op.input)?;
+            expect_tpe("ByIndex", "index", &op.index, &SType::SInt)?;
+            op.default
+                .iter()
+                .try_for_each(|d| expect_tpe("ByIndex", "default", d, &elem_tpe))
+        }
+        Expr::Slice(op) => {
+            let op = op.expr();
+            expect_coll("Slice", "input", &op.input)?;
+            expect_tpe("Slice", "from", &op.from, &SType::SInt)?;
+            expect_tpe("Slice", "until", &op.until, &SType::SInt)
+        }
+        Expr::Map(op) => {
+            let op = op.expr();
+            let elem_tpe = expect_coll("Map", "input", &op.input)?;
+            expect_func("Map", "mapper", &op.mapper, vec![elem_tpe], None)
+        }
+        Expr::Filter(op) => {
+            let op = op.expr();
+            let elem_tpe = expect_coll("Filter", "input", &op.input)?;
+            expect_func(
+                "Filter",
+                "condition",
+                &op.condition,
+                vec![elem_tpe],
+                Some(&SType::SBoolean),
+            )
+        }
+        Expr::Exists(op) => {
+            let op = op.expr();
+            let elem_tpe = expect_coll("Exists", "input", &op.input)?;
+            expect_func(
+                "Exists",
+                "condition",
+                &op.condition,
+                vec![elem_tpe],
+                Some(&SType::SBoolean),
+            )
+        }
+        Expr::ForAll(op) => {
+            let op = op.expr();
+            let elem_tpe = expect_coll("ForAll", "input", &op.input)?;
+            expect_func(
+                "ForAll",
+                "condition",
+                &op.condition,
+                vec![elem_tpe],
+                Some(&SType::SBoolean),
+            )
+        }
+        Expr::Fold(op) => {
+            let op = op.expr();
+            let elem_tpe = expect_coll("Fold", "input", &op.input)?;
+            expect_func(
+                "Fold",
+                "fold_op",
+                &op.fold_op,
+                vec![STuple::pair(op.zero.tpe(), elem_tpe).into()],
+                None,
+            )
+        }
+        Expr::Apply(op) => {
+            let arg_types = op.args.iter().map(Expr::tpe).collect();
+            expect_func("Apply", "func", &op.func, arg_types, None)
+        }
+        Expr::Collection(Collection::Exprs { elem_tpe, items }) => items
+            .iter()
+            .try_for_each(|i| expect_tpe("Collection", "item", i, elem_tpe)),
+        Expr::BlockValue(op) => op.expr().items.iter().try_for_each(|i| match i {
+            Expr::ValDef(_) => Ok(()),
+            _ => Err(TypeCheckError::new(format!(
+                "Type check error: BlockValue expected items to be ValDef, got {:?}",
+                i
+            ))),
+        }),
+        Expr::SubstConstants(op) => {
+            let op = op.expr();
+            expect_tpe(
+                "SubstConstants",
+                "script_bytes",
+                &op.script_bytes,
+                &coll_byte(),
+            )?;
+            expect_tpe(
+                "SubstConstants",
+                "positions",
+                &op.positions,
+                &SType::SColl(SType::SInt.into()),
+            )?;
+            expect_coll("SubstConstants", "new_values", &op.new_values).map(|_| ())
+        }
+        Expr::TreeLookup(op) => {
+            let op = op.expr();
+            expect_tpe("TreeLookup", "tree", &op.tree, &SType::SAvlTree)?;
+            expect_tpe("TreeLookup", "key", &op.key, &coll_byte())?;
+            expect_tpe("TreeLookup", "proof", &op.proof, &coll_byte())
+        }
+        Expr::CreateAvlTree(op) => {
+            expect_tpe("CreateAvlTree", "flags", &op.flags, &SType::SByte)?;
+            expect_tpe("CreateAvlTree", "digest", &op.digest, &coll_byte())?;
+            expect_tpe("CreateAvlTree", "key_length", &op.key_length, &SType::SInt)?;
+            op.value_length.iter().try_for_each(|v| {
+                expect_tpe(
+                    "CreateAvlTree",
+                    "value_length",
+                    v,
+                    &SType::SOption(SType::SInt.into()),
+                )
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the option element type
+fn expect_option(node: &str, e: &Expr) -> Result<SType, TypeCheckError> {
+    match e.post_eval_tpe() {
+        SType::SOption(elem_tpe) => Ok(*elem_tpe),
+        _ => Err(mismatch(node, "input", "SOption", e)),
+    }
+}
+
+/// Direct child expressions of the node
+fn children(e: &Expr) -> Vec<&Expr> {
+    match e {
+        Expr::Const(_)
+        | Expr::ConstPlaceholder(_)
+        | Expr::GlobalVars(_)
+        | Expr::ValUse(_)
+        | Expr::GetVar(_)
+        | Expr::DeserializeContext(_)
+        | Expr::Context
+        | Expr::Global => vec![],
+        Expr::Append(op) => vec![&op.expr().input, &op.expr().col_2],
+        Expr::SubstConstants(op) => {
+            let op = op.expr();
+            vec![&op.script_bytes, &op.positions, &op.new_values]
+        }
+        Expr::ByteArrayToLong(op) => vec![&op.expr().input],
+        Expr::ByteArrayToBigInt(op) => vec![&op.expr().input],
+        Expr::LongToByteArray(op) => vec![&op.input],
+        Expr::Collection(Collection::BoolConstants(_)) => vec![],
+        Expr::Collection(Collection::Exprs { items, .. }) => items.iter().collect(),
+        Expr::Tuple(op) => op.items.iter().collect(),
+        Expr::CalcBlake2b256(op) => vec![&op.input],
+        Expr::CalcSha256(op) => vec![&op.input],
+        Expr::FuncValue(op) => vec![op.body()],
+        Expr::Apply(op) => std::iter::once(op.func.as_ref())
+            .chain(op.args.iter())
+            .collect(),
+        Expr::MethodCall(op) => std::iter::once(op.expr().obj.as_ref())
+            .chain(op.expr().args.iter())
+            .collect(),
+        Expr::PropertyCall(op) => vec![&op.expr().obj],
+        Expr::BlockValue(op) => op
+            .expr()
+            .items
+            .iter()
+            .chain(std::iter::once(op.expr().result.as_ref()))
+            .collect(),
+        Expr::ValDef(op) => vec![&op.expr().rhs],
+        Expr::If(op) => vec![&op.condition, &op.true_branch, &op.false_branch],
+        Expr::BinOp(op) => vec![&op.expr().left, &op.expr().right],
+        Expr::And(op) => vec![&op.expr().input],
+        Expr::Or(op) => vec![&op.expr().input],
+        Expr::Xor(op) => vec![&op.left, &op.right],
+        Expr::Atleast(op) => vec![&op.bound, &op.input],
+        Expr::LogicalNot(op) => vec![&op.expr().input],
+        Expr::Negation(op) => vec![&op.expr().input],
+        Expr::BitInversion(op) => vec![&op.input],
+        Expr::OptionGet(op) => vec![&op.expr().input],
+        Expr::OptionIsDefined(op) => vec![&op.expr().input],
+        Expr::OptionGetOrElse(op) => vec![&op.expr().input, &op.expr().default],
+        Expr::ExtractAmount(op) => vec![&op.input],
+        Expr::ExtractRegisterAs(op) => vec![&op.expr().input],
+        Expr::ExtractBytes(op) => vec![&op.input],
+        Expr::ExtractBytesWithNoRef(op) => vec![&op.input],
+        Expr::ExtractScriptBytes(op) => vec![&op.input],
+        Expr::ExtractCreationInfo(op) => vec![&op.input],
+        Expr::ExtractId(op) => vec![&op.input],
+        Expr::ByIndex(op) => {
+            let op = op.expr();
+            std::iter::once(op.input.as_ref())
+                .chain(std::iter::once(op.index.as_ref()))
+                .chain(op.default.as_deref())
+                .collect()
+        }
+        Expr::SizeOf(op) => vec![&op.input],
+        Expr::Slice(op) => vec![&op.expr().input, &op.expr().from, &op.expr().until],
+        Expr::Fold(op) => vec![&op.expr().input, &op.expr().zero, &op.expr().fold_op],
+        Expr::Map(op) => vec![&op.expr().input, &op.expr().mapper],
+        Expr::Filter(op) => vec![&op.expr().input, &op.expr().condition],
+        Expr::Exists(op) => vec![&op.expr().input, &op.expr().condition],
+        Expr::ForAll(op) => vec![&op.expr().input, &op.expr().condition],
+        Expr::SelectField(op) => vec![&op.expr().input],
+        Expr::BoolToSigmaProp(op) => vec![&op.input],
+        Expr::Upcast(op) => vec![&op.input],
+        Expr::Downcast(op) => vec![&op.input],
+        Expr::CreateProveDlog(op) => vec![&op.input],
+        Expr::CreateProveDhTuple(op) => vec![&op.g, &op.h, &op.u, &op.v],
+        Expr::SigmaPropBytes(op) => vec![&op.input],
+        Expr::DecodePoint(op) => vec![&op.input],
+        Expr::SigmaAnd(op) => op.items.iter().collect(),
+        Expr::SigmaOr(op) => op.items.iter().collect(),
+        Expr::DeserializeRegister(op) => op.default.as_deref().into_iter().collect(),
+        Expr::MultiplyGroup(op) => vec![&op.left, &op.right],
+        Expr::Exponentiate(op) => vec![&op.left, &op.right],
+        Expr::XorOf(op) => vec![&op.input],
+        Expr::TreeLookup(op) => vec![&op.expr().tree, &op.expr().key, &op.expr().proof],
+        Expr::CreateAvlTree(op) => std::iter::once(op.flags.as_ref())
+            .chain(std::iter::once(op.digest.as_ref()))
+            .chain(std::iter::once(op.key_length.as_ref()))
+            .chain(op.value_length.as_deref())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir::bin_op::ArithOp;
+    use crate::mir::bin_op::BinOp;
+    use crate::mir::bool_to_sigma::BoolToSigmaProp;
+    use crate::mir::if_op::If;
+    use crate::mir::logical_not::LogicalNot;
+
+    #[test]
+    fn nested_operand_type_mismatch() {
+        // parsing does not check the If condition type
+        let bad_if = Expr::If(If {
+            condition: Box::new(Expr::Const(1i32.into())),
+            true_branch: Box::new(Expr::Const(true.into())),
+            false_branch: Box::new(Expr::Const(false.into())),
+        });
+        let expr = Expr::BoolToSigmaProp(BoolToSigmaProp {
+            input: Box::new(Expr::LogicalNot(
+                LogicalNot {
+                    input: Box::new(bad_if),
+                }
+                .into(),
+            )),
+        });
+        assert!(check_expr(&expr).is_err());
+    }
+
+    #[test]
+    fn bin_op_operands_mismatch() {
+        let plus = |left: Expr, right: Expr| {
+            Expr::BinOp(
+                BinOp {
+                    kind: BinOpKind::Arith(ArithOp::Plus),
+                    left: left.into(),
+                    right: right.into(),
+                }
+                .into(),
+            )
+        };
+        assert!(type_check(plus(1i32.into(), 1i64.into())).is_err());
+        assert!(type_check(plus(1i64.into(), 1i64.into())).is_ok());
     }
 }
@@ -14,6 +14,10 @@ use lazy_static::lazy_static;
 pub const TYPE_CODE: TypeCode = TypeCode::COLL;
 /// SColl type name
 pub static TYPE_NAME: &str = "Coll";
+/// Coll.slice
+pub const SLICE_METHOD_ID: MethodId = MethodId(7);
+/// Coll.append
+pub const APPEND_METHOD_ID: MethodId = MethodId(9);
 /// Coll.indexOf
 pub const INDEX_OF_METHOD_ID: MethodId = MethodId(26);
 /// Coll.flatmap
@@ -33,6 +37,8 @@ lazy_static! {
     /// Coll method descriptors
     pub(crate) static ref METHOD_DESC: Vec<&'static SMethodDesc> =
         vec![
+            &SLICE_METHOD_DESC,
+            &APPEND_METHOD_DESC,
             &INDEX_OF_METHOD_DESC,
             &FLATMAP_METHOD_DESC,
             &ZIP_METHOD_DESC,
@@ -44,6 +50,39 @@ lazy_static! {
     ;
 }
 
+lazy_static! {
+    static ref SLICE_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: SLICE_METHOD_ID,
+        name: "slice",
+        tpe: SFunc::new(
+            vec![
+                SType::SColl(SType::STypeVar(STypeVar::t()).into()),
+                SType::SInt,
+                SType::SInt,
+            ],
+            SType::SColl(SType::STypeVar(STypeVar::t()).into())
+        )
+    };
+    /// Coll.slice
+    pub static ref SLICE_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, SLICE_METHOD_DESC.clone());
+}
+
+lazy_static! {
+    static ref APPEND_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: APPEND_METHOD_ID,
+        name: "append",
+        tpe: SFunc::new(
+            vec![
+                SType::SColl(SType::STypeVar(STypeVar::t()).into()),
+                SType::SColl(SType::STypeVar(STypeVar::t()).into()),
+            ],
+            SType::SColl(SType::STypeVar(STypeVar::t()).into())
+        )
+    };
+    /// Coll.append
+    pub static ref APPEND_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, APPEND_METHOD_DESC.clone());
+}
+
 lazy_static! {
     static ref INDEX_OF_METHOD_DESC: SMethodDesc = SMethodDesc {
         method_id: INDEX_OF_METHOD_ID,
@@ -173,6 +212,8 @@ mod tests {
 
     #[test]
     fn test_from_ids() {
+        assert!(SMethod::from_ids(TYPE_CODE, SLICE_METHOD_ID).map(|e| e.name()) == Ok("slice"));
+        assert!(SMethod::from_ids(TYPE_CODE, APPEND_METHOD_ID).map(|e| e.name()) == Ok("append"));
         assert!(
             SMethod::from_ids(TYPE_CODE, INDEX_OF_METHOD_ID).map(|e| e.name()) == Ok("indexOf")
         );
@@ -14,6 +14,10 @@ pub const TYPE_CODE: TypeCode = TypeCode::SGROUP_ELEMENT;
 pub static TYPE_NAME: &str = "GroupElement";
 /// GroupElement.getEncoded
 pub const GET_ENCODED_METHOD_ID: MethodId = MethodId(2);
+/// GroupElement.exp
+pub const EXPONENTIATE_METHOD_ID: MethodId = MethodId(3);
+/// GroupElement.multiply
+pub const MULTIPLY_METHOD_ID: MethodId = MethodId(4);
 /// GroupElement.negate
 pub const NEGATE_METHOD_ID: MethodId = MethodId(5);
 
@@ -22,6 +26,8 @@ lazy_static! {
     pub(crate) static ref METHOD_DESC: Vec<&'static SMethodDesc> =
         vec![
             &GET_ENCODED_METHOD_DESC,
+            &EXPONENTIATE_METHOD_DESC,
+            &MULTIPLY_METHOD_DESC,
             &NEGATE_METHOD_DESC
         ]
     ;
@@ -40,6 +46,32 @@ lazy_static! {
     pub static ref GET_ENCODED_METHOD: SMethod = SMethod::new(STypeCompanion::GroupElem, GET_ENCODED_METHOD_DESC.clone(),);
 }
 
+lazy_static! {
+    static ref EXPONENTIATE_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: EXPONENTIATE_METHOD_ID,
+        name: "exp",
+        tpe: SFunc::new(
+            vec![SType::SGroupElement, SType::SBigInt],
+            SType::SGroupElement,
+        )
+    };
+    /// GroupElement.exp
+    pub static ref EXPONENTIATE_METHOD: SMethod = SMethod::new(STypeCompanion::GroupElem, EXPONENTIATE_METHOD_DESC.clone(),);
+}
+
+lazy_static! {
+    static ref MULTIPLY_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: MULTIPLY_METHOD_ID,
+        name: "multiply",
+        tpe: SFunc::new(
+            vec![SType::SGroupElement, SType::SGroupElement],
+            SType::SGroupElement,
+        )
+    };
+    /// GroupElement.multiply
+    pub static ref MULTIPLY_METHOD: SMethod = SMethod::new(STypeCompanion::GroupElem, MULTIPLY_METHOD_DESC.clone(),);
+}
+
 lazy_static! {
     static ref NEGATE_METHOD_DESC: SMethodDesc = SMethodDesc {
         method_id: NEGATE_METHOD_ID,
@@ -63,6 +95,12 @@ mod tests {
             SMethod::from_ids(TYPE_CODE, GET_ENCODED_METHOD_ID).map(|e| e.name())
                 == Ok("getEncoded")
         );
+        assert!(
+            SMethod::from_ids(TYPE_CODE, EXPONENTIATE_METHOD_ID).map(|e| e.name()) == Ok("exp")
+        );
+        assert!(
+            SMethod::from_ids(TYPE_CODE, MULTIPLY_METHOD_ID).map(|e| e.name()) == Ok("multiply")
+        );
         assert!(SMethod::from_ids(TYPE_CODE, NEGATE_METHOD_ID).map(|e| e.name()) == Ok("negate"));
     }
 }
@@ -45,10 +45,7 @@ impl SMethod {
     }
 
     /// Get method from type and method ids
-    pub(crate) fn from_ids(
-        type_id: TypeCode,
-        method_id: MethodId,
-    ) -> Result<Self, SigmaParsingError> {
+    pub fn from_ids(type_id: TypeCode, method_id: MethodId) -> Result<Self, SigmaParsingError> {
         let obj_type = STypeCompanion::try_from(type_id)?;
         match obj_type.method_by_id(&method_id) {
             Some(m) => Ok(m),
@@ -13,6 +13,12 @@ use lazy_static::lazy_static;
 pub const TYPE_CODE: TypeCode = TypeCode::OPTION;
 /// SOption type name
 pub static TYPE_NAME: &str = "Option";
+/// Option.isDefined
+pub const IS_DEFINED_METHOD_ID: MethodId = MethodId(2);
+/// Option.get
+pub const GET_METHOD_ID: MethodId = MethodId(3);
+/// Option.getOrElse
+pub const GET_OR_ELSE_METHOD_ID: MethodId = MethodId(4);
 /// Option.map
 pub const MAP_METHOD_ID: MethodId = MethodId(7);
 /// Option.filter
@@ -22,12 +28,63 @@ lazy_static! {
     /// Option method descriptors
     pub(crate) static ref METHOD_DESC: Vec<&'static SMethodDesc> =
         vec![
+            &IS_DEFINED_METHOD_DESC,
+            &GET_METHOD_DESC,
+            &GET_OR_ELSE_METHOD_DESC,
             &MAP_METHOD_DESC,
             &FILTER_METHOD_DESC,
         ]
     ;
 }
 
+lazy_static! {
+    static ref IS_DEFINED_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: IS_DEFINED_METHOD_ID,
+        name: "isDefined",
+        tpe: SFunc::new(
+            vec![SType::SOption(SType::STypeVar(STypeVar::t()).into())],
+            SType::SBoolean,
+        ),
+    };
+    /// Option.isDefined
+    pub static ref IS_DEFINED_METHOD: SMethod = SMethod::new(
+         STypeCompanion::Option,
+         IS_DEFINED_METHOD_DESC.clone());
+}
+
+lazy_static! {
+    static ref GET_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: GET_METHOD_ID,
+        name: "get",
+        tpe: SFunc::new(
+            vec![SType::SOption(SType::STypeVar(STypeVar::t()).into())],
+            STypeVar::t().into(),
+        ),
+    };
+    /// Option.get
+    pub static ref GET_METHOD: SMethod = SMethod::new(
+         STypeCompanion::Option,
+         GET_METHOD_DESC.clone());
+}
+
+lazy_static! {
+    static ref GET_OR_ELSE_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: GET_OR_ELSE_METHOD_ID,
+        name: "getOrElse",
+        tpe: SFunc::new(
+            vec![
+                SType::SOption(SType::STypeVar(STypeVar::t()).into()),
+                STypeVar::t().into(),
+                ],
+            STypeVar::t().into(),
+        ),
+    };
+    /// Option.getOrElse
+    pub static ref GET_OR_ELSE_METHOD: SMethod = SMethod::new(
+         STypeCompanion::Option,
+         GET_OR_ELSE_METHOD_DESC.clone());
+}
+
 lazy_static! {
     static ref MAP_METHOD_DESC: SMethodDesc = SMethodDesc {
         method_id: MAP_METHOD_ID,
@@ -64,7 +121,7 @@ lazy_static! {
             SType::SOption(SType::STypeVar(STypeVar::iv()).into()),
         ),
     };
-    /// Option.map
+    /// Option.filter
     pub static ref FILTER_METHOD: SMethod = SMethod::new(
          STypeCompanion::Option,
          FILTER_METHOD_DESC.clone());
@@ -76,6 +133,14 @@ mod tests {
 
     #[test]
     fn test_from_ids() {
+        assert!(
+            SMethod::from_ids(TYPE_CODE, IS_DEFINED_METHOD_ID).map(|e| e.name()) == Ok("isDefined")
+        );
+        assert!(SMethod::from_ids(TYPE_CODE, GET_METHOD_ID).map(|e| e.name()) == Ok("get"));
+        assert!(
+            SMethod::from_ids(TYPE_CODE, GET_OR_ELSE_METHOD_ID).map(|e| e.name())
+                == Ok("getOrElse")
+        );
         assert!(SMethod::from_ids(TYPE_CODE, MAP_METHOD_ID).map(|e| e.name()) == Ok("map"));
         assert!(SMethod::from_ids(TYPE_CODE, FILTER_METHOD_ID).map(|e| e.name()) == Ok("filter"));
     }
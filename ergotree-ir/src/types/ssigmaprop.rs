@@ -0,0 +1,50 @@
+use crate::serialization::types::TypeCode;
+use crate::types::stype_companion::STypeCompanion;
+
+use super::sfunc::SFunc;
+use super::smethod::MethodId;
+use super::smethod::SMethod;
+use super::smethod::SMethodDesc;
+use super::stype::SType;
+use lazy_static::lazy_static;
+
+/// SSigmaProp type code
+pub const TYPE_CODE: TypeCode = TypeCode::SSIGMAPROP;
+/// SSigmaProp type name
+pub static TYPE_NAME: &str = "SigmaProp";
+/// SigmaProp.propBytes
+pub const PROP_BYTES_METHOD_ID: MethodId = MethodId(1);
+
+lazy_static! {
+    /// SigmaProp method descriptors
+    pub(crate) static ref METHOD_DESC: Vec<&'static SMethodDesc> =
+        vec![
+            &PROP_BYTES_METHOD_DESC,
+        ]
+    ;
+}
+
+lazy_static! {
+    static ref PROP_BYTES_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: PROP_BYTES_METHOD_ID,
+        name: "propBytes",
+        tpe: SFunc::new(
+            vec![SType::SSigmaProp],
+            SType::SColl(Box::new(SType::SByte)),
+        )
+    };
+    /// SigmaProp.propBytes
+    pub static ref PROP_BYTES_METHOD: SMethod = SMethod::new(STypeCompanion::SigmaProp, PROP_BYTES_METHOD_DESC.clone(),);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_ids() {
+        assert!(
+            SMethod::from_ids(TYPE_CODE, PROP_BYTES_METHOD_ID).map(|e| e.name()) == Ok("propBytes")
+        );
+    }
+}
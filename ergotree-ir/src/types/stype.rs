@@ -75,6 +75,27 @@ impl SType {
         )
     }
 
+    /// Position of the numeric type in the implicit upcast order
+    /// (`Byte` -> `Short` -> `Int` -> `Long` -> `BigInt`), `None` for non-numeric types
+    pub fn numeric_rank(&self) -> Option<u8> {
+        match self {
+            SType::SByte => Some(0),
+            SType::SShort => Some(1),
+            SType::SInt => Some(2),
+            SType::SLong => Some(3),
+            SType::SBigInt => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Check if the numeric type can be upcast to the given numeric type (the same or a wider one)
+    pub fn can_upcast_to(&self, target: &SType) -> bool {
+        match (self.numeric_rank(), target.numeric_rank()) {
+            (Some(rank), Some(target_rank)) => rank <= target_rank,
+            _ => false,
+        }
+    }
+
     /// Check if type is primitive
     pub fn is_prim(&self) -> bool {
         matches!(
@@ -109,6 +130,21 @@ impl SType {
     }
 }
 
+/// Unify the types of two operands: the same type is returned as is, for the numeric types the
+/// wider one is returned (the narrower operand is implicitly upcast to it), `None` if the types
+/// cannot be unified
+pub fn unify(a: &SType, b: &SType) -> Option<SType> {
+    if a == b {
+        Some(a.clone())
+    } else if a.can_upcast_to(b) {
+        Some(b.clone())
+    } else if b.can_upcast_to(a) {
+        Some(a.clone())
+    } else {
+        None
+    }
+}
+
 impl From<STuple> for SType {
     fn from(v: STuple) -> Self {
         SType::STuple(v)
@@ -321,4 +357,14 @@ pub(crate) mod tests {
                 .boxed()
         }
     }
+
+    #[test]
+    fn unify_numeric() {
+        assert_eq!(unify(&SType::SInt, &SType::SInt), Some(SType::SInt));
+        assert_eq!(unify(&SType::SByte, &SType::SLong), Some(SType::SLong));
+        assert_eq!(unify(&SType::SBigInt, &SType::SShort), Some(SType::SBigInt));
+        assert_eq!(unify(&SType::SInt, &SType::SBoolean), None);
+        assert!(!SType::SLong.can_upcast_to(&SType::SInt));
+        assert!(!SType::SBoolean.can_upcast_to(&SType::SBoolean));
+    }
 }
@@ -16,6 +16,7 @@ use super::smethod::SMethod;
 use super::smethod::SMethodDesc;
 use super::soption;
 use super::spreheader;
+use super::ssigmaprop;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
@@ -40,6 +41,8 @@ pub enum STypeCompanion {
     Option,
     /// AVL tree
     AvlTree,
+    /// SigmaProp
+    SigmaProp,
 }
 
 impl STypeCompanion {
@@ -54,6 +57,7 @@ impl STypeCompanion {
             STypeCompanion::PreHeader => &spreheader::METHOD_DESC,
             STypeCompanion::Option => &soption::METHOD_DESC,
             STypeCompanion::AvlTree => &savltree::METHOD_DESC,
+            STypeCompanion::SigmaProp => &ssigmaprop::METHOD_DESC,
         }
     }
 
@@ -85,6 +89,7 @@ impl STypeCompanion {
             STypeCompanion::PreHeader => spreheader::TYPE_CODE,
             STypeCompanion::Option => soption::TYPE_CODE,
             STypeCompanion::AvlTree => savltree::TYPE_CODE,
+            STypeCompanion::SigmaProp => ssigmaprop::TYPE_CODE,
         }
     }
 
@@ -100,6 +105,7 @@ impl STypeCompanion {
             STypeCompanion::PreHeader => spreheader::TYPE_NAME,
             STypeCompanion::Option => soption::TYPE_NAME,
             STypeCompanion::AvlTree => savltree::TYPE_NAME,
+            STypeCompanion::SigmaProp => ssigmaprop::TYPE_NAME,
         }
     }
 }
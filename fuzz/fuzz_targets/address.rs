@@ -0,0 +1,15 @@
+//! Address decoding from the base58 string and from the raw bytes
+
+#![no_main]
+
+use ergotree_ir::chain::address::AddressEncoder;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        if let Ok(address) = AddressEncoder::unchecked_parse_address_from_str(s) {
+            let _ = address.script();
+        }
+    }
+    let _ = AddressEncoder::unchecked_parse_address_from_bytes(data);
+});
@@ -0,0 +1,13 @@
+//! Block header parsing (`Header::scorex_parse`)
+
+#![no_main]
+
+use ergo_chain_types::Header;
+use libfuzzer_sys::fuzz_target;
+use sigma_ser::ScorexSerializable;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(header) = Header::scorex_parse_bytes(data) {
+        let _ = header.scorex_serialize_bytes();
+    }
+});
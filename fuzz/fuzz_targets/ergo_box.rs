@@ -0,0 +1,17 @@
+//! `ErgoBox::sigma_parse` and the zero-copy box view, which must agree with each other
+
+#![no_main]
+
+use bytes::Bytes;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::ergo_box::ErgoBoxView;
+use ergotree_ir::serialization::SigmaSerializable;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let parsed = ErgoBox::sigma_parse_bytes(data);
+    let view = ErgoBoxView::sigma_parse_bytes(Bytes::copy_from_slice(data));
+    if let (Ok(b), Ok(view)) = (parsed, view) {
+        assert_eq!(view.box_id(), b.box_id());
+    }
+});
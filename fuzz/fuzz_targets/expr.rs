@@ -0,0 +1,13 @@
+//! `Expr::sigma_parse`, the parsed expression is serialized back
+
+#![no_main]
+
+use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::serialization::SigmaSerializable;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(expr) = Expr::sigma_parse_bytes(data) {
+        let _ = expr.sigma_serialize_bytes();
+    }
+});
@@ -0,0 +1,17 @@
+//! `Transaction::sigma_parse` and the zero-copy transaction view, which must agree with each other
+
+#![no_main]
+
+use bytes::Bytes;
+use ergo_lib::chain::transaction::view::TransactionView;
+use ergo_lib::chain::transaction::Transaction;
+use ergotree_ir::serialization::SigmaSerializable;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let parsed = Transaction::sigma_parse_bytes(data);
+    let view = TransactionView::sigma_parse_bytes(Bytes::copy_from_slice(data));
+    if let (Ok(tx), Ok(view)) = (parsed, view) {
+        assert_eq!(view.id(), tx.id());
+    }
+});
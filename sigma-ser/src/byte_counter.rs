@@ -0,0 +1,102 @@
+//! Writers counting the written bytes
+
+use std::io;
+
+/// Writer discarding the bytes and counting them, to get the serialized size of a value
+/// without allocating a buffer
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteCounter {
+    count: usize,
+}
+
+impl ByteCounter {
+    /// New counter at zero
+    pub fn new() -> Self {
+        ByteCounter::default()
+    }
+
+    /// Total number of bytes written
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.count += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writer passing the bytes to the inner writer and counting them (cumulatively, over all the
+/// values written through it)
+#[derive(Debug)]
+pub struct CountingWriter<W> {
+    inner: W,
+    count: usize,
+}
+
+impl<W: io::Write> CountingWriter<W> {
+    /// Wrap the writer
+    pub fn new(inner: W) -> Self {
+        CountingWriter { inner, count: 0 }
+    }
+
+    /// Total number of bytes written to the inner writer
+    pub fn bytes_written(&self) -> usize {
+        self.count
+    }
+
+    /// Unwrap the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: io::Write> io::Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::vlq_encode::WriteSigmaVlqExt;
+    use std::io::Write;
+
+    #[test]
+    fn counts_without_storing() {
+        let mut counter = ByteCounter::new();
+        counter.put_u64(u64::MAX).unwrap();
+        counter.write_all(&[1, 2, 3]).unwrap();
+        let mut buf = Vec::new();
+        buf.put_u64(u64::MAX).unwrap();
+        assert_eq!(counter.count(), buf.len() + 3);
+    }
+
+    #[test]
+    fn counts_into_caller_buffer() {
+        let mut buf = [0u8; 8];
+        let mut w = CountingWriter::new(&mut buf[..]);
+        w.put_u32(300).unwrap();
+        w.put_u8(7).unwrap();
+        assert_eq!(w.bytes_written(), 3);
+        assert_eq!(&buf[..3], &[0xac, 0x02, 7]);
+        // caller buffer is too small
+        let mut small = [0u8; 1];
+        let mut w = CountingWriter::new(&mut small[..]);
+        assert!(w.put_u32(300).is_err());
+    }
+}
@@ -17,6 +17,8 @@
 #![deny(clippy::panic)]
 #![deny(clippy::wildcard_enum_match_arm)]
 
+/// Writers counting the written bytes
+pub mod byte_counter;
 /// ScoreX Serialization
 mod scorex_serialize;
 pub use scorex_serialize::{
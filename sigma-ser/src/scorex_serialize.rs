@@ -1,6 +1,8 @@
 use std::convert::TryInto;
 use std::io;
 
+use crate::byte_counter::ByteCounter;
+use crate::byte_counter::CountingWriter;
 use crate::vlq_encode;
 use crate::vlq_encode::*;
 use bounded_vec::{BoundedVec, BoundedVecOutOfBounds};
@@ -53,6 +55,9 @@ pub enum ScorexParsingError {
     /// Failed to decode VLQ
     #[error("vlq encode error: {0}")]
     VlqEncode(#[from] vlq_encode::VlqEncodingError),
+    /// Failed to decode VLQ (with the position of the value)
+    #[error("vlq read error: {0}")]
+    VlqRead(#[from] vlq_encode::VlqReadError),
     /// IO fail (EOF, etc.)
     #[error("IO error: {0}")]
     Io(String),
@@ -110,6 +115,22 @@ pub trait ScorexSerializable: Sized {
         self.scorex_serialize(&mut w)?;
         Ok(w)
     }
+    /// Size of the serialized value in bytes, counted without allocating a buffer
+    fn scorex_serialized_size(&self) -> Result<usize, ScorexSerializationError> {
+        let mut counter = ByteCounter::new();
+        self.scorex_serialize(&mut counter)?;
+        Ok(counter.count())
+    }
+    /// Write `self` to any writer (e.g. caller's buffer `&mut [u8]` or `&mut dyn Write`).
+    /// Returns the number of bytes written
+    fn scorex_serialize_to<W: io::Write + ?Sized>(
+        &self,
+        w: &mut W,
+    ) -> Result<usize, ScorexSerializationError> {
+        let mut w = CountingWriter::new(w);
+        self.scorex_serialize(&mut w)?;
+        Ok(w.bytes_written())
+    }
     /// Parse `self` from the bytes
     fn scorex_parse_bytes(mut bytes: &[u8]) -> Result<Self, ScorexParsingError> {
         Self::scorex_parse(&mut bytes)
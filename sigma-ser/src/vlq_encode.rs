@@ -1,6 +1,7 @@
 use super::zig_zag_encode;
 use std::convert::TryFrom;
 use std::io;
+use std::io::SeekFrom;
 
 use bitvec::order::Lsb0;
 use bitvec::prelude::BitVec;
@@ -20,8 +21,33 @@ pub enum VlqEncodingError {
     /// Fail to decode a value from bytes
     #[error("VLQ decoding failed")]
     VlqDecodingFailed,
+    /// Encoded value is longer than the maximum length (bytes)
+    #[error("VLQ encoding is longer than {0} bytes")]
+    TooLong(usize),
+    /// Decoded value does not fit into 64 bits
+    #[error("VLQ value overflows 64 bits")]
+    Overflow,
 }
 
+/// VLQ decoding error with the position of the value in the input
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+#[error("Failed to read {expected_type} at byte offset {offset}: {error}")]
+pub struct VlqReadError {
+    /// Offset of the first byte of the value
+    pub offset: u64,
+    /// Type of the value being read
+    pub expected_type: &'static str,
+    /// Cause of the failure
+    pub error: VlqEncodingError,
+}
+
+/// Maximum length (bytes) of the VLQ-encoded `u16`
+pub const MAX_U16_VLQ_LEN: usize = 3;
+/// Maximum length (bytes) of the VLQ-encoded `u32` (and ZigZag-encoded `i16`)
+pub const MAX_U32_VLQ_LEN: usize = 5;
+/// Maximum length (bytes) of the VLQ-encoded `u64` (and ZigZag-encoded `i32`, `i64`)
+pub const MAX_U64_VLQ_LEN: usize = 10;
+
 impl From<io::Error> for VlqEncodingError {
     fn from(error: io::Error) -> Self {
         VlqEncodingError::Io(error.to_string())
@@ -221,6 +247,28 @@ pub trait ReadSigmaVlqExt: io::Read {
         Err(VlqEncodingError::VlqDecodingFailed)
     }
 
+    /// Read and decode using VLQ value written with [`WriteSigmaVlqExt::put_u64`], failing if the
+    /// encoding is longer than `max_len` (at most [`MAX_U64_VLQ_LEN`]) bytes or the value does
+    /// not fit into 64 bits
+    fn get_u64_max_len(&mut self, max_len: usize) -> Result<u64, VlqEncodingError> {
+        let max_len = max_len.min(MAX_U64_VLQ_LEN);
+        let mut result: u64 = 0;
+        for i in 0..max_len {
+            let b = self.get_u8()?;
+            let shift = 7 * i as u32;
+            let bits = (b & 0x7F) as u64;
+            // only the lowest bit of the 10th byte fits into 64 bits
+            if shift == 63 && bits > 1 {
+                return Err(VlqEncodingError::Overflow);
+            }
+            result |= bits << shift;
+            if (b & 0x80) == 0 {
+                return Ok(result);
+            }
+        }
+        Err(VlqEncodingError::TooLong(max_len))
+    }
+
     /// Read a vector of bits with the given size
     fn get_bits(&mut self, size: usize) -> Result<Vec<bool>, VlqEncodingError> {
         let byte_num = (size + 7) / 8;
@@ -258,6 +306,123 @@ pub trait ReadSigmaVlqExt: io::Read {
 /// Mark all types implementing `Read` as implementing the extension.
 impl<R: io::Read + ?Sized> ReadSigmaVlqExt for R {}
 
+/// Reads of the values written with [`WriteSigmaVlqExt`] checking the maximum encoding length
+/// of the type, with the errors carrying the byte offset and the expected type of the value
+/// (see [`VlqReadError`]), and the reads which don't advance the reader (`peek_*`).
+/// Meant for the diagnostics of the corrupted data.
+pub trait ReadSigmaVlqSeekExt: ReadSigmaVlqExt + io::Seek {
+    /// Run the read, adding the offset of the value and its type to the error
+    fn read_with_context<T, F>(
+        &mut self,
+        expected_type: &'static str,
+        read: F,
+    ) -> Result<T, VlqReadError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, VlqEncodingError>,
+    {
+        let offset = self.stream_position().map_err(|e| VlqReadError {
+            offset: 0,
+            expected_type,
+            error: e.into(),
+        })?;
+        read(self).map_err(|error| VlqReadError {
+            offset,
+            expected_type,
+            error,
+        })
+    }
+
+    /// Run the read and restore the reader position
+    fn peek<T, F>(&mut self, read: F) -> Result<T, VlqReadError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, VlqReadError>,
+    {
+        let offset = self.stream_position().map_err(|e| VlqReadError {
+            offset: 0,
+            expected_type: "position",
+            error: e.into(),
+        })?;
+        let res = read(self);
+        self.seek(SeekFrom::Start(offset))
+            .map_err(|e| VlqReadError {
+                offset,
+                expected_type: "position",
+                error: e.into(),
+            })?;
+        res
+    }
+
+    /// Read u8 (see [`ReadSigmaVlqExt::get_u8`])
+    fn get_u8_checked(&mut self) -> Result<u8, VlqReadError> {
+        self.read_with_context("u8", |r| Ok(r.get_u8()?))
+    }
+
+    /// Read u16 (see [`ReadSigmaVlqExt::get_u16`]) of at most [`MAX_U16_VLQ_LEN`] bytes
+    fn get_u16_checked(&mut self) -> Result<u16, VlqReadError> {
+        self.read_with_context("u16", |r| {
+            let v = r.get_u64_max_len(MAX_U16_VLQ_LEN)?;
+            u16::try_from(v).map_err(|err| VlqEncodingError::TryFrom(v.to_string(), err))
+        })
+    }
+
+    /// Read i16 (see [`ReadSigmaVlqExt::get_i16`]) of at most [`MAX_U32_VLQ_LEN`] bytes
+    fn get_i16_checked(&mut self) -> Result<i16, VlqReadError> {
+        self.read_with_context("i16", |r| {
+            let vd = zig_zag_encode::decode_u32(r.get_u64_max_len(MAX_U32_VLQ_LEN)?);
+            i16::try_from(vd).map_err(|err| VlqEncodingError::TryFrom(vd.to_string(), err))
+        })
+    }
+
+    /// Read u32 (see [`ReadSigmaVlqExt::get_u32`]) of at most [`MAX_U32_VLQ_LEN`] bytes
+    fn get_u32_checked(&mut self) -> Result<u32, VlqReadError> {
+        self.read_with_context("u32", |r| {
+            let v = r.get_u64_max_len(MAX_U32_VLQ_LEN)?;
+            u32::try_from(v).map_err(|err| VlqEncodingError::TryFrom(v.to_string(), err))
+        })
+    }
+
+    /// Read i32 (see [`ReadSigmaVlqExt::get_i32`]) of at most [`MAX_U64_VLQ_LEN`] bytes
+    fn get_i32_checked(&mut self) -> Result<i32, VlqReadError> {
+        self.read_with_context("i32", |r| {
+            r.get_u64_max_len(MAX_U64_VLQ_LEN)
+                .map(zig_zag_encode::decode_u32)
+        })
+    }
+
+    /// Read u64 (see [`ReadSigmaVlqExt::get_u64`]) of at most [`MAX_U64_VLQ_LEN`] bytes, failing
+    /// on the values not fitting into 64 bits
+    fn get_u64_checked(&mut self) -> Result<u64, VlqReadError> {
+        self.read_with_context("u64", |r| r.get_u64_max_len(MAX_U64_VLQ_LEN))
+    }
+
+    /// Read i64 (see [`ReadSigmaVlqExt::get_i64`]) of at most [`MAX_U64_VLQ_LEN`] bytes, failing
+    /// on the values not fitting into 64 bits
+    fn get_i64_checked(&mut self) -> Result<i64, VlqReadError> {
+        self.read_with_context("i64", |r| {
+            r.get_u64_max_len(MAX_U64_VLQ_LEN)
+                .map(zig_zag_encode::decode_u64)
+        })
+    }
+
+    /// Read u8 without advancing the reader
+    fn peek_u8(&mut self) -> Result<u8, VlqReadError> {
+        self.peek(|r| r.get_u8_checked())
+    }
+
+    /// Read u32 without advancing the reader
+    fn peek_u32(&mut self) -> Result<u32, VlqReadError> {
+        self.peek(|r| r.get_u32_checked())
+    }
+
+    /// Read u64 without advancing the reader
+    fn peek_u64(&mut self) -> Result<u64, VlqReadError> {
+        self.peek(|r| r.get_u64_checked())
+    }
+}
+
+/// Mark all types implementing `Read` and `Seek` as implementing the extension.
+impl<R: io::Read + io::Seek + ?Sized> ReadSigmaVlqSeekExt for R {}
+
 #[allow(clippy::unwrap_used)]
 #[cfg(test)]
 #[allow(clippy::panic)]
@@ -455,6 +620,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn checked_reads_errors() {
+        let mut r = Cursor::new(vec![0x01, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01]);
+        assert_eq!(r.peek_u8().unwrap(), 1);
+        assert_eq!(r.get_u8_checked().unwrap(), 1);
+        assert_eq!(
+            r.peek_u32(),
+            Err(VlqReadError {
+                offset: 1,
+                expected_type: "u32",
+                error: VlqEncodingError::TooLong(MAX_U32_VLQ_LEN)
+            })
+        );
+        assert_eq!(r.position(), 1);
+        assert_eq!(r.get_u64_checked().unwrap(), 1 << 35);
+        let err = r.get_u16_checked().unwrap_err();
+        assert_eq!(err.offset, 7);
+        assert_eq!(err.expected_type, "u16");
+        assert!(matches!(err.error, VlqEncodingError::Io(_)));
+        // above u16::MAX
+        let mut r = Cursor::new(vec![0xFF, 0xFF, 0x04]);
+        assert!(matches!(
+            r.get_u16_checked().unwrap_err().error,
+            VlqEncodingError::TryFrom(_, _)
+        ));
+        // 10th byte with more than the lowest bit set
+        let mut r = Cursor::new(vec![
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02,
+        ]);
+        assert_eq!(
+            r.get_u64_checked().unwrap_err().error,
+            VlqEncodingError::Overflow
+        );
+        let mut r = Cursor::new(vec![
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01,
+        ]);
+        assert_eq!(r.get_u64_checked().unwrap(), u64::MAX);
+    }
+
     #[test]
     fn i16_corner_cases() {
         fn roundtrip(v: i16, expected_bytes: &[u8]) {
@@ -995,6 +1199,19 @@ mod tests {
             prop_assert_eq!(&bytes_u32(i as u32), &expected_bytes);
         }
 
+        #[test]
+        fn checked_reads_full_range(u in any::<u64>(), i in any::<i64>(), i32v in any::<i32>()) {
+            let mut w = Cursor::new(vec![]);
+            w.put_u64(u).unwrap();
+            w.put_i64(i).unwrap();
+            w.put_i32(i32v).unwrap();
+            let mut r = Cursor::new(w.into_inner());
+            prop_assert_eq!(r.peek_u64().unwrap(), u);
+            prop_assert_eq!(r.get_u64_checked().unwrap(), u);
+            prop_assert_eq!(r.get_i64_checked().unwrap(), i);
+            prop_assert_eq!(r.get_i32_checked().unwrap(), i32v);
+        }
+
         #[test]
         fn i16_i32_i64_equivalence(i in any::<i16>()) {
             let expected_bytes = bytes_i16(i);
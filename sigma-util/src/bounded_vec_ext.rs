@@ -0,0 +1,101 @@
+//! BoundedVec extensions
+
+use bounded_vec::BoundedVec;
+use bounded_vec::BoundedVecOutOfBounds;
+
+/// Collect iterators into `BoundedVec` (e.g. tx inputs, data inputs, outputs) with the bounds checked
+pub trait BoundedVecIterExt: Iterator + Sized {
+    /// Collect items into `BoundedVec`.
+    /// Fails with the violated bound and the actual number of items if it's out of `[L, U]`.
+    fn try_collect_bounded<const L: usize, const U: usize>(
+        self,
+    ) -> Result<BoundedVec<Self::Item, L, U>, BoundedVecOutOfBounds> {
+        BoundedVec::from_vec(self.collect())
+    }
+
+    /// Map items with a fallible `f` and collect the results into `BoundedVec`.
+    /// Stops on the first error returned by `f`, bounds violation is converted into `E`.
+    fn map_result<T, E, F, const L: usize, const U: usize>(
+        self,
+        f: F,
+    ) -> Result<BoundedVec<T, L, U>, E>
+    where
+        F: FnMut(Self::Item) -> Result<T, E>,
+        E: From<BoundedVecOutOfBounds>,
+    {
+        let items = self.map(f).collect::<Result<Vec<T>, E>>()?;
+        Ok(BoundedVec::from_vec(items)?)
+    }
+}
+
+impl<I: Iterator> BoundedVecIterExt for I {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    type Bounded = BoundedVec<u8, 1, 3>;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum TestError {
+        TooBig(u8),
+        Bounds(BoundedVecOutOfBounds),
+    }
+
+    impl From<BoundedVecOutOfBounds> for TestError {
+        fn from(e: BoundedVecOutOfBounds) -> Self {
+            TestError::Bounds(e)
+        }
+    }
+
+    fn small(i: u8) -> Result<u8, TestError> {
+        if i < 10 {
+            Ok(i)
+        } else {
+            Err(TestError::TooBig(i))
+        }
+    }
+
+    #[test]
+    fn test_try_collect_bounded() {
+        let v: Bounded = (1..=3).try_collect_bounded().unwrap();
+        assert_eq!(v.as_vec(), &vec![1, 2, 3]);
+        let res: Result<Bounded, _> = (1..=4).try_collect_bounded();
+        assert_eq!(
+            res,
+            Err(BoundedVecOutOfBounds::UpperBoundError {
+                upper_bound: 3,
+                got: 4
+            })
+        );
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Upper bound violation: got 4 (expected <= 3)"
+        );
+        let res: Result<Bounded, _> = std::iter::empty().try_collect_bounded();
+        assert_eq!(
+            res,
+            Err(BoundedVecOutOfBounds::LowerBoundError {
+                lower_bound: 1,
+                got: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_map_result() {
+        let v: Bounded = vec![2, 4].into_iter().map_result(small).unwrap();
+        assert_eq!(v.as_vec(), &vec![2, 4]);
+        let res: Result<Bounded, _> = vec![2, 30].into_iter().map_result(small);
+        assert_eq!(res, Err(TestError::TooBig(30)));
+        let res: Result<Bounded, _> = vec![2, 4, 6, 8].into_iter().map_result(small);
+        assert_eq!(
+            res,
+            Err(TestError::Bounds(BoundedVecOutOfBounds::UpperBoundError {
+                upper_bound: 3,
+                got: 4
+            }))
+        );
+    }
+}
@@ -0,0 +1,376 @@
+//! Base16, Base58 and Base64 encoding of the bytes as text
+
+use std::convert::TryInto;
+use std::fmt;
+
+use thiserror::Error;
+
+/// Text encoding of the bytes
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Encoding {
+    /// Hex (either case on decoding, lower case on encoding)
+    Base16,
+    /// Bitcoin alphabet Base58 (addresses)
+    Base58,
+    /// Standard alphabet Base64
+    Base64,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encoding::Base16 => write!(f, "Base16"),
+            Encoding::Base58 => write!(f, "Base58"),
+            Encoding::Base64 => write!(f, "Base64"),
+        }
+    }
+}
+
+/// How strictly the input is checked on decoding
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum DecodingMode {
+    /// The input is accepted only in the form produced by the node (and the encoders here)
+    Strict,
+    /// The surrounding whitespace is ignored, `0x` prefix is allowed for Base16, padding is
+    /// optional and URL-safe alphabet is allowed for Base64
+    Lenient,
+}
+
+/// Decoding errors. Positions are byte offsets in the original input.
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum DecodingError {
+    /// Character is not allowed at this position
+    #[error("invalid {encoding} character {character:?} at position {position}")]
+    InvalidCharacter {
+        /// Encoding of the input
+        encoding: Encoding,
+        /// Invalid character
+        character: char,
+        /// Position of the character
+        position: usize,
+    },
+    /// Input length is not valid for the encoding
+    #[error("invalid {encoding} input length {length}")]
+    InvalidLength {
+        /// Encoding of the input
+        encoding: Encoding,
+        /// Length of the input (without the ignored parts in the lenient mode)
+        length: usize,
+    },
+    /// Decoded bytes are of unexpected size
+    #[error("expected {expected} decoded bytes, got {actual}")]
+    UnexpectedSize {
+        /// Expected number of bytes
+        expected: usize,
+        /// Number of the decoded bytes
+        actual: usize,
+    },
+}
+
+/// Encode bytes as lower case Base16
+pub fn encode_base16(bytes: &[u8]) -> String {
+    base16::encode_lower(bytes)
+}
+
+/// Encode bytes as Base58
+pub fn encode_base58(bytes: &[u8]) -> String {
+    bs58::encode(bytes).into_string()
+}
+
+/// Encode bytes as padded standard Base64
+pub fn encode_base64(bytes: &[u8]) -> String {
+    base64::encode(bytes)
+}
+
+/// Decode Base16 (either case)
+pub fn decode_base16(input: &str, mode: DecodingMode) -> Result<Vec<u8>, DecodingError> {
+    let (offset, s) = strip(input, mode, Encoding::Base16);
+    if let Some((i, c)) = s.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(DecodingError::InvalidCharacter {
+            encoding: Encoding::Base16,
+            character: c,
+            position: offset + i,
+        });
+    }
+    if s.len() % 2 != 0 {
+        return Err(DecodingError::InvalidLength {
+            encoding: Encoding::Base16,
+            length: s.len(),
+        });
+    }
+    Ok(s.as_bytes()
+        .chunks(2)
+        .map(|pair| (hex_digit_value(pair[0]) << 4) | hex_digit_value(pair[1]))
+        .collect())
+}
+
+/// Decode Base58
+pub fn decode_base58(input: &str, mode: DecodingMode) -> Result<Vec<u8>, DecodingError> {
+    let (offset, s) = strip(input, mode, Encoding::Base58);
+    bs58::decode(s).into_vec().map_err(|e| match e {
+        bs58::decode::Error::InvalidCharacter { character, index } => {
+            DecodingError::InvalidCharacter {
+                encoding: Encoding::Base58,
+                character,
+                position: offset + index,
+            }
+        }
+        bs58::decode::Error::NonAsciiCharacter { index } => DecodingError::InvalidCharacter {
+            encoding: Encoding::Base58,
+            character: s[index..].chars().next().unwrap_or_default(),
+            position: offset + index,
+        },
+        _ => DecodingError::InvalidLength {
+            encoding: Encoding::Base58,
+            length: s.len(),
+        },
+    })
+}
+
+/// Decode standard alphabet Base64
+pub fn decode_base64(input: &str, mode: DecodingMode) -> Result<Vec<u8>, DecodingError> {
+    let (offset, s) = strip(input, mode, Encoding::Base64);
+    let res = match mode {
+        DecodingMode::Strict => base64::decode_config(s, base64::STANDARD),
+        DecodingMode::Lenient => {
+            // URL-safe characters are mapped one to one, so the positions are kept
+            let standard: String = s
+                .trim_end_matches('=')
+                .chars()
+                .map(|c| match c {
+                    '-' => '+',
+                    '_' => '/',
+                    c => c,
+                })
+                .collect();
+            base64::decode_config(standard, base64::STANDARD_NO_PAD)
+        }
+    };
+    res.map_err(|e| match e {
+        base64::DecodeError::InvalidByte(index, _)
+        | base64::DecodeError::InvalidLastSymbol(index, _) => DecodingError::InvalidCharacter {
+            encoding: Encoding::Base64,
+            character: s[index..].chars().next().unwrap_or_default(),
+            position: offset + index,
+        },
+        base64::DecodeError::InvalidLength => DecodingError::InvalidLength {
+            encoding: Encoding::Base64,
+            length: s.len(),
+        },
+    })
+}
+
+/// Decode Base16 (strict mode) into exactly `N` bytes, e.g. `decode_base16_exact::<32>(id)`.
+/// The input of the expected length is decoded in constant time (without the branches and
+/// lookups depending on the characters), only the failure (not its position) is observable.
+pub fn decode_base16_exact<const N: usize>(input: &str) -> Result<[u8; N], DecodingError> {
+    let s = input.as_bytes();
+    if s.len() != 2 * N {
+        return to_array(decode_base16(input, DecodingMode::Strict)?);
+    }
+    let mut res = [0u8; N];
+    let mut invalid: u16 = 0;
+    for (byte, pair) in res.iter_mut().zip(s.chunks_exact(2)) {
+        let hi = hex_digit_value_ct(pair[0]);
+        let lo = hex_digit_value_ct(pair[1]);
+        invalid |= (hi | lo) & 0xff00;
+        *byte = ((hi << 4) | lo) as u8;
+    }
+    if invalid != 0 {
+        // find the invalid character for the error
+        return to_array(decode_base16(input, DecodingMode::Strict)?);
+    }
+    Ok(res)
+}
+
+/// Decode Base58 (strict mode) into exactly `N` bytes
+pub fn decode_base58_exact<const N: usize>(input: &str) -> Result<[u8; N], DecodingError> {
+    to_array(decode_base58(input, DecodingMode::Strict)?)
+}
+
+/// Decode Base64 (strict mode) into exactly `N` bytes
+pub fn decode_base64_exact<const N: usize>(input: &str) -> Result<[u8; N], DecodingError> {
+    to_array(decode_base64(input, DecodingMode::Strict)?)
+}
+
+fn to_array<const N: usize>(bytes: Vec<u8>) -> Result<[u8; N], DecodingError> {
+    let actual = bytes.len();
+    bytes.try_into().map_err(|_| DecodingError::UnexpectedSize {
+        expected: N,
+        actual,
+    })
+}
+
+/// Offset of the significant part of the input and the part itself
+fn strip(input: &str, mode: DecodingMode, encoding: Encoding) -> (usize, &str) {
+    match mode {
+        DecodingMode::Strict => (0, input),
+        DecodingMode::Lenient => {
+            let trimmed = input.trim();
+            let offset = input.len() - input.trim_start().len();
+            match encoding {
+                Encoding::Base16 => match trimmed
+                    .strip_prefix("0x")
+                    .or_else(|| trimmed.strip_prefix("0X"))
+                {
+                    Some(s) => (offset + 2, s),
+                    None => (offset, trimmed),
+                },
+                Encoding::Base58 | Encoding::Base64 => (offset, trimmed),
+            }
+        }
+    }
+}
+
+/// Value of the ASCII hex digit (checked by the caller)
+fn hex_digit_value(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        _ => c - b'A' + 10,
+    }
+}
+
+/// Value of the ASCII hex digit (`0xffff` for the other characters) computed with the arithmetic
+/// only, as in `base16ct`
+fn hex_digit_value_ct(c: u8) -> u16 {
+    let c = c as i16;
+    let mut res: i16 = -1;
+    // 0-9: 0x30..=0x39
+    res += (((0x2f - c) & (c - 0x3a)) >> 8) & (c - 0x2f);
+    // A-F: 0x41..=0x46
+    res += (((0x40 - c) & (c - 0x47)) >> 8) & (c - 0x36);
+    // a-f: 0x61..=0x66
+    res += (((0x60 - c) & (c - 0x67)) >> 8) & (c - 0x56);
+    res as u16
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base16() {
+        let bytes = vec![0u8, 0xab, 0xCD, 0xff];
+        assert_eq!(encode_base16(&bytes), "00abcdff");
+        assert_eq!(
+            decode_base16("00ABcdff", DecodingMode::Strict).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            decode_base16(" 0x00abcdff\n", DecodingMode::Lenient).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            decode_base16(" 00abcdff", DecodingMode::Strict),
+            Err(DecodingError::InvalidCharacter {
+                encoding: Encoding::Base16,
+                character: ' ',
+                position: 0
+            })
+        );
+        assert_eq!(
+            decode_base16(" 0x00abzdff", DecodingMode::Lenient),
+            Err(DecodingError::InvalidCharacter {
+                encoding: Encoding::Base16,
+                character: 'z',
+                position: 7
+            })
+        );
+        assert_eq!(
+            decode_base16("abc", DecodingMode::Strict),
+            Err(DecodingError::InvalidLength {
+                encoding: Encoding::Base16,
+                length: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_hex_digit_value_ct() {
+        for c in 0..=u8::MAX {
+            let expected = if c.is_ascii_hexdigit() {
+                hex_digit_value(c) as u16
+            } else {
+                0xffff
+            };
+            assert_eq!(hex_digit_value_ct(c), expected, "character {}", c);
+        }
+    }
+
+    #[test]
+    fn test_exact() {
+        assert_eq!(decode_base16_exact::<2>("0102").unwrap(), [1, 2]);
+        assert_eq!(decode_base16_exact::<2>("aBfF").unwrap(), [0xab, 0xff]);
+        assert_eq!(
+            decode_base16_exact::<2>("01g2"),
+            Err(DecodingError::InvalidCharacter {
+                encoding: Encoding::Base16,
+                character: 'g',
+                position: 2
+            })
+        );
+        let bytes: Vec<u8> = (0..=u8::MAX).collect();
+        assert_eq!(
+            decode_base16_exact::<256>(&encode_base16(&bytes)).unwrap()[..],
+            bytes[..]
+        );
+        assert_eq!(
+            decode_base16_exact::<32>("0102"),
+            Err(DecodingError::UnexpectedSize {
+                expected: 32,
+                actual: 2
+            })
+        );
+        let digest = [7u8; 32];
+        assert_eq!(
+            decode_base58_exact::<32>(&encode_base58(&digest)).unwrap(),
+            digest
+        );
+        assert_eq!(
+            decode_base64_exact::<32>(&encode_base64(&digest)).unwrap(),
+            digest
+        );
+    }
+
+    #[test]
+    fn test_base58() {
+        assert_eq!(
+            decode_base58("3yZe7d", DecodingMode::Strict).unwrap(),
+            decode_base58(" 3yZe7d ", DecodingMode::Lenient).unwrap()
+        );
+        assert_eq!(
+            decode_base58("3yZ0e7d", DecodingMode::Strict),
+            Err(DecodingError::InvalidCharacter {
+                encoding: Encoding::Base58,
+                character: '0',
+                position: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_base64() {
+        let bytes = vec![0xfbu8, 0xff, 0x01, 0x02];
+        let encoded = encode_base64(&bytes);
+        assert_eq!(encoded, "+/8BAg==");
+        assert_eq!(
+            decode_base64(&encoded, DecodingMode::Strict).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            decode_base64(" -_8BAg ", DecodingMode::Lenient).unwrap(),
+            bytes
+        );
+        assert!(decode_base64("-_8BAg==", DecodingMode::Strict).is_err());
+        assert_eq!(
+            decode_base64("+/8B*g==", DecodingMode::Strict),
+            Err(DecodingError::InvalidCharacter {
+                encoding: Encoding::Base64,
+                character: '*',
+                position: 4
+            })
+        );
+    }
+}
@@ -22,3 +22,89 @@ pub fn sha256_hash(bytes: &[u8]) -> Box<[u8; 32]> {
     hasher.update(bytes);
     Box::new(*hasher.finalize().as_ref())
 }
+
+/// Blake2b256 hash of the signed bytes (`Coll[Byte]` values) without converting the whole input
+pub fn blake2b256_hash_i8(bytes: &[i8]) -> Box<[u8; 32]> {
+    use blake2::Digest;
+
+    let mut hasher = Blake2b256Writer::new().0;
+    for_each_u8_chunk(bytes, |chunk| hasher.update(chunk));
+    let hash: [u8; 32] = hasher.finalize().into();
+    Box::new(hash)
+}
+
+/// Sha256 hash of the signed bytes (`Coll[Byte]` values) without converting the whole input
+pub fn sha256_hash_i8(bytes: &[i8]) -> Box<[u8; 32]> {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    for_each_u8_chunk(bytes, |chunk| hasher.update(chunk));
+    Box::new(*hasher.finalize().as_ref())
+}
+
+/// Feed the signed bytes to `f` as unsigned chunks converted in a stack buffer, so that
+/// large inputs are hashed without converting (copying) the whole input
+fn for_each_u8_chunk<F: FnMut(&[u8])>(bytes: &[i8], mut f: F) {
+    let mut buf = [0u8; 256];
+    for chunk in bytes.chunks(buf.len()) {
+        let buf = &mut buf[..chunk.len()];
+        buf.iter_mut().zip(chunk).for_each(|(b, i)| *b = *i as u8);
+        f(buf);
+    }
+}
+
+/// Blake2b256 hasher taking the input through [`std::io::Write`], so that the values can be
+/// serialized straight into it without an intermediate buffer
+#[derive(Default, Clone)]
+pub struct Blake2b256Writer(blake2::Blake2b<blake2::digest::typenum::U32>);
+
+impl Blake2b256Writer {
+    /// New hasher
+    pub fn new() -> Self {
+        Blake2b256Writer::default()
+    }
+
+    /// Hash of the bytes written so far
+    pub fn finalize(self) -> Box<[u8; 32]> {
+        use blake2::Digest;
+        let hash: [u8; 32] = self.0.finalize().into();
+        Box::new(hash)
+    }
+}
+
+impl std::io::Write for Blake2b256Writer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        use blake2::Digest;
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn blake2b256_writer() {
+        let mut w = Blake2b256Writer::new();
+        w.write_all(b"hello ").unwrap();
+        w.write_all(b"world").unwrap();
+        assert_eq!(w.finalize(), blake2b256_hash(b"hello world"));
+    }
+
+    #[test]
+    fn hash_i8_chunked() {
+        for len in [0, 1, 255, 256, 257, 1000] {
+            let bytes: Vec<i8> = (0..len).map(|i| (i % 256) as u8 as i8).collect();
+            let unsigned: Vec<u8> = bytes.iter().map(|b| *b as u8).collect();
+            assert_eq!(blake2b256_hash_i8(&bytes), blake2b256_hash(&unsigned));
+            assert_eq!(sha256_hash_i8(&bytes), sha256_hash(&unsigned));
+        }
+    }
+}
@@ -13,9 +13,13 @@
 #![allow(clippy::unit_arg)]
 #![deny(rustdoc::broken_intra_doc_links)]
 
+mod bounded_vec_ext;
+pub mod encoding;
 pub mod hash;
+pub mod lru;
 mod vec_ext;
 
+pub use bounded_vec_ext::BoundedVecIterExt;
 pub use vec_ext::AsVecI8;
 pub use vec_ext::AsVecU8;
 pub use vec_ext::FromVecI8;
@@ -0,0 +1,124 @@
+//! Least recently used (LRU) cache
+
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Map of a fixed capacity, evicting the least recently used entry on overflow
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, u64)>,
+    /// Keys by the last access tick
+    order: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /// Cache holding up to `capacity` entries (nothing is cached if `capacity` is 0)
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Maximum number of entries
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Value for the key, marking the entry as the most recently used
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.tick += 1;
+        let (value, last_used) = self.entries.get_mut(key)?;
+        if let Some(k) = self.order.remove(last_used) {
+            self.order.insert(self.tick, k);
+        }
+        *last_used = self.tick;
+        Some(value)
+    }
+
+    /// Insert the entry as the most recently used one, evicting the least recently used entry if
+    /// the capacity is exceeded. Returns the previous value for the key.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if self.capacity == 0 {
+            return None;
+        }
+        self.tick += 1;
+        let prev = self
+            .entries
+            .insert(key.clone(), (value, self.tick))
+            .map(|(prev, last_used)| {
+                self.order.remove(&last_used);
+                prev
+            });
+        self.order.insert(self.tick, key);
+        if self.entries.len() > self.capacity {
+            if let Some(oldest) = self.order.keys().next().copied() {
+                if let Some(k) = self.order.remove(&oldest) {
+                    self.entries.remove(&k);
+                }
+            }
+        }
+        prev
+    }
+
+    /// Remove the entry
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (value, last_used) = self.entries.remove(key)?;
+        self.order.remove(&last_used);
+        Some(value)
+    }
+
+    /// Remove all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        assert_eq!(cache.insert("a", 1), None);
+        assert_eq!(cache.insert("b", 2), None);
+        assert_eq!(cache.get("a"), Some(&1));
+        // "b" is the least recently used one
+        assert_eq!(cache.insert("c", 3), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.insert("c", 4), Some(3));
+        assert_eq!(cache.remove("a"), Some(1));
+        assert_eq!(cache.len(), 1);
+        let mut empty = LruCache::new(0);
+        assert_eq!(empty.insert("a", 1), None);
+        assert!(empty.is_empty());
+    }
+}